@@ -41,7 +41,7 @@ import {
   PreAggJob,
   PreAggJobStatusItem,
   PreAggJobStatusResponse,
-  SqlApiRequest, MetaResponseResultFn,
+  SqlApiRequest, MetaResponseResultFn, MetaResponse, CubeCostHints,
 } from './types/request';
 import {
   CheckAuthInternalOptions,
@@ -556,7 +556,7 @@ class ApiGateway {
         includeCompilerId: includeCompilerId || onlyCompilerId
       });
       if (onlyCompilerId) {
-        const response: { cubes: any[], compilerId?: string } = {
+        const response: MetaResponse = {
           cubes: [],
           compilerId: metaConfig.compilerId
         };
@@ -565,9 +565,11 @@ class ApiGateway {
       }
       const cubesConfig = includeCompilerId ? metaConfig.cubes : metaConfig;
       const cubes = this.filterVisibleItemsInMeta(context, cubesConfig).map(cube => cube.config);
-      const response: { cubes: any[], compilerId?: string } = { cubes };
+      const response: MetaResponse = { cubes };
       if (includeCompilerId) {
+        // Meta with compiler id is requested by the SQL API, which ranks query plans with cost hints
         response.compilerId = metaConfig.compilerId;
+        response.costHints = await this.sqlApiCostHints(compilerApi, cubes);
       }
       res(response);
     } catch (e: any) {
@@ -581,6 +583,31 @@ class ApiGateway {
     }
   }
 
+  /**
+   * Cube has pre-aggregations when any of its pre-aggregations can serve queries, i.e. isn't
+   * `originalSql`. Estimated row count is taken from `meta.estimatedRowCount` of the cube.
+   */
+  protected async sqlApiCostHints(compilerApi, cubes: any[]): Promise<Record<string, CubeCostHints>> {
+    const preAggregations = await compilerApi.preAggregations();
+    const preAggregatedCubes = new Set(
+      preAggregations
+        .filter(({ preAggregation }) => preAggregation.type !== 'originalSql')
+        .map(({ cube }) => cube)
+    );
+
+    return cubes.reduce((costHints, cube) => {
+      const estimatedRowCount = cube.meta?.estimatedRowCount;
+
+      return {
+        ...costHints,
+        [cube.name]: {
+          hasPreAggregations: preAggregatedCubes.has(cube.name),
+          ...(typeof estimatedRowCount === 'number' ? { estimatedRowCount } : {}),
+        },
+      };
+    }, {});
+  }
+
   public async metaExtended({ context, res }: { context: ExtendedRequestContext, res: ResponseResultFn }) {
     const requestStarted = new Date();
 
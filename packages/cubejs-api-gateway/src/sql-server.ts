@@ -6,15 +6,18 @@ import {
   SqlInterfaceInstance,
   Request as NativeRequest,
   LoadRequestMeta,
+  NativeJwtOptions,
+  loadResultToArrowIpc,
 } from '@cubejs-backend/native';
 import type { ShutdownMode } from '@cubejs-backend/native';
 import { displayCLIWarning, getEnv } from '@cubejs-backend/shared';
 
 import * as crypto from 'crypto';
 import type { ApiGateway } from './gateway';
-import type { CheckSQLAuthFn, ExtendedRequestContext, CanSwitchSQLUserFn } from './interfaces';
+import type { CheckAuthFn, CheckSQLAuthFn, ExtendedRequestContext, CanSwitchSQLUserFn } from './interfaces';
 
 export type SQLServerOptions = {
+  checkAuth?: CheckAuthFn,
   checkSqlAuth?: CheckSQLAuthFn,
   canSwitchSqlUser?: CanSwitchSQLUserFn,
   sqlPort?: number,
@@ -23,6 +26,8 @@ export type SQLServerOptions = {
   sqlSuperUser?: string,
   sqlPassword?: string,
   gatewayPort?: number,
+  // Verify JWT natively, only static key or local JWKS file are supported
+  jwt?: NativeJwtOptions,
 };
 
 export type SQLServerConstructorOptions = {
@@ -101,6 +106,16 @@ export class SQLServer {
     this.sqlInterfaceInstance = await registerInterface({
       gatewayPort: this.gatewayPort,
       pgPort: options.pgSqlPort,
+      // Custom checkAuth or checkSqlAuth may rely on JS logic for tokens, keep them in charge
+      jwt: !options.checkAuth && !options.checkSqlAuth && options.jwt && (options.jwt.key || options.jwt.jwkFile) ? {
+        key: options.jwt.key,
+        jwkFile: options.jwt.jwkFile,
+        algorithms: options.jwt.algorithms,
+        issuer: options.jwt.issuer,
+        audience: options.jwt.audience,
+        subject: options.jwt.subject,
+        claimsNamespace: options.jwt.claimsNamespace,
+      } : undefined,
       checkAuth: async ({ request, user, password }) => {
         const { password: returnedPassword, superuser, securityContext, skipPasswordCheck } = await checkSqlAuth(request, user, password);
 
@@ -112,6 +127,14 @@ export class SQLServer {
           skipPasswordCheck,
         };
       },
+      checkApiGatewayAuth: async ({ request, token }) => {
+        const req: any = { ...request };
+        await this.apiGateway.checkAuthFn(req, token || undefined);
+
+        return {
+          securityContext: req.securityContext,
+        };
+      },
       meta: async ({ request, session, onlyCompilerId }) => {
         const context = await this.apiGateway.contextByReq(<any> request, session.securityContext, request.id);
 
@@ -171,8 +194,10 @@ export class SQLServer {
           }
         });
       },
-      sqlApiLoad: async ({ request, session, query, queryKey, sqlQuery, streaming }) => {
+      sqlApiLoad: async ({ request, session, query, queryKey, sqlQuery, streaming, acceptArrow }) => {
         const context = await contextByRequest(request, session);
+        // Requests of the native REST API gateway are checked as regular REST requests
+        const restApi = !!request.meta?.restApi;
 
         // eslint-disable-next-line no-async-promise-executor
         return new Promise(async (resolve, reject) => {
@@ -183,7 +208,7 @@ export class SQLServer {
               sqlQuery,
               streaming,
               context,
-              memberExpressions: true,
+              memberExpressions: !restApi,
               res: (response) => {
                 if ('error' in response) {
                   reject({
@@ -193,9 +218,19 @@ export class SQLServer {
                   return;
                 }
 
+                if (acceptArrow && !streaming && response.results?.length === 1) {
+                  const [result] = response.results;
+                  const arrowIpc = loadResultToArrowIpc(result);
+                  if (arrowIpc) {
+                    resolve({ arrowIpc, total: result.total });
+
+                    return;
+                  }
+                }
+
                 resolve(response);
               },
-              apiType: 'sql',
+              apiType: restApi ? 'rest' : 'sql',
             });
           } catch (e) {
             reject(e);
@@ -204,6 +239,7 @@ export class SQLServer {
       },
       sql: async ({ request, session, query, memberToAlias, expressionParams }) => {
         const context = await contextByRequest(request, session);
+        const restApi = !!request.meta?.restApi;
 
         // eslint-disable-next-line no-async-promise-executor
         return new Promise(async (resolve, reject) => {
@@ -213,7 +249,7 @@ export class SQLServer {
               memberToAlias,
               expressionParams,
               exportAnnotatedSql: true,
-              memberExpressions: true,
+              memberExpressions: !restApi,
               disableExternalPreAggregations: true,
               queryType: 'multi',
               disableLimitEnforcing: true,
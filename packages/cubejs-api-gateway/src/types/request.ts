@@ -93,7 +93,12 @@ type ErrorResponse = {
   error: string,
 };
 
-type MetaResponse = { cubes: any[], compilerId?: string };
+/**
+ * Hints about the data behind the cube, used by the SQL API planner.
+ */
+type CubeCostHints = { hasPreAggregations: boolean, estimatedRowCount?: number };
+
+type MetaResponse = { cubes: any[], compilerId?: string, costHints?: Record<string, CubeCostHints> };
 type MetaResponseResultFn = (message: MetaResponse | ErrorResponse) => void;
 
 /**
@@ -214,7 +219,9 @@ export {
   SecurityContextExtractorFn,
   ExtendContextFn,
   ResponseResultFn,
+  MetaResponse,
   MetaResponseResultFn,
+  CubeCostHints,
   BaseRequest,
   QueryRequest,
   PreAggsJobsRequest,
@@ -655,6 +655,26 @@ describe('API Gateway', () => {
     expect(res.body.cubes[0]?.segments.find(segment => segment.name === 'Foo.quux').description).toBe('segment from compilerApi mock');
   });
 
+  test('meta for the SQL API contains cost hints', async () => {
+    const { apiGateway } = await createApiGateway();
+
+    const response: any = await new Promise((resolve) => {
+      apiGateway.meta({
+        context: <any>{ securityContext: {}, requestId: 'cost-hints' },
+        res: resolve,
+        includeCompilerId: true,
+      });
+    });
+
+    expect(response.compilerId).toBe('compiler-id');
+    expect(response.costHints).toEqual({
+      Foo: {
+        hasPreAggregations: false,
+        estimatedRowCount: 1000000,
+      },
+    });
+  });
+
   test('meta endpoint extended to get schema information with additional data', async () => {
     const { app } = await createApiGateway();
 
@@ -79,12 +79,15 @@ export const compilerApi = jest.fn().mockImplementation(async () => ({
     return { query, denied: false };
   },
 
-  async metaConfig() {
-    return [
+  async metaConfig(_context?: any, options: { includeCompilerId?: boolean } = {}) {
+    const cubes = [
       {
         config: {
           name: 'Foo',
           description: 'cube from compilerApi mock',
+          meta: {
+            estimatedRowCount: 1000000,
+          },
           measures: [
             {
               name: 'Foo.bar',
@@ -125,6 +128,8 @@ export const compilerApi = jest.fn().mockImplementation(async () => ({
         },
       },
     ];
+
+    return options.includeCompilerId ? { cubes, compilerId: 'compiler-id' } : cubes;
   },
 
   async metaConfigExtended() {
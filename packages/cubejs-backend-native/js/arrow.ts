@@ -0,0 +1,120 @@
+import {
+  Bool,
+  DataType,
+  Table,
+  TimestampMillisecond,
+  Utf8,
+  Vector,
+  tableToIPC,
+  vectorFromArray,
+} from 'apache-arrow';
+
+export interface LoadResultAnnotation {
+  measures: Record<string, { type?: string }>,
+  dimensions: Record<string, { type?: string }>,
+  segments: Record<string, { type?: string }>,
+  timeDimensions: Record<string, { type?: string }>,
+}
+
+export interface LoadResult {
+  annotation: LoadResultAnnotation,
+  data: Record<string, any>[],
+  total?: number,
+}
+
+function toBoolean(value: any): boolean | null {
+  if (value === null || value === undefined) {
+    return null;
+  }
+
+  if (typeof value === 'boolean') {
+    return value;
+  }
+
+  return value === 'true' || value === '1' || value === 1;
+}
+
+// Cube returns local time without time zone, it's kept as is (as UTC), same as JSON rows are parsed
+function toTimestamp(value: any): number | null {
+  if (value === null || value === undefined) {
+    return null;
+  }
+
+  if (value instanceof Date) {
+    return value.getTime();
+  }
+
+  const str = String(value);
+  const timestamp = Date.parse(/(Z|[+-]\d\d:?\d\d)$/.test(str) ? str : `${str}Z`);
+
+  return Number.isNaN(timestamp) ? null : timestamp;
+}
+
+function toUtf8(value: any): string | null {
+  if (value === null || value === undefined) {
+    return null;
+  }
+
+  return typeof value === 'object' ? JSON.stringify(value) : String(value);
+}
+
+function columnVector(rows: Record<string, any>[], name: string, type: string | undefined): Vector {
+  let arrowType: DataType;
+  let convert: (value: any) => any;
+
+  switch (type) {
+    case 'boolean':
+      arrowType = new Bool();
+      convert = toBoolean;
+      break;
+    case 'time':
+      arrowType = new TimestampMillisecond();
+      convert = toTimestamp;
+      break;
+    default:
+      // Numbers are passed as strings to keep precision of decimals and big integers,
+      // they are cast to the type of the column by cubesql
+      arrowType = new Utf8();
+      convert = toUtf8;
+  }
+
+  return vectorFromArray(rows.map((row) => convert(row[name])), arrowType);
+}
+
+/**
+ * Encodes the result of a regular load query to the Arrow IPC stream format,
+ * columns are named by members. Returns null when the result can't be encoded,
+ * in that case it should be passed as JSON.
+ */
+export function loadResultToArrowIpc(result: LoadResult): Buffer | null {
+  if (!result || !Array.isArray(result.data) || !result.annotation) {
+    return null;
+  }
+
+  const types: Record<string, string | undefined> = {};
+  for (const section of ['dimensions', 'timeDimensions', 'measures']) {
+    for (const [name, member] of Object.entries(result.annotation[section] || {})) {
+      types[name] = (member as { type?: string }).type;
+    }
+  }
+
+  for (const row of result.data) {
+    for (const name of Object.keys(row)) {
+      if (!(name in types)) {
+        types[name] = undefined;
+      }
+    }
+  }
+
+  const names = Object.keys(types);
+  if (names.length === 0) {
+    return null;
+  }
+
+  const columns: Record<string, Vector> = {};
+  for (const name of names) {
+    columns[name] = columnVector(result.data, name, types[name]);
+  }
+
+  return Buffer.from(tableToIPC(new Table(columns), 'stream'));
+}
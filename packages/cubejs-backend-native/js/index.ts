@@ -4,10 +4,13 @@ import path from 'path';
 import { Writable } from 'stream';
 import type { Request as ExpressRequest } from 'express';
 
+export { loadResultToArrowIpc } from './arrow';
+export type { LoadResult, LoadResultAnnotation } from './arrow';
+
 export interface BaseMeta {
-  // postgres or mysql
+  // postgres, mysql or http
   protocol: string,
-  // always sql
+  // sql or rest
   apiType: string,
   // Application name, for example Metabase
   appName?: string,
@@ -16,6 +19,8 @@ export interface BaseMeta {
 export interface LoadRequestMeta extends BaseMeta {
   // Security Context switching
   changeUser?: string,
+  // Request of the native REST API gateway
+  restApi: boolean,
 }
 
 export interface Request<Meta> {
@@ -28,6 +33,18 @@ export interface CheckAuthResponse {
   superuser: boolean,
   securityContext: any,
   skipPasswordCheck?: boolean,
+  securityPolicy?: SecurityPolicy,
+}
+
+export interface CubeSecurityPolicy {
+  // Filters added to every request which uses the cube
+  filters?: any[],
+  // Full names of visible members, all members are visible when it's not set
+  visibleMembers?: string[],
+}
+
+export interface SecurityPolicy {
+  cubes: Record<string, CubeSecurityPolicy>,
 }
 
 export interface CheckAuthPayload {
@@ -36,6 +53,39 @@ export interface CheckAuthPayload {
   password: string | null,
 }
 
+export interface GatewayRequest {
+  protocol: string,
+  method: string,
+  url: string,
+}
+
+export interface CheckApiGatewayAuthPayload {
+  request: GatewayRequest,
+  // Authorization header without scheme
+  token: string | null,
+}
+
+export interface CheckApiGatewayAuthResponse {
+  securityContext: any,
+  securityPolicy?: SecurityPolicy,
+}
+
+/**
+ * Options for JWT verification inside the native module. When set, the native gateway
+ * doesn't call checkApiGatewayAuth and SQL passwords which are JWTs skip checkAuth.
+ */
+export interface NativeJwtOptions {
+  key?: string,
+  // Path to a local JWKS file
+  jwkFile?: string,
+  algorithms?: string[],
+  issuer?: string[],
+  audience?: string,
+  subject?: string,
+  // Security context is taken from this claim of the payload
+  claimsNamespace?: string,
+}
+
 export interface SessionContext {
   user: string | null,
   superuser: boolean,
@@ -63,6 +113,9 @@ export interface SqlApiLoadPayload {
   queryKey: any,
   sqlQuery: any,
   streaming: boolean,
+  // When set, the result can be returned as `{ arrowIpc: Buffer, total?: number }`
+  // with Arrow IPC stream instead of JSON rows
+  acceptArrow: boolean,
 }
 
 export interface LogLoadEventPayload {
@@ -96,6 +149,8 @@ export type SQLInterfaceOptions = {
   canSwitchUserForSession: (payload: CanSwitchUserPayload) => unknown | Promise<unknown>,
   // gateway options
   gatewayPort?: number,
+  checkApiGatewayAuth?: (payload: CheckApiGatewayAuthPayload) => CheckApiGatewayAuthResponse | Promise<CheckApiGatewayAuthResponse>,
+  jwt?: NativeJwtOptions,
 };
 
 export function loadNative() {
@@ -253,6 +308,8 @@ function wrapNativeFunctionWithStream(
         });
       } else if (response.error) {
         writerOrChannel.reject(errorString(response));
+      } else if (Buffer.isBuffer(response.arrowIpc)) {
+        writerOrChannel.resolve(response);
       } else {
         // TODO remove JSON.stringify()
         writerOrChannel.resolve(JSON.stringify(response));
@@ -313,6 +370,14 @@ export const registerInterface = async (options: SQLInterfaceOptions): Promise<S
     throw new Error('options.sql must be a function');
   }
 
+  if (options.gatewayPort && !options.jwt && typeof options.checkApiGatewayAuth !== 'function') {
+    throw new Error('options.checkApiGatewayAuth must be a function when gatewayPort is set without jwt');
+  }
+
+  if (options.jwt && !options.jwt.key && !options.jwt.jwkFile) {
+    throw new Error('options.jwt must contain key or jwkFile');
+  }
+
   const native = loadNative();
   return native.registerInterface({
     ...options,
@@ -325,6 +390,10 @@ export const registerInterface = async (options: SQLInterfaceOptions): Promise<S
     sqlGenerators: wrapRawNativeFunctionWithChannelCallback(options.sqlGenerators),
     logLoadEvent: wrapRawNativeFunctionWithChannelCallback(options.logLoadEvent),
     canSwitchUserForSession: wrapRawNativeFunctionWithChannelCallback(options.canSwitchUserForSession),
+    checkApiGatewayAuth: options.checkApiGatewayAuth
+      ? wrapNativeFunctionWithChannelCallback(options.checkApiGatewayAuth)
+      : undefined,
+    jwt: options.jwt ? JSON.stringify(options.jwt) : undefined,
   });
 };
 
@@ -348,6 +417,12 @@ export const buildSqlAndParams = (cubeEvaluator: any): String => {
   return native.buildSqlAndParams(cubeEvaluator);
 };
 
+export const buildPlanDescription = (cubeEvaluator: any): any => {
+  const native = loadNative();
+
+  return native.buildPlanDescription(cubeEvaluator);
+};
+
 export interface PyConfiguration {
   repositoryFactory?: (ctx: unknown) => Promise<unknown>,
   logger?: (msg: string, params: Record<string, any>) => void,
@@ -437,9 +512,31 @@ export type PythonCtx = {
 export type JinjaEngineOptions = {
   debugInfo?: boolean,
   filters: Record<string, Function>,
-  workers: number
+  workers: number,
+  // Max number of instructions per render, 100 000 000 by default
+  fuel?: number,
+  // Max render time in milliseconds since the render has started, unlimited by default
+  renderTimeout?: number,
+};
+
+export type JinjaTemplateErrorFrame = {
+  fileName: string,
+  line?: number,
+  column?: number,
 };
 
+/**
+ * Errors thrown by loadTemplate/renderTemplate, location points to the innermost template,
+ * templateStack contains all templates up to the rendered one.
+ */
+export interface JinjaTemplateError extends Error {
+  kind: string,
+  fileName?: string,
+  line?: number,
+  column?: number,
+  templateStack: JinjaTemplateErrorFrame[],
+}
+
 export interface JinjaEngine {
   loadTemplate(templateName: string, templateContent: string): void;
 
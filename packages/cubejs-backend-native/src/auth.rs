@@ -1,7 +1,7 @@
 use async_trait::async_trait;
 use cubesql::{
     di_service,
-    sql::{AuthContext, AuthenticateResponse, SqlAuthService},
+    sql::{AuthContext, AuthenticateResponse, SecurityPolicy, SqlAuthService},
     transport::LoadRequestMeta,
     CubeError,
 };
@@ -13,18 +13,30 @@ use std::sync::Arc;
 use uuid::Uuid;
 
 use crate::channel::call_js_with_channel_as_callback;
+use crate::gateway::{GatewayAuthService, GatewayAuthenticateResponse, GatewayCheckAuthRequest};
+use crate::jwt::JwtAuth;
 
 #[derive(Debug)]
 pub struct NodeBridgeAuthService {
     channel: Arc<Channel>,
     check_auth: Arc<Root<JsFunction>>,
+    check_api_gateway_auth: Option<Arc<Root<JsFunction>>>,
+    // When configured, JWT tokens are verified natively without calling JS
+    jwt_auth: Option<Arc<JwtAuth>>,
 }
 
 impl NodeBridgeAuthService {
-    pub fn new(channel: Channel, check_auth: Root<JsFunction>) -> Self {
+    pub fn new(
+        channel: Channel,
+        check_auth: Root<JsFunction>,
+        check_api_gateway_auth: Option<Root<JsFunction>>,
+        jwt_auth: Option<JwtAuth>,
+    ) -> Self {
         Self {
             channel: Arc::new(channel),
             check_auth: Arc::new(check_auth),
+            check_api_gateway_auth: check_api_gateway_auth.map(Arc::new),
+            jwt_auth: jwt_auth.map(Arc::new),
         }
     }
 }
@@ -50,6 +62,22 @@ struct CheckAuthResponse {
     security_context: Option<serde_json::Value>,
     #[serde(rename = "skipPasswordCheck", skip_serializing_if = "Option::is_none")]
     skip_password_check: Option<bool>,
+    #[serde(rename = "securityPolicy", skip_serializing_if = "Option::is_none")]
+    security_policy: Option<SecurityPolicy>,
+}
+
+#[derive(Debug, Serialize)]
+struct CheckApiGatewayAuthRequest {
+    request: GatewayCheckAuthRequest,
+    token: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CheckApiGatewayAuthResponse {
+    #[serde(rename = "securityContext", skip_serializing_if = "Option::is_none")]
+    security_context: Option<serde_json::Value>,
+    #[serde(rename = "securityPolicy", skip_serializing_if = "Option::is_none")]
+    security_policy: Option<SecurityPolicy>,
 }
 
 #[derive(Debug)]
@@ -63,6 +91,10 @@ impl AuthContext for NativeAuthContext {
     fn as_any(&self) -> &dyn Any {
         self
     }
+
+    fn is_superuser(&self) -> bool {
+        self.superuser
+    }
 }
 
 #[async_trait]
@@ -74,6 +106,24 @@ impl SqlAuthService for NodeBridgeAuthService {
     ) -> Result<AuthenticateResponse, CubeError> {
         trace!("[auth] Request ->");
 
+        if let (Some(jwt_auth), Some(token)) = (&self.jwt_auth, &password) {
+            if JwtAuth::is_token(token) {
+                let security_context = jwt_auth.verify(token)?;
+                trace!("[auth] Request <- verified natively by JWT");
+
+                return Ok(AuthenticateResponse {
+                    context: Arc::new(NativeAuthContext {
+                        user,
+                        superuser: false,
+                        security_context: Some(security_context),
+                    }),
+                    password: None,
+                    skip_password_check: true,
+                    security_policy: None,
+                });
+            }
+        }
+
         let request_id = Uuid::new_v4().to_string();
 
         let extra = serde_json::to_string(&CheckAuthRequest {
@@ -100,8 +150,61 @@ impl SqlAuthService for NodeBridgeAuthService {
             }),
             password: response.password,
             skip_password_check: response.skip_password_check.unwrap_or(false),
+            security_policy: response.security_policy.map(Arc::new),
+        })
+    }
+}
+
+#[async_trait]
+impl GatewayAuthService for NodeBridgeAuthService {
+    async fn authenticate(
+        &self,
+        req: GatewayCheckAuthRequest,
+        token: Option<String>,
+    ) -> Result<GatewayAuthenticateResponse, CubeError> {
+        trace!("[gateway auth] Request ->");
+
+        if let Some(jwt_auth) = &self.jwt_auth {
+            let token = token
+                .ok_or_else(|| CubeError::user("Authorization header isn't set".to_string()))?;
+            let security_context = jwt_auth.verify(&token)?;
+            trace!("[gateway auth] Request <- verified natively by JWT");
+
+            return Ok(GatewayAuthenticateResponse {
+                context: Arc::new(NativeAuthContext {
+                    user: None,
+                    superuser: false,
+                    security_context: Some(security_context),
+                }),
+                security_policy: None,
+            });
+        }
+
+        let check_api_gateway_auth = self.check_api_gateway_auth.clone().ok_or_else(|| {
+            CubeError::internal("checkApiGatewayAuth is not configured".to_string())
+        })?;
+
+        let extra = serde_json::to_string(&CheckApiGatewayAuthRequest {
+            request: req,
+            token,
+        })?;
+        let response: CheckApiGatewayAuthResponse = call_js_with_channel_as_callback(
+            self.channel.clone(),
+            check_api_gateway_auth,
+            Some(extra),
+        )
+        .await?;
+        trace!("[gateway auth] Request <- {:?}", response);
+
+        Ok(GatewayAuthenticateResponse {
+            context: Arc::new(NativeAuthContext {
+                user: None,
+                superuser: false,
+                security_context: response.security_context,
+            }),
+            security_policy: response.security_policy.map(Arc::new),
         })
     }
 }
 
-di_service!(NodeBridgeAuthService, [SqlAuthService]);
+di_service!(NodeBridgeAuthService, [SqlAuthService, GatewayAuthService]);
@@ -1,5 +1,5 @@
 use crate::gateway::server::ApiGatewayServerImpl;
-use crate::gateway::{ApiGatewayRouterBuilder, ApiGatewayServer};
+use crate::gateway::{ApiGatewayRouterBuilder, ApiGatewayServer, GatewayAuthService};
 use crate::{auth::NodeBridgeAuthService, transport::NodeBridgeTransport};
 use async_trait::async_trait;
 use cubesql::config::injection::Injector;
@@ -154,6 +154,7 @@ impl NodeConfiguration for NodeConfigurationImpl {
             .register_typed::<dyn TransportService, _, _, _>(|_| async move { transport })
             .await;
 
+        let gateway_auth = auth.clone();
         injector
             .register_typed::<dyn SqlAuthService, _, _, _>(|_| async move { auth })
             .await;
@@ -161,6 +162,10 @@ impl NodeConfiguration for NodeConfigurationImpl {
         if let Some(api_gateway_address) = &self.api_gateway_address {
             let api_gateway_address = api_gateway_address.clone();
 
+            injector
+                .register_typed::<dyn GatewayAuthService, _, _, _>(|_| async move { gateway_auth })
+                .await;
+
             injector
                 .register_typed::<dyn ApiGatewayServer, _, _, _>(|i| async move {
                     ApiGatewayServerImpl::new(
@@ -1,5 +1,5 @@
 #[cfg(feature = "python")]
-use crate::cross::clrepr_python::PythonRef;
+use crate::cross::clrepr_python::{CLReprPython, PythonRef};
 #[cfg(feature = "python")]
 use crate::cross::py_in_js::{
     cl_repr_py_function_wrapper, BoxedJsPyFunctionWrapper, JsPyFunctionWrapper,
@@ -10,6 +10,8 @@ use neon::prelude::*;
 use neon::result::Throw;
 use neon::types::JsDate;
 #[cfg(feature = "python")]
+use pyo3::{PyResult, Python};
+#[cfg(feature = "python")]
 use std::cell::RefCell;
 use std::collections::hash_map::{IntoIter, Iter, Keys};
 use std::collections::HashMap;
@@ -300,6 +302,28 @@ impl CLRepr {
                 PythonRef::PyObject(_) => {
                     return cx.throw_error("Unable to represent PyObject in JS")
                 }
+                PythonRef::PyGenerator(generator) => {
+                    // JS expects all items, generator must be finite here
+                    let items = Python::with_gil(|py| -> PyResult<Vec<CLRepr>> {
+                        let mut items = Vec::new();
+
+                        for item in generator.as_ref(py).iter()? {
+                            items.push(CLRepr::from_python_ref(item?)?);
+                        }
+
+                        Ok(items)
+                    });
+
+                    match items {
+                        Ok(items) => Self::into_js_impl(CLRepr::Array(items), cx, tcx)?,
+                        Err(err) => {
+                            return cx.throw_error(format!(
+                                "Unable to consume Python generator: {}",
+                                err
+                            ))
+                        }
+                    }
+                }
             },
             CLRepr::Null => cx.undefined().upcast(),
             CLRepr::JsFunction(fun) => {
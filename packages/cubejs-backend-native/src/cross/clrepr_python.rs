@@ -14,6 +14,8 @@ pub enum PythonRef {
     /// Special type to transfer functions through JavaScript
     /// In JS it's an external object. It's not the same as Function.
     PyExternalFunction(Py<PyFunction>),
+    /// Generator, items are pulled only when they are requested
+    PyGenerator(PyObject),
 }
 
 pub trait CLReprPython: Sized {
@@ -111,6 +113,9 @@ impl CLReprPython for CLRepr {
                 "Unable to represent PyTraceback type as CLR from Python, value: {:?}",
                 trb
             )));
+        } else if unsafe { pyo3::ffi::PyGen_CheckExact(v.as_ptr()) == 1 } {
+            // Generators are not consumed on conversion, they can be infinite
+            Self::PythonRef(PythonRef::PyGenerator(v.into()))
         } else {
             let is_sequence = unsafe { pyo3::ffi::PySequence_Check(v.as_ptr()) == 1 };
             if is_sequence {
@@ -180,6 +185,7 @@ impl CLReprPython for CLRepr {
                         "Unable to represent PyExternalFunction in Python",
                     ))
                 }
+                PythonRef::PyGenerator(generator) => generator,
             },
             CLRepr::JsFunction(_) => {
                 return Err(PyErr::new::<PyNotImplementedError, _>(
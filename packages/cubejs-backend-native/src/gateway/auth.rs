@@ -0,0 +1,63 @@
+use crate::gateway::http_error::HttpError;
+use crate::gateway::{ApiGatewayState, GatewayCheckAuthRequest};
+use axum::async_trait;
+use axum::extract::FromRequestParts;
+use axum::http::header::AUTHORIZATION;
+use axum::http::request::Parts;
+use cubesql::sql::{AuthContextRef, SecurityPolicyRef};
+
+/// Extractor which authenticates request by the Authorization header
+pub struct GatewayAuth {
+    pub context: AuthContextRef,
+    pub security_policy: Option<SecurityPolicyRef>,
+}
+
+// Same as JS gateway: scheme is optional and x-cube-authorization takes precedence
+fn extract_token(parts: &Parts) -> Result<Option<String>, HttpError> {
+    let Some(value) = parts
+        .headers
+        .get("x-cube-authorization")
+        .or_else(|| parts.headers.get(AUTHORIZATION))
+    else {
+        return Ok(None);
+    };
+    let value = value.to_str().map_err(|_| {
+        HttpError::bad_request("Authorization header contains invalid characters".to_string())
+    })?;
+
+    let token = match value.split_once(' ') {
+        Some((_scheme, token)) => token,
+        None => value,
+    };
+
+    Ok(Some(token.to_string()))
+}
+
+#[async_trait]
+impl FromRequestParts<ApiGatewayState> for GatewayAuth {
+    type Rejection = HttpError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &ApiGatewayState,
+    ) -> Result<Self, Self::Rejection> {
+        let token = extract_token(parts)?;
+        let request = GatewayCheckAuthRequest {
+            protocol: "http".to_string(),
+            method: parts.method.to_string(),
+            url: parts.uri.to_string(),
+        };
+
+        let response = state
+            .auth_service()
+            .await
+            .authenticate(request, token)
+            .await
+            .map_err(|err| HttpError::forbidden(err.message))?;
+
+        Ok(Self {
+            context: response.context,
+            security_policy: response.security_policy,
+        })
+    }
+}
@@ -0,0 +1,28 @@
+use async_trait::async_trait;
+use cubesql::sql::{AuthContextRef, SecurityPolicyRef};
+use cubesql::CubeError;
+use serde::Serialize;
+use std::fmt::Debug;
+
+#[derive(Debug, Serialize)]
+pub struct GatewayCheckAuthRequest {
+    pub protocol: String,
+    pub method: String,
+    pub url: String,
+}
+
+#[derive(Debug)]
+pub struct GatewayAuthenticateResponse {
+    pub context: AuthContextRef,
+    pub security_policy: Option<SecurityPolicyRef>,
+}
+
+#[async_trait]
+pub trait GatewayAuthService: Send + Sync + Debug {
+    /// Authenticates HTTP request by the token from Authorization header (scheme is stripped)
+    async fn authenticate(
+        &self,
+        req: GatewayCheckAuthRequest,
+        token: Option<String>,
+    ) -> Result<GatewayAuthenticateResponse, CubeError>;
+}
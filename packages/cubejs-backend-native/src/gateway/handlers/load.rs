@@ -0,0 +1,70 @@
+use crate::gateway::auth::GatewayAuth;
+use crate::gateway::handlers::query::{
+    prepare_query, rest_load_request_meta, QueryBody, QueryParams,
+};
+use crate::gateway::http_error::HttpError;
+use crate::gateway::ApiGatewayState;
+use axum::extract::{Query, State};
+use axum::Json;
+use cubesql::transport::{TransportLoadRequestQuery, TransportLoadResponse};
+use serde_json::{json, Value};
+
+pub async fn load_handler_v1_get(
+    State(state): State<ApiGatewayState>,
+    auth: GatewayAuth,
+    Query(params): Query<QueryParams>,
+) -> Result<Json<Value>, HttpError> {
+    let query = params.parse_query()?;
+
+    load_v1(state, auth, query).await
+}
+
+pub async fn load_handler_v1_post(
+    State(state): State<ApiGatewayState>,
+    auth: GatewayAuth,
+    Json(body): Json<QueryBody>,
+) -> Result<Json<Value>, HttpError> {
+    load_v1(state, auth, body.query).await
+}
+
+async fn load_v1(
+    state: ApiGatewayState,
+    auth: GatewayAuth,
+    query: TransportLoadRequestQuery,
+) -> Result<Json<Value>, HttpError> {
+    let prepared_query = prepare_query(&auth, query.clone())?;
+    let transport = state.transport_service().await;
+
+    // Client repeats the request after "Continue wait" response, as with JS gateway
+    let mut meta = rest_load_request_meta();
+    meta.set_retry_continue_wait(false);
+
+    match transport
+        .load(None, prepared_query, None, auth.context.clone(), meta)
+        .await
+    {
+        Ok(response) => Ok(Json(load_response_to_json(query, response)?)),
+        Err(err) if err.message.eq_ignore_ascii_case("continue wait") => {
+            Ok(Json(json!({ "error": err.message })))
+        }
+        Err(err) => Err(err.into()),
+    }
+}
+
+fn load_response_to_json(
+    query: TransportLoadRequestQuery,
+    mut response: TransportLoadResponse,
+) -> Result<Value, HttpError> {
+    if response.results.len() != 1 {
+        return Ok(serde_json::to_value(response)?);
+    }
+
+    // Regular query is responded by the single result, as JS gateway does. Query is the one
+    // sent by the client, security filters applied by the gateway are not exposed
+    let mut result = serde_json::to_value(response.results.remove(0))?;
+    if let Value::Object(result) = &mut result {
+        result.insert("query".to_string(), serde_json::to_value(query)?);
+    }
+
+    Ok(result)
+}
@@ -0,0 +1,36 @@
+use crate::gateway::auth::GatewayAuth;
+use crate::gateway::http_error::HttpError;
+use crate::gateway::ApiGatewayState;
+use axum::extract::State;
+use axum::Json;
+use cubesql::transport::TransportMetaResponse;
+
+pub async fn meta_handler_v1(
+    State(state): State<ApiGatewayState>,
+    auth: GatewayAuth,
+) -> Result<Json<TransportMetaResponse>, HttpError> {
+    let meta = state
+        .transport_service()
+        .await
+        .meta(auth.context.clone())
+        .await?;
+
+    let cubes = match &auth.security_policy {
+        Some(security_policy) => meta.restrict(security_policy).cubes,
+        None => meta.cubes.clone(),
+    };
+
+    let cost_hints = cubes
+        .iter()
+        .filter_map(|cube| {
+            meta.cube_cost_hints(&cube.name)
+                .map(|cost_hints| (cube.name.clone(), cost_hints.clone()))
+        })
+        .collect();
+
+    Ok(Json(TransportMetaResponse {
+        cubes: Some(cubes),
+        compiler_id: None,
+        cost_hints: Some(cost_hints),
+    }))
+}
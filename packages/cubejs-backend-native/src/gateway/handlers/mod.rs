@@ -1,3 +1,10 @@
+mod load;
+mod meta;
+mod query;
+mod sql;
 mod stream;
 
+pub use load::*;
+pub use meta::*;
+pub use sql::*;
 pub use stream::*;
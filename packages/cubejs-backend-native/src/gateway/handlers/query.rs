@@ -0,0 +1,48 @@
+use crate::gateway::auth::GatewayAuth;
+use crate::gateway::http_error::HttpError;
+use cubesql::transport::{LoadRequestMeta, TransportLoadRequestQuery};
+use serde::Deserialize;
+
+/// Query string of GET requests, query is passed as JSON
+#[derive(Debug, Deserialize)]
+pub struct QueryParams {
+    pub query: Option<String>,
+    pub format: Option<String>,
+}
+
+/// Body of POST requests
+#[derive(Debug, Deserialize)]
+pub struct QueryBody {
+    pub query: TransportLoadRequestQuery,
+    pub format: Option<String>,
+}
+
+impl QueryParams {
+    pub fn parse_query(&self) -> Result<TransportLoadRequestQuery, HttpError> {
+        let query = self
+            .query
+            .as_ref()
+            .ok_or_else(|| HttpError::bad_request("Query param is required".to_string()))?;
+
+        serde_json::from_str(query)
+            .map_err(|err| HttpError::bad_request(format!("Unable to decode query: {}", err)))
+    }
+}
+
+pub fn prepare_query(
+    auth: &GatewayAuth,
+    mut query: TransportLoadRequestQuery,
+) -> Result<TransportLoadRequestQuery, HttpError> {
+    if let Some(security_policy) = &auth.security_policy {
+        security_policy.apply_to_request(&mut query, &[])?;
+    }
+
+    Ok(query)
+}
+
+pub fn rest_load_request_meta() -> LoadRequestMeta {
+    let mut meta = LoadRequestMeta::new("http".to_string(), "rest".to_string(), None);
+    meta.set_rest_api(true);
+
+    meta
+}
@@ -0,0 +1,57 @@
+use crate::gateway::auth::GatewayAuth;
+use crate::gateway::handlers::query::{
+    prepare_query, rest_load_request_meta, QueryBody, QueryParams,
+};
+use crate::gateway::http_error::HttpError;
+use crate::gateway::ApiGatewayState;
+use axum::extract::{Query, State};
+use axum::Json;
+use cubesql::transport::TransportLoadRequestQuery;
+use serde_json::{json, Value};
+
+pub async fn sql_handler_v1_get(
+    State(state): State<ApiGatewayState>,
+    auth: GatewayAuth,
+    Query(params): Query<QueryParams>,
+) -> Result<Json<Value>, HttpError> {
+    let query = params.parse_query()?;
+
+    sql_v1(state, auth, query).await
+}
+
+pub async fn sql_handler_v1_post(
+    State(state): State<ApiGatewayState>,
+    auth: GatewayAuth,
+    Json(body): Json<QueryBody>,
+) -> Result<Json<Value>, HttpError> {
+    sql_v1(state, auth, body.query).await
+}
+
+async fn sql_v1(
+    state: ApiGatewayState,
+    auth: GatewayAuth,
+    query: TransportLoadRequestQuery,
+) -> Result<Json<Value>, HttpError> {
+    // SQL is generated with security filters, but they are not returned as a part of the query
+    let prepared_query = prepare_query(&auth, query)?;
+
+    let response = state
+        .transport_service()
+        .await
+        .sql(
+            None,
+            prepared_query,
+            auth.context.clone(),
+            rest_load_request_meta(),
+            None,
+            None,
+        )
+        .await?;
+
+    // Same shape as `sql` of JS gateway response: SQL goes with its params
+    Ok(Json(json!({
+        "sql": {
+            "sql": [response.sql.sql, response.sql.values],
+        }
+    })))
+}
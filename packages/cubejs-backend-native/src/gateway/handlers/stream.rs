@@ -1,21 +1,279 @@
+use crate::gateway::auth::GatewayAuth;
+use crate::gateway::handlers::query::{
+    prepare_query, rest_load_request_meta, QueryBody, QueryParams,
+};
+use crate::gateway::http_error::HttpError;
 use crate::gateway::ApiGatewayState;
-use axum::extract::State;
-use axum::http::StatusCode;
+use crate::utils::batch_to_rows;
+use axum::body::Body;
+use axum::extract::{Query, State};
+use axum::http::header::{ACCEPT, CONTENT_TYPE};
+use axum::http::HeaderMap;
+use axum::response::{IntoResponse, Response};
 use axum::Json;
-use serde::Serialize;
-
-#[derive(Serialize)]
-pub struct HandlerResponse {
-    message: String,
-}
-
-pub async fn stream_handler_v2(
-    State(_state): State<ApiGatewayState>,
-) -> (StatusCode, Json<HandlerResponse>) {
-    (
-        StatusCode::NOT_IMPLEMENTED,
-        Json(HandlerResponse {
-            message: "Not implemented".to_string(),
-        }),
-    )
+use cubesql::compile::engine::df::scan::{DataType, MemberField, RecordBatch, SchemaRef};
+use cubesql::transport::{CubeStreamReceiver, MetaContext, TransportLoadRequestQuery};
+use cubesql::CubeError;
+use datafusion::arrow::datatypes::{Field, Schema};
+use datafusion::arrow::ipc::writer::{
+    write_message, DictionaryTracker, IpcDataGenerator, IpcWriteOptions,
+};
+use futures::stream::{self, StreamExt};
+use serde_json::{Map, Value};
+use std::sync::Arc;
+
+const NDJSON_CONTENT_TYPE: &str = "application/x-ndjson";
+const ARROW_STREAM_CONTENT_TYPE: &str = "application/vnd.apache.arrow.stream";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum StreamFormat {
+    NdJson,
+    ArrowIpc,
+}
+
+impl StreamFormat {
+    fn negotiate(format: &Option<String>, headers: &HeaderMap) -> Result<Self, HttpError> {
+        match format.as_deref() {
+            Some("ndjson") => return Ok(Self::NdJson),
+            Some("arrow") => return Ok(Self::ArrowIpc),
+            Some(other) => {
+                return Err(HttpError::bad_request(format!(
+                    "Unsupported format '{}', expected ndjson or arrow",
+                    other
+                )))
+            }
+            None => {}
+        }
+
+        let accepts_arrow = headers
+            .get(ACCEPT)
+            .and_then(|accept| accept.to_str().ok())
+            .map(|accept| accept.contains(ARROW_STREAM_CONTENT_TYPE))
+            .unwrap_or(false);
+
+        Ok(if accepts_arrow {
+            Self::ArrowIpc
+        } else {
+            Self::NdJson
+        })
+    }
+
+    fn content_type(&self) -> &'static str {
+        match self {
+            Self::NdJson => NDJSON_CONTENT_TYPE,
+            Self::ArrowIpc => ARROW_STREAM_CONTENT_TYPE,
+        }
+    }
+
+    fn encoder(&self, schema: SchemaRef) -> Box<dyn BatchEncoder> {
+        match self {
+            Self::NdJson => Box::new(NdJsonEncoder { schema }),
+            Self::ArrowIpc => Box::new(ArrowIpcEncoder::new(schema)),
+        }
+    }
+}
+
+trait BatchEncoder: Send {
+    fn start(&mut self) -> Result<Vec<u8>, CubeError>;
+
+    fn encode(&mut self, batch: RecordBatch) -> Result<Vec<u8>, CubeError>;
+
+    fn finish(&mut self) -> Result<Vec<u8>, CubeError>;
+}
+
+/// One JSON object per row, keys are member names
+struct NdJsonEncoder {
+    schema: SchemaRef,
+}
+
+impl BatchEncoder for NdJsonEncoder {
+    fn start(&mut self) -> Result<Vec<u8>, CubeError> {
+        Ok(vec![])
+    }
+
+    fn encode(&mut self, batch: RecordBatch) -> Result<Vec<u8>, CubeError> {
+        let (_, rows) = batch_to_rows(batch)?;
+
+        let mut buffer = Vec::new();
+        for row in rows {
+            let Value::Array(values) = row else {
+                return Err(CubeError::internal(
+                    "Row is expected to be an array".to_string(),
+                ));
+            };
+            let object = self
+                .schema
+                .fields()
+                .iter()
+                .map(|field| field.name().clone())
+                .zip(values)
+                .collect::<Map<_, _>>();
+            serde_json::to_writer(&mut buffer, &object)?;
+            buffer.push(b'\n');
+        }
+
+        Ok(buffer)
+    }
+
+    fn finish(&mut self) -> Result<Vec<u8>, CubeError> {
+        Ok(vec![])
+    }
+}
+
+/// Arrow IPC streaming format, schema message goes first and batches are written as they come
+struct ArrowIpcEncoder {
+    schema: SchemaRef,
+    generator: IpcDataGenerator,
+    dictionary_tracker: DictionaryTracker,
+    options: IpcWriteOptions,
+}
+
+impl ArrowIpcEncoder {
+    fn new(schema: SchemaRef) -> Self {
+        Self {
+            schema,
+            generator: IpcDataGenerator::default(),
+            dictionary_tracker: DictionaryTracker::new(false),
+            options: IpcWriteOptions::default(),
+        }
+    }
+}
+
+impl BatchEncoder for ArrowIpcEncoder {
+    fn start(&mut self) -> Result<Vec<u8>, CubeError> {
+        let mut buffer = Vec::new();
+        let encoded = self.generator.schema_to_bytes(&self.schema, &self.options);
+        write_message(&mut buffer, encoded, &self.options)?;
+
+        Ok(buffer)
+    }
+
+    fn encode(&mut self, batch: RecordBatch) -> Result<Vec<u8>, CubeError> {
+        let mut buffer = Vec::new();
+        let (dictionaries, encoded) =
+            self.generator
+                .encoded_batch(&batch, &mut self.dictionary_tracker, &self.options)?;
+        for dictionary in dictionaries {
+            write_message(&mut buffer, dictionary, &self.options)?;
+        }
+        write_message(&mut buffer, encoded, &self.options)?;
+
+        Ok(buffer)
+    }
+
+    fn finish(&mut self) -> Result<Vec<u8>, CubeError> {
+        // End-of-stream marker: continuation token followed by zero length
+        Ok([0xFF, 0xFF, 0xFF, 0xFF, 0x00, 0x00, 0x00, 0x00].to_vec())
+    }
+}
+
+pub async fn stream_handler_v2_get(
+    State(state): State<ApiGatewayState>,
+    auth: GatewayAuth,
+    headers: HeaderMap,
+    Query(params): Query<QueryParams>,
+) -> Result<Response, HttpError> {
+    let query = params.parse_query()?;
+    let format = StreamFormat::negotiate(&params.format, &headers)?;
+
+    stream_v2(state, auth, query, format).await
+}
+
+pub async fn stream_handler_v2_post(
+    State(state): State<ApiGatewayState>,
+    auth: GatewayAuth,
+    headers: HeaderMap,
+    Json(body): Json<QueryBody>,
+) -> Result<Response, HttpError> {
+    let format = StreamFormat::negotiate(&body.format, &headers)?;
+
+    stream_v2(state, auth, body.query, format).await
+}
+
+/// Members of the query in the order of the result columns
+fn query_member_names(query: &TransportLoadRequestQuery) -> Vec<String> {
+    let dimensions = query.dimensions.iter().flatten().cloned();
+    let time_dimensions = query.time_dimensions.iter().flatten().filter_map(|td| {
+        td.granularity
+            .as_ref()
+            .map(|granularity| format!("{}.{}", td.dimension, granularity))
+    });
+    let measures = query.measures.iter().flatten().cloned();
+
+    dimensions.chain(time_dimensions).chain(measures).collect()
+}
+
+fn build_schema(meta: &MetaContext, member_names: &[String]) -> (SchemaRef, Vec<MemberField>) {
+    let fields = member_names
+        .iter()
+        .map(|name| {
+            // Time dimension with granularity has the type of the dimension itself
+            let member = match name.split('.').collect::<Vec<_>>().as_slice() {
+                [cube, dimension, _granularity] => format!("{}.{}", cube, dimension),
+                _ => name.clone(),
+            };
+            let data_type = meta.find_df_data_type(member).unwrap_or(DataType::Utf8);
+
+            Field::new(name, data_type, true)
+        })
+        .collect::<Vec<_>>();
+    let member_fields = member_names
+        .iter()
+        .map(|name| MemberField::Member(name.clone()))
+        .collect();
+
+    (Arc::new(Schema::new(fields)), member_fields)
+}
+
+async fn stream_v2(
+    state: ApiGatewayState,
+    auth: GatewayAuth,
+    query: TransportLoadRequestQuery,
+    format: StreamFormat,
+) -> Result<Response, HttpError> {
+    let query = prepare_query(&auth, query)?;
+    let transport = state.transport_service().await;
+
+    let meta = transport.meta(auth.context.clone()).await?;
+    let (schema, member_fields) = build_schema(&meta, &query_member_names(&query));
+
+    let receiver = transport
+        .load_stream(
+            None,
+            query,
+            None,
+            auth.context.clone(),
+            rest_load_request_meta(),
+            schema.clone(),
+            member_fields,
+        )
+        .await?;
+
+    let body = Body::from_stream(encode_stream(receiver, format.encoder(schema)));
+
+    Ok(([(CONTENT_TYPE, format.content_type())], body).into_response())
+}
+
+fn encode_stream(
+    receiver: CubeStreamReceiver,
+    mut encoder: Box<dyn BatchEncoder>,
+) -> impl futures::Stream<Item = Result<Vec<u8>, CubeError>> {
+    let start = encoder.start();
+    let batches = stream::unfold(Some((receiver, encoder)), |state| async move {
+        let (mut receiver, mut encoder) = state?;
+
+        match receiver.recv().await {
+            Some(Some(Ok(batch))) => {
+                let chunk = encoder.encode(batch);
+                Some((chunk, Some((receiver, encoder))))
+            }
+            Some(Some(Err(err))) => {
+                log::error!("[gateway] Stream error: {}", err);
+                Some((Err(err), None))
+            }
+            Some(None) | None => Some((encoder.finish(), None)),
+        }
+    });
+
+    stream::once(async move { start }).chain(batches)
 }
@@ -0,0 +1,71 @@
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use cubesql::{CubeError, CubeErrorCauseType};
+use serde::Serialize;
+
+#[derive(Debug)]
+pub struct HttpError {
+    status: StatusCode,
+    message: String,
+}
+
+#[derive(Serialize)]
+struct HttpErrorBody {
+    error: String,
+}
+
+impl HttpError {
+    pub fn new(status: StatusCode, message: String) -> Self {
+        Self { status, message }
+    }
+
+    pub fn bad_request(message: String) -> Self {
+        Self::new(StatusCode::BAD_REQUEST, message)
+    }
+
+    pub fn forbidden(message: String) -> Self {
+        Self::new(StatusCode::FORBIDDEN, message)
+    }
+
+    pub fn internal(message: String) -> Self {
+        Self::new(StatusCode::INTERNAL_SERVER_ERROR, message)
+    }
+
+    pub fn status(&self) -> StatusCode {
+        self.status
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl From<CubeError> for HttpError {
+    fn from(err: CubeError) -> Self {
+        match err.cause {
+            CubeErrorCauseType::User(_) => Self::bad_request(err.message),
+            CubeErrorCauseType::Internal(_) => Self::internal(err.message),
+        }
+    }
+}
+
+impl From<serde_json::Error> for HttpError {
+    fn from(err: serde_json::Error) -> Self {
+        Self::internal(err.to_string())
+    }
+}
+
+impl IntoResponse for HttpError {
+    fn into_response(self) -> Response {
+        log::debug!("[gateway] {} {}", self.status, self.message);
+
+        (
+            self.status,
+            Json(HttpErrorBody {
+                error: self.message,
+            }),
+        )
+            .into_response()
+    }
+}
@@ -1,8 +1,12 @@
+pub mod auth;
+pub mod auth_service;
 pub mod handlers;
+pub mod http_error;
 pub mod router;
 pub mod server;
 pub mod state;
 
+pub use auth_service::{GatewayAuthService, GatewayAuthenticateResponse, GatewayCheckAuthRequest};
 pub use router::ApiGatewayRouterBuilder;
 pub use server::{ApiGatewayServer, ApiGatewayServerImpl};
 pub use state::ApiGatewayState;
@@ -1,4 +1,7 @@
-use crate::gateway::handlers::stream_handler_v2;
+use crate::gateway::handlers::{
+    load_handler_v1_get, load_handler_v1_post, meta_handler_v1, sql_handler_v1_get,
+    sql_handler_v1_post, stream_handler_v2_get, stream_handler_v2_post,
+};
 use crate::gateway::ApiGatewayState;
 use axum::routing::{get, MethodRouter};
 use axum::Router;
@@ -17,7 +20,17 @@ impl Default for ApiGatewayRouterBuilder {
 impl ApiGatewayRouterBuilder {
     pub fn new() -> Self {
         let router = Router::new();
-        let router = router.route("/v2/stream", get(stream_handler_v2));
+        let router = router
+            .route("/v1/meta", get(meta_handler_v1))
+            .route(
+                "/v1/load",
+                get(load_handler_v1_get).post(load_handler_v1_post),
+            )
+            .route("/v1/sql", get(sql_handler_v1_get).post(sql_handler_v1_post))
+            .route(
+                "/v2/stream",
+                get(stream_handler_v2_get).post(stream_handler_v2_post),
+            );
 
         Self { router }
     }
@@ -1,4 +1,6 @@
+use crate::gateway::GatewayAuthService;
 use cubesql::config::injection::Injector;
+use cubesql::transport::TransportService;
 use std::sync::Arc;
 
 #[derive(Clone)]
@@ -14,4 +16,16 @@ impl ApiGatewayState {
     pub fn injector_ref(&self) -> &Arc<Injector> {
         &self.injector
     }
+
+    pub async fn transport_service(&self) -> Arc<dyn TransportService> {
+        self.injector
+            .get_service_typed::<dyn TransportService>()
+            .await
+    }
+
+    pub async fn auth_service(&self) -> Arc<dyn GatewayAuthService> {
+        self.injector
+            .get_service_typed::<dyn GatewayAuthService>()
+            .await
+    }
 }
@@ -0,0 +1,409 @@
+use cubesql::CubeError;
+use jsonwebtoken::{
+    decode, decode_header,
+    jwk::{Jwk, JwkSet},
+    Algorithm, DecodingKey, Validation,
+};
+use log::trace;
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::str::FromStr;
+
+/// JWT options passed from JS, field names follow `JWTOptions` from the api-gateway.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct JwtAuthConfig {
+    pub key: Option<String>,
+    /// Path to a local file with JWKS (`{"keys": [...]}`)
+    #[serde(rename = "jwkFile")]
+    pub jwk_file: Option<String>,
+    pub algorithms: Option<Vec<String>>,
+    pub issuer: Option<Vec<String>>,
+    pub audience: Option<String>,
+    pub subject: Option<String>,
+    /// Security context is taken from this claim of the payload
+    #[serde(rename = "claimsNamespace")]
+    pub claims_namespace: Option<String>,
+}
+
+enum JwtKeys {
+    Static(DecodingKey),
+    Jwks(HashMap<String, (DecodingKey, Option<Algorithm>)>),
+}
+
+impl fmt::Debug for JwtKeys {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Never print key material
+        match self {
+            JwtKeys::Static(_) => write!(f, "Static"),
+            JwtKeys::Jwks(keys) => f.debug_list().entries(keys.keys()).finish(),
+        }
+    }
+}
+
+/// Verifies JWT tokens natively, without calling back into JS.
+#[derive(Debug)]
+pub struct JwtAuth {
+    keys: JwtKeys,
+    algorithms: Vec<Algorithm>,
+    issuer: Option<Vec<String>>,
+    audience: Option<String>,
+    subject: Option<String>,
+    claims_namespace: Option<String>,
+}
+
+impl JwtAuth {
+    pub fn try_new(config: JwtAuthConfig) -> Result<Self, CubeError> {
+        let algorithms = config
+            .algorithms
+            .unwrap_or_default()
+            .iter()
+            .map(|alg| {
+                Algorithm::from_str(alg)
+                    .map_err(|_| CubeError::user(format!("Unsupported JWT algorithm: {}", alg)))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let (keys, default_algorithms) = match (config.key, config.jwk_file) {
+            (_, Some(jwk_file)) => (
+                JwtKeys::Jwks(Self::read_jwks(&jwk_file)?),
+                vec![Algorithm::RS256],
+            ),
+            (Some(key), None) if key.trim_start().starts_with("-----BEGIN") => (
+                JwtKeys::Static(DecodingKey::from_rsa_pem(key.as_bytes()).map_err(|e| {
+                    CubeError::user(format!("Unable to parse JWT public key: {}", e))
+                })?),
+                vec![Algorithm::RS256],
+            ),
+            (Some(key), None) => (
+                JwtKeys::Static(DecodingKey::from_secret(key.as_bytes())),
+                vec![Algorithm::HS256],
+            ),
+            (None, None) => {
+                return Err(CubeError::user(
+                    "JWT key or jwkFile must be specified for native JWT auth".to_string(),
+                ))
+            }
+        };
+
+        Ok(Self {
+            keys,
+            algorithms: if algorithms.is_empty() {
+                default_algorithms
+            } else {
+                algorithms
+            },
+            issuer: config.issuer,
+            audience: config.audience,
+            subject: config.subject,
+            claims_namespace: config.claims_namespace,
+        })
+    }
+
+    fn read_jwks(
+        path: &str,
+    ) -> Result<HashMap<String, (DecodingKey, Option<Algorithm>)>, CubeError> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| CubeError::user(format!("Unable to read JWK file {}: {}", path, e)))?;
+        let jwks: JwkSet = serde_json::from_str(&content)
+            .map_err(|e| CubeError::user(format!("Unable to parse JWK file {}: {}", path, e)))?;
+
+        let mut keys = HashMap::new();
+
+        for jwk in jwks.keys.iter() {
+            let Some(kid) = jwk.common.key_id.clone() else {
+                continue;
+            };
+
+            keys.insert(kid, (Self::decoding_key(jwk)?, Self::jwk_algorithm(jwk)));
+        }
+
+        trace!("[jwt] Loaded {} JWKs from {}", keys.len(), path);
+
+        Ok(keys)
+    }
+
+    fn decoding_key(jwk: &Jwk) -> Result<DecodingKey, CubeError> {
+        DecodingKey::from_jwk(jwk).map_err(|e| CubeError::user(format!("Unable to use JWK: {}", e)))
+    }
+
+    fn jwk_algorithm(jwk: &Jwk) -> Option<Algorithm> {
+        let alg = serde_json::to_value(jwk.common.key_algorithm.as_ref()?).ok()?;
+
+        Algorithm::from_str(alg.as_str()?).ok()
+    }
+
+    /// Returns `true` if value has a shape of JWT, it's used to distinguish tokens from passwords.
+    pub fn is_token(value: &str) -> bool {
+        decode_header(value).is_ok()
+    }
+
+    /// Verifies signature and registered claims (exp, nbf, iss, aud, sub), returns the payload,
+    /// which is used as a security context. With `claimsNamespace` only this claim is returned,
+    /// same as the security context extractor of JS gateway does.
+    pub fn verify(&self, token: &str) -> Result<serde_json::Value, CubeError> {
+        let header = decode_header(token)
+            .map_err(|e| CubeError::user(format!("Unable to decode JWT: {}", e)))?;
+
+        let key = match &self.keys {
+            JwtKeys::Static(key) => key,
+            JwtKeys::Jwks(keys) => {
+                let kid = header
+                    .kid
+                    .as_ref()
+                    .ok_or_else(|| CubeError::user("JWT without kid inside headers".to_string()))?;
+                let (key, algorithm) = keys.get(kid).ok_or_else(|| {
+                    CubeError::user(format!(
+                        "Unable to verify, JWK with kid: \"{}\" not found",
+                        kid
+                    ))
+                })?;
+
+                if let Some(algorithm) = algorithm {
+                    if *algorithm != header.alg {
+                        return Err(CubeError::user(format!(
+                            "JWT algorithm {:?} doesn't match JWK algorithm {:?}",
+                            header.alg, algorithm
+                        )));
+                    }
+                }
+
+                key
+            }
+        };
+
+        let data = decode::<serde_json::Value>(token, key, &self.validation())
+            .map_err(|e| CubeError::user(format!("Invalid token: {}", e)))?;
+
+        match &self.claims_namespace {
+            Some(namespace) => Ok(data
+                .claims
+                .get(namespace)
+                .cloned()
+                .unwrap_or_else(|| serde_json::json!({}))),
+            None => Ok(data.claims),
+        }
+    }
+
+    fn validation(&self) -> Validation {
+        let mut validation = Validation::new(self.algorithms[0]);
+        validation.algorithms = self.algorithms.clone();
+        // Same as jsonwebtoken in JS: exp is checked only when it's present
+        validation.required_spec_claims = HashSet::new();
+        validation.validate_nbf = true;
+
+        if let Some(issuer) = &self.issuer {
+            validation.set_issuer(issuer);
+        }
+
+        match &self.audience {
+            Some(audience) => validation.set_audience(&[audience]),
+            None => validation.validate_aud = false,
+        }
+
+        validation.sub = self.subject.clone();
+
+        validation
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jsonwebtoken::{encode, get_current_timestamp, EncodingKey, Header};
+    use serde_json::{json, Value};
+
+    // RSA key pair and JWKS of its public key, generated for tests only
+    const PRIVATE_KEY: &str = include_str!("../test/fixtures/jwt/private.pem");
+    const PUBLIC_KEY: &str = include_str!("../test/fixtures/jwt/public.pem");
+
+    fn jwks_path() -> String {
+        format!("{}/test/fixtures/jwt/jwks.json", env!("CARGO_MANIFEST_DIR"))
+    }
+
+    fn hs256_token(secret: &str, claims: &Value) -> String {
+        encode(
+            &Header::new(Algorithm::HS256),
+            claims,
+            &EncodingKey::from_secret(secret.as_bytes()),
+        )
+        .unwrap()
+    }
+
+    fn rs256_token(kid: Option<&str>, claims: &Value) -> String {
+        let mut header = Header::new(Algorithm::RS256);
+        header.kid = kid.map(|kid| kid.to_string());
+
+        encode(
+            &header,
+            claims,
+            &EncodingKey::from_rsa_pem(PRIVATE_KEY.as_bytes()).unwrap(),
+        )
+        .unwrap()
+    }
+
+    fn secret_auth(config: JwtAuthConfig) -> JwtAuth {
+        JwtAuth::try_new(JwtAuthConfig {
+            key: Some("secret".to_string()),
+            ..config
+        })
+        .unwrap()
+    }
+
+    fn assert_invalid(result: Result<Value, CubeError>, message: &str) {
+        match result {
+            Ok(payload) => panic!("Token must be rejected, payload: {}", payload),
+            Err(err) => assert!(
+                err.message.contains(message),
+                "Expected error with '{}', actual: {}",
+                message,
+                err.message
+            ),
+        }
+    }
+
+    #[test]
+    fn test_hs256() {
+        let auth = secret_auth(JwtAuthConfig::default());
+        let claims = json!({ "tenant_id": 42 });
+
+        assert_eq!(
+            auth.verify(&hs256_token("secret", &claims)).unwrap(),
+            claims
+        );
+        assert_invalid(
+            auth.verify(&hs256_token("other secret", &claims)),
+            "InvalidSignature",
+        );
+        // RS256 is not allowed for a static secret
+        assert_invalid(auth.verify(&rs256_token(None, &claims)), "InvalidAlgorithm");
+    }
+
+    #[test]
+    fn test_rs256() {
+        let auth = JwtAuth::try_new(JwtAuthConfig {
+            key: Some(PUBLIC_KEY.to_string()),
+            ..JwtAuthConfig::default()
+        })
+        .unwrap();
+        let claims = json!({ "tenant_id": 42 });
+
+        assert_eq!(auth.verify(&rs256_token(None, &claims)).unwrap(), claims);
+        assert_invalid(
+            auth.verify(&hs256_token("secret", &claims)),
+            "InvalidAlgorithm",
+        );
+    }
+
+    #[test]
+    fn test_jwks_kid() {
+        let auth = JwtAuth::try_new(JwtAuthConfig {
+            jwk_file: Some(jwks_path()),
+            ..JwtAuthConfig::default()
+        })
+        .unwrap();
+        let claims = json!({ "tenant_id": 42 });
+
+        assert_eq!(
+            auth.verify(&rs256_token(Some("test-rs256"), &claims))
+                .unwrap(),
+            claims
+        );
+        assert_invalid(
+            auth.verify(&rs256_token(Some("unknown"), &claims)),
+            "JWK with kid: \"unknown\" not found",
+        );
+        assert_invalid(
+            auth.verify(&rs256_token(None, &claims)),
+            "JWT without kid inside headers",
+        );
+    }
+
+    #[test]
+    fn test_jwks_algorithm_mismatch() {
+        let auth = JwtAuth::try_new(JwtAuthConfig {
+            jwk_file: Some(jwks_path()),
+            algorithms: Some(vec!["RS256".to_string(), "RS512".to_string()]),
+            ..JwtAuthConfig::default()
+        })
+        .unwrap();
+
+        assert_invalid(
+            auth.verify(&rs256_token(Some("test-rs512"), &json!({}))),
+            "doesn't match JWK algorithm",
+        );
+    }
+
+    #[test]
+    fn test_time_claims() {
+        let auth = secret_auth(JwtAuthConfig::default());
+        let now = get_current_timestamp();
+
+        assert!(auth
+            .verify(&hs256_token(
+                "secret",
+                &json!({ "exp": now + 600, "nbf": now - 600 })
+            ))
+            .is_ok());
+        // Default leeway is 60 seconds
+        assert_invalid(
+            auth.verify(&hs256_token("secret", &json!({ "exp": now - 600 }))),
+            "ExpiredSignature",
+        );
+        assert_invalid(
+            auth.verify(&hs256_token("secret", &json!({ "nbf": now + 600 }))),
+            "ImmatureSignature",
+        );
+    }
+
+    #[test]
+    fn test_registered_claims() {
+        let auth = secret_auth(JwtAuthConfig {
+            issuer: Some(vec!["cube".to_string(), "cube-cloud".to_string()]),
+            audience: Some("cube-api".to_string()),
+            subject: Some("user".to_string()),
+            ..JwtAuthConfig::default()
+        });
+        let valid = json!({ "iss": "cube-cloud", "aud": "cube-api", "sub": "user" });
+
+        assert!(auth.verify(&hs256_token("secret", &valid)).is_ok());
+
+        let with = |key: &str, value: Value| {
+            let mut claims = valid.clone();
+            claims[key] = value;
+            hs256_token("secret", &claims)
+        };
+
+        assert_invalid(auth.verify(&with("iss", json!("other"))), "InvalidIssuer");
+        assert_invalid(auth.verify(&with("aud", json!("other"))), "InvalidAudience");
+        assert_invalid(auth.verify(&with("sub", json!("other"))), "InvalidSubject");
+    }
+
+    #[test]
+    fn test_claims_namespace() {
+        let auth = secret_auth(JwtAuthConfig {
+            claims_namespace: Some("https://cube.dev/claims".to_string()),
+            ..JwtAuthConfig::default()
+        });
+
+        assert_eq!(
+            auth.verify(&hs256_token(
+                "secret",
+                &json!({ "sub": "user", "https://cube.dev/claims": { "tenant_id": 42 } })
+            ))
+            .unwrap(),
+            json!({ "tenant_id": 42 })
+        );
+        assert_eq!(
+            auth.verify(&hs256_token("secret", &json!({ "tenant_id": 42 })))
+                .unwrap(),
+            json!({})
+        );
+    }
+
+    #[test]
+    fn test_is_token() {
+        assert!(JwtAuth::is_token(&hs256_token("secret", &json!({}))));
+        assert!(!JwtAuth::is_token("password"));
+    }
+}
@@ -9,6 +9,7 @@ pub mod channel;
 pub mod config;
 pub mod cross;
 pub mod gateway;
+pub mod jwt;
 pub mod logger;
 pub mod node_export;
 pub mod node_obj_serializer;
@@ -18,6 +18,7 @@ use crate::auth::{NativeAuthContext, NodeBridgeAuthService};
 use crate::channel::call_js_fn;
 use crate::config::{NodeConfiguration, NodeConfigurationFactoryOptions, NodeCubeServices};
 use crate::cross::CLRepr;
+use crate::jwt::{JwtAuth, JwtAuthConfig};
 use crate::logger::NodeBridgeLogger;
 use crate::stream::OnDrainHandler;
 use crate::tokio_runtime_node;
@@ -71,6 +72,18 @@ fn register_interface<C: NodeConfiguration>(mut cx: FunctionContext) -> JsResult
     let transport_can_switch_user_for_session = options
         .get::<JsFunction, _, _>(&mut cx, "canSwitchUserForSession")?
         .root(&mut cx);
+    let check_api_gateway_auth = options
+        .get_opt::<JsFunction, _, _>(&mut cx, "checkApiGatewayAuth")?
+        .map(|f| f.root(&mut cx));
+    let jwt_auth = match options.get_opt::<JsString, _, _>(&mut cx, "jwt")? {
+        Some(jwt) => {
+            let config = serde_json::from_str::<JwtAuthConfig>(&jwt.value(&mut cx))
+                .or_else(|err| cx.throw_error(format!("Unable to parse jwt options: {}", err)))?;
+
+            Some(JwtAuth::try_new(config).or_else(|err| cx.throw_error(err.message))?)
+        }
+        None => None,
+    };
 
     let pg_port_handle = options.get_value(&mut cx, "pgPort")?;
     let pg_port = if pg_port_handle.is_a::<JsNumber, _>(&mut cx) {
@@ -103,7 +116,8 @@ fn register_interface<C: NodeConfiguration>(mut cx: FunctionContext) -> JsResult
         transport_sql_generator,
         transport_can_switch_user_for_session,
     );
-    let auth_service = NodeBridgeAuthService::new(cx.channel(), check_auth);
+    let auth_service =
+        NodeBridgeAuthService::new(cx.channel(), check_auth, check_api_gateway_auth, jwt_auth);
 
     std::thread::spawn(move || {
         let config = C::new(NodeConfigurationFactoryOptions {
@@ -462,7 +476,16 @@ pub fn setup_logger(mut cx: FunctionContext) -> JsResult<JsUndefined> {
 
 //============ sql planner ===================
 
-fn build_sql_and_params(cx: FunctionContext) -> JsResult<JsValue> {
+type SqlPlannerInnerTypes = NeonInnerTypes<'static, FunctionContext<'static>>;
+
+/// Builds BaseQuery from the options passed as the first argument and calls `f` with it,
+/// planner errors are thrown as JS errors
+fn with_sql_planner_base_query<F>(cx: FunctionContext, f: F) -> JsResult<JsValue>
+where
+    F: FnOnce(
+        &BaseQuery<SqlPlannerInnerTypes>,
+    ) -> Result<NativeObjectHandle<SqlPlannerInnerTypes>, CubeError>,
+{
     //IMPORTANT It seems to be safe here, because context lifetime is bound to function, but this
     //context should be used only inside function
     let mut cx = extend_function_context_lifetime(cx);
@@ -470,26 +493,34 @@ fn build_sql_and_params(cx: FunctionContext) -> JsResult<JsValue> {
 
     let neon_context_holder = ContextHolder::new(cx);
 
-    let options = NativeObjectHandle::<NeonInnerTypes<'static, FunctionContext<'static>>>::new(
-        NeonObject::new(neon_context_holder.clone(), options),
-    );
+    let options = NativeObjectHandle::<SqlPlannerInnerTypes>::new(NeonObject::new(
+        neon_context_holder.clone(),
+        options,
+    ));
 
     let context_holder =
-        NativeContextHolder::<NeonInnerTypes<'static, FunctionContext<'static>>>::new(
-            neon_context_holder,
-        );
+        NativeContextHolder::<SqlPlannerInnerTypes>::new(neon_context_holder.clone());
 
-    let base_query_options = Rc::new(NativeBaseQueryOptions::from_native(options).unwrap());
+    let result = NativeBaseQueryOptions::from_native(options)
+        .and_then(|options| BaseQuery::try_new(context_holder, Rc::new(options)))
+        .and_then(|base_query| f(&base_query));
 
-    let base_query = BaseQuery::try_new(context_holder.clone(), base_query_options).unwrap();
+    match result {
+        Ok(res) => {
+            let result: NeonObject<'static, FunctionContext<'static>> = res.into_object();
 
-    //arg_clrep.into_js(&mut cx)
-    let res = base_query.build_sql_and_params().unwrap();
+            Ok(result.into_object())
+        }
+        Err(err) => neon_context_holder.with_context(|cx| cx.throw_error(err.message)),
+    }
+}
 
-    let result: NeonObject<'static, FunctionContext<'static>> = res.into_object();
-    let result = result.into_object();
+fn build_sql_and_params(cx: FunctionContext) -> JsResult<JsValue> {
+    with_sql_planner_base_query(cx, |base_query| base_query.build_sql_and_params())
+}
 
-    Ok(result)
+fn build_plan_description(cx: FunctionContext) -> JsResult<JsValue> {
+    with_sql_planner_base_query(cx, |base_query| base_query.build_plan_description())
 }
 
 fn extend_function_context_lifetime<'a>(cx: FunctionContext<'a>) -> FunctionContext<'static> {
@@ -514,6 +545,7 @@ pub fn register_module_exports<C: NodeConfiguration + 'static>(
     cx.export_function("__js_to_clrepr_to_js", debug_js_to_clrepr_to_js)?;
 
     cx.export_function("buildSqlAndParams", build_sql_and_params)?;
+    cx.export_function("buildPlanDescription", build_plan_description)?;
 
     crate::template::template_register_module(&mut cx)?;
 
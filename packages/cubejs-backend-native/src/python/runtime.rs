@@ -1,5 +1,6 @@
 use crate::cross::{CLRepr, CLReprPython};
 use crate::python::neon_py::*;
+use crate::python::utils::python_awaitable_result;
 use crate::tokio_runtime_node;
 use cubesql::CubeError;
 use log::{error, trace};
@@ -104,9 +105,9 @@ impl PyRuntime {
             let args = PyTuple::new(py, args_tuple);
             let call_res = fun.call1(py, args)?;
 
-            let is_coroutine = unsafe { pyo3::ffi::PyCoro_CheckExact(call_res.as_ptr()) == 1 };
-            if is_coroutine {
-                let fut = pyo3_asyncio::tokio::into_future(call_res.as_ref(py))?;
+            // Coroutines and async generators are resolved on the event loop of this runtime
+            if let Some(awaitable) = python_awaitable_result(py, call_res.as_ref(py))? {
+                let fut = pyo3_asyncio::tokio::into_future(awaitable)?;
                 Ok(PyScheduledFunResult::Poll(Box::pin(fut)))
             } else {
                 Ok(PyScheduledFunResult::Ready(CLRepr::from_python_ref(
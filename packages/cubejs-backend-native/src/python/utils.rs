@@ -1,8 +1,76 @@
 use crate::cross::*;
-use pyo3::exceptions::PyNotImplementedError;
+use pyo3::exceptions::PyRuntimeError;
 use pyo3::prelude::*;
+use pyo3::sync::GILOnceCell;
 use pyo3::types::{PyFunction, PyString, PyTuple};
 
+const PY_AWAIT_HELPER: &str = r#"
+import inspect
+
+async def resolve(value):
+    while True:
+        if inspect.isasyncgen(value):
+            return [item async for item in value]
+
+        if inspect.isawaitable(value):
+            value = await value
+            continue
+
+        return value
+"#;
+
+static PY_AWAIT_HELPER_RESOLVE: GILOnceCell<PyObject> = GILOnceCell::new();
+
+/// Coroutines and async generators can't be converted to CLRepr as is. For them, this function
+/// returns a coroutine which awaits the value (or collects all items of an async generator).
+/// `None` means that the value doesn't need an event loop.
+pub(crate) fn python_awaitable_result<'py>(
+    py: Python<'py>,
+    value: &'py PyAny,
+) -> PyResult<Option<&'py PyAny>> {
+    let is_async = unsafe {
+        pyo3::ffi::PyCoro_CheckExact(value.as_ptr()) == 1
+            || pyo3::ffi::PyAsyncGen_CheckExact(value.as_ptr()) == 1
+    };
+    if !is_async {
+        return Ok(None);
+    }
+
+    let resolve = PY_AWAIT_HELPER_RESOLVE.get_or_try_init(py, || -> PyResult<PyObject> {
+        let module = PyModule::from_code(py, PY_AWAIT_HELPER, "cube_await.py", "cube_await")?;
+
+        Ok(module.getattr("resolve")?.into())
+    })?;
+
+    Ok(Some(resolve.as_ref(py).call1((value,))?))
+}
+
+/// Converts result of a Python call to CLRepr. It's used by Jinja workers (filters, methods of
+/// objects), these threads don't have an event loop, so every async result is resolved by
+/// `asyncio.run`, which creates a new event loop per call. Async code which depends on a shared
+/// loop (for example, a client created at import time) should be called via `PyRuntime` instead.
+/// Calling it from a thread with a running event loop is an error, `asyncio.run` would fail anyway.
+fn python_call_result_sync(py: Python, call_res: PyObject) -> PyResult<CLRepr> {
+    match python_awaitable_result(py, call_res.as_ref(py))? {
+        Some(coroutine) => {
+            let asyncio = py.import("asyncio")?;
+            if !asyncio.call_method0("_get_running_loop")?.is_none() {
+                // Coroutine will never be awaited, close it to suppress the warning
+                coroutine.call_method0("close")?;
+
+                return Err(PyErr::new::<PyRuntimeError, _>(
+                    "Async result can't be resolved synchronously inside a running event loop",
+                ));
+            }
+
+            let res = asyncio.call_method1("run", (coroutine,))?;
+
+            CLRepr::from_python_ref(res)
+        }
+        None => CLRepr::from_python_ref(call_res.as_ref(py)),
+    }
+}
+
 pub fn python_fn_call_sync(py_fun: &Py<PyFunction>, arguments: Vec<CLRepr>) -> PyResult<CLRepr> {
     Python::with_gil(|py| {
         let mut args_tuple = Vec::with_capacity(arguments.len());
@@ -15,14 +83,7 @@ pub fn python_fn_call_sync(py_fun: &Py<PyFunction>, arguments: Vec<CLRepr>) -> P
 
         let call_res = py_fun.call1(py, tuple)?;
 
-        let is_coroutine = unsafe { pyo3::ffi::PyCoro_CheckExact(call_res.as_ptr()) == 1 };
-        if is_coroutine {
-            Err(PyErr::new::<PyNotImplementedError, _>(
-                "Calling function with async response is not supported",
-            ))
-        } else {
-            CLRepr::from_python_ref(call_res.as_ref(py))
-        }
+        python_call_result_sync(py, call_res)
     })
 }
 
@@ -38,14 +99,7 @@ pub fn python_obj_call_sync(py_fun: &PyObject, arguments: Vec<CLRepr>) -> PyResu
 
         let call_res = py_fun.call1(py, tuple)?;
 
-        let is_coroutine = unsafe { pyo3::ffi::PyCoro_CheckExact(call_res.as_ptr()) == 1 };
-        if is_coroutine {
-            Err(PyErr::new::<PyNotImplementedError, _>(
-                "Calling object with async response is not supported",
-            ))
-        } else {
-            CLRepr::from_python_ref(call_res.as_ref(py))
-        }
+        python_call_result_sync(py, call_res)
     })
 }
 
@@ -68,13 +122,6 @@ where
 
         let call_res = py_fun.call_method1(py, name, tuple)?;
 
-        let is_coroutine = unsafe { pyo3::ffi::PyCoro_CheckExact(call_res.as_ptr()) == 1 };
-        if is_coroutine {
-            Err(PyErr::new::<PyNotImplementedError, _>(
-                "Calling object method with async response is not supported",
-            ))
-        } else {
-            CLRepr::from_python_ref(call_res.as_ref(py))
-        }
+        python_call_result_sync(py, call_res)
     })
 }
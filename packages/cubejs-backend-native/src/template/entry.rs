@@ -7,15 +7,25 @@ use log::trace;
 use minijinja as mj;
 use neon::prelude::*;
 use std::cell::RefCell;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::time::Duration;
 
 #[cfg(feature = "python")]
 use crate::template::engine_python;
 use crate::template::workers::{JinjaEngineWorkerJob, JinjaEngineWorkerPool};
 
+/// Fuel of a single render when the fuel option is not passed
+const DEFAULT_JINJA_FUEL: u64 = 100_000_000;
+
 struct JinjaEngine {
     inner: mj::Environment<'static>,
     workers_count: usize,
     workers: Option<JinjaEngineWorkerPool>,
+    render_timeout: Option<Duration>,
+    // Source hash of every loaded template, unchanged templates are not compiled again
+    template_hashes: HashMap<String, u64>,
 }
 
 impl Finalize for JinjaEngine {}
@@ -52,6 +62,16 @@ impl JinjaEngine {
         );
         engine.set_auto_escape_callback(|_name: &str| mj::AutoEscape::Json);
 
+        // Every instruction consumes fuel, it stops runaway loops inside the worker thread,
+        // including renders which were rejected by the render timeout
+        let fuel = Self::positive_int_option(cx, options, "fuel")?
+            .map(|fuel| fuel as u64)
+            .unwrap_or(DEFAULT_JINJA_FUEL);
+        engine.set_fuel(Some(fuel));
+
+        let render_timeout = Self::positive_int_option(cx, options, "renderTimeout")?
+            .map(|ms| Duration::from_millis(ms as u64));
+
         #[cfg(feature = "python")]
         engine_python::mj_inject_python_extension(cx, options, &mut engine)?;
 
@@ -77,8 +97,33 @@ impl JinjaEngine {
             inner: engine,
             workers_count,
             workers: None,
+            render_timeout,
+            template_hashes: HashMap::new(),
         })
     }
+
+    fn positive_int_option(
+        cx: &mut FunctionContext,
+        options: Handle<JsObject>,
+        name: &str,
+    ) -> NeonResult<Option<usize>> {
+        let Some(value) = options.get_opt::<JsNumber, _, _>(cx, name)? else {
+            return Ok(None);
+        };
+
+        let value = value.value(cx);
+        if value < 1_f64 || value.fract() != 0_f64 {
+            return cx.throw_error(format!("Option {} must be a positive integer", name));
+        }
+
+        Ok(Some(value as usize))
+    }
+
+    fn source_hash(content: &str) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        content.hash(&mut hasher);
+        hasher.finish()
+    }
 }
 
 type BoxedJinjaEngine = JsBox<RefCell<JinjaEngine>>;
@@ -93,6 +138,7 @@ impl JinjaEngine {
             self.workers_count,
             cx.channel(),
             self.inner.clone(),
+            self.render_timeout,
         ));
 
         self.workers.as_ref().unwrap()
@@ -148,15 +194,30 @@ impl JinjaEngine {
         let template_name = cx.argument::<JsString>(0)?;
         let template_content = cx.argument::<JsString>(1)?;
 
+        let template_name = template_name.value(&mut cx);
+        let template_content = template_content.value(&mut cx);
+        let source_hash = Self::source_hash(&template_content);
+
         let mut borrowed = this.borrow_mut();
-        if let Err(err) = borrowed.inner.add_template_owned(
-            template_name.value(&mut cx),
-            template_content.value(&mut cx),
-        ) {
+        // Compiler transpiles files in every phase and on reloads, skip templates which were not changed
+        if borrowed.template_hashes.get(&template_name) == Some(&source_hash) {
+            trace!("jinja template {} is not changed, skipping", template_name);
+
+            return Ok(cx.undefined());
+        }
+
+        if let Err(err) = borrowed
+            .inner
+            .add_template_owned(template_name.clone(), template_content)
+        {
             trace!("jinja load error: {:?}", err);
+            borrowed.template_hashes.remove(&template_name);
+
             return cx.throw_from_mj_error(err);
         };
 
+        borrowed.template_hashes.insert(template_name, source_hash);
+
         if borrowed.workers.is_some() {
             trace!("Restart jinja workers");
             borrowed.workers = None;
@@ -27,6 +27,9 @@ pub fn to_minijinja_value(from: CLRepr) -> mjv::Value {
             PythonRef::PyFunction(inner) | PythonRef::PyExternalFunction(inner) => {
                 mjv::Value::from_object(python::JinjaPythonFunction { inner })
             }
+            PythonRef::PyGenerator(inner) => {
+                mjv::Value::from_seq_object(python::JinjaPythonGenerator::new(inner))
+            }
         },
         CLRepr::JsFunction(_) => panic!(
             "Converting from {:?} to minijinja::Value is not supported",
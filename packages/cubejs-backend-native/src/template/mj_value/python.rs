@@ -7,11 +7,11 @@ use crate::tokio_runtime;
 use log::error;
 use minijinja as mj;
 use minijinja::value as mjv;
-use minijinja::value::{Object, ObjectKind, StructObject, Value};
+use minijinja::value::{Object, ObjectKind, SeqObject, StructObject, Value};
 use pyo3::types::{PyDict, PyFunction};
 use pyo3::{Py, PyObject, PyResult, Python};
 use std::convert::TryInto;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
 pub fn from_minijinja_value(from: &mjv::Value) -> Result<CLRepr, mj::Error> {
     match from.kind() {
@@ -274,3 +274,76 @@ impl StructObject for JinjaPythonFunction {
         None
     }
 }
+
+/// Python generator as a sequence. Items are pulled from the generator when the template iterates
+/// over them and are kept, so the sequence can be iterated again. Infinite generators are supported,
+/// but the length of the sequence (`loop.length`, `length` filter) is the number of already pulled items.
+pub struct JinjaPythonGenerator {
+    inner: PyObject,
+    state: Mutex<JinjaPythonGeneratorState>,
+}
+
+#[derive(Default)]
+struct JinjaPythonGeneratorState {
+    items: Vec<Value>,
+    exhausted: bool,
+}
+
+impl JinjaPythonGenerator {
+    pub fn new(inner: PyObject) -> Self {
+        Self {
+            inner,
+            state: Mutex::new(JinjaPythonGeneratorState::default()),
+        }
+    }
+
+    fn pull_next(&self) -> PyResult<Option<Value>> {
+        Python::with_gil(|py| {
+            let item = match self.inner.as_ref(py).iter()?.next() {
+                Some(item) => item?,
+                None => return Ok(None),
+            };
+
+            Ok(Some(to_minijinja_value(CLRepr::from_python_ref(item)?)))
+        })
+    }
+}
+
+impl std::fmt::Debug for JinjaPythonGenerator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Debug::fmt(&self.inner, f)
+    }
+}
+
+impl std::fmt::Display for JinjaPythonGenerator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Display::fmt(&self.inner, f)
+    }
+}
+
+impl SeqObject for JinjaPythonGenerator {
+    fn get_item(&self, idx: usize) -> Option<Value> {
+        let mut state = self.state.lock().ok()?;
+
+        while state.items.len() <= idx && !state.exhausted {
+            match self.pull_next() {
+                Ok(Some(item)) => state.items.push(item),
+                Ok(None) => state.exhausted = true,
+                Err(err) => {
+                    error!("Error while pulling item from generator: {}", err);
+
+                    state.exhausted = true;
+                }
+            }
+        }
+
+        state.items.get(idx).cloned()
+    }
+
+    fn item_count(&self) -> usize {
+        self.state
+            .lock()
+            .map(|state| state.items.len())
+            .unwrap_or(0)
+    }
+}
@@ -3,8 +3,128 @@ use std::error::Error;
 
 use neon::prelude::*;
 
+/// Location of an error inside a template, it's exposed to JS as an element of `templateStack`.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct MjErrorFrame {
+    pub(crate) file_name: String,
+    pub(crate) line: Option<usize>,
+    pub(crate) column: Option<usize>,
+}
+
+impl MjErrorFrame {
+    fn from_mj_error(err: &mj::Error) -> Option<Self> {
+        let file_name = err.name()?.to_string();
+        let column = match (err.range(), err.template_source()) {
+            (Some(range), Some(source)) => source.get(..range.start).map(|before| {
+                let line_start = before.rfind('\n').map_or(0, |idx| idx + 1);
+
+                before[line_start..].chars().count() + 1
+            }),
+            _ => None,
+        };
+
+        Some(Self {
+            file_name,
+            line: err.line(),
+            column,
+        })
+    }
+
+    /// Frames from the innermost template (where the error happened) to the template which was
+    /// rendered, root template is added when it's not a part of the error chain.
+    pub(crate) fn stack(err: &mj::Error, root_template: Option<&str>) -> Vec<Self> {
+        let mut frames = vec![];
+        let mut current: Option<&(dyn Error + 'static)> = Some(err);
+
+        while let Some(e) = current {
+            if let Some(frame) = e.downcast_ref::<mj::Error>().and_then(Self::from_mj_error) {
+                if frames.last() != Some(&frame) {
+                    frames.push(frame);
+                }
+            }
+
+            current = e.source();
+        }
+
+        if let Some(root_template) = root_template {
+            if frames.last().map(|f| f.file_name.as_str()) != Some(root_template) {
+                frames.push(Self {
+                    file_name: root_template.to_string(),
+                    line: None,
+                    column: None,
+                });
+            }
+        }
+
+        frames
+    }
+}
+
 pub(crate) trait NeonMiniJinjaContext<'a>: Context<'a> {
+    /// Throws JS error with `fileName`, `line`, `column` and `templateStack` properties,
+    /// which point to the place where the error happened.
+    fn throw_structured_error<T>(
+        &mut self,
+        message: String,
+        kind: &str,
+        stack: Vec<MjErrorFrame>,
+    ) -> NeonResult<T> {
+        let js_err = self.error(message)?;
+
+        let js_kind = self.string(kind);
+        js_err.set(self, "kind", js_kind)?;
+
+        let js_stack = self.empty_array();
+        for (idx, frame) in stack.iter().enumerate() {
+            let js_frame = self.empty_object();
+
+            let file_name = self.string(&frame.file_name);
+            js_frame.set(self, "fileName", file_name)?;
+
+            if let Some(line) = frame.line {
+                let line = self.number(line as f64);
+                js_frame.set(self, "line", line)?;
+            }
+
+            if let Some(column) = frame.column {
+                let column = self.number(column as f64);
+                js_frame.set(self, "column", column)?;
+            }
+
+            js_stack.set(self, idx as u32, js_frame)?;
+        }
+
+        if let Some(frame) = stack.first() {
+            let file_name = self.string(&frame.file_name);
+            js_err.set(self, "fileName", file_name)?;
+
+            if let Some(line) = frame.line {
+                let line = self.number(line as f64);
+                js_err.set(self, "line", line)?;
+            }
+
+            if let Some(column) = frame.column {
+                let column = self.number(column as f64);
+                js_err.set(self, "column", column)?;
+            }
+        }
+
+        js_err.set(self, "templateStack", js_stack)?;
+
+        self.throw(js_err)
+    }
+
     fn throw_from_mj_error<T>(&mut self, err: mj::Error) -> NeonResult<T> {
+        self.throw_from_mj_render_error(err, None)
+    }
+
+    fn throw_from_mj_render_error<T>(
+        &mut self,
+        err: mj::Error,
+        root_template: Option<&str>,
+    ) -> NeonResult<T> {
+        let stack = MjErrorFrame::stack(&err, root_template);
+
         let codeblock = if let Some(source) = err.template_source() {
             let lines: Vec<_> = source.lines().enumerate().collect();
             let idx = err.line().unwrap_or(1).saturating_sub(1);
@@ -56,14 +176,13 @@ pub(crate) trait NeonMiniJinjaContext<'a>: Context<'a> {
             format!("{}", err.kind())
         };
 
-        if let Some(next_err) = err.source() {
-            self.throw_error(format!(
-                "{} caused by: {:#}{}",
-                formatted_err, next_err, codeblock
-            ))
+        let message = if let Some(next_err) = err.source() {
+            format!("{} caused by: {:#}{}", formatted_err, next_err, codeblock)
         } else {
-            self.throw_error(format!("{}{}", formatted_err, codeblock))
-        }
+            format!("{}{}", formatted_err, codeblock)
+        };
+
+        self.throw_structured_error(message, &format!("{:?}", err.kind()), stack)
     }
 }
 
@@ -1,10 +1,14 @@
 use crate::template::neon_mj::*;
+use crate::tokio_runtime;
 use cubesql::CubeError;
 
-use log::trace;
+use log::{error, trace};
 use minijinja as mj;
 use neon::prelude::*;
 use neon::types::Deferred;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::task::JoinHandle;
 
 pub struct JinjaEngineWorkerJob {
     pub(crate) template_name: String,
@@ -12,6 +16,26 @@ pub struct JinjaEngineWorkerJob {
     pub(crate) deferred: Deferred,
 }
 
+/// Deferred which is settled only once: by the worker or by the render timeout, whichever is first.
+#[derive(Clone)]
+struct SharedDeferred(Arc<Mutex<Option<Deferred>>>);
+
+impl SharedDeferred {
+    fn new(deferred: Deferred) -> Self {
+        Self(Arc::new(Mutex::new(Some(deferred))))
+    }
+
+    fn take(&self) -> Option<Deferred> {
+        self.0.lock().ok().and_then(|mut d| d.take())
+    }
+}
+
+struct JinjaEngineWorkerTask {
+    template_name: String,
+    ctx: minijinja::value::Value,
+    deferred: SharedDeferred,
+}
+
 struct JinjaEngineWorker {
     _thread: std::thread::JoinHandle<()>,
 }
@@ -21,34 +45,45 @@ impl JinjaEngineWorker {
         id: usize,
         env: mj::Environment<'static>,
         js_channel: neon::event::Channel,
-        receiver: async_channel::Receiver<JinjaEngineWorkerJob>,
+        receiver: async_channel::Receiver<JinjaEngineWorkerTask>,
+        render_timeout: Option<Duration>,
     ) -> Self {
         let thread = std::thread::spawn(move || loop {
-            if let Ok(job) = receiver.recv_blocking() {
-                let template = match env.get_template(&job.template_name) {
-                    Ok(t) => t,
-                    Err(err) => {
-                        job.deferred.settle_with(
-                            &js_channel,
-                            move |mut cx| -> NeonResult<Handle<JsString>> {
-                                cx.throw_from_mj_error(err)
-                            },
-                        );
-
-                        continue;
-                    }
+            if let Ok(task) = receiver.recv_blocking() {
+                let template_name = task.template_name;
+                // Time limit starts with the render, time spent in the queue is not counted
+                let timer = render_timeout.and_then(|render_timeout| {
+                    Self::spawn_render_timer(
+                        render_timeout,
+                        task.deferred.clone(),
+                        js_channel.clone(),
+                        template_name.clone(),
+                    )
+                });
+
+                let result = env
+                    .get_template(&template_name)
+                    .and_then(|template| template.render(task.ctx));
+
+                if let Some(timer) = timer {
+                    timer.abort();
+                }
+
+                let Some(deferred) = task.deferred.take() else {
+                    trace!(
+                        "Jinja template {} was rendered after timeout, result is dropped",
+                        template_name
+                    );
+
+                    continue;
                 };
 
-                let result = template.render(job.ctx);
-                job.deferred.settle_with(
-                    &js_channel,
-                    move |mut cx| -> NeonResult<Handle<JsString>> {
-                        match result {
-                            Ok(r) => Ok(cx.string(r)),
-                            Err(err) => cx.throw_from_mj_error(err),
-                        }
-                    },
-                );
+                deferred.settle_with(&js_channel, move |mut cx| -> NeonResult<Handle<JsString>> {
+                    match result {
+                        Ok(r) => Ok(cx.string(r)),
+                        Err(err) => cx.throw_from_mj_render_error(err, Some(&template_name)),
+                    }
+                });
             } else {
                 trace!(
                     "Closing jinja thread, id: {}, threadId: {}",
@@ -62,10 +97,50 @@ impl JinjaEngineWorker {
 
         Self { _thread: thread }
     }
+
+    /// Rejects the render with the Timeout error when it's exceeded. Worker thread can't be
+    /// interrupted, the render itself is stopped by fuel which is limited by default.
+    fn spawn_render_timer(
+        render_timeout: Duration,
+        deferred: SharedDeferred,
+        js_channel: Channel,
+        template_name: String,
+    ) -> Option<JoinHandle<()>> {
+        let runtime = match tokio_runtime() {
+            Ok(runtime) => runtime,
+            Err(err) => {
+                error!("Unable to start jinja render timer: {}", err);
+
+                return None;
+            }
+        };
+
+        Some(runtime.spawn(async move {
+            tokio::time::sleep(render_timeout).await;
+
+            if let Some(deferred) = deferred.take() {
+                deferred.settle_with(&js_channel, move |mut cx| -> NeonResult<Handle<JsString>> {
+                    cx.throw_structured_error(
+                        format!(
+                            "Rendering of {} exceeded the time limit of {}ms",
+                            template_name,
+                            render_timeout.as_millis()
+                        ),
+                        "Timeout",
+                        vec![MjErrorFrame {
+                            file_name: template_name,
+                            line: None,
+                            column: None,
+                        }],
+                    )
+                });
+            }
+        }))
+    }
 }
 
 pub struct JinjaEngineWorkerPool {
-    workers_rx: async_channel::Sender<JinjaEngineWorkerJob>,
+    workers_rx: async_channel::Sender<JinjaEngineWorkerTask>,
     _workers: Vec<JinjaEngineWorker>,
 }
 
@@ -74,8 +149,9 @@ impl JinjaEngineWorkerPool {
         workers_count: usize,
         js_channel: Channel,
         jinja_engine: minijinja::Environment<'static>,
+        render_timeout: Option<Duration>,
     ) -> Self {
-        let (workers_rx, receiver) = async_channel::bounded::<JinjaEngineWorkerJob>(1_000);
+        let (workers_rx, receiver) = async_channel::bounded::<JinjaEngineWorkerTask>(1_000);
 
         let mut workers = vec![];
 
@@ -85,6 +161,7 @@ impl JinjaEngineWorkerPool {
                 jinja_engine.clone(),
                 js_channel.clone(),
                 receiver.clone(),
+                render_timeout,
             ));
         }
 
@@ -96,7 +173,11 @@ impl JinjaEngineWorkerPool {
 
     pub fn render(&self, job: JinjaEngineWorkerJob) -> Result<(), CubeError> {
         self.workers_rx
-            .send_blocking(job)
+            .send_blocking(JinjaEngineWorkerTask {
+                template_name: job.template_name,
+                ctx: job.ctx,
+                deferred: SharedDeferred::new(job.deferred),
+            })
             .map_err(|err| CubeError::internal(format!("Unable to schedule rendering: {}", err)))
     }
 }
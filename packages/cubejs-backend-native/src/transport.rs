@@ -8,7 +8,7 @@ use cubesql::compile::engine::df::scan::{MemberField, SchemaRef};
 use cubesql::compile::engine::df::wrapper::SqlQuery;
 use cubesql::transport::{
     SpanId, SqlGenerator, SqlResponse, TransportLoadRequestQuery, TransportLoadResponse,
-    TransportMetaResponse,
+    TransportLoadResult, TransportMetaResponse,
 };
 use cubesql::{
     di_service,
@@ -16,7 +16,9 @@ use cubesql::{
     transport::{CubeStreamReceiver, LoadRequestMeta, MetaContext, TransportService},
     CubeError,
 };
+use datafusion::arrow::{ipc::reader::StreamReader, record_batch::RecordBatch};
 use serde::Serialize;
+use std::io::Cursor;
 use std::sync::Arc;
 use uuid::Uuid;
 
@@ -59,6 +61,115 @@ impl NodeBridgeTransport {
             can_switch_user_for_session: Arc::new(can_switch_user_for_session),
         }
     }
+
+    async fn load_with_format(
+        &self,
+        span_id: Option<Arc<SpanId>>,
+        query: TransportLoadRequestQuery,
+        sql_query: Option<SqlQuery>,
+        ctx: AuthContextRef,
+        meta: LoadRequestMeta,
+        accept_arrow: bool,
+    ) -> Result<TransportLoadResult, CubeError> {
+        trace!("[transport] Request ->");
+
+        let native_auth = ctx
+            .as_any()
+            .downcast_ref::<NativeAuthContext>()
+            .expect("Unable to cast AuthContext to NativeAuthContext");
+
+        let request_id = span_id
+            .as_ref()
+            .map(|s| s.span_id.clone())
+            .unwrap_or_else(|| Uuid::new_v4().to_string());
+
+        loop {
+            let extra = serde_json::to_string(&LoadRequest {
+                request: TransportRequest {
+                    id: format!("{}-span-{}", request_id, 1),
+                    meta: Some(meta.clone()),
+                },
+                query: query.clone(),
+                query_key: span_id.as_ref().map(|s| s.query_key.clone()),
+                session: SessionContext {
+                    user: native_auth.user.clone(),
+                    superuser: native_auth.superuser,
+                    security_context: native_auth.security_context.clone(),
+                },
+                sql_query: sql_query.clone().map(|q| (q.sql, q.values)),
+                member_to_alias: None,
+                expression_params: None,
+                streaming: false,
+                accept_arrow,
+            })?;
+
+            let result = call_raw_js_with_channel_as_callback(
+                self.channel.clone(),
+                self.on_sql_api_load.clone(),
+                extra,
+                Box::new(|cx, v| Ok(cx.string(v).as_value(cx))),
+                Box::new(JsLoadResponse::from_js_value),
+            )
+            .await;
+            if let Err(e) = &result {
+                if e.message.to_lowercase().contains("continue wait") && meta.retry_continue_wait()
+                {
+                    continue;
+                }
+            }
+
+            let response = match result? {
+                JsLoadResponse::Json(response) => response,
+                JsLoadResponse::ArrowIpc { buffer, total } => {
+                    trace!("[transport] Request <- Arrow IPC, {} bytes", buffer.len());
+
+                    let batches = decode_arrow_ipc(buffer)?;
+
+                    break Ok(TransportLoadResult::Arrow { batches, total });
+                }
+            };
+
+            #[cfg(debug_assertions)]
+            trace!("[transport] Request <- {:?}", response);
+            #[cfg(not(debug_assertions))]
+            trace!("[transport] Request <- <hidden>");
+
+            if let Some(error_value) = response.get("error") {
+                match error_value {
+                    serde_json::Value::String(error) => {
+                        if error.to_lowercase() == *"continue wait" {
+                            if !meta.retry_continue_wait() {
+                                return Err(CubeError::user(error.clone()));
+                            }
+
+                            debug!(
+                                "[transport] load - retrying request (continue wait) requestId: {}",
+                                request_id
+                            );
+
+                            continue;
+                        } else {
+                            return Err(CubeError::user(error.clone()));
+                        }
+                    }
+                    other => {
+                        error!(
+                            "[transport] load - strange response, success which contains error: {:?}",
+                            other
+                        );
+
+                        return Err(CubeError::internal(
+                            "Error response with broken data inside".to_string(),
+                        ));
+                    }
+                }
+            };
+
+            break serde_json::from_value::<TransportLoadResponse>(response)
+                .map(TransportLoadResult::Json)
+                .map_err(|err| CubeError::user(err.to_string()));
+        }
+    }
 }
 
 #[derive(Debug, Serialize)]
@@ -87,10 +198,52 @@ struct LoadRequest {
     #[serde(rename = "expressionParams", skip_serializing_if = "Option::is_none")]
     expression_params: Option<Vec<Option<String>>>,
     streaming: bool,
+    #[serde(rename = "acceptArrow")]
+    accept_arrow: bool,
     #[serde(rename = "queryKey", skip_serializing_if = "Option::is_none")]
     query_key: Option<serde_json::Value>,
 }
 
+/// Load response as it was handed back by JS, before any decoding
+#[derive(Debug)]
+enum JsLoadResponse {
+    Json(serde_json::Value),
+    ArrowIpc { buffer: Vec<u8>, total: Option<i64> },
+}
+
+impl JsLoadResponse {
+    fn from_js_value(cx: &mut FunctionContext, v: Handle<JsValue>) -> Result<Self, CubeError> {
+        if let Ok(s) = v.downcast::<JsString, _>(cx) {
+            let response = serde_json::from_str(&s.value(cx))
+                .map_err(|err| CubeError::internal(err.to_string()))?;
+
+            return Ok(JsLoadResponse::Json(response));
+        }
+
+        let obj = v
+            .downcast::<JsObject, _>(cx)
+            .map_cube_err("Can't cast load response to object")?;
+        let buffer = obj
+            .get::<JsBuffer, _, _>(cx, "arrowIpc")
+            .map_cube_err("Can't cast arrowIpc to buffer")?;
+        let total = obj
+            .get_opt::<JsNumber, _, _>(cx, "total")
+            .map_cube_err("Can't cast total to number")?
+            .map(|n| n.value(cx) as i64);
+
+        Ok(JsLoadResponse::ArrowIpc {
+            buffer: buffer.as_slice(cx).to_vec(),
+            total,
+        })
+    }
+}
+
+fn decode_arrow_ipc(buffer: Vec<u8>) -> Result<Vec<RecordBatch>, CubeError> {
+    let reader = StreamReader::try_new(Cursor::new(buffer))?;
+
+    Ok(reader.collect::<Result<Vec<_>, _>>()?)
+}
+
 #[derive(Debug, Serialize)]
 struct LogEvent {
     request: TransportRequest,
@@ -204,12 +357,15 @@ impl TransportService for NodeBridgeTransport {
                 response.compiler_id, e
             ))
         })?;
-        Ok(Arc::new(MetaContext::new(
-            response.cubes.unwrap_or_default(),
-            cube_to_data_source,
-            data_source_to_sql_generator,
-            compiler_id,
-        )))
+        Ok(Arc::new(
+            MetaContext::new(
+                response.cubes.unwrap_or_default(),
+                cube_to_data_source,
+                data_source_to_sql_generator,
+                compiler_id,
+            )
+            .with_cost_hints(response.cost_hints.unwrap_or_default()),
+        ))
     }
 
     async fn compiler_id(&self, ctx: AuthContextRef) -> Result<Uuid, CubeError> {
@@ -285,6 +441,7 @@ impl TransportService for NodeBridgeTransport {
             member_to_alias,
             expression_params,
             streaming: false,
+            accept_arrow: false,
         })?;
 
         let response: serde_json::Value = call_js_with_channel_as_callback(
@@ -335,88 +492,29 @@ impl TransportService for NodeBridgeTransport {
         ctx: AuthContextRef,
         meta: LoadRequestMeta,
     ) -> Result<TransportLoadResponse, CubeError> {
-        trace!("[transport] Request ->");
-
-        let native_auth = ctx
-            .as_any()
-            .downcast_ref::<NativeAuthContext>()
-            .expect("Unable to cast AuthContext to NativeAuthContext");
-
-        let request_id = span_id
-            .as_ref()
-            .map(|s| s.span_id.clone())
-            .unwrap_or_else(|| Uuid::new_v4().to_string());
-
-        loop {
-            let extra = serde_json::to_string(&LoadRequest {
-                request: TransportRequest {
-                    id: format!("{}-span-{}", request_id, 1),
-                    meta: Some(meta.clone()),
-                },
-                query: query.clone(),
-                query_key: span_id.as_ref().map(|s| s.query_key.clone()),
-                session: SessionContext {
-                    user: native_auth.user.clone(),
-                    superuser: native_auth.superuser,
-                    security_context: native_auth.security_context.clone(),
-                },
-                sql_query: sql_query.clone().map(|q| (q.sql, q.values)),
-                member_to_alias: None,
-                expression_params: None,
-                streaming: false,
-            })?;
-
-            let result = call_js_with_channel_as_callback(
-                self.channel.clone(),
-                self.on_sql_api_load.clone(),
-                Some(extra),
-            )
-            .await;
-            if let Err(e) = &result {
-                if e.message.to_lowercase().contains("continue wait") {
-                    continue;
-                }
-            }
-
-            let response: serde_json::Value = result?;
-
-            #[cfg(debug_assertions)]
-            trace!("[transport] Request <- {:?}", response);
-            #[cfg(not(debug_assertions))]
-            trace!("[transport] Request <- <hidden>");
-
-            if let Some(error_value) = response.get("error") {
-                match error_value {
-                    serde_json::Value::String(error) => {
-                        if error.to_lowercase() == *"continue wait" {
-                            debug!(
-                                "[transport] load - retrying request (continue wait) requestId: {}",
-                                request_id
-                            );
-
-                            continue;
-                        } else {
-                            return Err(CubeError::user(error.clone()));
-                        }
-                    }
-                    other => {
-                        error!(
-                            "[transport] load - strange response, success which contains error: {:?}",
-                            other
-                        );
-
-                        return Err(CubeError::internal(
-                            "Error response with broken data inside".to_string(),
-                        ));
-                    }
-                }
-            };
-
-            break serde_json::from_value::<TransportLoadResponse>(response)
-                .map_err(|err| CubeError::user(err.to_string()));
+        match self
+            .load_with_format(span_id, query, sql_query, ctx, meta, false)
+            .await?
+        {
+            TransportLoadResult::Json(response) => Ok(response),
+            TransportLoadResult::Arrow { .. } => Err(CubeError::internal(
+                "Arrow response was returned for a JSON load request".to_string(),
+            )),
         }
     }
 
+    async fn load_result(
+        &self,
+        span_id: Option<Arc<SpanId>>,
+        query: TransportLoadRequestQuery,
+        sql_query: Option<SqlQuery>,
+        ctx: AuthContextRef,
+        meta: LoadRequestMeta,
+    ) -> Result<TransportLoadResult, CubeError> {
+        self.load_with_format(span_id, query, sql_query, ctx, meta, true)
+            .await
+    }
+
     async fn load_stream(
         &self,
         span_id: Option<Arc<SpanId>>,
@@ -455,6 +553,7 @@ impl TransportService for NodeBridgeTransport {
                 member_to_alias: None,
                 expression_params: None,
                 streaming: true,
+                accept_arrow: false,
             })?;
 
             let res = call_js_with_stream_as_callback(
@@ -0,0 +1,133 @@
+import { Client } from 'pg';
+
+import * as native from '../js';
+import metaFixture from './meta';
+
+const PG_PORT = 5559;
+
+const loadResult = {
+  annotation: {
+    measures: {
+      'KibanaSampleDataEcommerce.count': { type: 'number' },
+    },
+    dimensions: {
+      'KibanaSampleDataEcommerce.customer_gender': { type: 'string' },
+    },
+    segments: {},
+    timeDimensions: {
+      'KibanaSampleDataEcommerce.order_date.day': { type: 'time' },
+      'KibanaSampleDataEcommerce.order_date': { type: 'time' },
+    },
+  },
+  data: [
+    {
+      'KibanaSampleDataEcommerce.customer_gender': 'female',
+      'KibanaSampleDataEcommerce.order_date.day': '2024-01-15T00:00:00.000',
+      'KibanaSampleDataEcommerce.count': '9007199254740993',
+    },
+    {
+      'KibanaSampleDataEcommerce.customer_gender': null,
+      'KibanaSampleDataEcommerce.order_date.day': null,
+      'KibanaSampleDataEcommerce.count': '5',
+    },
+  ],
+};
+
+function interfaceMethods() {
+  return {
+    load: jest.fn(async () => ({ error: 'load is not expected to be called' })),
+    sqlApiLoad: jest.fn(async ({ acceptArrow, streaming }) => {
+      if (streaming || !acceptArrow) {
+        return { error: 'Arrow result is expected to be accepted' };
+      }
+
+      return {
+        arrowIpc: native.loadResultToArrowIpc(loadResult),
+      };
+    }),
+    sql: jest.fn(async () => ({ error: 'sql is not expected to be called' })),
+    stream: jest.fn(async () => ({ error: 'stream is not expected to be called' })),
+    meta: jest.fn(async () => metaFixture),
+    sqlGenerators: jest.fn(async () => ({
+      cubeNameToDataSource: {},
+      dataSourceToSqlGenerator: {},
+    })),
+    checkAuth: jest.fn(async () => ({
+      password: 'password',
+      superuser: false,
+      securityContext: {},
+    })),
+    logLoadEvent: jest.fn(() => {
+      // nothing to do
+    }),
+  };
+}
+
+describe('Arrow IPC load results', () => {
+  jest.setTimeout(60 * 1000);
+
+  it('encodes the result to Arrow IPC stream', () => {
+    const buffer = native.loadResultToArrowIpc(loadResult);
+
+    expect(Buffer.isBuffer(buffer)).toEqual(true);
+    // Stream format starts with the continuation token of the schema message
+    expect(buffer!.readUInt32LE(0)).toEqual(0xFFFFFFFF);
+
+    expect(native.loadResultToArrowIpc({ ...loadResult, annotation: undefined } as any)).toEqual(null);
+  });
+
+  it('passes Arrow IPC result from sqlApiLoad to the SQL client', async () => {
+    const methods = interfaceMethods();
+
+    const instance = await native.registerInterface({
+      pgPort: PG_PORT,
+      ...methods,
+      canSwitchUserForSession: (_payload) => false,
+    });
+
+    try {
+      const connection = new Client({
+        host: '127.0.0.1',
+        database: 'test',
+        port: PG_PORT,
+        user: 'user',
+        password: 'password',
+        // Values are compared as they are sent by the server
+        types: {
+          getTypeParser: () => (value: string) => value,
+        } as any,
+      });
+      await connection.connect();
+
+      try {
+        const result = await connection.query(
+          'SELECT customer_gender, DATE_TRUNC(\'day\', order_date) AS order_day, MEASURE(count) AS cnt ' +
+          'FROM KibanaSampleDataEcommerce GROUP BY 1, 2'
+        );
+
+        expect(result.rows).toEqual([
+          {
+            customer_gender: 'female',
+            order_day: expect.stringMatching(/^2024-01-15[ T]00:00:00/),
+            cnt: '9007199254740993',
+          },
+          {
+            customer_gender: null,
+            order_day: null,
+            cnt: '5',
+          },
+        ]);
+      } finally {
+        await connection.end();
+      }
+
+      expect(methods.sqlApiLoad).toHaveBeenCalledTimes(1);
+      expect(methods.sqlApiLoad.mock.calls[0][0]).toMatchObject({
+        acceptArrow: true,
+        streaming: false,
+      });
+    } finally {
+      await native.shutdownInterface(instance, 'fast');
+    }
+  });
+});
@@ -0,0 +1,254 @@
+import http from 'http';
+
+import * as native from '../js';
+import metaFixture from './meta';
+import { FakeRowStream } from './response-fake';
+
+const GATEWAY_PORT = 5558;
+
+const SECURITY_FILTER = {
+  member: 'Logs.agent',
+  operator: 'equals',
+  values: ['tenant-agent'],
+};
+
+type GatewayResponse = {
+  status: number,
+  contentType: string | undefined,
+  body: string,
+};
+
+function request(method: string, path: string, token: string | null, body?: any): Promise<GatewayResponse> {
+  return new Promise((resolve, reject) => {
+    const req = http.request({
+      host: '127.0.0.1',
+      port: GATEWAY_PORT,
+      method,
+      path,
+      headers: {
+        ...(token ? { Authorization: `Bearer ${token}` } : {}),
+        ...(body ? { 'Content-Type': 'application/json' } : {}),
+      },
+    }, (res) => {
+      const chunks: Buffer[] = [];
+      res.on('data', (chunk) => chunks.push(chunk));
+      res.on('end', () => resolve({
+        status: res.statusCode || 0,
+        contentType: res.headers['content-type'],
+        body: Buffer.concat(chunks).toString(),
+      }));
+    });
+    req.on('error', reject);
+    if (body) {
+      req.write(JSON.stringify(body));
+    }
+    req.end();
+  });
+}
+
+function loadResult(query: any) {
+  return {
+    results: [{
+      query,
+      annotation: {
+        measures: {},
+        dimensions: {},
+        segments: {},
+        timeDimensions: {},
+      },
+      data: [{ 'Logs.agent': 'tenant-agent' }],
+    }],
+  };
+}
+
+function interfaceMethods() {
+  let continueWait = false;
+
+  return {
+    setContinueWait: (value: boolean) => {
+      continueWait = value;
+    },
+    load: jest.fn(async () => ({ error: 'load is not expected to be called by the gateway' })),
+    sqlApiLoad: jest.fn(async ({ request, query, streaming }) => {
+      // Same check as in ApiGateway.getNormalizedQueries for REST requests
+      const hasExpressions = [query.measures, query.dimensions, query.segments]
+        .some((members) => members?.some((member: string) => member.startsWith('{')));
+      if (request.meta.restApi && hasExpressions) {
+        return { error: 'Expressions are not allowed in this context' };
+      }
+
+      if (streaming) {
+        return {
+          stream: new FakeRowStream(query),
+        };
+      }
+
+      if (continueWait) {
+        return { error: 'Continue wait' };
+      }
+
+      return loadResult(query);
+    }),
+    sql: jest.fn(async ({ query }) => ({
+      sql: {
+        sql: ['SELECT agent FROM logs WHERE agent = $1', query.filters[0].values],
+      },
+    })),
+    stream: jest.fn(async ({ query }) => ({
+      stream: new FakeRowStream(query),
+    })),
+    meta: jest.fn(async () => metaFixture),
+    sqlGenerators: jest.fn(async () => ({
+      cubeNameToDataSource: {},
+      dataSourceToSqlGenerator: {},
+    })),
+    checkAuth: jest.fn(async () => {
+      throw new Error('SQL API is not used in this test');
+    }),
+    checkApiGatewayAuth: jest.fn(async ({ token }) => {
+      if (token === 'tenant') {
+        return {
+          securityContext: { tenant: 'tenant' },
+          securityPolicy: {
+            cubes: {
+              Logs: {
+                filters: [SECURITY_FILTER],
+              },
+            },
+          },
+        };
+      }
+
+      throw new Error('Invalid token');
+    }),
+    logLoadEvent: jest.fn(() => {
+      // nothing to do
+    }),
+  };
+}
+
+describe('Native API gateway', () => {
+  jest.setTimeout(60 * 1000);
+
+  const methods = interfaceMethods();
+  let instance: native.SqlInterfaceInstance;
+
+  beforeAll(async () => {
+    const { setContinueWait: _, ...options } = methods;
+
+    instance = await native.registerInterface({
+      gatewayPort: GATEWAY_PORT,
+      ...options,
+      canSwitchUserForSession: (_payload) => false,
+    });
+  });
+
+  afterAll(async () => {
+    await native.shutdownInterface(instance, 'fast');
+  });
+
+  beforeEach(() => {
+    methods.setContinueWait(false);
+    jest.clearAllMocks();
+  });
+
+  const query = {
+    dimensions: ['Logs.agent'],
+    limit: 10,
+  };
+
+  it('rejects requests without a valid token', async () => {
+    const response = await request('GET', '/v1/meta', 'invalid');
+
+    expect(response.status).toEqual(403);
+    expect(JSON.parse(response.body)).toEqual({ error: expect.stringContaining('Invalid token') });
+    expect(methods.checkApiGatewayAuth.mock.calls[0][0]).toEqual({
+      request: {
+        protocol: 'http',
+        method: 'GET',
+        url: '/v1/meta',
+      },
+      token: 'invalid',
+    });
+    expect(methods.meta).not.toHaveBeenCalled();
+  });
+
+  it('serves /v1/meta', async () => {
+    const response = await request('GET', '/v1/meta', 'tenant');
+
+    expect(response.status).toEqual(200);
+    const cubes = JSON.parse(response.body).cubes.map((cube: any) => cube.name);
+    expect(cubes).toEqual(['KibanaSampleDataEcommerce', 'Logs']);
+  });
+
+  it('responds /v1/load with the query of the client', async () => {
+    const response = await request('POST', '/v1/load', 'tenant', { query });
+
+    expect(response.status).toEqual(200);
+    const result = JSON.parse(response.body);
+    expect(result.data).toEqual([{ 'Logs.agent': 'tenant-agent' }]);
+    // Security filters are applied to the request, but are not exposed to the client
+    expect(result.query).toEqual(query);
+    expect(methods.sqlApiLoad.mock.calls[0][0].query.filters).toEqual([SECURITY_FILTER]);
+    expect(methods.sqlApiLoad.mock.calls[0][0].session.securityContext).toEqual({ tenant: 'tenant' });
+    expect(methods.sqlApiLoad.mock.calls[0][0].request.meta).toMatchObject({ apiType: 'rest', restApi: true });
+  });
+
+  it('rejects member expressions in /v1/load', async () => {
+    const expression = JSON.stringify({
+      cube_name: 'Logs',
+      alias: 'agent_upper',
+      cube_params: ['Logs'],
+      expr: 'UPPER(agent)',
+      grouping_set: null,
+    });
+    const response = await request('POST', '/v1/load', 'tenant', { query: { dimensions: [expression] } });
+
+    expect(response.status).toEqual(400);
+    expect(JSON.parse(response.body)).toEqual({ error: 'Expressions are not allowed in this context' });
+  });
+
+  it('passes "Continue wait" of /v1/load to the client', async () => {
+    methods.setContinueWait(true);
+
+    const response = await request('GET', `/v1/load?query=${encodeURIComponent(JSON.stringify(query))}`, 'tenant');
+
+    expect(response.status).toEqual(200);
+    expect(JSON.parse(response.body)).toEqual({ error: 'Continue wait' });
+    // Transport doesn't retry, the client repeats the request
+    expect(methods.sqlApiLoad).toHaveBeenCalledTimes(1);
+  });
+
+  it('serves /v1/sql', async () => {
+    const response = await request('POST', '/v1/sql', 'tenant', { query });
+
+    expect(response.status).toEqual(200);
+    expect(JSON.parse(response.body)).toEqual({
+      sql: {
+        sql: ['SELECT agent FROM logs WHERE agent = $1', ['tenant-agent']],
+      },
+    });
+    expect(methods.sql.mock.calls[0][0].query.filters).toEqual([SECURITY_FILTER]);
+  });
+
+  it('streams /v2/stream as NDJSON', async () => {
+    const response = await request('POST', '/v2/stream', 'tenant', { query, format: 'ndjson' });
+
+    expect(response.status).toEqual(200);
+    expect(response.contentType).toEqual('application/x-ndjson');
+    const rows = response.body.trim().split('\n').map((line) => JSON.parse(line));
+    expect(rows.length).toEqual(10);
+    expect(Object.keys(rows[0])).toEqual(['Logs.agent']);
+    expect(methods.sqlApiLoad.mock.calls[0][0].streaming).toEqual(true);
+    expect(methods.sqlApiLoad.mock.calls[0][0].request.meta.restApi).toEqual(true);
+  });
+
+  it('rejects unknown /v2/stream format', async () => {
+    const response = await request('POST', '/v2/stream', 'tenant', { query, format: 'csv' });
+
+    expect(response.status).toEqual(400);
+    expect(JSON.parse(response.body)).toEqual({
+      error: 'Unsupported format \'csv\', expected ndjson or arrow',
+    });
+  });
+});
@@ -103,6 +103,8 @@ suite('Python model', () => {
       new_object_from_dict: expect.any(Object),
       load_class_model: expect.any(Object),
       throw_exception: expect.any(Object),
+      load_cubes_gen: expect.any(Object),
+      load_cubes_async_gen: expect.any(Object),
     });
 
     expect(pythonModule.variables).toEqual({
@@ -179,6 +181,7 @@ suite('Jinja (new api)', () => {
     loadTemplateFile(jinjaEngine, 'python.yml');
     loadTemplateFile(jinjaEngine, 'variables.yml.jinja');
     loadTemplateFile(jinjaEngine, 'filters.yml.jinja');
+    loadTemplateFile(jinjaEngine, 'generators.yml.jinja');
     loadTemplateFile(jinjaEngine, 'template_error_python.jinja');
 
     for (let i = 1; i < 9; i++) {
@@ -208,11 +211,114 @@ suite('Jinja (new api)', () => {
   testTemplateWithPythonCtxBySnapshot(initJinjaEngine, 'python.yml', {});
   testTemplateWithPythonCtxBySnapshot(initJinjaEngine, 'variables.yml.jinja', {});
   testTemplateWithPythonCtxBySnapshot(initJinjaEngine, 'filters.yml.jinja', {});
+  testTemplateWithPythonCtxBySnapshot(initJinjaEngine, 'generators.yml.jinja', {});
   testTemplateErrorWithPythonCtxBySnapshot(initJinjaEngine, 'template_error_python.jinja', {});
 
   testLoadBrokenTemplateBySnapshot(initJinjaEngine, 'template_error_syntax.jinja');
 
+  it('resolves coroutine returned from a filter', async () => {
+    const { jinjaEngine, pyCtx } = await initJinjaEngine();
+
+    jinjaEngine.loadTemplate('async_filter.yml.jinja', 'value: {{ "my string" | async_upper_filter | filter_return_arg }}');
+
+    const actual = await jinjaEngine.renderTemplate('async_filter.yml.jinja', {}, {
+      ...pyCtx.variables,
+      ...pyCtx.functions,
+    });
+
+    expect(actual).toEqual('value: "MY STRING"');
+  });
+
   for (let i = 1; i < 9; i++) {
     testTemplateBySnapshot(initJinjaEngine, `0${i}.yml.jinja`, {});
   }
 });
+
+suite('Jinja (limits)', () => {
+  const initJinjaEngine = () => {
+    const jinjaEngine = nativeInstance.newJinjaEngine({
+      debugInfo: true,
+      filters: {},
+      workers: 1,
+      fuel: 10000,
+    });
+
+    loadTemplateFile(jinjaEngine, 'runaway_loop.yml.jinja');
+
+    return jinjaEngine;
+  };
+
+  it('stops runaway loop by fuel', async () => {
+    const jinjaEngine = initJinjaEngine();
+
+    await expect(jinjaEngine.renderTemplate('runaway_loop.yml.jinja', {}, null)).rejects.toMatchObject({
+      kind: 'OutOfFuel',
+      fileName: 'runaway_loop.yml.jinja',
+      templateStack: [
+        expect.objectContaining({ fileName: 'runaway_loop.yml.jinja' }),
+      ],
+    });
+  });
+
+  it('returns location for broken template', async () => {
+    const jinjaEngine = initJinjaEngine();
+
+    try {
+      loadTemplateFile(jinjaEngine, 'template_error_syntax.jinja');
+
+      throw new Error('Template template_error_syntax.jinja should throw an error!');
+    } catch (e: any) {
+      expect(e.fileName).toEqual('template_error_syntax.jinja');
+      expect(e.line).toEqual(10);
+      expect(e.templateStack).toEqual([
+        expect.objectContaining({ fileName: 'template_error_syntax.jinja', line: 10 }),
+      ]);
+    }
+  });
+
+  it('rejects render by timeout counted since the render has started', async () => {
+    const jinjaEngine = nativeInstance.newJinjaEngine({
+      debugInfo: true,
+      filters: {},
+      workers: 1,
+      fuel: 20_000_000,
+      renderTimeout: 10,
+    });
+
+    loadTemplateFile(jinjaEngine, 'runaway_loop.yml.jinja');
+    jinjaEngine.loadTemplate('small.yml.jinja', 'cubes: {{ 1 + 1 }}');
+
+    const runaway = jinjaEngine.renderTemplate('runaway_loop.yml.jinja', {}, null);
+    // Waits in the queue longer than the timeout, but it's rendered in time after the worker is released by fuel
+    const small = jinjaEngine.renderTemplate('small.yml.jinja', {}, null);
+
+    await expect(runaway).rejects.toMatchObject({
+      kind: 'Timeout',
+      fileName: 'runaway_loop.yml.jinja',
+    });
+    await expect(small).resolves.toEqual('cubes: 2');
+  }, 30 * 1000);
+
+  it('compiles only changed templates', async () => {
+    const jinjaEngine = nativeInstance.newJinjaEngine({
+      debugInfo: true,
+      filters: {},
+      workers: 1,
+    });
+
+    jinjaEngine.loadTemplate('cached.yml.jinja', 'cubes: {{ "first" }}');
+    expect(await jinjaEngine.renderTemplate('cached.yml.jinja', {}, null)).toEqual('cubes: "first"');
+
+    // Same source is skipped
+    jinjaEngine.loadTemplate('cached.yml.jinja', 'cubes: {{ "first" }}');
+    expect(await jinjaEngine.renderTemplate('cached.yml.jinja', {}, null)).toEqual('cubes: "first"');
+
+    jinjaEngine.loadTemplate('cached.yml.jinja', 'cubes: {{ "second" }}');
+    expect(await jinjaEngine.renderTemplate('cached.yml.jinja', {}, null)).toEqual('cubes: "second"');
+
+    // Broken source is not cached, it's reported on every load
+    for (let i = 0; i < 2; i++) {
+      expect(() => jinjaEngine.loadTemplate('cached.yml.jinja', 'cubes: {{ "broken" ')).toThrow();
+    }
+  });
+});
@@ -1,3 +1,5 @@
+import asyncio
+
 from cube import (TemplateContext, SafeString)
 
 template = TemplateContext()
@@ -108,3 +110,43 @@ def filter_return_arg(i):
 @template.function
 def throw_exception():
     raise Exception('Random Exception')
+
+@template.function
+def load_cubes_gen():
+  for name in ["cube_from_generator_1", "cube_from_generator_2"]:
+    yield { "name": name }
+
+lazy_gen_pulled_items = 0
+
+@template.function
+def load_cubes_lazy_gen():
+  global lazy_gen_pulled_items
+  lazy_gen_pulled_items = 0
+  for name in ["cube_from_lazy_generator_1", "cube_from_lazy_generator_2"]:
+    lazy_gen_pulled_items += 1
+    yield { "name": name }
+
+@template.function
+def lazy_gen_pulled():
+  return lazy_gen_pulled_items
+
+@template.function
+def load_cubes_infinite_gen():
+  i = 0
+  while True:
+    i += 1
+    yield { "name": f"cube_from_infinite_generator_{i}" }
+
+@template.function
+async def load_cubes_async_gen():
+  for name in ["cube_from_async_generator_1", "cube_from_async_generator_2"]:
+    yield { "name": name }
+
+@template.filter
+async def async_str_filter(i):
+  return 'str from async python'
+
+@template.filter
+async def async_upper_filter(i):
+  await asyncio.sleep(0)
+  return i.upper()
@@ -1719,6 +1719,11 @@ const variables: Record<string, (...args: any) => any> = {
     .asString(),
   jwtClaimsNamespace: () => get('CUBEJS_JWT_CLAIMS_NAMESPACE')
     .asString(),
+  jinjaFuel: () => get('CUBEJS_JINJA_FUEL')
+    .asIntPositive(),
+  jinjaRenderTimeout: () => get('CUBEJS_JINJA_RENDER_TIMEOUT')
+    .default(60 * 1000)
+    .asIntPositive(),
   playgroundAuthSecret: () => get('CUBEJS_PLAYGROUND_AUTH_SECRET')
     .asString(),
   agentFrameSize: () => get('CUBEJS_AGENT_FRAME_SIZE')
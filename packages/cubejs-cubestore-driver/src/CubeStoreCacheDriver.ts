@@ -31,12 +31,15 @@ export class CubeStoreCacheDriver implements CacheDriverInterface {
 
     const connection = (await this.getConnection());
 
-    const rows = await connection.query('CACHE SET NX TTL ? ? ?', [expiration, key, '1']);
-    if (rows && rows.length === 1 && rows[0]?.success === 'true') {
+    const rows = await connection.query('CACHE LOCK ? TTL ?', [key, expiration]);
+    // Fencing token, the lock is released only if it's still held with it
+    const token = rows && rows.length === 1 ? rows[0]?.token : null;
+    if (token) {
       if (tkn.isCanceled()) {
         if (freeAfter) {
-          await connection.query('CACHE REMOVE ?', [
-            key
+          await connection.query('CACHE UNLOCK ? ?', [
+            key,
+            token,
           ]);
         }
 
@@ -47,8 +50,9 @@ export class CubeStoreCacheDriver implements CacheDriverInterface {
         await tkn.with(cb());
       } finally {
         if (freeAfter) {
-          await connection.query('CACHE REMOVE ?', [
-            key
+          await connection.query('CACHE UNLOCK ? ?', [
+            key,
+            token,
           ]);
         }
       }
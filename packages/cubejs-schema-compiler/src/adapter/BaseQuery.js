@@ -11,10 +11,11 @@ import cronParser from 'cron-parser';
 
 import moment from 'moment-timezone';
 import inflection from 'inflection';
-import { FROM_PARTITION_RANGE, inDbTimeZone, MAX_SOURCE_ROW_LIMIT, QueryAlias, getEnv, timeSeries as timeSeriesBase } from '@cubejs-backend/shared';
+import { FROM_PARTITION_RANGE, inDbTimeZone, MAX_SOURCE_ROW_LIMIT, QueryAlias, getEnv } from '@cubejs-backend/shared';
 
 import {
   buildSqlAndParams as nativeBuildSqlAndParams,
+  buildPlanDescription as nativeBuildPlanDescription,
 } from '@cubejs-backend/native';
 import { UserError } from '../compiler/UserError';
 import { BaseMeasure } from './BaseMeasure';
@@ -586,14 +587,14 @@ export class BaseQuery {
    * @returns {[string, Array<unknown>]}
    */
   buildSqlAndParams(exportAnnotatedSql) {
+    if (!this.options.preAggregationQuery && !this.options.disableExternalPreAggregations && this.externalQueryClass) {
+      if (this.externalPreAggregationQuery()) { // TODO performance
+        return this.externalQuery().buildSqlAndParams(exportAnnotatedSql);
+      }
+    }
     if (getEnv('nativeSqlPlanner')) {
       return this.buildSqlAndParamsRust(exportAnnotatedSql);
     } else {
-      if (!this.options.preAggregationQuery && !this.options.disableExternalPreAggregations && this.externalQueryClass) {
-        if (this.externalPreAggregationQuery()) { // TODO performance
-          return this.externalQuery().buildSqlAndParams(exportAnnotatedSql);
-        }
-      }
       return this.compilers.compiler.withQuery(
         this,
         () => this.cacheValue(
@@ -610,12 +611,46 @@ export class BaseQuery {
   }
 
   buildSqlAndParamsRust(exportAnnotatedSql) {
+    const res = nativeBuildSqlAndParams(this.rustQueryParams());
+    // FIXME
+    res[1] = [...res[1]];
+    return res;
+  }
+
+  /**
+   * Returns the plan Tesseract builds for the query, it's picked for the same query
+   * as `buildSqlAndParams()` uses, including external pre-aggregation queries.
+   * @returns {Object | null} null when Tesseract is disabled
+   */
+  buildPlanDescription() {
+    if (!this.options.preAggregationQuery && !this.options.disableExternalPreAggregations && this.externalQueryClass) {
+      if (this.externalPreAggregationQuery()) {
+        return this.externalQuery().buildPlanDescription();
+      }
+    }
+    if (!getEnv('nativeSqlPlanner')) {
+      return null;
+    }
+
+    return this.buildPlanDescriptionRust();
+  }
+
+  /**
+   * Returns the plan Tesseract builds for the query instead of SQL:
+   * the chosen planner, joins, subqueries, multi-stage CTEs and applied filters.
+   * @returns {Object}
+   */
+  buildPlanDescriptionRust() {
+    return nativeBuildPlanDescription(this.rustQueryParams());
+  }
+
+  rustQueryParams() {
     const order = this.options.order && R.pipe(
       R.map((hash) => ((!hash || !hash.id) ? null : hash)),
       R.reject(R.isNil),
     )(this.options.order);
 
-    const queryParams = {
+    return {
       measures: this.options.measures,
       dimensions: this.options.dimensions,
       timeDimensions: this.options.timeDimensions,
@@ -628,13 +663,40 @@ export class BaseQuery {
       rowLimit: this.options.rowLimit ? this.options.rowLimit.toString() : null,
       offset: this.options.offset ? this.options.offset.toString() : null,
       baseTools: this,
-      ungrouped: this.options.ungrouped
+      ungrouped: this.options.ungrouped,
+      preAggregationQuery: this.options.preAggregationQuery,
+      preAggregationForQuery: this.rustPreAggregationForQuery(),
+    };
+  }
+
+  /**
+   * Pre-aggregation Tesseract reads the query from. It's selected by the same
+   * `findPreAggregationForQuery()` as `preAggregationsDescription()` uses, so the planner
+   * reads from the table the orchestrator builds. Only rollups are passed,
+   * other pre-aggregation types are planned against the cube SQL.
+   * @returns {{ cubeName: string, name: string, tableName: string } | null}
+   */
+  rustPreAggregationForQuery() {
+    if (this.options.preAggregationQuery) {
+      return null;
+    }
+    const preAggForQuery = this.preAggregations.findPreAggregationForQuery();
+    if (!preAggForQuery || preAggForQuery.preAggregation.type !== 'rollup') {
+      return null;
+    }
+    if (this.options.disableExternalPreAggregations && preAggForQuery.preAggregation.external) {
+      return null;
+    }
 
+    return {
+      cubeName: preAggForQuery.cube,
+      name: preAggForQuery.preAggregationName,
+      tableName: this.preAggregations.preAggregationTableName(
+        preAggForQuery.cube,
+        preAggForQuery.preAggregationName,
+        preAggForQuery.preAggregation
+      ),
     };
-    const res = nativeBuildSqlAndParams(queryParams);
-    // FIXME
-    res[1] = [...res[1]];
-    return res;
   }
 
   allCubeMembers(path) {
@@ -647,11 +709,6 @@ export class BaseQuery {
     return this.paramAllocator.getParams();
   }
 
-  // FIXME helper for native generator, maybe should be moved entire to rust
-  generateTimeSeries(granularity, dateRange) {
-    return timeSeriesBase(granularity, dateRange);
-  }
-
   get shouldReuseParams() {
     return false;
   }
@@ -3307,6 +3364,7 @@ export class BaseQuery {
         in: '{{ column }} IN ({{ values_concat }}){{ is_null_check }}',
         not_in: '{{ column }} NOT IN ({{ values_concat }}){{ is_null_check }}',
         time_range_filter: '{{ column }} >= {{ from_timestamp }} AND {{ column }} <= {{ to_timestamp }}',
+        time_not_in_range_filter: '{{ column }} < {{ from_timestamp }} OR {{ column }} > {{ to_timestamp }}',
         gt: '{{ column }} > {{ param }}',
         gte: '{{ column }} >= {{ param }}',
         lt: '{{ column }} < {{ param }}',
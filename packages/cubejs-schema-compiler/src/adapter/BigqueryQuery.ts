@@ -258,6 +258,7 @@ export class BigqueryQuery extends BaseQuery {
     templates.types.binary = 'BYTES';
     templates.operators.is_not_distinct_from = 'IS NOT DISTINCT FROM';
     templates.join_types.full = 'FULL';
+    templates.filters.regex = '{% if negated %}NOT {% endif %}REGEXP_CONTAINS({{ column }}, {{ value }})';
     return templates;
   }
 }
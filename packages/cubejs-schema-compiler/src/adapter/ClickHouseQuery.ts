@@ -281,6 +281,7 @@ export class ClickHouseQuery extends BaseQuery {
     // ClickHouse intervals have a distinct type for each granularity
     delete templates.types.interval;
     delete templates.types.binary;
+    templates.filters.regex = '{% if negated %}NOT {% endif %}match({{ column }}, {{ value }})';
     return templates;
   }
 }
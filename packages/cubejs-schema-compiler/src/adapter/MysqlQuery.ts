@@ -166,6 +166,7 @@ export class MysqlQuery extends BaseQuery {
     templates.types.timestamp = 'DATETIME';
     delete templates.types.interval;
     templates.types.binary = 'BLOB';
+    templates.filters.regex = '{{ column }} {% if negated %}NOT {% endif %}REGEXP {{ value }}';
     return templates;
   }
 }
@@ -82,6 +82,7 @@ export class PostgresQuery extends BaseQuery {
     templates.types.double = 'DOUBLE PRECISION';
     templates.types.binary = 'BYTEA';
     templates.operators.is_not_distinct_from = 'IS NOT DISTINCT FROM';
+    templates.filters.regex = '{{ column }} {% if negated %}!{% endif %}~ {{ value }}';
     return templates;
   }
 
@@ -463,6 +463,29 @@ export class CubeEvaluator extends CubeSymbols {
     return this.cubeFromPath(path).preAggregations || {};
   }
 
+  public preAggregationsForCubeAsArray(path: string) {
+    const preAggregations = this.preAggregationsForCube(path);
+    return Object.keys(preAggregations).map(name => {
+      const preAggregation = preAggregations[name];
+      const references = this.evaluatePreAggregationReferences(path, {
+        ...preAggregation,
+        segmentReferences: undefined,
+      });
+      return {
+        name,
+        type: preAggregation.type,
+        sqlAlias: preAggregation.sqlAlias,
+        external: preAggregation.external,
+        measures: references.measures,
+        dimensions: references.dimensions,
+        segments: preAggregation.segmentReferences &&
+          this.evaluateReferences(path, preAggregation.segmentReferences) || [],
+        timeDimensions: references.timeDimensions,
+        allowNonStrictDateRangeMatch: references.allowNonStrictDateRangeMatch,
+      };
+    });
+  }
+
   /**
    * Returns pre-aggregations filtered by the spcified selector.
    * @param {{
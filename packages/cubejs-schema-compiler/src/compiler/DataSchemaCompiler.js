@@ -80,7 +80,8 @@ export class DataSchemaCompiler {
     const files = await this.repository.dataSchemaFiles();
 
     this.pythonContext = await this.loadPythonContext(files, 'globals.py');
-    this.yamlCompiler.initFromPythonContext(this.pythonContext);
+    const pythonContextSource = files.find((f) => f.fileName === 'globals.py')?.content || '';
+    this.yamlCompiler.initFromPythonContext(this.pythonContext, pythonContextSource);
 
     const toCompile = files.filter((f) => !this.filesToCompile || this.filesToCompile.indexOf(f.fileName) !== -1);
 
@@ -97,7 +98,8 @@ export class DataSchemaCompiler {
       .then(() => compilePhase({
         cubeCompilers: this.cubeCompilers,
         contextCompilers: this.contextCompilers,
-      }));
+      }))
+      .finally(() => this.yamlCompiler.releaseJinjaEngine());
   }
 
   compile() {
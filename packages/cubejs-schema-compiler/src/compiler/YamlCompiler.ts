@@ -3,7 +3,7 @@ import * as t from '@babel/types';
 import { parse } from '@babel/parser';
 import babelGenerator from '@babel/generator';
 import babelTraverse from '@babel/traverse';
-import { JinjaEngine, NativeInstance, PythonCtx } from '@cubejs-backend/native';
+import { JinjaEngine, JinjaTemplateError, NativeInstance, PythonCtx } from '@cubejs-backend/native';
 
 import type { FileContent } from '@cubejs-backend/shared';
 
@@ -16,6 +16,7 @@ import { nonStringFields } from './CubeValidator';
 import { CubeDictionary } from './CubeDictionary';
 import { ErrorReporter } from './ErrorReporter';
 import { camelizeCube } from './utils';
+import { UserError } from './UserError';
 
 type EscapeStateStack = {
   inFormattedStr?: boolean;
@@ -24,11 +25,20 @@ type EscapeStateStack = {
   depth?: number;
 };
 
+/**
+ * Jinja engines are kept between compilations of the same app, they are grouped by the source of the Python context.
+ * Engine caches loaded templates by the hash of their source, so templates which were not changed
+ * are not compiled again. Engine is used by one compilation at a time.
+ */
+const jinjaEnginePools = new WeakMap<NativeInstance, Map<string, JinjaEngine[]>>();
+
 export class YamlCompiler {
   public dataSchemaCompiler: DataSchemaCompiler | null = null;
 
   protected jinjaEngine: JinjaEngine | null = null;
 
+  protected jinjaEngineKey: string | null = null;
+
   public constructor(
     private readonly cubeSymbols: CubeSymbols,
     private readonly cubeDictionary: CubeDictionary,
@@ -44,14 +54,52 @@ export class YamlCompiler {
     throw new Error('Jinja engine was not initialized');
   }
 
-  public initFromPythonContext(ctx: PythonCtx) {
-    this.jinjaEngine = this.nativeInstance.newJinjaEngine({
+  protected getJinjaEnginePool(): Map<string, JinjaEngine[]> {
+    let pool = jinjaEnginePools.get(this.nativeInstance);
+    if (!pool) {
+      pool = new Map();
+      jinjaEnginePools.set(this.nativeInstance, pool);
+    }
+
+    return pool;
+  }
+
+  /**
+   * @param ctx Python context of the compilation
+   * @param ctxKey Source of the Python context, engines are reused only for the same source
+   */
+  public initFromPythonContext(ctx: PythonCtx, ctxKey: string) {
+    this.releaseJinjaEngine();
+
+    const engine = this.getJinjaEnginePool().get(ctxKey)?.pop();
+
+    this.jinjaEngineKey = ctxKey;
+    this.jinjaEngine = engine || this.nativeInstance.newJinjaEngine({
       debugInfo: getEnv('devMode'),
       filters: ctx.filters,
       workers: 1,
+      fuel: getEnv('jinjaFuel'),
+      renderTimeout: getEnv('jinjaRenderTimeout'),
     });
   }
 
+  /**
+   * Returns the engine to the pool, it should be called when the compilation is finished.
+   */
+  public releaseJinjaEngine() {
+    if (!this.jinjaEngine || this.jinjaEngineKey === null) {
+      return;
+    }
+
+    const pool = this.getJinjaEnginePool();
+    const engines = pool.get(this.jinjaEngineKey) || [];
+    engines.push(this.jinjaEngine);
+    pool.set(this.jinjaEngineKey, engines);
+
+    this.jinjaEngine = null;
+    this.jinjaEngineKey = null;
+  }
+
   public async compileYamlWithJinjaFile(
     file: FileContent,
     errorsReport: ErrorReporter,
@@ -64,12 +112,28 @@ export class YamlCompiler {
     compileContext,
     pythonContext
   ) {
-    const compiledFile = {
-      fileName: file.fileName,
-      content: await this.getJinjaEngine().renderTemplate(file.fileName, compileContext, {
+    let content: string;
+
+    try {
+      content = await this.getJinjaEngine().renderTemplate(file.fileName, compileContext, {
         ...pythonContext.functions,
         ...pythonContext.variables
-      }),
+      });
+    } catch (e: any) {
+      if (!e?.templateStack) {
+        throw e;
+      }
+
+      // Point the error to the model file and line where it happened, including imported macros
+      const err = <JinjaTemplateError>e;
+      errorsReport.error(new UserError(err.message), err.fileName || file.fileName, err.line, err.column);
+
+      return undefined;
+    }
+
+    const compiledFile = {
+      fileName: file.fileName,
+      content,
     };
 
     return this.compileYamlFile(
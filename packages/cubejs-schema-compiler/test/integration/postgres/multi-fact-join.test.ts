@@ -131,4 +131,26 @@ cube(\`city\`, {
     orders__amount: '6',
     shipments__count: '1',
   }]));
+
+  it('describes the plan of two regular sub-queries', async () => {
+    if (!getEnv('nativeSqlPlanner')) {
+      return;
+    }
+    await compiler.compile();
+    const query = new PostgresQuery({ joinGraph, cubeEvaluator, compiler }, {
+      measures: ['orders.amount', 'shipments.count'],
+      dimensions: [
+        'city.name'
+      ],
+    });
+
+    const plan = query.buildPlanDescription();
+
+    expect(plan.planner).toEqual('full_key_aggregate');
+    expect(plan.subqueries.length).toEqual(2);
+    expect(plan.subqueries.map((subquery) => subquery.measures)).toEqual(expect.arrayContaining([
+      ['orders.amount'],
+      ['shipments.count'],
+    ]));
+  });
 });
@@ -1,3 +1,5 @@
+import { NativeInstance } from '@cubejs-backend/native';
+
 import { prepareYamlCompiler } from './PrepareCompiler';
 
 describe('Yaml Schema Testing', () => {
@@ -397,4 +399,35 @@ describe('Yaml Schema Testing', () => {
       await compiler.compile();
     });
   });
+
+  it('reuses jinja engine between compilations of the same app', async () => {
+    const nativeInstance = new NativeInstance();
+    const newJinjaEngine = jest.spyOn(nativeInstance, 'newJinjaEngine');
+
+    const compile = async (cubeName: string) => {
+      const { compiler, cubeEvaluator } = prepareYamlCompiler(
+        `
+        cubes:
+        - name: {{ "${cubeName}" }}
+          sql: "select * from tbl"
+          measures:
+            - name: count
+              type: count
+        `,
+        false,
+        { nativeInstance }
+      );
+
+      await compiler.compile();
+
+      return cubeEvaluator;
+    };
+
+    expect((await compile('Orders')).cubeExists('Orders')).toEqual(true);
+    // Changed template is compiled again by the same engine
+    expect((await compile('Products')).cubeExists('Products')).toEqual(true);
+    expect((await compile('Products')).cubeExists('Orders')).toEqual(false);
+
+    expect(newJinjaEngine).toHaveBeenCalledTimes(1);
+  });
 });
@@ -173,6 +173,8 @@ export class CompilerApi {
       aliasNameToMember: sqlGenerator.aliasNameToMember,
       rollupMatchResults: includeDebugInfo ?
         sqlGenerator.preAggregations.rollupMatchResultDescriptions() : undefined,
+      // Tesseract plan, it's returned by /v1/sql with debug info for the Playground and CI checks
+      planDescription: includeDebugInfo ? sqlGenerator.buildPlanDescription() : undefined,
       canUseTransformedQuery: sqlGenerator.preAggregations.canUseTransformedQuery(),
       memberNames: sqlGenerator.collectAllMemberNames(),
     }));
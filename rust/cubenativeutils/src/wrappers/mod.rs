@@ -3,6 +3,7 @@ pub mod inner_types;
 pub mod neon;
 pub mod object;
 pub mod object_handle;
+pub mod rust;
 pub mod serializer;
 
 pub use context::NativeContextHolder;
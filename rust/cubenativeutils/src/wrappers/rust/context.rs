@@ -0,0 +1,50 @@
+use super::inner_types::RustInnerTypes;
+use super::object::{
+    RustArray, RustBoolean, RustFunction, RustNumber, RustObject, RustString, RustStruct,
+};
+use crate::wrappers::context::{NativeContext, NativeContextHolder};
+use crate::wrappers::object::NativeType;
+use crate::wrappers::object_handle::NativeObjectHandle;
+
+#[derive(Clone, Default)]
+pub struct RustContext;
+
+impl RustContext {
+    pub fn new_holder() -> NativeContextHolder<RustInnerTypes> {
+        NativeContextHolder::new(Self)
+    }
+}
+
+impl NativeContext<RustInnerTypes> for RustContext {
+    fn boolean(&self, v: bool) -> RustBoolean {
+        RustBoolean::new(v)
+    }
+
+    fn string(&self, v: String) -> RustString {
+        RustString::new(v)
+    }
+
+    fn number(&self, v: f64) -> RustNumber {
+        RustNumber::new(v)
+    }
+
+    fn undefined(&self) -> NativeObjectHandle<RustInnerTypes> {
+        NativeObjectHandle::new(RustObject::Undefined)
+    }
+
+    fn empty_array(&self) -> RustArray {
+        RustArray::new()
+    }
+
+    fn empty_struct(&self) -> RustStruct {
+        RustStruct::new()
+    }
+
+    fn to_string_fn(&self, result: String) -> RustFunction {
+        RustFunction::new(vec![], "toString", move |_| {
+            Ok(NativeObjectHandle::new(
+                RustString::new(result.clone()).into_object(),
+            ))
+        })
+    }
+}
@@ -0,0 +1,19 @@
+use super::context::RustContext;
+use super::object::{
+    RustArray, RustBoolean, RustFunction, RustNumber, RustObject, RustString, RustStruct,
+};
+use crate::wrappers::inner_types::InnerTypes;
+
+#[derive(Clone)]
+pub struct RustInnerTypes;
+
+impl InnerTypes for RustInnerTypes {
+    type Object = RustObject;
+    type Context = RustContext;
+    type Array = RustArray;
+    type Struct = RustStruct;
+    type String = RustString;
+    type Boolean = RustBoolean;
+    type Function = RustFunction;
+    type Number = RustNumber;
+}
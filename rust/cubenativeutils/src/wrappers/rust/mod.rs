@@ -0,0 +1,11 @@
+//! In-memory implementation of the native wrappers, backed by plain Rust values.
+//! It allows code generic over `InnerTypes` to run without a JS runtime, e.g. in `cargo test`.
+pub mod context;
+pub mod inner_types;
+pub mod object;
+
+pub use context::RustContext;
+pub use inner_types::RustInnerTypes;
+pub use object::{
+    RustArray, RustBoolean, RustFunction, RustNumber, RustObject, RustString, RustStruct,
+};
@@ -0,0 +1,73 @@
+use super::RustObject;
+use crate::wrappers::object::{NativeBoolean, NativeNumber, NativeString, NativeType};
+use crate::wrappers::rust::inner_types::RustInnerTypes;
+use cubesql::CubeError;
+
+#[derive(Clone)]
+pub struct RustString {
+    value: String,
+}
+
+impl RustString {
+    pub fn new(value: String) -> Self {
+        Self { value }
+    }
+}
+
+impl NativeType<RustInnerTypes> for RustString {
+    fn into_object(self) -> RustObject {
+        RustObject::String(self)
+    }
+}
+
+impl NativeString<RustInnerTypes> for RustString {
+    fn value(&self) -> Result<String, CubeError> {
+        Ok(self.value.clone())
+    }
+}
+
+#[derive(Clone)]
+pub struct RustNumber {
+    value: f64,
+}
+
+impl RustNumber {
+    pub fn new(value: f64) -> Self {
+        Self { value }
+    }
+}
+
+impl NativeType<RustInnerTypes> for RustNumber {
+    fn into_object(self) -> RustObject {
+        RustObject::Number(self)
+    }
+}
+
+impl NativeNumber<RustInnerTypes> for RustNumber {
+    fn value(&self) -> Result<f64, CubeError> {
+        Ok(self.value)
+    }
+}
+
+#[derive(Clone)]
+pub struct RustBoolean {
+    value: bool,
+}
+
+impl RustBoolean {
+    pub fn new(value: bool) -> Self {
+        Self { value }
+    }
+}
+
+impl NativeType<RustInnerTypes> for RustBoolean {
+    fn into_object(self) -> RustObject {
+        RustObject::Boolean(self)
+    }
+}
+
+impl NativeBoolean<RustInnerTypes> for RustBoolean {
+    fn value(&self) -> Result<bool, CubeError> {
+        Ok(self.value)
+    }
+}
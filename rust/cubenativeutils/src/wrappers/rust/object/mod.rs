@@ -0,0 +1,134 @@
+pub mod base_types;
+pub mod rust_array;
+pub mod rust_function;
+pub mod rust_struct;
+
+pub use self::base_types::{RustBoolean, RustNumber, RustString};
+pub use self::rust_array::RustArray;
+pub use self::rust_function::RustFunction;
+pub use self::rust_struct::RustStruct;
+use super::context::RustContext;
+use super::inner_types::RustInnerTypes;
+use crate::wrappers::object::{NativeArray, NativeObject, NativeStruct, NativeType};
+use crate::wrappers::object_handle::NativeObjectHandle;
+use cubesql::CubeError;
+use serde_json::Value;
+
+#[derive(Clone)]
+pub enum RustObject {
+    Null,
+    Undefined,
+    Boolean(RustBoolean),
+    Number(RustNumber),
+    String(RustString),
+    Array(RustArray),
+    Struct(RustStruct),
+    Function(RustFunction),
+}
+
+impl RustObject {
+    /// Builds object tree from json value. Json objects become structs with fields in the
+    /// same order as in the source value.
+    pub fn from_json(value: &Value) -> Self {
+        match value {
+            Value::Null => RustObject::Null,
+            Value::Bool(v) => RustBoolean::new(*v).into_object(),
+            Value::Number(v) => RustNumber::new(v.as_f64().unwrap_or_default()).into_object(),
+            Value::String(v) => RustString::new(v.clone()).into_object(),
+            Value::Array(items) => {
+                let array = RustArray::new();
+                for (i, item) in items.iter().enumerate() {
+                    array
+                        .set(i as u32, NativeObjectHandle::new(Self::from_json(item)))
+                        .unwrap();
+                }
+                array.into_object()
+            }
+            Value::Object(fields) => {
+                let result = RustStruct::new();
+                for (name, item) in fields.iter() {
+                    result
+                        .set_field(name, NativeObjectHandle::new(Self::from_json(item)))
+                        .unwrap();
+                }
+                result.into_object()
+            }
+        }
+    }
+
+    pub fn into_handle(self) -> NativeObjectHandle<RustInnerTypes> {
+        NativeObjectHandle::new(self)
+    }
+
+    fn type_name(&self) -> &'static str {
+        match self {
+            RustObject::Null => "Null",
+            RustObject::Undefined => "Undefined",
+            RustObject::Boolean(_) => "Boolean",
+            RustObject::Number(_) => "Number",
+            RustObject::String(_) => "String",
+            RustObject::Array(_) => "Array",
+            RustObject::Struct(_) => "Struct",
+            RustObject::Function(_) => "Function",
+        }
+    }
+
+    fn type_error(&self, expected: &str) -> CubeError {
+        CubeError::internal(format!(
+            "RustObject is not the {} but {}",
+            expected,
+            self.type_name()
+        ))
+    }
+}
+
+impl NativeObject<RustInnerTypes> for RustObject {
+    fn get_context(&self) -> RustContext {
+        RustContext
+    }
+
+    fn into_struct(self) -> Result<RustStruct, CubeError> {
+        match self {
+            RustObject::Struct(v) => Ok(v),
+            other => Err(other.type_error("Struct")),
+        }
+    }
+    fn into_array(self) -> Result<RustArray, CubeError> {
+        match self {
+            RustObject::Array(v) => Ok(v),
+            other => Err(other.type_error("Array")),
+        }
+    }
+    fn into_string(self) -> Result<RustString, CubeError> {
+        match self {
+            RustObject::String(v) => Ok(v),
+            other => Err(other.type_error("String")),
+        }
+    }
+    fn into_number(self) -> Result<RustNumber, CubeError> {
+        match self {
+            RustObject::Number(v) => Ok(v),
+            other => Err(other.type_error("Number")),
+        }
+    }
+    fn into_boolean(self) -> Result<RustBoolean, CubeError> {
+        match self {
+            RustObject::Boolean(v) => Ok(v),
+            other => Err(other.type_error("Boolean")),
+        }
+    }
+    fn into_function(self) -> Result<RustFunction, CubeError> {
+        match self {
+            RustObject::Function(v) => Ok(v),
+            other => Err(other.type_error("Function")),
+        }
+    }
+
+    fn is_null(&self) -> bool {
+        matches!(self, RustObject::Null)
+    }
+
+    fn is_undefined(&self) -> bool {
+        matches!(self, RustObject::Undefined)
+    }
+}
@@ -0,0 +1,62 @@
+use super::RustObject;
+use crate::wrappers::object::{NativeArray, NativeType};
+use crate::wrappers::object_handle::NativeObjectHandle;
+use crate::wrappers::rust::inner_types::RustInnerTypes;
+use cubesql::CubeError;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// Array shares its items between clones, as JS arrays do.
+#[derive(Clone, Default)]
+pub struct RustArray {
+    items: Rc<RefCell<Vec<RustObject>>>,
+}
+
+impl RustArray {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl NativeType<RustInnerTypes> for RustArray {
+    fn into_object(self) -> RustObject {
+        RustObject::Array(self)
+    }
+}
+
+impl NativeArray<RustInnerTypes> for RustArray {
+    fn len(&self) -> Result<u32, CubeError> {
+        Ok(self.items.borrow().len() as u32)
+    }
+
+    fn to_vec(&self) -> Result<Vec<NativeObjectHandle<RustInnerTypes>>, CubeError> {
+        Ok(self
+            .items
+            .borrow()
+            .iter()
+            .map(|item| NativeObjectHandle::new(item.clone()))
+            .collect())
+    }
+
+    fn set(
+        &self,
+        index: u32,
+        value: NativeObjectHandle<RustInnerTypes>,
+    ) -> Result<bool, CubeError> {
+        let mut items = self.items.borrow_mut();
+        let index = index as usize;
+        if index >= items.len() {
+            items.resize(index + 1, RustObject::Undefined);
+        }
+        items[index] = value.into_object();
+        Ok(true)
+    }
+
+    fn get(&self, index: u32) -> Result<NativeObjectHandle<RustInnerTypes>, CubeError> {
+        let items = self.items.borrow();
+        let item = items.get(index as usize).cloned().ok_or_else(|| {
+            CubeError::internal(format!("Array index {} is out of bounds", index))
+        })?;
+        Ok(NativeObjectHandle::new(item))
+    }
+}
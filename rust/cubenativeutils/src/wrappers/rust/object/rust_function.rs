@@ -0,0 +1,58 @@
+use super::RustObject;
+use crate::wrappers::object::{NativeFunction, NativeType};
+use crate::wrappers::object_handle::NativeObjectHandle;
+use crate::wrappers::rust::inner_types::RustInnerTypes;
+use cubesql::CubeError;
+use std::rc::Rc;
+
+type RustCallback = dyn Fn(
+    Vec<NativeObjectHandle<RustInnerTypes>>,
+) -> Result<NativeObjectHandle<RustInnerTypes>, CubeError>;
+
+/// Function backed by a Rust closure. Since there is no source text to parse,
+/// argument names are passed explicitly on creation.
+#[derive(Clone)]
+pub struct RustFunction {
+    args_names: Vec<String>,
+    definition: String,
+    callback: Rc<RustCallback>,
+}
+
+impl RustFunction {
+    pub fn new<F>(args_names: Vec<String>, definition: &str, callback: F) -> Self
+    where
+        F: Fn(
+                Vec<NativeObjectHandle<RustInnerTypes>>,
+            ) -> Result<NativeObjectHandle<RustInnerTypes>, CubeError>
+            + 'static,
+    {
+        Self {
+            args_names,
+            definition: definition.to_string(),
+            callback: Rc::new(callback),
+        }
+    }
+}
+
+impl NativeType<RustInnerTypes> for RustFunction {
+    fn into_object(self) -> RustObject {
+        RustObject::Function(self)
+    }
+}
+
+impl NativeFunction<RustInnerTypes> for RustFunction {
+    fn call(
+        &self,
+        args: Vec<NativeObjectHandle<RustInnerTypes>>,
+    ) -> Result<NativeObjectHandle<RustInnerTypes>, CubeError> {
+        (self.callback)(args)
+    }
+
+    fn definition(&self) -> Result<String, CubeError> {
+        Ok(self.definition.clone())
+    }
+
+    fn args_names(&self) -> Result<Vec<String>, CubeError> {
+        Ok(self.args_names.clone())
+    }
+}
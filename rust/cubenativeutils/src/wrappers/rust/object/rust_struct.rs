@@ -0,0 +1,88 @@
+use super::{RustObject, RustString};
+use crate::wrappers::object::{NativeFunction, NativeStruct, NativeType};
+use crate::wrappers::object_handle::NativeObjectHandle;
+use crate::wrappers::rust::inner_types::RustInnerTypes;
+use cubesql::CubeError;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// Struct shares its fields between clones, as JS objects do. Fields keep
+/// insertion order, so property names are returned in the order they were set.
+#[derive(Clone, Default)]
+pub struct RustStruct {
+    fields: Rc<RefCell<Vec<(String, RustObject)>>>,
+}
+
+impl RustStruct {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_field(self, field_name: &str, value: RustObject) -> Self {
+        self.set_field(field_name, NativeObjectHandle::new(value))
+            .unwrap();
+        self
+    }
+}
+
+impl NativeType<RustInnerTypes> for RustStruct {
+    fn into_object(self) -> RustObject {
+        RustObject::Struct(self)
+    }
+}
+
+impl NativeStruct<RustInnerTypes> for RustStruct {
+    fn get_field(&self, field_name: &str) -> Result<NativeObjectHandle<RustInnerTypes>, CubeError> {
+        let value = self
+            .fields
+            .borrow()
+            .iter()
+            .find(|(name, _)| name == field_name)
+            .map(|(_, value)| value.clone())
+            .unwrap_or(RustObject::Undefined);
+        Ok(NativeObjectHandle::new(value))
+    }
+
+    fn set_field(
+        &self,
+        field_name: &str,
+        value: NativeObjectHandle<RustInnerTypes>,
+    ) -> Result<bool, CubeError> {
+        let mut fields = self.fields.borrow_mut();
+        let value = value.into_object();
+        if let Some(field) = fields.iter_mut().find(|(name, _)| name == field_name) {
+            field.1 = value;
+        } else {
+            fields.push((field_name.to_string(), value));
+        }
+        Ok(true)
+    }
+
+    fn has_field(&self, field_name: &str) -> Result<bool, CubeError> {
+        Ok(self
+            .fields
+            .borrow()
+            .iter()
+            .any(|(name, _)| name == field_name))
+    }
+
+    fn get_own_property_names(&self) -> Result<Vec<NativeObjectHandle<RustInnerTypes>>, CubeError> {
+        Ok(self
+            .fields
+            .borrow()
+            .iter()
+            .map(|(name, _)| NativeObjectHandle::new(RustString::new(name.clone()).into_object()))
+            .collect())
+    }
+
+    fn call_method(
+        &self,
+        method: &str,
+        args: Vec<NativeObjectHandle<RustInnerTypes>>,
+    ) -> Result<NativeObjectHandle<RustInnerTypes>, CubeError> {
+        let function = self.get_field(method)?.into_function().map_err(|_| {
+            CubeError::internal(format!("Method {} is not defined in struct", method))
+        })?;
+        function.call(args)
+    }
+}
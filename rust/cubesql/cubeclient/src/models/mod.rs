@@ -1,3 +1,5 @@
+pub mod v1_cube_cost_hints;
+pub use self::v1_cube_cost_hints::V1CubeCostHints;
 pub mod v1_cube_meta;
 pub use self::v1_cube_meta::V1CubeMeta;
 pub mod v1_cube_meta_dimension;
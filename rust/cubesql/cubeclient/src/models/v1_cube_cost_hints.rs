@@ -0,0 +1,26 @@
+/*
+ * Cube.js
+ *
+ * Cube.js Swagger Schema
+ *
+ * The version of the OpenAPI document: 1.0.0
+ *
+ * Generated by: https://openapi-generator.tech
+ */
+
+#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
+pub struct V1CubeCostHints {
+    #[serde(rename = "hasPreAggregations", skip_serializing_if = "Option::is_none")]
+    pub has_pre_aggregations: Option<bool>,
+    #[serde(rename = "estimatedRowCount", skip_serializing_if = "Option::is_none")]
+    pub estimated_row_count: Option<i64>,
+}
+
+impl V1CubeCostHints {
+    pub fn new() -> V1CubeCostHints {
+        V1CubeCostHints {
+            has_pre_aggregations: None,
+            estimated_row_count: None,
+        }
+    }
+}
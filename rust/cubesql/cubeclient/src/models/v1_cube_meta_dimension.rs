@@ -12,10 +12,14 @@
 pub struct V1CubeMetaDimension {
     #[serde(rename = "name")]
     pub name: String,
+    #[serde(rename = "title", skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
     #[serde(rename = "description", skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
     #[serde(rename = "type")]
     pub r#type: String,
+    #[serde(rename = "format", skip_serializing_if = "Option::is_none")]
+    pub format: Option<String>,
     /// When dimension is defined in View, it keeps the original path: Cube.dimension
     #[serde(rename = "aliasMember", skip_serializing_if = "Option::is_none")]
     pub alias_member: Option<String>,
@@ -29,8 +33,10 @@ impl V1CubeMetaDimension {
     pub fn new(name: String, r#type: String) -> V1CubeMetaDimension {
         V1CubeMetaDimension {
             name,
+            title: None,
             description: None,
             r#type,
+            format: None,
             alias_member: None,
             granularities: None,
             meta: None,
@@ -20,6 +20,8 @@ pub struct V1CubeMetaMeasure {
     pub r#type: String,
     #[serde(rename = "aggType", skip_serializing_if = "Option::is_none")]
     pub agg_type: Option<String>,
+    #[serde(rename = "format", skip_serializing_if = "Option::is_none")]
+    pub format: Option<String>,
     #[serde(rename = "meta", skip_serializing_if = "Option::is_none")]
     pub meta: Option<serde_json::Value>,
 }
@@ -32,6 +34,7 @@ impl V1CubeMetaMeasure {
             description: None,
             r#type,
             agg_type: None,
+            format: None,
             meta: None,
         }
     }
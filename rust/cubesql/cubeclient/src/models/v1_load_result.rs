@@ -18,6 +18,8 @@ pub struct V1LoadResult {
     pub data: Vec<serde_json::Value>,
     #[serde(rename = "refreshKeyValues", skip_serializing_if = "Option::is_none")]
     pub refresh_key_values: Option<Vec<serde_json::Value>>,
+    #[serde(rename = "total", skip_serializing_if = "Option::is_none")]
+    pub total: Option<i64>,
 }
 
 impl V1LoadResult {
@@ -30,6 +32,7 @@ impl V1LoadResult {
             annotation: Box::new(annotation),
             data,
             refresh_key_values: None,
+            total: None,
         }
     }
 }
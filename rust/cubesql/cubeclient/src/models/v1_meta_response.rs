@@ -14,6 +14,8 @@ pub struct V1MetaResponse {
     pub cubes: Option<Vec<crate::models::V1CubeMeta>>,
     #[serde(rename = "compilerId", skip_serializing_if = "Option::is_none")]
     pub compiler_id: Option<String>,
+    #[serde(rename = "costHints", skip_serializing_if = "Option::is_none")]
+    pub cost_hints: Option<::std::collections::HashMap<String, crate::models::V1CubeCostHints>>,
 }
 
 impl V1MetaResponse {
@@ -21,6 +23,7 @@ impl V1MetaResponse {
         V1MetaResponse {
             cubes: None,
             compiler_id: None,
+            cost_hints: None,
         }
     }
 }
@@ -80,6 +80,7 @@ pub fn get_large_model_test_meta(dims: usize) -> Vec<V1CubeMeta> {
                 description: None,
                 r#type: "number".to_string(),
                 agg_type: Some("count".to_string()),
+                format: None,
                 meta: None,
             },
             V1CubeMetaMeasure {
@@ -88,6 +89,7 @@ pub fn get_large_model_test_meta(dims: usize) -> Vec<V1CubeMeta> {
                 description: None,
                 r#type: "number".to_string(),
                 agg_type: Some("sum".to_string()),
+                format: None,
                 meta: None,
             },
         ],
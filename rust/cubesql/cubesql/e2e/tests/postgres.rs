@@ -875,6 +875,131 @@ impl PostgresIntegrationTestSuite {
         Ok(())
     }
 
+    async fn test_simple_query_batch(&self) -> RunResult<()> {
+        self.test_simple_query(
+            "SELECT 1 AS a; SET application_name = 'batch'; SELECT 'x;y' AS b".to_string(),
+            |messages| {
+                let messages = messages
+                    .iter()
+                    .filter(|m| {
+                        matches!(
+                            m,
+                            SimpleQueryMessage::Row(_) | SimpleQueryMessage::CommandComplete(_)
+                        )
+                    })
+                    .collect::<Vec<_>>();
+                assert_eq!(messages.len(), 5);
+
+                let SimpleQueryMessage::Row(row) = messages[0] else {
+                    panic!("Must be Row command, 0")
+                };
+                assert_eq!(row.get(0), Some("1"));
+                assert!(matches!(
+                    messages[1],
+                    SimpleQueryMessage::CommandComplete(1)
+                ));
+                assert!(matches!(
+                    messages[2],
+                    SimpleQueryMessage::CommandComplete(0)
+                ));
+                let SimpleQueryMessage::Row(row) = messages[3] else {
+                    panic!("Must be Row command, 3")
+                };
+                assert_eq!(row.get(0), Some("x;y"));
+                assert!(matches!(
+                    messages[4],
+                    SimpleQueryMessage::CommandComplete(1)
+                ));
+            },
+        )
+        .await?;
+
+        // Error stops the remaining statements of the batch
+        let err = self
+            .test_simple_query(
+                "SET application_name = 'before'; SELECT * FROM unknown_table; SET application_name = 'after'"
+                    .to_string(),
+                |_| {},
+            )
+            .await
+            .unwrap_err();
+        assert_contains!(err.to_string(), "unknown_table");
+
+        self.test_simple_query("SHOW application_name".to_string(), |messages| {
+            let SimpleQueryMessage::Row(row) = &messages[0] else {
+                panic!("Must be Row command, 0")
+            };
+            // Implicit transaction of the batch is rolled back
+            assert_ne!(row.get(0), Some("after"));
+            assert_ne!(row.get(0), Some("before"));
+        })
+        .await?;
+
+        Ok(())
+    }
+
+    async fn test_simple_query_failed_transaction(&self) -> RunResult<()> {
+        let client = PostgresIntegrationTestSuite::create_client(
+            format!("host=127.0.0.1 port={} user=test password=test", self.port)
+                .parse()
+                .unwrap(),
+        )
+        .await;
+
+        client.simple_query("BEGIN").await?;
+        client
+            .simple_query("SELECT * FROM unknown_table")
+            .await
+            .unwrap_err();
+
+        let err = client.simple_query("SELECT 1").await.unwrap_err();
+        assert_eq!(err.code(), Some(&SqlState::IN_FAILED_SQL_TRANSACTION));
+
+        // COMMIT of the failed transaction is a rollback
+        client.simple_query("COMMIT").await?;
+        client.simple_query("SELECT 1").await?;
+
+        Ok(())
+    }
+
+    async fn test_extended_failed_transaction(&self) -> RunResult<()> {
+        let mut client = PostgresIntegrationTestSuite::create_client(
+            format!("host=127.0.0.1 port={} user=test password=test", self.port)
+                .parse()
+                .unwrap(),
+        )
+        .await;
+
+        let stmt = client.prepare("SELECT 1").await?;
+
+        let transaction = client.transaction().await?;
+        let portal = transaction.bind(&stmt, &[]).await?;
+        transaction
+            .simple_query("SELECT * FROM unknown_table")
+            .await
+            .unwrap_err();
+
+        // Parse
+        let err = transaction.prepare("SELECT 2").await.unwrap_err();
+        assert_eq!(err.code(), Some(&SqlState::IN_FAILED_SQL_TRANSACTION));
+
+        // Bind
+        let err = transaction.query(&stmt, &[]).await.unwrap_err();
+        assert_eq!(err.code(), Some(&SqlState::IN_FAILED_SQL_TRANSACTION));
+
+        // Execute
+        let err = transaction.query_portal(&portal, 0).await.unwrap_err();
+        assert_eq!(err.code(), Some(&SqlState::IN_FAILED_SQL_TRANSACTION));
+
+        // ROLLBACK over the extended protocol ends the failed transaction
+        transaction.execute("ROLLBACK", &[]).await?;
+        drop(transaction);
+
+        client.query(&stmt, &[]).await?;
+
+        Ok(())
+    }
+
     async fn test_database_change(&self) -> RunResult<()> {
         self.test_simple_query("SELECT current_database()".to_string(), |messages| {
             assert_eq!(messages.len(), 2);
@@ -1192,6 +1317,9 @@ impl AsyncTestSuite for PostgresIntegrationTestSuite {
         self.test_simple_query_deallocate_all().await?;
         self.test_df_panic_handle().await?;
         self.test_simple_query_discard_all().await?;
+        self.test_simple_query_batch().await?;
+        self.test_simple_query_failed_transaction().await?;
+        self.test_extended_failed_transaction().await?;
         self.test_database_change().await?;
         self.test_temp_tables().await?;
 
@@ -151,6 +151,8 @@ impl QueryBuilder {
                     None
                 },
                 ungrouped: None,
+                timezone: None,
+                total: None,
             },
             meta: self.meta,
         }
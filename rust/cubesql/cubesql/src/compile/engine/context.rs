@@ -26,6 +26,9 @@ pub struct CubeContext {
     pub meta: Arc<MetaContext>,
     pub sessions: Arc<SessionManager>,
     pub session_state: Arc<SessionState>,
+    /// Requests to Cube are ungrouped, set for plans without aggregations when the session
+    /// has `cube.ungrouped` enabled
+    pub ungrouped: bool,
 }
 
 impl CubeContext {
@@ -40,6 +43,7 @@ impl CubeContext {
             meta,
             sessions,
             session_state,
+            ungrouped: false,
         }
     }
 
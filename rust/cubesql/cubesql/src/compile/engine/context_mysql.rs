@@ -2,16 +2,19 @@ use std::sync::Arc;
 
 use datafusion::datasource::{self, TableProvider};
 
-use super::information_schema::mysql::{
-    collations::InfoSchemaCollationsProvider as MySqlSchemaCollationsProvider,
-    columns::InfoSchemaColumnsProvider as MySqlSchemaColumnsProvider,
-    key_column_usage::InfoSchemaKeyColumnUsageProvider as MySqlSchemaKeyColumnUsageProvider,
-    processlist::InfoSchemaProcesslistProvider as MySqlSchemaProcesslistProvider,
-    referential_constraints::InfoSchemaReferentialConstraintsProvider as MySqlSchemaReferentialConstraintsProvider,
-    schemata::InfoSchemaSchemataProvider as MySqlSchemaSchemataProvider,
-    statistics::InfoSchemaStatisticsProvider as MySqlSchemaStatisticsProvider,
-    tables::InfoSchemaTableProvider as MySqlSchemaTableProvider,
-    variables::PerfSchemaVariablesProvider as MySqlPerfSchemaVariablesProvider,
+use super::information_schema::{
+    cube_members::InfoSchemaCubeMembersProvider,
+    mysql::{
+        collations::InfoSchemaCollationsProvider as MySqlSchemaCollationsProvider,
+        columns::InfoSchemaColumnsProvider as MySqlSchemaColumnsProvider,
+        key_column_usage::InfoSchemaKeyColumnUsageProvider as MySqlSchemaKeyColumnUsageProvider,
+        processlist::InfoSchemaProcesslistProvider as MySqlSchemaProcesslistProvider,
+        referential_constraints::InfoSchemaReferentialConstraintsProvider as MySqlSchemaReferentialConstraintsProvider,
+        schemata::InfoSchemaSchemataProvider as MySqlSchemaSchemataProvider,
+        statistics::InfoSchemaStatisticsProvider as MySqlSchemaStatisticsProvider,
+        tables::InfoSchemaTableProvider as MySqlSchemaTableProvider,
+        variables::PerfSchemaVariablesProvider as MySqlPerfSchemaVariablesProvider,
+    },
 };
 use crate::{
     compile::{
@@ -47,6 +50,8 @@ impl DatabaseProtocol {
             t.table_name().to_string()
         } else if let Some(t) = any.downcast_ref::<MySqlSchemaProcesslistProvider>() {
             t.table_name().to_string()
+        } else if let Some(t) = any.downcast_ref::<InfoSchemaCubeMembersProvider>() {
+            t.table_name().to_string()
         } else {
             return Err(CubeError::internal(format!(
                 "Unknown table provider with schema: {:?}",
@@ -113,6 +118,11 @@ impl DatabaseProtocol {
                     return Some(Arc::new(MySqlSchemaReferentialConstraintsProvider::new()))
                 }
                 "collations" => return Some(Arc::new(MySqlSchemaCollationsProvider::new())),
+                "cube_members" => {
+                    return Some(Arc::new(InfoSchemaCubeMembersProvider::new(
+                        &context.meta.cubes,
+                    )))
+                }
                 _ => return None,
             },
             "performance_schema" => match table.as_str() {
@@ -23,9 +23,9 @@ use super::information_schema::postgres::{
     PgCatalogIndexProvider, PgCatalogInheritsProvider, PgCatalogMatviewsProvider,
     PgCatalogNamespaceProvider, PgCatalogPartitionedTableProvider, PgCatalogProcProvider,
     PgCatalogRangeProvider, PgCatalogRolesProvider, PgCatalogSequenceProvider,
-    PgCatalogSettingsProvider, PgCatalogStatActivityProvider, PgCatalogStatUserTablesProvider,
-    PgCatalogStatioUserTablesProvider, PgCatalogStatsProvider, PgCatalogTableProvider,
-    PgCatalogTypeProvider, PgCatalogUserProvider, PgCatalogViewsProvider,
+    PgCatalogSettingsProvider, PgCatalogStatActivityProvider, PgCatalogStatStatementsProvider,
+    PgCatalogStatUserTablesProvider, PgCatalogStatioUserTablesProvider, PgCatalogStatsProvider,
+    PgCatalogTableProvider, PgCatalogTypeProvider, PgCatalogUserProvider, PgCatalogViewsProvider,
     PgPreparedStatementsProvider,
 };
 use crate::{
@@ -37,11 +37,14 @@ use crate::{
     CubeError,
 };
 
-use super::information_schema::redshift::{
-    RedshiftLateBindingViewUnpackedTableProvider, RedshiftPgExternalSchemaProvider,
-    RedshiftStlDdltextProvider, RedshiftStlQueryProvider, RedshiftStlQuerytextProvider,
-    RedshiftStvSlicesProvider, RedshiftSvvExternalSchemasTableProvider,
-    RedshiftSvvTableInfoProvider, RedshiftSvvTablesTableProvider,
+use super::information_schema::{
+    cube_members::InfoSchemaCubeMembersProvider,
+    redshift::{
+        RedshiftLateBindingViewUnpackedTableProvider, RedshiftPgExternalSchemaProvider,
+        RedshiftStlDdltextProvider, RedshiftStlQueryProvider, RedshiftStlQuerytextProvider,
+        RedshiftStvSlicesProvider, RedshiftSvvExternalSchemasTableProvider,
+        RedshiftSvvTableInfoProvider, RedshiftSvvTablesTableProvider,
+    },
 };
 
 impl DatabaseProtocol {
@@ -78,6 +81,8 @@ impl DatabaseProtocol {
             "information_schema.sql_implementation_info".to_string()
         } else if let Some(_) = any.downcast_ref::<PostgresInfoSchemaSqlSizingProvider>() {
             "information_schema.sql_sizing".to_string()
+        } else if let Some(t) = any.downcast_ref::<InfoSchemaCubeMembersProvider>() {
+            t.table_name().to_string()
         } else if let Some(_) = any.downcast_ref::<PgCatalogTableProvider>() {
             "pg_catalog.pg_tables".to_string()
         } else if let Some(_) = any.downcast_ref::<PgCatalogTypeProvider>() {
@@ -118,6 +123,8 @@ impl DatabaseProtocol {
             "pg_catalog.pg_roles".to_string()
         } else if let Some(_) = any.downcast_ref::<PgCatalogStatActivityProvider>() {
             "pg_catalog.pg_stat_activity".to_string()
+        } else if let Some(_) = any.downcast_ref::<PgCatalogStatStatementsProvider>() {
+            "pg_catalog.pg_stat_statements".to_string()
         } else if let Some(_) = any.downcast_ref::<PgCatalogStatioUserTablesProvider>() {
             "pg_catalog.pg_statio_user_tables".to_string()
         } else if let Some(_) = any.downcast_ref::<PgCatalogSequenceProvider>() {
@@ -253,6 +260,11 @@ impl DatabaseProtocol {
                 };
             }
             "information_schema" => match table.as_str() {
+                "cube_members" => {
+                    return Some(Arc::new(InfoSchemaCubeMembersProvider::new(
+                        &context.meta.cubes,
+                    )))
+                }
                 "columns" => {
                     return Some(Arc::new(PostgresSchemaColumnsProvider::new(
                         &context.session_state.database().unwrap_or("db".to_string()),
@@ -376,6 +388,12 @@ impl DatabaseProtocol {
                         context.sessions.clone(),
                     )))
                 }
+                "pg_stat_statements" => {
+                    return Some(Arc::new(PgCatalogStatStatementsProvider::new(
+                        context.sessions.clone(),
+                        context.session_state.clone(),
+                    )))
+                }
                 "pg_statio_user_tables" => {
                     return Some(Arc::new(PgCatalogStatioUserTablesProvider::new(
                         &context.meta.tables,
@@ -3,7 +3,9 @@ pub mod utils;
 mod filter_push_down;
 mod limit_push_down;
 mod sort_push_down;
+mod total_count_push_down;
 
 pub use filter_push_down::FilterPushDown;
 pub use limit_push_down::LimitPushDown;
 pub use sort_push_down::SortPushDown;
+pub use total_count_push_down::TotalCountPushDown;
@@ -0,0 +1,271 @@
+use std::{collections::HashMap, sync::Arc};
+
+use datafusion::{
+    error::Result,
+    logical_plan::{
+        plan::{Extension, Limit, Projection, Sort, Window},
+        Column, DFField, DFSchema, Expr, LogicalPlan,
+    },
+    physical_plan::{aggregates::AggregateFunction, windows::WindowFunction},
+    scalar::ScalarValue,
+};
+
+use super::utils::get_expr_columns;
+use crate::compile::engine::df::scan::{CubeScanNode, CubeScanOptions, MemberField};
+
+/// Column which carries total row count from CubeScan up to the original projection.
+const TOTAL_COUNT_COLUMN: &str = "__cube_total_count";
+
+/// Total Count Push Down replaces `COUNT(*) OVER ()` with the `total` flag of Cube load request,
+/// so BI tools paginating over the results don't need a separate `COUNT(*)` query.
+///
+/// Window is removed from the plan before the rewrite: this way LIMIT and OFFSET can be pushed
+/// down to Cube as usual. After the rewrite total column is attached back to the CubeScan,
+/// which is filled with the total returned by Cube. If the rewritten plan isn't a CubeScan
+/// (possibly under projections, sorts and limits), the original plan should be used instead.
+#[derive(Debug)]
+pub struct TotalCountPushDown {
+    /// Plan without the window, should be rewritten instead of the original one.
+    pub plan: LogicalPlan,
+    /// Position of the total count column in the original projection.
+    position: usize,
+    /// Total count field of the original projection.
+    field: DFField,
+}
+
+impl TotalCountPushDown {
+    /// Matches `[Limit] -> Projection -> Window(COUNT(*) OVER ())` on top of the plan.
+    pub fn extract(plan: &LogicalPlan) -> Option<Self> {
+        match plan {
+            LogicalPlan::Limit(Limit { skip, fetch, input }) => {
+                let inner = Self::extract(input)?;
+
+                Some(Self {
+                    plan: LogicalPlan::Limit(Limit {
+                        skip: *skip,
+                        fetch: *fetch,
+                        input: Arc::new(inner.plan),
+                    }),
+                    ..inner
+                })
+            }
+            LogicalPlan::Projection(Projection {
+                expr,
+                input,
+                schema,
+                alias,
+            }) => {
+                let LogicalPlan::Window(Window {
+                    input: window_input,
+                    window_expr,
+                    ..
+                }) = input.as_ref()
+                else {
+                    return None;
+                };
+                if window_expr.len() != 1
+                    || !is_total_count_expr(&window_expr[0])
+                    || plan_has_limit(window_input)
+                {
+                    return None;
+                }
+
+                let window_column = window_expr[0].name(input.schema()).ok()?;
+                let mut position = None;
+                let mut stripped_expr = vec![];
+                for (i, e) in expr.iter().enumerate() {
+                    let unaliased = match e {
+                        Expr::Alias(e, _) => e.as_ref(),
+                        e => e,
+                    };
+                    match unaliased {
+                        Expr::Column(column)
+                            if column.name == window_column && position.is_none() =>
+                        {
+                            position = Some(i);
+                        }
+                        _ if get_expr_columns(e)
+                            .iter()
+                            .any(|column| column.name == window_column) =>
+                        {
+                            return None;
+                        }
+                        _ => stripped_expr.push(e.clone()),
+                    }
+                }
+                let position = position?;
+                // Cube query should have at least one member
+                if stripped_expr.is_empty() {
+                    return None;
+                }
+
+                let stripped_fields = schema
+                    .fields()
+                    .iter()
+                    .enumerate()
+                    .filter(|(i, _)| *i != position)
+                    .map(|(_, f)| f.clone())
+                    .collect();
+
+                Some(Self {
+                    plan: LogicalPlan::Projection(Projection {
+                        expr: stripped_expr,
+                        input: window_input.clone(),
+                        schema: Arc::new(
+                            DFSchema::new_with_metadata(stripped_fields, HashMap::new()).ok()?,
+                        ),
+                        alias: alias.clone(),
+                    }),
+                    position,
+                    field: schema.field(position).clone(),
+                })
+            }
+            _ => None,
+        }
+    }
+
+    /// Attaches total count column to the rewritten plan.
+    /// Returns `None` when total can't be requested from Cube for this plan.
+    pub fn restore(&self, plan: &LogicalPlan) -> Result<Option<LogicalPlan>> {
+        let Some(input) = self.append_total_count(plan)? else {
+            return Ok(None);
+        };
+
+        let mut expr = plan
+            .schema()
+            .fields()
+            .iter()
+            .map(|f| Expr::Column(f.qualified_column()))
+            .collect::<Vec<_>>();
+        expr.insert(
+            self.position,
+            Expr::Column(Column::from_name(TOTAL_COUNT_COLUMN)).alias(self.field.name()),
+        );
+        let mut fields = plan.schema().fields().clone();
+        fields.insert(self.position, self.field.clone());
+
+        Ok(Some(LogicalPlan::Projection(Projection {
+            expr,
+            input: Arc::new(input),
+            schema: Arc::new(DFSchema::new_with_metadata(fields, HashMap::new())?),
+            alias: None,
+        })))
+    }
+
+    fn append_total_count(&self, plan: &LogicalPlan) -> Result<Option<LogicalPlan>> {
+        Ok(Some(match plan {
+            LogicalPlan::Projection(Projection {
+                expr,
+                input,
+                schema,
+                alias,
+            }) => {
+                let Some(input) = self.append_total_count(input)? else {
+                    return Ok(None);
+                };
+                let mut expr = expr.clone();
+                expr.push(Expr::Column(Column::from_name(TOTAL_COUNT_COLUMN)));
+                let mut fields = schema.fields().clone();
+                fields.push(self.total_count_field(alias.as_deref()));
+
+                LogicalPlan::Projection(Projection {
+                    expr,
+                    input: Arc::new(input),
+                    schema: Arc::new(DFSchema::new_with_metadata(fields, HashMap::new())?),
+                    alias: alias.clone(),
+                })
+            }
+            LogicalPlan::Limit(Limit { skip, fetch, input }) => {
+                let Some(input) = self.append_total_count(input)? else {
+                    return Ok(None);
+                };
+
+                LogicalPlan::Limit(Limit {
+                    skip: *skip,
+                    fetch: *fetch,
+                    input: Arc::new(input),
+                })
+            }
+            LogicalPlan::Sort(Sort { expr, input }) => {
+                let Some(input) = self.append_total_count(input)? else {
+                    return Ok(None);
+                };
+
+                LogicalPlan::Sort(Sort {
+                    expr: expr.clone(),
+                    input: Arc::new(input),
+                })
+            }
+            LogicalPlan::Extension(Extension { node }) => {
+                let Some(scan_node) = node.as_any().downcast_ref::<CubeScanNode>() else {
+                    return Ok(None);
+                };
+
+                let mut fields = scan_node.schema.fields().clone();
+                fields.push(self.total_count_field(None));
+                let mut member_fields = scan_node.member_fields.clone();
+                member_fields.push(MemberField::Literal(ScalarValue::try_from(
+                    self.field.data_type(),
+                )?));
+                let mut request = scan_node.request.clone();
+                request.total = Some(true);
+
+                LogicalPlan::Extension(Extension {
+                    node: Arc::new(CubeScanNode::new(
+                        Arc::new(DFSchema::new_with_metadata(fields, HashMap::new())?),
+                        member_fields,
+                        request,
+                        scan_node.auth_context.clone(),
+                        CubeScanOptions {
+                            total_field: Some(scan_node.member_fields.len()),
+                            ..scan_node.options.clone()
+                        },
+                        scan_node.used_cubes.clone(),
+                        scan_node.span_id.clone(),
+                    )),
+                })
+            }
+            _ => return Ok(None),
+        }))
+    }
+
+    fn total_count_field(&self, qualifier: Option<&str>) -> DFField {
+        DFField::new(
+            qualifier,
+            TOTAL_COUNT_COLUMN,
+            self.field.data_type().clone(),
+            false,
+        )
+    }
+}
+
+/// `COUNT(*) OVER ()`, possibly aliased. `COUNT(x)` skips NULL values, so it isn't a total.
+fn is_total_count_expr(expr: &Expr) -> bool {
+    match expr {
+        Expr::Alias(expr, _) => is_total_count_expr(expr),
+        Expr::WindowFunction {
+            fun: WindowFunction::AggregateFunction(AggregateFunction::Count),
+            args,
+            partition_by,
+            order_by,
+            window_frame: None,
+        } => {
+            let is_count_star = match args.as_slice() {
+                [Expr::Wildcard] => true,
+                [Expr::Literal(value)] => !value.is_null(),
+                _ => false,
+            };
+
+            is_count_star && partition_by.is_empty() && order_by.is_empty()
+        }
+        _ => false,
+    }
+}
+
+/// Cube total ignores limits, so window input must not be limited on its own.
+fn plan_has_limit(plan: &LogicalPlan) -> bool {
+    match plan {
+        LogicalPlan::Limit(_) => true,
+        plan => plan.inputs().into_iter().any(plan_has_limit),
+    }
+}
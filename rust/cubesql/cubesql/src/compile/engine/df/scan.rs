@@ -36,16 +36,19 @@ use crate::{
     },
     config::ConfigObj,
     sql::AuthContextRef,
-    transport::{CubeStreamReceiver, LoadRequestMeta, SpanId, TransportService},
+    transport::{
+        CubeStreamReceiver, LoadRequestMeta, SpanId, TransportLoadResult, TransportService,
+    },
     CubeError,
 };
 use chrono::{Datelike, NaiveDate, NaiveDateTime};
 use datafusion::{
     arrow::{
         array::{
-            IntervalDayTimeBuilder, IntervalMonthDayNanoBuilder, IntervalYearMonthBuilder,
-            TimestampMillisecondBuilder, TimestampNanosecondBuilder,
+            new_empty_array, new_null_array, IntervalDayTimeBuilder, IntervalMonthDayNanoBuilder,
+            IntervalYearMonthBuilder, TimestampMillisecondBuilder, TimestampNanosecondBuilder,
         },
+        compute::{cast, concat},
         datatypes::{IntervalUnit, TimeUnit},
     },
     execution::context::TaskContext,
@@ -64,6 +67,8 @@ pub enum MemberField {
 pub struct CubeScanOptions {
     pub change_user: Option<String>,
     pub max_records: Option<usize>,
+    /// Index of the field which should be filled with total row count returned by Cube
+    pub total_field: Option<usize>,
 }
 
 #[derive(Debug, Clone)]
@@ -610,6 +615,8 @@ impl ExecutionPlan for CubeScanExecutionPlan {
         let query_limit = self.config_obj.non_streaming_query_max_row_limit();
 
         let stream_mode = match (stream_mode, self.request.limit) {
+            // Total is returned with the whole response only
+            _ if self.options.total_field.is_some() => false,
             (true, None) => true,
             (true, Some(limit)) if limit > query_limit => true,
             (_, _) => false,
@@ -658,27 +665,19 @@ impl ExecutionPlan for CubeScanExecutionPlan {
             )));
         }
 
-        let mut response = JsonValueObject::new(
-            load_data(
-                self.span_id.clone(),
-                request,
-                self.auth_context.clone(),
-                self.transport.clone(),
-                meta.clone(),
-                self.options.clone(),
-                self.wrapped_sql.clone(),
-            )
-            .await?
-            .data,
-        );
-        one_shot_stream.data = Some(
-            transform_response(
-                &mut response,
-                one_shot_stream.schema.clone(),
-                &one_shot_stream.member_fields,
-            )
-            .map_err(|e| DataFusionError::Execution(e.message.to_string()))?,
-        );
+        let batch = load_data(
+            self.span_id.clone(),
+            request,
+            self.auth_context.clone(),
+            self.transport.clone(),
+            meta.clone(),
+            one_shot_stream.schema.clone(),
+            one_shot_stream.member_fields.clone(),
+            self.options.clone(),
+            self.wrapped_sql.clone(),
+        )
+        .await?;
+        one_shot_stream.data = Some(batch);
 
         Ok(Box::pin(CubeScanStreamRouter::new(
             None,
@@ -841,15 +840,18 @@ impl RecordBatchStream for CubeScanStreamRouter {
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn load_data(
     span_id: Option<Arc<SpanId>>,
     request: V1LoadRequestQuery,
     auth_context: AuthContextRef,
     transport: Arc<dyn TransportService>,
     meta: LoadRequestMeta,
+    schema: SchemaRef,
+    member_fields: Vec<MemberField>,
     options: CubeScanOptions,
     sql_query: Option<SqlQuery>,
-) -> ArrowResult<V1LoadResult> {
+) -> Result<RecordBatch> {
     let no_members_query = request.measures.as_ref().map(|v| v.len()).unwrap_or(0) == 0
         && request.dimensions.as_ref().map(|v| v.len()).unwrap_or(0) == 0
         && request
@@ -859,7 +861,7 @@ async fn load_data(
             .unwrap_or(0)
             == 0;
 
-    let result = if no_members_query {
+    let (batch, total) = if no_members_query {
         let limit = request.limit.unwrap_or(1);
         let mut data = Vec::new();
 
@@ -867,7 +869,7 @@ async fn load_data(
             data.push(serde_json::Value::Null)
         }
 
-        V1LoadResult::new(
+        let result = V1LoadResult::new(
             V1LoadResultAnnotation {
                 measures: json!(Vec::<serde_json::Value>::new()),
                 dimensions: json!(Vec::<serde_json::Value>::new()),
@@ -875,29 +877,55 @@ async fn load_data(
                 time_dimensions: json!(Vec::<serde_json::Value>::new()),
             },
             data,
-        )
+        );
+
+        let mut response = JsonValueObject::new(result.data);
+        let batch = transform_response(&mut response, schema, &member_fields)
+            .map_err(|e| DataFusionError::Execution(e.message.to_string()))?;
+
+        (batch, result.total)
     } else {
         let result = transport
-            .load(span_id, request, sql_query, auth_context, meta)
+            .load_result(span_id, request, sql_query, auth_context, meta)
             .await;
-        let mut response = result.map_err(|err| ArrowError::ComputeError(err.to_string()))?;
-        if let Some(data) = response.results.pop() {
-            match (options.max_records, data.data.len()) {
-                (Some(max_records), len) if len >= max_records => {
-                    return Err(ArrowError::ComputeError(format!("One of the Cube queries exceeded the maximum row limit ({}). JOIN/UNION is not possible as it will produce incorrect results. Try filtering the results more precisely or moving post-processing functions to an outer query.", max_records)));
-                }
-                (_, _) => (),
+        let result = result.map_err(|err| ArrowError::ComputeError(err.to_string()))?;
+        match result {
+            TransportLoadResult::Json(mut response) => {
+                let Some(data) = response.results.pop() else {
+                    return Err(ArrowError::ComputeError(
+                        "Unable to extract results from response: results is empty".to_string(),
+                    )
+                    .into());
+                };
+                check_max_records(&options, data.data.len())?;
+
+                let mut response = JsonValueObject::new(data.data);
+                let batch = transform_response(&mut response, schema, &member_fields)
+                    .map_err(|e| DataFusionError::Execution(e.message.to_string()))?;
+
+                (batch, data.total)
             }
+            TransportLoadResult::Arrow { batches, total } => {
+                check_max_records(&options, batches.iter().map(|b| b.num_rows()).sum())?;
 
-            data
-        } else {
-            return Err(ArrowError::ComputeError(
-                "Unable to extract results from response: results is empty".to_string(),
-            ));
+                let batch = transform_record_batches(&batches, schema, &member_fields)
+                    .map_err(|e| DataFusionError::Execution(e.message.to_string()))?;
+
+                (batch, total)
+            }
         }
     };
 
-    Ok(result)
+    fill_total_field(batch, &options, total)
+}
+
+fn check_max_records(options: &CubeScanOptions, len: usize) -> ArrowResult<()> {
+    match (options.max_records, len) {
+        (Some(max_records), len) if len >= max_records => {
+            Err(ArrowError::ComputeError(format!("One of the Cube queries exceeded the maximum row limit ({}). JOIN/UNION is not possible as it will produce incorrect results. Try filtering the results more precisely or moving post-processing functions to an outer query.", max_records)))
+        }
+        (_, _) => Ok(()),
+    }
 }
 
 fn load_to_stream_sync(one_shot_stream: &mut CubeScanOneShotStream) -> Result<()> {
@@ -906,6 +934,8 @@ fn load_to_stream_sync(one_shot_stream: &mut CubeScanOneShotStream) -> Result<()
     let auth = one_shot_stream.auth_context.clone();
     let transport = one_shot_stream.transport.clone();
     let meta = one_shot_stream.meta.clone();
+    let schema = one_shot_stream.schema.clone();
+    let member_fields = one_shot_stream.member_fields.clone();
     let options = one_shot_stream.options.clone();
     let wrapped_sql = one_shot_stream.wrapped_sql.clone();
 
@@ -917,6 +947,8 @@ fn load_to_stream_sync(one_shot_stream: &mut CubeScanOneShotStream) -> Result<()
             auth,
             transport,
             meta,
+            schema,
+            member_fields,
             options,
             wrapped_sql,
         ))
@@ -924,19 +956,36 @@ fn load_to_stream_sync(one_shot_stream: &mut CubeScanOneShotStream) -> Result<()
     .join()
     .map_err(|_| DataFusionError::Execution(format!("Can't load to stream")))?;
 
-    let mut response = JsonValueObject::new(res.unwrap().data);
-    one_shot_stream.data = Some(
-        transform_response(
-            &mut response,
-            one_shot_stream.schema.clone(),
-            &one_shot_stream.member_fields,
-        )
-        .map_err(|e| DataFusionError::Execution(e.message.to_string()))?,
-    );
+    one_shot_stream.data = Some(res?);
 
     Ok(())
 }
 
+/// Fills total count column requested by `COUNT(*) OVER ()` with total returned by Cube.
+fn fill_total_field(
+    batch: RecordBatch,
+    options: &CubeScanOptions,
+    total: Option<i64>,
+) -> Result<RecordBatch> {
+    let Some(index) = options.total_field else {
+        return Ok(batch);
+    };
+    let total = total.ok_or_else(|| {
+        DataFusionError::Execution(
+            "Unable to extract total from response: total is not set".to_string(),
+        )
+    })?;
+
+    let schema = batch.schema();
+    let mut columns = batch.columns().to_vec();
+    columns[index] = cast(
+        &ScalarValue::Int64(Some(total)).to_array_of_size(batch.num_rows()),
+        schema.field(index).data_type(),
+    )?;
+
+    Ok(RecordBatch::try_new(schema, columns)?)
+}
+
 pub fn transform_response<V: ValueObject>(
     response: &mut V,
     schema: SchemaRef,
@@ -1338,6 +1387,58 @@ pub fn transform_response<V: ValueObject>(
     Ok(RecordBatch::try_new(schema.clone(), columns)?)
 }
 
+/// Maps Arrow batches returned by Cube (columns named by member) to the CubeScan schema.
+/// Columns are cast to the expected types, literals are expanded and batches are merged into one.
+pub fn transform_record_batches(
+    batches: &[RecordBatch],
+    schema: SchemaRef,
+    member_fields: &Vec<MemberField>,
+) -> std::result::Result<RecordBatch, CubeError> {
+    let mut columns = vec![];
+
+    for (i, schema_field) in schema.fields().iter().enumerate() {
+        let data_type = schema_field.data_type();
+        let mut chunks = vec![];
+
+        for batch in batches {
+            let chunk = match &member_fields[i] {
+                MemberField::Member(field_name) => {
+                    match batch.schema().column_with_name(field_name) {
+                        Some((index, _)) => batch.column(index).clone(),
+                        None => new_null_array(data_type, batch.num_rows()),
+                    }
+                }
+                MemberField::Literal(value) => value.to_array_of_size(batch.num_rows()),
+            };
+
+            let chunk = if chunk.data_type() == data_type {
+                chunk
+            } else {
+                cast(&chunk, data_type).map_err(|e| {
+                    CubeError::user(format!(
+                        "Unable to map column {} to {:?}: {}",
+                        schema_field.name(),
+                        data_type,
+                        e
+                    ))
+                })?
+            };
+
+            chunks.push(chunk);
+        }
+
+        let column = match chunks.len() {
+            0 => new_empty_array(data_type),
+            1 => chunks.remove(0),
+            _ => concat(&chunks.iter().map(|c| c.as_ref()).collect::<Vec<_>>())?,
+        };
+
+        columns.push(column);
+    }
+
+    Ok(RecordBatch::try_new(schema.clone(), columns)?)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1350,7 +1451,9 @@ mod tests {
     use cubeclient::models::V1LoadResponse;
     use datafusion::{
         arrow::{
-            array::{BooleanArray, Float64Array, StringArray, TimestampNanosecondArray},
+            array::{
+                BooleanArray, Float64Array, Int64Array, StringArray, TimestampNanosecondArray,
+            },
             datatypes::{Field, Schema},
         },
         execution::{
@@ -1529,6 +1632,7 @@ mod tests {
             options: CubeScanOptions {
                 change_user: None,
                 max_records: None,
+                total_field: None,
             },
             transport: get_test_transport(),
             meta: get_test_load_meta(DatabaseProtocol::PostgreSQL),
@@ -1603,4 +1707,71 @@ mod tests {
             .unwrap()
         )
     }
+
+    #[test]
+    fn test_transform_record_batches() {
+        let response_schema = Arc::new(Schema::new(vec![
+            Field::new("KibanaSampleDataEcommerce.count", DataType::Int64, true),
+            Field::new("KibanaSampleDataEcommerce.city", DataType::Utf8, true),
+        ]));
+        let batches = vec![
+            RecordBatch::try_new(
+                response_schema.clone(),
+                vec![
+                    Arc::new(Int64Array::from(vec![Some(1), None])) as ArrayRef,
+                    Arc::new(StringArray::from(vec![Some("City 1"), Some("City 2")])) as ArrayRef,
+                ],
+            )
+            .unwrap(),
+            RecordBatch::try_new(
+                response_schema,
+                vec![
+                    Arc::new(Int64Array::from(vec![Some(3)])) as ArrayRef,
+                    Arc::new(StringArray::from(vec![None as Option<&str>])) as ArrayRef,
+                ],
+            )
+            .unwrap(),
+        ];
+
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("KibanaSampleDataEcommerce.count", DataType::Float64, true),
+            Field::new("KibanaSampleDataEcommerce.city", DataType::Utf8, true),
+            Field::new(
+                "KibanaSampleDataEcommerce.is_female",
+                DataType::Boolean,
+                true,
+            ),
+            Field::new(
+                "KibanaSampleDataEcommerce.maxPrice",
+                DataType::Float64,
+                true,
+            ),
+        ]));
+        let member_fields = vec![
+            MemberField::Member("KibanaSampleDataEcommerce.count".to_string()),
+            MemberField::Member("KibanaSampleDataEcommerce.city".to_string()),
+            MemberField::Literal(ScalarValue::Boolean(Some(true))),
+            MemberField::Member("KibanaSampleDataEcommerce.maxPrice".to_string()),
+        ];
+
+        let batch = transform_record_batches(&batches, schema.clone(), &member_fields).unwrap();
+
+        assert_eq!(
+            batch,
+            RecordBatch::try_new(
+                schema,
+                vec![
+                    Arc::new(Float64Array::from(vec![Some(1.0), None, Some(3.0)])) as ArrayRef,
+                    Arc::new(StringArray::from(vec![
+                        Some("City 1"),
+                        Some("City 2"),
+                        None
+                    ])) as ArrayRef,
+                    Arc::new(BooleanArray::from(vec![true, true, true])) as ArrayRef,
+                    Arc::new(Float64Array::from(vec![None as Option<f64>, None, None])) as ArrayRef,
+                ],
+            )
+            .unwrap()
+        )
+    }
 }
@@ -0,0 +1,191 @@
+use std::{any::Any, sync::Arc};
+
+use async_trait::async_trait;
+use datafusion::{
+    arrow::{
+        array::{Array, ArrayRef, StringBuilder},
+        datatypes::{DataType, Field, Schema, SchemaRef},
+        record_batch::RecordBatch,
+    },
+    datasource::{datasource::TableProviderFilterPushDown, TableProvider, TableType},
+    error::DataFusionError,
+    logical_plan::Expr,
+    physical_plan::{memory::MemoryExec, ExecutionPlan},
+};
+
+use crate::{
+    compile::engine::context::TableName,
+    transport::{CubeMember, CubeMeta, V1CubeMetaExt},
+};
+
+struct InfoSchemaCubeMembersBuilder {
+    cube_name: StringBuilder,
+    column_name: StringBuilder,
+    member_name: StringBuilder,
+    kind: StringBuilder,
+    member_type: StringBuilder,
+    agg_type: StringBuilder,
+    format: StringBuilder,
+    title: StringBuilder,
+    description: StringBuilder,
+    alias_member: StringBuilder,
+    granularities: StringBuilder,
+    meta: StringBuilder,
+    comment: StringBuilder,
+}
+
+impl InfoSchemaCubeMembersBuilder {
+    fn new(capacity: usize) -> Self {
+        Self {
+            cube_name: StringBuilder::new(capacity),
+            column_name: StringBuilder::new(capacity),
+            member_name: StringBuilder::new(capacity),
+            kind: StringBuilder::new(capacity),
+            member_type: StringBuilder::new(capacity),
+            agg_type: StringBuilder::new(capacity),
+            format: StringBuilder::new(capacity),
+            title: StringBuilder::new(capacity),
+            description: StringBuilder::new(capacity),
+            alias_member: StringBuilder::new(capacity),
+            granularities: StringBuilder::new(capacity),
+            meta: StringBuilder::new(capacity),
+            comment: StringBuilder::new(capacity),
+        }
+    }
+
+    fn add_member(&mut self, cube_name: impl AsRef<str>, member: CubeMember) {
+        self.cube_name.append_value(cube_name).unwrap();
+        self.column_name.append_value(&member.name).unwrap();
+        self.member_name.append_value(&member.member_name).unwrap();
+        self.kind.append_value(member.kind.as_str()).unwrap();
+        self.member_type.append_value(&member.r#type).unwrap();
+        self.agg_type
+            .append_option(member.agg_type.as_ref())
+            .unwrap();
+        self.format.append_option(member.format.as_ref()).unwrap();
+        self.title.append_option(member.title.as_ref()).unwrap();
+        self.description
+            .append_option(member.description.as_ref())
+            .unwrap();
+        self.alias_member
+            .append_option(member.alias_member.as_ref())
+            .unwrap();
+        if member.granularities.is_empty() {
+            self.granularities.append_null().unwrap();
+        } else {
+            self.granularities
+                .append_value(member.granularities.join(","))
+                .unwrap();
+        }
+        self.meta
+            .append_option(member.meta.as_ref().map(|meta| meta.to_string()))
+            .unwrap();
+        self.comment.append_option(member.comment()).unwrap();
+    }
+
+    fn finish(mut self) -> Vec<Arc<dyn Array>> {
+        let columns: Vec<Arc<dyn Array>> = vec![
+            Arc::new(self.cube_name.finish()),
+            Arc::new(self.column_name.finish()),
+            Arc::new(self.member_name.finish()),
+            Arc::new(self.kind.finish()),
+            Arc::new(self.member_type.finish()),
+            Arc::new(self.agg_type.finish()),
+            Arc::new(self.format.finish()),
+            Arc::new(self.title.finish()),
+            Arc::new(self.description.finish()),
+            Arc::new(self.alias_member.finish()),
+            Arc::new(self.granularities.finish()),
+            Arc::new(self.meta.finish()),
+            Arc::new(self.comment.finish()),
+        ];
+
+        columns
+    }
+}
+
+/// Cube specific view, which lists all measures, dimensions and segments with their metadata.
+pub struct InfoSchemaCubeMembersProvider {
+    data: Arc<Vec<ArrayRef>>,
+}
+
+impl TableName for InfoSchemaCubeMembersProvider {
+    fn table_name(&self) -> &str {
+        "information_schema.cube_members"
+    }
+}
+
+impl InfoSchemaCubeMembersProvider {
+    pub fn new(cubes: &Vec<CubeMeta>) -> Self {
+        let members = cubes
+            .iter()
+            .map(|cube| (&cube.name, cube.get_members()))
+            .collect::<Vec<_>>();
+        let capacity = members.iter().map(|(_, members)| members.len()).sum();
+
+        let mut builder = InfoSchemaCubeMembersBuilder::new(capacity);
+
+        for (cube_name, members) in members {
+            for member in members {
+                builder.add_member(cube_name, member);
+            }
+        }
+
+        Self {
+            data: Arc::new(builder.finish()),
+        }
+    }
+}
+
+#[async_trait]
+impl TableProvider for InfoSchemaCubeMembersProvider {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn table_type(&self) -> TableType {
+        TableType::View
+    }
+
+    fn schema(&self) -> SchemaRef {
+        Arc::new(Schema::new(vec![
+            Field::new("cube_name", DataType::Utf8, false),
+            Field::new("column_name", DataType::Utf8, false),
+            Field::new("member_name", DataType::Utf8, false),
+            // measure, dimension or segment
+            Field::new("kind", DataType::Utf8, false),
+            Field::new("type", DataType::Utf8, false),
+            Field::new("agg_type", DataType::Utf8, true),
+            Field::new("format", DataType::Utf8, true),
+            Field::new("title", DataType::Utf8, true),
+            Field::new("description", DataType::Utf8, true),
+            Field::new("alias_member", DataType::Utf8, true),
+            Field::new("granularities", DataType::Utf8, true),
+            // JSON encoded
+            Field::new("meta", DataType::Utf8, true),
+            Field::new("comment", DataType::Utf8, true),
+        ]))
+    }
+
+    async fn scan(
+        &self,
+        projection: &Option<Vec<usize>>,
+        _filters: &[Expr],
+        _limit: Option<usize>,
+    ) -> Result<Arc<dyn ExecutionPlan>, DataFusionError> {
+        let batch = RecordBatch::try_new(self.schema(), self.data.to_vec())?;
+
+        Ok(Arc::new(MemoryExec::try_new(
+            &[vec![batch]],
+            self.schema(),
+            projection.clone(),
+        )?))
+    }
+
+    fn supports_filter_pushdown(
+        &self,
+        _filter: &Expr,
+    ) -> Result<TableProviderFilterPushDown, DataFusionError> {
+        Ok(TableProviderFilterPushDown::Unsupported)
+    }
+}
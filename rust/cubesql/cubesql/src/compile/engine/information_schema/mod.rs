@@ -1,3 +1,4 @@
+pub mod cube_members;
 pub mod mysql;
 pub mod postgres;
 pub mod redshift;
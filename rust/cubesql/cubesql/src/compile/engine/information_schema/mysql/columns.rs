@@ -33,6 +33,7 @@ struct InformationSchemaColumnsBuilder {
     numeric_scale: UInt32Builder,
     numeric_precision: UInt32Builder,
     datetime_precision: UInt32Builder,
+    column_comment: StringBuilder,
 }
 
 impl InformationSchemaColumnsBuilder {
@@ -54,6 +55,7 @@ impl InformationSchemaColumnsBuilder {
             numeric_precision: UInt32Builder::new(capacity),
             numeric_scale: UInt32Builder::new(capacity),
             datetime_precision: UInt32Builder::new(capacity),
+            column_comment: StringBuilder::new(capacity),
         }
     }
 
@@ -95,6 +97,9 @@ impl InformationSchemaColumnsBuilder {
         self.numeric_precision.append_null().unwrap();
         self.numeric_scale.append_null().unwrap();
         self.datetime_precision.append_null().unwrap();
+        self.column_comment
+            .append_value(column.get_description().as_deref().unwrap_or(""))
+            .unwrap();
     }
 
     fn finish(mut self) -> Vec<Arc<dyn Array>> {
@@ -127,11 +132,7 @@ impl InformationSchemaColumnsBuilder {
             total,
             Some("".to_string()),
         )));
-        // COLUMN_COMMENT
-        columns.push(Arc::new(new_string_array_with_placeholder(
-            total,
-            Some("".to_string()),
-        )));
+        columns.push(Arc::new(self.column_comment.finish()));
         // GENERATION_EXPRESSION
         columns.push(Arc::new(new_string_array_with_placeholder(
             total,
@@ -35,6 +35,7 @@ mod pg_roles;
 mod pg_sequence;
 mod pg_settings;
 mod pg_stat_activity;
+mod pg_stat_statements;
 mod pg_stat_user_tables;
 mod pg_statio_user_tables;
 mod pg_stats;
@@ -70,6 +71,7 @@ pub use pg_roles::*;
 pub use pg_sequence::*;
 pub use pg_settings::*;
 pub use pg_stat_activity::*;
+pub use pg_stat_statements::*;
 pub use pg_stat_user_tables::*;
 pub use pg_statio_user_tables::*;
 pub use pg_stats::*;
@@ -0,0 +1,174 @@
+use std::{any::Any, sync::Arc};
+
+use async_trait::async_trait;
+
+use crate::sql::{QueryStatsEntry, SessionManager, SessionState};
+use datafusion::{
+    arrow::{
+        array::{Array, Float64Builder, Int64Builder, StringBuilder},
+        datatypes::{DataType, Field, Schema, SchemaRef},
+        record_batch::RecordBatch,
+    },
+    datasource::{datasource::TableProviderFilterPushDown, TableProvider, TableType},
+    error::DataFusionError,
+    logical_plan::Expr,
+    physical_plan::{memory::MemoryExec, ExecutionPlan},
+};
+
+struct PgStatStatementsBuilder {
+    queryid: Int64Builder,
+    query: StringBuilder,
+    calls: Int64Builder,
+    total_exec_time: Float64Builder,
+    mean_exec_time: Float64Builder,
+    max_exec_time: Float64Builder,
+    rows: Int64Builder,
+    total_rewrite_time: Float64Builder,
+    errors: Int64Builder,
+    rewrite_errors: Int64Builder,
+    post_processing_calls: Int64Builder,
+}
+
+impl PgStatStatementsBuilder {
+    fn new(capacity: usize) -> Self {
+        Self {
+            queryid: Int64Builder::new(capacity),
+            query: StringBuilder::new(capacity),
+            calls: Int64Builder::new(capacity),
+            total_exec_time: Float64Builder::new(capacity),
+            mean_exec_time: Float64Builder::new(capacity),
+            max_exec_time: Float64Builder::new(capacity),
+            rows: Int64Builder::new(capacity),
+            total_rewrite_time: Float64Builder::new(capacity),
+            errors: Int64Builder::new(capacity),
+            rewrite_errors: Int64Builder::new(capacity),
+            post_processing_calls: Int64Builder::new(capacity),
+        }
+    }
+
+    fn add_entry(&mut self, entry: QueryStatsEntry, visible: bool) {
+        // Times are reported in milliseconds, same as in PostgreSQL
+        self.queryid.append_value(entry.query_id).unwrap();
+        if visible {
+            self.query.append_value(&entry.query).unwrap();
+        } else {
+            self.query.append_value("<insufficient privilege>").unwrap();
+        }
+        self.calls.append_value(entry.calls as i64).unwrap();
+        self.total_exec_time
+            .append_value(entry.total_time.as_secs_f64() * 1000.0)
+            .unwrap();
+        self.mean_exec_time
+            .append_value(entry.mean_time().as_secs_f64() * 1000.0)
+            .unwrap();
+        self.max_exec_time
+            .append_value(entry.max_time.as_secs_f64() * 1000.0)
+            .unwrap();
+        self.rows.append_value(entry.rows as i64).unwrap();
+        self.total_rewrite_time
+            .append_value(entry.rewrite_time.as_secs_f64() * 1000.0)
+            .unwrap();
+        self.errors.append_value(entry.errors as i64).unwrap();
+        self.rewrite_errors
+            .append_value(entry.rewrite_errors as i64)
+            .unwrap();
+        self.post_processing_calls
+            .append_value(entry.post_processing_calls as i64)
+            .unwrap();
+    }
+
+    fn finish(mut self) -> Vec<Arc<dyn Array>> {
+        let columns: Vec<Arc<dyn Array>> = vec![
+            Arc::new(self.queryid.finish()),
+            Arc::new(self.query.finish()),
+            Arc::new(self.calls.finish()),
+            Arc::new(self.total_exec_time.finish()),
+            Arc::new(self.mean_exec_time.finish()),
+            Arc::new(self.max_exec_time.finish()),
+            Arc::new(self.rows.finish()),
+            Arc::new(self.total_rewrite_time.finish()),
+            Arc::new(self.errors.finish()),
+            Arc::new(self.rewrite_errors.finish()),
+            Arc::new(self.post_processing_calls.finish()),
+        ];
+
+        columns
+    }
+}
+
+pub struct PgCatalogStatStatementsProvider {
+    sessions: Arc<SessionManager>,
+    session_state: Arc<SessionState>,
+}
+
+impl PgCatalogStatStatementsProvider {
+    pub fn new(sessions: Arc<SessionManager>, session_state: Arc<SessionState>) -> Self {
+        Self {
+            sessions,
+            session_state,
+        }
+    }
+}
+
+#[async_trait]
+impl TableProvider for PgCatalogStatStatementsProvider {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn table_type(&self) -> TableType {
+        TableType::View
+    }
+
+    fn schema(&self) -> SchemaRef {
+        Arc::new(Schema::new(vec![
+            Field::new("queryid", DataType::Int64, false),
+            Field::new("query", DataType::Utf8, false),
+            Field::new("calls", DataType::Int64, false),
+            Field::new("total_exec_time", DataType::Float64, false),
+            Field::new("mean_exec_time", DataType::Float64, false),
+            Field::new("max_exec_time", DataType::Float64, false),
+            Field::new("rows", DataType::Int64, false),
+            // Cube specific columns
+            Field::new("total_rewrite_time", DataType::Float64, false),
+            Field::new("errors", DataType::Int64, false),
+            Field::new("rewrite_errors", DataType::Int64, false),
+            Field::new("post_processing_calls", DataType::Int64, false),
+        ]))
+    }
+
+    async fn scan(
+        &self,
+        projection: &Option<Vec<usize>>,
+        _filters: &[Expr],
+        _limit: Option<usize>,
+    ) -> Result<Arc<dyn ExecutionPlan>, DataFusionError> {
+        let entries = self.sessions.server.query_stats.entries();
+        let mut builder = PgStatStatementsBuilder::new(entries.len());
+
+        // Same as in PostgreSQL, text of statements executed by other users is visible only to
+        // superusers
+        let superuser = self.session_state.is_superuser();
+        let user = self.session_state.user();
+
+        for entry in entries {
+            let visible = superuser || entry.user == user;
+            builder.add_entry(entry, visible)
+        }
+
+        let batch = RecordBatch::try_new(self.schema(), builder.finish())?;
+
+        Ok(Arc::new(MemoryExec::try_new(
+            &[vec![batch]],
+            self.schema(),
+            projection.clone(),
+        )?))
+    }
+
+    fn supports_filter_pushdown(
+        &self,
+        _filter: &Expr,
+    ) -> Result<TableProviderFilterPushDown, DataFusionError> {
+        Ok(TableProviderFilterPushDown::Unsupported)
+    }
+}
@@ -4,7 +4,8 @@ use std::{
     thread,
 };
 
-use chrono::{Datelike, Days, Duration, Months, NaiveDate, NaiveDateTime, NaiveTime};
+use chrono::{Datelike, Days, Duration, Months, NaiveDate, NaiveDateTime, NaiveTime, TimeZone};
+use chrono_tz::Tz;
 use datafusion::{
     arrow::{
         array::{
@@ -48,7 +49,7 @@ use crate::{
         information_schema::postgres::{PG_NAMESPACE_CATALOG_OID, PG_NAMESPACE_PUBLIC_OID},
         udf::utils::*,
     },
-    sql::SessionState,
+    sql::{parse_timezone, QueryStatsStore, SessionState},
 };
 
 type IntervalDayTime = <IntervalDayTimeType as ArrowPrimitiveType>::Native;
@@ -182,6 +183,37 @@ pub fn create_connection_id_udf(state: Arc<SessionState>) -> ScalarUDF {
     )
 }
 
+pub fn create_pg_stat_statements_reset_udf(
+    state: Arc<SessionState>,
+    query_stats: Arc<QueryStatsStore>,
+) -> ScalarUDF {
+    let fun = make_scalar_function(move |_args: &[ArrayRef]| {
+        // Same as in PostgreSQL, only superusers can reset statistics
+        if !state.is_superuser() {
+            return Err(DataFusionError::Execution(
+                "permission denied for function pg_stat_statements_reset".to_string(),
+            ));
+        }
+
+        let reset_at = query_stats
+            .reset()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_err(|e| DataFusionError::Execution(e.to_string()))?;
+
+        Ok(Arc::new(TimestampNanosecondArray::from(vec![
+            reset_at.as_nanos() as i64
+        ])) as ArrayRef)
+    });
+
+    create_udf(
+        "pg_stat_statements_reset",
+        vec![],
+        Arc::new(DataType::Timestamp(TimeUnit::Nanosecond, None)),
+        Volatility::Volatile,
+        fun,
+    )
+}
+
 pub fn create_pg_backend_pid_udf(state: Arc<SessionState>) -> ScalarUDF {
     let fun = make_scalar_function(move |_args: &[ArrayRef]| {
         let mut builder = UInt32Builder::new(1);
@@ -773,7 +805,10 @@ pub fn create_greatest_udf() -> ScalarUDF {
 }
 
 // CONVERT_TZ() converts a datetime value dt from the time zone given by from_tz to the time zone given by to_tz and returns the resulting value.
-pub fn create_convert_tz_udf() -> ScalarUDF {
+// SYSTEM stands for the session time zone, because Cube returns date times in it.
+pub fn create_convert_tz_udf(state: Arc<SessionState>) -> ScalarUDF {
+    let session_tz = state.timezone().unwrap_or(Tz::UTC);
+
     let fun = make_scalar_function(move |args: &[ArrayRef]| {
         assert!(args.len() == 3);
 
@@ -781,12 +816,12 @@ pub fn create_convert_tz_udf() -> ScalarUDF {
         let from_tz = &args[1];
         let to_tz = &args[2];
 
-        let (_, input_tz) = match input_dt.data_type() {
+        let (input_unit, input_tz) = match input_dt.data_type() {
             DataType::Timestamp(unit, tz) => (unit, tz),
             _ => {
                 return Err(DataFusionError::Execution(format!(
                     "dt argument must be a Timestamp, actual: {}",
-                    from_tz.data_type()
+                    input_dt.data_type()
                 )));
             }
         };
@@ -805,15 +840,6 @@ pub fn create_convert_tz_udf() -> ScalarUDF {
             )));
         };
 
-        let from_tz = downcast_string_arg!(&from_tz, "from_tz", i32);
-        let to_tz = downcast_string_arg!(&to_tz, "to_tz", i32);
-
-        if from_tz.value(0) != "SYSTEM" || to_tz.value(0) != "+00:00" {
-            return Err(DataFusionError::NotImplemented(format!(
-                "convert_tz is not implemented, it's stub"
-            )));
-        }
-
         if let Some(tz) = input_tz {
             if tz != &"UTC" {
                 return Err(DataFusionError::NotImplemented(format!(
@@ -823,7 +849,48 @@ pub fn create_convert_tz_udf() -> ScalarUDF {
             };
         };
 
-        Ok(input_dt.clone())
+        let from_tz = downcast_string_arg!(&from_tz, "from_tz", i32);
+        let to_tz = downcast_string_arg!(&to_tz, "to_tz", i32);
+
+        let input_ns = cast(input_dt, &DataType::Timestamp(TimeUnit::Nanosecond, None))?;
+        let input_ns = downcast_primitive_arg!(&input_ns, "dt", TimestampNanosecondType);
+
+        let parse_tz = |name: &str| {
+            if name.eq_ignore_ascii_case("SYSTEM") {
+                Some(session_tz)
+            } else {
+                parse_timezone(name)
+            }
+        };
+
+        let mut builder = TimestampNanosecondArray::builder(input_ns.len());
+        for i in 0..input_ns.len() {
+            if input_ns.is_null(i) || from_tz.is_null(i) || to_tz.is_null(i) {
+                builder.append_null()?;
+                continue;
+            }
+
+            // Same as MySQL, unknown time zones and nonexistent local times produce NULL
+            let converted = match (parse_tz(from_tz.value(i)), parse_tz(to_tz.value(i))) {
+                (Some(from), Some(to)) => input_ns
+                    .value_as_datetime(i)
+                    .and_then(|dt| from.from_local_datetime(&dt).earliest())
+                    .and_then(|dt| dt.with_timezone(&to).naive_local().timestamp_nanos_opt()),
+                _ => None,
+            };
+
+            match converted {
+                Some(value) => builder.append_value(value)?,
+                None => builder.append_null()?,
+            }
+        }
+
+        let result = Arc::new(builder.finish()) as ArrayRef;
+
+        Ok(cast(
+            &result,
+            &DataType::Timestamp(input_unit.clone(), input_tz.clone()),
+        )?)
     });
 
     let return_type: ReturnTypeFunction = Arc::new(move |types| {
@@ -1620,7 +1687,9 @@ macro_rules! parse_timestamp_arr {
     }};
 }
 
-pub fn create_to_char_udf() -> ScalarUDF {
+pub fn create_to_char_udf(state: Arc<SessionState>) -> ScalarUDF {
+    let session_tz = state.timezone();
+
     let fun: Arc<dyn Fn(&[ColumnarValue]) -> Result<ColumnarValue> + Send + Sync> =
         make_scalar_function(move |args: &[ArrayRef]| {
             let arr = &args[0];
@@ -1658,6 +1727,13 @@ pub fn create_to_char_udf() -> ScalarUDF {
             let durations = durations.unwrap();
             let formats = downcast_string_arg!(&args[1], "format_str", i32);
 
+            // Timestamps with time zone are rendered in the session time zone, same as Postgres
+            // does. Timestamps without time zone are already local, as Cube returns them so.
+            let (shift_to_session_tz, timezone) = match session_tz {
+                Some(tz) => (!timezone.is_empty(), tz.name().to_string()),
+                None => (false, timezone),
+            };
+
             let mut builder = StringBuilder::new(durations.len());
 
             for (i, duration) in durations.iter().enumerate() {
@@ -1672,6 +1748,12 @@ pub fn create_to_char_udf() -> ScalarUDF {
                     let nanosecs = duration.num_nanoseconds().unwrap_or(0) - secs * 1_000_000_000;
                     let timestamp = NaiveDateTime::from_timestamp_opt(secs, nanosecs as u32)
                         .expect(format!("Invalid secs {} nanosecs {}", secs, nanosecs).as_str());
+                    let timestamp = match session_tz {
+                        Some(tz) if shift_to_session_tz => {
+                            tz.from_utc_datetime(&timestamp).naive_local()
+                        }
+                        _ => timestamp,
+                    };
 
                     // chrono's strftime is missing quarter format, as such a workaround is required
                     let quarter = &format!("{}", timestamp.date().month0() / 3 + 1);
@@ -40,6 +40,8 @@ mod tests {
             rewrite::rewriter::Rewriter,
             test::{get_sixteen_char_member_cube, get_string_cube_meta},
         },
+        config::ConfigObjImpl,
+        sql::{AuthContext, QueryExecutionStats, QueryFingerprint},
         CubeError,
     };
     use chrono::Datelike;
@@ -52,7 +54,7 @@ mod tests {
     use pretty_assertions::assert_eq;
     use regex::Regex;
     use serde_json::json;
-    use std::env;
+    use std::{env, sync::Arc, time::Duration};
 
     use crate::compile::test::{
         convert_select_to_query_plan, convert_select_to_query_plan_customized,
@@ -4213,6 +4215,20 @@ limit
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_information_schema_cube_members() -> Result<(), CubeError> {
+        insta::assert_snapshot!(
+            "information_schema_cube_members",
+            execute_query(
+                "SELECT column_name, kind, agg_type, format, granularities, comment FROM information_schema.cube_members WHERE cube_name = 'KibanaSampleDataEcommerce' AND column_name IN ('count', 'customer_gender', 'is_male') ORDER BY column_name".to_string(),
+                DatabaseProtocol::PostgreSQL
+            )
+            .await?
+        );
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_information_schema_schemata() -> Result<(), CubeError> {
         insta::assert_snapshot!(
@@ -5693,6 +5709,124 @@ ORDER BY
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_pgcatalog_pg_stat_statements_postgres() -> Result<(), CubeError> {
+        insta::assert_snapshot!(
+            "pgcatalog_pg_stat_statements_postgres",
+            execute_query(
+                "SELECT * FROM pg_catalog.pg_stat_statements".to_string(),
+                DatabaseProtocol::PostgreSQL
+            )
+            .await?
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_pgcatalog_pg_stat_statements_privileges() -> Result<(), CubeError> {
+        init_testing_logger();
+
+        #[derive(Debug)]
+        struct SuperuserAuthContext {}
+
+        impl AuthContext for SuperuserAuthContext {
+            fn as_any(&self) -> &dyn std::any::Any {
+                self
+            }
+
+            fn is_superuser(&self) -> bool {
+                true
+            }
+        }
+
+        let context = TestContext::new(DatabaseProtocol::PostgreSQL).await;
+        for (user, query_id, query) in [("ovr", 1, "SELECT 1"), ("other", 2, "SELECT 2")] {
+            context.query_stats().record(
+                Some(user.to_string()),
+                QueryFingerprint {
+                    query_id,
+                    query: query.to_string(),
+                },
+                Duration::from_millis(10),
+                false,
+                QueryExecutionStats::default(),
+            );
+        }
+
+        // Other users see only statistics of their statements
+        let output = context
+            .execute_query("SELECT queryid, query FROM pg_stat_statements ORDER BY queryid")
+            .await?;
+        assert!(output.contains("SELECT 1"), "{}", output);
+        assert!(!output.contains("SELECT 2"), "{}", output);
+        assert!(output.contains("<insufficient privilege>"), "{}", output);
+
+        let err = context
+            .execute_query("SELECT pg_stat_statements_reset()")
+            .await
+            .unwrap_err();
+        assert!(err.message.contains("permission denied"), "{}", err.message);
+        assert_eq!(context.query_stats().entries().len(), 2);
+
+        context
+            .session_state()
+            .set_auth_context(Some(Arc::new(SuperuserAuthContext {})));
+
+        let output = context
+            .execute_query("SELECT queryid, query FROM pg_stat_statements ORDER BY queryid")
+            .await?;
+        assert!(output.contains("SELECT 1"), "{}", output);
+        assert!(output.contains("SELECT 2"), "{}", output);
+
+        context
+            .execute_query("SELECT pg_stat_statements_reset()")
+            .await?;
+        assert!(context.query_stats().entries().is_empty());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_query_stats_plan_query_id() -> Result<(), CubeError> {
+        init_testing_logger();
+
+        async fn plan_query_id(context: &TestContext, query: &str) -> Option<i64> {
+            context.convert_sql_to_cube_query(query).await.unwrap();
+            context.session_state().take_query_stats().query_id
+        }
+
+        let context = TestContext::new(DatabaseProtocol::PostgreSQL).await;
+
+        let query_id = plan_query_id(
+            &context,
+            "SELECT COUNT(*) FROM KibanaSampleDataEcommerce WHERE customer_gender = 'female'",
+        )
+        .await;
+        assert!(query_id.is_some());
+
+        // Literals and formatting don't change the parameterized plan
+        assert_eq!(
+            plan_query_id(
+                &context,
+                "select count(*)\nfrom KibanaSampleDataEcommerce\nwhere customer_gender = 'male'",
+            )
+            .await,
+            query_id
+        );
+
+        assert_ne!(
+            plan_query_id(
+                &context,
+                "SELECT COUNT(*) FROM KibanaSampleDataEcommerce WHERE notes = 'female'",
+            )
+            .await,
+            query_id
+        );
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_pgcatalog_pguser_postgres() -> Result<(), CubeError> {
         insta::assert_snapshot!(
@@ -6816,7 +6950,7 @@ ORDER BY
 
     #[tokio::test]
     async fn test_localtimestamp() -> Result<(), CubeError> {
-        // TODO: the value will be different with the introduction of TZ support
+        // Same as current_timestamp for UTC sessions, see test_session_timezone_date_rules
         insta::assert_snapshot!(
             "localtimestamp",
             execute_query(
@@ -6829,6 +6963,167 @@ ORDER BY
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_session_timezone_in_load_request() -> Result<(), CubeError> {
+        init_testing_logger();
+
+        let context = TestContext::new(DatabaseProtocol::PostgreSQL).await;
+        context
+            .execute_query("SET timezone = 'Europe/Berlin'")
+            .await?;
+
+        let query_plan = context
+            .convert_sql_to_cube_query(
+                "SELECT date_trunc('day', order_date) AS d, COUNT(*) FROM KibanaSampleDataEcommerce GROUP BY 1",
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(
+            query_plan.as_logical_plan().find_cube_scan().request,
+            V1LoadRequestQuery {
+                measures: Some(vec!["KibanaSampleDataEcommerce.count".to_string()]),
+                dimensions: Some(vec![]),
+                segments: Some(vec![]),
+                time_dimensions: Some(vec![V1LoadRequestQueryTimeDimension {
+                    dimension: "KibanaSampleDataEcommerce.order_date".to_string(),
+                    granularity: Some("day".to_string()),
+                    date_range: None,
+                }]),
+                order: Some(vec![]),
+                timezone: Some("Europe/Berlin".to_string()),
+                ..Default::default()
+            }
+        );
+
+        // UTC doesn't need to be passed to Cube
+        context.execute_query("SET timezone = 'UTC'").await?;
+        let query_plan = context
+            .convert_sql_to_cube_query("SELECT COUNT(*) FROM KibanaSampleDataEcommerce")
+            .await
+            .unwrap();
+        assert_eq!(
+            query_plan
+                .as_logical_plan()
+                .find_cube_scan()
+                .request
+                .timezone,
+            None
+        );
+
+        let err = context
+            .execute_query("SET timezone = 'Mars/Olympus_Mons'")
+            .await
+            .unwrap_err();
+        assert!(err
+            .message
+            .contains("invalid value for parameter \"TimeZone\": \"Mars/Olympus_Mons\""));
+
+        let context = TestContext::new(DatabaseProtocol::MySQL).await;
+        context.execute_query("SET time_zone = '+03:00'").await?;
+        let query_plan = context
+            .convert_sql_to_cube_query("SELECT COUNT(*) FROM KibanaSampleDataEcommerce")
+            .await
+            .unwrap();
+        assert_eq!(
+            query_plan
+                .as_logical_plan()
+                .find_cube_scan()
+                .request
+                .timezone,
+            Some("Etc/GMT-3".to_string())
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_session_timezone_date_rules() -> Result<(), CubeError> {
+        init_testing_logger();
+
+        let context = TestContext::new(DatabaseProtocol::PostgreSQL).await;
+        context
+            .execute_query("SET timezone = 'Europe/Berlin'")
+            .await?;
+
+        // Converting between names of the session time zone keeps the granularity push down
+        let query_plan = context
+            .convert_sql_to_cube_query(
+                "SELECT date_trunc('month', convert_tz(order_date, 'SYSTEM', 'Europe/Berlin')) AS m, COUNT(*) \
+                FROM KibanaSampleDataEcommerce GROUP BY 1",
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(
+            query_plan.as_logical_plan().find_cube_scan().request,
+            V1LoadRequestQuery {
+                measures: Some(vec!["KibanaSampleDataEcommerce.count".to_string()]),
+                dimensions: Some(vec![]),
+                segments: Some(vec![]),
+                time_dimensions: Some(vec![V1LoadRequestQueryTimeDimension {
+                    dimension: "KibanaSampleDataEcommerce.order_date".to_string(),
+                    granularity: Some("month".to_string()),
+                    date_range: None,
+                }]),
+                order: Some(vec![]),
+                timezone: Some("Europe/Berlin".to_string()),
+                ..Default::default()
+            }
+        );
+
+        // localtimestamp is the current time in the session time zone
+        let query_plan = context
+            .convert_sql_to_cube_query("SELECT localtimestamp AS ts")
+            .await
+            .unwrap();
+        let logical_plan = format!("{:?}", query_plan.as_logical_plan());
+        assert!(logical_plan.contains("convert_tz"), "{}", logical_plan);
+        assert!(logical_plan.contains("Europe/Berlin"), "{}", logical_plan);
+
+        context.execute_query("SET timezone = 'UTC'").await?;
+        let query_plan = context
+            .convert_sql_to_cube_query("SELECT localtimestamp AS ts")
+            .await
+            .unwrap();
+        let logical_plan = format!("{:?}", query_plan.as_logical_plan());
+        assert!(!logical_plan.contains("convert_tz"), "{}", logical_plan);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_session_timezone_rewrite_cache() -> Result<(), CubeError> {
+        init_testing_logger();
+
+        let mut config = ConfigObjImpl::default();
+        config.enable_rewrite_cache = true;
+
+        let berlin = TestContext::with_config(DatabaseProtocol::PostgreSQL, Arc::new(config)).await;
+        berlin
+            .execute_query("SET timezone = 'Europe/Berlin'")
+            .await?;
+        let new_york = berlin.new_session().await;
+        new_york
+            .execute_query("SET timezone = 'America/New_York'")
+            .await?;
+
+        for (context, timezone, other) in [
+            (&berlin, "Europe/Berlin", "America/New_York"),
+            (&new_york, "America/New_York", "Europe/Berlin"),
+        ] {
+            let query_plan = context
+                .convert_sql_to_cube_query("SELECT localtimestamp AS ts")
+                .await
+                .unwrap();
+            let logical_plan = format!("{:?}", query_plan.as_logical_plan());
+            assert!(logical_plan.contains(timezone), "{}", logical_plan);
+            assert!(!logical_plan.contains(other), "{}", logical_plan);
+        }
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_current_date() -> Result<(), CubeError> {
         init_testing_logger();
@@ -11682,6 +11977,8 @@ ORDER BY "source"."str0" ASC
                 offset: None,
                 filters: None,
                 ungrouped: None,
+                timezone: None,
+                total: None,
             }
         );
     }
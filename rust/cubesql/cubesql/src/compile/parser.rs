@@ -48,9 +48,138 @@ pub fn parse_sql_to_statements(
     let original_query = query.clone();
 
     log::debug!("Parsing SQL: {}", query);
+    // Workarounds are applied to every statement of the batch on its own,
+    // since most of them are anchored to the beginning or to the end of the query
+    let queries = split_statements(query, &protocol)
+        .into_iter()
+        .map(|query| apply_workarounds(query.to_string(), &protocol))
+        .collect::<Vec<_>>();
+
+    if let Some(qtrace) = qtrace {
+        qtrace.set_replaced_query(&queries.join(";"))
+    }
+
+    let mut statements = vec![];
+    for query in queries {
+        let parse_result = match protocol {
+            DatabaseProtocol::MySQL => {
+                Parser::parse_sql(&MySqlDialectWithBackTicks {}, query.as_str())
+            }
+            DatabaseProtocol::PostgreSQL => {
+                Parser::parse_sql(&PostgreSqlDialect {}, query.as_str())
+            }
+            DatabaseProtocol::Extension(_) => unimplemented!(),
+        };
+
+        statements.extend(parse_result.map_err(|err| {
+            CompilationError::user(format!("Unable to parse: {:?}", err)).with_meta(Some(
+                HashMap::from([("query".to_string(), original_query.clone())]),
+            ))
+        })?);
+    }
+
+    Ok(statements)
+}
+
+/// Splits query into separate statements by `;`. Semicolons inside of string literals,
+/// quoted identifiers, dollar-quoted strings and comments don't terminate a statement.
+fn split_statements<'a>(query: &'a str, protocol: &DatabaseProtocol) -> Vec<&'a str> {
+    let is_mysql = matches!(protocol, DatabaseProtocol::MySQL);
+    let bytes = query.as_bytes();
+
+    let mut statements = vec![];
+    let mut start = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b';' => {
+                statements.push(&query[start..i]);
+                start = i + 1;
+                i += 1;
+            }
+            quote @ (b'\'' | b'"' | b'`') => {
+                // MySQL strings and PostgreSQL E'' strings support backslash escapes
+                let backslash_escapes = quote != b'`'
+                    && (is_mysql
+                        || (quote == b'\''
+                            && i > 0
+                            && matches!(bytes[i - 1], b'E' | b'e')
+                            && (i < 2 || !is_identifier_byte(bytes[i - 2]))));
+                i = skip_quoted(bytes, i + 1, quote, backslash_escapes);
+            }
+            b'-' if bytes.get(i + 1) == Some(&b'-') => i = skip_line(bytes, i),
+            b'#' if is_mysql => i = skip_line(bytes, i),
+            b'/' if bytes.get(i + 1) == Some(&b'*') => {
+                i = find_bytes(bytes, i + 2, b"*/").map_or(bytes.len(), |end| end + 2);
+            }
+            b'$' if !is_mysql && (i == 0 || !is_identifier_byte(bytes[i - 1])) => {
+                i = match dollar_quote_tag(bytes, i) {
+                    Some(tag) => find_bytes(bytes, i + tag.len(), tag)
+                        .map_or(bytes.len(), |end| end + tag.len()),
+                    None => i + 1,
+                };
+            }
+            _ => i += 1,
+        }
+    }
+    statements.push(&query[start..]);
+
+    statements
+}
+
+fn is_identifier_byte(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || b == b'_'
+}
+
+/// Returns position right after the closing quote or the end of query if it's not terminated.
+fn skip_quoted(bytes: &[u8], mut i: usize, quote: u8, backslash_escapes: bool) -> usize {
+    while i < bytes.len() {
+        if backslash_escapes && bytes[i] == b'\\' {
+            i += 2;
+        } else if bytes[i] == quote {
+            // Doubled quote is an escaped quote
+            if bytes.get(i + 1) == Some(&quote) {
+                i += 2;
+            } else {
+                return i + 1;
+            }
+        } else {
+            i += 1;
+        }
+    }
+
+    bytes.len()
+}
+
+fn skip_line(bytes: &[u8], i: usize) -> usize {
+    find_bytes(bytes, i, b"\n").map_or(bytes.len(), |end| end + 1)
+}
+
+fn find_bytes(bytes: &[u8], from: usize, needle: &[u8]) -> Option<usize> {
+    bytes
+        .get(from..)?
+        .windows(needle.len())
+        .position(|window| window == needle)
+        .map(|position| from + position)
+}
+
+/// `$$` or `$tag$` at the position `i`, `$1` placeholders are not dollar quotes.
+fn dollar_quote_tag(bytes: &[u8], i: usize) -> Option<&[u8]> {
+    let mut end = i + 1;
+    while end < bytes.len() && is_identifier_byte(bytes[end]) {
+        end += 1;
+    }
+    if bytes.get(end) != Some(&b'$') || bytes.get(i + 1).map_or(false, u8::is_ascii_digit) {
+        return None;
+    }
+
+    Some(&bytes[i..=end])
+}
+
+fn apply_workarounds(query: String, protocol: &DatabaseProtocol) -> String {
     // @todo Support without workarounds
     // metabase
-    let query = query.clone().replace("IF(TABLE_TYPE='BASE TABLE' or TABLE_TYPE='SYSTEM VERSIONED', 'TABLE', TABLE_TYPE) as TABLE_TYPE", "TABLE_TYPE");
+    let query = query.replace("IF(TABLE_TYPE='BASE TABLE' or TABLE_TYPE='SYSTEM VERSIONED', 'TABLE', TABLE_TYPE) as TABLE_TYPE", "TABLE_TYPE");
     let query = query.replace("ORDER BY TABLE_TYPE, TABLE_SCHEMA, TABLE_NAME", "");
     // @todo Implement CONVERT function
     let query = query.replace("CONVERT (CASE DATA_TYPE WHEN 'year' THEN NUMERIC_SCALE WHEN 'tinyint' THEN 0 ELSE NUMERIC_SCALE END, UNSIGNED INTEGER)", "0");
@@ -235,21 +364,21 @@ pub fn parse_sql_to_statements(
             )
             .to_string()
     };
-
-    if let Some(qtrace) = qtrace {
-        qtrace.set_replaced_query(&query)
-    }
-
-    let parse_result = match protocol {
-        DatabaseProtocol::MySQL => Parser::parse_sql(&MySqlDialectWithBackTicks {}, query.as_str()),
-        DatabaseProtocol::PostgreSQL => Parser::parse_sql(&PostgreSqlDialect {}, query.as_str()),
-        DatabaseProtocol::Extension(_) => unimplemented!(),
+    // Parser doesn't support compound identifiers in SET: SET cube.ungrouped = on
+    let query = {
+        static SET_CUBE_VARIABLE_RE: LazyLock<Regex> = LazyLock::new(|| {
+            Regex::new(r#"(?i)^(?P<set>\s*SET\s+(?:SESSION\s+|LOCAL\s+)?)(?P<name>cube\.[a-z_]+)(?P<rest>\s*(?:=|\bTO\b))"#).unwrap()
+        });
+        let replacement = match protocol {
+            DatabaseProtocol::MySQL => "${set}`${name}`${rest}",
+            _ => "${set}\"${name}\"${rest}",
+        };
+        SET_CUBE_VARIABLE_RE
+            .replace(&query, replacement)
+            .to_string()
     };
 
-    parse_result.map_err(|err| {
-        CompilationError::user(format!("Unable to parse: {:?}", err))
-            .with_meta(Some(HashMap::from([("query".to_string(), original_query)])))
-    })
+    query
 }
 
 pub fn parse_sql_to_statement(
@@ -395,4 +524,39 @@ mod tests {
             Err(err) => panic!("{}", err),
         }
     }
+
+    #[test]
+    fn test_multiple_statements_postgres() {
+        let statements = parse_sql_to_statements(
+            &"SELECT ';' AS a; SELECT \"b;\" FROM t -- c;\n; SELECT /* ; */ 2;".to_string(),
+            DatabaseProtocol::PostgreSQL,
+            &mut None,
+        )
+        .unwrap();
+        assert_eq!(statements.len(), 3);
+        assert_eq!(statements[0].to_string(), "SELECT ';' AS a");
+        assert_eq!(statements[1].to_string(), "SELECT \"b;\" FROM t");
+    }
+
+    #[test]
+    fn test_split_statements_mysql() {
+        assert_eq!(
+            split_statements(
+                "SELECT 'a\\';' AS a; # b;\nSELECT `c;`",
+                &DatabaseProtocol::MySQL
+            ),
+            vec!["SELECT 'a\\';' AS a", " # b;\nSELECT `c;`"]
+        );
+    }
+
+    #[test]
+    fn test_workarounds_per_statement() {
+        let statements = parse_sql_to_statements(
+            &"SELECT 1; SET cube.ungrouped = on".to_string(),
+            DatabaseProtocol::PostgreSQL,
+            &mut None,
+        )
+        .unwrap();
+        assert_eq!(statements.len(), 2);
+    }
 }
@@ -1,14 +1,18 @@
 use crate::compile::engine::df::planner::CubeQueryPlanner;
 use std::{
-    backtrace::Backtrace, collections::HashMap, future::Future, pin::Pin, sync::Arc,
-    time::SystemTime,
+    backtrace::Backtrace,
+    collections::HashMap,
+    future::Future,
+    pin::Pin,
+    sync::Arc,
+    time::{Instant, SystemTime},
 };
 
 use crate::{
     compile::{
         engine::{
             df::{
-                optimizers::{FilterPushDown, LimitPushDown, SortPushDown},
+                optimizers::{FilterPushDown, LimitPushDown, SortPushDown, TotalCountPushDown},
                 scan::CubeScanNode,
                 wrapper::CubeScanWrapperNode,
             },
@@ -26,10 +30,10 @@ use crate::{
     sql::{
         compiler_cache::{CompilerCache, CompilerCacheEntry},
         statement::SensitiveDataSanitizer,
-        SessionManager, SessionState,
+        QueryFingerprint, SecurityPolicy, SessionManager, SessionState,
     },
     transport::{LoadRequestMeta, MetaContext, SpanId, TransportService},
-    CubeErrorCauseType,
+    CubeError, CubeErrorCauseType,
 };
 use datafusion::{
     error::DataFusionError,
@@ -37,7 +41,7 @@ use datafusion::{
         default_session_builder, SessionConfig as DFSessionConfig,
         SessionContext as DFSessionContext,
     },
-    logical_plan::{plan::Extension, LogicalPlan, PlanVisitor},
+    logical_plan::{plan::Extension, Expr, LogicalPlan, PlanVisitor},
     optimizer::{
         optimizer::{OptimizerConfig, OptimizerRule},
         projection_drop_out::ProjectionDropOut,
@@ -97,6 +101,13 @@ pub trait QueryEngine {
     ) -> CompilationResult<(QueryPlan, Self::PlanMetadataType)> {
         let cache_entry = self.get_cache_entry(state.clone()).await?;
 
+        // Invisible members are neither planned nor introspected
+        let security_policy = state.security_policy();
+        let meta = match &security_policy {
+            Some(security_policy) => Arc::new(meta.restrict(security_policy)),
+            None => meta,
+        };
+
         let planning_start = SystemTime::now();
         if let Some(span_id) = span_id.as_ref() {
             if let Some(auth_context) = state.auth_context() {
@@ -156,59 +167,92 @@ pub trait QueryEngine {
 
         log::debug!("Initial Plan: {:#?}", optimized_plan);
 
+        // COUNT(*) OVER () is requested from Cube as total. The plan without the window is
+        // rewritten together with the original one, so when it can't be restored only the
+        // original plan has to be extracted
+        let total_count = TotalCountPushDown::extract(&optimized_plan);
+
+        // SET cube.ungrouped = on can't change results of aggregations
+        let mut cube_ctx = cube_ctx;
+        cube_ctx.ungrouped = state.ungrouped() && !has_aggregation(&optimized_plan);
         let cube_ctx = Arc::new(cube_ctx);
+
+        let rewrite_start = Instant::now();
         let mut converter = LogicalPlanToLanguageConverter::new(
             cube_ctx.clone(),
             self.config_ref().push_down_pull_up_split(),
         );
         let mut query_params = Some(HashMap::new());
-        let root = converter
-            .add_logical_plan_replace_params(
-                &optimized_plan,
-                &mut query_params,
-                &mut LogicalPlanToLanguageContext::default(),
-            )
-            .map_err(|e| CompilationError::internal(e.to_string()))?;
+        let mut roots = vec![];
+        for plan_to_rewrite in total_count
+            .iter()
+            .map(|total_count| &total_count.plan)
+            .chain([&optimized_plan])
+        {
+            let root = converter
+                .add_logical_plan_replace_params(
+                    plan_to_rewrite,
+                    &mut query_params,
+                    &mut LogicalPlanToLanguageContext::default(),
+                )
+                .map_err(|e| CompilationError::internal(e.to_string()))?;
+            roots.push(root);
+        }
+
+        let parameterized_graph = converter.take_egraph();
+        let query_id = QueryFingerprint::plan_query_id(&parameterized_graph);
+        state.update_query_stats(|stats| stats.query_id = Some(query_id));
+
+        let rewrite_error = |e: CubeError| match e.cause {
+            CubeErrorCauseType::Internal(_) => CompilationError::Internal(
+                format!(
+                    "Error during rewrite: {}. Please check logs for additional information.",
+                    e.message
+                ),
+                e.to_backtrace().unwrap_or_else(|| Backtrace::capture()),
+                Some(HashMap::from([
+                    ("query".to_string(), stmt.to_string()),
+                    (
+                        "sanitizedQuery".to_string(),
+                        self.sanitize_statement(&stmt).to_string(),
+                    ),
+                ])),
+            ),
+            CubeErrorCauseType::User(_) => CompilationError::User(
+                format!(
+                    "Error during rewrite: {}. Please check logs for additional information.",
+                    e.message
+                ),
+                Some(HashMap::from([
+                    ("query".to_string(), stmt.to_string()),
+                    (
+                        "sanitizedQuery".to_string(),
+                        self.sanitize_statement(&stmt).to_string(),
+                    ),
+                ])),
+            ),
+        };
+        let rewrite_failed = |err: CompilationError| {
+            state.update_query_stats(|stats| {
+                stats.rewrite_time += rewrite_start.elapsed();
+                stats.rewrite_failed = true;
+            });
+
+            err
+        };
 
         let mut finalized_graph = self
             .compiler_cache_ref()
             .rewrite(
                 Arc::clone(&cache_entry),
                 cube_ctx.clone(),
-                converter.take_egraph(),
+                parameterized_graph,
                 &query_params.unwrap(),
                 qtrace,
             )
             .await
-            .map_err(|e| match e.cause {
-                CubeErrorCauseType::Internal(_) => CompilationError::Internal(
-                    format!(
-                        "Error during rewrite: {}. Please check logs for additional information.",
-                        e.message
-                    ),
-                    e.to_backtrace().unwrap_or_else(|| Backtrace::capture()),
-                    Some(HashMap::from([
-                        ("query".to_string(), stmt.to_string()),
-                        (
-                            "sanitizedQuery".to_string(),
-                            self.sanitize_statement(&stmt).to_string(),
-                        ),
-                    ])),
-                ),
-                CubeErrorCauseType::User(_) => CompilationError::User(
-                    format!(
-                        "Error during rewrite: {}. Please check logs for additional information.",
-                        e.message
-                    ),
-                    Some(HashMap::from([
-                        ("query".to_string(), stmt.to_string()),
-                        (
-                            "sanitizedQuery".to_string(),
-                            self.sanitize_statement(&stmt).to_string(),
-                        ),
-                    ])),
-                ),
-            })?;
+            .map_err(rewrite_error)
+            .map_err(rewrite_failed)?;
 
         // Replace Analysis as at least time has changed but it might be also context may affect rewriting in some other ways
         finalized_graph.analysis = LogicalPlanAnalysis::new(
@@ -218,51 +262,47 @@ pub trait QueryEngine {
 
         let mut rewriter = Rewriter::new(finalized_graph, cube_ctx.clone());
 
-        let result = rewriter
-            .find_best_plan(
-                root,
-                cache_entry,
+        let results = rewriter
+            .find_best_plans(
+                &roots,
+                Arc::clone(&cache_entry),
                 state.auth_context().unwrap(),
                 qtrace,
                 span_id.clone(),
                 self.config_ref().top_down_extractor(),
             )
             .await
-            .map_err(|e| match e.cause {
-                CubeErrorCauseType::Internal(_) => CompilationError::Internal(
-                    format!(
-                        "Error during rewrite: {}. Please check logs for additional information.",
-                        e.message
-                    ),
-                    e.to_backtrace().unwrap_or_else(|| Backtrace::capture()),
-                    Some(HashMap::from([
-                        ("query".to_string(), stmt.to_string()),
-                        (
-                            "sanitizedQuery".to_string(),
-                            self.sanitize_statement(&stmt).to_string(),
-                        ),
-                    ])),
-                ),
-                CubeErrorCauseType::User(_) => CompilationError::User(
-                    format!(
-                        "Error during rewrite: {}. Please check logs for additional information.",
-                        e.message
-                    ),
-                    Some(HashMap::from([
-                        ("query".to_string(), stmt.to_string()),
-                        (
-                            "sanitizedQuery".to_string(),
-                            self.sanitize_statement(&stmt).to_string(),
-                        ),
-                    ])),
-                ),
-            });
+            .map_err(rewrite_error)
+            .map_err(rewrite_failed)?;
+
+        let mut results = results.into_iter();
+        let total_count_result = total_count.as_ref().and_then(|_| results.next());
+        let result = results
+            .next()
+            .unwrap_or_else(|| Err(CubeError::internal("Unable to find best plan".to_string())));
+
+        let restored = match (&total_count, total_count_result) {
+            (Some(total_count), Some(Ok((plan, cost)))) => total_count
+                .restore(&plan)
+                .map_err(|e| CompilationError::internal(e.to_string()))?
+                .map(|plan| (plan, cost)),
+            _ => None,
+        };
+        let result = match restored {
+            Some(restored) => Ok(restored),
+            None => result.map_err(rewrite_error),
+        };
+
+        state.update_query_stats(|stats| {
+            stats.rewrite_time += rewrite_start.elapsed();
+            stats.rewrite_failed = result.is_err();
+        });
 
         if let Err(_) = &result {
             log::error!("It may be this query is not supported yet. Please post an issue on GitHub https://github.com/cube-js/cube.js/issues/new?template=sql_api_query_issue.md or ask about it in Slack https://slack.cube.dev.");
         }
 
-        let rewrite_plan = result?;
+        let (rewrite_plan, plan_cost) = result?;
 
         // DF optimizes logical plan (second time) on physical plan creation
         // It's not safety to use all optimizers from DF for OLAP queries, because it will lead to errors
@@ -276,6 +316,12 @@ pub trait QueryEngine {
             if let Some(span_id) = &span_id {
                 span_id.set_is_data_query(true).await;
             }
+
+            let post_processing = is_post_processing_query(&rewrite_plan);
+            state.update_query_stats(|stats| {
+                stats.post_processing = post_processing;
+                stats.plan_cost = Some(plan_cost);
+            });
         };
 
         log::debug!("Rewrite: {:#?}", rewrite_plan);
@@ -298,6 +344,11 @@ pub trait QueryEngine {
             }
         }
 
+        let rewrite_plan = match &security_policy {
+            Some(security_policy) => Self::apply_security_policy(rewrite_plan, security_policy)?,
+            None => rewrite_plan,
+        };
+
         let rewrite_plan = Self::evaluate_wrapped_sql(
             self.transport_ref().clone(),
             Arc::new(state.get_load_request_meta()),
@@ -347,6 +398,45 @@ pub trait QueryEngine {
                 .map_err(|e| CompilationError::internal(e.to_string()))
         })
     }
+
+    /// Adds mandatory filters to every Cube request, including requests wrapped into SQL push down.
+    fn apply_security_policy(
+        plan: LogicalPlan,
+        security_policy: &SecurityPolicy,
+    ) -> CompilationResult<LogicalPlan> {
+        if let LogicalPlan::Extension(Extension { node }) = &plan {
+            if let Some(scan_node) = node.as_any().downcast_ref::<CubeScanNode>() {
+                let mut scan_node = scan_node.clone();
+                security_policy
+                    .apply_to_request(&mut scan_node.request, &scan_node.used_cubes)
+                    .map_err(|e| CompilationError::user(e.message))?;
+
+                return Ok(LogicalPlan::Extension(Extension {
+                    node: Arc::new(scan_node),
+                }));
+            }
+
+            if let Some(wrapper) = node.as_any().downcast_ref::<CubeScanWrapperNode>() {
+                let mut wrapper = wrapper.clone();
+                wrapper.wrapped_plan = Arc::new(Self::apply_security_policy(
+                    wrapper.wrapped_plan.as_ref().clone(),
+                    security_policy,
+                )?);
+
+                return Ok(LogicalPlan::Extension(Extension {
+                    node: Arc::new(wrapper),
+                }));
+            }
+        }
+
+        let children = plan
+            .inputs()
+            .into_iter()
+            .map(|input| Self::apply_security_policy(input.clone(), security_policy))
+            .collect::<CompilationResult<Vec<_>>>()?;
+        from_plan(&plan, plan.expressions().as_slice(), children.as_slice())
+            .map_err(|e| CompilationError::internal(e.to_string()))
+    }
 }
 
 pub struct SqlQueryEngine {
@@ -434,6 +524,10 @@ impl QueryEngine for SqlQueryEngine {
             ));
             ctx.register_udf(create_current_user_udf(state.clone(), "user", false));
             ctx.register_udf(create_session_user_udf(state.clone()));
+            ctx.register_udf(create_pg_stat_statements_reset_udf(
+                state.clone(),
+                self.session_manager.server.query_stats.clone(),
+            ));
         }
 
         ctx.register_udf(create_connection_id_udf(state.clone()));
@@ -444,7 +538,7 @@ impl QueryEngine for SqlQueryEngine {
         ctx.register_udf(create_if_udf());
         ctx.register_udf(create_least_udf());
         ctx.register_udf(create_greatest_udf());
-        ctx.register_udf(create_convert_tz_udf());
+        ctx.register_udf(create_convert_tz_udf(state.clone()));
         ctx.register_udf(create_timediff_udf());
         ctx.register_udf(create_time_format_udf());
         ctx.register_udf(create_locate_udf());
@@ -476,7 +570,7 @@ impl QueryEngine for SqlQueryEngine {
         ctx.register_udf(create_pg_get_constraintdef_udf());
         ctx.register_udf(create_pg_truetypid_udf());
         ctx.register_udf(create_pg_truetypmod_udf());
-        ctx.register_udf(create_to_char_udf());
+        ctx.register_udf(create_to_char_udf(state.clone()));
         ctx.register_udf(create_array_lower_udf());
         ctx.register_udf(create_array_upper_udf());
         ctx.register_udf(create_pg_my_temp_schema());
@@ -567,6 +661,35 @@ impl QueryEngine for SqlQueryEngine {
     }
 }
 
+/// Query is executed by Cube alone when it's a plain `CubeScan` or a fully pushed down
+/// `CubeScanWrapper`, optionally under a projection which only selects columns.
+fn is_post_processing_query(plan: &LogicalPlan) -> bool {
+    fn is_column(expr: &Expr) -> bool {
+        match expr {
+            Expr::Column(_) => true,
+            Expr::Alias(expr, _) => is_column(expr),
+            _ => false,
+        }
+    }
+
+    match plan {
+        LogicalPlan::Projection(projection) if projection.expr.iter().all(is_column) => {
+            is_post_processing_query(&projection.input)
+        }
+        LogicalPlan::Extension(Extension { node }) => {
+            let node = node.as_any();
+            !(node.is::<CubeScanNode>() || node.is::<CubeScanWrapperNode>())
+        }
+        _ => true,
+    }
+}
+
+/// Whether the plan groups rows anywhere, `Distinct` included.
+fn has_aggregation(plan: &LogicalPlan) -> bool {
+    matches!(plan, LogicalPlan::Aggregate(_) | LogicalPlan::Distinct(_))
+        || plan.inputs().into_iter().any(has_aggregation)
+}
+
 fn is_olap_query(parent: &LogicalPlan) -> Result<bool, CompilationError> {
     pub struct FindCubeScanNodeVisitor(bool);
 
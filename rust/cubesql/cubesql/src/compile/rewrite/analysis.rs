@@ -16,6 +16,7 @@ use crate::{
     transport::ext::{V1CubeMetaDimensionExt, V1CubeMetaMeasureExt, V1CubeMetaSegmentExt},
     var_iter, var_list_iter, CubeError,
 };
+use chrono_tz::Tz;
 use datafusion::{
     arrow::{
         array::NullArray,
@@ -281,6 +282,11 @@ impl LogicalPlanAnalysis {
         }
     }
 
+    /// Time zone of the session the query is rewritten for, `None` for UTC sessions.
+    pub fn session_timezone(&self) -> Option<Tz> {
+        self.cube_context.session_state.timezone()
+    }
+
     pub fn store_egraph_debug_state(egraph: &mut CubeEGraph) {
         debug_assert_eq!(
             egraph.analysis.iteration_timestamp,
@@ -1980,10 +1980,19 @@ impl LanguageToLogicalPlanConverter {
                 let ungrouped =
                     match_data_node!(node_by_id, cube_scan_params[9], CubeScanUngrouped);
 
-                if ungrouped {
+                // SET cube.ungrouped = on forces ungrouped queries without aggregations
+                if ungrouped || self.cube_context.ungrouped {
                     query.ungrouped = Some(true);
                 }
 
+                // Cube evaluates granularities and date ranges in this time zone and returns
+                // local time, it's inherited by SQL push down as it reuses this request
+                query.timezone = self
+                    .cube_context
+                    .session_state
+                    .timezone()
+                    .map(|tz| tz.name().to_string());
+
                 query.order = if !query_order.is_empty() {
                     Some(query_order)
                 } else {
@@ -2021,6 +2030,7 @@ impl LanguageToLogicalPlanConverter {
                     CubeScanOptions {
                         change_user,
                         max_records,
+                        total_field: None,
                     },
                     alias_to_cube.into_iter().map(|(_, c)| c).unique().collect(),
                     self.span_id.clone(),
@@ -1,9 +1,15 @@
-use std::{collections::HashMap, fmt::Debug, hash::Hash, marker::PhantomData, sync::Arc};
+use std::{
+    collections::HashMap,
+    fmt::{self, Debug},
+    hash::Hash,
+    marker::PhantomData,
+    sync::Arc,
+};
 
 use crate::{
     compile::rewrite::{
-        rules::utils::granularity_str_to_int_order, CubeScanUngrouped, CubeScanWrapped,
-        DimensionName, LogicalPlanLanguage, MemberErrorPriority, ScalarUDFExprFun,
+        rules::utils::granularity_str_to_int_order, CubeScanAliasToCube, CubeScanUngrouped,
+        CubeScanWrapped, DimensionName, LogicalPlanLanguage, MemberErrorPriority, ScalarUDFExprFun,
         TimeDimensionGranularity, WrappedSelectUngroupedScan,
     },
     transport::{MetaContext, V1CubeMetaDimensionExt},
@@ -11,6 +17,10 @@ use crate::{
 use egg::{Analysis, CostFunction, EGraph, Id, Language, RecExpr};
 use indexmap::IndexSet;
 
+/// Ungrouped results of cubes estimated to have more rows than this shouldn't be loaded
+/// for post-processing: it's the default row limit of Cube load requests.
+const LARGE_CUBE_ROW_COUNT: i64 = 50000;
+
 #[derive(Debug)]
 pub struct BestCubePlan {
     meta_context: Arc<MetaContext>,
@@ -191,6 +201,28 @@ impl BestCubePlan {
             _ => 0,
         };
 
+        let (pre_aggregated_cubes, large_cubes) = match enode {
+            LogicalPlanLanguage::CubeScanAliasToCube(CubeScanAliasToCube(alias_to_cube)) => {
+                let hints = alias_to_cube
+                    .iter()
+                    .filter_map(|(_, cube)| self.meta_context.cube_cost_hints(cube))
+                    .collect::<Vec<_>>();
+                (
+                    hints
+                        .iter()
+                        .filter(|hints| hints.has_pre_aggregations == Some(true))
+                        .count(),
+                    hints
+                        .iter()
+                        .filter(|hints| {
+                            hints.estimated_row_count.unwrap_or(0) > LARGE_CUBE_ROW_COUNT
+                        })
+                        .count(),
+                )
+            }
+            _ => (0, 0),
+        };
+
         CubePlanCost {
             replacers: this_replacers,
             table_scans,
@@ -208,6 +240,8 @@ impl BestCubePlan {
             max_time_dimensions_granularity,
             structure_points,
             ungrouped_aggregates: 0,
+            large_ungrouped_post_processing: 0,
+            wrapped_pre_aggregated_scans: 0,
             wrapper_nodes,
             wrapped_select_ungrouped_scan,
             empty_wrappers: 0,
@@ -218,6 +252,8 @@ impl BestCubePlan {
             ast_size: 1,
             ungrouped_nodes,
             unwrapped_subqueries,
+            pre_aggregated_cubes,
+            large_cubes,
         }
     }
 }
@@ -231,6 +267,8 @@ impl BestCubePlan {
 /// - `filter_members` > `cube_members` - optimize for `inDateRange` filter push down to time dimension
 /// - `member_errors` > `cube_members` - extra cube members may be required (e.g. CASE)
 /// - `member_errors` > `wrapper_nodes` - use SQL push down where possible if cube scan can't be detected
+/// - `large_ungrouped_post_processing` > `wrapper_nodes` - don't load ungrouped results of large cubes for post-processing
+/// - `wrapped_pre_aggregated_scans` > `non_pushed_down_window` - load requests can be served by pre-aggregations
 /// - `non_pushed_down_window` > `wrapper_nodes` - prefer to always push down window functions
 /// - `non_pushed_down_limit_sort` > `wrapper_nodes` - prefer to always push down limit-sort expressions
 /// - match errors by priority - optimize for more specific errors
@@ -243,7 +281,8 @@ pub struct CubePlanCost {
     unwrapped_subqueries: usize,
     member_errors: i64,
     ungrouped_aggregates: usize,
-    // TODO if pre-aggregation can be used for window functions, then it'd be suboptimal
+    large_ungrouped_post_processing: usize,
+    wrapped_pre_aggregated_scans: usize,
     non_pushed_down_window: i64,
     non_pushed_down_grouping_sets: i64,
     non_pushed_down_limit_sort: i64,
@@ -267,6 +306,9 @@ pub struct CubePlanCost {
     ast_size: usize,
     ast_size_inside_wrapper: usize,
     ungrouped_nodes: usize,
+    // Cost hints of cubes in the subtree, zero if transport doesn't provide hints
+    pre_aggregated_cubes: usize,
+    large_cubes: usize,
 }
 
 #[derive(Debug, Clone, Eq, Hash, PartialEq)]
@@ -344,6 +386,10 @@ impl CubePlanCostAndState {
 }
 
 impl CubePlanCost {
+    pub fn cube_scan_nodes(&self) -> i64 {
+        self.cube_scan_nodes
+    }
+
     pub fn add_child(&self, other: &Self) -> Self {
         Self {
             replacers: self.replacers + other.replacers,
@@ -373,6 +419,10 @@ impl CubePlanCost {
             ast_size_outside_wrapper: self.ast_size_outside_wrapper
                 + other.ast_size_outside_wrapper,
             ungrouped_aggregates: self.ungrouped_aggregates + other.ungrouped_aggregates,
+            large_ungrouped_post_processing: self.large_ungrouped_post_processing
+                + other.large_ungrouped_post_processing,
+            wrapped_pre_aggregated_scans: self.wrapped_pre_aggregated_scans
+                + other.wrapped_pre_aggregated_scans,
             wrapper_nodes: self.wrapper_nodes + other.wrapper_nodes,
             wrapped_select_ungrouped_scan: self.wrapped_select_ungrouped_scan
                 + other.wrapped_select_ungrouped_scan,
@@ -387,6 +437,8 @@ impl CubePlanCost {
             ast_size_inside_wrapper: self.ast_size_inside_wrapper + other.ast_size_inside_wrapper,
             ungrouped_nodes: self.ungrouped_nodes + other.ungrouped_nodes,
             unwrapped_subqueries: self.unwrapped_subqueries + other.unwrapped_subqueries,
+            pre_aggregated_cubes: self.pre_aggregated_cubes + other.pre_aggregated_cubes,
+            large_cubes: self.large_cubes + other.large_cubes,
         }
     }
 
@@ -457,6 +509,31 @@ impl CubePlanCost {
                 }
                 CubePlanState::Wrapper => 0,
             } + self.ungrouped_aggregates,
+            large_ungrouped_post_processing: match state {
+                CubePlanState::Unwrapped(_)
+                    if self.ungrouped_nodes > 0
+                        && self.large_cubes > 0
+                        && self.wrapper_nodes == 0 =>
+                {
+                    match enode {
+                        LogicalPlanLanguage::Aggregate(_)
+                        | LogicalPlanLanguage::Filter(_)
+                        | LogicalPlanLanguage::Sort(_)
+                        | LogicalPlanLanguage::Limit(_)
+                        | LogicalPlanLanguage::Window(_)
+                        | LogicalPlanLanguage::Join(_)
+                        | LogicalPlanLanguage::CrossJoin(_) => 1,
+                        _ => 0,
+                    }
+                }
+                _ => 0,
+            } + self.large_ungrouped_post_processing,
+            wrapped_pre_aggregated_scans: match (state, enode) {
+                (CubePlanState::Wrapped, LogicalPlanLanguage::CubeScan(_)) => {
+                    self.pre_aggregated_cubes
+                }
+                _ => 0,
+            } + self.wrapped_pre_aggregated_scans,
             unwrapped_subqueries: self.unwrapped_subqueries,
             wrapper_nodes: self.wrapper_nodes,
             wrapped_select_ungrouped_scan: self.wrapped_select_ungrouped_scan,
@@ -465,10 +542,82 @@ impl CubePlanCost {
             ast_size: self.ast_size,
             ast_size_inside_wrapper: self.ast_size_inside_wrapper,
             ungrouped_nodes: self.ungrouped_nodes,
+            pre_aggregated_cubes: self.pre_aggregated_cubes,
+            large_cubes: self.large_cubes,
         }
     }
 }
 
+/// Breakdown of non-zero components in the order of their priority, used by `EXPLAIN`.
+impl fmt::Display for CubePlanCost {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let components = [
+            ("replacers", self.replacers),
+            ("table_scans", self.table_scans),
+            ("empty_wrappers", self.empty_wrappers),
+            ("non_detected_cube_scans", self.non_detected_cube_scans),
+            ("unwrapped_subqueries", self.unwrapped_subqueries as i64),
+            ("member_errors", self.member_errors),
+            ("ungrouped_aggregates", self.ungrouped_aggregates as i64),
+            (
+                "large_ungrouped_post_processing",
+                self.large_ungrouped_post_processing as i64,
+            ),
+            (
+                "wrapped_pre_aggregated_scans",
+                self.wrapped_pre_aggregated_scans as i64,
+            ),
+            ("non_pushed_down_window", self.non_pushed_down_window),
+            (
+                "non_pushed_down_grouping_sets",
+                self.non_pushed_down_grouping_sets,
+            ),
+            (
+                "non_pushed_down_limit_sort",
+                self.non_pushed_down_limit_sort,
+            ),
+            ("wrapper_nodes", self.wrapper_nodes),
+            (
+                "wrapped_select_ungrouped_scan",
+                self.wrapped_select_ungrouped_scan as i64,
+            ),
+            (
+                "ast_size_outside_wrapper",
+                self.ast_size_outside_wrapper as i64,
+            ),
+            ("filters", self.filters),
+            ("structure_points", self.structure_points),
+            ("filter_members", self.filter_members),
+            ("zero_members_wrapper", self.zero_members_wrapper),
+            ("cube_members", self.cube_members),
+            ("errors", self.errors),
+            (
+                "time_dimensions_used_as_dimensions",
+                self.time_dimensions_used_as_dimensions,
+            ),
+            (
+                "max_time_dimensions_granularity",
+                self.max_time_dimensions_granularity,
+            ),
+            ("cube_scan_nodes", self.cube_scan_nodes),
+            ("ast_size_without_alias", self.ast_size_without_alias as i64),
+            ("ast_size", self.ast_size as i64),
+            (
+                "ast_size_inside_wrapper",
+                self.ast_size_inside_wrapper as i64,
+            ),
+            ("ungrouped_nodes", self.ungrouped_nodes as i64),
+        ];
+
+        let components = components
+            .iter()
+            .filter(|(_, value)| *value != 0)
+            .map(|(name, value)| format!("{}={}", name, value))
+            .collect::<Vec<_>>();
+        f.write_str(&components.join(", "))
+    }
+}
+
 impl CostFunction<LogicalPlanLanguage> for BestCubePlan {
     type Cost = CubePlanCostAndState;
     fn cost<C>(&mut self, enode: &LogicalPlanLanguage, mut costs: C) -> Self::Cost
@@ -1,6 +1,6 @@
 pub mod analysis;
 pub mod converter;
-mod cost;
+pub mod cost;
 pub mod language;
 pub mod rewriter;
 pub mod rules;
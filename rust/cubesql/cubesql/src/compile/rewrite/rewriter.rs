@@ -4,7 +4,7 @@ use crate::{
         rewrite::{
             analysis::LogicalPlanAnalysis,
             converter::LanguageToLogicalPlanConverter,
-            cost::{BestCubePlan, CubePlanTopDownState, TopDownExtractor},
+            cost::{BestCubePlan, CubePlanCost, CubePlanTopDownState, TopDownExtractor},
             rules::{
                 case::CaseRules, common::CommonRules, dates::DateRules, filters::FilterRules,
                 flatten::FlattenRules, members::MemberRules, old_split::OldSplitRules,
@@ -331,7 +331,31 @@ impl Rewriter {
         qtrace: &mut Option<Qtrace>,
         span_id: Option<Arc<SpanId>>,
         top_down_extractor: bool,
-    ) -> Result<LogicalPlan, CubeError> {
+    ) -> Result<(LogicalPlan, CubePlanCost), CubeError> {
+        self.find_best_plans(
+            &[root],
+            cache_entry,
+            auth_context,
+            qtrace,
+            span_id,
+            top_down_extractor,
+        )
+        .await?
+        .pop()
+        .unwrap_or_else(|| Err(CubeError::internal("Unable to find best plan".to_string())))
+    }
+
+    /// Runs final rewrites once and extracts the best plan for each of the roots, so plans
+    /// added to the same graph can be tried one after another without rewriting them again.
+    pub async fn find_best_plans(
+        &mut self,
+        roots: &[Id],
+        cache_entry: Arc<CompilerCacheEntry>,
+        auth_context: AuthContextRef,
+        qtrace: &mut Option<Qtrace>,
+        span_id: Option<Arc<SpanId>>,
+        top_down_extractor: bool,
+    ) -> Result<Vec<Result<(LogicalPlan, CubePlanCost), CubeError>>, CubeError> {
         let cube_context = self.cube_context.clone();
         let egraph = self.graph.clone();
         if let Some(qtrace) = qtrace {
@@ -345,55 +369,67 @@ impl Rewriter {
             .rewrite_rules(cache_entry, true)
             .await?;
 
-        let (plan, qtrace_egraph_iterations, qtrace_best_graph) =
+        let roots = roots.to_vec();
+        let (plans, qtrace_egraph_iterations, qtrace_best_graph) =
             tokio::task::spawn_blocking(move || {
                 let (runner, qtrace_egraph_iterations) =
                     Self::run_rewrites(&cube_context, egraph, rules, "final")?;
 
-                let best = if top_down_extractor {
-                    let mut extractor = TopDownExtractor::new(
-                        &runner.egraph,
-                        BestCubePlan::new(cube_context.meta.clone()),
-                        CubePlanTopDownState::new(),
-                    );
-                    let Some((best_cost, best)) = extractor.find_best(root) else {
-                        return Err(CubeError::internal("Unable to find best plan".to_string()));
+                let mut plans = Vec::with_capacity(roots.len());
+                let mut qtrace_best_graph = None;
+                for root in roots {
+                    let (best_cost, best) = if top_down_extractor {
+                        let mut extractor = TopDownExtractor::new(
+                            &runner.egraph,
+                            BestCubePlan::new(cube_context.meta.clone()),
+                            CubePlanTopDownState::new(),
+                        );
+                        let Some((best_cost, best)) = extractor.find_best(root) else {
+                            plans.push(Err(CubeError::internal(
+                                "Unable to find best plan".to_string(),
+                            )));
+                            continue;
+                        };
+                        log::debug!("Best cost: {:?}", best_cost);
+                        (best_cost, best)
+                    } else {
+                        let extractor = Extractor::new(
+                            &runner.egraph,
+                            BestCubePlan::new(cube_context.meta.clone()),
+                        );
+                        let (best_cost, best) = extractor.find_best(root);
+                        log::debug!("Best cost: {:?}", best_cost);
+                        (best_cost.cost, best)
                     };
-                    log::debug!("Best cost: {:?}", best_cost);
-                    best
-                } else {
-                    let extractor = Extractor::new(
-                        &runner.egraph,
-                        BestCubePlan::new(cube_context.meta.clone()),
+                    if qtrace_best_graph.is_none() && Qtrace::is_enabled() {
+                        qtrace_best_graph = Some(best.as_ref().iter().cloned().collect::<Vec<_>>());
+                    }
+                    let new_root = Id::from(best.as_ref().len() - 1);
+                    log::debug!(
+                        "Best: {}",
+                        best.as_ref()
+                            .iter()
+                            .enumerate()
+                            .map(|(i, n)| format!("{}: {:?}", i, n))
+                            .join(", ")
+                    );
+                    let converter = LanguageToLogicalPlanConverter::new(
+                        best,
+                        cube_context.clone(),
+                        auth_context.clone(),
+                        span_id.clone(),
                     );
-                    let (best_cost, best) = extractor.find_best(root);
-                    log::debug!("Best cost: {:?}", best_cost);
-                    best
-                };
-                let qtrace_best_graph = if Qtrace::is_enabled() {
-                    best.as_ref().iter().cloned().collect()
-                } else {
-                    vec![]
-                };
-                let new_root = Id::from(best.as_ref().len() - 1);
-                log::debug!(
-                    "Best: {}",
-                    best.as_ref()
-                        .iter()
-                        .enumerate()
-                        .map(|(i, n)| format!("{}: {:?}", i, n))
-                        .join(", ")
-                );
-                let converter = LanguageToLogicalPlanConverter::new(
-                    best,
-                    cube_context.clone(),
-                    auth_context,
-                    span_id.clone(),
-                );
-                Ok((
-                    converter.to_logical_plan(new_root),
+                    plans.push(
+                        converter
+                            .to_logical_plan(new_root)
+                            .map(|plan| (plan, best_cost)),
+                    );
+                }
+
+                Ok::<_, CubeError>((
+                    plans,
                     qtrace_egraph_iterations,
-                    qtrace_best_graph,
+                    qtrace_best_graph.unwrap_or_default(),
                 ))
             })
             .await??;
@@ -403,7 +439,7 @@ impl Rewriter {
             qtrace.set_best_graph(&qtrace_best_graph);
         }
 
-        plan
+        Ok(plans)
     }
 
     fn run_rewrites(
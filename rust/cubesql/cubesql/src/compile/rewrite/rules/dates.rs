@@ -1,7 +1,7 @@
 use super::utils;
 use crate::{
     compile::rewrite::{
-        alias_expr,
+        add_root_original_expr_alias, alias_expr,
         analysis::{ConstantFolding, OriginalExpr},
         binary_expr, cast_expr, cast_expr_explicit, column_expr, fun_expr, literal_expr,
         literal_int, literal_string, negative_expr, original_expr_name, rewrite,
@@ -12,8 +12,10 @@ use crate::{
         LiteralExprValue, LogicalPlanLanguage,
     },
     config::ConfigObj,
+    sql::{is_utc_timezone, parse_timezone},
     var, var_iter,
 };
+use chrono_tz::Tz;
 use datafusion::{
     arrow::datatypes::{DataType, TimeUnit},
     logical_plan::DFSchema,
@@ -255,7 +257,53 @@ impl RewriteRules for DateRules {
                     self.fun_expr("UtcTimestamp", Vec::<String>::new()),
                     "?alias",
                 ),
-                transform_original_expr_to_alias("?alias"),
+                self.transform_localtimestamp_utc("?alias"),
+            ),
+            transforming_rewrite_with_root(
+                "localtimestamp-to-session-now",
+                udf_expr("localtimestamp", Vec::<String>::new()),
+                alias_expr(
+                    cast_expr(
+                        udf_expr(
+                            "convert_tz",
+                            vec![
+                                self.fun_expr("UtcTimestamp", Vec::<String>::new()),
+                                literal_string("UTC"),
+                                literal_expr("?timezone"),
+                            ],
+                        ),
+                        "?data_type",
+                    ),
+                    "?alias",
+                ),
+                self.transform_localtimestamp_session("?timezone", "?data_type", "?alias"),
+            ),
+            // Cube returns time dimensions in the session time zone, so converting them
+            // between two names of the same zone doesn't prevent granularity push down
+            transforming_rewrite_with_root(
+                "date-trunc-convert-tz-same-timezone",
+                self.fun_expr(
+                    "DateTrunc",
+                    vec![
+                        "?granularity".to_string(),
+                        udf_expr(
+                            "convert_tz",
+                            vec![
+                                column_expr("?column"),
+                                literal_expr("?from_tz"),
+                                literal_expr("?to_tz"),
+                            ],
+                        ),
+                    ],
+                ),
+                alias_expr(
+                    self.fun_expr(
+                        "DateTrunc",
+                        vec!["?granularity".to_string(), column_expr("?column")],
+                    ),
+                    "?alias",
+                ),
+                self.transform_same_timezone("?from_tz", "?to_tz", "?alias"),
             ),
             transforming_rewrite_with_root(
                 "tableau-week",
@@ -628,6 +676,86 @@ impl DateRules {
         }
     }
 
+    fn transform_localtimestamp_utc(
+        &self,
+        alias_var: &'static str,
+    ) -> impl Fn(&mut CubeEGraph, Id, &mut Subst) -> bool {
+        let alias_var = var!(alias_var);
+        move |egraph, root, subst| {
+            if egraph.analysis.session_timezone().is_some() {
+                return false;
+            }
+            add_root_original_expr_alias(egraph, root, subst, alias_var)
+        }
+    }
+
+    fn transform_localtimestamp_session(
+        &self,
+        timezone_var: &'static str,
+        data_type_var: &'static str,
+        alias_var: &'static str,
+    ) -> impl Fn(&mut CubeEGraph, Id, &mut Subst) -> bool {
+        let timezone_var = var!(timezone_var);
+        let data_type_var = var!(data_type_var);
+        let alias_var = var!(alias_var);
+        move |egraph, root, subst| {
+            let Some(timezone) = egraph.analysis.session_timezone() else {
+                return false;
+            };
+
+            subst.insert(
+                timezone_var,
+                egraph.add(LogicalPlanLanguage::LiteralExprValue(LiteralExprValue(
+                    ScalarValue::Utf8(Some(timezone.name().to_string())),
+                ))),
+            );
+            subst.insert(
+                data_type_var,
+                egraph.add(LogicalPlanLanguage::CastExprDataType(CastExprDataType(
+                    DataType::Timestamp(TimeUnit::Nanosecond, None),
+                ))),
+            );
+            add_root_original_expr_alias(egraph, root, subst, alias_var)
+        }
+    }
+
+    fn transform_same_timezone(
+        &self,
+        from_tz_var: &'static str,
+        to_tz_var: &'static str,
+        alias_var: &'static str,
+    ) -> impl Fn(&mut CubeEGraph, Id, &mut Subst) -> bool {
+        let from_tz_var = var!(from_tz_var);
+        let to_tz_var = var!(to_tz_var);
+        let alias_var = var!(alias_var);
+        move |egraph, root, subst| {
+            let session_timezone = egraph.analysis.session_timezone().unwrap_or(Tz::UTC);
+            // Same as in CONVERT_TZ, SYSTEM stands for the session time zone
+            let resolve = |value: &ScalarValue| {
+                match value {
+                    ScalarValue::Utf8(Some(name)) if name.eq_ignore_ascii_case("SYSTEM") => {
+                        Some(session_timezone)
+                    }
+                    ScalarValue::Utf8(Some(name)) => parse_timezone(name),
+                    _ => None,
+                }
+                .map(|tz| if is_utc_timezone(&tz) { Tz::UTC } else { tz })
+            };
+
+            let from_tz = var_iter!(egraph[subst[from_tz_var]], LiteralExprValue)
+                .find_map(|value| resolve(value));
+            let to_tz = var_iter!(egraph[subst[to_tz_var]], LiteralExprValue)
+                .find_map(|value| resolve(value));
+
+            match (from_tz, to_tz) {
+                (Some(from_tz), Some(to_tz)) if from_tz == to_tz => {
+                    add_root_original_expr_alias(egraph, root, subst, alias_var)
+                }
+                _ => false,
+            }
+        }
+    }
+
     pub fn transform_root_alias(
         &self,
         alias_var: &'static str,
@@ -12,7 +12,8 @@ use crate::{
         DatabaseVariable, DatabaseVariablesToUpdate,
     },
     sql::{
-        dataframe,
+        dataframe::{self, batches_to_dataframe},
+        parse_bool_setting, parse_timezone,
         statement::{
             ApproximateCountDistinctVisitor, CastReplacer, DateTokenNormalizeReplacer,
             RedshiftDatePartReplacer, SensitiveDataSanitizer, ToTimestampReplacer,
@@ -23,6 +24,7 @@ use crate::{
     transport::{MetaContext, SpanId},
 };
 use datafusion::{
+    dataframe::DataFrame as DFDataFrame,
     logical_plan::{
         plan::{Analyze, Explain, ToStringifiedPlan},
         LogicalPlan, PlanType, ToDFSchema,
@@ -196,7 +198,15 @@ impl QueryRouter {
         variable: &Vec<ast::Ident>,
         span_id: Option<Arc<SpanId>>,
     ) -> CompilationResult<QueryPlan> {
-        let full_variable = variable.iter().map(|v| v.value.to_lowercase()).join("_");
+        let separator = match variable.first() {
+            // Cube specific settings are namespaced: SHOW cube.ungrouped
+            Some(v) if variable.len() > 1 && v.value.eq_ignore_ascii_case("cube") => ".",
+            _ => "_",
+        };
+        let full_variable = variable
+            .iter()
+            .map(|v| v.value.to_lowercase())
+            .join(separator);
         let full_variable = match full_variable.as_str() {
             "transaction_isolation_level" => "transaction_isolation",
             x => x,
@@ -233,7 +243,12 @@ impl QueryRouter {
         analyze: bool,
     ) -> Result<QueryPlan, CompilationError> {
         // TODO span_id ?
+        self.state
+            .update_query_stats(|stats| stats.plan_cost = None);
         let plan = self.plan_query(&statement, &mut None, None).await?;
+        let mut plan_cost = None;
+        self.state
+            .update_query_stats(|stats| plan_cost = stats.plan_cost.take());
 
         match plan {
             QueryPlan::MetaOk(_, _) | QueryPlan::MetaTabular(_, _) => Ok(QueryPlan::MetaTabular(
@@ -279,6 +294,30 @@ impl QueryRouter {
                     })
                 };
 
+                // Cost of the plan chosen by the rewrite is known only here, so plans are
+                // rendered right away and the cost is added as a separate line after them
+                let plan_cost = plan_cost.filter(|plan_cost| plan_cost.cube_scan_nodes() > 0);
+                if let (false, Some(plan_cost)) = (analyze, plan_cost) {
+                    let df = DFDataFrame::new(context.state.clone(), &explain_plan);
+                    let batches = df.collect().await.map_err(|err| {
+                        CompilationError::internal(format!(
+                            "Unable to render explain plan: {}",
+                            err
+                        ))
+                    })?;
+                    let mut frame = batches_to_dataframe(&df.schema().into(), batches)
+                        .map_err(|err| CompilationError::internal(err.to_string()))?;
+                    frame.mut_rows().push(dataframe::Row::new(vec![
+                        dataframe::TableValue::String("cube_plan_cost".to_string()),
+                        dataframe::TableValue::String(plan_cost.to_string()),
+                    ]));
+
+                    return Ok(QueryPlan::MetaTabular(
+                        StatusFlags::empty(),
+                        Box::new(frame),
+                    ));
+                }
+
                 Ok(QueryPlan::DataFusionSelect(explain_plan, context))
             }
         }
@@ -324,6 +363,7 @@ impl QueryRouter {
                                 double_quoted_str.to_string()
                             }
                             ast::Value::Number(number, _) => number.to_string(),
+                            ast::Value::Boolean(value) => value.to_string(),
                             _ => {
                                 return Err(CompilationError::user(format!(
                                     "invalid {} variable format",
@@ -373,6 +413,7 @@ impl QueryRouter {
                                 double_quoted_str.to_string()
                             }
                             ast::Value::Number(number, _) => number.to_string(),
+                            ast::Value::Boolean(value) => value.to_string(),
                             _ => {
                                 return Err(CompilationError::user(format!(
                                     "invalid {} variable format",
@@ -394,11 +435,17 @@ impl QueryRouter {
                         } else {
                             key_value.key.value.to_lowercase()
                         };
-                        global_columns_to_update.push(DatabaseVariable::system(
+                        let variable = DatabaseVariable::system(
                             key.to_lowercase(),
                             ScalarValue::Utf8(Some(value.clone())),
                             None,
-                        ));
+                        );
+                        // Time zone and Cube specific settings are used by queries of the current session only
+                        if key == "time_zone" || key.starts_with("cube.") {
+                            session_columns_to_update.push(variable);
+                        } else {
+                            global_columns_to_update.push(variable);
+                        }
                     } else if is_user_defined_var {
                         let key = key_value.key.value[1..].to_lowercase();
                         session_columns_to_update.push(DatabaseVariable::user_defined(
@@ -414,6 +461,30 @@ impl QueryRouter {
             }
         }
 
+        for v in session_columns_to_update.iter() {
+            if v.name == "timezone" || v.name == "time_zone" {
+                if let ScalarValue::Utf8(Some(value)) = &v.value {
+                    if parse_timezone(value).is_none() {
+                        return Err(CompilationError::user(format!(
+                            "invalid value for parameter \"TimeZone\": \"{}\"",
+                            value
+                        )));
+                    }
+                }
+            }
+
+            if v.name == "cube.ungrouped" {
+                if let ScalarValue::Utf8(Some(value)) = &v.value {
+                    if parse_bool_setting(value).is_none() {
+                        return Err(CompilationError::user(format!(
+                            "invalid value for parameter \"{}\": \"{}\"",
+                            v.name, value
+                        )));
+                    }
+                }
+            }
+        }
+
         let (user_variables, session_columns_to_update): (Vec<_>, Vec<_>) =
             session_columns_to_update.into_iter().partition(|v| {
                 v.name.to_lowercase() == "user" || v.name.to_lowercase() == "current_user"
@@ -460,6 +531,8 @@ impl QueryRouter {
                     })?;
                 self.state
                     .set_auth_context(Some(authenticate_response.context));
+                self.state
+                    .set_security_policy(authenticate_response.security_policy);
             } else {
                 return Err(CompilationError::user(format!(
                     "{:?} is not allowed to switch to '{}'",
@@ -577,6 +650,8 @@ impl QueryRouter {
                 })?;
             self.state
                 .set_auth_context(Some(authenticate_response.context));
+            self.state
+                .set_security_policy(authenticate_response.security_policy);
         }
         Ok(())
     }
@@ -10,10 +10,11 @@ use crate::{
     sql::{
         compiler_cache::CompilerCacheImpl, dataframe::batches_to_dataframe,
         pg_auth_service::PostgresAuthServiceDefaultImpl, AuthContextRef, AuthenticateResponse,
-        HttpAuthContext, ServerManager, Session, SessionManager, SqlAuthService,
+        HttpAuthContext, QueryStatsStore, ServerManager, Session, SessionManager, SessionState,
+        SqlAuthService,
     },
     transport::{
-        CubeMeta, CubeMetaDimension, CubeMetaJoin, CubeMetaMeasure, CubeMetaSegment,
+        CubeCostHints, CubeMeta, CubeMetaDimension, CubeMetaJoin, CubeMetaMeasure, CubeMetaSegment,
         CubeStreamReceiver, LoadRequestMeta, MetaContext, SpanId, SqlGenerator, SqlResponse,
         SqlTemplates, TransportLoadRequestQuery, TransportLoadResponse, TransportService,
     },
@@ -28,12 +29,20 @@ pub mod rewrite_engine;
 #[cfg(test)]
 pub mod test_bi_workarounds;
 #[cfg(test)]
+pub mod test_cost_hints;
+#[cfg(test)]
 pub mod test_cube_join;
 #[cfg(test)]
+pub mod test_cube_scan_modes;
+#[cfg(test)]
 pub mod test_df_execution;
 #[cfg(test)]
 pub mod test_introspection;
 #[cfg(test)]
+pub mod test_prepared_statements;
+#[cfg(test)]
+pub mod test_security_policy;
+#[cfg(test)]
 pub mod test_udfs;
 #[cfg(test)]
 pub mod test_user_change;
@@ -89,6 +98,7 @@ pub fn get_test_meta() -> Vec<CubeMeta> {
                     description: Some("Events count".to_string()),
                     r#type: "number".to_string(),
                     agg_type: Some("count".to_string()),
+                    format: None,
                     meta: None,
                 },
                 CubeMetaMeasure {
@@ -97,6 +107,7 @@ pub fn get_test_meta() -> Vec<CubeMeta> {
                     description: None,
                     r#type: "number".to_string(),
                     agg_type: Some("max".to_string()),
+                    format: None,
                     meta: None,
                 },
                 CubeMetaMeasure {
@@ -105,6 +116,7 @@ pub fn get_test_meta() -> Vec<CubeMeta> {
                     description: None,
                     r#type: "number".to_string(),
                     agg_type: Some("sum".to_string()),
+                    format: None,
                     meta: None,
                 },
                 CubeMetaMeasure {
@@ -113,6 +125,7 @@ pub fn get_test_meta() -> Vec<CubeMeta> {
                     description: None,
                     r#type: "number".to_string(),
                     agg_type: Some("min".to_string()),
+                    format: None,
                     meta: None,
                 },
                 CubeMetaMeasure {
@@ -121,6 +134,7 @@ pub fn get_test_meta() -> Vec<CubeMeta> {
                     description: None,
                     r#type: "number".to_string(),
                     agg_type: Some("avg".to_string()),
+                    format: None,
                     meta: None,
                 },
                 CubeMetaMeasure {
@@ -129,6 +143,7 @@ pub fn get_test_meta() -> Vec<CubeMeta> {
                     description: None,
                     r#type: "number".to_string(),
                     agg_type: Some("countDistinct".to_string()),
+                    format: None,
                     meta: None,
                 },
             ],
@@ -183,6 +198,7 @@ pub fn get_test_meta() -> Vec<CubeMeta> {
                     description: None,
                     r#type: "number".to_string(),
                     agg_type: Some("countDistinct".to_string()),
+                    format: None,
                     meta: None,
                 },
                 CubeMetaMeasure {
@@ -191,6 +207,7 @@ pub fn get_test_meta() -> Vec<CubeMeta> {
                     description: None,
                     r#type: "number".to_string(),
                     agg_type: Some("countDistinctApprox".to_string()),
+                    format: None,
                     meta: None,
                 },
             ],
@@ -213,6 +230,7 @@ pub fn get_test_meta() -> Vec<CubeMeta> {
                 description: None,
                 r#type: "number".to_string(),
                 agg_type: Some("number".to_string()),
+                format: None,
                 meta: None,
             }],
             segments: vec![],
@@ -238,6 +256,7 @@ pub fn get_test_meta() -> Vec<CubeMeta> {
                     agg_type: Some("number".to_string()),
                     title: None,
                     description: None,
+                    format: None,
                     meta: None,
                 })
                 .chain(
@@ -248,6 +267,7 @@ pub fn get_test_meta() -> Vec<CubeMeta> {
                             description: None,
                             r#type: "number".to_string(),
                             agg_type: Some("count".to_string()),
+                            format: None,
                             meta: None,
                         },
                         CubeMetaMeasure {
@@ -256,6 +276,7 @@ pub fn get_test_meta() -> Vec<CubeMeta> {
                             description: None,
                             r#type: "number".to_string(),
                             agg_type: Some("max".to_string()),
+                            format: None,
                             meta: None,
                         },
                         CubeMetaMeasure {
@@ -264,6 +285,7 @@ pub fn get_test_meta() -> Vec<CubeMeta> {
                             description: None,
                             r#type: "number".to_string(),
                             agg_type: Some("min".to_string()),
+                            format: None,
                             meta: None,
                         },
                         CubeMetaMeasure {
@@ -272,6 +294,7 @@ pub fn get_test_meta() -> Vec<CubeMeta> {
                             description: None,
                             r#type: "number".to_string(),
                             agg_type: Some("avg".to_string()),
+                            format: None,
                             meta: None,
                         },
                         CubeMetaMeasure {
@@ -280,6 +303,7 @@ pub fn get_test_meta() -> Vec<CubeMeta> {
                             description: None,
                             r#type: "number".to_string(),
                             agg_type: Some("countDistinct".to_string()),
+                            format: None,
                             meta: None,
                         },
                     ]
@@ -328,6 +352,7 @@ pub fn get_test_meta() -> Vec<CubeMeta> {
                             agg_type: Some("number".to_string()),
                             title: None,
                             description: Some(format!("Test number measure {i}")),
+                            format: None,
                             meta: None,
                         },
                         CubeMetaMeasure {
@@ -336,6 +361,7 @@ pub fn get_test_meta() -> Vec<CubeMeta> {
                             agg_type: Some("max".to_string()),
                             title: None,
                             description: Some(format!("Test max(string) measure {i}")),
+                            format: None,
                             meta: None,
                         },
                         CubeMetaMeasure {
@@ -344,6 +370,7 @@ pub fn get_test_meta() -> Vec<CubeMeta> {
                             agg_type: Some("max".to_string()),
                             title: None,
                             description: Some(format!("Test max(time) measure {i}")),
+                            format: None,
                             meta: None,
                         },
                     ]
@@ -356,6 +383,7 @@ pub fn get_test_meta() -> Vec<CubeMeta> {
                             description: Some("Test count measure".to_string()),
                             r#type: "number".to_string(),
                             agg_type: Some("count".to_string()),
+                            format: None,
                             meta: None,
                         },
                         CubeMetaMeasure {
@@ -364,6 +392,7 @@ pub fn get_test_meta() -> Vec<CubeMeta> {
                             description: Some("Test maxPrice measure".to_string()),
                             r#type: "number".to_string(),
                             agg_type: Some("max".to_string()),
+                            format: None,
                             meta: None,
                         },
                         CubeMetaMeasure {
@@ -372,6 +401,7 @@ pub fn get_test_meta() -> Vec<CubeMeta> {
                             description: Some("Test minPrice measure".to_string()),
                             r#type: "number".to_string(),
                             agg_type: Some("min".to_string()),
+                            format: None,
                             meta: None,
                         },
                         CubeMetaMeasure {
@@ -380,6 +410,7 @@ pub fn get_test_meta() -> Vec<CubeMeta> {
                             description: Some("Test avgPrice measure".to_string()),
                             r#type: "number".to_string(),
                             agg_type: Some("avg".to_string()),
+                            format: None,
                             meta: None,
                         },
                         CubeMetaMeasure {
@@ -388,6 +419,7 @@ pub fn get_test_meta() -> Vec<CubeMeta> {
                             description: Some("Test countDistinct measure".to_string()),
                             r#type: "number".to_string(),
                             agg_type: Some("countDistinct".to_string()),
+                            format: None,
                             meta: None,
                         },
                     ]
@@ -414,6 +446,7 @@ pub fn get_string_cube_meta() -> Vec<CubeMeta> {
             description: None,
             r#type: "string".to_string(),
             agg_type: Some("string".to_string()),
+            format: None,
             meta: None,
         }],
         segments: vec![],
@@ -436,6 +469,7 @@ pub fn get_sixteen_char_member_cube() -> Vec<CubeMeta> {
                 description: None,
                 r#type: "number".to_string(),
                 agg_type: Some("sum".to_string()),
+                format: None,
                 meta: None,
             },
             CubeMetaMeasure {
@@ -444,6 +478,7 @@ pub fn get_sixteen_char_member_cube() -> Vec<CubeMeta> {
                 description: None,
                 r#type: "number".to_string(),
                 agg_type: Some("avg".to_string()),
+                format: None,
                 meta: None,
             },
             CubeMetaMeasure {
@@ -452,6 +487,7 @@ pub fn get_sixteen_char_member_cube() -> Vec<CubeMeta> {
                 description: None,
                 r#type: "number".to_string(),
                 agg_type: Some("count".to_string()),
+                format: None,
                 meta: None,
             },
         ],
@@ -486,7 +522,17 @@ pub fn get_test_tenant_ctx() -> Arc<MetaContext> {
 }
 
 pub fn get_test_tenant_ctx_customized(custom_templates: Vec<(String, String)>) -> Arc<MetaContext> {
-    Arc::new(MetaContext::new(
+    Arc::new(get_test_tenant_meta_context(custom_templates))
+}
+
+pub fn get_test_tenant_ctx_with_cost_hints(
+    cost_hints: HashMap<String, CubeCostHints>,
+) -> Arc<MetaContext> {
+    Arc::new(get_test_tenant_meta_context(vec![]).with_cost_hints(cost_hints))
+}
+
+fn get_test_tenant_meta_context(custom_templates: Vec<(String, String)>) -> MetaContext {
+    MetaContext::new(
         get_test_meta(),
         vec![
             (
@@ -503,7 +549,7 @@ pub fn get_test_tenant_ctx_customized(custom_templates: Vec<(String, String)>) -
             .into_iter()
             .collect(),
         Uuid::new_v4(),
-    ))
+    )
 }
 
 pub fn sql_generator(
@@ -650,11 +696,18 @@ pub async fn get_test_session_with_config(
     .await
 }
 
-async fn get_test_session_with_config_and_transport(
-    protocol: DatabaseProtocol,
+/// Session manager for listeners, which create sessions on their own
+pub fn get_test_session_manager(meta_context: Arc<MetaContext>) -> Arc<SessionManager> {
+    get_test_session_manager_with_config_and_transport(
+        Arc::new(ConfigObjImpl::default()),
+        get_test_transport(meta_context),
+    )
+}
+
+fn get_test_session_manager_with_config_and_transport(
     config_obj: Arc<dyn ConfigObj>,
     test_transport: Arc<dyn TransportService>,
-) -> Arc<Session> {
+) -> Arc<SessionManager> {
     let server = Arc::new(ServerManager::new(
         get_test_auth(),
         test_transport.clone(),
@@ -664,11 +717,27 @@ async fn get_test_session_with_config_and_transport(
         config_obj,
     ));
 
+    Arc::new(SessionManager::new(server))
+}
+
+async fn get_test_session_with_config_and_transport(
+    protocol: DatabaseProtocol,
+    config_obj: Arc<dyn ConfigObj>,
+    test_transport: Arc<dyn TransportService>,
+) -> Arc<Session> {
+    let session_manager =
+        get_test_session_manager_with_config_and_transport(config_obj, test_transport);
+    get_test_session_with_session_manager(protocol, session_manager).await
+}
+
+async fn get_test_session_with_session_manager(
+    protocol: DatabaseProtocol,
+    session_manager: Arc<SessionManager>,
+) -> Arc<Session> {
     let db_name = match &protocol {
         DatabaseProtocol::MySQL => "db",
         _ => "cubedb",
     };
-    let session_manager = Arc::new(SessionManager::new(server.clone()));
     let session = session_manager
         .create_session(protocol, "127.0.0.1".to_string(), 1234, None)
         .await
@@ -706,6 +775,7 @@ pub fn get_test_auth() -> Arc<dyn SqlAuthService> {
                 }),
                 password,
                 skip_password_check: false,
+                security_policy: None,
             })
         }
     }
@@ -939,6 +1009,22 @@ impl TestContext {
         }
     }
 
+    /// Opens another session on the same server, sharing its compiler cache
+    pub async fn new_session(&self) -> Self {
+        let session = get_test_session_with_session_manager(
+            self.session.state.protocol.clone(),
+            self.session.session_manager.clone(),
+        )
+        .await;
+
+        TestContext {
+            meta: self.meta.clone(),
+            transport: self.transport.clone(),
+            config_obj: self.config_obj.clone(),
+            session,
+        }
+    }
+
     pub async fn add_cube_load_mock(
         &self,
         mut req: TransportLoadRequestQuery,
@@ -956,6 +1042,14 @@ impl TestContext {
         self.transport.load_calls().await
     }
 
+    pub fn session_state(&self) -> Arc<SessionState> {
+        self.session.state.clone()
+    }
+
+    pub fn query_stats(&self) -> Arc<QueryStatsStore> {
+        self.session.server.query_stats.clone()
+    }
+
     pub async fn convert_sql_to_cube_query(&self, query: &str) -> CompilationResult<QueryPlan> {
         // TODO push to_string() deeper
         convert_sql_to_cube_query(&query.to_string(), self.meta.clone(), self.session.clone()).await
@@ -0,0 +1,154 @@
+use std::collections::HashMap;
+
+use datafusion::logical_plan::{plan::Extension, LogicalPlan};
+use pretty_assertions::assert_eq;
+
+use crate::{
+    compile::{
+        convert_sql_to_cube_query,
+        engine::df::wrapper::CubeScanWrapperNode,
+        rewrite::rewriter::Rewriter,
+        test::{
+            get_test_session, get_test_tenant_ctx_with_cost_hints, init_testing_logger,
+            utils::LogicalPlanTestUtils,
+        },
+        DatabaseProtocol, QueryPlan,
+    },
+    sql::dataframe::TableValue,
+    transport::CubeCostHints,
+};
+
+async fn convert_sql_with_cost_hints(query: &str, cost_hints: CubeCostHints) -> QueryPlan {
+    let meta_context = get_test_tenant_ctx_with_cost_hints(HashMap::from([(
+        "KibanaSampleDataEcommerce".to_string(),
+        cost_hints,
+    )]));
+
+    convert_sql_to_cube_query(
+        &query.to_string(),
+        meta_context.clone(),
+        get_test_session(DatabaseProtocol::PostgreSQL, meta_context).await,
+    )
+    .await
+    .unwrap()
+}
+
+fn is_cube_scan_wrapper(plan: &LogicalPlan) -> bool {
+    match plan {
+        LogicalPlan::Extension(Extension { node }) => node
+            .as_any()
+            .downcast_ref::<CubeScanWrapperNode>()
+            .is_some(),
+        _ => false,
+    }
+}
+
+#[tokio::test]
+async fn test_pre_aggregated_cube_window_post_processing() {
+    if !Rewriter::sql_push_down_enabled() {
+        return;
+    }
+    init_testing_logger();
+
+    // Grouped load request can be served by a pre-aggregation, window is computed on top of it
+    let query_plan = convert_sql_with_cost_hints(
+        r#"
+        SELECT
+            customer_gender,
+            COUNT(*) AS count,
+            DENSE_RANK() OVER (ORDER BY customer_gender DESC NULLS LAST) AS rank
+        FROM KibanaSampleDataEcommerce
+        GROUP BY customer_gender
+        "#,
+        CubeCostHints {
+            has_pre_aggregations: Some(true),
+            ..CubeCostHints::new()
+        },
+    )
+    .await;
+
+    let logical_plan = query_plan.as_logical_plan();
+    assert!(!is_cube_scan_wrapper(&logical_plan));
+
+    let request = logical_plan.find_cube_scan().request;
+    assert_eq!(
+        request.measures,
+        Some(vec!["KibanaSampleDataEcommerce.count".to_string()])
+    );
+    assert_eq!(
+        request.dimensions,
+        Some(vec!["KibanaSampleDataEcommerce.customer_gender".to_string()])
+    );
+    assert_eq!(request.ungrouped, None);
+}
+
+#[tokio::test]
+async fn test_large_cube_filter_push_down() {
+    if !Rewriter::sql_push_down_enabled() {
+        return;
+    }
+    init_testing_logger();
+
+    // Filter over ungrouped scan would load the whole cube for post-processing
+    let query_plan = convert_sql_with_cost_hints(
+        r#"
+        SELECT customer_gender, notes
+        FROM KibanaSampleDataEcommerce
+        WHERE LOWER(customer_gender) = 'male'
+        "#,
+        CubeCostHints {
+            estimated_row_count: Some(10_000_000),
+            ..CubeCostHints::new()
+        },
+    )
+    .await;
+
+    let logical_plan = query_plan.as_logical_plan();
+    assert!(logical_plan
+        .find_cube_scan_wrapper()
+        .wrapped_sql
+        .unwrap()
+        .sql
+        .contains("LOWER"));
+}
+
+#[tokio::test]
+async fn test_explain_plan_cost() {
+    init_testing_logger();
+
+    for explain in ["EXPLAIN", "EXPLAIN VERBOSE"] {
+        let query_plan = convert_sql_with_cost_hints(
+            &format!(
+                r#"
+                {}
+                SELECT customer_gender, MEASURE(count)
+                FROM KibanaSampleDataEcommerce
+                GROUP BY 1
+                "#,
+                explain
+            ),
+            CubeCostHints::new(),
+        )
+        .await;
+
+        let QueryPlan::MetaTabular(_, frame) = query_plan else {
+            panic!("{} must be rendered with the plan cost", explain);
+        };
+        let plan_types = frame
+            .get_rows()
+            .iter()
+            .map(|row| match &row.values()[0] {
+                TableValue::String(plan_type) => plan_type.clone(),
+                value => panic!("unexpected plan type: {:?}", value),
+            })
+            .collect::<Vec<_>>();
+        assert!(plan_types.contains(&"logical_plan".to_string()));
+        assert!(plan_types.contains(&"physical_plan".to_string()));
+        assert_eq!(plan_types.last(), Some(&"cube_plan_cost".to_string()));
+
+        let TableValue::String(plan_cost) = &frame.get_rows().last().unwrap().values()[1] else {
+            panic!("plan cost must be a string");
+        };
+        assert!(plan_cost.contains("cube_scan_nodes=1"));
+    }
+}
@@ -0,0 +1,247 @@
+use cubeclient::models::{
+    V1LoadRequestQuery, V1LoadResponse, V1LoadResult, V1LoadResultAnnotation,
+};
+use pretty_assertions::assert_eq;
+use serde_json::json;
+
+use crate::compile::{
+    test::{init_testing_logger, utils::LogicalPlanTestUtils, TestContext},
+    DatabaseProtocol,
+};
+
+fn total_load_response(data: Vec<serde_json::Value>, total: i64) -> V1LoadResponse {
+    let mut result = V1LoadResult::new(
+        V1LoadResultAnnotation::new(json!([]), json!([]), json!([]), json!([])),
+        data,
+    );
+    result.total = Some(total);
+
+    V1LoadResponse::new(vec![result])
+}
+
+#[tokio::test]
+async fn test_total_count_push_down() {
+    init_testing_logger();
+
+    let context = TestContext::new(DatabaseProtocol::PostgreSQL).await;
+
+    // language=PostgreSQL
+    let query = r#"
+        SELECT customer_gender, COUNT(*) OVER () AS total_count
+        FROM KibanaSampleDataEcommerce
+        GROUP BY 1
+        LIMIT 2
+        OFFSET 1
+    "#;
+
+    let expected_cube_scan = V1LoadRequestQuery {
+        measures: Some(vec![]),
+        dimensions: Some(vec!["KibanaSampleDataEcommerce.customer_gender".to_string()]),
+        segments: Some(vec![]),
+        order: Some(vec![]),
+        limit: Some(2),
+        offset: Some(1),
+        total: Some(true),
+        ..Default::default()
+    };
+
+    assert_eq!(
+        context
+            .convert_sql_to_cube_query(&query)
+            .await
+            .unwrap()
+            .as_logical_plan()
+            .find_cube_scan()
+            .request,
+        expected_cube_scan
+    );
+
+    context
+        .add_cube_load_mock(
+            expected_cube_scan,
+            total_load_response(
+                vec![
+                    json!({"KibanaSampleDataEcommerce.customer_gender": "female"}),
+                    json!({"KibanaSampleDataEcommerce.customer_gender": "male"}),
+                ],
+                3,
+            ),
+        )
+        .await;
+
+    insta::assert_snapshot!(context.execute_query(query).await.unwrap());
+}
+
+#[tokio::test]
+async fn test_total_count_partitioned_window_not_pushed_down() {
+    init_testing_logger();
+
+    let context = TestContext::new(DatabaseProtocol::PostgreSQL).await;
+
+    // Only COUNT(*) OVER () is a total row count
+    let query_plan = context
+        .convert_sql_to_cube_query(
+            r#"
+            SELECT customer_gender, COUNT(*) OVER (PARTITION BY customer_gender) AS c
+            FROM KibanaSampleDataEcommerce
+            GROUP BY 1
+            LIMIT 2
+            "#,
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(
+        query_plan.as_logical_plan().find_cube_scan().request.total,
+        None
+    );
+}
+
+#[tokio::test]
+async fn test_set_cube_ungrouped() {
+    init_testing_logger();
+
+    let context = TestContext::new(DatabaseProtocol::PostgreSQL).await;
+
+    // language=PostgreSQL
+    let query = r#"
+        SELECT customer_gender, MEASURE(count)
+        FROM KibanaSampleDataEcommerce
+        GROUP BY 1
+    "#;
+
+    let expected_cube_scan = V1LoadRequestQuery {
+        measures: Some(vec!["KibanaSampleDataEcommerce.count".to_string()]),
+        dimensions: Some(vec!["KibanaSampleDataEcommerce.customer_gender".to_string()]),
+        segments: Some(vec![]),
+        order: Some(vec![]),
+        ..Default::default()
+    };
+
+    assert_eq!(
+        context
+            .convert_sql_to_cube_query(&query)
+            .await
+            .unwrap()
+            .as_logical_plan()
+            .find_cube_scan()
+            .request,
+        expected_cube_scan
+    );
+
+    context
+        .execute_query("SET cube.ungrouped = on")
+        .await
+        .unwrap();
+
+    // Aggregation can't be ungrouped without changing the result
+    assert_eq!(
+        context
+            .convert_sql_to_cube_query(&query)
+            .await
+            .unwrap()
+            .as_logical_plan()
+            .find_cube_scan()
+            .request,
+        expected_cube_scan
+    );
+
+    // language=PostgreSQL
+    let ungrouped_query = r#"
+        SELECT customer_gender, notes
+        FROM KibanaSampleDataEcommerce
+    "#;
+    assert_eq!(
+        context
+            .convert_sql_to_cube_query(&ungrouped_query)
+            .await
+            .unwrap()
+            .as_logical_plan()
+            .find_cube_scan()
+            .request
+            .ungrouped,
+        Some(true)
+    );
+
+    assert_eq!(
+        context.execute_query("SHOW cube.ungrouped").await.unwrap(),
+        "+---------+\n\
+         | setting |\n\
+         +---------+\n\
+         | on      |\n\
+         +---------+"
+    );
+
+    context
+        .execute_query("SET cube.ungrouped TO false")
+        .await
+        .unwrap();
+
+    assert_eq!(
+        context
+            .convert_sql_to_cube_query(&query)
+            .await
+            .unwrap()
+            .as_logical_plan()
+            .find_cube_scan()
+            .request,
+        expected_cube_scan
+    );
+}
+
+#[tokio::test]
+async fn test_set_cube_ungrouped_invalid_value() {
+    init_testing_logger();
+
+    let context = TestContext::new(DatabaseProtocol::PostgreSQL).await;
+
+    let err = context
+        .execute_query("SET cube.ungrouped = 'sometimes'")
+        .await
+        .unwrap_err();
+    assert!(err
+        .message
+        .contains("invalid value for parameter \"cube.ungrouped\": \"sometimes\""));
+}
+
+#[tokio::test]
+async fn test_set_cube_ungrouped_mysql() {
+    init_testing_logger();
+
+    let context = TestContext::new(DatabaseProtocol::MySQL).await;
+
+    context
+        .execute_query("SET cube.ungrouped = 1")
+        .await
+        .unwrap();
+
+    let query_plan = context
+        .convert_sql_to_cube_query("SELECT customer_gender FROM KibanaSampleDataEcommerce")
+        .await
+        .unwrap();
+
+    assert_eq!(
+        query_plan
+            .as_logical_plan()
+            .find_cube_scan()
+            .request
+            .ungrouped,
+        Some(true)
+    );
+
+    let query_plan = context
+        .convert_sql_to_cube_query(
+            "SELECT customer_gender FROM KibanaSampleDataEcommerce GROUP BY 1",
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(
+        query_plan
+            .as_logical_plan()
+            .find_cube_scan()
+            .request
+            .ungrouped,
+        None
+    );
+}
@@ -0,0 +1,286 @@
+use pg_srv::{BindValue, PgTypeId};
+use pretty_assertions::assert_eq;
+
+use crate::{
+    compile::{
+        convert_statement_to_cube_query,
+        parser::parse_sql_to_statement,
+        test::{init_testing_logger, utils::LogicalPlanTestUtils, TestContext},
+        DatabaseProtocol, QueryPlan,
+    },
+    sql::{
+        generic_plan::{infer_parameter_types, GenericPlan},
+        statement::{GenericPlanPlaceholderReplacer, PostgresStatementParamsBinder},
+    },
+};
+
+async fn prepare_generic_plan(
+    context: &TestContext,
+    query: &str,
+    parameters: &mut Vec<PgTypeId>,
+) -> Option<GenericPlan> {
+    let stmt = parse_sql_to_statement(&query.to_string(), DatabaseProtocol::PostgreSQL, &mut None)
+        .unwrap();
+    let plan = convert_statement_to_cube_query(
+        GenericPlanPlaceholderReplacer::new()
+            .replace(stmt.clone())
+            .unwrap(),
+        context.meta.clone(),
+        context.session.clone(),
+        &mut None,
+        None,
+    )
+    .await
+    .unwrap();
+
+    infer_parameter_types(&plan, &context.meta, parameters);
+    GenericPlan::try_new(
+        &stmt,
+        plan,
+        parameters.len(),
+        &context.meta,
+        &context.session.state,
+    )
+}
+
+async fn convert_bound_statement(
+    context: &TestContext,
+    query: &str,
+    values: Vec<BindValue>,
+) -> QueryPlan {
+    let mut stmt =
+        parse_sql_to_statement(&query.to_string(), DatabaseProtocol::PostgreSQL, &mut None)
+            .unwrap();
+    PostgresStatementParamsBinder::new(values)
+        .bind(&mut stmt)
+        .unwrap();
+
+    convert_statement_to_cube_query(
+        stmt,
+        context.meta.clone(),
+        context.session.clone(),
+        &mut None,
+        None,
+    )
+    .await
+    .unwrap()
+}
+
+#[tokio::test]
+async fn test_generic_plan_bind() {
+    init_testing_logger();
+
+    let context = TestContext::new(DatabaseProtocol::PostgreSQL).await;
+
+    // language=PostgreSQL
+    let query = r#"
+        SELECT customer_gender, MEASURE(count)
+        FROM KibanaSampleDataEcommerce
+        WHERE customer_gender = $1 OR customer_gender IN ($2, $3)
+        GROUP BY 1
+    "#;
+
+    let mut parameters = vec![PgTypeId::TEXT; 3];
+    let generic_plan = prepare_generic_plan(&context, query, &mut parameters)
+        .await
+        .expect("generic plan must be created");
+    assert_eq!(parameters, vec![PgTypeId::TEXT; 3]);
+
+    for values in [
+        vec!["female", "male", "other"],
+        vec!["male", "female", "female"],
+    ] {
+        let values = values
+            .into_iter()
+            .map(|v| BindValue::String(v.to_string()))
+            .collect::<Vec<_>>();
+
+        let bound_plan = generic_plan
+            .bind(
+                &values,
+                context.meta.compiler_id,
+                &context.session.state,
+                None,
+            )
+            .expect("generic plan must be bound");
+        let expected_plan = convert_bound_statement(&context, query, values).await;
+
+        assert_eq!(
+            bound_plan.as_logical_plan().find_cube_scan().request,
+            expected_plan.as_logical_plan().find_cube_scan().request
+        );
+    }
+
+    // NULL is rewritten to another filter
+    assert!(generic_plan
+        .bind(
+            &[
+                BindValue::Null,
+                BindValue::String("male".to_string()),
+                BindValue::String("female".to_string()),
+            ],
+            context.meta.compiler_id,
+            &context.session.state,
+            None,
+        )
+        .is_none());
+
+    // Plan depends on session variables
+    context
+        .execute_query("SET cube.ungrouped = on")
+        .await
+        .unwrap();
+    assert!(generic_plan
+        .bind(
+            &[
+                BindValue::String("female".to_string()),
+                BindValue::String("male".to_string()),
+                BindValue::String("female".to_string()),
+            ],
+            context.meta.compiler_id,
+            &context.session.state,
+            None,
+        )
+        .is_none());
+}
+
+#[tokio::test]
+async fn test_generic_plan_value_dependent_rewrite() {
+    init_testing_logger();
+
+    let context = TestContext::new(DatabaseProtocol::PostgreSQL).await;
+
+    // LIKE is rewritten depending on the pattern
+    let mut parameters = vec![PgTypeId::TEXT];
+    assert!(prepare_generic_plan(
+        &context,
+        r#"
+        SELECT customer_gender, MEASURE(count)
+        FROM KibanaSampleDataEcommerce
+        WHERE customer_gender LIKE $1
+        GROUP BY 1
+        "#,
+        &mut parameters,
+    )
+    .await
+    .is_none());
+
+    // Relative dates are evaluated during the rewrite
+    for condition in [
+        "order_date > NOW() - INTERVAL '1 day'",
+        "order_date > 'now'::timestamp - INTERVAL '1 day'",
+        "order_date > CURRENT_DATE",
+    ] {
+        let mut parameters = vec![PgTypeId::TEXT];
+        assert!(
+            prepare_generic_plan(
+                &context,
+                &format!(
+                    r#"
+                    SELECT customer_gender, MEASURE(count)
+                    FROM KibanaSampleDataEcommerce
+                    WHERE customer_gender = $1 AND {}
+                    GROUP BY 1
+                    "#,
+                    condition
+                ),
+                &mut parameters,
+            )
+            .await
+            .is_none(),
+            "generic plan must not be created for: {}",
+            condition
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_generic_plan_parameter_types() {
+    init_testing_logger();
+
+    let context = TestContext::new(DatabaseProtocol::PostgreSQL).await;
+
+    let mut parameters = vec![PgTypeId::TEXT; 4];
+    prepare_generic_plan(
+        &context,
+        r#"
+        SELECT customer_gender, MEASURE(count)
+        FROM KibanaSampleDataEcommerce
+        WHERE customer_gender = $1 AND order_date >= $2 AND taxful_total_price > $3 AND has_subscription = $4
+        GROUP BY 1
+        "#,
+        &mut parameters,
+    )
+    .await;
+    assert_eq!(
+        parameters,
+        vec![
+            PgTypeId::TEXT,
+            PgTypeId::TIMESTAMP,
+            PgTypeId::FLOAT8,
+            PgTypeId::BOOL
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_generic_plan_bind_timestamp() {
+    init_testing_logger();
+
+    let context = TestContext::new(DatabaseProtocol::PostgreSQL).await;
+
+    let mut parameters = vec![PgTypeId::TEXT];
+    let generic_plan = prepare_generic_plan(
+        &context,
+        r#"
+        SELECT customer_gender, MEASURE(count)
+        FROM KibanaSampleDataEcommerce
+        WHERE order_date >= $1
+        GROUP BY 1
+        "#,
+        &mut parameters,
+    )
+    .await
+    .expect("generic plan must be created");
+    assert_eq!(parameters, vec![PgTypeId::TIMESTAMP]);
+
+    for (value, expected) in [
+        ("2024-01-01", "2024-01-01T00:00:00.000Z"),
+        ("2024-01-01 10:00:00", "2024-01-01T10:00:00.000Z"),
+        ("2024-01-01T10:00:00.123456", "2024-01-01T10:00:00.123Z"),
+        ("2024-01-01 10:00:00+02", "2024-01-01T08:00:00.000Z"),
+        ("2024-01-01T10:00:00-05:30", "2024-01-01T15:30:00.000Z"),
+    ] {
+        let bound_plan = generic_plan
+            .bind(
+                &[BindValue::String(value.to_string())],
+                context.meta.compiler_id,
+                &context.session.state,
+                None,
+            )
+            .expect("generic plan must be bound");
+        let filters = bound_plan
+            .as_logical_plan()
+            .find_cube_scan()
+            .request
+            .filters
+            .unwrap();
+
+        assert_eq!(
+            filters[0].values,
+            Some(vec![expected.to_string()]),
+            "bound value: {}",
+            value
+        );
+    }
+
+    // Invalid timestamp is planned as usual and fails there
+    assert!(generic_plan
+        .bind(
+            &[BindValue::String("not a timestamp".to_string())],
+            context.meta.compiler_id,
+            &context.session.state,
+            None,
+        )
+        .is_none());
+}
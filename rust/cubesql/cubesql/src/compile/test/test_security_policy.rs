@@ -0,0 +1,126 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
+
+use cubeclient::models::{V1LoadRequestQuery, V1LoadRequestQueryFilterItem};
+use pretty_assertions::assert_eq;
+
+use crate::{
+    compile::{
+        test::{init_testing_logger, utils::LogicalPlanTestUtils, TestContext},
+        DatabaseProtocol,
+    },
+    sql::{CubeSecurityPolicy, SecurityPolicy},
+};
+
+fn tenant_filter() -> V1LoadRequestQueryFilterItem {
+    V1LoadRequestQueryFilterItem {
+        member: Some("KibanaSampleDataEcommerce.notes".to_string()),
+        operator: Some("equals".to_string()),
+        values: Some(vec!["tenant-1".to_string()]),
+        or: None,
+        and: None,
+    }
+}
+
+async fn context_with_security_policy(db: DatabaseProtocol) -> TestContext {
+    let context = TestContext::new(db).await;
+    context
+        .session
+        .state
+        .set_security_policy(Some(Arc::new(SecurityPolicy::new(HashMap::from([(
+            "KibanaSampleDataEcommerce".to_string(),
+            CubeSecurityPolicy {
+                filters: vec![tenant_filter()],
+                visible_members: Some(HashSet::from([
+                    "KibanaSampleDataEcommerce.count".to_string(),
+                    "KibanaSampleDataEcommerce.customer_gender".to_string(),
+                    "KibanaSampleDataEcommerce.order_date".to_string(),
+                ])),
+            },
+        )])))));
+
+    context
+}
+
+#[tokio::test]
+async fn test_security_policy_filters() {
+    init_testing_logger();
+
+    let context = context_with_security_policy(DatabaseProtocol::PostgreSQL).await;
+
+    let query_plan = context
+        .convert_sql_to_cube_query(
+            r#"
+            SELECT customer_gender, MEASURE(count)
+            FROM KibanaSampleDataEcommerce
+            WHERE customer_gender = 'female'
+            GROUP BY 1
+            "#,
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(
+        query_plan.as_logical_plan().find_cube_scan().request,
+        V1LoadRequestQuery {
+            measures: Some(vec!["KibanaSampleDataEcommerce.count".to_string()]),
+            dimensions: Some(vec!["KibanaSampleDataEcommerce.customer_gender".to_string()]),
+            segments: Some(vec![]),
+            order: Some(vec![]),
+            filters: Some(vec![
+                V1LoadRequestQueryFilterItem {
+                    member: Some("KibanaSampleDataEcommerce.customer_gender".to_string()),
+                    operator: Some("equals".to_string()),
+                    values: Some(vec!["female".to_string()]),
+                    or: None,
+                    and: None,
+                },
+                tenant_filter(),
+            ]),
+            ..Default::default()
+        }
+    );
+}
+
+#[tokio::test]
+async fn test_security_policy_invisible_members() {
+    init_testing_logger();
+
+    let context = context_with_security_policy(DatabaseProtocol::PostgreSQL).await;
+
+    assert!(context
+        .convert_sql_to_cube_query("SELECT notes FROM KibanaSampleDataEcommerce")
+        .await
+        .is_err());
+
+    let columns = context
+        .execute_query(
+            r#"
+            SELECT column_name
+            FROM information_schema.columns
+            WHERE table_name = 'KibanaSampleDataEcommerce'
+            ORDER BY column_name
+            "#,
+        )
+        .await
+        .unwrap();
+    assert!(columns.contains("customer_gender"));
+    assert!(!columns.contains("notes"));
+    assert!(!columns.contains("maxPrice"));
+
+    let attributes = context
+        .execute_query(
+            r#"
+            SELECT a.attname
+            FROM pg_catalog.pg_attribute a
+            JOIN pg_catalog.pg_class c ON c.oid = a.attrelid
+            WHERE c.relname = 'KibanaSampleDataEcommerce'
+            "#,
+        )
+        .await
+        .unwrap();
+    assert!(attributes.contains("customer_gender"));
+    assert!(!attributes.contains("notes"));
+}
@@ -288,6 +288,29 @@ async fn test_convert_tz() -> Result<(), CubeError> {
     Ok(())
 }
 
+#[tokio::test]
+async fn test_convert_tz_named_zones() -> Result<(), CubeError> {
+    assert_eq!(
+        execute_query(
+            "select \
+                convert_tz('2021-12-08T15:50:14.337Z'::timestamp, 'UTC', 'Europe/Berlin') as r1, \
+                convert_tz('2021-07-08T15:50:14.337Z'::timestamp, 'America/New_York', '+03:00') as r2, \
+                convert_tz('2021-07-08T15:50:14.337Z'::timestamp, 'UTC', 'Unknown/Zone') as r3;
+            "
+            .to_string(),
+            DatabaseProtocol::MySQL
+        )
+        .await?,
+        "+-------------------------+-------------------------+------+\n\
+            | r1                      | r2                      | r3   |\n\
+            +-------------------------+-------------------------+------+\n\
+            | 2021-12-08T16:50:14.337 | 2021-07-08T22:50:14.337 | NULL |\n\
+            +-------------------------+-------------------------+------+"
+    );
+
+    Ok(())
+}
+
 #[tokio::test]
 async fn test_pg_backend_pid() -> Result<(), CubeError> {
     insta::assert_snapshot!(
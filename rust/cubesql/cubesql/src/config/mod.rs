@@ -1,6 +1,8 @@
 pub mod injection;
 pub mod processing_loop;
 
+#[cfg(feature = "flight-sql")]
+use crate::sql::FlightSqlServer;
 use crate::{
     config::{
         injection::{DIService, Injector},
@@ -8,7 +10,8 @@ use crate::{
     },
     sql::{
         pg_auth_service::{PostgresAuthService, PostgresAuthServiceDefaultImpl},
-        PostgresServer, ServerManager, SessionManager, SqlAuthDefaultImpl, SqlAuthService,
+        MySqlServer, PostgresServer, ServerManager, SessionManager, SqlAuthDefaultImpl,
+        SqlAuthService,
     },
     transport::{HttpTransport, TransportService},
     CubeError,
@@ -49,6 +52,17 @@ impl CubeServices {
     pub async fn spawn_processing_loops(&self) -> Result<Vec<LoopHandle>, CubeError> {
         let mut futures = Vec::new();
 
+        if self.injector.has_service_typed::<MySqlServer>().await {
+            let mysql_server = self.injector.get_service_typed::<MySqlServer>().await;
+            futures.push(tokio::spawn(async move {
+                if let Err(e) = mysql_server.processing_loop().await {
+                    error!("{}", e.to_string());
+                };
+
+                Ok(())
+            }));
+        }
+
         if self.injector.has_service_typed::<PostgresServer>().await {
             let postgres_server = self.injector.get_service_typed::<PostgresServer>().await;
             futures.push(tokio::spawn(async move {
@@ -60,6 +74,18 @@ impl CubeServices {
             }));
         }
 
+        #[cfg(feature = "flight-sql")]
+        if self.injector.has_service_typed::<FlightSqlServer>().await {
+            let flight_sql_server = self.injector.get_service_typed::<FlightSqlServer>().await;
+            futures.push(tokio::spawn(async move {
+                if let Err(e) = flight_sql_server.processing_loop().await {
+                    error!("{}", e.to_string());
+                };
+
+                Ok(())
+            }));
+        }
+
         Ok(futures)
     }
 
@@ -67,6 +93,14 @@ impl CubeServices {
         &self,
         shutdown_mode: ShutdownMode,
     ) -> Result<(), CubeError> {
+        if self.injector.has_service_typed::<MySqlServer>().await {
+            self.injector
+                .get_service_typed::<MySqlServer>()
+                .await
+                .stop_processing(shutdown_mode)
+                .await?;
+        }
+
         if self.injector.has_service_typed::<PostgresServer>().await {
             self.injector
                 .get_service_typed::<PostgresServer>()
@@ -75,6 +109,15 @@ impl CubeServices {
                 .await?;
         }
 
+        #[cfg(feature = "flight-sql")]
+        if self.injector.has_service_typed::<FlightSqlServer>().await {
+            self.injector
+                .get_service_typed::<FlightSqlServer>()
+                .await
+                .stop_processing(shutdown_mode)
+                .await?;
+        }
+
         Ok(())
     }
 }
@@ -88,8 +131,14 @@ pub struct Config {
 pub trait ConfigObj: DIService + Debug {
     fn bind_address(&self) -> &Option<String>;
 
+    fn mysql_server_enabled(&self) -> bool;
+
     fn postgres_bind_address(&self) -> &Option<String>;
 
+    fn flight_sql_bind_address(&self) -> &Option<String>;
+
+    fn flight_sql_session_idle_secs(&self) -> u64;
+
     fn query_timeout(&self) -> u64;
 
     fn nonce(&self) -> &Option<Vec<u8>>;
@@ -117,12 +166,19 @@ pub trait ConfigObj: DIService + Debug {
     fn no_implicit_order(&self) -> bool;
 
     fn top_down_extractor(&self) -> bool;
+
+    fn query_stats_max_entries(&self) -> usize;
+
+    fn enable_generic_plan_cache(&self) -> bool;
 }
 
 #[derive(Debug, Clone)]
 pub struct ConfigObjImpl {
     pub bind_address: Option<String>,
+    pub mysql_server_enabled: bool,
     pub postgres_bind_address: Option<String>,
+    pub flight_sql_bind_address: Option<String>,
+    pub flight_sql_session_idle_secs: u64,
     pub nonce: Option<Vec<u8>>,
     pub query_timeout: u64,
     pub auth_expire_secs: u64,
@@ -138,6 +194,8 @@ pub struct ConfigObjImpl {
     pub max_sessions: usize,
     pub no_implicit_order: bool,
     pub top_down_extractor: bool,
+    pub query_stats_max_entries: usize,
+    pub enable_generic_plan_cache: bool,
 }
 
 impl ConfigObjImpl {
@@ -153,9 +211,15 @@ impl ConfigObjImpl {
                     .ok()
                     .map(|v| format!("0.0.0.0:{}", v.parse::<u16>().unwrap()))
             }),
+            // MySQL protocol is opt-in, CUBESQL_PORT alone doesn't start the listener
+            mysql_server_enabled: env_parse("CUBESQL_MYSQL_SERVER_ENABLED", false),
             postgres_bind_address: env::var("CUBESQL_PG_PORT")
                 .ok()
                 .map(|port| format!("0.0.0.0:{}", port.parse::<u16>().unwrap())),
+            flight_sql_bind_address: env::var("CUBESQL_FLIGHT_SQL_PORT")
+                .ok()
+                .map(|port| format!("0.0.0.0:{}", port.parse::<u16>().unwrap())),
+            flight_sql_session_idle_secs: env_parse("CUBESQL_FLIGHT_SQL_SESSION_IDLE_SECS", 1800),
             nonce: None,
             query_timeout,
             timezone: Some("UTC".to_string()),
@@ -176,6 +240,8 @@ impl ConfigObjImpl {
             max_sessions: env_parse("CUBEJS_MAX_SESSIONS", 1024),
             no_implicit_order: env_parse("CUBESQL_SQL_NO_IMPLICIT_ORDER", true),
             top_down_extractor: env_parse("CUBESQL_TOP_DOWN_EXTRACTOR", true),
+            query_stats_max_entries: env_parse("CUBESQL_QUERY_STATS_MAX_ENTRIES", 5000),
+            enable_generic_plan_cache: env_parse("CUBESQL_GENERIC_PLAN_CACHE", true),
         }
     }
 }
@@ -187,10 +253,22 @@ impl ConfigObj for ConfigObjImpl {
         &self.bind_address
     }
 
+    fn mysql_server_enabled(&self) -> bool {
+        self.mysql_server_enabled
+    }
+
     fn postgres_bind_address(&self) -> &Option<String> {
         &self.postgres_bind_address
     }
 
+    fn flight_sql_bind_address(&self) -> &Option<String> {
+        &self.flight_sql_bind_address
+    }
+
+    fn flight_sql_session_idle_secs(&self) -> u64 {
+        self.flight_sql_session_idle_secs
+    }
+
     fn nonce(&self) -> &Option<Vec<u8>> {
         &self.nonce
     }
@@ -246,6 +324,14 @@ impl ConfigObj for ConfigObjImpl {
     fn top_down_extractor(&self) -> bool {
         self.top_down_extractor
     }
+
+    fn query_stats_max_entries(&self) -> usize {
+        self.query_stats_max_entries
+    }
+
+    fn enable_generic_plan_cache(&self) -> bool {
+        self.enable_generic_plan_cache
+    }
 }
 
 impl Config {
@@ -263,7 +349,10 @@ impl Config {
             injector: Injector::new(),
             config_obj: Arc::new(ConfigObjImpl {
                 bind_address: None,
+                mysql_server_enabled: false,
                 postgres_bind_address: None,
+                flight_sql_bind_address: None,
+                flight_sql_session_idle_secs: 1800,
                 nonce: None,
                 query_timeout,
                 auth_expire_secs: 60,
@@ -279,6 +368,8 @@ impl Config {
                 max_sessions: 1024,
                 no_implicit_order: true,
                 top_down_extractor: true,
+                query_stats_max_entries: 5000,
+                enable_generic_plan_cache: true,
             }),
         }
     }
@@ -356,6 +447,18 @@ impl Config {
             })
             .await;
 
+        if self.config_obj.mysql_server_enabled() && self.config_obj.bind_address().is_some() {
+            self.injector
+                .register_typed::<MySqlServer, _, _, _>(|i| async move {
+                    let config = i.get_service_typed::<dyn ConfigObj>().await;
+                    MySqlServer::new(
+                        config.bind_address().as_ref().unwrap().to_string(),
+                        i.get_service_typed().await,
+                    )
+                })
+                .await;
+        }
+
         if self.config_obj.postgres_bind_address().is_some() {
             self.injector
                 .register_typed::<PostgresServer, _, _, _>(|i| async move {
@@ -367,6 +470,24 @@ impl Config {
                 })
                 .await;
         }
+
+        #[cfg(feature = "flight-sql")]
+        if self.config_obj.flight_sql_bind_address().is_some() {
+            self.injector
+                .register_typed::<FlightSqlServer, _, _, _>(|i| async move {
+                    let config = i.get_service_typed::<dyn ConfigObj>().await;
+                    FlightSqlServer::new(
+                        config
+                            .flight_sql_bind_address()
+                            .as_ref()
+                            .unwrap()
+                            .to_string(),
+                        std::time::Duration::from_secs(config.flight_sql_session_idle_secs()),
+                        i.get_service_typed().await,
+                    )
+                })
+                .await;
+        }
     }
 
     pub async fn cube_services(&self) -> CubeServices {
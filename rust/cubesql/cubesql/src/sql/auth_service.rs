@@ -2,12 +2,17 @@ use std::{any::Any, env, fmt::Debug, sync::Arc};
 
 use async_trait::async_trait;
 
-use crate::CubeError;
+use crate::{sql::SecurityPolicyRef, CubeError};
 
 // We cannot use generic here. It's why there is this trait
 // Any type will allow us to split (with downcast) auth context into HTTP (standalone) or Native
 pub trait AuthContext: Debug + Send + Sync {
     fn as_any(&self) -> &dyn Any;
+
+    /// Superusers can see and reset statistics of statements executed by other users
+    fn is_superuser(&self) -> bool {
+        false
+    }
 }
 
 pub type AuthContextRef = Arc<dyn AuthContext>;
@@ -29,6 +34,7 @@ pub struct AuthenticateResponse {
     pub context: AuthContextRef,
     pub password: Option<String>,
     pub skip_password_check: bool,
+    pub security_policy: Option<SecurityPolicyRef>,
 }
 
 #[async_trait]
@@ -63,6 +69,7 @@ impl SqlAuthService for SqlAuthDefaultImpl {
             }),
             password,
             skip_password_check: false,
+            security_policy: None,
         })
     }
 }
@@ -8,13 +8,14 @@ use crate::{
     config::ConfigObj,
     sql::AuthContextRef,
     transport::{MetaContext, TransportService},
-    utils::egraph_hash,
+    utils::{egraph_hash, ShaHasher},
     CubeError, MutexAsync, RWLockAsync,
 };
 use async_trait::async_trait;
 use datafusion::scalar::ScalarValue;
 use lru::LruCache;
-use std::{collections::HashMap, fmt::Debug, num::NonZeroUsize, sync::Arc};
+use sha2::Digest;
+use std::{collections::HashMap, fmt::Debug, hash::Hash, num::NonZeroUsize, sync::Arc};
 use uuid::Uuid;
 
 #[async_trait]
@@ -118,7 +119,7 @@ impl CompilerCache for CompilerCacheImpl {
         parameterized_graph: CubeEGraph,
         qtrace: &mut Option<Qtrace>,
     ) -> Result<CubeEGraph, CubeError> {
-        let graph_key = egraph_hash(&parameterized_graph, None);
+        let graph_key = rewrite_cache_key(&parameterized_graph, None, &cube_context);
 
         let cache_entry_clone = Arc::clone(&cache_entry);
         let mut rewrites_cache_lock = cache_entry.parameterized_cache.lock().await;
@@ -150,7 +151,7 @@ impl CompilerCache for CompilerCacheImpl {
                 .await?);
         }
 
-        let graph_key = egraph_hash(&input_plan, Some(param_values));
+        let graph_key = rewrite_cache_key(&input_plan, Some(param_values), &cube_context);
 
         let cache_entry_clone = Arc::clone(&cache_entry);
         let mut rewrites_cache_lock = cache_entry.queries_cache.lock().await;
@@ -234,3 +235,20 @@ impl CompilerCacheImpl {
         }
     }
 }
+
+/// Date rules depend on the time zone of the session, so graphs rewritten for one time zone
+/// can't be reused by sessions in another one
+fn rewrite_cache_key(
+    graph: &CubeEGraph,
+    params: Option<&HashMap<usize, ScalarValue>>,
+    cube_context: &CubeContext,
+) -> [u8; 32] {
+    let mut hasher = ShaHasher::new();
+    egraph_hash(graph, params).hash(&mut hasher);
+    cube_context
+        .session_state
+        .timezone()
+        .map(|tz| tz.name())
+        .hash(&mut hasher);
+    hasher.take_hasher().finalize().into()
+}
@@ -47,6 +47,15 @@ pub fn defaults() -> DatabaseVariables {
         ),
     );
 
+    variables.insert(
+        "cube.ungrouped".to_string(),
+        DatabaseVariable::system(
+            "cube.ungrouped".to_string(),
+            ScalarValue::Utf8(Some("off".to_string())),
+            None,
+        ),
+    );
+
     variables.insert(
         "tx_isolation".to_string(),
         DatabaseVariable::system(
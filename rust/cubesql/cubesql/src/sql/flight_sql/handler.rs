@@ -0,0 +1,1443 @@
+use std::{
+    collections::HashMap,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+use arrow_flight::{
+    flight_service_server::FlightService,
+    sql::{
+        server::FlightSqlService, ActionClosePreparedStatementRequest,
+        ActionCreatePreparedStatementRequest, ActionCreatePreparedStatementResult,
+        CommandGetCatalogs, CommandGetCrossReference, CommandGetDbSchemas, CommandGetExportedKeys,
+        CommandGetImportedKeys, CommandGetPrimaryKeys, CommandGetSqlInfo, CommandGetTableTypes,
+        CommandGetTables, CommandPreparedStatementQuery, CommandPreparedStatementUpdate,
+        CommandStatementQuery, CommandStatementUpdate, ProstMessageExt, SqlInfo,
+        TicketStatementQuery,
+    },
+    utils::{flight_data_from_arrow_batch, flight_data_to_arrow_batch},
+    Action, FlightData, FlightDescriptor, FlightEndpoint, FlightInfo, HandshakeRequest,
+    HandshakeResponse, IpcMessage, SchemaAsIpc, Ticket,
+};
+use datafusion::{
+    arrow::{
+        array::{
+            Array, ArrayRef, BooleanArray, BooleanBuilder, Float32Array, Float64Array,
+            Float64Builder, Int16Array, Int32Array, Int64Array, Int64Builder, Int8Array,
+            StringArray, StringBuilder,
+        },
+        datatypes::{DataType, Field, Schema, SchemaRef},
+        ipc::writer::IpcWriteOptions,
+        record_batch::RecordBatch,
+    },
+    dataframe::DataFrame as DFDataFrame,
+    physical_plan::SendableRecordBatchStream,
+};
+use futures::{Stream, StreamExt};
+use log::{debug, trace};
+use pg_srv::BindValue;
+use prost::Message;
+use sqlparser::ast;
+use tokio::sync::RwLock;
+use tonic::{metadata::MetadataValue, Request, Response, Status, Streaming};
+use uuid::Uuid;
+
+use crate::{
+    compile::{
+        convert_statement_to_cube_query, parser::parse_sql_to_statement, CompilationError,
+        DatabaseProtocol, QueryPlan,
+    },
+    sql::{
+        dataframe::{DataFrame, TableValue},
+        statement::{
+            PostgresStatementParamsBinder, PostgresStatementParamsFinder,
+            StatementPlaceholderReplacer,
+        },
+        ColumnType, QueryFingerprint, Session, SessionManager,
+    },
+    CubeError,
+};
+
+type FlightDataStream = Pin<Box<dyn Stream<Item = Result<FlightData, Status>> + Send + 'static>>;
+
+/// Prepared statement created by `ActionCreatePreparedStatementRequest`
+struct PreparedStatement {
+    // Statement is accessible only from the session which has created it
+    connection_id: u32,
+    query: String,
+    parameters: usize,
+    values: Vec<BindValue>,
+}
+
+/// Session issued by the handshake
+struct FlightSession {
+    session: Arc<Session>,
+    last_used: Mutex<Instant>,
+    // Result streams of DoGet, session isn't evicted while they are sent
+    active_queries: AtomicUsize,
+}
+
+impl FlightSession {
+    fn new(session: Arc<Session>) -> Self {
+        Self {
+            session,
+            last_used: Mutex::new(Instant::now()),
+            active_queries: AtomicUsize::new(0),
+        }
+    }
+
+    fn touch(&self) {
+        *self
+            .last_used
+            .lock()
+            .expect("failed to unlock last_used for touch") = Instant::now();
+    }
+
+    fn is_idle(&self, timeout: Duration) -> bool {
+        self.active_queries.load(Ordering::SeqCst) == 0
+            && self
+                .last_used
+                .lock()
+                .expect("failed to unlock last_used for is_idle")
+                .elapsed()
+                >= timeout
+    }
+}
+
+/// Ends the query when its result stream is finished or dropped by the client
+struct QueryGuard {
+    flight_session: Arc<FlightSession>,
+    fingerprint: Option<QueryFingerprint>,
+    start_time: Instant,
+    completed: bool,
+}
+
+impl QueryGuard {
+    fn new(flight_session: Arc<FlightSession>, fingerprint: QueryFingerprint) -> Self {
+        flight_session.active_queries.fetch_add(1, Ordering::SeqCst);
+
+        Self {
+            flight_session,
+            fingerprint: Some(fingerprint),
+            start_time: Instant::now(),
+            completed: false,
+        }
+    }
+}
+
+impl Drop for QueryGuard {
+    fn drop(&mut self) {
+        let session = &self.flight_session.session;
+        session.state.end_query();
+        if let Some(fingerprint) = self.fingerprint.take() {
+            // Stream which is dropped before the end is recorded as failed
+            record_query_stats(session, fingerprint, self.start_time, !self.completed);
+        }
+
+        self.flight_session.touch();
+        self.flight_session
+            .active_queries
+            .fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// Flight SQL service backed by cubesql sessions.
+///
+/// Handshake authenticates the user with `SqlAuthService` (Basic authorization) and issues a
+/// bearer token, which is bound to the session. Flight SQL doesn't have a way to close sessions,
+/// so clients are expected to reuse the token, and sessions which are idle for longer than
+/// `session_idle_timeout` are closed together with their tokens and prepared statements.
+///
+/// Queries are parsed with the PostgreSQL dialect and planned by `QueryRouter`, results of Cube
+/// scans are sent as Arrow record batches without any conversion.
+pub struct FlightSqlHandler {
+    session_manager: Arc<SessionManager>,
+    session_idle_timeout: Duration,
+    // Token -> Session
+    sessions: RwLock<HashMap<String, Arc<FlightSession>>>,
+    // Handle -> Prepared statement
+    statements: RwLock<HashMap<Vec<u8>, PreparedStatement>>,
+}
+
+impl FlightSqlHandler {
+    pub fn new(session_manager: Arc<SessionManager>, session_idle_timeout: Duration) -> Self {
+        Self {
+            session_manager,
+            session_idle_timeout,
+            sessions: RwLock::new(HashMap::new()),
+            statements: RwLock::new(HashMap::new()),
+        }
+    }
+
+    pub async fn drop_sessions(&self) {
+        let sessions = std::mem::take(&mut *self.sessions.write().await);
+        self.statements.write().await.clear();
+
+        for flight_session in sessions.into_values() {
+            self.session_manager
+                .drop_session(flight_session.session.state.connection_id)
+                .await;
+        }
+    }
+
+    /// Closes sessions which are idle for longer than `session_idle_timeout`
+    pub async fn evict_idle_sessions(&self) {
+        let evicted = {
+            let mut sessions = self.sessions.write().await;
+            let tokens = sessions
+                .iter()
+                .filter(|(_, flight_session)| flight_session.is_idle(self.session_idle_timeout))
+                .map(|(token, _)| token.clone())
+                .collect::<Vec<_>>();
+
+            tokens
+                .into_iter()
+                .filter_map(|token| sessions.remove(&token))
+                .map(|flight_session| flight_session.session.state.connection_id)
+                .collect::<Vec<_>>()
+        };
+        if evicted.is_empty() {
+            return;
+        }
+
+        self.statements
+            .write()
+            .await
+            .retain(|_, statement| !evicted.contains(&statement.connection_id));
+        for connection_id in evicted {
+            trace!("[flight] Closing idle session {}", connection_id);
+
+            self.session_manager.drop_session(connection_id).await;
+        }
+    }
+
+    async fn authenticate<T>(&self, request: &Request<T>) -> Result<String, Status> {
+        let authorization = request
+            .metadata()
+            .get("authorization")
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Basic "))
+            .ok_or_else(|| Status::unauthenticated("Basic authorization is required"))?;
+        let credentials = base64::decode(authorization)
+            .ok()
+            .and_then(|credentials| String::from_utf8(credentials).ok())
+            .ok_or_else(|| Status::unauthenticated("Malformed authorization header"))?;
+        let (user, password) = credentials
+            .split_once(':')
+            .ok_or_else(|| Status::unauthenticated("Malformed authorization header"))?;
+
+        let (client_addr, client_port) = match request.remote_addr() {
+            Some(addr) => (addr.ip().to_string(), addr.port()),
+            None => ("127.0.0.1".to_string(), 0000_u16),
+        };
+        let session = self
+            .session_manager
+            .create_session(DatabaseProtocol::PostgreSQL, client_addr, client_port, None)
+            .await
+            .map_err(|err| Status::resource_exhausted(err.message))?;
+
+        let authenticate_response = session
+            .server
+            .auth
+            .authenticate(Some(user.to_string()), Some(password.to_string()))
+            .await;
+        let authenticate_response = match authenticate_response {
+            Ok(response)
+                if response.skip_password_check
+                    || response.password.as_deref() == Some(password) =>
+            {
+                response
+            }
+            _ => {
+                self.session_manager
+                    .drop_session(session.state.connection_id)
+                    .await;
+
+                return Err(Status::unauthenticated(format!(
+                    "password authentication failed for user \"{}\"",
+                    user
+                )));
+            }
+        };
+
+        session.state.set_user(Some(user.to_string()));
+        session.state.set_database(Some("db".to_string()));
+        session
+            .state
+            .set_auth_context(Some(authenticate_response.context));
+        session
+            .state
+            .set_security_policy(authenticate_response.security_policy);
+
+        let token = Uuid::new_v4().to_string();
+        self.sessions
+            .write()
+            .await
+            .insert(token.clone(), Arc::new(FlightSession::new(session)));
+
+        Ok(token)
+    }
+
+    async fn session<T>(&self, request: &Request<T>) -> Result<Arc<Session>, Status> {
+        Ok(self.flight_session(request).await?.session.clone())
+    }
+
+    async fn flight_session<T>(&self, request: &Request<T>) -> Result<Arc<FlightSession>, Status> {
+        let token = request
+            .metadata()
+            .get("authorization")
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .ok_or_else(|| Status::unauthenticated("Bearer token is required"))?;
+
+        let flight_session = self
+            .sessions
+            .read()
+            .await
+            .get(token)
+            .cloned()
+            .ok_or_else(|| Status::unauthenticated("Invalid bearer token"))?;
+        // Background eviction runs periodically, token mustn't outlive the timeout in between
+        if flight_session.is_idle(self.session_idle_timeout) {
+            self.evict_idle_sessions().await;
+
+            return Err(Status::unauthenticated("Bearer token is expired"));
+        }
+        flight_session.touch();
+
+        Ok(flight_session)
+    }
+
+    async fn plan(
+        &self,
+        session: &Arc<Session>,
+        stmt: ast::Statement,
+    ) -> Result<QueryPlan, Status> {
+        let auth_context = session
+            .state
+            .auth_context()
+            .ok_or_else(|| Status::unauthenticated("must be auth"))?;
+        let cache_entry = session
+            .server
+            .compiler_cache
+            .get_cache_entry(auth_context, session.state.protocol.clone())
+            .await
+            .map_err(cube_error_to_status)?;
+        let meta = session
+            .server
+            .compiler_cache
+            .meta(cache_entry)
+            .await
+            .map_err(cube_error_to_status)?;
+
+        convert_statement_to_cube_query(stmt, meta, session.clone(), &mut None, None)
+            .await
+            .map_err(compilation_error_to_status)
+    }
+
+    async fn plan_query(&self, session: &Arc<Session>, query: &str) -> Result<QueryPlan, Status> {
+        let stmt =
+            parse_sql_to_statement(&query.to_string(), DatabaseProtocol::PostgreSQL, &mut None)
+                .map_err(compilation_error_to_status)?;
+
+        self.plan(session, stmt).await
+    }
+
+    /// Plans the query and streams its result
+    async fn execute_query(
+        &self,
+        flight_session: Arc<FlightSession>,
+        query: &str,
+        values: Vec<BindValue>,
+    ) -> Result<Response<FlightDataStream>, Status> {
+        debug!("Query: {}", query);
+
+        let mut stmt =
+            parse_sql_to_statement(&query.to_string(), DatabaseProtocol::PostgreSQL, &mut None)
+                .map_err(compilation_error_to_status)?;
+        if !values.is_empty() {
+            PostgresStatementParamsBinder::new(values)
+                .bind(&mut stmt)
+                .map_err(|err| Status::invalid_argument(err.to_string()))?;
+        }
+
+        let session = flight_session.session.clone();
+        let cancel = session.state.begin_query(query.to_string());
+        // Guard is moved into the stream, so the query ends even if the client drops it
+        let mut guard = QueryGuard::new(flight_session, QueryFingerprint::new(&stmt));
+
+        let stream = match self.plan(&session, stmt).await {
+            Ok(plan) => plan_to_stream(plan).await,
+            Err(err) => Err(err),
+        };
+        let (schema, mut stream) = stream?;
+
+        let output = async_stream::stream! {
+            let options = IpcWriteOptions::default();
+            yield Ok(SchemaAsIpc::new(&schema, &options).into());
+
+            loop {
+                let batch = tokio::select! {
+                    _ = cancel.cancelled() => Some(Err(Status::cancelled(
+                        "canceling statement due to user request",
+                    ))),
+                    batch = stream.next() => {
+                        batch.map(|batch| batch.map_err(|err| Status::internal(err.to_string())))
+                    }
+                };
+
+                match batch {
+                    Some(Ok(batch)) => {
+                        let rows = batch.num_rows() as u64;
+                        session.state.update_query_stats(|stats| stats.rows += rows);
+
+                        let (dictionaries, data) = flight_data_from_arrow_batch(&batch, &options);
+                        for dictionary in dictionaries {
+                            yield Ok(dictionary);
+                        }
+                        yield Ok(data);
+                    }
+                    Some(Err(err)) => {
+                        yield Err(err);
+                        break;
+                    }
+                    None => {
+                        guard.completed = true;
+                        break;
+                    }
+                }
+            }
+
+            drop(guard);
+        };
+
+        Ok(Response::new(Box::pin(output)))
+    }
+
+    /// Catalog commands are answered from the information schema
+    async fn execute_catalog_query(
+        &self,
+        session: Arc<Session>,
+        query: String,
+        schema: SchemaRef,
+    ) -> Result<Response<FlightDataStream>, Status> {
+        let plan = self.plan_query(&session, &query).await?;
+        let (_, stream) = plan_to_stream(plan).await?;
+        let batches = stream
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .map(|batch| {
+                let batch = batch.map_err(|err| Status::internal(err.to_string()))?;
+                RecordBatch::try_new(schema.clone(), batch.columns().to_vec())
+                    .map_err(|err| Status::internal(err.to_string()))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let options = IpcWriteOptions::default();
+        let mut output = vec![Ok(SchemaAsIpc::new(&schema, &options).into())];
+        for batch in batches.iter() {
+            let (dictionaries, data) = flight_data_from_arrow_batch(batch, &options);
+            output.extend(dictionaries.into_iter().map(Ok));
+            output.push(Ok(data));
+        }
+
+        Ok(Response::new(Box::pin(futures::stream::iter(output))))
+    }
+
+    fn flight_info(
+        schema: &Schema,
+        descriptor: FlightDescriptor,
+        ticket: Vec<u8>,
+    ) -> Result<Response<FlightInfo>, Status> {
+        let options = IpcWriteOptions::default();
+        let message = IpcMessage::try_from(SchemaAsIpc::new(schema, &options))
+            .map_err(|err| Status::internal(err.to_string()))?;
+        let endpoint = FlightEndpoint {
+            ticket: Some(Ticket {
+                ticket: ticket.into(),
+            }),
+            location: vec![],
+        };
+
+        Ok(Response::new(FlightInfo::new(
+            message,
+            Some(descriptor),
+            vec![endpoint],
+            -1,
+            -1,
+        )))
+    }
+
+    async fn prepared_statement<T>(
+        &self,
+        session: &Arc<Session>,
+        handle: &[u8],
+        with: impl FnOnce(&mut PreparedStatement) -> T,
+    ) -> Result<T, Status> {
+        let mut statements = self.statements.write().await;
+        match statements.get_mut(handle) {
+            Some(statement) if statement.connection_id == session.state.connection_id => {
+                Ok(with(statement))
+            }
+            _ => Err(Status::not_found("Unknown prepared statement")),
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl FlightSqlService for FlightSqlHandler {
+    type FlightService = FlightSqlHandler;
+
+    async fn do_handshake(
+        &self,
+        request: Request<Streaming<HandshakeRequest>>,
+    ) -> Result<
+        Response<Pin<Box<dyn Stream<Item = Result<HandshakeResponse, Status>> + Send>>>,
+        Status,
+    > {
+        let token = self.authenticate(&request).await?;
+
+        let output = futures::stream::iter(vec![Ok(HandshakeResponse {
+            protocol_version: 0,
+            payload: token.as_bytes().to_vec().into(),
+        })]);
+        let mut response: Response<
+            Pin<Box<dyn Stream<Item = Result<HandshakeResponse, Status>> + Send>>,
+        > = Response::new(Box::pin(output));
+        let authorization = MetadataValue::try_from(format!("Bearer {}", token))
+            .map_err(|err| Status::internal(err.to_string()))?;
+        response
+            .metadata_mut()
+            .insert("authorization", authorization);
+
+        Ok(response)
+    }
+
+    async fn get_flight_info_statement(
+        &self,
+        query: CommandStatementQuery,
+        request: Request<FlightDescriptor>,
+    ) -> Result<Response<FlightInfo>, Status> {
+        let session = self.session(&request).await?;
+        // Plan is used only to describe the result, query is planned again on DoGet
+        let plan = self.plan_query(&session, &query.query).await?;
+        let schema = plan_schema(&plan)?;
+
+        let ticket = TicketStatementQuery {
+            statement_handle: query.query.into_bytes().into(),
+        };
+
+        Self::flight_info(
+            &schema,
+            request.into_inner(),
+            ticket.as_any().encode_to_vec(),
+        )
+    }
+
+    async fn get_flight_info_prepared_statement(
+        &self,
+        cmd: CommandPreparedStatementQuery,
+        request: Request<FlightDescriptor>,
+    ) -> Result<Response<FlightInfo>, Status> {
+        let session = self.session(&request).await?;
+        let (query, values) = self
+            .prepared_statement(&session, &cmd.prepared_statement_handle, |statement| {
+                (statement.query.clone(), statement.values.clone())
+            })
+            .await?;
+
+        let mut stmt = parse_sql_to_statement(&query, DatabaseProtocol::PostgreSQL, &mut None)
+            .map_err(compilation_error_to_status)?;
+        if !values.is_empty() {
+            PostgresStatementParamsBinder::new(values)
+                .bind(&mut stmt)
+                .map_err(|err| Status::invalid_argument(err.to_string()))?;
+        }
+        let schema = plan_schema(&self.plan(&session, stmt).await?)?;
+
+        Self::flight_info(&schema, request.into_inner(), cmd.as_any().encode_to_vec())
+    }
+
+    async fn get_flight_info_catalogs(
+        &self,
+        query: CommandGetCatalogs,
+        request: Request<FlightDescriptor>,
+    ) -> Result<Response<FlightInfo>, Status> {
+        self.session(&request).await?;
+
+        Self::flight_info(
+            &catalogs_schema(),
+            request.into_inner(),
+            query.as_any().encode_to_vec(),
+        )
+    }
+
+    async fn get_flight_info_schemas(
+        &self,
+        query: CommandGetDbSchemas,
+        request: Request<FlightDescriptor>,
+    ) -> Result<Response<FlightInfo>, Status> {
+        self.session(&request).await?;
+
+        Self::flight_info(
+            &db_schemas_schema(),
+            request.into_inner(),
+            query.as_any().encode_to_vec(),
+        )
+    }
+
+    async fn get_flight_info_tables(
+        &self,
+        query: CommandGetTables,
+        request: Request<FlightDescriptor>,
+    ) -> Result<Response<FlightInfo>, Status> {
+        self.session(&request).await?;
+        if query.include_schema {
+            return Err(Status::unimplemented(
+                "GetTables with include_schema is not supported",
+            ));
+        }
+
+        Self::flight_info(
+            &tables_schema(),
+            request.into_inner(),
+            query.as_any().encode_to_vec(),
+        )
+    }
+
+    async fn get_flight_info_table_types(
+        &self,
+        query: CommandGetTableTypes,
+        request: Request<FlightDescriptor>,
+    ) -> Result<Response<FlightInfo>, Status> {
+        self.session(&request).await?;
+
+        Self::flight_info(
+            &table_types_schema(),
+            request.into_inner(),
+            query.as_any().encode_to_vec(),
+        )
+    }
+
+    async fn get_flight_info_sql_info(
+        &self,
+        _query: CommandGetSqlInfo,
+        _request: Request<FlightDescriptor>,
+    ) -> Result<Response<FlightInfo>, Status> {
+        Err(Status::unimplemented("GetSqlInfo is not supported"))
+    }
+
+    async fn get_flight_info_primary_keys(
+        &self,
+        _query: CommandGetPrimaryKeys,
+        _request: Request<FlightDescriptor>,
+    ) -> Result<Response<FlightInfo>, Status> {
+        Err(Status::unimplemented("GetPrimaryKeys is not supported"))
+    }
+
+    async fn get_flight_info_exported_keys(
+        &self,
+        _query: CommandGetExportedKeys,
+        _request: Request<FlightDescriptor>,
+    ) -> Result<Response<FlightInfo>, Status> {
+        Err(Status::unimplemented("GetExportedKeys is not supported"))
+    }
+
+    async fn get_flight_info_imported_keys(
+        &self,
+        _query: CommandGetImportedKeys,
+        _request: Request<FlightDescriptor>,
+    ) -> Result<Response<FlightInfo>, Status> {
+        Err(Status::unimplemented("GetImportedKeys is not supported"))
+    }
+
+    async fn get_flight_info_cross_reference(
+        &self,
+        _query: CommandGetCrossReference,
+        _request: Request<FlightDescriptor>,
+    ) -> Result<Response<FlightInfo>, Status> {
+        Err(Status::unimplemented("GetCrossReference is not supported"))
+    }
+
+    async fn do_get_statement(
+        &self,
+        ticket: TicketStatementQuery,
+        request: Request<Ticket>,
+    ) -> Result<Response<<Self as FlightService>::DoGetStream>, Status> {
+        let flight_session = self.flight_session(&request).await?;
+        let query = String::from_utf8(ticket.statement_handle.to_vec())
+            .map_err(|_| Status::invalid_argument("Malformed statement handle"))?;
+
+        self.execute_query(flight_session, &query, vec![]).await
+    }
+
+    async fn do_get_prepared_statement(
+        &self,
+        query: CommandPreparedStatementQuery,
+        request: Request<Ticket>,
+    ) -> Result<Response<<Self as FlightService>::DoGetStream>, Status> {
+        let flight_session = self.flight_session(&request).await?;
+        let (query, values) = self
+            .prepared_statement(
+                &flight_session.session,
+                &query.prepared_statement_handle,
+                |statement| (statement.query.clone(), statement.values.clone()),
+            )
+            .await?;
+
+        self.execute_query(flight_session, &query, values).await
+    }
+
+    async fn do_get_catalogs(
+        &self,
+        _query: CommandGetCatalogs,
+        request: Request<Ticket>,
+    ) -> Result<Response<<Self as FlightService>::DoGetStream>, Status> {
+        let session = self.session(&request).await?;
+
+        self.execute_catalog_query(
+            session,
+            "SELECT DISTINCT catalog_name FROM information_schema.schemata ORDER BY 1".to_string(),
+            catalogs_schema(),
+        )
+        .await
+    }
+
+    async fn do_get_schemas(
+        &self,
+        query: CommandGetDbSchemas,
+        request: Request<Ticket>,
+    ) -> Result<Response<<Self as FlightService>::DoGetStream>, Status> {
+        let session = self.session(&request).await?;
+
+        let mut conditions = vec![];
+        if let Some(catalog) = &query.catalog {
+            conditions.push(format!("catalog_name = {}", quote_literal(catalog)));
+        }
+        if let Some(pattern) = &query.db_schema_filter_pattern {
+            conditions.push(format!("schema_name LIKE {}", quote_literal(pattern)));
+        }
+
+        self.execute_catalog_query(
+            session,
+            format!(
+                "SELECT catalog_name, schema_name AS db_schema_name FROM information_schema.schemata{} ORDER BY 1, 2",
+                where_clause(conditions)
+            ),
+            db_schemas_schema(),
+        )
+        .await
+    }
+
+    async fn do_get_tables(
+        &self,
+        query: CommandGetTables,
+        request: Request<Ticket>,
+    ) -> Result<Response<<Self as FlightService>::DoGetStream>, Status> {
+        let session = self.session(&request).await?;
+
+        self.execute_catalog_query(session, tables_query(&query), tables_schema())
+            .await
+    }
+
+    async fn do_get_table_types(
+        &self,
+        _query: CommandGetTableTypes,
+        request: Request<Ticket>,
+    ) -> Result<Response<<Self as FlightService>::DoGetStream>, Status> {
+        let session = self.session(&request).await?;
+
+        self.execute_catalog_query(
+            session,
+            "SELECT DISTINCT table_type FROM information_schema.tables ORDER BY 1".to_string(),
+            table_types_schema(),
+        )
+        .await
+    }
+
+    async fn do_get_sql_info(
+        &self,
+        _query: CommandGetSqlInfo,
+        _request: Request<Ticket>,
+    ) -> Result<Response<<Self as FlightService>::DoGetStream>, Status> {
+        Err(Status::unimplemented("GetSqlInfo is not supported"))
+    }
+
+    async fn do_get_primary_keys(
+        &self,
+        _query: CommandGetPrimaryKeys,
+        _request: Request<Ticket>,
+    ) -> Result<Response<<Self as FlightService>::DoGetStream>, Status> {
+        Err(Status::unimplemented("GetPrimaryKeys is not supported"))
+    }
+
+    async fn do_get_exported_keys(
+        &self,
+        _query: CommandGetExportedKeys,
+        _request: Request<Ticket>,
+    ) -> Result<Response<<Self as FlightService>::DoGetStream>, Status> {
+        Err(Status::unimplemented("GetExportedKeys is not supported"))
+    }
+
+    async fn do_get_imported_keys(
+        &self,
+        _query: CommandGetImportedKeys,
+        _request: Request<Ticket>,
+    ) -> Result<Response<<Self as FlightService>::DoGetStream>, Status> {
+        Err(Status::unimplemented("GetImportedKeys is not supported"))
+    }
+
+    async fn do_get_cross_reference(
+        &self,
+        _query: CommandGetCrossReference,
+        _request: Request<Ticket>,
+    ) -> Result<Response<<Self as FlightService>::DoGetStream>, Status> {
+        Err(Status::unimplemented("GetCrossReference is not supported"))
+    }
+
+    async fn do_put_statement_update(
+        &self,
+        _ticket: CommandStatementUpdate,
+        _request: Request<Streaming<FlightData>>,
+    ) -> Result<i64, Status> {
+        Err(Status::unimplemented("Updates are not supported"))
+    }
+
+    /// Binds parameters of the prepared statement, values are taken from the first row
+    async fn do_put_prepared_statement_query(
+        &self,
+        query: CommandPreparedStatementQuery,
+        request: Request<Streaming<FlightData>>,
+    ) -> Result<Response<<Self as FlightService>::DoPutStream>, Status> {
+        let session = self.session(&request).await?;
+
+        let mut stream = request.into_inner();
+        let mut schema: Option<SchemaRef> = None;
+        let mut values = None;
+        while let Some(data) = stream.next().await {
+            let data = data?;
+            match &schema {
+                None => {
+                    schema = Some(Arc::new(
+                        Schema::try_from(&data)
+                            .map_err(|err| Status::invalid_argument(err.to_string()))?,
+                    ));
+                }
+                Some(schema) if values.is_none() => {
+                    let batch = flight_data_to_arrow_batch(&data, schema.clone(), &HashMap::new())
+                        .map_err(|err| Status::invalid_argument(err.to_string()))?;
+                    if batch.num_rows() > 0 {
+                        values = Some(record_batch_to_bind_values(&batch)?);
+                    }
+                }
+                Some(_) => {}
+            }
+        }
+
+        let values = values.unwrap_or_default();
+        self.prepared_statement(&session, &query.prepared_statement_handle, |statement| {
+            if values.len() != statement.parameters {
+                return Err(Status::invalid_argument(format!(
+                    "Prepared statement expects {} parameters, but {} were bound",
+                    statement.parameters,
+                    values.len()
+                )));
+            }
+
+            statement.values = values;
+
+            Ok(())
+        })
+        .await??;
+
+        Ok(Response::new(Box::pin(futures::stream::empty())))
+    }
+
+    async fn do_put_prepared_statement_update(
+        &self,
+        _query: CommandPreparedStatementUpdate,
+        _request: Request<Streaming<FlightData>>,
+    ) -> Result<i64, Status> {
+        Err(Status::unimplemented("Updates are not supported"))
+    }
+
+    async fn do_action_create_prepared_statement(
+        &self,
+        query: ActionCreatePreparedStatementRequest,
+        request: Request<Action>,
+    ) -> Result<ActionCreatePreparedStatementResult, Status> {
+        let session = self.session(&request).await?;
+
+        let stmt = parse_sql_to_statement(&query.query, DatabaseProtocol::PostgreSQL, &mut None)
+            .map_err(compilation_error_to_status)?;
+        let parameters = PostgresStatementParamsFinder::new()
+            .find(&stmt)
+            .map_err(|err| Status::invalid_argument(err.to_string()))?;
+        // Statement is planned with placeholders replaced to describe its result
+        let stmt = StatementPlaceholderReplacer::new()
+            .replace(stmt)
+            .map_err(|err| Status::invalid_argument(err.to_string()))?;
+        let dataset_schema = plan_schema(&self.plan(&session, stmt).await?)?;
+        let parameter_schema = Schema::new(
+            parameters
+                .iter()
+                .enumerate()
+                .map(|(i, parameter)| {
+                    Field::new(&format!("${}", i + 1), parameter.coltype.to_arrow(), true)
+                })
+                .collect(),
+        );
+
+        let max_prepared_statements = session
+            .server
+            .configuration
+            .connection_max_prepared_statements;
+        let handle = Uuid::new_v4().as_bytes().to_vec();
+        {
+            let mut statements = self.statements.write().await;
+            let session_statements = statements
+                .values()
+                .filter(|statement| statement.connection_id == session.state.connection_id)
+                .count();
+            if session_statements >= max_prepared_statements {
+                return Err(Status::resource_exhausted(format!(
+                    "Unable to allocate a new prepared statement: max allocation reached, actual: {}, max: {}",
+                    session_statements, max_prepared_statements
+                )));
+            }
+
+            statements.insert(
+                handle.clone(),
+                PreparedStatement {
+                    connection_id: session.state.connection_id,
+                    query: query.query,
+                    parameters: parameters.len(),
+                    values: vec![],
+                },
+            );
+        }
+
+        Ok(ActionCreatePreparedStatementResult {
+            prepared_statement_handle: handle.into(),
+            dataset_schema: schema_to_ipc(&dataset_schema)?.into(),
+            parameter_schema: schema_to_ipc(&parameter_schema)?.into(),
+        })
+    }
+
+    async fn do_action_close_prepared_statement(
+        &self,
+        query: ActionClosePreparedStatementRequest,
+        request: Request<Action>,
+    ) {
+        if let Ok(session) = self.session(&request).await {
+            let mut statements = self.statements.write().await;
+            if matches!(
+                statements.get(query.prepared_statement_handle.as_ref()),
+                Some(statement) if statement.connection_id == session.state.connection_id
+            ) {
+                statements.remove(query.prepared_statement_handle.as_ref());
+            }
+        }
+    }
+
+    async fn register_sql_info(&self, _id: i32, _result: &SqlInfo) {}
+}
+
+fn cube_error_to_status(err: CubeError) -> Status {
+    Status::internal(err.message)
+}
+
+fn compilation_error_to_status(err: CompilationError) -> Status {
+    match &err {
+        CompilationError::User(_, _) => Status::invalid_argument(err.message()),
+        CompilationError::Unsupported(_, _) => Status::unimplemented(err.message()),
+        CompilationError::Internal(_, _, _) | CompilationError::Fatal(_, _) => {
+            Status::internal(err.message())
+        }
+    }
+}
+
+fn schema_to_ipc(schema: &Schema) -> Result<Vec<u8>, Status> {
+    let options = IpcWriteOptions::default();
+    let message = IpcMessage::try_from(SchemaAsIpc::new(schema, &options))
+        .map_err(|err| Status::internal(err.to_string()))?;
+
+    Ok(message.0.to_vec())
+}
+
+fn plan_schema(plan: &QueryPlan) -> Result<Schema, Status> {
+    match plan {
+        QueryPlan::MetaOk(_, _) => Ok(Schema::empty()),
+        QueryPlan::MetaTabular(_, frame) => Ok(dataframe_schema(frame)),
+        QueryPlan::DataFusionSelect(plan, _) => Ok(plan.schema().as_ref().clone().into()),
+        QueryPlan::CreateTempTable(_, _, _, _) => Err(Status::unimplemented(
+            "Temporary tables are not supported by Flight SQL",
+        )),
+    }
+}
+
+async fn plan_to_stream(plan: QueryPlan) -> Result<(SchemaRef, SendableRecordBatchStream), Status> {
+    let (schema, batches) = match plan {
+        QueryPlan::DataFusionSelect(plan, ctx) => {
+            let stream = DFDataFrame::new(ctx.state.clone(), &plan)
+                .execute_stream()
+                .await
+                .map_err(|err| Status::internal(err.to_string()))?;
+
+            return Ok((stream.schema(), stream));
+        }
+        QueryPlan::MetaOk(_, _) => (Arc::new(Schema::empty()), vec![]),
+        QueryPlan::MetaTabular(_, frame) => {
+            let batch = dataframe_to_record_batch(&frame).map_err(cube_error_to_status)?;
+            (batch.schema(), vec![batch])
+        }
+        QueryPlan::CreateTempTable(_, _, _, _) => {
+            return Err(Status::unimplemented(
+                "Temporary tables are not supported by Flight SQL",
+            ))
+        }
+    };
+
+    let stream =
+        datafusion::physical_plan::memory::MemoryStream::try_new(batches, schema.clone(), None)
+            .map_err(|err| Status::internal(err.to_string()))?;
+
+    Ok((schema, Box::pin(stream)))
+}
+
+fn dataframe_schema(frame: &DataFrame) -> Schema {
+    Schema::new(
+        frame
+            .get_columns()
+            .iter()
+            .map(|column| {
+                Field::new(
+                    &column.get_name(),
+                    meta_column_data_type(&column.get_type()),
+                    true,
+                )
+            })
+            .collect(),
+    )
+}
+
+/// Meta queries return small frames, columns of other types are sent as strings
+fn meta_column_data_type(column_type: &ColumnType) -> DataType {
+    match column_type {
+        ColumnType::Boolean => DataType::Boolean,
+        ColumnType::Int8 | ColumnType::Int32 | ColumnType::Int64 => DataType::Int64,
+        ColumnType::Double => DataType::Float64,
+        _ => DataType::Utf8,
+    }
+}
+
+fn dataframe_to_record_batch(frame: &DataFrame) -> Result<RecordBatch, CubeError> {
+    let schema = Arc::new(dataframe_schema(frame));
+    let rows = frame.get_rows();
+
+    let mut columns: Vec<ArrayRef> = Vec::with_capacity(schema.fields().len());
+    for (i, field) in schema.fields().iter().enumerate() {
+        let values = rows.iter().map(|row| &row.values()[i]);
+        let array: ArrayRef = match field.data_type() {
+            DataType::Boolean => {
+                let mut builder = BooleanBuilder::new(rows.len());
+                for value in values {
+                    match value {
+                        TableValue::Boolean(v) => builder.append_value(*v)?,
+                        _ => builder.append_null()?,
+                    }
+                }
+                Arc::new(builder.finish())
+            }
+            DataType::Int64 => {
+                let mut builder = Int64Builder::new(rows.len());
+                for value in values {
+                    match value {
+                        TableValue::Int16(v) => builder.append_value(*v as i64)?,
+                        TableValue::Int32(v) => builder.append_value(*v as i64)?,
+                        TableValue::Int64(v) => builder.append_value(*v)?,
+                        _ => builder.append_null()?,
+                    }
+                }
+                Arc::new(builder.finish())
+            }
+            DataType::Float64 => {
+                let mut builder = Float64Builder::new(rows.len());
+                for value in values {
+                    match value {
+                        TableValue::Float32(v) => builder.append_value(*v as f64)?,
+                        TableValue::Float64(v) => builder.append_value(*v)?,
+                        _ => builder.append_null()?,
+                    }
+                }
+                Arc::new(builder.finish())
+            }
+            _ => {
+                let mut builder = StringBuilder::new(rows.len());
+                for value in values {
+                    match value {
+                        TableValue::Null => builder.append_null()?,
+                        value => builder.append_value(value.to_string())?,
+                    }
+                }
+                Arc::new(builder.finish())
+            }
+        };
+        columns.push(array);
+    }
+
+    Ok(RecordBatch::try_new(schema, columns)?)
+}
+
+fn record_query_stats(
+    session: &Session,
+    fingerprint: QueryFingerprint,
+    start_time: Instant,
+    failed: bool,
+) {
+    let stats = session.state.take_query_stats();
+    session.server.query_stats.record(
+        session.state.user(),
+        fingerprint.with_query_id(stats.query_id),
+        start_time.elapsed(),
+        failed,
+        stats,
+    );
+}
+
+fn record_batch_to_bind_values(batch: &RecordBatch) -> Result<Vec<BindValue>, Status> {
+    batch
+        .columns()
+        .iter()
+        .map(|array| {
+            if array.is_null(0) {
+                return Ok(BindValue::Null);
+            }
+
+            macro_rules! value {
+                ($ARRAY_TYPE:ident, $VARIANT:ident, $CAST:ty) => {
+                    BindValue::$VARIANT(
+                        array
+                            .as_any()
+                            .downcast_ref::<$ARRAY_TYPE>()
+                            .unwrap()
+                            .value(0) as $CAST,
+                    )
+                };
+            }
+
+            Ok(match array.data_type() {
+                DataType::Utf8 => BindValue::String(
+                    array
+                        .as_any()
+                        .downcast_ref::<StringArray>()
+                        .unwrap()
+                        .value(0)
+                        .to_string(),
+                ),
+                DataType::Boolean => BindValue::Bool(
+                    array
+                        .as_any()
+                        .downcast_ref::<BooleanArray>()
+                        .unwrap()
+                        .value(0),
+                ),
+                DataType::Int8 => value!(Int8Array, Int64, i64),
+                DataType::Int16 => value!(Int16Array, Int64, i64),
+                DataType::Int32 => value!(Int32Array, Int64, i64),
+                DataType::Int64 => value!(Int64Array, Int64, i64),
+                DataType::Float32 => value!(Float32Array, Float64, f64),
+                DataType::Float64 => value!(Float64Array, Float64, f64),
+                data_type => {
+                    return Err(Status::invalid_argument(format!(
+                        "Parameter type {:?} is not supported",
+                        data_type
+                    )))
+                }
+            })
+        })
+        .collect()
+}
+
+fn quote_literal(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+fn where_clause(conditions: Vec<String>) -> String {
+    if conditions.is_empty() {
+        "".to_string()
+    } else {
+        format!(" WHERE {}", conditions.join(" AND "))
+    }
+}
+
+fn tables_query(query: &CommandGetTables) -> String {
+    let mut conditions = vec![];
+    if let Some(catalog) = &query.catalog {
+        conditions.push(format!("table_catalog = {}", quote_literal(catalog)));
+    }
+    if let Some(pattern) = &query.db_schema_filter_pattern {
+        conditions.push(format!("table_schema LIKE {}", quote_literal(pattern)));
+    }
+    if let Some(pattern) = &query.table_name_filter_pattern {
+        conditions.push(format!("table_name LIKE {}", quote_literal(pattern)));
+    }
+    if !query.table_types.is_empty() {
+        conditions.push(format!(
+            "table_type IN ({})",
+            query
+                .table_types
+                .iter()
+                .map(|table_type| quote_literal(table_type))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ));
+    }
+
+    format!(
+        "SELECT table_catalog AS catalog_name, table_schema AS db_schema_name, table_name, table_type FROM information_schema.tables{} ORDER BY 1, 2, 3",
+        where_clause(conditions)
+    )
+}
+
+fn catalogs_schema() -> SchemaRef {
+    Arc::new(Schema::new(vec![Field::new(
+        "catalog_name",
+        DataType::Utf8,
+        false,
+    )]))
+}
+
+fn db_schemas_schema() -> SchemaRef {
+    Arc::new(Schema::new(vec![
+        Field::new("catalog_name", DataType::Utf8, true),
+        Field::new("db_schema_name", DataType::Utf8, false),
+    ]))
+}
+
+fn tables_schema() -> SchemaRef {
+    Arc::new(Schema::new(vec![
+        Field::new("catalog_name", DataType::Utf8, true),
+        Field::new("db_schema_name", DataType::Utf8, true),
+        Field::new("table_name", DataType::Utf8, false),
+        Field::new("table_type", DataType::Utf8, false),
+    ]))
+}
+
+fn table_types_schema() -> SchemaRef {
+    Arc::new(Schema::new(vec![Field::new(
+        "table_type",
+        DataType::Utf8,
+        false,
+    )]))
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow_flight::{
+        flight_descriptor::DescriptorType, flight_service_client::FlightServiceClient,
+        flight_service_server::FlightServiceServer,
+    };
+    use portpicker::pick_unused_port;
+    use tonic::{metadata::AsciiMetadataValue, transport::Channel, Code};
+
+    use super::*;
+    use crate::{
+        compile::test::{get_test_session_manager, get_test_tenant_ctx},
+        sql::{
+            dataframe::{Column, Row},
+            ColumnFlags, SessionProcessList,
+        },
+    };
+
+    async fn start_server(
+        session_idle_timeout: Duration,
+    ) -> (FlightServiceClient<Channel>, Arc<SessionManager>) {
+        let session_manager = get_test_session_manager(get_test_tenant_ctx());
+        let handler = Arc::new(FlightSqlHandler::new(
+            session_manager.clone(),
+            session_idle_timeout,
+        ));
+        let port = pick_unused_port().expect("No ports free");
+
+        tokio::spawn(
+            tonic::transport::Server::builder()
+                .add_service(FlightServiceServer::from_arc(handler))
+                .serve(format!("127.0.0.1:{}", port).parse().unwrap()),
+        );
+
+        // Server is started in the background
+        for _ in 0..50 {
+            if let Ok(client) =
+                FlightServiceClient::connect(format!("http://127.0.0.1:{}", port)).await
+            {
+                return (client, session_manager);
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+
+        panic!("Flight SQL server is not started");
+    }
+
+    fn with_authorization<T>(message: T, authorization: &str) -> Request<T> {
+        let mut request = Request::new(message);
+        request.metadata_mut().insert(
+            "authorization",
+            AsciiMetadataValue::try_from(authorization).unwrap(),
+        );
+
+        request
+    }
+
+    async fn handshake(client: &mut FlightServiceClient<Channel>) -> String {
+        let request = with_authorization(
+            futures::stream::iter(vec![HandshakeRequest {
+                protocol_version: 0,
+                payload: vec![].into(),
+            }]),
+            &format!("Basic {}", base64::encode("ovr:test")),
+        );
+        let response = client.handshake(request).await.unwrap();
+
+        response
+            .metadata()
+            .get("authorization")
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .to_string()
+    }
+
+    fn statement_descriptor(query: &str) -> FlightDescriptor {
+        FlightDescriptor {
+            r#type: DescriptorType::Cmd as i32,
+            cmd: CommandStatementQuery {
+                query: query.to_string(),
+            }
+            .as_any()
+            .encode_to_vec()
+            .into(),
+            path: vec![],
+        }
+    }
+
+    #[tokio::test]
+    async fn test_flight_sql_client() {
+        let (mut client, _) = start_server(Duration::from_secs(60)).await;
+        let token = handshake(&mut client).await;
+        assert!(token.starts_with("Bearer "));
+
+        let info = client
+            .get_flight_info(with_authorization(
+                statement_descriptor("SELECT 1 AS value"),
+                &token,
+            ))
+            .await
+            .unwrap()
+            .into_inner();
+        let ticket = info.endpoint[0].ticket.clone().unwrap();
+
+        let mut stream = client
+            .do_get(with_authorization(ticket, &token))
+            .await
+            .unwrap()
+            .into_inner();
+        let schema = Arc::new(Schema::try_from(&stream.message().await.unwrap().unwrap()).unwrap());
+        assert_eq!(schema.field(0).name(), "value");
+
+        let mut rows = 0;
+        while let Some(data) = stream.message().await.unwrap() {
+            let batch = flight_data_to_arrow_batch(&data, schema.clone(), &HashMap::new()).unwrap();
+            rows += batch.num_rows();
+        }
+        assert_eq!(rows, 1);
+
+        let err = client
+            .get_flight_info(with_authorization(
+                statement_descriptor("SELECT 1"),
+                "Bearer unknown",
+            ))
+            .await
+            .unwrap_err();
+        assert_eq!(err.code(), Code::Unauthenticated);
+    }
+
+    #[tokio::test]
+    async fn test_idle_session_eviction() {
+        let (mut client, session_manager) = start_server(Duration::ZERO).await;
+        let token = handshake(&mut client).await;
+        assert_eq!(
+            session_manager
+                .map_sessions::<SessionProcessList>()
+                .await
+                .len(),
+            1
+        );
+
+        let err = client
+            .get_flight_info(with_authorization(statement_descriptor("SELECT 1"), &token))
+            .await
+            .unwrap_err();
+        assert_eq!(err.code(), Code::Unauthenticated);
+        assert_eq!(
+            session_manager
+                .map_sessions::<SessionProcessList>()
+                .await
+                .len(),
+            0
+        );
+    }
+
+    #[test]
+    fn test_tables_query() {
+        let query = CommandGetTables {
+            catalog: None,
+            db_schema_filter_pattern: Some("public".to_string()),
+            table_name_filter_pattern: Some("Ord%".to_string()),
+            table_types: vec!["BASE TABLE".to_string(), "VIEW".to_string()],
+            include_schema: false,
+        };
+
+        assert_eq!(
+            tables_query(&query),
+            "SELECT table_catalog AS catalog_name, table_schema AS db_schema_name, table_name, table_type FROM information_schema.tables \
+            WHERE table_schema LIKE 'public' AND table_name LIKE 'Ord%' AND table_type IN ('BASE TABLE', 'VIEW') ORDER BY 1, 2, 3"
+        );
+    }
+
+    #[test]
+    fn test_dataframe_to_record_batch() -> Result<(), CubeError> {
+        let frame = DataFrame::new(
+            vec![
+                Column::new("name".to_string(), ColumnType::String, ColumnFlags::empty()),
+                Column::new("value".to_string(), ColumnType::Int64, ColumnFlags::empty()),
+            ],
+            vec![
+                Row::new(vec![
+                    TableValue::String("max_connections".to_string()),
+                    TableValue::Int64(100),
+                ]),
+                Row::new(vec![TableValue::Null, TableValue::Null]),
+            ],
+        );
+
+        let batch = dataframe_to_record_batch(&frame)?;
+        assert_eq!(batch.num_rows(), 2);
+        assert_eq!(batch.schema().field(1).data_type(), &DataType::Int64);
+
+        let names = batch
+            .column(0)
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap();
+        assert_eq!(names.value(0), "max_connections");
+        assert!(names.is_null(1));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,4 @@
+pub(crate) mod handler;
+pub(crate) mod service;
+
+pub use service::*;
@@ -0,0 +1,112 @@
+use arrow_flight::flight_service_server::FlightServiceServer;
+use async_trait::async_trait;
+use log::trace;
+use std::{net::SocketAddr, sync::Arc, time::Duration};
+use tokio::sync::watch;
+
+use super::handler::FlightSqlHandler;
+use crate::{
+    config::processing_loop::{ProcessingLoop, ShutdownMode},
+    sql::SessionManager,
+    CubeError,
+};
+
+pub struct FlightSqlServer {
+    // options
+    address: String,
+    session_idle_timeout: Duration,
+    close_socket_rx: watch::Receiver<Option<ShutdownMode>>,
+    close_socket_tx: watch::Sender<Option<ShutdownMode>>,
+    // reference
+    session_manager: Arc<SessionManager>,
+}
+
+crate::di_service!(FlightSqlServer, []);
+
+#[async_trait]
+impl ProcessingLoop for FlightSqlServer {
+    async fn processing_loop(&self) -> Result<(), CubeError> {
+        let address = self.address.parse::<SocketAddr>().map_err(|err| {
+            CubeError::user(format!(
+                "Invalid Flight SQL address '{}': {}",
+                self.address, err
+            ))
+        })?;
+        let handler = Arc::new(FlightSqlHandler::new(
+            self.session_manager.clone(),
+            self.session_idle_timeout,
+        ));
+
+        println!("🔗 Cube SQL (flight) is listening on {}", self.address);
+
+        // Smart and semifast modes wait for running requests, fast mode drops them
+        let mut graceful_receiver = self.close_socket_rx.clone();
+        let graceful_shutdown = async move {
+            while graceful_receiver.changed().await.is_ok() {
+                if graceful_receiver.borrow().is_some() {
+                    trace!("[flight] Stopping processing_loop via channel");
+                    break;
+                }
+            }
+        };
+        let mut fast_receiver = self.close_socket_rx.clone();
+        let fast_shutdown = async move {
+            while fast_receiver.changed().await.is_ok() {
+                if *fast_receiver.borrow() == Some(ShutdownMode::Fast) {
+                    trace!("[flight] Stopping processing_loop via channel, fast mode");
+                    break;
+                }
+            }
+        };
+
+        let server = tonic::transport::Server::builder()
+            .add_service(FlightServiceServer::from_arc(handler.clone()))
+            .serve_with_shutdown(address, graceful_shutdown);
+
+        // Sessions are evicted in the background too, tokens of clients which never come back
+        // shouldn't hold sessions until the listener stops
+        let eviction_handler = handler.clone();
+        let eviction_interval = self
+            .session_idle_timeout
+            .clamp(Duration::from_secs(1), Duration::from_secs(60));
+        let eviction = async move {
+            let mut interval = tokio::time::interval(eviction_interval);
+            loop {
+                interval.tick().await;
+                eviction_handler.evict_idle_sessions().await;
+            }
+        };
+
+        let result = tokio::select! {
+            res = server => res.map_err(|err| CubeError::internal(err.to_string())),
+            _ = fast_shutdown => Ok(()),
+            _ = eviction => Ok(()),
+        };
+
+        handler.drop_sessions().await;
+
+        result
+    }
+
+    async fn stop_processing(&self, mode: ShutdownMode) -> Result<(), CubeError> {
+        self.close_socket_tx.send(Some(mode))?;
+        Ok(())
+    }
+}
+
+impl FlightSqlServer {
+    pub fn new(
+        address: String,
+        session_idle_timeout: Duration,
+        session_manager: Arc<SessionManager>,
+    ) -> Arc<Self> {
+        let (close_socket_tx, close_socket_rx) = watch::channel(None::<ShutdownMode>);
+        Arc::new(Self {
+            address,
+            session_idle_timeout,
+            session_manager,
+            close_socket_rx,
+            close_socket_tx,
+        })
+    }
+}
@@ -2,7 +2,12 @@ pub(crate) mod auth_service;
 pub mod compiler_cache;
 pub(crate) mod database_variables;
 pub mod dataframe;
+#[cfg(feature = "flight-sql")]
+pub(crate) mod flight_sql;
+pub(crate) mod mysql;
 pub(crate) mod postgres;
+pub(crate) mod query_stats;
+pub(crate) mod security_policy;
 pub(crate) mod server_manager;
 pub(crate) mod session;
 pub(crate) mod session_manager;
@@ -15,8 +20,18 @@ pub use auth_service::{
     AuthContext, AuthContextRef, AuthenticateResponse, HttpAuthContext, SqlAuthDefaultImpl,
     SqlAuthService,
 };
+#[cfg(feature = "flight-sql")]
+pub use flight_sql::*;
+pub use mysql::*;
 pub use postgres::*;
+pub use query_stats::{
+    PortalQueryStats, QueryExecutionStats, QueryFingerprint, QueryStatsEntry, QueryStatsStore,
+};
+pub use security_policy::{CubeSecurityPolicy, SecurityPolicy, SecurityPolicyRef};
 pub use server_manager::ServerManager;
-pub use session::{Session, SessionProcessList, SessionProperties, SessionState};
+pub use session::{
+    is_utc_timezone, parse_bool_setting, parse_timezone, Session, SessionProcessList,
+    SessionProperties, SessionState,
+};
 pub use session_manager::SessionManager;
 pub use types::{ColumnFlags, ColumnType};
@@ -0,0 +1,176 @@
+use pg_srv::BindValue;
+use sqlparser::ast;
+
+use super::protocol::ColumnDefinition;
+use crate::{
+    compile::{
+        parser::parse_sql_to_statement, CompilationError, CompilationResult, DatabaseProtocol,
+    },
+    sql::statement::{mysql_parameter_marker, MySqlStatementParamsBinder},
+};
+
+/// Prepared statement of the binary protocol (COM_STMT_PREPARE)
+#[derive(Debug)]
+pub struct PreparedStatement {
+    pub query: String,
+    pub params: usize,
+    pub columns: Vec<ColumnDefinition>,
+    // Types of parameters are sent by client only on the first execution
+    pub param_types: Vec<(u8, bool)>,
+}
+
+impl PreparedStatement {
+    pub fn new(query: String, columns: Vec<ColumnDefinition>) -> Self {
+        Self {
+            params: find_placeholders(&query).len(),
+            query,
+            columns,
+            param_types: vec![],
+        }
+    }
+
+    /// Binds values to `?` placeholders in the order of appearance. Placeholders are replaced
+    /// with markers before the statement is parsed and values are bound in the AST.
+    pub fn bind(&self, values: Vec<BindValue>) -> CompilationResult<ast::Statement> {
+        let mut query = String::with_capacity(self.query.len());
+        let mut last = 0;
+        for (index, placeholder) in find_placeholders(&self.query).into_iter().enumerate() {
+            query.push_str(&self.query[last..placeholder]);
+            query.push_str(&format!("'{}'", mysql_parameter_marker(index)));
+            last = placeholder + 1;
+        }
+        query.push_str(&self.query[last..]);
+
+        let mut stmt = parse_sql_to_statement(&query, DatabaseProtocol::MySQL, &mut None)?;
+        let params = values.len();
+        MySqlStatementParamsBinder::new(values).bind(&mut stmt);
+
+        // Placeholder is used where parameters aren't supported (ORDER BY, SET, etc.)
+        let bound_query = stmt.to_string();
+        if (0..params).any(|index| bound_query.contains(&mysql_parameter_marker(index))) {
+            return Err(CompilationError::unsupported(
+                "Placeholder is not supported in this position of the statement".to_string(),
+            ));
+        }
+
+        Ok(stmt)
+    }
+}
+
+/// Byte offsets of `?` placeholders, skipping string literals, quoted identifiers and comments.
+fn find_placeholders(query: &str) -> Vec<usize> {
+    let bytes = query.as_bytes();
+    let mut result = vec![];
+    let mut i = 0;
+
+    while i < bytes.len() {
+        match bytes[i] {
+            b'?' => result.push(i),
+            quote @ (b'\'' | b'"' | b'`') => {
+                i += 1;
+                while i < bytes.len() && bytes[i] != quote {
+                    // Backslash escapes the next character in MySQL strings
+                    if bytes[i] == b'\\' && quote != b'`' {
+                        i += 1;
+                    }
+                    i += 1;
+                }
+            }
+            b'#' => {
+                while i < bytes.len() && bytes[i] != b'\n' {
+                    i += 1;
+                }
+            }
+            b'-' if bytes.get(i + 1) == Some(&b'-') => {
+                while i < bytes.len() && bytes[i] != b'\n' {
+                    i += 1;
+                }
+            }
+            b'/' if bytes.get(i + 1) == Some(&b'*') => {
+                i += 2;
+                while i < bytes.len() && !(bytes[i] == b'*' && bytes.get(i + 1) == Some(&b'/')) {
+                    i += 1;
+                }
+                i += 1;
+            }
+            _ => {}
+        }
+
+        i += 1;
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(query: &str) -> ast::Statement {
+        parse_sql_to_statement(&query.to_string(), DatabaseProtocol::MySQL, &mut None).unwrap()
+    }
+
+    #[test]
+    fn test_bind_placeholders() {
+        let statement = PreparedStatement::new(
+            r#"SELECT '?', `a?b` FROM t -- ?
+            WHERE a = ? /* ? */ AND b IN (?, ?) # ?
+            LIMIT ?"#
+                .to_string(),
+            vec![],
+        );
+        assert_eq!(statement.params, 4);
+
+        let stmt = statement
+            .bind(vec![
+                BindValue::String("it's".to_string()),
+                BindValue::Int64(-1),
+                BindValue::Null,
+                BindValue::Int64(10),
+            ])
+            .unwrap();
+        assert_eq!(
+            stmt.to_string(),
+            parse("SELECT '?', `a?b` FROM t WHERE a = 'it''s' AND b IN (-1, NULL) LIMIT 10")
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn test_bind_injection() {
+        let statement = PreparedStatement::new("SELECT a FROM t WHERE b = ?".to_string(), vec![]);
+
+        for payload in [r"\' OR 1=1 -- ", "' OR 1=1 -- ", r"\", r"\\'"] {
+            let stmt = statement
+                .bind(vec![BindValue::String(payload.to_string())])
+                .unwrap();
+
+            // Value is bound as a single string literal, nothing is added to the statement
+            let ast::Statement::Query(query) = &stmt else {
+                panic!("SELECT must be parsed as a query");
+            };
+            let ast::SetExpr::Select(select) = &query.body else {
+                panic!("SELECT must be parsed as a select");
+            };
+            assert_eq!(
+                select.selection,
+                Some(ast::Expr::BinaryOp {
+                    left: Box::new(ast::Expr::Identifier(ast::Ident::new("b"))),
+                    op: ast::BinaryOperator::Eq,
+                    right: Box::new(ast::Expr::Value(ast::Value::SingleQuotedString(
+                        payload.to_string()
+                    ))),
+                }),
+                "payload: {}",
+                payload
+            );
+        }
+    }
+
+    #[test]
+    fn test_bind_unsupported_position() {
+        let statement = PreparedStatement::new("SELECT a FROM t ORDER BY ?".to_string(), vec![]);
+
+        assert!(statement.bind(vec![BindValue::Int64(1)]).is_err());
+    }
+}
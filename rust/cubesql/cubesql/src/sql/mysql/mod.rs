@@ -0,0 +1,6 @@
+pub(crate) mod extended;
+pub(crate) mod protocol;
+pub(crate) mod service;
+pub(crate) mod shim;
+
+pub use service::*;
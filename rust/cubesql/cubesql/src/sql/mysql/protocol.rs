@@ -0,0 +1,1085 @@
+//! Messages of the MySQL client/server protocol which are used by cubesql.
+//! You can find overview of the protocol at
+//! <https://dev.mysql.com/doc/dev/mysql-server/latest/PAGE_PROTOCOL.html>
+
+use std::io;
+
+use bitflags::bitflags;
+use bytes::{BufMut, BytesMut};
+use chrono::{Datelike, NaiveDate, NaiveDateTime, Timelike};
+use pg_srv::BindValue;
+use sha1_smol::Sha1;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+use crate::{
+    sql::{dataframe::TableValue, ColumnFlags, ColumnType},
+    CubeError,
+};
+
+/// Max size of the payload, larger payloads are split into several packets
+pub const MAX_PAYLOAD_LEN: usize = 0xff_ff_ff;
+
+pub const AUTH_PLUGIN_NATIVE_PASSWORD: &str = "mysql_native_password";
+pub const AUTH_PLUGIN_CLEAR_PASSWORD: &str = "mysql_clear_password";
+
+/// utf8_general_ci
+pub const CHARSET_UTF8: u16 = 33;
+pub const CHARSET_BINARY: u16 = 63;
+
+bitflags! {
+    pub struct CapabilityFlags: u32 {
+        const CLIENT_LONG_PASSWORD = 0x00000001;
+        const CLIENT_FOUND_ROWS = 0x00000002;
+        const CLIENT_LONG_FLAG = 0x00000004;
+        const CLIENT_CONNECT_WITH_DB = 0x00000008;
+        const CLIENT_NO_SCHEMA = 0x00000010;
+        const CLIENT_PROTOCOL_41 = 0x00000200;
+        const CLIENT_TRANSACTIONS = 0x00002000;
+        const CLIENT_SECURE_CONNECTION = 0x00008000;
+        const CLIENT_MULTI_RESULTS = 0x00020000;
+        const CLIENT_PS_MULTI_RESULTS = 0x00040000;
+        const CLIENT_PLUGIN_AUTH = 0x00080000;
+        const CLIENT_CONNECT_ATTRS = 0x00100000;
+        const CLIENT_PLUGIN_AUTH_LENENC_CLIENT_DATA = 0x00200000;
+    }
+}
+
+impl CapabilityFlags {
+    /// Capabilities supported by the server, the connection uses the intersection with client ones
+    pub fn server_default() -> Self {
+        Self::CLIENT_LONG_PASSWORD
+            | Self::CLIENT_FOUND_ROWS
+            | Self::CLIENT_LONG_FLAG
+            | Self::CLIENT_CONNECT_WITH_DB
+            | Self::CLIENT_NO_SCHEMA
+            | Self::CLIENT_PROTOCOL_41
+            | Self::CLIENT_TRANSACTIONS
+            | Self::CLIENT_SECURE_CONNECTION
+            | Self::CLIENT_MULTI_RESULTS
+            | Self::CLIENT_PS_MULTI_RESULTS
+            | Self::CLIENT_PLUGIN_AUTH
+            | Self::CLIENT_CONNECT_ATTRS
+            | Self::CLIENT_PLUGIN_AUTH_LENENC_CLIENT_DATA
+    }
+}
+
+bitflags! {
+    pub struct ServerStatusFlags: u16 {
+        const SERVER_STATUS_AUTOCOMMIT = 0x0002;
+    }
+}
+
+/// Column flags of the column definition
+const NOT_NULL_FLAG: u16 = 0x0001;
+const UNSIGNED_FLAG: u16 = 0x0020;
+const BINARY_FLAG: u16 = 0x0080;
+const NUM_FLAG: u16 = 0x8000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum MySqlType {
+    Tiny = 0x01,
+    Short = 0x02,
+    Long = 0x03,
+    Float = 0x04,
+    Double = 0x05,
+    Null = 0x06,
+    Timestamp = 0x07,
+    LongLong = 0x08,
+    Int24 = 0x09,
+    Date = 0x0a,
+    Time = 0x0b,
+    DateTime = 0x0c,
+    Year = 0x0d,
+    VarChar = 0x0f,
+    NewDecimal = 0xf6,
+    Blob = 0xfc,
+    VarString = 0xfd,
+    String = 0xfe,
+}
+
+impl MySqlType {
+    pub fn from_u8(value: u8) -> Option<Self> {
+        Some(match value {
+            0x01 => Self::Tiny,
+            0x02 => Self::Short,
+            0x03 => Self::Long,
+            0x04 => Self::Float,
+            0x05 => Self::Double,
+            0x06 => Self::Null,
+            0x07 => Self::Timestamp,
+            0x08 => Self::LongLong,
+            0x09 => Self::Int24,
+            0x0a => Self::Date,
+            0x0b => Self::Time,
+            0x0c => Self::DateTime,
+            0x0d => Self::Year,
+            0x0f => Self::VarChar,
+            0xf6 => Self::NewDecimal,
+            // All blob kinds and JSON are sent as length encoded strings
+            0xf5 | 0xf9..=0xfc => Self::Blob,
+            0xfd => Self::VarString,
+            0xfe => Self::String,
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Debug)]
+pub enum Command {
+    Quit,
+    InitDb(String),
+    Query(String),
+    FieldList(String),
+    Ping,
+    StmtPrepare(String),
+    // Statement id + the rest of the payload, which can be decoded only with the statement
+    StmtExecute(u32, Vec<u8>),
+    StmtSendLongData,
+    StmtClose(u32),
+    StmtReset(u32),
+    Unsupported(u8),
+}
+
+impl Command {
+    pub fn decode(payload: &[u8]) -> Result<Self, CubeError> {
+        let mut reader = PayloadReader::new(payload);
+
+        Ok(match reader.read_u8()? {
+            0x01 => Command::Quit,
+            0x02 => Command::InitDb(reader.read_rest_string()),
+            0x03 => Command::Query(reader.read_rest_string()),
+            // Wildcard of columns is not supported, all columns of the table are returned
+            0x04 => Command::FieldList(reader.read_null_string()?),
+            0x0e => Command::Ping,
+            0x16 => Command::StmtPrepare(reader.read_rest_string()),
+            0x17 => Command::StmtExecute(reader.read_u32()?, reader.read_rest().to_vec()),
+            0x18 => Command::StmtSendLongData,
+            0x19 => Command::StmtClose(reader.read_u32()?),
+            0x1a => Command::StmtReset(reader.read_u32()?),
+            code => Command::Unsupported(code),
+        })
+    }
+}
+
+/// Initial handshake packet (protocol version 10)
+#[derive(Debug)]
+pub struct Handshake {
+    pub server_version: String,
+    pub connection_id: u32,
+    pub scramble: [u8; 20],
+    pub capabilities: CapabilityFlags,
+    pub status: ServerStatusFlags,
+}
+
+impl Handshake {
+    pub fn encode(&self, buf: &mut BytesMut) {
+        buf.put_u8(10);
+        put_null_str(buf, self.server_version.as_bytes());
+        buf.put_u32_le(self.connection_id);
+        buf.put_slice(&self.scramble[..8]);
+        buf.put_u8(0);
+        buf.put_u16_le(self.capabilities.bits() as u16);
+        buf.put_u8(CHARSET_UTF8 as u8);
+        buf.put_u16_le(self.status.bits());
+        buf.put_u16_le((self.capabilities.bits() >> 16) as u16);
+        buf.put_u8(self.scramble.len() as u8 + 1);
+        buf.put_slice(&[0; 10]);
+        put_null_str(buf, &self.scramble[8..]);
+        put_null_str(buf, AUTH_PLUGIN_NATIVE_PASSWORD.as_bytes());
+    }
+}
+
+#[derive(Debug)]
+pub struct HandshakeResponse {
+    pub user: String,
+    pub auth_response: Vec<u8>,
+    pub database: Option<String>,
+    pub auth_plugin: Option<String>,
+}
+
+impl HandshakeResponse {
+    pub fn decode(payload: &[u8]) -> Result<Self, CubeError> {
+        let mut reader = PayloadReader::new(payload);
+
+        let capabilities = CapabilityFlags::from_bits_truncate(reader.read_u32()?);
+        if !capabilities.contains(CapabilityFlags::CLIENT_PROTOCOL_41) {
+            return Err(CubeError::user(
+                "Client doesn't support protocol 4.1".to_string(),
+            ));
+        }
+
+        // max packet size, character set, filler
+        reader.skip(4 + 1 + 23)?;
+
+        let user = reader.read_null_string()?;
+        let auth_response =
+            if capabilities.contains(CapabilityFlags::CLIENT_PLUGIN_AUTH_LENENC_CLIENT_DATA) {
+                reader.read_lenenc_bytes()?.to_vec()
+            } else if capabilities.contains(CapabilityFlags::CLIENT_SECURE_CONNECTION) {
+                let len = reader.read_u8()? as usize;
+                reader.read_bytes(len)?.to_vec()
+            } else {
+                reader.read_null_bytes()?.to_vec()
+            };
+        let database = if capabilities.contains(CapabilityFlags::CLIENT_CONNECT_WITH_DB)
+            && reader.has_remaining()
+        {
+            Some(reader.read_null_string()?).filter(|database| !database.is_empty())
+        } else {
+            None
+        };
+        let auth_plugin = if capabilities.contains(CapabilityFlags::CLIENT_PLUGIN_AUTH)
+            && reader.has_remaining()
+        {
+            Some(reader.read_null_string()?)
+        } else {
+            None
+        };
+
+        Ok(Self {
+            user,
+            auth_response,
+            database,
+            auth_plugin,
+        })
+    }
+}
+
+/// Asks client to authenticate again with `mysql_native_password`
+#[derive(Debug)]
+pub struct AuthSwitchRequest<'a> {
+    pub scramble: &'a [u8; 20],
+}
+
+impl AuthSwitchRequest<'_> {
+    pub fn encode(&self, buf: &mut BytesMut) {
+        buf.put_u8(0xfe);
+        put_null_str(buf, AUTH_PLUGIN_NATIVE_PASSWORD.as_bytes());
+        put_null_str(buf, self.scramble);
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct OkPacket {
+    pub affected_rows: u64,
+    pub status: ServerStatusFlags,
+    pub warnings: u16,
+}
+
+impl Default for ServerStatusFlags {
+    fn default() -> Self {
+        Self::SERVER_STATUS_AUTOCOMMIT
+    }
+}
+
+impl OkPacket {
+    pub fn encode(&self, buf: &mut BytesMut) {
+        buf.put_u8(0x00);
+        put_lenenc_int(buf, self.affected_rows);
+        // last insert id
+        put_lenenc_int(buf, 0);
+        buf.put_u16_le(self.status.bits());
+        buf.put_u16_le(self.warnings);
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct EofPacket {
+    pub status: ServerStatusFlags,
+    pub warnings: u16,
+}
+
+impl EofPacket {
+    pub fn encode(&self, buf: &mut BytesMut) {
+        buf.put_u8(0xfe);
+        buf.put_u16_le(self.warnings);
+        buf.put_u16_le(self.status.bits());
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorCode {
+    ConCountError,
+    UnknownCommand,
+    AccessDenied,
+    NoSuchTable,
+    UnknownStatementHandler,
+    QueryInterrupted,
+    UnknownError,
+}
+
+impl ErrorCode {
+    pub fn code(&self) -> u16 {
+        match self {
+            ErrorCode::ConCountError => 1040,
+            ErrorCode::AccessDenied => 1045,
+            ErrorCode::UnknownCommand => 1047,
+            ErrorCode::UnknownError => 1105,
+            ErrorCode::NoSuchTable => 1146,
+            ErrorCode::UnknownStatementHandler => 1243,
+            ErrorCode::QueryInterrupted => 1317,
+        }
+    }
+
+    pub fn sql_state(&self) -> &'static str {
+        match self {
+            ErrorCode::ConCountError => "08004",
+            ErrorCode::AccessDenied => "28000",
+            ErrorCode::UnknownCommand => "08S01",
+            ErrorCode::NoSuchTable => "42S02",
+            ErrorCode::QueryInterrupted => "70100",
+            ErrorCode::UnknownStatementHandler | ErrorCode::UnknownError => "HY000",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ErrPacket {
+    pub code: ErrorCode,
+    pub message: String,
+}
+
+impl ErrPacket {
+    pub fn new(code: ErrorCode, message: String) -> Self {
+        Self { code, message }
+    }
+
+    pub fn encode(&self, buf: &mut BytesMut) {
+        buf.put_u8(0xff);
+        buf.put_u16_le(self.code.code());
+        buf.put_u8(b'#');
+        buf.put_slice(self.code.sql_state().as_bytes());
+        buf.put_slice(self.message.as_bytes());
+    }
+}
+
+/// Response to COM_STMT_PREPARE, it's followed by parameter and column definitions
+#[derive(Debug)]
+pub struct StmtPrepareOk {
+    pub statement_id: u32,
+    pub columns: u16,
+    pub params: u16,
+}
+
+impl StmtPrepareOk {
+    pub fn encode(&self, buf: &mut BytesMut) {
+        buf.put_u8(0x00);
+        buf.put_u32_le(self.statement_id);
+        buf.put_u16_le(self.columns);
+        buf.put_u16_le(self.params);
+        buf.put_u8(0);
+        // warnings
+        buf.put_u16_le(0);
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColumnDefinition {
+    pub table: String,
+    pub name: String,
+    pub column_type: MySqlType,
+    pub column_length: u32,
+    pub decimals: u8,
+    pub charset: u16,
+    pub flags: u16,
+}
+
+impl ColumnDefinition {
+    pub fn new(table: String, name: String, column_type: &ColumnType, flags: ColumnFlags) -> Self {
+        let (mysql_type, column_length, decimals) = match column_type {
+            ColumnType::Boolean => (MySqlType::Tiny, 1, 0),
+            ColumnType::Int8 => (MySqlType::Tiny, 4, 0),
+            ColumnType::Int32 => (MySqlType::Long, 11, 0),
+            ColumnType::Int64 => (MySqlType::LongLong, 20, 0),
+            // 0x1f means that the number of decimals is not fixed
+            ColumnType::Double => (MySqlType::Double, 22, 0x1f),
+            ColumnType::Decimal(precision, scale) => {
+                (MySqlType::NewDecimal, *precision as u32 + 2, *scale as u8)
+            }
+            ColumnType::Date(_) => (MySqlType::Date, 10, 0),
+            ColumnType::Timestamp => (MySqlType::DateTime, 23, 3),
+            ColumnType::Blob => (MySqlType::Blob, 65535, 0),
+            ColumnType::String
+            | ColumnType::VarStr
+            | ColumnType::Interval(_)
+            | ColumnType::List(_) => (MySqlType::VarString, 255 * 3, 0),
+        };
+
+        let (charset, mut column_flags) = match mysql_type {
+            MySqlType::VarString => (CHARSET_UTF8, 0),
+            MySqlType::Date | MySqlType::DateTime | MySqlType::Blob => {
+                (CHARSET_BINARY, BINARY_FLAG)
+            }
+            _ => (CHARSET_BINARY, BINARY_FLAG | NUM_FLAG),
+        };
+        if flags.contains(ColumnFlags::NOT_NULL) {
+            column_flags |= NOT_NULL_FLAG;
+        }
+        if flags.contains(ColumnFlags::UNSIGNED) {
+            column_flags |= UNSIGNED_FLAG;
+        }
+
+        Self {
+            table,
+            name,
+            column_type: mysql_type,
+            column_length,
+            decimals,
+            charset,
+            flags: column_flags,
+        }
+    }
+
+    /// Definition of the prepared statement parameter, types of parameters are not known before execution
+    pub fn parameter() -> Self {
+        Self::new(
+            "".to_string(),
+            "?".to_string(),
+            &ColumnType::VarStr,
+            ColumnFlags::empty(),
+        )
+    }
+
+    pub fn encode(&self, buf: &mut BytesMut) {
+        // catalog, schema, table, original table, name, original name
+        put_lenenc_str(buf, b"def");
+        put_lenenc_str(buf, b"");
+        put_lenenc_str(buf, self.table.as_bytes());
+        put_lenenc_str(buf, self.table.as_bytes());
+        put_lenenc_str(buf, self.name.as_bytes());
+        put_lenenc_str(buf, self.name.as_bytes());
+        // length of fixed fields
+        buf.put_u8(0x0c);
+        buf.put_u16_le(self.charset);
+        buf.put_u32_le(self.column_length);
+        buf.put_u8(self.column_type as u8);
+        buf.put_u16_le(self.flags);
+        buf.put_u8(self.decimals);
+        buf.put_u16_le(0);
+    }
+}
+
+/// Row of the text result set (COM_QUERY)
+pub fn encode_text_row(buf: &mut BytesMut, values: &[TableValue]) {
+    for value in values {
+        match value {
+            TableValue::Null => buf.put_u8(0xfb),
+            TableValue::Boolean(v) => put_lenenc_str(buf, if *v { b"1" } else { b"0" }),
+            TableValue::Timestamp(v) => put_lenenc_str(
+                buf,
+                v.to_naive_datetime()
+                    .format("%Y-%m-%d %H:%M:%S%.3f")
+                    .to_string()
+                    .as_bytes(),
+            ),
+            value => put_lenenc_str(buf, value.to_string().as_bytes()),
+        }
+    }
+}
+
+/// Row of the binary result set (COM_STMT_EXECUTE), values are encoded by the types of column definitions
+pub fn encode_binary_row(
+    buf: &mut BytesMut,
+    columns: &[ColumnDefinition],
+    values: &[TableValue],
+) -> Result<(), CubeError> {
+    buf.put_u8(0x00);
+
+    // NULL bitmap has an offset of 2 bits for result set rows
+    let mut null_bitmap = vec![0_u8; (values.len() + 7 + 2) / 8];
+    for (i, value) in values.iter().enumerate() {
+        if let TableValue::Null = value {
+            null_bitmap[(i + 2) / 8] |= 1 << ((i + 2) % 8);
+        }
+    }
+    buf.put_slice(&null_bitmap);
+
+    for (column, value) in columns.iter().zip(values.iter()) {
+        if let TableValue::Null = value {
+            continue;
+        }
+
+        let unsupported = || {
+            CubeError::internal(format!(
+                "Unable to encode value {:?} as MySQL type {:?}",
+                value, column.column_type
+            ))
+        };
+
+        match column.column_type {
+            MySqlType::Tiny => buf.put_i8(table_value_as_i64(value).ok_or_else(unsupported)? as i8),
+            MySqlType::Long => {
+                buf.put_i32_le(table_value_as_i64(value).ok_or_else(unsupported)? as i32)
+            }
+            MySqlType::LongLong => {
+                buf.put_i64_le(table_value_as_i64(value).ok_or_else(unsupported)?)
+            }
+            MySqlType::Double => buf.put_f64_le(match value {
+                TableValue::Float32(v) => *v as f64,
+                TableValue::Float64(v) => *v,
+                value => table_value_as_i64(value).ok_or_else(unsupported)? as f64,
+            }),
+            MySqlType::Date => match value {
+                TableValue::Date(v) => {
+                    buf.put_u8(4);
+                    put_binary_date(buf, v);
+                }
+                _ => return Err(unsupported()),
+            },
+            MySqlType::DateTime => match value {
+                TableValue::Timestamp(v) => put_binary_datetime(buf, &v.to_naive_datetime()),
+                _ => return Err(unsupported()),
+            },
+            _ => put_lenenc_str(buf, value.to_string().as_bytes()),
+        }
+    }
+
+    Ok(())
+}
+
+fn table_value_as_i64(value: &TableValue) -> Option<i64> {
+    match value {
+        TableValue::Boolean(v) => Some(*v as i64),
+        TableValue::Int16(v) => Some(*v as i64),
+        TableValue::Int32(v) => Some(*v as i64),
+        TableValue::Int64(v) => Some(*v),
+        _ => None,
+    }
+}
+
+fn put_binary_date(buf: &mut BytesMut, date: &NaiveDate) {
+    buf.put_u16_le(date.year() as u16);
+    buf.put_u8(date.month() as u8);
+    buf.put_u8(date.day() as u8);
+}
+
+fn put_binary_datetime(buf: &mut BytesMut, datetime: &NaiveDateTime) {
+    buf.put_u8(11);
+    put_binary_date(buf, &datetime.date());
+    buf.put_u8(datetime.hour() as u8);
+    buf.put_u8(datetime.minute() as u8);
+    buf.put_u8(datetime.second() as u8);
+    buf.put_u32_le(datetime.nanosecond() / 1000);
+}
+
+/// Parameters of COM_STMT_EXECUTE. Types are sent only on the first execution (or when they are
+/// changed), so they are stored in `types` between executions of the statement.
+pub fn decode_execute_params(
+    payload: &[u8],
+    params: usize,
+    types: &mut Vec<(u8, bool)>,
+) -> Result<Vec<BindValue>, CubeError> {
+    let mut reader = PayloadReader::new(payload);
+    // flags (cursors are not supported) + iteration count (always 1)
+    reader.skip(1 + 4)?;
+
+    if params == 0 {
+        return Ok(vec![]);
+    }
+
+    let null_bitmap = reader.read_bytes((params + 7) / 8)?.to_vec();
+    if reader.read_u8()? == 1 {
+        types.clear();
+        for _ in 0..params {
+            let param_type = reader.read_u8()?;
+            let flags = reader.read_u8()?;
+            types.push((param_type, flags & 0x80 != 0));
+        }
+    }
+    if types.len() != params {
+        return Err(CubeError::user(
+            "Types of prepared statement parameters are not specified".to_string(),
+        ));
+    }
+
+    let mut values = Vec::with_capacity(params);
+    for (i, (param_type, unsigned)) in types.iter().enumerate() {
+        if null_bitmap[i / 8] & (1 << (i % 8)) != 0 {
+            values.push(BindValue::Null);
+            continue;
+        }
+
+        let value = match MySqlType::from_u8(*param_type) {
+            Some(MySqlType::Null) => BindValue::Null,
+            Some(MySqlType::Tiny) => BindValue::Int64(match unsigned {
+                true => reader.read_u8()? as i64,
+                false => reader.read_u8()? as i8 as i64,
+            }),
+            Some(MySqlType::Short | MySqlType::Year) => BindValue::Int64(match unsigned {
+                true => reader.read_u16()? as i64,
+                false => reader.read_u16()? as i16 as i64,
+            }),
+            Some(MySqlType::Long | MySqlType::Int24) => BindValue::Int64(match unsigned {
+                true => reader.read_u32()? as i64,
+                false => reader.read_u32()? as i32 as i64,
+            }),
+            Some(MySqlType::LongLong) => {
+                let value = reader.read_u64()?;
+                match unsigned {
+                    // Values which don't fit into i64 are bound as numeric strings
+                    true => i64::try_from(value)
+                        .map(BindValue::Int64)
+                        .unwrap_or_else(|_| BindValue::String(value.to_string())),
+                    false => BindValue::Int64(value as i64),
+                }
+            }
+            Some(MySqlType::Float) => BindValue::Float64(f32::from_bits(reader.read_u32()?) as f64),
+            Some(MySqlType::Double) => BindValue::Float64(f64::from_bits(reader.read_u64()?)),
+            Some(MySqlType::Date | MySqlType::DateTime | MySqlType::Timestamp) => {
+                BindValue::String(read_binary_datetime(&mut reader)?)
+            }
+            Some(MySqlType::Time) => BindValue::String(read_binary_time(&mut reader)?),
+            Some(
+                MySqlType::VarChar
+                | MySqlType::NewDecimal
+                | MySqlType::Blob
+                | MySqlType::VarString
+                | MySqlType::String,
+            ) => {
+                BindValue::String(String::from_utf8_lossy(reader.read_lenenc_bytes()?).to_string())
+            }
+            None => {
+                return Err(CubeError::user(format!(
+                    "Unsupported type of prepared statement parameter: 0x{:02x}",
+                    param_type
+                )))
+            }
+        };
+        values.push(value);
+    }
+
+    Ok(values)
+}
+
+fn read_binary_datetime(reader: &mut PayloadReader) -> Result<String, CubeError> {
+    let len = reader.read_u8()?;
+    let (mut year, mut month, mut day, mut hour, mut minute, mut second, mut micros) =
+        (0, 0, 0, 0, 0, 0, 0);
+    if len >= 4 {
+        year = reader.read_u16()?;
+        month = reader.read_u8()?;
+        day = reader.read_u8()?;
+    }
+    if len >= 7 {
+        hour = reader.read_u8()?;
+        minute = reader.read_u8()?;
+        second = reader.read_u8()?;
+    }
+    if len >= 11 {
+        micros = reader.read_u32()?;
+    }
+
+    Ok(match len {
+        0..=4 => format!("{:04}-{:02}-{:02}", year, month, day),
+        _ => format!(
+            "{:04}-{:02}-{:02} {:02}:{:02}:{:02}.{:06}",
+            year, month, day, hour, minute, second, micros
+        ),
+    })
+}
+
+fn read_binary_time(reader: &mut PayloadReader) -> Result<String, CubeError> {
+    let len = reader.read_u8()?;
+    if len == 0 {
+        return Ok("00:00:00".to_string());
+    }
+
+    let negative = reader.read_u8()? == 1;
+    let days = reader.read_u32()?;
+    let hours = reader.read_u8()? as u32 + days * 24;
+    let minutes = reader.read_u8()?;
+    let seconds = reader.read_u8()?;
+    let micros = if len >= 12 { reader.read_u32()? } else { 0 };
+
+    Ok(format!(
+        "{}{:02}:{:02}:{:02}.{:06}",
+        if negative { "-" } else { "" },
+        hours,
+        minutes,
+        seconds,
+        micros
+    ))
+}
+
+/// Checks `mysql_native_password` auth response: SHA1(password) XOR SHA1(scramble + SHA1(SHA1(password)))
+pub fn verify_native_password(password: &str, scramble: &[u8], auth_response: &[u8]) -> bool {
+    if password.is_empty() {
+        return auth_response.is_empty();
+    }
+
+    let stage1 = Sha1::from(password.as_bytes()).digest().bytes();
+    let stage2 = Sha1::from(stage1).digest().bytes();
+
+    let mut hasher = Sha1::new();
+    hasher.update(scramble);
+    hasher.update(&stage2);
+    let expected = hasher
+        .digest()
+        .bytes()
+        .iter()
+        .zip(stage1.iter())
+        .map(|(l, r)| l ^ r)
+        .collect::<Vec<_>>();
+
+    expected.as_slice() == auth_response
+}
+
+pub fn put_lenenc_int(buf: &mut BytesMut, value: u64) {
+    if value < 0xfb {
+        buf.put_u8(value as u8);
+    } else if value <= 0xffff {
+        buf.put_u8(0xfc);
+        buf.put_u16_le(value as u16);
+    } else if value <= 0xff_ff_ff {
+        buf.put_u8(0xfd);
+        buf.put_uint_le(value, 3);
+    } else {
+        buf.put_u8(0xfe);
+        buf.put_u64_le(value);
+    }
+}
+
+pub fn put_lenenc_str(buf: &mut BytesMut, value: &[u8]) {
+    put_lenenc_int(buf, value.len() as u64);
+    buf.put_slice(value);
+}
+
+fn put_null_str(buf: &mut BytesMut, value: &[u8]) {
+    buf.put_slice(value);
+    buf.put_u8(0);
+}
+
+fn malformed_packet() -> CubeError {
+    CubeError::user("Malformed packet".to_string())
+}
+
+pub struct PayloadReader<'a> {
+    payload: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> PayloadReader<'a> {
+    pub fn new(payload: &'a [u8]) -> Self {
+        Self { payload, pos: 0 }
+    }
+
+    pub fn has_remaining(&self) -> bool {
+        self.pos < self.payload.len()
+    }
+
+    pub fn skip(&mut self, n: usize) -> Result<(), CubeError> {
+        self.read_bytes(n).map(|_| ())
+    }
+
+    pub fn read_bytes(&mut self, n: usize) -> Result<&'a [u8], CubeError> {
+        let end = self.pos.checked_add(n).ok_or_else(malformed_packet)?;
+        let bytes = self
+            .payload
+            .get(self.pos..end)
+            .ok_or_else(malformed_packet)?;
+        self.pos = end;
+
+        Ok(bytes)
+    }
+
+    pub fn read_u8(&mut self) -> Result<u8, CubeError> {
+        Ok(self.read_bytes(1)?[0])
+    }
+
+    pub fn read_u16(&mut self) -> Result<u16, CubeError> {
+        Ok(u16::from_le_bytes(self.read_bytes(2)?.try_into().unwrap()))
+    }
+
+    pub fn read_u32(&mut self) -> Result<u32, CubeError> {
+        Ok(u32::from_le_bytes(self.read_bytes(4)?.try_into().unwrap()))
+    }
+
+    pub fn read_u64(&mut self) -> Result<u64, CubeError> {
+        Ok(u64::from_le_bytes(self.read_bytes(8)?.try_into().unwrap()))
+    }
+
+    pub fn read_lenenc_int(&mut self) -> Result<u64, CubeError> {
+        Ok(match self.read_u8()? {
+            0xfc => self.read_u16()? as u64,
+            0xfd => {
+                let bytes = self.read_bytes(3)?;
+                u32::from_le_bytes([bytes[0], bytes[1], bytes[2], 0]) as u64
+            }
+            0xfe => self.read_u64()?,
+            0xfb | 0xff => return Err(malformed_packet()),
+            v => v as u64,
+        })
+    }
+
+    pub fn read_lenenc_bytes(&mut self) -> Result<&'a [u8], CubeError> {
+        let len = self.read_lenenc_int()?;
+        self.read_bytes(usize::try_from(len).map_err(|_| malformed_packet())?)
+    }
+
+    pub fn read_null_bytes(&mut self) -> Result<&'a [u8], CubeError> {
+        let rest = &self.payload[self.pos..];
+        let len = rest
+            .iter()
+            .position(|b| *b == 0)
+            .ok_or_else(malformed_packet)?;
+        self.pos += len + 1;
+
+        Ok(&rest[..len])
+    }
+
+    pub fn read_null_string(&mut self) -> Result<String, CubeError> {
+        Ok(String::from_utf8_lossy(self.read_null_bytes()?).to_string())
+    }
+
+    pub fn read_rest(&mut self) -> &'a [u8] {
+        let rest = &self.payload[self.pos..];
+        self.pos = self.payload.len();
+
+        rest
+    }
+
+    pub fn read_rest_string(&mut self) -> String {
+        String::from_utf8_lossy(self.read_rest()).to_string()
+    }
+}
+
+/// Reads a payload, which can be split into several packets. Returns `None` when the connection
+/// is closed on the packet boundary.
+pub async fn read_packet<R: AsyncRead + Unpin>(
+    reader: &mut R,
+    sequence_id: &mut u8,
+) -> Result<Option<Vec<u8>>, io::Error> {
+    let mut payload = Vec::new();
+
+    loop {
+        let mut header = [0_u8; 4];
+        match reader.read_exact(&mut header).await {
+            Ok(_) => {}
+            Err(err) if err.kind() == io::ErrorKind::UnexpectedEof && payload.is_empty() => {
+                return Ok(None)
+            }
+            Err(err) => return Err(err),
+        }
+
+        let len = u32::from_le_bytes([header[0], header[1], header[2], 0]) as usize;
+        *sequence_id = header[3].wrapping_add(1);
+
+        let start = payload.len();
+        payload.resize(start + len, 0);
+        reader.read_exact(&mut payload[start..]).await?;
+
+        if len < MAX_PAYLOAD_LEN {
+            return Ok(Some(payload));
+        }
+    }
+}
+
+/// Buffers packets of the response, which are sent to the client on flush
+#[derive(Debug)]
+pub struct PacketWriter {
+    buf: BytesMut,
+    payload: BytesMut,
+    sequence_id: u8,
+}
+
+impl PacketWriter {
+    pub fn new() -> Self {
+        Self {
+            buf: BytesMut::new(),
+            payload: BytesMut::new(),
+            sequence_id: 0,
+        }
+    }
+
+    pub fn set_sequence_id(&mut self, sequence_id: u8) {
+        self.sequence_id = sequence_id;
+    }
+
+    /// Encodes a single packet with the payload written by `encode`
+    pub fn write(&mut self, encode: impl FnOnce(&mut BytesMut)) {
+        self.payload.clear();
+        encode(&mut self.payload);
+
+        let mut chunks = self.payload.chunks(MAX_PAYLOAD_LEN).peekable();
+        // Empty payload and payload which is a multiple of max length are terminated by an empty packet
+        let terminated = self.payload.len() % MAX_PAYLOAD_LEN == 0;
+        while let Some(chunk) = chunks.next() {
+            self.buf.put_uint_le(chunk.len() as u64, 3);
+            self.buf.put_u8(self.sequence_id);
+            self.buf.put_slice(chunk);
+            self.sequence_id = self.sequence_id.wrapping_add(1);
+        }
+        if terminated {
+            self.buf.put_uint_le(0, 3);
+            self.buf.put_u8(self.sequence_id);
+            self.sequence_id = self.sequence_id.wrapping_add(1);
+        }
+    }
+
+    pub async fn flush<W: AsyncWrite + Unpin>(&mut self, writer: &mut W) -> Result<(), io::Error> {
+        writer.write_all_buf(&mut self.buf).await?;
+        writer.flush().await?;
+        self.buf.clear();
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_packet_round_trip() {
+        let mut writer = PacketWriter::new();
+        writer.set_sequence_id(1);
+        writer.write(|buf| {
+            OkPacket {
+                affected_rows: 300,
+                ..Default::default()
+            }
+            .encode(buf)
+        });
+        writer.write(|buf| buf.put_slice(&vec![7; MAX_PAYLOAD_LEN + 10]));
+
+        let mut output = Vec::new();
+        writer.flush(&mut output).await.unwrap();
+
+        let mut input = output.as_slice();
+        let mut sequence_id = 0;
+
+        let ok = read_packet(&mut input, &mut sequence_id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            ok,
+            vec![0x00, 0xfc, 0x2c, 0x01, 0x00, 0x02, 0x00, 0x00, 0x00]
+        );
+        assert_eq!(sequence_id, 2);
+
+        let large = read_packet(&mut input, &mut sequence_id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(large.len(), MAX_PAYLOAD_LEN + 10);
+        assert_eq!(sequence_id, 4);
+
+        assert!(read_packet(&mut input, &mut sequence_id)
+            .await
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn test_handshake_response_decode() {
+        let capabilities = CapabilityFlags::CLIENT_PROTOCOL_41
+            | CapabilityFlags::CLIENT_SECURE_CONNECTION
+            | CapabilityFlags::CLIENT_CONNECT_WITH_DB
+            | CapabilityFlags::CLIENT_PLUGIN_AUTH;
+
+        let mut payload = BytesMut::new();
+        payload.put_u32_le(capabilities.bits());
+        payload.put_u32_le(MAX_PAYLOAD_LEN as u32);
+        payload.put_u8(CHARSET_UTF8 as u8);
+        payload.put_slice(&[0; 23]);
+        put_null_str(&mut payload, b"root");
+        payload.put_u8(3);
+        payload.put_slice(&[1, 2, 3]);
+        put_null_str(&mut payload, b"db");
+        put_null_str(&mut payload, AUTH_PLUGIN_NATIVE_PASSWORD.as_bytes());
+
+        let response = HandshakeResponse::decode(&payload).unwrap();
+        assert_eq!(response.user, "root");
+        assert_eq!(response.auth_response, vec![1, 2, 3]);
+        assert_eq!(response.database, Some("db".to_string()));
+        assert_eq!(
+            response.auth_plugin,
+            Some(AUTH_PLUGIN_NATIVE_PASSWORD.to_string())
+        );
+
+        assert!(HandshakeResponse::decode(&payload[..10]).is_err());
+    }
+
+    #[test]
+    fn test_verify_native_password() {
+        let scramble = b"abcdefghijklmnopqrst";
+
+        // Computed by the client side of the protocol
+        let stage1 = Sha1::from("secret").digest().bytes();
+        let stage2 = Sha1::from(stage1).digest().bytes();
+        let mut hasher = Sha1::new();
+        hasher.update(scramble);
+        hasher.update(&stage2);
+        let auth_response = hasher
+            .digest()
+            .bytes()
+            .iter()
+            .zip(stage1.iter())
+            .map(|(l, r)| l ^ r)
+            .collect::<Vec<_>>();
+
+        assert!(verify_native_password("secret", scramble, &auth_response));
+        assert!(!verify_native_password("other", scramble, &auth_response));
+        assert!(verify_native_password("", scramble, &[]));
+        assert!(!verify_native_password("secret", scramble, &[]));
+    }
+
+    #[test]
+    fn test_decode_execute_params() {
+        let mut payload = BytesMut::new();
+        // flags + iteration count
+        payload.put_u8(0);
+        payload.put_u32_le(1);
+        // NULL bitmap: the third parameter is NULL
+        payload.put_u8(0b100);
+        // new params bound
+        payload.put_u8(1);
+        payload.put_slice(&[MySqlType::LongLong as u8, 0]);
+        payload.put_slice(&[MySqlType::VarString as u8, 0]);
+        payload.put_slice(&[MySqlType::Long as u8, 0]);
+        payload.put_slice(&[MySqlType::DateTime as u8, 0]);
+        payload.put_i64_le(-5);
+        put_lenenc_str(&mut payload, b"female");
+        put_binary_datetime(
+            &mut payload,
+            &NaiveDate::from_ymd_opt(2024, 1, 2)
+                .unwrap()
+                .and_hms_opt(3, 4, 5)
+                .unwrap(),
+        );
+
+        let mut types = vec![];
+        let values = decode_execute_params(&payload, 4, &mut types).unwrap();
+        assert_eq!(
+            values,
+            vec![
+                BindValue::Int64(-5),
+                BindValue::String("female".to_string()),
+                BindValue::Null,
+                BindValue::String("2024-01-02 03:04:05.000000".to_string()),
+            ]
+        );
+        assert_eq!(types.len(), 4);
+
+        // Types are not sent again on the next execution
+        let mut payload = BytesMut::new();
+        payload.put_u8(0);
+        payload.put_u32_le(1);
+        payload.put_u8(0b1110);
+        payload.put_u8(0);
+        payload.put_i64_le(1);
+
+        let values = decode_execute_params(&payload, 4, &mut types).unwrap();
+        assert_eq!(
+            values,
+            vec![
+                BindValue::Int64(1),
+                BindValue::Null,
+                BindValue::Null,
+                BindValue::Null
+            ]
+        );
+    }
+}
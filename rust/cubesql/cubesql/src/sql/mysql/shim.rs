@@ -0,0 +1,991 @@
+use std::{collections::HashMap, io::ErrorKind, sync::Arc, time::Instant};
+
+use bytes::{BufMut, BytesMut};
+use datafusion::{arrow::datatypes::DataType, dataframe::DataFrame as DFDataFrame};
+use futures::{FutureExt, StreamExt};
+use log::{debug, trace};
+use rand::Rng;
+use sqlparser::ast;
+use tokio::{io::AsyncWriteExt, net::TcpStream};
+use tokio_util::sync::CancellationToken;
+use uuid::Uuid;
+
+use super::{
+    extended::PreparedStatement,
+    protocol::{
+        decode_execute_params, encode_binary_row, encode_text_row, put_lenenc_int, read_packet,
+        verify_native_password, AuthSwitchRequest, CapabilityFlags, ColumnDefinition, Command,
+        EofPacket, ErrPacket, ErrorCode, Handshake, HandshakeResponse, OkPacket, PacketWriter,
+        ServerStatusFlags, StmtPrepareOk, AUTH_PLUGIN_CLEAR_PASSWORD, AUTH_PLUGIN_NATIVE_PASSWORD,
+    },
+};
+use crate::{
+    compile::{
+        convert_statement_to_cube_query, parser::parse_sql_to_statement, CompilationError,
+        DatabaseProtocol, QueryPlan,
+    },
+    sql::{
+        dataframe::{arrow_to_column_type, batches_to_dataframe, TableValue},
+        statement::StatementPlaceholderReplacer,
+        AuthContextRef, ColumnFlags, ColumnType, QueryFingerprint, Session,
+    },
+    telemetry::ContextLogger,
+    transport::{MetaContext, SpanId, V1CubeMetaExt},
+    CubeError,
+};
+
+/// Same version is returned by `VERSION()`
+const SERVER_VERSION: &str = "8.0.25";
+
+#[derive(thiserror::Error, Debug)]
+pub enum ConnectionError {
+    #[error("CubeError: {0}")]
+    Cube(CubeError),
+    #[error("CompilationError: {0}")]
+    CompilationError(CompilationError),
+    #[error("MySQL Error: {}", .0.message)]
+    Response(ErrPacket),
+    #[error("IO Error: {0}")]
+    IO(std::io::Error),
+}
+
+impl ConnectionError {
+    pub fn to_err_packet(&self) -> ErrPacket {
+        match self {
+            ConnectionError::Cube(e) => ErrPacket::new(ErrorCode::UnknownError, e.message.clone()),
+            ConnectionError::CompilationError(e) => {
+                ErrPacket::new(ErrorCode::UnknownError, e.message())
+            }
+            ConnectionError::Response(packet) => packet.clone(),
+            ConnectionError::IO(e) => ErrPacket::new(ErrorCode::UnknownError, e.to_string()),
+        }
+    }
+}
+
+impl From<CubeError> for ConnectionError {
+    fn from(e: CubeError) -> Self {
+        ConnectionError::Cube(e)
+    }
+}
+
+impl From<CompilationError> for ConnectionError {
+    fn from(e: CompilationError) -> Self {
+        ConnectionError::CompilationError(e)
+    }
+}
+
+impl From<ErrPacket> for ConnectionError {
+    fn from(e: ErrPacket) -> Self {
+        ConnectionError::Response(e)
+    }
+}
+
+impl From<std::io::Error> for ConnectionError {
+    fn from(e: std::io::Error) -> Self {
+        ConnectionError::IO(e)
+    }
+}
+
+impl From<datafusion::error::DataFusionError> for ConnectionError {
+    fn from(e: datafusion::error::DataFusionError) -> Self {
+        ConnectionError::Cube(e.into())
+    }
+}
+
+impl From<datafusion::arrow::error::ArrowError> for ConnectionError {
+    fn from(e: datafusion::arrow::error::ArrowError) -> Self {
+        ConnectionError::Cube(e.into())
+    }
+}
+
+pub struct AsyncMySqlShim {
+    socket: TcpStream,
+    // Packets of the response, which are not flushed yet
+    writer: PacketWriter,
+    semifast_shutdown_interruptor: CancellationToken,
+    // Binary protocol
+    statements: HashMap<u32, PreparedStatement>,
+    last_statement_id: u32,
+    // Shared
+    session: Arc<Session>,
+    logger: Arc<dyn ContextLogger>,
+}
+
+impl AsyncMySqlShim {
+    pub async fn run_on(
+        fast_shutdown_interruptor: CancellationToken,
+        semifast_shutdown_interruptor: CancellationToken,
+        socket: TcpStream,
+        session: Arc<Session>,
+        logger: Arc<dyn ContextLogger>,
+    ) -> Result<(), ConnectionError> {
+        let mut shim = Self {
+            socket,
+            writer: PacketWriter::new(),
+            semifast_shutdown_interruptor,
+            statements: HashMap::new(),
+            last_statement_id: 0,
+            session,
+            logger,
+        };
+
+        let run_result = tokio::select! {
+            _ = fast_shutdown_interruptor.cancelled() => {
+                shim.socket.shutdown().await?;
+                return Ok(());
+            }
+            res = shim.run() => res,
+        };
+
+        match run_result {
+            Err(ConnectionError::IO(e))
+                if matches!(
+                    e.kind(),
+                    ErrorKind::BrokenPipe | ErrorKind::UnexpectedEof | ErrorKind::ConnectionReset
+                ) =>
+            {
+                trace!("Error during processing MySQL connection: {}", e);
+
+                Ok(())
+            }
+            // Error is already sent to the client
+            Err(ConnectionError::CompilationError(CompilationError::Fatal(_, _))) | Ok(_) => {
+                shim.socket.shutdown().await?;
+                Ok(())
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    pub async fn run(&mut self) -> Result<(), ConnectionError> {
+        if !self.handshake().await? {
+            return Ok(());
+        }
+
+        // Clone here to avoid conflicting borrows of self in the tokio::select!.
+        let semifast_shutdown_interruptor = self.semifast_shutdown_interruptor.clone();
+
+        loop {
+            let mut sequence_id = 0;
+            // Connection is idle between commands, so it's always semifast shutdownable
+            let payload = tokio::select! {
+                _ = semifast_shutdown_interruptor.cancelled() => return Ok(()),
+                payload = read_packet(&mut self.socket, &mut sequence_id) => payload?,
+            };
+            let Some(payload) = payload else {
+                return Ok(());
+            };
+            self.writer.set_sequence_id(sequence_id);
+
+            let result = match Command::decode(&payload) {
+                Ok(Command::Quit) => return Ok(()),
+                Ok(command) => self.process_command(command).await,
+                Err(err) => Err(err.into()),
+            };
+            if let Err(err) = result {
+                self.handle_connection_error(err).await?;
+            }
+
+            self.writer.flush(&mut self.socket).await?;
+        }
+    }
+
+    fn new_scramble() -> [u8; 20] {
+        let mut rng = rand::thread_rng();
+        // Scramble is sent as a null-terminated string, so it must not contain zero bytes
+        let mut scramble = [0_u8; 20];
+        for byte in scramble.iter_mut() {
+            *byte = rng.gen_range(1..128);
+        }
+
+        scramble
+    }
+
+    /// Returns `false` when the connection should be closed
+    async fn handshake(&mut self) -> Result<bool, ConnectionError> {
+        let scramble = Self::new_scramble();
+
+        let handshake = Handshake {
+            server_version: SERVER_VERSION.to_string(),
+            connection_id: self.session.state.connection_id,
+            scramble,
+            capabilities: CapabilityFlags::server_default(),
+            status: ServerStatusFlags::default(),
+        };
+        self.writer.set_sequence_id(0);
+        self.writer.write(|buf| handshake.encode(buf));
+        self.writer.flush(&mut self.socket).await?;
+
+        let mut sequence_id = 0;
+        let Some(payload) = read_packet(&mut self.socket, &mut sequence_id).await? else {
+            return Ok(false);
+        };
+        self.writer.set_sequence_id(sequence_id);
+
+        let response = match HandshakeResponse::decode(&payload) {
+            Ok(response) => response,
+            Err(err) => {
+                let err = ErrPacket::new(ErrorCode::AccessDenied, err.message);
+                self.writer.write(|buf| err.encode(buf));
+                self.writer.flush(&mut self.socket).await?;
+
+                return Ok(false);
+            }
+        };
+
+        let (auth_response, clear_password) = match response.auth_plugin.as_deref() {
+            None | Some(AUTH_PLUGIN_NATIVE_PASSWORD) => (response.auth_response, false),
+            Some(AUTH_PLUGIN_CLEAR_PASSWORD) => (response.auth_response, true),
+            // Other plugins (caching_sha2_password is the default one for MySQL 8 clients)
+            // are switched to mysql_native_password
+            Some(_) => {
+                let request = AuthSwitchRequest {
+                    scramble: &scramble,
+                };
+                self.writer.write(|buf| request.encode(buf));
+                self.writer.flush(&mut self.socket).await?;
+
+                let Some(payload) = read_packet(&mut self.socket, &mut sequence_id).await? else {
+                    return Ok(false);
+                };
+                self.writer.set_sequence_id(sequence_id);
+
+                (payload, false)
+            }
+        };
+
+        self.authenticate(
+            response.user,
+            response.database,
+            auth_response,
+            clear_password,
+            &scramble,
+        )
+        .await
+    }
+
+    async fn authenticate(
+        &mut self,
+        user: String,
+        database: Option<String>,
+        auth_response: Vec<u8>,
+        clear_password: bool,
+        scramble: &[u8; 20],
+    ) -> Result<bool, ConnectionError> {
+        // Password itself is known only when client sends it in clear text
+        let password = if clear_password {
+            let password = auth_response.strip_suffix(&[0]).unwrap_or(&auth_response);
+            Some(String::from_utf8_lossy(password).to_string())
+        } else {
+            None
+        };
+
+        let authenticate_response = self
+            .session
+            .server
+            .auth
+            .authenticate(Some(user.clone()), password.clone())
+            .await;
+
+        let authenticate_response = match authenticate_response {
+            Ok(response)
+                if response.skip_password_check
+                    || match (&response.password, &password) {
+                        (Some(expected), Some(password)) => expected == password,
+                        (Some(expected), None) => {
+                            verify_native_password(expected, scramble, &auth_response)
+                        }
+                        (None, _) => false,
+                    } =>
+            {
+                response
+            }
+            _ => {
+                let err = ErrPacket::new(
+                    ErrorCode::AccessDenied,
+                    format!("Access denied for user '{}'", user),
+                );
+                self.writer.write(|buf| err.encode(buf));
+                self.writer.flush(&mut self.socket).await?;
+
+                return Ok(false);
+            }
+        };
+
+        self.session
+            .state
+            .set_database(Some(database.unwrap_or("db".to_string())));
+        self.session.state.set_user(Some(user));
+        self.session
+            .state
+            .set_auth_context(Some(authenticate_response.context));
+        self.session
+            .state
+            .set_security_policy(authenticate_response.security_policy);
+
+        self.write_ok();
+        self.writer.flush(&mut self.socket).await?;
+
+        Ok(true)
+    }
+
+    async fn process_command(&mut self, command: Command) -> Result<(), ConnectionError> {
+        match command {
+            Command::Ping => self.write_ok(),
+            Command::InitDb(database) => {
+                self.session.state.set_database(Some(database));
+                self.write_ok();
+            }
+            Command::Query(query) => {
+                let span_id = Self::new_span_id(query.clone());
+                self.log_load_state(
+                    span_id.clone(),
+                    "Load Request",
+                    serde_json::json!({
+                        "query": {
+                            "sql": query.clone(),
+                        }
+                    }),
+                )
+                .await?;
+
+                self.process_query(query, None, span_id, false).await?;
+            }
+            Command::FieldList(table) => self.field_list(table).await?,
+            Command::StmtPrepare(query) => self.prepare_statement(query).await?,
+            Command::StmtExecute(statement_id, payload) => {
+                let statement = self
+                    .statements
+                    .get_mut(&statement_id)
+                    .ok_or_else(|| Self::unknown_statement(statement_id))?;
+                let values =
+                    decode_execute_params(&payload, statement.params, &mut statement.param_types)?;
+                let stmt = statement.bind(values)?;
+                let query = stmt.to_string();
+
+                let span_id = Self::new_span_id(query.clone());
+                self.log_load_state(
+                    span_id.clone(),
+                    "Load Request",
+                    serde_json::json!({
+                        "query": {
+                            "sql": query.clone(),
+                        }
+                    }),
+                )
+                .await?;
+
+                self.process_query(query, Some(stmt), span_id, true).await?;
+            }
+            // There is no response to COM_STMT_SEND_LONG_DATA, even for errors
+            Command::StmtSendLongData => {
+                trace!("[mysql] COM_STMT_SEND_LONG_DATA is not supported, ignoring");
+            }
+            // There is no response to COM_STMT_CLOSE
+            Command::StmtClose(statement_id) => {
+                self.statements.remove(&statement_id);
+            }
+            Command::StmtReset(statement_id) => {
+                if !self.statements.contains_key(&statement_id) {
+                    return Err(Self::unknown_statement(statement_id).into());
+                }
+
+                self.write_ok();
+            }
+            Command::Quit => unreachable!("COM_QUIT must be handled by the connection loop"),
+            Command::Unsupported(code) => {
+                return Err(ErrPacket::new(
+                    ErrorCode::UnknownCommand,
+                    format!("Unsupported command: 0x{:02x}", code),
+                )
+                .into())
+            }
+        };
+
+        Ok(())
+    }
+
+    fn unknown_statement(statement_id: u32) -> ErrPacket {
+        ErrPacket::new(
+            ErrorCode::UnknownStatementHandler,
+            format!("Unknown prepared statement handler ({})", statement_id),
+        )
+    }
+
+    fn new_span_id(sql: String) -> Option<Arc<SpanId>> {
+        Some(Arc::new(SpanId::new(
+            Uuid::new_v4().to_string(),
+            serde_json::json!({ "sql": sql }),
+        )))
+    }
+
+    async fn log_load_state(
+        &self,
+        span_id: Option<Arc<SpanId>>,
+        event: &str,
+        properties: serde_json::Value,
+    ) -> Result<(), CubeError> {
+        if let Some(auth_context) = self.session.state.auth_context() {
+            self.session
+                .server
+                .transport
+                .log_load_state(
+                    span_id,
+                    auth_context,
+                    self.session.state.get_load_request_meta(),
+                    event.to_string(),
+                    properties,
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Executes the query and writes the result set, binary one is used for prepared statements.
+    /// Prepared statements are passed already parsed, with parameters bound in the AST
+    async fn process_query(
+        &mut self,
+        query: String,
+        stmt: Option<ast::Statement>,
+        span_id: Option<Arc<SpanId>>,
+        binary: bool,
+    ) -> Result<(), ConnectionError> {
+        debug!("Query: {}", query);
+
+        let start_time = Instant::now();
+        let result = self
+            .execute_query(&query, stmt, span_id.clone(), binary)
+            .await;
+
+        if let Some(span_id) = span_id {
+            match &result {
+                Ok(_) => {
+                    self.log_load_state(
+                        Some(span_id.clone()),
+                        "Load Request Success",
+                        serde_json::json!({
+                            "query": {
+                                "sql": query,
+                            },
+                            "apiType": "sql",
+                            "duration": start_time.elapsed().as_millis() as u64,
+                            "isDataQuery": span_id.is_data_query().await,
+                        }),
+                    )
+                    .await?;
+                }
+                Err(ConnectionError::IO(_)) => {}
+                Err(err) => {
+                    self.log_load_state(
+                        Some(span_id.clone()),
+                        "SQL API Error",
+                        serde_json::json!({
+                            "query": span_id.query_key.clone(),
+                            "error": err.to_err_packet().message,
+                            "duration": span_id.duration(),
+                        }),
+                    )
+                    .await?;
+                }
+            }
+        }
+
+        result
+    }
+
+    async fn execute_query(
+        &mut self,
+        query: &str,
+        stmt: Option<ast::Statement>,
+        span_id: Option<Arc<SpanId>>,
+        binary: bool,
+    ) -> Result<(), ConnectionError> {
+        let meta = self.meta().await?;
+        let stmt = match stmt {
+            Some(stmt) => stmt,
+            None => parse_sql_to_statement(&query.to_string(), DatabaseProtocol::MySQL, &mut None)?,
+        };
+
+        let cancel = self.session.state.begin_query(query.to_string());
+        let fingerprint = QueryFingerprint::new(&stmt);
+        let start_time = Instant::now();
+
+        let result = tokio::select! {
+            _ = cancel.cancelled() => Err(ErrPacket::new(
+                ErrorCode::QueryInterrupted,
+                "Query execution was interrupted".to_string(),
+            )
+            .into()),
+            res = std::panic::AssertUnwindSafe(async {
+                let plan = convert_statement_to_cube_query(
+                    stmt,
+                    meta,
+                    self.session.clone(),
+                    &mut None,
+                    span_id,
+                )
+                .await?;
+
+                self.write_plan(plan, binary).await
+            })
+            .catch_unwind() => res.unwrap_or_else(|err| Err(CubeError::panic(err).into())),
+        };
+
+        self.session.state.end_query();
+        let stats = self.session.state.take_query_stats();
+        self.session.server.query_stats.record(
+            self.session.state.user(),
+            fingerprint.with_query_id(stats.query_id),
+            start_time.elapsed(),
+            result.is_err(),
+            stats,
+        );
+
+        result
+    }
+
+    async fn meta(&self) -> Result<Arc<MetaContext>, CubeError> {
+        let cache_entry = self
+            .session
+            .server
+            .compiler_cache
+            .get_cache_entry(self.auth_context()?, self.session.state.protocol.clone())
+            .await?;
+
+        self.session.server.compiler_cache.meta(cache_entry).await
+    }
+
+    fn plan_columns(plan: &QueryPlan) -> Result<Vec<ColumnDefinition>, CubeError> {
+        match plan {
+            QueryPlan::MetaOk(_, _) | QueryPlan::CreateTempTable(_, _, _, _) => Ok(vec![]),
+            QueryPlan::MetaTabular(_, frame) => Ok(frame
+                .get_columns()
+                .iter()
+                .map(|column| {
+                    ColumnDefinition::new(
+                        "".to_string(),
+                        column.get_name(),
+                        &column.get_type(),
+                        column.get_flags(),
+                    )
+                })
+                .collect()),
+            QueryPlan::DataFusionSelect(logical_plan, _) => logical_plan
+                .schema()
+                .fields()
+                .iter()
+                .map(|field| {
+                    let column_type = match field.data_type() {
+                        // Decimals are sent as strings with precision and scale of the column
+                        DataType::Decimal(precision, scale) => {
+                            ColumnType::Decimal(*precision, *scale)
+                        }
+                        data_type => arrow_to_column_type(data_type.clone())?,
+                    };
+                    let flags = if field.is_nullable() {
+                        ColumnFlags::empty()
+                    } else {
+                        ColumnFlags::NOT_NULL
+                    };
+
+                    Ok(ColumnDefinition::new(
+                        "".to_string(),
+                        field.name().clone(),
+                        &column_type,
+                        flags,
+                    ))
+                })
+                .collect(),
+        }
+    }
+
+    async fn write_plan(&mut self, plan: QueryPlan, binary: bool) -> Result<(), ConnectionError> {
+        let columns = Self::plan_columns(&plan)?;
+
+        match plan {
+            QueryPlan::MetaOk(_, _) => self.write_ok(),
+            // SET TRANSACTION and similar statements don't have a result set
+            QueryPlan::MetaTabular(_, _) if columns.is_empty() => self.write_ok(),
+            QueryPlan::MetaTabular(_, frame) => {
+                self.write_columns(&columns);
+                for row in frame.get_rows() {
+                    self.write_row(&columns, row.values(), binary)?;
+                }
+                let rows = frame.len() as u64;
+                self.session
+                    .state
+                    .update_query_stats(|stats| stats.rows += rows);
+                self.write_eof();
+            }
+            QueryPlan::DataFusionSelect(logical_plan, ctx) => {
+                let df = DFDataFrame::new(ctx.state.clone(), &logical_plan);
+                let mut stream = df.execute_stream().await?;
+
+                self.write_columns(&columns);
+                while let Some(batch) = stream.next().await {
+                    let batch = batch?;
+                    let frame = batches_to_dataframe(batch.schema().as_ref(), vec![batch])?;
+                    for row in frame.get_rows() {
+                        self.write_row(&columns, row.values(), binary)?;
+                    }
+
+                    let rows = frame.len() as u64;
+                    self.session
+                        .state
+                        .update_query_stats(|stats| stats.rows += rows);
+                    self.writer.flush(&mut self.socket).await?;
+                }
+                self.write_eof();
+            }
+            QueryPlan::CreateTempTable(_, _, _, _) => {
+                return Err(CubeError::user(
+                    "Temporary tables are not supported by MySQL protocol".to_string(),
+                )
+                .into())
+            }
+        };
+
+        Ok(())
+    }
+
+    /// COM_FIELD_LIST is used by clients to autocomplete column names
+    async fn field_list(&mut self, table: String) -> Result<(), ConnectionError> {
+        let meta = self.meta().await?;
+        let meta = match self.session.state.security_policy() {
+            Some(security_policy) => Arc::new(meta.restrict(&security_policy)),
+            None => meta,
+        };
+
+        let cube = meta.find_cube_with_name(&table).ok_or_else(|| {
+            ErrPacket::new(
+                ErrorCode::NoSuchTable,
+                format!(
+                    "Table '{}.{}' doesn't exist",
+                    self.session.state.database().unwrap_or("db".to_string()),
+                    table
+                ),
+            )
+        })?;
+
+        for column in cube.get_columns() {
+            let flags = if column.sql_can_be_null() {
+                ColumnFlags::empty()
+            } else {
+                ColumnFlags::NOT_NULL
+            };
+            let definition = ColumnDefinition::new(
+                table.clone(),
+                column.get_name().clone(),
+                &column.get_column_type(),
+                flags,
+            );
+            self.writer.write(|buf| definition.encode(buf));
+        }
+        self.write_eof();
+
+        Ok(())
+    }
+
+    async fn prepare_statement(&mut self, query: String) -> Result<(), ConnectionError> {
+        let max_prepared_statements = self
+            .session
+            .server
+            .configuration
+            .connection_max_prepared_statements;
+        if self.statements.len() >= max_prepared_statements {
+            return Err(CubeError::user(format!(
+                "Unable to allocate a new prepared statement: max allocation reached, actual: {}, max: {}",
+                self.statements.len(),
+                max_prepared_statements
+            ))
+            .into());
+        }
+
+        let meta = self.meta().await?;
+        let stmt = parse_sql_to_statement(&query, DatabaseProtocol::MySQL, &mut None)?;
+        // Statement is planned with placeholders replaced to describe its columns
+        let stmt = StatementPlaceholderReplacer::new()
+            .replace(stmt)
+            .map_err(|err| CubeError::user(err.to_string()))?;
+        let plan =
+            convert_statement_to_cube_query(stmt, meta, self.session.clone(), &mut None, None)
+                .await?;
+
+        let statement = PreparedStatement::new(query, Self::plan_columns(&plan)?);
+        self.last_statement_id += 1;
+        let statement_id = self.last_statement_id;
+
+        let prepare_ok = StmtPrepareOk {
+            statement_id,
+            columns: statement.columns.len() as u16,
+            params: statement.params as u16,
+        };
+        self.writer.write(|buf| prepare_ok.encode(buf));
+        if statement.params > 0 {
+            let parameter = ColumnDefinition::parameter();
+            for _ in 0..statement.params {
+                self.writer.write(|buf| parameter.encode(buf));
+            }
+            self.write_eof();
+        }
+        if !statement.columns.is_empty() {
+            for column in statement.columns.iter() {
+                self.writer.write(|buf| column.encode(buf));
+            }
+            self.write_eof();
+        }
+
+        self.statements.insert(statement_id, statement);
+
+        Ok(())
+    }
+
+    async fn handle_connection_error(
+        &mut self,
+        err: ConnectionError,
+    ) -> Result<(), ConnectionError> {
+        let props = match &err {
+            // Propagate unrecoverable errors to top level - run_on
+            ConnectionError::IO(_) => return Err(err),
+            ConnectionError::CompilationError(
+                CompilationError::Unsupported(_, meta)
+                | CompilationError::User(_, meta)
+                | CompilationError::Internal(_, _, meta)
+                | CompilationError::Fatal(_, meta),
+            ) => meta.clone(),
+            _ => None,
+        };
+
+        let err_packet = err.to_err_packet();
+        self.logger.error(err_packet.message.as_str(), props);
+        self.writer.write(|buf| err_packet.encode(buf));
+
+        if let ConnectionError::CompilationError(CompilationError::Fatal(_, _)) = &err {
+            self.writer.flush(&mut self.socket).await?;
+
+            return Err(err);
+        }
+
+        Ok(())
+    }
+
+    fn write_ok(&mut self) {
+        self.writer.write(|buf| OkPacket::default().encode(buf));
+    }
+
+    fn write_eof(&mut self) {
+        self.writer.write(|buf| EofPacket::default().encode(buf));
+    }
+
+    fn write_columns(&mut self, columns: &[ColumnDefinition]) {
+        let count = columns.len() as u64;
+        self.writer.write(|buf| put_lenenc_int(buf, count));
+        for column in columns {
+            self.writer.write(|buf| column.encode(buf));
+        }
+        self.write_eof();
+    }
+
+    fn write_row(
+        &mut self,
+        columns: &[ColumnDefinition],
+        values: &[TableValue],
+        binary: bool,
+    ) -> Result<(), CubeError> {
+        if binary {
+            let mut payload = BytesMut::new();
+            encode_binary_row(&mut payload, columns, values)?;
+            self.writer.write(|buf| buf.put_slice(&payload));
+        } else {
+            self.writer.write(|buf| encode_text_row(buf, values));
+        }
+
+        Ok(())
+    }
+
+    fn auth_context(&self) -> Result<AuthContextRef, CubeError> {
+        self.session
+            .state
+            .auth_context()
+            .ok_or(CubeError::internal("must be auth".to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::net::TcpListener;
+
+    use super::{super::protocol::MySqlType, *};
+    use crate::{
+        compile::test::{get_test_session, get_test_tenant_ctx},
+        telemetry::SessionLogger,
+    };
+
+    struct TestClient {
+        socket: TcpStream,
+        sequence_id: u8,
+    }
+
+    impl TestClient {
+        async fn connect() -> Self {
+            let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let address = listener.local_addr().unwrap();
+
+            tokio::spawn(async move {
+                let (socket, _) = listener.accept().await.unwrap();
+                let session =
+                    get_test_session(DatabaseProtocol::MySQL, get_test_tenant_ctx()).await;
+                let logger = Arc::new(SessionLogger::new(session.state.clone()));
+
+                AsyncMySqlShim::run_on(
+                    CancellationToken::new(),
+                    CancellationToken::new(),
+                    socket,
+                    session,
+                    logger,
+                )
+                .await
+                .unwrap();
+            });
+
+            Self {
+                socket: TcpStream::connect(address).await.unwrap(),
+                sequence_id: 0,
+            }
+        }
+
+        async fn read(&mut self) -> Vec<u8> {
+            read_packet(&mut self.socket, &mut self.sequence_id)
+                .await
+                .unwrap()
+                .expect("connection must not be closed")
+        }
+
+        async fn write(&mut self, encode: impl FnOnce(&mut BytesMut)) {
+            let mut writer = PacketWriter::new();
+            writer.set_sequence_id(self.sequence_id);
+            writer.write(encode);
+            writer.flush(&mut self.socket).await.unwrap();
+        }
+
+        async fn command(&mut self, encode: impl FnOnce(&mut BytesMut)) {
+            self.sequence_id = 0;
+            self.write(encode).await;
+        }
+
+        /// Reads column definitions and rows of the result set, returns payloads of rows
+        async fn read_result_set(&mut self) -> Vec<Vec<u8>> {
+            let payload = self.read().await;
+            assert_ne!(
+                payload[0],
+                0xff,
+                "unexpected error: {}",
+                String::from_utf8_lossy(&payload[9..])
+            );
+
+            for _ in 0..payload[0] {
+                self.read().await;
+            }
+            assert!(is_eof(&self.read().await));
+
+            let mut rows = vec![];
+            loop {
+                let payload = self.read().await;
+                if is_eof(&payload) {
+                    return rows;
+                }
+                rows.push(payload);
+            }
+        }
+    }
+
+    fn is_eof(payload: &[u8]) -> bool {
+        payload[0] == 0xfe && payload.len() < 9
+    }
+
+    #[tokio::test]
+    async fn test_handshake_query_and_execute() {
+        let mut client = TestClient::connect().await;
+
+        // Handshake, test auth accepts the password sent in clear text
+        let handshake = client.read().await;
+        assert_eq!(handshake[0], 10);
+        client
+            .write(|buf| {
+                let capabilities = CapabilityFlags::CLIENT_PROTOCOL_41
+                    | CapabilityFlags::CLIENT_SECURE_CONNECTION
+                    | CapabilityFlags::CLIENT_CONNECT_WITH_DB
+                    | CapabilityFlags::CLIENT_PLUGIN_AUTH;
+                buf.put_u32_le(capabilities.bits());
+                buf.put_u32_le(0);
+                buf.put_u8(33);
+                buf.put_slice(&[0; 23]);
+                buf.put_slice(b"ovr\0");
+                buf.put_u8(5);
+                buf.put_slice(b"test\0");
+                buf.put_slice(b"db\0");
+                buf.put_slice(AUTH_PLUGIN_CLEAR_PASSWORD.as_bytes());
+                buf.put_u8(0);
+            })
+            .await;
+        assert_eq!(client.read().await[0], 0x00);
+
+        // COM_QUERY
+        client
+            .command(|buf| {
+                buf.put_u8(0x03);
+                buf.put_slice(b"SELECT * FROM performance_schema.session_variables WHERE VARIABLE_NAME = 'max_allowed_packet'");
+            })
+            .await;
+        let rows = client.read_result_set().await;
+        assert_eq!(rows.len(), 1);
+        assert!(rows[0].ends_with(b"67108864"));
+
+        // COM_STMT_PREPARE
+        client
+            .command(|buf| {
+                buf.put_u8(0x16);
+                buf.put_slice(b"SELECT VARIABLE_VALUE FROM performance_schema.session_variables WHERE VARIABLE_NAME = ?");
+            })
+            .await;
+        let prepare_ok = client.read().await;
+        assert_eq!(prepare_ok[0], 0x00);
+        let statement_id = u32::from_le_bytes(prepare_ok[1..5].try_into().unwrap());
+        let columns = u16::from_le_bytes(prepare_ok[5..7].try_into().unwrap());
+        let params = u16::from_le_bytes(prepare_ok[7..9].try_into().unwrap());
+        assert_eq!((columns, params), (1, 1));
+        // Parameter and column definitions, each followed by EOF
+        for _ in 0..4 {
+            client.read().await;
+        }
+
+        // COM_STMT_EXECUTE, injected SQL must be compared as a plain string
+        for (value, expected_rows) in [
+            ("max_allowed_packet", 1),
+            (r"\' OR 1=1 -- ", 0),
+            ("' OR 1=1 -- ", 0),
+        ] {
+            client
+                .command(|buf| {
+                    buf.put_u8(0x17);
+                    buf.put_u32_le(statement_id);
+                    buf.put_u8(0);
+                    buf.put_u32_le(1);
+                    // NULL bitmap
+                    buf.put_u8(0);
+                    // New params bound
+                    buf.put_u8(1);
+                    buf.put_slice(&[MySqlType::VarString as u8, 0]);
+                    put_lenenc_int(buf, value.len() as u64);
+                    buf.put_slice(value.as_bytes());
+                })
+                .await;
+            let rows = client.read_result_set().await;
+            assert_eq!(rows.len(), expected_rows, "value: {}", value);
+            if let Some(row) = rows.first() {
+                assert!(row.ends_with(b"67108864"));
+            }
+        }
+    }
+}
@@ -1,7 +1,8 @@
 use crate::{
-    compile::QueryPlan,
+    compile::{CommandCompletion, QueryPlan},
     sql::{
         dataframe::{batches_to_dataframe, DataFrame, TableValue},
+        query_stats::{PortalQueryStats, QueryFingerprint},
         statement::PostgresStatementParamsBinder,
         temp_tables::TempTable,
         writer::BatchWriter,
@@ -14,7 +15,10 @@ use pg_srv::{protocol, BindValue, PgTypeId, ProtocolError};
 use sqlparser::ast;
 use std::{fmt, pin::Pin, sync::Arc};
 
-use crate::sql::shim::{ConnectionError, QueryPlanExt};
+use crate::sql::{
+    generic_plan::GenericPlan,
+    shim::{ConnectionError, QueryPlanExt},
+};
 use datafusion::{
     arrow::array::Array, dataframe::DataFrame as DFDataFrame,
     physical_plan::SendableRecordBatchStream,
@@ -52,10 +56,14 @@ pub enum PreparedStatement {
         from_sql: bool,
         created: DateTime<Utc>,
         query: ast::Statement,
+        /// Statistics of all executions are recorded under it
+        fingerprint: QueryFingerprint,
         parameters: protocol::ParameterDescription,
         /// Fields which will be returned to the client, It can be None if server doesnt return any field
         /// for example BEGIN
         description: Option<protocol::RowDescription>,
+        /// Plan which is reused by executions instead of planning the bound statement
+        generic_plan: Option<Arc<GenericPlan>>,
         span_id: Option<Arc<SpanId>>,
     },
     Error {
@@ -217,6 +225,8 @@ pub struct Portal {
     // State which holds corresponding data for each step. Option is used for dereferencing
     state: Option<PortalState>,
     span_id: Option<Arc<SpanId>>,
+    // Statistics of the statement, recorded when the portal completes
+    query_stats: Option<PortalQueryStats>,
 }
 
 unsafe impl Send for Portal {}
@@ -254,6 +264,7 @@ impl Portal {
             from,
             span_id,
             state: Some(PortalState::Prepared(PreparedState { plan })),
+            query_stats: None,
         }
     }
 
@@ -267,9 +278,23 @@ impl Portal {
             from,
             span_id,
             state: Some(PortalState::Empty),
+            query_stats: None,
         }
     }
 
+    pub fn with_query_stats(mut self, query_stats: PortalQueryStats) -> Self {
+        self.query_stats = Some(query_stats);
+        self
+    }
+
+    pub fn take_query_stats(&mut self) -> Option<PortalQueryStats> {
+        self.query_stats.take()
+    }
+
+    pub fn set_query_stats(&mut self, query_stats: PortalQueryStats) {
+        self.query_stats = Some(query_stats);
+    }
+
     pub fn get_description(&self) -> Result<Option<protocol::RowDescription>, ConnectionError> {
         match &self.state {
             Some(PortalState::Prepared(state)) => state.plan.to_row_description(self.format),
@@ -298,6 +323,22 @@ impl Portal {
         }
     }
 
+    /// Completion of the transaction control statement (BEGIN, COMMIT or ROLLBACK) which the portal executes
+    pub fn transaction_completion(&self) -> Option<CommandCompletion> {
+        match &self.state {
+            Some(PortalState::Prepared(PreparedState {
+                plan:
+                    QueryPlan::MetaOk(
+                        _,
+                        completion @ (CommandCompletion::Begin
+                        | CommandCompletion::Commit
+                        | CommandCompletion::Rollback),
+                    ),
+            })) => Some(completion.clone()),
+            _ => None,
+        }
+    }
+
     pub fn get_format(&self) -> protocol::Format {
         self.format.clone()
     }
@@ -705,6 +746,7 @@ mod tests {
                 None,
             ))),
             span_id: None,
+            query_stats: None,
         };
 
         let mut portal = Pin::new(&mut p);
@@ -738,6 +780,7 @@ mod tests {
                 None,
             ))),
             span_id: None,
+            query_stats: None,
         };
 
         let mut portal = Pin::new(&mut p);
@@ -766,6 +809,7 @@ mod tests {
                 Some(protocol::RowDescription::new(vec![])),
             ))),
             span_id: None,
+            query_stats: None,
         };
 
         let mut portal = Pin::new(&mut p);
@@ -801,6 +845,7 @@ mod tests {
                 Some(protocol::RowDescription::new(vec![])),
             ))),
             span_id: None,
+            query_stats: None,
         };
 
         execute_portal_single_batch(&mut portal, 1, 1).await?;
@@ -824,6 +869,7 @@ mod tests {
                 Some(protocol::RowDescription::new(vec![])),
             ))),
             span_id: None,
+            query_stats: None,
         };
 
         // use 1 batch
@@ -0,0 +1,363 @@
+use std::{fmt, sync::Arc};
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime};
+use datafusion::{
+    execution::context::SessionContext as DFSessionContext,
+    logical_plan::{plan::Extension, LogicalPlan},
+    optimizer::utils::from_plan,
+    scalar::ScalarValue,
+};
+use pg_srv::{BindValue, PgTypeId};
+use serde_json::Value;
+use sqlparser::ast;
+use uuid::Uuid;
+
+use crate::{
+    compile::{engine::df::scan::CubeScanNode, QueryPlan},
+    sql::{
+        statement::{generic_plan_parameter_marker, TimeDependenceFinder},
+        AuthContextRef, SecurityPolicyRef, SessionState,
+    },
+    transport::{MemberType, MetaContext, SpanId, V1CubeMetaExt},
+};
+
+/// Generic plan of the prepared statement. It's planned once on Parse with markers instead of
+/// parameter values and every Bind substitutes markers in the Cube requests with the bound
+/// values, so the rewrite doesn't run again for every execution.
+///
+/// Plan is generic only when every parameter ends up as a filter value of a Cube request.
+/// Otherwise (parameter is used in post-processing, in the generated SQL, etc.) the statement
+/// is planned on every Bind as usual.
+pub struct GenericPlan {
+    plan: LogicalPlan,
+    ctx: DFSessionContext,
+    markers: Vec<String>,
+    // Types of the members which parameters are compared with
+    parameter_types: Vec<PgTypeId>,
+    compiler_id: Uuid,
+    variables: Vec<(String, ScalarValue)>,
+    // Filters of the security policy are already added to Cube requests
+    security_policy: Option<SecurityPolicyRef>,
+}
+
+impl fmt::Debug for GenericPlan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&format!(
+            "GenericPlan(LogicalPlan: hidden, DFSessionContext: hidden, Parameters: {})",
+            self.markers.len()
+        ))
+    }
+}
+
+impl GenericPlan {
+    pub fn try_new(
+        query: &ast::Statement,
+        plan: QueryPlan,
+        parameters: usize,
+        meta: &MetaContext,
+        state: &SessionState,
+    ) -> Option<Self> {
+        let QueryPlan::DataFusionSelect(plan, ctx) = plan else {
+            return None;
+        };
+
+        // Relative dates are evaluated during the rewrite
+        if TimeDependenceFinder::new().find(query) {
+            return None;
+        }
+
+        let markers = (0..parameters)
+            .map(generic_plan_parameter_marker)
+            .collect::<Vec<_>>();
+        let mut found = vec![false; parameters];
+        if !collect_plan_markers(&plan, &markers, &mut found) || found.contains(&false) {
+            return None;
+        }
+
+        let parameter_types = parameter_member_types(&plan, meta, &markers);
+
+        Some(Self {
+            plan,
+            ctx,
+            markers,
+            parameter_types,
+            compiler_id: meta.compiler_id,
+            variables: variables_snapshot(state),
+            security_policy: state.security_policy(),
+        })
+    }
+
+    /// Returns `None` when the plan can't be used for these values or the session has changed since
+    /// the statement was prepared, then the statement should be planned again.
+    pub fn bind(
+        &self,
+        values: &[BindValue],
+        compiler_id: Uuid,
+        state: &SessionState,
+        span_id: Option<Arc<SpanId>>,
+    ) -> Option<QueryPlan> {
+        if compiler_id != self.compiler_id
+            || values.len() != self.markers.len()
+            || variables_snapshot(state) != self.variables
+            || state.security_policy() != self.security_policy
+        {
+            return None;
+        }
+
+        let values = values
+            .iter()
+            .zip(self.parameter_types.iter())
+            .map(|(value, parameter_type)| match (value, parameter_type) {
+                (BindValue::String(v), PgTypeId::TIMESTAMP) => normalize_timestamp(v),
+                // Value of another type compared with a time dimension is planned as usual
+                (_, PgTypeId::TIMESTAMP) => None,
+                (BindValue::String(v), _) => Some(v.clone()),
+                (BindValue::Int64(v), _) => Some(v.to_string()),
+                (BindValue::Float64(v), _) => Some(v.to_string()),
+                (BindValue::Bool(v), _) => Some(v.to_string()),
+                // NULL comparison is rewritten to a different filter
+                (BindValue::Null, _) => None,
+            })
+            .collect::<Option<Vec<_>>>()?;
+        let auth_context = state.auth_context()?;
+
+        let plan = self.bind_plan(&self.plan, &values, &auth_context, &span_id)?;
+
+        Some(QueryPlan::DataFusionSelect(plan, self.ctx.clone()))
+    }
+
+    fn bind_plan(
+        &self,
+        plan: &LogicalPlan,
+        values: &[String],
+        auth_context: &AuthContextRef,
+        span_id: &Option<Arc<SpanId>>,
+    ) -> Option<LogicalPlan> {
+        if let LogicalPlan::Extension(Extension { node }) = plan {
+            if let Some(scan_node) = node.as_any().downcast_ref::<CubeScanNode>() {
+                let mut request = serde_json::to_value(&scan_node.request).ok()?;
+                bind_request_markers(&mut request, &self.markers, values);
+
+                return Some(LogicalPlan::Extension(Extension {
+                    node: Arc::new(CubeScanNode::new(
+                        scan_node.schema.clone(),
+                        scan_node.member_fields.clone(),
+                        serde_json::from_value(request).ok()?,
+                        auth_context.clone(),
+                        scan_node.options.clone(),
+                        scan_node.used_cubes.clone(),
+                        span_id.clone(),
+                    )),
+                }));
+            }
+        }
+
+        let inputs = plan
+            .inputs()
+            .into_iter()
+            .map(|input| self.bind_plan(input, values, auth_context, span_id))
+            .collect::<Option<Vec<_>>>()?;
+
+        from_plan(plan, &plan.expressions(), &inputs).ok()
+    }
+}
+
+/// Infers types of parameters compared with Cube members from the member types.
+pub fn infer_parameter_types(plan: &QueryPlan, meta: &MetaContext, parameters: &mut [PgTypeId]) {
+    if let QueryPlan::DataFusionSelect(plan, _) = plan {
+        let markers = (0..parameters.len())
+            .map(generic_plan_parameter_marker)
+            .collect::<Vec<_>>();
+
+        let member_types = parameter_member_types(plan, meta, &markers);
+        for (parameter, member_type) in parameters.iter_mut().zip(member_types) {
+            if *parameter == PgTypeId::TEXT {
+                *parameter = member_type;
+            }
+        }
+    }
+}
+
+/// Types of the members which parameters are compared with, `TEXT` when it's unknown.
+fn parameter_member_types(
+    plan: &LogicalPlan,
+    meta: &MetaContext,
+    markers: &[String],
+) -> Vec<PgTypeId> {
+    let mut parameter_types = vec![PgTypeId::TEXT; markers.len()];
+    infer_plan_parameter_types(plan, meta, markers, &mut parameter_types);
+
+    parameter_types
+}
+
+fn infer_plan_parameter_types(
+    plan: &LogicalPlan,
+    meta: &MetaContext,
+    markers: &[String],
+    parameters: &mut [PgTypeId],
+) {
+    if let LogicalPlan::Extension(Extension { node }) = plan {
+        if let Some(scan_node) = node.as_any().downcast_ref::<CubeScanNode>() {
+            if let Ok(request) = serde_json::to_value(&scan_node.request) {
+                infer_request_parameter_types(&request, None, meta, markers, parameters);
+            }
+
+            return;
+        }
+    }
+
+    for input in plan.inputs() {
+        infer_plan_parameter_types(input, meta, markers, parameters);
+    }
+}
+
+fn infer_request_parameter_types(
+    value: &Value,
+    member: Option<&str>,
+    meta: &MetaContext,
+    markers: &[String],
+    parameters: &mut [PgTypeId],
+) {
+    match value {
+        Value::Object(object) => {
+            // Filters are bound to "member", time dimensions to "dimension"
+            let member = object
+                .get("member")
+                .or_else(|| object.get("dimension"))
+                .and_then(Value::as_str)
+                .or(member);
+            for value in object.values() {
+                infer_request_parameter_types(value, member, meta, markers, parameters);
+            }
+        }
+        Value::Array(items) => {
+            for value in items {
+                infer_request_parameter_types(value, member, meta, markers, parameters);
+            }
+        }
+        Value::String(value) => {
+            let Some(member) = member else {
+                return;
+            };
+            let Some(index) = markers.iter().position(|marker| marker == value) else {
+                return;
+            };
+            if parameters[index] != PgTypeId::TEXT {
+                return;
+            }
+
+            let member_type = member
+                .split('.')
+                .next()
+                .and_then(|cube_name| meta.find_cube_with_name(cube_name))
+                .and_then(|cube| cube.member_type(member));
+            parameters[index] = match member_type {
+                Some(MemberType::Number) => PgTypeId::FLOAT8,
+                Some(MemberType::Time) => PgTypeId::TIMESTAMP,
+                Some(MemberType::Boolean) => PgTypeId::BOOL,
+                Some(MemberType::String) | None => PgTypeId::TEXT,
+            };
+        }
+        _ => {}
+    }
+}
+
+/// Marks parameters found in Cube requests, returns `false` when a marker is used anywhere else.
+fn collect_plan_markers(plan: &LogicalPlan, markers: &[String], found: &mut [bool]) -> bool {
+    if let LogicalPlan::Extension(Extension { node }) = plan {
+        if let Some(scan_node) = node.as_any().downcast_ref::<CubeScanNode>() {
+            return match serde_json::to_value(&scan_node.request) {
+                Ok(request) => collect_request_markers(&request, markers, found),
+                Err(_) => false,
+            };
+        }
+
+        if contains_marker(&format!("{:?}", node), markers) {
+            return false;
+        }
+    } else if contains_marker(&format!("{:?}", plan.expressions()), markers) {
+        return false;
+    }
+
+    plan.inputs()
+        .into_iter()
+        .all(|input| collect_plan_markers(input, markers, found))
+}
+
+fn collect_request_markers(value: &Value, markers: &[String], found: &mut [bool]) -> bool {
+    match value {
+        Value::Object(object) => object
+            .values()
+            .all(|value| collect_request_markers(value, markers, found)),
+        Value::Array(items) => items
+            .iter()
+            .all(|value| collect_request_markers(value, markers, found)),
+        Value::String(value) => match markers.iter().position(|marker| marker == value) {
+            Some(index) => {
+                found[index] = true;
+                true
+            }
+            // Marker is a part of another value, e.g. LIKE pattern
+            None => !contains_marker(value, markers),
+        },
+        _ => true,
+    }
+}
+
+fn bind_request_markers(value: &mut Value, markers: &[String], values: &[String]) {
+    match value {
+        Value::Object(object) => {
+            for value in object.values_mut() {
+                bind_request_markers(value, markers, values);
+            }
+        }
+        Value::Array(items) => {
+            for value in items.iter_mut() {
+                bind_request_markers(value, markers, values);
+            }
+        }
+        Value::String(value) => {
+            if let Some(index) = markers.iter().position(|marker| marker == value) {
+                *value = values[index].clone();
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Bound timestamps are formatted the same way as timestamp literals in filters of the rewritten
+/// plan, timestamps with a time zone are converted to UTC. Returns `None` for invalid values, the
+/// statement is planned as usual then.
+fn normalize_timestamp(value: &str) -> Option<String> {
+    let value = value.trim();
+
+    let timestamp = DateTime::parse_from_rfc3339(value)
+        .or_else(|_| DateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S%.f%#z"))
+        .map(|timestamp| timestamp.naive_utc())
+        .or_else(|_| NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S%.f"))
+        .or_else(|_| NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S%.f"))
+        .ok()
+        .or_else(|| {
+            NaiveDate::parse_from_str(value, "%Y-%m-%d")
+                .ok()
+                .and_then(|date| date.and_hms_opt(0, 0, 0))
+        })?;
+
+    Some(timestamp.format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string())
+}
+
+fn contains_marker(value: &str, markers: &[String]) -> bool {
+    markers.iter().any(|marker| value.contains(marker))
+}
+
+/// Plan depends on session variables (time zone, cube.ungrouped, etc.)
+fn variables_snapshot(state: &SessionState) -> Vec<(String, ScalarValue)> {
+    let mut variables = state
+        .all_variables()
+        .into_iter()
+        .map(|(name, variable)| (name, variable.value))
+        .collect::<Vec<_>>();
+    variables.sort_by(|(l, _), (r, _)| l.cmp(r));
+
+    variables
+}
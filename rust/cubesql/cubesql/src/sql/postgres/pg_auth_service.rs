@@ -3,7 +3,7 @@ use std::{collections::HashMap, fmt::Debug, sync::Arc};
 use async_trait::async_trait;
 
 use crate::{
-    sql::{AuthContextRef, SqlAuthService},
+    sql::{AuthContextRef, SecurityPolicyRef, SqlAuthService},
     CubeError,
 };
 
@@ -20,8 +20,8 @@ pub use pg_srv::{
 pub enum AuthenticationStatus {
     UnexpectedFrontendMessage,
     Failed(String),
-    // User name + auth context
-    Success(String, AuthContextRef),
+    // User name + auth context + security policy
+    Success(String, AuthContextRef, Option<SecurityPolicyRef>),
 }
 
 #[async_trait]
@@ -99,7 +99,11 @@ impl PostgresAuthService for PostgresAuthServiceDefaultImpl {
             }
         }
 
-        AuthenticationStatus::Success(user, authenticate_response.context)
+        AuthenticationStatus::Success(
+            user,
+            authenticate_response.context,
+            authenticate_response.security_policy,
+        )
     }
 
     fn get_pg_message_tag_parser(&self) -> Arc<dyn MessageTagParser> {
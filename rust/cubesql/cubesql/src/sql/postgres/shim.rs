@@ -1,6 +1,10 @@
 use std::{
-    backtrace::Backtrace, collections::HashMap, io::ErrorKind, pin::Pin, sync::Arc,
-    time::SystemTime,
+    backtrace::Backtrace,
+    collections::HashMap,
+    io::ErrorKind,
+    pin::Pin,
+    sync::Arc,
+    time::{Instant, SystemTime},
 };
 
 use super::{extended::PreparedStatement, pg_auth_service::AuthenticationStatus};
@@ -15,8 +19,9 @@ use crate::{
         compiler_cache::CompilerCacheEntry,
         df_type_to_pg_tid,
         extended::{Cursor, Portal, PortalBatch, PortalFrom},
-        statement::{PostgresStatementParamsFinder, StatementPlaceholderReplacer},
-        AuthContextRef, Session, SessionState,
+        generic_plan::{infer_parameter_types, GenericPlan},
+        statement::{GenericPlanPlaceholderReplacer, PostgresStatementParamsFinder},
+        AuthContextRef, PortalQueryStats, QueryFingerprint, Session, SessionState,
     },
     telemetry::ContextLogger,
     transport::{MetaContext, SpanId},
@@ -27,8 +32,8 @@ use log::{debug, error, trace};
 use pg_srv::{
     buffer,
     protocol::{
-        self, AuthenticationRequest, ErrorCode, ErrorResponse, Format, InitialMessage,
-        PortalCompletion,
+        self, AuthenticationRequest, CommandComplete, ErrorCode, ErrorResponse, Format,
+        InitialMessage, PortalCompletion,
     },
     PgType, PgTypeId, ProtocolError,
 };
@@ -629,6 +634,8 @@ impl AsyncPostgresShim {
 
         self.logger.error(message.as_str(), props);
 
+        // Any error aborts the current transaction block, same as in PostgreSQL
+        self.session.state.fail_transaction();
         self.write(err_response).await?;
 
         Ok(())
@@ -785,7 +792,9 @@ impl AsyncPostgresShim {
                 protocol::ErrorCode::InvalidAuthorizationSpecification,
             )),
             AuthenticationStatus::Failed(err) => Err((err, protocol::ErrorCode::InvalidPassword)),
-            AuthenticationStatus::Success(user, auth_context) => Ok((user, auth_context)),
+            AuthenticationStatus::Success(user, auth_context, security_policy) => {
+                Ok((user, auth_context, security_policy))
+            }
         };
 
         match result {
@@ -800,7 +809,7 @@ impl AsyncPostgresShim {
 
                 Ok(false)
             }
-            Ok((user, auth_context)) => {
+            Ok((user, auth_context, security_policy)) => {
                 let database = parameters
                     .get("database")
                     .map(|v| v.clone())
@@ -808,6 +817,7 @@ impl AsyncPostgresShim {
                 self.session.state.set_database(Some(database));
                 self.session.state.set_user(Some(user));
                 self.session.state.set_auth_context(Some(auth_context));
+                self.session.state.set_security_policy(security_policy);
 
                 self.write(protocol::Authentication::new(AuthenticationRequest::Ok))
                     .await?;
@@ -854,7 +864,9 @@ impl AsyncPostgresShim {
 
     pub async fn write_ready(&mut self) -> Result<(), ConnectionError> {
         self.write(protocol::ReadyForQuery::new(
-            if self.session.state.is_in_transaction() {
+            if self.session.state.is_in_failed_transaction() {
+                protocol::TransactionStatus::InFailedTransactionBlock
+            } else if self.session.state.is_in_transaction() {
                 protocol::TransactionStatus::InTransactionBlock
             } else {
                 protocol::TransactionStatus::Idle
@@ -965,56 +977,113 @@ impl AsyncPostgresShim {
             if portal.is_empty() {
                 self.write(protocol::EmptyQueryResponse::new()).await?;
             } else {
+                let transaction_completion = portal.transaction_completion();
+                if self.session.state.is_in_failed_transaction()
+                    && !matches!(
+                        transaction_completion,
+                        Some(CommandCompletion::Commit | CommandCompletion::Rollback)
+                    )
+                {
+                    return Err(failed_transaction_error(None));
+                }
+
                 let cancel = self
                     .session
                     .state
                     .begin_query(format!("portal #{}", execute.portal));
 
-                let mut portal = Pin::new(portal);
-                let stream = portal.execute(execute.max_rows as usize);
-                pin_mut!(stream);
+                let mut query_stats = portal.take_query_stats();
+                let start_time = Instant::now();
 
-                loop {
-                    tokio::select! {
-                        _ = cancel.cancelled() => {
-                            self.session.state.end_query();
+                let result: Result<Option<PortalCompletion>, ConnectionError> = {
+                    let mut portal = Pin::new(&mut *portal);
+                    let stream = portal.execute(execute.max_rows as usize);
+                    pin_mut!(stream);
 
-                            return Err(protocol::ErrorResponse::query_canceled().into());
-                        },
-                        chunk = stream.next() => {
-                            let chunk = match chunk {
-                                Some(chunk) => match chunk {
-                                    Ok(chunk) => chunk,
-                                    Err(_) => {
-                                        self.session.state.end_query();
-                                        chunk?
-                                    }
-                                },
-                                None => return Ok(()),
-                            };
+                    loop {
+                        tokio::select! {
+                            _ = cancel.cancelled() => {
+                                break Err(protocol::ErrorResponse::query_canceled().into());
+                            },
+                            chunk = stream.next() => {
+                                let chunk = match chunk {
+                                    Some(Ok(chunk)) => chunk,
+                                    Some(Err(err)) => break Err(err),
+                                    None => break Ok(None),
+                                };
+
+                                if cancel.is_cancelled() {
+                                    break Err(protocol::ErrorResponse::query_canceled().into());
+                                }
+
+                                match chunk {
+                                    PortalBatch::Rows(writer) if writer.has_data() => {
+                                        if let Err(err) = buffer::write_direct(&mut self.partial_write_buf, &mut self.socket, writer).await {
+                                            break Err(err.into());
+                                        }
+                                    },
+                                    PortalBatch::Completion(completion) => break Ok(Some(completion)),
+                                    _ => (),
+                                }
+                            },
+                        }
+                    }
+                };
 
-                            if cancel.is_cancelled() {
-                                self.session.state.end_query();
+                self.session.state.end_query();
 
-                                return Err(protocol::ErrorResponse::query_canceled().into());
-                            }
+                let execution_stats = self.session.state.take_query_stats();
+                if let Some(query_stats) = query_stats.as_mut() {
+                    query_stats.duration += start_time.elapsed();
+                    query_stats.stats.rows += execution_stats.rows;
+                }
 
-                            match chunk {
-                                PortalBatch::Rows(writer) if writer.has_data() => buffer::write_direct(&mut self.partial_write_buf, &mut self.socket, writer).await?,
-                                PortalBatch::Completion(completion) => {
-                                    self.session.state.end_query();
+                let completion = match result {
+                    Ok(Some(completion)) => completion,
+                    Ok(None) => {
+                        self.put_back_portal_query_stats(&execute.portal, query_stats);
 
-                                    // TODO:
-                                    match completion {
-                                        PortalCompletion::Complete(c) => buffer::write_message(&mut self.partial_write_buf, &mut self.socket, c).await?,
-                                        PortalCompletion::Suspended(s) => buffer::write_message(&mut self.partial_write_buf, &mut self.socket, s).await?,
-                                    }
+                        return Ok(());
+                    }
+                    Err(err) => {
+                        if let Some(query_stats) = query_stats {
+                            self.record_portal_query_stats(query_stats, true);
+                        }
 
-                                    return Ok(());
-                                },
-                                _ => (),
+                        return Err(err);
+                    }
+                };
+
+                // TODO:
+                match completion {
+                    PortalCompletion::Complete(c) => {
+                        if let Some(mut query_stats) = query_stats {
+                            if let CommandComplete::Select(rows) | CommandComplete::Fetch(rows) = &c
+                            {
+                                query_stats.stats.rows += *rows as u64;
                             }
-                        },
+                            self.record_portal_query_stats(query_stats, false);
+                        }
+
+                        match transaction_completion {
+                            Some(CommandCompletion::Begin) => {
+                                self.session.state.begin_transaction();
+                            }
+                            Some(_) => {
+                                self.end_transaction()?;
+                            }
+                            None => (),
+                        }
+
+                        buffer::write_message(&mut self.partial_write_buf, &mut self.socket, c)
+                            .await?
+                    }
+                    PortalCompletion::Suspended(s) => {
+                        // Execution continues with the next Execute message
+                        self.put_back_portal_query_stats(&execute.portal, query_stats);
+
+                        buffer::write_message(&mut self.partial_write_buf, &mut self.socket, s)
+                            .await?
                     }
                 }
             };
@@ -1029,6 +1098,12 @@ impl AsyncPostgresShim {
         }
     }
 
+    fn put_back_portal_query_stats(&mut self, name: &str, query_stats: Option<PortalQueryStats>) {
+        if let (Some(portal), Some(query_stats)) = (self.portals.get_mut(name), query_stats) {
+            portal.set_query_stats(query_stats);
+        }
+    }
+
     pub async fn bind(
         &mut self,
         body: protocol::Bind,
@@ -1056,6 +1131,12 @@ impl AsyncPostgresShim {
             )
         })?;
 
+        if self.session.state.is_in_failed_transaction()
+            && !matches!(source_statement, PreparedStatement::Query { query, .. } if is_transaction_exit(query))
+        {
+            return Err(failed_transaction_error(span_id));
+        }
+
         let format = body.result_formats.first().unwrap_or(&Format::Text).clone();
         let portal = match source_statement {
             PreparedStatement::Empty { .. } => {
@@ -1063,24 +1144,63 @@ impl AsyncPostgresShim {
 
                 Portal::new_empty(format, PortalFrom::Extended, span_id)
             }
-            PreparedStatement::Query { parameters, .. } => {
-                let prepared_statement =
-                    source_statement.bind(body.to_bind_values(&parameters)?)?;
+            PreparedStatement::Query {
+                parameters,
+                generic_plan,
+                fingerprint,
+                ..
+            } => {
+                let values = body.to_bind_values(&parameters)?;
+                let generic_plan = generic_plan.clone();
+                let fingerprint = fingerprint.clone();
+                let prepared_statement = source_statement.bind(values.clone())?;
                 drop(statements_guard);
 
                 let cache_entry = self.get_cache_entry().await?;
                 let meta = self.session.server.compiler_cache.meta(cache_entry).await?;
 
-                let plan = convert_statement_to_cube_query(
-                    prepared_statement,
-                    meta,
-                    self.session.clone(),
-                    &mut None,
-                    span_id.clone(),
-                )
-                .await?;
+                // Planning is a part of the statement execution, it's recorded with the portal
+                let planning_start = Instant::now();
+                self.session.state.take_query_stats();
+
+                let generic_plan = generic_plan.and_then(|generic_plan| {
+                    generic_plan.bind(
+                        &values,
+                        meta.compiler_id,
+                        &self.session.state,
+                        span_id.clone(),
+                    )
+                });
+                let plan = match generic_plan {
+                    Some(plan) => Ok(plan),
+                    None => {
+                        convert_statement_to_cube_query(
+                            prepared_statement,
+                            meta,
+                            self.session.clone(),
+                            &mut None,
+                            span_id.clone(),
+                        )
+                        .await
+                    }
+                };
+
+                let query_stats = PortalQueryStats {
+                    fingerprint,
+                    duration: planning_start.elapsed(),
+                    stats: self.session.state.take_query_stats(),
+                };
+                let plan = match plan {
+                    Ok(plan) => plan,
+                    Err(err) => {
+                        self.record_portal_query_stats(query_stats, true);
+
+                        return Err(err.into());
+                    }
+                };
 
                 Portal::new(plan, format, PortalFrom::Extended, span_id)
+                    .with_query_stats(query_stats)
             }
             PreparedStatement::Error { .. } => {
                 drop(statements_guard);
@@ -1117,6 +1237,10 @@ impl AsyncPostgresShim {
                     if let Some(qtrace) = qtrace {
                         qtrace.push_statement(&query);
                     }
+                    if self.session.state.is_in_failed_transaction() && !is_transaction_exit(&query)
+                    {
+                        return Err(failed_transaction_error(span_id));
+                    }
                     self.prepare_statement(parse.name, Ok(query), false, qtrace, span_id.clone())
                         .await?;
                 }
@@ -1171,7 +1295,7 @@ impl AsyncPostgresShim {
         let (pstmt, result) = match query {
             Ok(query) => {
                 let stmt_finder = PostgresStatementParamsFinder::new();
-                let parameters: Vec<PgTypeId> = stmt_finder
+                let mut parameters: Vec<PgTypeId> = stmt_finder
                     .find(&query)?
                     .into_iter()
                     .map(|param| param.coltype.to_pg_tid())
@@ -1180,18 +1304,28 @@ impl AsyncPostgresShim {
                 let cache_entry = self.get_cache_entry().await?;
                 let meta = self.session.server.compiler_cache.meta(cache_entry).await?;
 
-                let stmt_replacer = StatementPlaceholderReplacer::new();
+                // Parameters compared with columns are replaced with markers,
+                // which are bound in the generic plan on execution
+                let stmt_replacer = GenericPlanPlaceholderReplacer::new();
                 let hacked_query = stmt_replacer.replace(query.clone())?;
 
                 let plan = convert_statement_to_cube_query(
                     hacked_query,
-                    meta,
+                    meta.clone(),
                     self.session.clone(),
                     qtrace,
                     span_id.clone(),
                 )
                 .await;
 
+                // Statement is identified by the plan built on Parse, executions may use the
+                // generic plan and skip the rewrite
+                let mut query_id = None;
+                self.session
+                    .state
+                    .update_query_stats(|stats| query_id = stats.query_id.take());
+                let fingerprint = QueryFingerprint::new(&query).with_query_id(query_id);
+
                 match plan {
                     Ok(plan) => {
                         let description =
@@ -1204,13 +1338,30 @@ impl AsyncPostgresShim {
                                     }
                                 });
 
+                        infer_parameter_types(&plan, &meta, &mut parameters);
+                        let generic_plan =
+                            if self.session.server.config_obj.enable_generic_plan_cache() {
+                                GenericPlan::try_new(
+                                    &query,
+                                    plan,
+                                    parameters.len(),
+                                    &meta,
+                                    &self.session.state,
+                                )
+                                .map(Arc::new)
+                            } else {
+                                None
+                            };
+
                         (
                             PreparedStatement::Query {
                                 from_sql,
                                 created: chrono::offset::Utc::now(),
                                 query,
+                                fingerprint,
                                 parameters: protocol::ParameterDescription::new(parameters),
                                 description,
+                                generic_plan,
                                 span_id,
                             },
                             Ok(()),
@@ -1279,28 +1430,37 @@ impl AsyncPostgresShim {
         span_id: Option<Arc<SpanId>>,
     ) -> Result<(), ConnectionError> {
         let cancel = self.session.state.begin_query(stmt.to_string());
+        let fingerprint = QueryFingerprint::new(&stmt);
+        let start_time = Instant::now();
 
         tokio::select! {
             _ = cancel.cancelled() => {
                 self.session.state.end_query();
+                self.record_query_stats(fingerprint, start_time, true);
 
-                // We don't return error, because query can contains multiple statements
-                // then cancel request will cancel only one query
-                self.write(protocol::ErrorResponse::query_canceled()).await?;
+                // Cancellation is an error as any other, it stops the remaining statements of the batch
                 if let Some(qtrace) = qtrace {
                     qtrace.set_statement_error_message("Execution cancelled by user");
                 }
 
-                Ok(())
+                Err(ConnectionError::Protocol(
+                    protocol::ErrorResponse::query_canceled().into(),
+                    span_id.clone(),
+                ))
             },
-            res = self.process_simple_query(stmt, meta, cancel.clone(), qtrace, span_id) => {
+            res = self.process_simple_query(stmt, meta, cancel.clone(), qtrace, span_id.clone()) => {
                 self.session.state.end_query();
+                self.record_query_stats(fingerprint, start_time, res.is_err() || cancel.is_cancelled());
 
                 if cancel.is_cancelled() {
-                    self.write(protocol::ErrorResponse::query_canceled()).await?;
                     if let Some(qtrace) = qtrace {
                         qtrace.set_statement_error_message("Execution cancelled by user");
                     }
+
+                    return Err(ConnectionError::Protocol(
+                        protocol::ErrorResponse::query_canceled().into(),
+                        span_id,
+                    ));
                 }
 
                 res
@@ -1308,6 +1468,27 @@ impl AsyncPostgresShim {
         }
     }
 
+    fn record_query_stats(&self, fingerprint: QueryFingerprint, start_time: Instant, failed: bool) {
+        let stats = self.session.state.take_query_stats();
+        self.session.server.query_stats.record(
+            self.session.state.user(),
+            fingerprint.with_query_id(stats.query_id),
+            start_time.elapsed(),
+            failed,
+            stats,
+        );
+    }
+
+    fn record_portal_query_stats(&self, query_stats: PortalQueryStats, failed: bool) {
+        self.session.server.query_stats.record(
+            self.session.state.user(),
+            query_stats.fingerprint,
+            query_stats.duration,
+            failed,
+            query_stats.stats,
+        );
+    }
+
     pub async fn process_simple_query(
         &mut self,
         stmt: ast::Statement,
@@ -1355,6 +1536,13 @@ impl AsyncPostgresShim {
                 .await?;
             }
             Statement::Commit { .. } => {
+                // COMMIT of the failed transaction rolls it back
+                let completion = if self.session.state.is_in_failed_transaction() {
+                    CommandCompletion::Rollback
+                } else {
+                    CommandCompletion::Commit
+                };
+
                 if self.end_transaction()? == false {
                     // PostgreSQL returns command completion anyway
                     self.write(protocol::NoticeResponse::warning(
@@ -1364,7 +1552,7 @@ impl AsyncPostgresShim {
                     .await?
                 };
 
-                let plan = QueryPlan::MetaOk(StatusFlags::empty(), CommandCompletion::Commit);
+                let plan = QueryPlan::MetaOk(StatusFlags::empty(), completion);
 
                 self.write_portal(
                     &mut Portal::new(plan, Format::Text, PortalFrom::Simple, span_id.clone()),
@@ -1777,7 +1965,17 @@ impl AsyncPostgresShim {
                                 buffer::write_direct(&mut self.partial_write_buf, &mut self.socket, writer).await?
                             }
                         }
-                        PortalBatch::Completion(completion) => return self.write_completion(completion).await,
+                        PortalBatch::Completion(completion) => {
+                            if let PortalCompletion::Complete(
+                                CommandComplete::Select(rows) | CommandComplete::Fetch(rows),
+                            ) = &completion
+                            {
+                                let rows = *rows as u64;
+                                self.session.state.update_query_stats(|stats| stats.rows += rows);
+                            }
+
+                            return self.write_completion(completion).await
+                        },
                     }
                 }
             }
@@ -1805,11 +2003,25 @@ impl AsyncPostgresShim {
         if statements.len() == 0 {
             self.write(protocol::EmptyQuery::new()).await?;
         } else {
+            // Statements of the batch outside of transaction block are executed in an implicit
+            // transaction: an error stops the batch and rolls back session variables
+            let implicit_transaction = if statements.len() > 1 {
+                Some(self.session.state.all_variables())
+            } else {
+                None
+            }
+            .filter(|_| !self.session.state.is_in_transaction());
+
             for statement in statements {
                 if let Some(qtrace) = qtrace {
                     qtrace.push_statement(&statement);
                 }
-                match std::panic::AssertUnwindSafe(self.handle_simple_query(
+                if self.session.state.is_in_failed_transaction() && !is_transaction_exit(&statement)
+                {
+                    return Err(failed_transaction_error(span_id.clone()));
+                }
+
+                let res = match std::panic::AssertUnwindSafe(self.handle_simple_query(
                     statement,
                     meta.clone(),
                     qtrace,
@@ -1824,15 +2036,25 @@ impl AsyncPostgresShim {
                                 qtrace.set_statement_error_message(&err.to_string());
                             }
                         }
-                        res?
+                        res
                     }
                     Err(err) => {
                         let err: ConnectionError = CubeError::panic(err).into();
                         if let Some(qtrace) = qtrace {
                             qtrace.set_statement_error_message(&err.to_string());
                         }
-                        return Err(err);
+                        Err(err)
+                    }
+                };
+
+                if res.is_err() {
+                    if let Some(variables) = implicit_transaction {
+                        if !self.session.state.is_in_transaction() {
+                            self.session.state.restore_variables(variables);
+                        }
                     }
+
+                    return res;
                 }
             }
         }
@@ -1909,3 +2131,23 @@ impl AsyncPostgresShim {
             .ok_or(CubeError::internal("must be auth".to_string()))
     }
 }
+
+/// COMMIT and ROLLBACK are the only statements accepted in the failed transaction block
+fn is_transaction_exit(statement: &Statement) -> bool {
+    matches!(
+        statement,
+        Statement::Commit { .. } | Statement::Rollback { .. }
+    )
+}
+
+fn failed_transaction_error(span_id: Option<Arc<SpanId>>) -> ConnectionError {
+    ConnectionError::Protocol(
+        protocol::ErrorResponse::error(
+            ErrorCode::InFailedSqlTransaction,
+            "current transaction is aborted, commands ignored until end of transaction block"
+                .to_string(),
+        )
+        .into(),
+        span_id,
+    )
+}
@@ -0,0 +1,328 @@
+use crate::{
+    compile::rewrite::{cost::CubePlanCost, rewriter::CubeEGraph},
+    sql::statement::StatementLiteralsNormalizer,
+    utils::egraph_hash,
+};
+use lru::LruCache;
+use sqlparser::ast;
+use std::{
+    collections::hash_map::DefaultHasher,
+    hash::{Hash, Hasher},
+    num::NonZeroUsize,
+    sync::Mutex,
+    time::{Duration, SystemTime},
+};
+
+/// Statistics of the statement in progress, collected by the session while it's planned and executed.
+#[derive(Debug, Clone, Default)]
+pub struct QueryExecutionStats {
+    pub rows: u64,
+    pub rewrite_time: Duration,
+    pub rewrite_failed: bool,
+    pub post_processing: bool,
+    /// Cost of the plan chosen by the rewrite, only for queries to Cube.
+    pub plan_cost: Option<CubePlanCost>,
+    /// Id of the parameterized plan, see `QueryFingerprint::plan_query_id`.
+    pub query_id: Option<i64>,
+}
+
+/// Normalized statement text (literals are replaced by placeholders) and its id. Statements which
+/// are rewritten are identified by their parameterized plan, other ones by the hash of the text.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueryFingerprint {
+    pub query_id: i64,
+    pub query: String,
+}
+
+impl QueryFingerprint {
+    pub fn new(stmt: &ast::Statement) -> Self {
+        let query = StatementLiteralsNormalizer::new()
+            .normalize(stmt.clone())
+            .to_string();
+
+        let mut hasher = DefaultHasher::new();
+        query.hash(&mut hasher);
+
+        Self {
+            query_id: hasher.finish() as i64,
+            query,
+        }
+    }
+
+    /// Id of the plan with literals replaced by parameters, it's the same key which is used by
+    /// `CUBESQL_PARAMETERIZED_REWRITE_CACHE`, so statements which differ only by values or
+    /// formatting share it.
+    pub fn plan_query_id(parameterized_graph: &CubeEGraph) -> i64 {
+        let hash = egraph_hash(parameterized_graph, None);
+
+        let mut bytes = [0; 8];
+        bytes.copy_from_slice(&hash[..8]);
+
+        i64::from_le_bytes(bytes)
+    }
+
+    pub fn with_query_id(mut self, query_id: Option<i64>) -> Self {
+        if let Some(query_id) = query_id {
+            self.query_id = query_id;
+        }
+
+        self
+    }
+}
+
+/// Statistics of a statement executed through a portal of the extended query protocol. It's planned
+/// on Bind and can be executed by several Execute messages, so statistics are kept with the portal
+/// and recorded when it completes.
+#[derive(Debug, Clone)]
+pub struct PortalQueryStats {
+    pub fingerprint: QueryFingerprint,
+    pub duration: Duration,
+    pub stats: QueryExecutionStats,
+}
+
+#[derive(Debug, Clone)]
+pub struct QueryStatsEntry {
+    /// User who executed the statement, statistics are collected per user like in PostgreSQL
+    pub user: Option<String>,
+    pub query_id: i64,
+    pub query: String,
+    pub calls: u64,
+    pub total_time: Duration,
+    pub max_time: Duration,
+    pub rows: u64,
+    pub rewrite_time: Duration,
+    pub errors: u64,
+    pub rewrite_errors: u64,
+    pub post_processing_calls: u64,
+}
+
+impl QueryStatsEntry {
+    fn new(user: Option<String>, fingerprint: QueryFingerprint) -> Self {
+        Self {
+            user,
+            query_id: fingerprint.query_id,
+            query: fingerprint.query,
+            calls: 0,
+            total_time: Duration::ZERO,
+            max_time: Duration::ZERO,
+            rows: 0,
+            rewrite_time: Duration::ZERO,
+            errors: 0,
+            rewrite_errors: 0,
+            post_processing_calls: 0,
+        }
+    }
+
+    pub fn mean_time(&self) -> Duration {
+        if self.calls == 0 {
+            Duration::ZERO
+        } else {
+            Duration::from_nanos((self.total_time.as_nanos() / self.calls as u128) as u64)
+        }
+    }
+}
+
+/// Bounded in-memory statistics of executed statements, the least recently executed statements
+/// are evicted first. Exposed as `pg_catalog.pg_stat_statements`.
+#[derive(Debug)]
+pub struct QueryStatsStore {
+    // None when statistics are disabled
+    entries: Option<Mutex<LruCache<(Option<String>, i64), QueryStatsEntry>>>,
+}
+
+impl QueryStatsStore {
+    pub fn new(max_entries: usize) -> Self {
+        Self {
+            entries: NonZeroUsize::new(max_entries).map(|size| Mutex::new(LruCache::new(size))),
+        }
+    }
+
+    pub fn record(
+        &self,
+        user: Option<String>,
+        fingerprint: QueryFingerprint,
+        duration: Duration,
+        failed: bool,
+        stats: QueryExecutionStats,
+    ) {
+        let Some(entries) = &self.entries else {
+            return;
+        };
+
+        let mut guard = entries
+            .lock()
+            .expect("failed to unlock query stats for writing");
+        let entry = guard.get_or_insert_mut((user.clone(), fingerprint.query_id), || {
+            QueryStatsEntry::new(user, fingerprint)
+        });
+
+        entry.calls += 1;
+        entry.total_time += duration;
+        entry.max_time = entry.max_time.max(duration);
+        entry.rows += stats.rows;
+        entry.rewrite_time += stats.rewrite_time;
+        if failed {
+            entry.errors += 1;
+        }
+        if stats.rewrite_failed {
+            entry.rewrite_errors += 1;
+        }
+        if stats.post_processing {
+            entry.post_processing_calls += 1;
+        }
+    }
+
+    pub fn entries(&self) -> Vec<QueryStatsEntry> {
+        let Some(entries) = &self.entries else {
+            return vec![];
+        };
+
+        let guard = entries
+            .lock()
+            .expect("failed to unlock query stats for reading");
+
+        guard.iter().map(|(_, entry)| entry.clone()).collect()
+    }
+
+    /// Drops all collected statistics, returns time of the reset.
+    pub fn reset(&self) -> SystemTime {
+        if let Some(entries) = &self.entries {
+            entries
+                .lock()
+                .expect("failed to unlock query stats for writing")
+                .clear();
+        }
+
+        SystemTime::now()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlparser::{dialect::PostgreSqlDialect, parser::Parser};
+
+    fn fingerprint(query: &str) -> QueryFingerprint {
+        let stmt = Parser::parse_sql(&PostgreSqlDialect {}, query)
+            .unwrap()
+            .pop()
+            .expect("must contain at least one statement");
+
+        QueryFingerprint::new(&stmt)
+    }
+
+    #[test]
+    fn test_query_stats_store() {
+        let store = QueryStatsStore::new(2);
+
+        let first = fingerprint("SELECT * FROM t WHERE id = 1");
+        assert_eq!(first, fingerprint("SELECT * FROM t WHERE id = 2"));
+
+        store.record(
+            Some("alice".to_string()),
+            first.clone(),
+            Duration::from_millis(10),
+            false,
+            QueryExecutionStats {
+                rows: 5,
+                ..Default::default()
+            },
+        );
+        store.record(
+            Some("alice".to_string()),
+            first.clone(),
+            Duration::from_millis(30),
+            true,
+            QueryExecutionStats {
+                rewrite_failed: true,
+                ..Default::default()
+            },
+        );
+
+        let entries = store.entries();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].query, "SELECT * FROM t WHERE id = $1");
+        assert_eq!(entries[0].calls, 2);
+        assert_eq!(entries[0].rows, 5);
+        assert_eq!(entries[0].errors, 1);
+        assert_eq!(entries[0].rewrite_errors, 1);
+        assert_eq!(entries[0].max_time, Duration::from_millis(30));
+        assert_eq!(entries[0].mean_time(), Duration::from_millis(20));
+
+        // Least recently used statement is evicted
+        store.record(
+            Some("alice".to_string()),
+            fingerprint("SELECT 1"),
+            Duration::ZERO,
+            false,
+            QueryExecutionStats::default(),
+        );
+        store.record(
+            Some("alice".to_string()),
+            fingerprint("SELECT a FROM t"),
+            Duration::ZERO,
+            false,
+            QueryExecutionStats::default(),
+        );
+        assert!(store
+            .entries()
+            .iter()
+            .all(|entry| entry.query_id != first.query_id));
+
+        store.reset();
+        assert!(store.entries().is_empty());
+    }
+
+    #[test]
+    fn test_query_stats_store_per_user() {
+        let store = QueryStatsStore::new(10);
+        let query = fingerprint("SELECT 1");
+
+        for user in ["alice", "bob", "bob"] {
+            store.record(
+                Some(user.to_string()),
+                query.clone(),
+                Duration::ZERO,
+                false,
+                QueryExecutionStats::default(),
+            );
+        }
+
+        let mut entries = store
+            .entries()
+            .into_iter()
+            .map(|entry| (entry.user, entry.query_id, entry.calls))
+            .collect::<Vec<_>>();
+        entries.sort();
+        assert_eq!(
+            entries,
+            vec![
+                (Some("alice".to_string()), query.query_id, 1),
+                (Some("bob".to_string()), query.query_id, 2),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_query_stats_entry_mean_time() {
+        let mut entry = QueryStatsEntry::new(None, fingerprint("SELECT 1"));
+        assert_eq!(entry.mean_time(), Duration::ZERO);
+
+        // Number of calls doesn't fit into u32
+        entry.calls = u32::MAX as u64 * 2;
+        entry.total_time = Duration::from_secs(u32::MAX as u64 * 6);
+        assert_eq!(entry.mean_time(), Duration::from_secs(3));
+    }
+
+    #[test]
+    fn test_query_fingerprint_with_query_id() {
+        let text = fingerprint("SELECT * FROM t WHERE id = 1");
+
+        let planned = text.clone().with_query_id(Some(42));
+        assert_eq!(planned.query_id, 42);
+        assert_eq!(planned.query, text.query);
+
+        // Statements which aren't rewritten keep the text hash
+        assert_eq!(text.clone().with_query_id(None), text);
+    }
+}
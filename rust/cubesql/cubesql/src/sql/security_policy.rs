@@ -0,0 +1,267 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
+
+use cubeclient::models::{V1LoadRequestQuery, V1LoadRequestQueryFilterItem};
+use serde::{Deserialize, Serialize};
+
+use crate::CubeError;
+
+/// Row-level security of the session, returned by `SqlAuthService::authenticate`.
+///
+/// It's enforced by cubesql itself: mandatory filters are added to every Cube request and
+/// invisible members are removed from the metadata used for planning and introspection, so
+/// it applies regardless of what the transport does with the security context.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SecurityPolicy {
+    #[serde(rename = "cubes", default)]
+    pub cubes: HashMap<String, CubeSecurityPolicy>,
+}
+
+/// Policy of the single cube. Cubes without policy are not restricted.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CubeSecurityPolicy {
+    /// Filters added to every request which uses the cube
+    #[serde(rename = "filters", default)]
+    pub filters: Vec<V1LoadRequestQueryFilterItem>,
+    /// Full names of visible members, all members are visible when it's not set
+    #[serde(rename = "visibleMembers", skip_serializing_if = "Option::is_none")]
+    pub visible_members: Option<HashSet<String>>,
+}
+
+pub type SecurityPolicyRef = Arc<SecurityPolicy>;
+
+impl SecurityPolicy {
+    pub fn new(cubes: HashMap<String, CubeSecurityPolicy>) -> Self {
+        Self { cubes }
+    }
+
+    pub fn is_member_visible(&self, member_name: &str) -> bool {
+        let Some((cube_name, _)) = member_name.split_once('.') else {
+            return true;
+        };
+
+        match self
+            .cubes
+            .get(cube_name)
+            .and_then(|cube| cube.visible_members.as_ref())
+        {
+            Some(visible_members) => visible_members.contains(member_name),
+            None => true,
+        }
+    }
+
+    pub fn cube_filters(&self, cube_name: &str) -> &[V1LoadRequestQueryFilterItem] {
+        self.cubes
+            .get(cube_name)
+            .map(|cube| cube.filters.as_slice())
+            .unwrap_or_default()
+    }
+
+    /// Checks that the request uses only visible members and adds mandatory filters of the cubes.
+    pub fn apply_to_request(
+        &self,
+        request: &mut V1LoadRequestQuery,
+        used_cubes: &[String],
+    ) -> Result<(), CubeError> {
+        let mut members = request
+            .measures
+            .iter()
+            .chain(request.dimensions.iter())
+            .chain(request.segments.iter())
+            .flatten()
+            .map(|member| member.as_str())
+            .chain(
+                request
+                    .time_dimensions
+                    .iter()
+                    .flatten()
+                    .map(|time_dimension| time_dimension.dimension.as_str()),
+            )
+            .chain(
+                request
+                    .order
+                    .iter()
+                    .flatten()
+                    .filter_map(|order| order.first().map(|member| member.as_str())),
+            )
+            .collect::<Vec<_>>();
+        for filter in request.filters.iter().flatten() {
+            Self::collect_filter_members(filter, &mut members);
+        }
+
+        // Members of joined cubes may be requested without the cube in used cubes
+        let mut cube_names = used_cubes.to_vec();
+        for member in members {
+            if !self.is_member_visible(member) {
+                return Err(CubeError::user(format!(
+                    "Member '{}' is not accessible",
+                    member
+                )));
+            }
+            if let Some((cube_name, _)) = member.split_once('.') {
+                if !cube_names.iter().any(|name| name == cube_name) {
+                    cube_names.push(cube_name.to_string());
+                }
+            }
+        }
+
+        for cube_name in cube_names.iter() {
+            let filters = self.cube_filters(cube_name);
+            if !filters.is_empty() {
+                request
+                    .filters
+                    .get_or_insert_with(Vec::new)
+                    .extend(filters.iter().cloned());
+            }
+        }
+
+        Ok(())
+    }
+
+    fn collect_filter_members<'a>(
+        filter: &'a V1LoadRequestQueryFilterItem,
+        members: &mut Vec<&'a str>,
+    ) {
+        members.extend(filter.member.as_deref());
+        for sub_filter in filter.or.iter().chain(filter.and.iter()).flatten() {
+            Self::collect_json_filter_members(sub_filter, members);
+        }
+    }
+
+    /// Logical filters keep their sub-filters as JSON, they can be nested at any depth
+    fn collect_json_filter_members<'a>(filter: &'a serde_json::Value, members: &mut Vec<&'a str>) {
+        members.extend(
+            ["member", "dimension"]
+                .iter()
+                .filter_map(|key| filter.get(key).and_then(|member| member.as_str())),
+        );
+        for key in ["or", "and"] {
+            if let Some(sub_filters) = filter.get(key).and_then(|value| value.as_array()) {
+                for sub_filter in sub_filters {
+                    Self::collect_json_filter_members(sub_filter, members);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tenant_policy() -> SecurityPolicy {
+        SecurityPolicy::new(HashMap::from([(
+            "Orders".to_string(),
+            CubeSecurityPolicy {
+                filters: vec![V1LoadRequestQueryFilterItem {
+                    member: Some("Orders.tenant_id".to_string()),
+                    operator: Some("equals".to_string()),
+                    values: Some(vec!["42".to_string()]),
+                    or: None,
+                    and: None,
+                }],
+                visible_members: Some(HashSet::from([
+                    "Orders.count".to_string(),
+                    "Orders.status".to_string(),
+                ])),
+            },
+        )]))
+    }
+
+    #[test]
+    fn test_member_visibility() {
+        let policy = tenant_policy();
+
+        assert!(policy.is_member_visible("Orders.count"));
+        assert!(!policy.is_member_visible("Orders.tenant_id"));
+        assert!(policy.is_member_visible("Users.name"));
+    }
+
+    #[test]
+    fn test_apply_to_request() {
+        let policy = tenant_policy();
+
+        let mut request = V1LoadRequestQuery {
+            measures: Some(vec!["Orders.count".to_string()]),
+            dimensions: Some(vec!["Orders.status".to_string()]),
+            ..Default::default()
+        };
+        policy
+            .apply_to_request(&mut request, &["Orders".to_string()])
+            .unwrap();
+        assert_eq!(
+            request.filters,
+            Some(policy.cube_filters("Orders").to_vec())
+        );
+
+        let mut request = V1LoadRequestQuery {
+            dimensions: Some(vec!["Orders.tenant_id".to_string()]),
+            ..Default::default()
+        };
+        let err = policy
+            .apply_to_request(&mut request, &["Orders".to_string()])
+            .unwrap_err();
+        assert_eq!(err.message, "Member 'Orders.tenant_id' is not accessible");
+    }
+
+    #[test]
+    fn test_apply_to_request_nested_filters() {
+        let policy = tenant_policy();
+
+        let mut request: V1LoadRequestQuery = serde_json::from_value(serde_json::json!({
+            "measures": ["Orders.count"],
+            "filters": [{
+                "or": [
+                    { "member": "Orders.status", "operator": "equals", "values": ["new"] },
+                    {
+                        "and": [
+                            { "member": "Orders.tenant_id", "operator": "equals", "values": ["1"] }
+                        ]
+                    }
+                ]
+            }]
+        }))
+        .unwrap();
+        let err = policy
+            .apply_to_request(&mut request, &["Orders".to_string()])
+            .unwrap_err();
+        assert_eq!(err.message, "Member 'Orders.tenant_id' is not accessible");
+    }
+
+    #[test]
+    fn test_apply_to_request_order() {
+        let policy = tenant_policy();
+
+        let mut request = V1LoadRequestQuery {
+            measures: Some(vec!["Orders.count".to_string()]),
+            order: Some(vec![vec![
+                "Orders.tenant_id".to_string(),
+                "asc".to_string(),
+            ]]),
+            ..Default::default()
+        };
+        let err = policy
+            .apply_to_request(&mut request, &["Orders".to_string()])
+            .unwrap_err();
+        assert_eq!(err.message, "Member 'Orders.tenant_id' is not accessible");
+    }
+
+    #[test]
+    fn test_deserialize() {
+        let policy: SecurityPolicy = serde_json::from_value(serde_json::json!({
+            "cubes": {
+                "Orders": {
+                    "filters": [
+                        { "member": "Orders.tenant_id", "operator": "equals", "values": ["42"] }
+                    ],
+                    "visibleMembers": ["Orders.count", "Orders.status"]
+                }
+            }
+        }))
+        .unwrap();
+
+        assert_eq!(policy, tenant_policy());
+    }
+}
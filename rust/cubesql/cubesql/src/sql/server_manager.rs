@@ -5,6 +5,7 @@ use crate::{
         compiler_cache::CompilerCache,
         database_variables::{mysql_default_global_variables, postgres_default_global_variables},
         pg_auth_service::PostgresAuthService,
+        query_stats::QueryStatsStore,
         SqlAuthService,
     },
     transport::TransportService,
@@ -44,6 +45,7 @@ pub struct ServerManager {
     pub nonce: Option<Vec<u8>>,
     pub config_obj: Arc<dyn ConfigObj>,
     pub compiler_cache: Arc<dyn CompilerCache>,
+    pub query_stats: Arc<QueryStatsStore>,
     postgres_variables: RwLockSync<DatabaseVariables>,
     mysql_variables: RwLockSync<DatabaseVariables>,
 }
@@ -64,6 +66,7 @@ impl ServerManager {
             transport,
             pg_auth,
             compiler_cache,
+            query_stats: Arc::new(QueryStatsStore::new(config_obj.query_stats_max_entries())),
             nonce,
             config_obj,
             configuration: ServerConfiguration::default(),
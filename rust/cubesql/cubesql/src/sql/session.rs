@@ -1,3 +1,4 @@
+use chrono_tz::Tz;
 use datafusion::scalar::ScalarValue;
 use log::trace;
 use rand::Rng;
@@ -8,7 +9,10 @@ use std::{
 };
 use tokio_util::sync::CancellationToken;
 
-use super::{server_manager::ServerManager, session_manager::SessionManager, AuthContextRef};
+use super::{
+    server_manager::ServerManager, session_manager::SessionManager, AuthContextRef,
+    SecurityPolicyRef,
+};
 use crate::{
     compile::{
         DatabaseProtocol, DatabaseProtocolDetails, DatabaseVariable, DatabaseVariables,
@@ -17,6 +21,7 @@ use crate::{
     sql::{
         database_variables::{mysql_default_session_variables, postgres_default_session_variables},
         extended::PreparedStatement,
+        query_stats::QueryExecutionStats,
         temp_tables::TempTableManager,
     },
     transport::LoadRequestMeta,
@@ -40,11 +45,80 @@ static POSTGRES_DEFAULT_VARIABLES: LazyLock<DatabaseVariables> =
 static MYSQL_DEFAULT_VARIABLES: LazyLock<DatabaseVariables> =
     LazyLock::new(mysql_default_session_variables);
 
+/// Parses time zone as accepted by `SET TIME ZONE` / `SET time_zone`: IANA name or whole hour
+/// offset (`+03:00`, `-8`). Offsets are mapped to `Etc/GMT` zones which Cube understands.
+pub fn parse_timezone(name: &str) -> Option<Tz> {
+    let name = name.trim();
+    if name.eq_ignore_ascii_case("SYSTEM") || name.eq_ignore_ascii_case("Z") {
+        // MySQL reports UTC as system_time_zone
+        return Some(Tz::UTC);
+    }
+
+    if let Ok(tz) = name.parse::<Tz>() {
+        return Some(tz);
+    }
+
+    if let Ok(tz) = name.to_uppercase().parse::<Tz>() {
+        return Some(tz);
+    }
+
+    let (sign, offset) = match *name.as_bytes().first()? {
+        b'+' => (1, &name[1..]),
+        b'-' => (-1, &name[1..]),
+        _ => (1, name),
+    };
+    let (hours, minutes) = match offset.split_once(':') {
+        Some((hours, minutes)) => (hours, minutes),
+        None if offset.len() == 4 => offset.split_at(2),
+        None => (offset, "0"),
+    };
+    let hours = hours.parse::<i32>().ok()?;
+    if minutes.parse::<u32>().ok()? != 0 || !(0..=14).contains(&hours) {
+        return None;
+    }
+
+    if hours == 0 {
+        return Some(Tz::UTC);
+    }
+
+    // Etc/GMT zones use POSIX notation with inverted sign
+    format!("Etc/GMT{:+}", -sign * hours).parse::<Tz>().ok()
+}
+
+/// Parses boolean setting value the same way PostgreSQL does for `SET name = value`.
+pub fn parse_bool_setting(value: &str) -> Option<bool> {
+    match value.trim().to_lowercase().as_str() {
+        "on" | "true" | "yes" | "1" | "t" | "y" => Some(true),
+        "off" | "false" | "no" | "0" | "f" | "n" => Some(false),
+        _ => None,
+    }
+}
+
+pub fn is_utc_timezone(tz: &Tz) -> bool {
+    matches!(
+        tz,
+        Tz::UTC
+            | Tz::UCT
+            | Tz::GMT
+            | Tz::GMT0
+            | Tz::Universal
+            | Tz::Zulu
+            | Tz::Etc__UTC
+            | Tz::Etc__UCT
+            | Tz::Etc__GMT
+            | Tz::Etc__GMT0
+            | Tz::Etc__Universal
+            | Tz::Etc__Zulu
+    )
+}
+
 #[derive(Debug)]
 pub enum TransactionState {
     None,
     // Right now, it's 1 for all the time.
     Active(u64),
+    // Error happened inside the transaction block, commands are ignored until the end of it
+    Failed(u64),
 }
 
 #[derive(Debug)]
@@ -82,9 +156,13 @@ pub struct SessionState {
     // @todo Remove RWLock after split of Connection & SQLWorker
     // Context for Transport
     auth_context: RwLockSync<(Option<AuthContextRef>, SystemTime)>,
+    // Row-level security returned by authentication together with auth context
+    security_policy: RwLockSync<Option<SecurityPolicyRef>>,
 
     transaction: RwLockSync<TransactionState>,
     query: RwLockSync<QueryState>,
+    // Statistics of the active query, reported to QueryStatsStore when it ends
+    query_stats: RwLockSync<QueryExecutionStats>,
 
     // Extended Query
     pub statements: RWLockAsync<HashMap<String, PreparedStatement>>,
@@ -116,8 +194,10 @@ impl SessionState {
             temp_tables: Arc::new(TempTableManager::new(session_manager)),
             properties: RwLockSync::new(SessionProperties::new(None, None)),
             auth_context: RwLockSync::new((auth_context, SystemTime::now())),
+            security_policy: RwLockSync::new(None),
             transaction: RwLockSync::new(TransactionState::None),
             query: RwLockSync::new(QueryState::None),
+            query_stats: RwLockSync::new(QueryExecutionStats::default()),
             statements: RWLockAsync::new(HashMap::new()),
             auth_context_expiration,
         }
@@ -131,7 +211,33 @@ impl SessionState {
 
         match *guard {
             TransactionState::None => false,
-            TransactionState::Active(_) => true,
+            TransactionState::Active(_) | TransactionState::Failed(_) => true,
+        }
+    }
+
+    pub fn is_in_failed_transaction(&self) -> bool {
+        let guard = self
+            .transaction
+            .read()
+            .expect("failed to unlock transaction for is_in_failed_transaction");
+
+        matches!(*guard, TransactionState::Failed(_))
+    }
+
+    /// Marks active transaction as failed, returns `false` when there is no active transaction.
+    pub fn fail_transaction(&self) -> bool {
+        let mut guard = self
+            .transaction
+            .write()
+            .expect("failed to unlock transaction for fail_transaction");
+
+        match *guard {
+            TransactionState::Active(n) => {
+                *guard = TransactionState::Failed(n);
+
+                true
+            }
+            TransactionState::None | TransactionState::Failed(_) => false,
         }
     }
 
@@ -147,7 +253,7 @@ impl SessionState {
 
                 true
             }
-            TransactionState::Active(_) => false,
+            TransactionState::Active(_) | TransactionState::Failed(_) => false,
         }
     }
 
@@ -221,6 +327,12 @@ impl SessionState {
 
         let cancel = CancellationToken::new();
 
+        *self
+            .query_stats
+            .write()
+            .expect("failed to unlock query stats for begin_query") =
+            QueryExecutionStats::default();
+
         *guard = QueryState::Active {
             query,
             cancel: cancel.clone(),
@@ -229,13 +341,31 @@ impl SessionState {
         cancel
     }
 
+    pub fn update_query_stats(&self, update: impl FnOnce(&mut QueryExecutionStats)) {
+        let mut guard = self
+            .query_stats
+            .write()
+            .expect("failed to unlock query stats for writing");
+
+        update(&mut guard)
+    }
+
+    pub fn take_query_stats(&self) -> QueryExecutionStats {
+        let mut guard = self
+            .query_stats
+            .write()
+            .expect("failed to unlock query stats for writing");
+
+        std::mem::take(&mut *guard)
+    }
+
     pub fn end_transaction(&self) -> Option<u64> {
         let mut guard = self
             .transaction
             .write()
             .expect("failed to unlock transaction for checking end_transaction");
 
-        if let TransactionState::Active(n) = *guard {
+        if let TransactionState::Active(n) | TransactionState::Failed(n) = *guard {
             *guard = TransactionState::None;
 
             Some(n)
@@ -314,6 +444,28 @@ impl SessionState {
         *guard = (auth_context, SystemTime::now());
     }
 
+    pub fn is_superuser(&self) -> bool {
+        self.auth_context()
+            .map(|auth_context| auth_context.is_superuser())
+            .unwrap_or(false)
+    }
+
+    pub fn security_policy(&self) -> Option<SecurityPolicyRef> {
+        let guard = self
+            .security_policy
+            .read()
+            .expect("failed to unlock security_policy for reading");
+        guard.clone()
+    }
+
+    pub fn set_security_policy(&self, security_policy: Option<SecurityPolicyRef>) {
+        let mut guard = self
+            .security_policy
+            .write()
+            .expect("failed to unlock security_policy for writing");
+        *guard = security_policy;
+    }
+
     // TODO: Read without copy by holding acquired lock
     pub fn all_variables(&self) -> DatabaseVariables {
         let guard = self
@@ -350,6 +502,16 @@ impl SessionState {
         }
     }
 
+    /// Restores variables captured with `all_variables`, used to roll back implicit transactions.
+    pub fn restore_variables(&self, variables: DatabaseVariables) {
+        let mut guard = self
+            .variables
+            .write()
+            .expect("failed to unlock variables for writing");
+
+        *guard = Some(variables);
+    }
+
     pub fn set_variables(&self, variables: DatabaseVariablesToUpdate) {
         let mut to_override = false;
         let mut current_variables = self.all_variables();
@@ -377,6 +539,29 @@ impl SessionState {
         Arc::clone(&self.temp_tables)
     }
 
+    /// Time zone set for the session, `None` when the session works in UTC.
+    pub fn timezone(&self) -> Option<Tz> {
+        let name = match self.protocol {
+            DatabaseProtocol::MySQL => "time_zone",
+            _ => "timezone",
+        };
+
+        match self.get_variable(name)?.value {
+            ScalarValue::Utf8(Some(value)) => {
+                parse_timezone(&value).filter(|tz| !is_utc_timezone(tz))
+            }
+            _ => None,
+        }
+    }
+
+    /// Forces ungrouped queries to Cube, controlled by `SET cube.ungrouped = on`.
+    pub fn ungrouped(&self) -> bool {
+        match self.get_variable("cube.ungrouped").map(|v| v.value) {
+            Some(ScalarValue::Utf8(Some(value))) => parse_bool_setting(&value).unwrap_or(false),
+            _ => false,
+        }
+    }
+
     pub fn get_load_request_meta(&self) -> LoadRequestMeta {
         let application_name = if let Some(var) = self.get_variable("application_name") {
             Some(var.value.to_string())
@@ -520,7 +520,7 @@ impl FoundParameter {
 
 #[derive(Debug)]
 pub struct PostgresStatementParamsFinder {
-    parameters: HashMap<String, FoundParameter>,
+    parameters: HashMap<usize, FoundParameter>,
 }
 
 impl PostgresStatementParamsFinder {
@@ -553,7 +553,7 @@ impl<'ast> Visitor<'ast, ConnectionError> for PostgresStatementParamsFinder {
                 let position = self.extract_placeholder_index(&name)?;
 
                 self.parameters
-                    .insert(position.to_string(), FoundParameter::new(pt.to_coltype()));
+                    .insert(position, FoundParameter::new(pt.to_coltype()));
             }
             _ => {}
         };
@@ -624,6 +624,57 @@ impl<'ast> Visitor<'ast, ConnectionError> for PostgresStatementParamsBinder {
     }
 }
 
+/// Marker which replaces `?` placeholder of a MySQL prepared statement before it's parsed.
+pub fn mysql_parameter_marker(index: usize) -> String {
+    format!("__cube_mysql_parameter_{}__", index + 1)
+}
+
+/// Binds values of the binary protocol (COM_STMT_EXECUTE) to markers of [mysql_parameter_marker].
+/// Values are set in the AST, so they are never parsed as a part of SQL.
+#[derive(Debug)]
+pub struct MySqlStatementParamsBinder {
+    markers: Vec<String>,
+    values: Vec<BindValue>,
+}
+
+impl MySqlStatementParamsBinder {
+    pub fn new(values: Vec<BindValue>) -> Self {
+        Self {
+            markers: (0..values.len()).map(mysql_parameter_marker).collect(),
+            values,
+        }
+    }
+
+    pub fn bind(mut self, stmt: &mut ast::Statement) {
+        self.visit_statement(stmt).unwrap();
+    }
+}
+
+impl<'ast> Visitor<'ast, ConnectionError> for MySqlStatementParamsBinder {
+    fn visit_value(
+        &mut self,
+        value: &mut ast::Value,
+        _placeholder_type: PlaceholderType,
+    ) -> Result<(), ConnectionError> {
+        let ast::Value::SingleQuotedString(marker) = &value else {
+            return Ok(());
+        };
+        let Some(position) = self.markers.iter().position(|m| m == marker) else {
+            return Ok(());
+        };
+
+        *value = match &self.values[position] {
+            BindValue::String(v) => ast::Value::SingleQuotedString(v.clone()),
+            BindValue::Int64(v) => ast::Value::Number(v.to_string(), false),
+            BindValue::Float64(v) => ast::Value::Number(v.to_string(), false),
+            BindValue::Bool(v) => ast::Value::Boolean(*v),
+            BindValue::Null => ast::Value::Null,
+        };
+
+        Ok(())
+    }
+}
+
 #[derive(Debug)]
 pub struct StatementPlaceholderReplacer {}
 
@@ -663,6 +714,171 @@ impl<'ast> Visitor<'ast, ConnectionError> for StatementPlaceholderReplacer {
     }
 }
 
+/// Marker which is used instead of the placeholder value to build a generic plan of the prepared statement.
+pub fn generic_plan_parameter_marker(index: usize) -> String {
+    format!("__cube_parameter_{}__", index + 1)
+}
+
+/// Replaces placeholders compared with a column (`column = $1`, `column IN ($1, $2)`, `column BETWEEN $1 AND $2`)
+/// by markers, which can be found in the rewritten plan and substituted with bound values on execution.
+/// Rewrite of other placeholders may depend on the actual value (LIKE patterns, function arguments),
+/// they are replaced the same way as [StatementPlaceholderReplacer] does.
+#[derive(Debug)]
+pub struct GenericPlanPlaceholderReplacer {}
+
+impl GenericPlanPlaceholderReplacer {
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    pub fn replace(mut self, stmt: ast::Statement) -> Result<ast::Statement, ConnectionError> {
+        let mut result = stmt;
+
+        self.visit_statement(&mut result)?;
+
+        Ok(result)
+    }
+
+    fn is_column(expr: &Expr) -> bool {
+        matches!(expr, Expr::Identifier(_) | Expr::CompoundIdentifier(_))
+    }
+
+    fn replace_with_marker(&self, expr: &mut Expr) -> Result<(), ConnectionError> {
+        if let Expr::Value(Value::Placeholder(name)) = expr {
+            let position = self.extract_placeholder_index(name)?;
+            *expr = Expr::Value(Value::SingleQuotedString(generic_plan_parameter_marker(
+                position,
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+impl<'ast> Visitor<'ast, ConnectionError> for GenericPlanPlaceholderReplacer {
+    fn visit_value(
+        &mut self,
+        value: &mut ast::Value,
+        placeholder_type: PlaceholderType,
+    ) -> Result<(), ConnectionError> {
+        StatementPlaceholderReplacer::new().visit_value(value, placeholder_type)
+    }
+
+    fn visit_expr(&mut self, expr: &mut Expr) -> Result<(), ConnectionError> {
+        match expr {
+            Expr::BinaryOp { left, op, right }
+                if matches!(
+                    op,
+                    ast::BinaryOperator::Eq
+                        | ast::BinaryOperator::NotEq
+                        | ast::BinaryOperator::Lt
+                        | ast::BinaryOperator::LtEq
+                        | ast::BinaryOperator::Gt
+                        | ast::BinaryOperator::GtEq
+                ) =>
+            {
+                if Self::is_column(left) {
+                    self.replace_with_marker(right)?;
+                } else if Self::is_column(right) {
+                    self.replace_with_marker(left)?;
+                }
+            }
+            Expr::InList { expr, list, .. } if Self::is_column(expr) => {
+                for item in list.iter_mut() {
+                    self.replace_with_marker(item)?;
+                }
+            }
+            Expr::Between {
+                expr, low, high, ..
+            } if Self::is_column(expr) => {
+                self.replace_with_marker(low)?;
+                self.replace_with_marker(high)?;
+            }
+            _ => {}
+        }
+
+        self.visit_expr_with_placeholder_type(expr, PlaceholderType::String)
+    }
+}
+
+/// Functions and special date/time inputs which are evaluated during the rewrite
+const TIME_DEPENDENT_FUNCTIONS: [&str; 12] = [
+    "now",
+    "current_date",
+    "current_time",
+    "current_timestamp",
+    "localtime",
+    "localtimestamp",
+    "today",
+    "utc_timestamp",
+    "transaction_timestamp",
+    "statement_timestamp",
+    "clock_timestamp",
+    "unix_timestamp",
+];
+
+const TIME_DEPENDENT_INPUTS: [&str; 4] = ["now", "today", "tomorrow", "yesterday"];
+
+/// Finds values which depend on the current time: functions like `NOW()` and special inputs
+/// like `'now'::timestamp`. Plan of such statement can't be reused between executions.
+#[derive(Debug)]
+pub struct TimeDependenceFinder {
+    found: bool,
+}
+
+impl TimeDependenceFinder {
+    pub fn new() -> Self {
+        Self { found: false }
+    }
+
+    pub fn find(mut self, stmt: &ast::Statement) -> bool {
+        let mut stmt = stmt.clone();
+
+        self.visit_statement(&mut stmt).unwrap();
+
+        self.found
+    }
+
+    fn is_time_dependent_input(value: &str) -> bool {
+        TIME_DEPENDENT_INPUTS.contains(&value.trim().to_lowercase().as_str())
+    }
+}
+
+impl<'ast> Visitor<'ast, ConnectionError> for TimeDependenceFinder {
+    fn visit_value(
+        &mut self,
+        value: &mut ast::Value,
+        _placeholder_type: PlaceholderType,
+    ) -> Result<(), ConnectionError> {
+        if let Value::SingleQuotedString(value) = value {
+            if Self::is_time_dependent_input(value) {
+                self.found = true;
+            }
+        }
+
+        Ok(())
+    }
+
+    // Function names are visited as identifiers, `CURRENT_DATE` can be parsed as an identifier too
+    fn visit_identifier(&mut self, identifier: &mut Ident) -> Result<(), ConnectionError> {
+        if TIME_DEPENDENT_FUNCTIONS.contains(&identifier.value.to_lowercase().as_str()) {
+            self.found = true;
+        }
+
+        Ok(())
+    }
+
+    fn visit_expr(&mut self, expr: &mut Expr) -> Result<(), ConnectionError> {
+        if let Expr::TypedString { value, .. } = expr {
+            if Self::is_time_dependent_input(value) {
+                self.found = true;
+            }
+        }
+
+        self.visit_expr_with_placeholder_type(expr, PlaceholderType::String)
+    }
+}
+
 #[derive(Debug)]
 pub struct CastReplacer {}
 
@@ -1067,6 +1283,49 @@ impl<'a> Visitor<'a, ConnectionError> for ApproximateCountDistinctVisitor {
     }
 }
 
+/// Replaces literals with numbered placeholders, so queries which differ only by values share
+/// the same text. It's used as a fingerprint for query statistics.
+#[derive(Debug)]
+pub struct StatementLiteralsNormalizer {
+    position: usize,
+}
+
+impl StatementLiteralsNormalizer {
+    pub fn new() -> Self {
+        Self { position: 0 }
+    }
+
+    pub fn normalize(mut self, stmt: ast::Statement) -> ast::Statement {
+        let mut result = stmt;
+
+        self.visit_statement(&mut result).unwrap();
+
+        result
+    }
+}
+
+impl<'ast> Visitor<'ast, ConnectionError> for StatementLiteralsNormalizer {
+    fn visit_value(
+        &mut self,
+        val: &mut ast::Value,
+        _pt: PlaceholderType,
+    ) -> Result<(), ConnectionError> {
+        match val {
+            ast::Value::Number(_, _)
+            | ast::Value::SingleQuotedString(_)
+            | ast::Value::DoubleQuotedString(_)
+            | ast::Value::NationalStringLiteral(_)
+            | ast::Value::Boolean(_) => {
+                self.position += 1;
+                *val = ast::Value::Placeholder(format!("${}", self.position));
+            }
+            _ => (),
+        };
+
+        Ok(())
+    }
+}
+
 #[derive(Debug)]
 pub struct SensitiveDataSanitizer {}
 
@@ -1385,6 +1644,34 @@ mod tests {
         Ok(())
     }
 
+    fn assert_generic_plan_placeholder_replacer(input: &str, output: &str) {
+        let stmt = Parser::parse_sql(&PostgreSqlDialect {}, &input)
+            .unwrap()
+            .pop()
+            .expect("must contain at least one statement");
+
+        let result = GenericPlanPlaceholderReplacer::new().replace(stmt).unwrap();
+
+        assert_eq!(result.to_string(), output);
+    }
+
+    #[test]
+    fn test_generic_plan_placeholder_replacer() {
+        assert_generic_plan_placeholder_replacer(
+            "SELECT a FROM t WHERE b = $1 AND $2 < t.c",
+            "SELECT a FROM t WHERE b = '__cube_parameter_1__' AND '__cube_parameter_2__' < t.c",
+        );
+        assert_generic_plan_placeholder_replacer(
+            "SELECT a FROM t WHERE b IN ($1, $2) OR c BETWEEN $3 AND $4",
+            "SELECT a FROM t WHERE b IN ('__cube_parameter_1__', '__cube_parameter_2__') OR c BETWEEN '__cube_parameter_3__' AND '__cube_parameter_4__'",
+        );
+        // Rewrite of these placeholders depends on the value
+        assert_generic_plan_placeholder_replacer(
+            "SELECT a FROM t WHERE b LIKE $1 AND LOWER(c) = $2 LIMIT $3",
+            "SELECT a FROM t WHERE b LIKE 'replaced_placeholder' AND LOWER(c) = 'replaced_placeholder' LIMIT 1",
+        );
+    }
+
     fn assert_sensitive_data_sanitizer(input: &str, output: &str) -> Result<(), CubeError> {
         let stmt = Parser::parse_sql(&PostgreSqlDialect {}, &input)
             .unwrap()
@@ -1399,6 +1686,26 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_statement_literals_normalizer() -> Result<(), CubeError> {
+        let stmt = Parser::parse_sql(
+            &PostgreSqlDialect {},
+            "SELECT * FROM testdata WHERE email = 'to@replace.com' AND age > 18 LIMIT 10",
+        )
+        .unwrap()
+        .pop()
+        .expect("must contain at least one statement");
+
+        let result = StatementLiteralsNormalizer::new().normalize(stmt);
+
+        assert_eq!(
+            result.to_string(),
+            "SELECT * FROM testdata WHERE email = $1 AND age > $2 LIMIT $3"
+        );
+
+        Ok(())
+    }
+
     #[test]
     fn test_sensitive_data_sanitizer() -> Result<(), CubeError> {
         assert_sensitive_data_sanitizer(
@@ -3,17 +3,21 @@ use itertools::Itertools;
 use std::{collections::HashMap, ops::RangeFrom, sync::Arc};
 use uuid::Uuid;
 
-use crate::{sql::ColumnType, transport::SqlGenerator};
+use crate::{
+    sql::{ColumnType, SecurityPolicy},
+    transport::SqlGenerator,
+};
 
-use super::{CubeMeta, CubeMetaDimension, CubeMetaMeasure, V1CubeMetaExt};
+use super::{CubeCostHints, CubeMeta, CubeMetaDimension, CubeMetaMeasure, V1CubeMetaExt};
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct MetaContext {
     pub cubes: Vec<CubeMeta>,
     pub tables: Vec<CubeMetaTable>,
     pub cube_to_data_source: HashMap<String, String>,
     pub data_source_to_sql_generator: HashMap<String, Arc<dyn SqlGenerator + Send + Sync>>,
     pub compiler_id: Uuid,
+    pub cost_hints: HashMap<String, CubeCostHints>,
 }
 
 #[derive(Debug, Clone)]
@@ -72,7 +76,49 @@ impl MetaContext {
             cube_to_data_source,
             data_source_to_sql_generator,
             compiler_id,
+            cost_hints: HashMap::new(),
+        }
+    }
+
+    /// Hints from the transport about the data behind cubes, used to rank rewrite candidates.
+    pub fn with_cost_hints(mut self, cost_hints: HashMap<String, CubeCostHints>) -> Self {
+        self.cost_hints = cost_hints;
+        self
+    }
+
+    pub fn cube_cost_hints(&self, cube_name: &str) -> Option<&CubeCostHints> {
+        self.cost_hints.get(cube_name)
+    }
+
+    /// Copy of the context without members which are invisible by the security policy.
+    /// OIDs are kept, so they don't depend on the policy of the session.
+    pub fn restrict(&self, policy: &SecurityPolicy) -> Self {
+        let mut restricted = self.clone();
+        for cube in restricted.cubes.iter_mut() {
+            cube.measures
+                .retain(|measure| policy.is_member_visible(&measure.name));
+            cube.dimensions
+                .retain(|dimension| policy.is_member_visible(&dimension.name));
+            cube.segments
+                .retain(|segment| policy.is_member_visible(&segment.name));
         }
+        for table in restricted.tables.iter_mut() {
+            let Some(cube) = self.find_cube_with_name(&table.name) else {
+                continue;
+            };
+            // Virtual columns like __user are not members
+            table.columns.retain(|column| {
+                let member_name = cube.member_name(&column.name);
+                let is_member = cube.contains_member(&member_name)
+                    || cube
+                        .segments
+                        .iter()
+                        .any(|segment| segment.name.eq_ignore_ascii_case(&member_name));
+                !is_member || policy.is_member_visible(&member_name)
+            });
+        }
+
+        restricted
     }
 
     pub fn sql_generator_by_alias_to_cube(
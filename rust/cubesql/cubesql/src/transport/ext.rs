@@ -101,6 +101,8 @@ pub trait V1CubeMetaDimensionExt {
     fn get_sql_type(&self) -> ColumnType;
 
     fn is_time(&self) -> bool;
+
+    fn get_granularity_names(&self) -> Vec<String>;
 }
 
 impl V1CubeMetaDimensionExt for CubeMetaDimension {
@@ -114,6 +116,14 @@ impl V1CubeMetaDimensionExt for CubeMetaDimension {
         self.r#type.to_lowercase().eq("time")
     }
 
+    fn get_granularity_names(&self) -> Vec<String> {
+        self.granularities
+            .iter()
+            .flatten()
+            .map(|granularity| granularity.name.clone())
+            .collect()
+    }
+
     fn sql_can_be_null(&self) -> bool {
         // @todo Possible not null?
         true
@@ -129,6 +139,66 @@ impl V1CubeMetaDimensionExt for CubeMetaDimension {
     }
 }
 
+/// Builds a comment for the member, which is exposed as `pg_description` and MySQL `COLUMN_COMMENT`.
+pub fn cube_member_comment(
+    description: &Option<String>,
+    format: &Option<String>,
+    granularities: &[String],
+) -> Option<String> {
+    let mut details = Vec::new();
+    if let Some(format) = format {
+        details.push(format!("format: {}", format));
+    }
+    if !granularities.is_empty() {
+        details.push(format!("granularities: {}", granularities.join(", ")));
+    }
+
+    match (description, details.is_empty()) {
+        (description, true) => description.clone(),
+        (None, false) => Some(details.join("; ")),
+        (Some(description), false) => Some(format!("{} ({})", description, details.join("; "))),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CubeMemberKind {
+    Measure,
+    Dimension,
+    Segment,
+}
+
+impl CubeMemberKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CubeMemberKind::Measure => "measure",
+            CubeMemberKind::Dimension => "dimension",
+            CubeMemberKind::Segment => "segment",
+        }
+    }
+}
+
+/// Member of the cube with all metadata which is known about it, exposed as `information_schema.cube_members`.
+#[derive(Debug)]
+pub struct CubeMember {
+    pub member_name: String,
+    pub name: String,
+    pub kind: CubeMemberKind,
+    pub r#type: String,
+    pub agg_type: Option<String>,
+    pub format: Option<String>,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub alias_member: Option<String>,
+    pub granularities: Vec<String>,
+    pub meta: Option<serde_json::Value>,
+}
+
+impl CubeMember {
+    pub fn comment(&self) -> Option<String> {
+        cube_member_comment(&self.description, &self.format, &self.granularities)
+    }
+}
+
 #[derive(Debug)]
 pub struct CubeColumn {
     member_name: String,
@@ -163,6 +233,8 @@ impl CubeColumn {
 pub trait V1CubeMetaExt {
     fn get_columns(&self) -> Vec<CubeColumn>;
 
+    fn get_members(&self) -> Vec<CubeMember>;
+
     fn get_scan_columns(&self) -> Vec<CubeColumn>;
 
     fn contains_member(&self, member_name: &str) -> bool;
@@ -199,7 +271,7 @@ impl V1CubeMetaExt for CubeMeta {
             columns.push(CubeColumn {
                 member_name: measure.name.clone(),
                 name: measure.get_real_name(),
-                description: measure.description.clone(),
+                description: cube_member_comment(&measure.description, &measure.format, &[]),
                 column_type: measure.get_sql_type(),
                 can_be_null: false,
             });
@@ -209,7 +281,11 @@ impl V1CubeMetaExt for CubeMeta {
             columns.push(CubeColumn {
                 member_name: dimension.name.clone(),
                 name: dimension.get_real_name(),
-                description: dimension.description.clone(),
+                description: cube_member_comment(
+                    &dimension.description,
+                    &dimension.format,
+                    &dimension.get_granularity_names(),
+                ),
                 column_type: dimension.get_sql_type(),
                 can_be_null: dimension.sql_can_be_null(),
             });
@@ -244,6 +320,60 @@ impl V1CubeMetaExt for CubeMeta {
         columns
     }
 
+    fn get_members(&self) -> Vec<CubeMember> {
+        let mut members = Vec::new();
+
+        for measure in &self.measures {
+            members.push(CubeMember {
+                member_name: measure.name.clone(),
+                name: measure.get_real_name(),
+                kind: CubeMemberKind::Measure,
+                r#type: measure.r#type.clone(),
+                agg_type: measure.agg_type.clone(),
+                format: measure.format.clone(),
+                title: measure.title.clone(),
+                description: measure.description.clone(),
+                alias_member: None,
+                granularities: vec![],
+                meta: measure.meta.clone(),
+            });
+        }
+
+        for dimension in &self.dimensions {
+            members.push(CubeMember {
+                member_name: dimension.name.clone(),
+                name: dimension.get_real_name(),
+                kind: CubeMemberKind::Dimension,
+                r#type: dimension.r#type.clone(),
+                agg_type: None,
+                format: dimension.format.clone(),
+                title: dimension.title.clone(),
+                description: dimension.description.clone(),
+                alias_member: dimension.alias_member.clone(),
+                granularities: dimension.get_granularity_names(),
+                meta: dimension.meta.clone(),
+            });
+        }
+
+        for segment in &self.segments {
+            members.push(CubeMember {
+                member_name: segment.name.clone(),
+                name: segment.get_real_name(),
+                kind: CubeMemberKind::Segment,
+                r#type: "boolean".to_string(),
+                agg_type: None,
+                format: None,
+                title: Some(segment.title.clone()),
+                description: segment.description.clone(),
+                alias_member: None,
+                granularities: vec![],
+                meta: segment.meta.clone(),
+            });
+        }
+
+        members
+    }
+
     fn get_scan_columns(&self) -> Vec<CubeColumn> {
         let mut columns = Vec::new();
 
@@ -384,3 +514,88 @@ pub fn df_data_type_by_column_type(column_type: ColumnType) -> DataType {
         _ => panic!("Unimplemented support for {:?}", column_type),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transport::CubeMetaType;
+    use cubeclient::models::V1CubeMetaDimensionGranularity;
+
+    #[test]
+    fn test_cube_members_comment() {
+        let cube = CubeMeta {
+            name: "Orders".to_string(),
+            description: None,
+            title: None,
+            r#type: CubeMetaType::Cube,
+            dimensions: vec![CubeMetaDimension {
+                name: "Orders.createdAt".to_string(),
+                description: Some("Order creation time".to_string()),
+                r#type: "time".to_string(),
+                granularities: Some(vec![V1CubeMetaDimensionGranularity::new(
+                    "fiscal_year".to_string(),
+                    "Fiscal Year".to_string(),
+                    "1 year".to_string(),
+                )]),
+                ..CubeMetaDimension::default()
+            }],
+            measures: vec![
+                CubeMetaMeasure {
+                    name: "Orders.revenue".to_string(),
+                    title: Some("Revenue".to_string()),
+                    r#type: "number".to_string(),
+                    agg_type: Some("sum".to_string()),
+                    format: Some("currency".to_string()),
+                    ..CubeMetaMeasure::default()
+                },
+                CubeMetaMeasure {
+                    name: "Orders.count".to_string(),
+                    description: Some("Orders count".to_string()),
+                    r#type: "number".to_string(),
+                    agg_type: Some("count".to_string()),
+                    ..CubeMetaMeasure::default()
+                },
+            ],
+            segments: vec![],
+            joins: None,
+            meta: None,
+        };
+
+        let comments = cube
+            .get_members()
+            .into_iter()
+            .map(|member| (member.name.clone(), member.kind, member.comment()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            comments,
+            vec![
+                (
+                    "revenue".to_string(),
+                    CubeMemberKind::Measure,
+                    Some("format: currency".to_string())
+                ),
+                (
+                    "count".to_string(),
+                    CubeMemberKind::Measure,
+                    Some("Orders count".to_string())
+                ),
+                (
+                    "createdAt".to_string(),
+                    CubeMemberKind::Dimension,
+                    Some("Order creation time (granularities: fiscal_year)".to_string())
+                ),
+            ]
+        );
+
+        // Columns are described the same way as members
+        let columns = cube.get_columns();
+        assert_eq!(
+            columns[0].get_description(),
+            &Some("format: currency".to_string())
+        );
+        assert_eq!(
+            columns[2].get_description(),
+            &Some("Order creation time (granularities: fiscal_year)".to_string())
+        );
+    }
+}
@@ -47,9 +47,17 @@ pub struct LoadRequestMeta {
     api_type: String,
     #[serde(rename = "appName")]
     app_name: Option<String>,
+    // Requests of the REST API are handled by JS as REST requests, e.g. member expressions
+    // are not allowed
+    #[serde(rename = "restApi")]
+    rest_api: bool,
     // Optional fields
     #[serde(rename = "changeUser", skip_serializing_if = "Option::is_none")]
     change_user: Option<String>,
+    // Transport retries the request on "Continue wait" until the result is ready, clients
+    // which implement the continue-wait protocol themselves get the error instead
+    #[serde(skip)]
+    retry_continue_wait: bool,
 }
 
 impl LoadRequestMeta {
@@ -60,6 +68,8 @@ impl LoadRequestMeta {
             api_type,
             app_name,
             change_user: None,
+            rest_api: false,
+            retry_continue_wait: true,
         }
     }
 
@@ -70,6 +80,22 @@ impl LoadRequestMeta {
     pub fn set_change_user(&mut self, change_user: Option<String>) {
         self.change_user = change_user;
     }
+
+    pub fn rest_api(&self) -> bool {
+        self.rest_api
+    }
+
+    pub fn set_rest_api(&mut self, rest_api: bool) {
+        self.rest_api = rest_api;
+    }
+
+    pub fn retry_continue_wait(&self) -> bool {
+        self.retry_continue_wait
+    }
+
+    pub fn set_retry_continue_wait(&mut self, retry_continue_wait: bool) {
+        self.retry_continue_wait = retry_continue_wait;
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -144,6 +170,23 @@ pub trait TransportService: Send + Sync + Debug {
         meta_fields: LoadRequestMeta,
     ) -> Result<TransportLoadResponse, CubeError>;
 
+    // Execute load query, allowing transport to return columnar data which skips JSON decoding.
+    // Transports which can't provide Arrow data fall back to `load`
+    async fn load_result(
+        &self,
+        span_id: Option<Arc<SpanId>>,
+        query: TransportLoadRequestQuery,
+        sql_query: Option<SqlQuery>,
+        ctx: AuthContextRef,
+        meta_fields: LoadRequestMeta,
+    ) -> Result<TransportLoadResult, CubeError> {
+        let response = self
+            .load(span_id, query, sql_query, ctx, meta_fields)
+            .await?;
+
+        Ok(TransportLoadResult::Json(response))
+    }
+
     async fn load_stream(
         &self,
         span_id: Option<Arc<SpanId>>,
@@ -182,6 +225,18 @@ pub trait SqlGenerator: Send + Sync + Debug {
     ) -> Result<String, CubeError>;
 }
 
+/// Result of a load query in the format the transport was able to provide.
+#[derive(Debug)]
+pub enum TransportLoadResult {
+    /// Rows as JSON objects keyed by member name
+    Json(TransportLoadResponse),
+    /// Columns keyed by member name, already decoded into Arrow batches
+    Arrow {
+        batches: Vec<RecordBatch>,
+        total: Option<i64>,
+    },
+}
+
 pub type CubeStreamReceiver = Receiver<Option<Result<RecordBatch, CubeError>>>;
 
 #[derive(Debug)]
@@ -246,12 +301,15 @@ impl TransportService for HttpTransport {
         };
 
         // Not used -- doesn't make sense to implement
-        let value = Arc::new(MetaContext::new(
-            response.cubes.unwrap_or_else(Vec::new),
-            HashMap::new(),
-            HashMap::new(),
-            Uuid::new_v4(),
-        ));
+        let value = Arc::new(
+            MetaContext::new(
+                response.cubes.unwrap_or_else(Vec::new),
+                HashMap::new(),
+                HashMap::new(),
+                Uuid::new_v4(),
+            )
+            .with_cost_hints(response.cost_hints.unwrap_or_default()),
+        );
 
         *store = Some(MetaCacheBucket {
             lifetime: Instant::now(),
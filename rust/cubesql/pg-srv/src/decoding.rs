@@ -5,6 +5,8 @@ use crate::{
     ProtocolError,
 };
 use byteorder::{BigEndian, ByteOrder};
+#[cfg(feature = "with-chrono")]
+use chrono::{Duration, NaiveDateTime};
 use std::backtrace::Backtrace;
 
 /// This trait explains how to decode values from the protocol
@@ -72,6 +74,63 @@ impl FromProtocolValue for i64 {
     }
 }
 
+impl FromProtocolValue for f64 {
+    fn from_text(raw: &[u8]) -> Result<Self, ProtocolError> {
+        let as_str = std::str::from_utf8(raw).map_err(|err| ProtocolError::ErrorResponse {
+            source: ErrorResponse::error(ErrorCode::ProtocolViolation, err.to_string()),
+            backtrace: Backtrace::capture(),
+        })?;
+
+        as_str
+            .parse::<f64>()
+            .map_err(|err| ProtocolError::ErrorResponse {
+                source: ErrorResponse::error(ErrorCode::ProtocolViolation, err.to_string()),
+                backtrace: Backtrace::capture(),
+            })
+    }
+
+    fn from_binary(raw: &[u8]) -> Result<Self, ProtocolError> {
+        Ok(BigEndian::read_f64(raw))
+    }
+}
+
+#[cfg(feature = "with-chrono")]
+impl FromProtocolValue for NaiveDateTime {
+    // timestamp_in - https://github.com/postgres/postgres/blob/REL_14_4/src/backend/utils/adt/timestamp.c#L147
+    fn from_text(raw: &[u8]) -> Result<Self, ProtocolError> {
+        let as_str = String::from_text(raw)?;
+
+        ["%Y-%m-%d %H:%M:%S%.f", "%Y-%m-%dT%H:%M:%S%.f"]
+            .iter()
+            .find_map(|format| NaiveDateTime::parse_from_str(&as_str, format).ok())
+            .ok_or_else(|| ProtocolError::ErrorResponse {
+                source: ErrorResponse::error(
+                    ErrorCode::ProtocolViolation,
+                    format!("Unable to decode timestamp from text, actual: {}", as_str),
+                ),
+                backtrace: Backtrace::capture(),
+            })
+    }
+
+    // timestamp_recv - https://github.com/postgres/postgres/blob/REL_14_4/src/backend/utils/adt/timestamp.c#L253
+    fn from_binary(raw: &[u8]) -> Result<Self, ProtocolError> {
+        let microseconds = BigEndian::read_i64(raw);
+
+        crate::encoding::pg_base_date_epoch()
+            .checked_add_signed(Duration::microseconds(microseconds))
+            .ok_or_else(|| ProtocolError::ErrorResponse {
+                source: ErrorResponse::error(
+                    ErrorCode::ProtocolViolation,
+                    format!(
+                        "Unable to decode timestamp from binary, actual: {}",
+                        microseconds
+                    ),
+                ),
+                backtrace: Backtrace::capture(),
+            })
+    }
+}
+
 impl FromProtocolValue for bool {
     fn from_text(raw: &[u8]) -> Result<Self, ProtocolError> {
         match raw[0] {
@@ -132,6 +191,7 @@ mod tests {
         assert_test_decode(false, Format::Text)?;
         assert_test_decode(1_i64, Format::Text)?;
         assert_test_decode(100_i64, Format::Text)?;
+        assert_test_decode(1.5_f64, Format::Text)?;
 
         Ok(())
     }
@@ -143,6 +203,30 @@ mod tests {
         assert_test_decode(false, Format::Binary)?;
         assert_test_decode(1_i64, Format::Binary)?;
         assert_test_decode(100_i64, Format::Binary)?;
+        assert_test_decode(1.5_f64, Format::Binary)?;
+
+        Ok(())
+    }
+
+    #[cfg(feature = "with-chrono")]
+    #[test]
+    fn test_timestamp_decoders() -> Result<(), ProtocolError> {
+        use chrono::{NaiveDate, NaiveDateTime};
+
+        let expected = NaiveDate::from_ymd_opt(2024, 1, 2)
+            .unwrap()
+            .and_hms_micro_opt(3, 4, 5, 6)
+            .unwrap();
+
+        assert_eq!(
+            NaiveDateTime::from_protocol(b"2024-01-02 03:04:05.000006", Format::Text)?,
+            expected
+        );
+        // 2024-01-02 03:04:05.000006 as microseconds since 2000-01-01
+        assert_eq!(
+            NaiveDateTime::from_protocol(&757479845000006_i64.to_be_bytes(), Format::Binary)?,
+            expected
+        );
 
         Ok(())
     }
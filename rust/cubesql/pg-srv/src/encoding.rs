@@ -109,7 +109,7 @@ impl_primitive!(f64);
 
 // POSTGRES_EPOCH_JDATE
 #[cfg(feature = "with-chrono")]
-fn pg_base_date_epoch() -> NaiveDateTime {
+pub(crate) fn pg_base_date_epoch() -> NaiveDateTime {
     NaiveDate::from_ymd_opt(2000, 1, 1)
         .unwrap()
         .and_hms_opt(0, 0, 0)
@@ -1,6 +1,6 @@
 //! Implementation for Extended Query
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum BindValue {
     String(String),
     Int64(i64),
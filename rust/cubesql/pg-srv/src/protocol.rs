@@ -17,6 +17,8 @@ use bytes::BufMut;
 use tokio::io::AsyncReadExt;
 
 use crate::{buffer, BindValue, FromProtocolValue, PgType, PgTypeId, ProtocolError};
+#[cfg(feature = "with-chrono")]
+use chrono::NaiveDateTime;
 
 const DEFAULT_CAPACITY: usize = 64;
 
@@ -783,6 +785,17 @@ impl Bind {
                     PgTypeId::INT8 => {
                         BindValue::Int64(i64::from_protocol(raw_value, param_format)?)
                     }
+                    PgTypeId::FLOAT8 => {
+                        BindValue::Float64(f64::from_protocol(raw_value, param_format)?)
+                    }
+                    // Timestamps are passed to the query as strings, text representation is kept as is
+                    #[cfg(feature = "with-chrono")]
+                    PgTypeId::TIMESTAMP => BindValue::String(match param_format {
+                        Format::Text => String::from_protocol(raw_value, param_format)?,
+                        Format::Binary => NaiveDateTime::from_protocol(raw_value, param_format)?
+                            .format("%Y-%m-%dT%H:%M:%S%.f")
+                            .to_string(),
+                    }),
                     _ => {
                         return Err(ErrorResponse::error(
                             ErrorCode::FeatureNotSupported,
@@ -961,6 +974,7 @@ pub enum ErrorCode {
     // Class 25 — Invalid Transaction State
     ActiveSqlTransaction,
     NoActiveSqlTransaction,
+    InFailedSqlTransaction,
     // 26
     InvalidSqlStatement,
     // 34
@@ -990,6 +1004,7 @@ impl Display for ErrorCode {
             Self::DataException => "22000",
             Self::ActiveSqlTransaction => "25001",
             Self::NoActiveSqlTransaction => "25P01",
+            Self::InFailedSqlTransaction => "25P02",
             Self::InvalidSqlStatement => "26000",
             Self::InvalidCursorName => "34000",
             Self::DuplicateCursor => "42P03",
@@ -1050,7 +1065,7 @@ impl Display for ErrorSeverity {
 pub enum TransactionStatus {
     Idle,
     InTransactionBlock,
-    // InFailedTransactionBlock,
+    InFailedTransactionBlock,
 }
 
 impl TransactionStatus {
@@ -1058,7 +1073,7 @@ impl TransactionStatus {
         match self {
             Self::Idle => b'I',
             Self::InTransactionBlock => b'T',
-            // Self::InFailedTransactionBlock => b'E',
+            Self::InFailedTransactionBlock => b'E',
         }
     }
 }
@@ -1,6 +1,7 @@
 use super::join_graph::{JoinGraph, NativeJoinGraph};
 use crate::cube_bridge::base_tools::{BaseTools, NativeBaseTools};
 use crate::cube_bridge::evaluator::{CubeEvaluator, NativeCubeEvaluator};
+use crate::cube_bridge::pre_aggregation_description::PreAggregationForQuery;
 use cubenativeutils::wrappers::serializer::{
     NativeDeserialize, NativeDeserializer, NativeSerialize,
 };
@@ -61,6 +62,10 @@ pub struct BaseQueryOptionsStatic {
     pub row_limit: Option<String>,
     pub offset: Option<String>,
     pub ungrouped: Option<bool>,
+    #[serde(rename = "preAggregationQuery")]
+    pub pre_aggregation_query: Option<bool>,
+    #[serde(rename = "preAggregationForQuery")]
+    pub pre_aggregation_for_query: Option<PreAggregationForQuery>,
 }
 
 #[nativebridge::native_bridge(BaseQueryOptionsStatic)]
@@ -27,6 +27,12 @@ pub trait BaseTools {
         granularity: String,
         dimension: String,
     ) -> Result<String, CubeError>;
+    fn date_bin(
+        &self,
+        interval: String,
+        source: String,
+        origin: String,
+    ) -> Result<String, CubeError>;
     fn sql_templates(&self) -> Result<Rc<dyn SqlTemplatesRender>, CubeError>;
     fn resolve_symbols_call_deps(
         &self,
@@ -38,11 +44,6 @@ pub trait BaseTools {
     fn filter_group_function(&self) -> Result<Rc<dyn FilterGroup>, CubeError>;
     fn timestamp_precision(&self) -> Result<u32, CubeError>;
     fn in_db_time_zone(&self, date: String) -> Result<String, CubeError>;
-    fn generate_time_series(
-        &self,
-        granularity: String,
-        date_range: Vec<String>,
-    ) -> Result<Vec<Vec<String>>, CubeError>;
     fn get_allocated_params(&self) -> Result<Vec<String>, CubeError>;
     fn all_cube_members(&self, path: String) -> Result<Vec<String>, CubeError>;
 }
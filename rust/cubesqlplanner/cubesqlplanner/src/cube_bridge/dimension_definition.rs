@@ -7,8 +7,17 @@ use cubenativeutils::wrappers::NativeObjectHandle;
 use cubenativeutils::CubeError;
 use serde::{Deserialize, Serialize};
 use std::any::Any;
+use std::collections::HashMap;
 use std::rc::Rc;
 
+/// Custom granularity of a time dimension, `origin` and `offset` are mutually exclusive
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct GranularityDefinition {
+    pub interval: String,
+    pub origin: Option<String>,
+    pub offset: Option<String>,
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct DimenstionDefinitionStatic {
     #[serde(rename = "type")]
@@ -17,6 +26,7 @@ pub struct DimenstionDefinitionStatic {
     pub owned_by_cube: Option<bool>,
     #[serde(rename = "multiStage")]
     pub multi_stage: Option<bool>,
+    pub granularities: Option<HashMap<String, GranularityDefinition>>,
 }
 
 #[nativebridge::native_bridge(DimenstionDefinitionStatic)]
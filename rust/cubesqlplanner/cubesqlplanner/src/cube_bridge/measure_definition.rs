@@ -60,6 +60,10 @@ pub trait MeasureDefinition {
     #[field]
     fn filters(&self) -> Result<Option<Rc<dyn MeasureFiltersVec>>, CubeError>;
 
+    #[optional]
+    #[field]
+    fn drill_filters(&self) -> Result<Option<Rc<dyn MeasureFiltersVec>>, CubeError>;
+
     #[optional]
     #[field]
     fn order_by(&self) -> Result<Option<Rc<dyn MemberOrderByVec>>, CubeError>;
@@ -14,5 +14,6 @@ pub mod measure_filter;
 pub mod member_definition;
 pub mod member_order_by;
 pub mod memeber_sql;
+pub mod pre_aggregation_description;
 pub mod security_context;
 pub mod sql_templates_render;
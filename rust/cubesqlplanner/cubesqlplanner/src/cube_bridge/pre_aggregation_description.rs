@@ -0,0 +1,44 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct PreAggregationTimeDimension {
+    pub dimension: String,
+    pub granularity: String,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct PreAggregationDescription {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub pre_aggregation_type: String,
+    #[serde(rename = "sqlAlias")]
+    pub sql_alias: Option<String>,
+    pub external: Option<bool>,
+    #[serde(default)]
+    pub measures: Vec<String>,
+    #[serde(default)]
+    pub dimensions: Vec<String>,
+    #[serde(default)]
+    pub segments: Vec<String>,
+    #[serde(rename = "timeDimensions", default)]
+    pub time_dimensions: Vec<PreAggregationTimeDimension>,
+    #[serde(rename = "allowNonStrictDateRangeMatch")]
+    pub allow_non_strict_date_range_match: Option<bool>,
+}
+
+/// Pre-aggregation selected for the query by `PreAggregations.findPreAggregationForQuery()`
+/// on the JS side, so both sides agree on the table which is built and read.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PreAggregationForQuery {
+    #[serde(rename = "cubeName")]
+    pub cube_name: String,
+    pub name: String,
+    #[serde(rename = "tableName")]
+    pub table_name: String,
+}
+
+impl PreAggregationDescription {
+    pub fn is_rollup(&self) -> bool {
+        self.pre_aggregation_type == "rollup"
+    }
+}
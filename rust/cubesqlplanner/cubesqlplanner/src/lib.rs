@@ -1,3 +1,6 @@
 pub mod cube_bridge;
 pub mod plan;
 pub mod planner;
+
+#[cfg(test)]
+mod test;
@@ -1,10 +1,14 @@
+use super::plan_description::{
+    FiltersDescription, JoinDescription, PreAggregationUsageDescription, QueryPlanDescription,
+};
 use super::planners::{
-    FullKeyAggregateQueryPlanner, MultiStageQueryPlanner, MultipliedMeasuresQueryPlanner,
-    SimpleQueryPlanner,
+    FullKeyAggregateQueryPlanner, MatchedRollup, MultiStageQueryPlanner,
+    MultipliedMeasuresQueryPlanner, PreAggregationMatcher, RollupQueryPlanner, SimpleQueryPlanner,
 };
 use super::query_tools::QueryTools;
 use super::QueryProperties;
 use crate::cube_bridge::base_query_options::BaseQueryOptions;
+use crate::cube_bridge::pre_aggregation_description::PreAggregationForQuery;
 use crate::plan::Select;
 use crate::planner::sql_evaluator::sql_nodes::SqlNodesFactory;
 use crate::planner::sql_templates::PlanSqlTemplates;
@@ -16,10 +20,28 @@ use cubenativeutils::wrappers::{NativeContextHolder, NativeObjectHandle};
 use cubenativeutils::CubeError;
 use std::rc::Rc;
 
+enum QueryPlanKind {
+    Rollup(MatchedRollup),
+    Simple,
+    FullKeyAggregate,
+}
+
+impl QueryPlanKind {
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Rollup(_) => "rollup",
+            Self::Simple => "simple",
+            Self::FullKeyAggregate => "full_key_aggregate",
+        }
+    }
+}
+
 pub struct BaseQuery<IT: InnerTypes> {
     context: NativeContextHolder<IT>,
     query_tools: Rc<QueryTools>,
     request: Rc<QueryProperties>,
+    pre_aggregation_query: bool,
+    pre_aggregation_for_query: Option<PreAggregationForQuery>,
 }
 
 impl<IT: InnerTypes> BaseQuery<IT> {
@@ -34,15 +56,42 @@ impl<IT: InnerTypes> BaseQuery<IT> {
             options.static_data().timezone.clone(),
         )?;
 
+        let pre_aggregation_query = options.static_data().pre_aggregation_query.unwrap_or(false);
+        let pre_aggregation_for_query = options.static_data().pre_aggregation_for_query.clone();
         let request = QueryProperties::try_new(query_tools.clone(), options)?;
 
         Ok(Self {
             context,
             query_tools,
             request,
+            pre_aggregation_query,
+            pre_aggregation_for_query,
         })
     }
 
+    /// Rollup selected on the JS side, if this planner can read the query from it.
+    /// Query which builds the pre-aggregation itself should never read from it
+    fn matched_rollup(&self) -> Result<Option<MatchedRollup>, CubeError> {
+        match &self.pre_aggregation_for_query {
+            Some(selected) if !self.pre_aggregation_query => {
+                PreAggregationMatcher::new(self.query_tools.clone(), self.request.clone())
+                    .try_match(selected)
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Chooses the planner, both SQL and the plan description are built from this decision
+    fn plan_kind(&self) -> Result<QueryPlanKind, CubeError> {
+        if let Some(rollup) = self.matched_rollup()? {
+            Ok(QueryPlanKind::Rollup(rollup))
+        } else if self.request.is_simple_query()? {
+            Ok(QueryPlanKind::Simple)
+        } else {
+            Ok(QueryPlanKind::FullKeyAggregate)
+        }
+    }
+
     pub fn build_sql_and_params(&self) -> Result<NativeObjectHandle<IT>, CubeError> {
         let templates = PlanSqlTemplates::new(self.query_tools.templates_render());
         let plan = self.build_sql_and_params_impl(templates.clone())?;
@@ -58,6 +107,66 @@ impl<IT: InnerTypes> BaseQuery<IT> {
         Ok(result)
     }
 
+    /// Returns a structured description of the plan instead of SQL: the chosen planner,
+    /// joins, full key aggregate subqueries, multi-stage CTEs and applied filters.
+    pub fn build_plan_description(&self) -> Result<NativeObjectHandle<IT>, CubeError> {
+        let description = self.build_plan_description_impl()?;
+        description.to_native(self.context.clone())
+    }
+
+    fn build_plan_description_impl(&self) -> Result<QueryPlanDescription, CubeError> {
+        let filters = FiltersDescription::new(
+            self.request.time_dimensions_filters(),
+            self.request.dimensions_filters(),
+            self.request.measures_filters(),
+        );
+
+        let plan_kind = self.plan_kind()?;
+        let planner = plan_kind.name().to_string();
+
+        match plan_kind {
+            QueryPlanKind::Rollup(rollup) => Ok(QueryPlanDescription {
+                planner,
+                pre_aggregation: Some(PreAggregationUsageDescription::new(&rollup)),
+                join: None,
+                subqueries: vec![],
+                multi_stage: vec![],
+                filters,
+            }),
+            QueryPlanKind::Simple => Ok(QueryPlanDescription {
+                planner,
+                pre_aggregation: None,
+                join: Some(JoinDescription::try_new(
+                    &self.request.simple_query_join()?,
+                )?),
+                subqueries: vec![],
+                multi_stage: vec![],
+                filters,
+            }),
+            QueryPlanKind::FullKeyAggregate => {
+                let multiplied_measures_query_planner = MultipliedMeasuresQueryPlanner::new(
+                    self.query_tools.clone(),
+                    self.request.clone(),
+                    SqlNodesFactory::new(),
+                );
+                let multi_stage_query_planner =
+                    MultiStageQueryPlanner::new(self.query_tools.clone(), self.request.clone());
+                let mut subqueries = multiplied_measures_query_planner.describe_queries()?;
+                let (multi_stage, multi_stage_subqueries) =
+                    multi_stage_query_planner.describe_queries()?;
+                subqueries.extend(multi_stage_subqueries.into_iter());
+                Ok(QueryPlanDescription {
+                    planner,
+                    pre_aggregation: None,
+                    join: None,
+                    subqueries,
+                    multi_stage,
+                    filters,
+                })
+            }
+        }
+    }
+
     fn build_sql_and_params_impl(&self, templates: PlanSqlTemplates) -> Result<Select, CubeError> {
         let mut nodes_factory = SqlNodesFactory::new();
 
@@ -65,33 +174,44 @@ impl<IT: InnerTypes> BaseQuery<IT> {
             nodes_factory.set_ungrouped(true)
         }
 
-        if self.request.is_simple_query()? {
-            let planner = SimpleQueryPlanner::new(
-                self.query_tools.clone(),
-                self.request.clone(),
-                nodes_factory.clone(),
-            );
-            planner.plan()
-        } else {
-            let request = self.request.clone();
-            let multiplied_measures_query_planner = MultipliedMeasuresQueryPlanner::new(
-                self.query_tools.clone(),
-                request.clone(),
-                nodes_factory.clone(),
-            );
-            let multi_stage_query_planner =
-                MultiStageQueryPlanner::new(self.query_tools.clone(), request.clone());
-            let full_key_aggregate_planner = FullKeyAggregateQueryPlanner::new(
-                request.clone(),
-                nodes_factory.clone(),
-                templates,
-            );
-            let mut subqueries = multiplied_measures_query_planner.plan_queries()?;
-            let (multi_stage_ctes, multi_stage_subqueries) =
-                multi_stage_query_planner.plan_queries()?;
-            subqueries.extend(multi_stage_subqueries.into_iter());
-            let result = full_key_aggregate_planner.plan(subqueries, multi_stage_ctes)?;
-            Ok(result)
+        match self.plan_kind()? {
+            QueryPlanKind::Rollup(rollup) => {
+                let planner = RollupQueryPlanner::new(
+                    self.query_tools.clone(),
+                    self.request.clone(),
+                    nodes_factory.clone(),
+                );
+                planner.plan(&rollup)
+            }
+            QueryPlanKind::Simple => {
+                let planner = SimpleQueryPlanner::new(
+                    self.query_tools.clone(),
+                    self.request.clone(),
+                    nodes_factory.clone(),
+                );
+                planner.plan()
+            }
+            QueryPlanKind::FullKeyAggregate => {
+                let request = self.request.clone();
+                let multiplied_measures_query_planner = MultipliedMeasuresQueryPlanner::new(
+                    self.query_tools.clone(),
+                    request.clone(),
+                    nodes_factory.clone(),
+                );
+                let multi_stage_query_planner =
+                    MultiStageQueryPlanner::new(self.query_tools.clone(), request.clone());
+                let full_key_aggregate_planner = FullKeyAggregateQueryPlanner::new(
+                    request.clone(),
+                    nodes_factory.clone(),
+                    templates,
+                );
+                let mut subqueries = multiplied_measures_query_planner.plan_queries()?;
+                let (multi_stage_ctes, multi_stage_subqueries) =
+                    multi_stage_query_planner.plan_queries()?;
+                subqueries.extend(multi_stage_subqueries.into_iter());
+                let result = full_key_aggregate_planner.plan(subqueries, multi_stage_ctes)?;
+                Ok(result)
+            }
         }
     }
 }
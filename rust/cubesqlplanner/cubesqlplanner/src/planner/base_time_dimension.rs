@@ -1,7 +1,8 @@
 use super::query_tools::QueryTools;
 use super::sql_evaluator::MemberSymbol;
-use super::BaseDimension;
+use super::{BaseDimension, Granularity, GranularityHelper};
 use super::{BaseMember, VisitorContext};
+use chrono_tz::Tz;
 use cubenativeutils::CubeError;
 use std::rc::Rc;
 
@@ -9,6 +10,7 @@ pub struct BaseTimeDimension {
     dimension: Rc<BaseDimension>,
     query_tools: Rc<QueryTools>,
     granularity: Option<String>,
+    granularity_obj: Option<Granularity>,
     date_range: Option<Vec<String>>,
     alias_suffix: String,
 }
@@ -55,29 +57,92 @@ impl BaseTimeDimension {
         } else {
             "day".to_string()
         };
+        let granularity_obj =
+            Self::resolve_granularity(&query_tools, &member_evaluator, &granularity)?;
         Ok(Rc::new(Self {
             dimension: BaseDimension::try_new_required(member_evaluator, query_tools.clone())?,
             query_tools,
             granularity,
+            granularity_obj,
             date_range,
             alias_suffix,
         }))
     }
 
-    pub fn change_granularity(&self, new_granularity: Option<String>) -> Rc<Self> {
-        Rc::new(Self {
+    pub fn change_granularity(
+        &self,
+        new_granularity: Option<String>,
+    ) -> Result<Rc<Self>, CubeError> {
+        let granularity_obj = Self::resolve_granularity(
+            &self.query_tools,
+            &self.dimension.member_evaluator(),
+            &new_granularity,
+        )?;
+        Ok(Rc::new(Self {
             dimension: self.dimension.clone(),
             query_tools: self.query_tools.clone(),
             granularity: new_granularity,
+            granularity_obj,
             date_range: self.date_range.clone(),
             alias_suffix: self.alias_suffix.clone(),
-        })
+        }))
+    }
+
+    fn resolve_granularity(
+        query_tools: &Rc<QueryTools>,
+        member_evaluator: &Rc<MemberSymbol>,
+        granularity: &Option<String>,
+    ) -> Result<Option<Granularity>, CubeError> {
+        let Some(granularity) = granularity else {
+            return Ok(None);
+        };
+        let timezone = query_tools.timezone().unwrap_or(Tz::UTC);
+
+        if GranularityHelper::is_predefined_granularity(granularity) {
+            return Ok(Some(Granularity::try_new_predefined(
+                timezone,
+                granularity.clone(),
+            )?));
+        }
+
+        let definition = match member_evaluator.as_ref() {
+            MemberSymbol::Dimension(dimension) => dimension.granularity_definition(granularity),
+            _ => None,
+        };
+        if let Some(definition) = definition {
+            Ok(Some(Granularity::try_new_custom(
+                timezone,
+                granularity.clone(),
+                definition.origin,
+                definition.interval,
+                definition.offset,
+            )?))
+        } else {
+            Err(CubeError::user(format!(
+                "Granularity \"{}\" does not exist in dimension {}",
+                granularity,
+                member_evaluator.full_name()
+            )))
+        }
     }
 
     pub fn get_granularity(&self) -> Option<String> {
         self.granularity.clone()
     }
 
+    pub fn get_granularity_obj(&self) -> &Option<Granularity> {
+        &self.granularity_obj
+    }
+
+    /// The biggest predefined granularity which can be used to calculate this one, for custom
+    /// granularities it takes origin and offset into account
+    pub fn min_granularity(&self) -> Result<Option<String>, CubeError> {
+        self.granularity_obj
+            .as_ref()
+            .map(|granularity| granularity.min_granularity())
+            .transpose()
+    }
+
     pub fn has_granularity(&self) -> bool {
         self.granularity.is_some()
     }
@@ -2,7 +2,7 @@ use super::filter_operator::FilterOperator;
 use crate::planner::query_tools::QueryTools;
 use crate::planner::sql_evaluator::MemberSymbol;
 use crate::planner::sql_templates::filter::FilterTemplates;
-use crate::planner::{evaluate_with_context, VisitorContext};
+use crate::planner::{evaluate_sql_call_with_context, evaluate_with_context, VisitorContext};
 use cubenativeutils::CubeError;
 use lazy_static::lazy_static;
 use regex::Regex;
@@ -96,13 +96,21 @@ impl BaseFilter {
     }
 
     pub fn to_sql(&self, context: Rc<VisitorContext>) -> Result<String, CubeError> {
-        let member_sql =
-            evaluate_with_context(&self.member_evaluator, self.query_tools.clone(), context)?;
+        let member_sql = evaluate_with_context(
+            &self.member_evaluator,
+            self.query_tools.clone(),
+            context.clone(),
+        )?;
         let res = match self.filter_operator {
             FilterOperator::Equal => self.equals_where(&member_sql)?,
             FilterOperator::NotEqual => self.not_equals_where(&member_sql)?,
             FilterOperator::InDateRange => self.in_date_range(&member_sql)?,
             FilterOperator::InDateRangeExtended => self.in_date_range_extended(&member_sql)?,
+            FilterOperator::NotInDateRange => self.not_in_date_range(&member_sql)?,
+            FilterOperator::BeforeDate => self.before_date(&member_sql)?,
+            FilterOperator::BeforeOrOnDate => self.before_or_on_date(&member_sql)?,
+            FilterOperator::AfterDate => self.after_date(&member_sql)?,
+            FilterOperator::AfterOrOnDate => self.after_or_on_date(&member_sql)?,
             FilterOperator::In => self.in_where(&member_sql)?,
             FilterOperator::NotIn => self.not_in_where(&member_sql)?,
             FilterOperator::Set => self.set_where(&member_sql)?,
@@ -117,6 +125,9 @@ impl BaseFilter {
             FilterOperator::NotStartsWith => self.not_starts_with_where(&member_sql)?,
             FilterOperator::EndsWith => self.ends_with_where(&member_sql)?,
             FilterOperator::NotEndsWith => self.not_ends_with_where(&member_sql)?,
+            FilterOperator::Regex => self.regex_where(&member_sql, false)?,
+            FilterOperator::NotRegex => self.regex_where(&member_sql, true)?,
+            FilterOperator::MeasureFilter => self.measure_filter_where(context)?,
         };
         Ok(res)
     }
@@ -159,6 +170,65 @@ impl BaseFilter {
             .time_range_filter(member_sql.to_string(), from, to)
     }
 
+    fn not_in_date_range(&self, member_sql: &str) -> Result<String, CubeError> {
+        let (from, to) = self.allocate_date_params()?;
+        self.templates
+            .time_not_in_range_filter(member_sql.to_string(), from, to)
+    }
+
+    // As in JS BaseFilter, single date operators take the start of the given date
+    fn before_date(&self, member_sql: &str) -> Result<String, CubeError> {
+        self.templates
+            .lt(member_sql.to_string(), self.first_timestamp_param()?)
+    }
+
+    fn before_or_on_date(&self, member_sql: &str) -> Result<String, CubeError> {
+        self.templates
+            .lte(member_sql.to_string(), self.first_timestamp_param()?)
+    }
+
+    fn after_date(&self, member_sql: &str) -> Result<String, CubeError> {
+        self.templates
+            .gt(member_sql.to_string(), self.first_timestamp_param()?)
+    }
+
+    fn after_or_on_date(&self, member_sql: &str) -> Result<String, CubeError> {
+        self.templates
+            .gte(member_sql.to_string(), self.first_timestamp_param()?)
+    }
+
+    // Filters and drill filters of the measure applied to the whole query, used in drill downs
+    fn measure_filter_where(&self, context: Rc<VisitorContext>) -> Result<String, CubeError> {
+        let measure = match self.member_evaluator.as_ref() {
+            MemberSymbol::Measure(measure) => measure,
+            _ => {
+                return Err(CubeError::user(format!(
+                    "measureFilter operator can be applied only to measures, but {} is not a measure",
+                    self.member_name()
+                )));
+            }
+        };
+        if measure.measure_filters().is_empty() && measure.measure_drill_filters().is_empty() {
+            return self.templates.always_true();
+        }
+        let filters = measure
+            .measure_filters()
+            .iter()
+            .chain(measure.measure_drill_filters().iter())
+            .map(|filter| -> Result<String, CubeError> {
+                Ok(format!(
+                    "({})",
+                    evaluate_sql_call_with_context(
+                        filter,
+                        self.query_tools.clone(),
+                        context.clone()
+                    )?
+                ))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(filters.join(" AND "))
+    }
+
     fn extend_date_range_bound(
         &self,
         date: String,
@@ -283,42 +353,37 @@ impl BaseFilter {
                     .ilike(member_sql, &v, start_wild, end_wild, not)
             })
             .collect::<Result<Vec<_>, _>>()?;
+        self.join_values_where(member_sql, like_parts, not)
+    }
+
+    fn regex_where(&self, member_sql: &str, not: bool) -> Result<String, CubeError> {
+        let values = self.filter_and_allocate_values();
+        let regex_parts = values
+            .into_iter()
+            .map(|v| self.templates.regex(member_sql, &v, not))
+            .collect::<Result<Vec<_>, _>>()?;
+        self.join_values_where(member_sql, regex_parts, not)
+    }
+
+    fn join_values_where(
+        &self,
+        member_sql: &str,
+        parts: Vec<String>,
+        not: bool,
+    ) -> Result<String, CubeError> {
         let logical_symbol = if not { " AND " } else { " OR " };
         let null_check = if self.is_need_null_chek(not) {
             self.templates.or_is_null_check(member_sql.to_string())?
         } else {
             "".to_string()
         };
-        Ok(format!(
-            "({}){}",
-            like_parts.join(logical_symbol),
-            null_check
-        ))
+        Ok(format!("({}){}", parts.join(logical_symbol), null_check))
     }
 
     fn allocate_date_params(&self) -> Result<(String, String), CubeError> {
         if self.values.len() >= 2 {
-            let from = if let Some(from_str) = &self.values[0] {
-                self.query_tools
-                    .base_tools()
-                    .in_db_time_zone(self.format_from_date(&from_str)?)?
-            } else {
-                return Err(CubeError::user(format!(
-                    "Arguments for date range is not valid"
-                )));
-            };
-
-            let to = if let Some(to_str) = &self.values[1] {
-                self.query_tools
-                    .base_tools()
-                    .in_db_time_zone(self.format_to_date(&to_str)?)?
-            } else {
-                return Err(CubeError::user(format!(
-                    "Arguments for date range is not valid"
-                )));
-            };
-            let from = self.allocate_timestamp_param(&from);
-            let to = self.allocate_timestamp_param(&to);
+            let from = self.allocate_date_param(&self.values[0], true)?;
+            let to = self.allocate_date_param(&self.values[1], false)?;
             Ok((from, to))
         } else {
             Err(CubeError::user(format!(
@@ -328,6 +393,36 @@ impl BaseFilter {
         }
     }
 
+    fn first_timestamp_param(&self) -> Result<String, CubeError> {
+        if let Some(value) = self.values.first() {
+            self.allocate_date_param(value, true)
+        } else {
+            Err(CubeError::user(format!(
+                "Expected one parameter but nothing found"
+            )))
+        }
+    }
+
+    fn allocate_date_param(
+        &self,
+        value: &Option<String>,
+        is_from: bool,
+    ) -> Result<String, CubeError> {
+        if let Some(date_str) = value {
+            let date = if is_from {
+                self.format_from_date(date_str)?
+            } else {
+                self.format_to_date(date_str)?
+            };
+            let date = self.query_tools.base_tools().in_db_time_zone(date)?;
+            Ok(self.allocate_timestamp_param(&date))
+        } else {
+            Err(CubeError::user(format!(
+                "Arguments for date range is not valid"
+            )))
+        }
+    }
+
     fn format_from_date(&self, date: &str) -> Result<String, CubeError> {
         let precision = self.query_tools.base_tools().timestamp_precision()?;
         if precision == 3 {
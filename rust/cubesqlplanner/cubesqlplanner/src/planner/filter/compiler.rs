@@ -33,6 +33,10 @@ impl<'a> FilterCompiler<'a> {
             let compiled_item = self.compile_item(item, &item_type)?;
             match item_type {
                 FilterType::Dimension => self.dimension_filters.push(compiled_item),
+                // measureFilter evaluates filters of the measure itself, so it goes to WHERE
+                FilterType::Measure if Self::is_measure_filter_operator(item) => {
+                    self.dimension_filters.push(compiled_item)
+                }
                 FilterType::Measure => self.measures_filters.push(compiled_item),
             }
         }
@@ -61,6 +65,15 @@ impl<'a> FilterCompiler<'a> {
         )
     }
 
+    fn is_measure_filter_operator(item: &NativeFilterItem) -> bool {
+        matches!(
+            item.operator
+                .as_ref()
+                .map(|op| FilterOperator::from_str(op)),
+            Some(Ok(FilterOperator::MeasureFilter))
+        )
+    }
+
     fn compile_item(
         &mut self,
         item: &NativeFilterItem,
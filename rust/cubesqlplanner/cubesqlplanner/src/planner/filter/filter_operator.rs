@@ -1,4 +1,5 @@
 use cubenativeutils::CubeError;
+use std::fmt;
 use std::str::FromStr;
 
 #[derive(Clone, PartialEq, Debug)]
@@ -7,6 +8,11 @@ pub enum FilterOperator {
     NotEqual,
     InDateRange,
     InDateRangeExtended,
+    NotInDateRange,
+    BeforeDate,
+    BeforeOrOnDate,
+    AfterDate,
+    AfterOrOnDate,
     In,
     NotIn,
     Set,
@@ -21,6 +27,9 @@ pub enum FilterOperator {
     NotStartsWith,
     NotEndsWith,
     EndsWith,
+    Regex,
+    NotRegex,
+    MeasureFilter,
 }
 
 impl FromStr for FilterOperator {
@@ -30,7 +39,13 @@ impl FromStr for FilterOperator {
         match s.to_lowercase().as_str() {
             "equals" => Ok(Self::Equal),
             "notequals" => Ok(Self::NotEqual),
-            "indaterange" => Ok(Self::InDateRange),
+            "indaterange" | "onthedate" => Ok(Self::InDateRange),
+            "indaterangeextended" => Ok(Self::InDateRangeExtended),
+            "notindaterange" => Ok(Self::NotInDateRange),
+            "beforedate" => Ok(Self::BeforeDate),
+            "beforeorondate" => Ok(Self::BeforeOrOnDate),
+            "afterdate" => Ok(Self::AfterDate),
+            "afterorondate" => Ok(Self::AfterOrOnDate),
             "in" => Ok(Self::In),
             "notin" => Ok(Self::NotIn),
             "set" => Ok(Self::Set),
@@ -44,8 +59,45 @@ impl FromStr for FilterOperator {
             "notstartswith" => Ok(Self::NotStartsWith),
             "endswith" => Ok(Self::EndsWith),
             "notendswith" => Ok(Self::NotEndsWith),
+            "regex" => Ok(Self::Regex),
+            "notregex" => Ok(Self::NotRegex),
+            "measurefilter" => Ok(Self::MeasureFilter),
 
             _ => Err(CubeError::user(format!("Unknown filter operator {}", s))),
         }
     }
 }
+
+impl fmt::Display for FilterOperator {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Self::Equal => "equals",
+            Self::NotEqual => "notEquals",
+            Self::InDateRange => "inDateRange",
+            Self::InDateRangeExtended => "inDateRangeExtended",
+            Self::NotInDateRange => "notInDateRange",
+            Self::BeforeDate => "beforeDate",
+            Self::BeforeOrOnDate => "beforeOrOnDate",
+            Self::AfterDate => "afterDate",
+            Self::AfterOrOnDate => "afterOrOnDate",
+            Self::In => "in",
+            Self::NotIn => "notIn",
+            Self::Set => "set",
+            Self::NotSet => "notSet",
+            Self::Gt => "gt",
+            Self::Gte => "gte",
+            Self::Lt => "lt",
+            Self::Lte => "lte",
+            Self::Contains => "contains",
+            Self::NotContains => "notContains",
+            Self::StartsWith => "startsWith",
+            Self::NotStartsWith => "notStartsWith",
+            Self::NotEndsWith => "notEndsWith",
+            Self::EndsWith => "endsWith",
+            Self::Regex => "regex",
+            Self::NotRegex => "notRegex",
+            Self::MeasureFilter => "measureFilter",
+        };
+        write!(f, "{}", name)
+    }
+}
@@ -0,0 +1,308 @@
+use super::query_tools::QueryTools;
+use super::sql_interval::SqlInterval;
+use super::sql_templates::FilterTemplates;
+use super::GranularityHelper;
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use cubenativeutils::CubeError;
+use std::str::FromStr;
+
+//TODO Make this as configurable soft limit
+const TIME_SERIES_LIMIT: i64 = 50000;
+
+/// Predefined or custom (interval + origin/offset) granularity of a time dimension.
+/// It's a counterpart of the JS `Granularity` from schema-compiler.
+#[derive(Clone, Debug)]
+pub struct Granularity {
+    granularity: String,
+    granularity_interval: String,
+    granularity_offset: Option<String>,
+    /// Naive timestamp in the query timezone
+    origin: NaiveDateTime,
+    timezone: Tz,
+    is_predefined: bool,
+}
+
+impl Granularity {
+    pub fn try_new_predefined(timezone: Tz, granularity: String) -> Result<Self, CubeError> {
+        if !GranularityHelper::is_predefined_granularity(&granularity) {
+            return Err(CubeError::user(format!(
+                "Unsupported time granularity: {}",
+                granularity
+            )));
+        }
+
+        Ok(Self {
+            granularity_interval: format!("1 {}", granularity),
+            granularity,
+            granularity_offset: None,
+            origin: Self::default_origin(timezone)?,
+            timezone,
+            is_predefined: true,
+        })
+    }
+
+    pub fn try_new_custom(
+        timezone: Tz,
+        granularity: String,
+        origin: Option<String>,
+        interval: String,
+        offset: Option<String>,
+    ) -> Result<Self, CubeError> {
+        if SqlInterval::from_str(&interval)?.to_seconds() <= 0 {
+            return Err(CubeError::user(format!(
+                "Interval of granularity '{}' must be positive, actual: '{}'",
+                granularity, interval
+            )));
+        }
+
+        let (origin, granularity_offset) = if let Some(origin) = origin {
+            (Self::parse_origin(&origin, timezone)?, None)
+        } else if let Some(offset) = offset {
+            let origin =
+                SqlInterval::from_str(&offset)?.add_to(&Self::default_origin(timezone)?)?;
+            (origin, Some(offset))
+        } else {
+            (Self::default_origin(timezone)?, None)
+        };
+
+        Ok(Self {
+            granularity,
+            granularity_interval: interval,
+            granularity_offset,
+            origin,
+            timezone,
+            is_predefined: false,
+        })
+    }
+
+    pub fn granularity(&self) -> &String {
+        &self.granularity
+    }
+
+    pub fn granularity_interval(&self) -> &String {
+        &self.granularity_interval
+    }
+
+    pub fn granularity_offset(&self) -> &Option<String> {
+        &self.granularity_offset
+    }
+
+    pub fn is_predefined(&self) -> bool {
+        self.is_predefined
+    }
+
+    /// Origin date string in the query timezone
+    pub fn origin_local_formatted(&self) -> String {
+        self.origin.format("%Y-%m-%dT%H:%M:%S%.3f").to_string()
+    }
+
+    /// Interval is aligned with natural calendar, so DATE_TRUNC can be used instead of date bin
+    pub fn is_natural_aligned(&self) -> bool {
+        let parts = self
+            .granularity_interval
+            .split_whitespace()
+            .collect::<Vec<_>>();
+        parts.len() == 2 && parts[0] == "1"
+    }
+
+    /// Predefined granularity which is used to truncate dates for this granularity
+    pub fn resolved_granularity(&self) -> Result<String, CubeError> {
+        if self.is_predefined {
+            Ok(self.granularity.clone())
+        } else {
+            Ok(SqlInterval::from_str(&self.granularity_interval)?.min_granularity())
+        }
+    }
+
+    /// The biggest predefined granularity which can be used to calculate this one, e.g. for rollups
+    pub fn min_granularity(&self) -> Result<String, CubeError> {
+        if self.is_predefined {
+            return Ok(self.granularity.clone());
+        }
+
+        let interval_granularity = Some(self.resolved_granularity()?);
+        let other_granularity = if let Some(offset) = &self.granularity_offset {
+            SqlInterval::from_str(offset)?.min_granularity()
+        } else {
+            GranularityHelper::granularity_for_date(&self.origin_utc()?)
+        };
+
+        GranularityHelper::min_granularity(&interval_granularity, &Some(other_granularity))
+            .map(|granularity| granularity.unwrap_or_else(|| self.granularity.clone()))
+    }
+
+    pub fn apply_to_input_sql(
+        &self,
+        query_tools: &QueryTools,
+        input: String,
+    ) -> Result<String, CubeError> {
+        if self.is_predefined {
+            return query_tools
+                .base_tools()
+                .time_grouped_column(self.granularity.clone(), input);
+        }
+
+        if self.is_natural_aligned() {
+            let granularity = self.resolved_granularity()?;
+            if let Some(offset) = &self.granularity_offset {
+                // DATE_TRUNC(interval, dimension - INTERVAL 'offset') + INTERVAL 'offset'
+                let templates = FilterTemplates::new(query_tools.templates_render());
+                let sql = templates.sub_interval(input, offset.clone())?;
+                let sql = query_tools
+                    .base_tools()
+                    .time_grouped_column(granularity, sql)?;
+                return templates.add_interval(sql, offset.clone());
+            }
+
+            return query_tools
+                .base_tools()
+                .time_grouped_column(granularity, input);
+        }
+
+        query_tools.base_tools().date_bin(
+            self.granularity_interval.clone(),
+            input,
+            self.origin_local_formatted(),
+        )
+    }
+
+    /// Returns `[date_from, date_to]` pairs of the series for the date range. Predefined
+    /// granularities are aligned with the calendar, custom ones with the origin.
+    pub fn time_series(
+        &self,
+        date_range: &[String],
+        timestamp_precision: u32,
+    ) -> Result<Vec<Vec<String>>, CubeError> {
+        if date_range.len() != 2 {
+            return Err(CubeError::user(format!(
+                "Date range for time series is expected to have 2 dates, actual: {:?}",
+                date_range
+            )));
+        }
+        let start = Self::parse_local_date(&date_range[0], false)?;
+        let end = Self::parse_local_date(&date_range[1], true)?;
+        let interval = SqlInterval::from_str(&self.granularity_interval)?;
+
+        let count = (end - start).num_seconds() / interval.to_seconds();
+        if count > TIME_SERIES_LIMIT {
+            return Err(CubeError::user(format!(
+                "The count of generated date ranges ({}) for the request from [{}] to [{}] by {} is over limit ({}). Please reduce the requested date interval or use bigger granularity.",
+                count, date_range[0], date_range[1], self.granularity_interval, TIME_SERIES_LIMIT
+            )));
+        }
+
+        let mut result = Vec::new();
+        if self.is_predefined {
+            let mut current =
+                GranularityHelper::align_date_to_granularity(&start, &self.granularity)?;
+            while current <= end {
+                let next = GranularityHelper::add_granularity(&current, &self.granularity)?;
+                result.push(Self::time_series_item(&current, &next, timestamp_precision));
+                current = next;
+            }
+        } else {
+            let mut current = self.align_to_origin(&start, &interval)?;
+            while current < end {
+                let next = interval.add_to(&current)?;
+                result.push(Self::time_series_item(&current, &next, timestamp_precision));
+                current = next;
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// Returns the closest date prior to the date aligned with the origin
+    fn align_to_origin(
+        &self,
+        date: &NaiveDateTime,
+        interval: &SqlInterval,
+    ) -> Result<NaiveDateTime, CubeError> {
+        let mut offset_date = self.origin;
+        if *date < self.origin {
+            while offset_date > *date {
+                offset_date = interval.subtract_from(&offset_date)?;
+            }
+            Ok(offset_date)
+        } else {
+            let mut aligned_date = offset_date;
+            while offset_date < *date {
+                aligned_date = offset_date;
+                offset_date = interval.add_to(&offset_date)?;
+            }
+            if offset_date == *date {
+                aligned_date = offset_date;
+            }
+            Ok(aligned_date)
+        }
+    }
+
+    fn time_series_item(from: &NaiveDateTime, next: &NaiveDateTime, precision: u32) -> Vec<String> {
+        let to = *next - Duration::seconds(1);
+        vec![
+            format!(
+                "{}.{}",
+                from.format("%Y-%m-%dT%H:%M:%S"),
+                "0".repeat(precision as usize)
+            ),
+            format!(
+                "{}.{}",
+                to.format("%Y-%m-%dT%H:%M:%S"),
+                "9".repeat(precision as usize)
+            ),
+        ]
+    }
+
+    fn origin_utc(&self) -> Result<NaiveDateTime, CubeError> {
+        self.timezone
+            .from_local_datetime(&self.origin)
+            .earliest()
+            .map(|d| d.naive_utc())
+            .ok_or_else(|| {
+                CubeError::user(format!(
+                    "Origin {} doesn't exist in timezone {}",
+                    self.origin, self.timezone
+                ))
+            })
+    }
+
+    /// Defaults to the start of the current year in the query timezone
+    fn default_origin(timezone: Tz) -> Result<NaiveDateTime, CubeError> {
+        let year = Utc::now().with_timezone(&timezone).year();
+        NaiveDate::from_ymd_opt(year, 1, 1)
+            .and_then(|d| d.and_hms_opt(0, 0, 0))
+            .ok_or_else(|| CubeError::internal(format!("Can't build origin for year {}", year)))
+    }
+
+    fn parse_origin(origin: &str, timezone: Tz) -> Result<NaiveDateTime, CubeError> {
+        if let Ok(date) = DateTime::parse_from_rfc3339(origin) {
+            return Ok(date.with_timezone(&timezone).naive_local());
+        }
+        Self::parse_local_date(origin, false)
+    }
+
+    /// Dates without time are expanded to the start or to the end of the day
+    fn parse_local_date(date: &str, is_end: bool) -> Result<NaiveDateTime, CubeError> {
+        let date = date.trim_end_matches('Z');
+        for format in ["%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%d %H:%M:%S%.f"] {
+            if let Ok(result) = NaiveDateTime::parse_from_str(date, format) {
+                return Ok(result);
+            }
+        }
+        if let Ok(result) = NaiveDate::parse_from_str(date, "%Y-%m-%d") {
+            let time = if is_end {
+                result.and_hms_milli_opt(23, 59, 59, 999)
+            } else {
+                result.and_hms_opt(0, 0, 0)
+            };
+            if let Some(time) = time {
+                return Ok(time);
+            }
+        }
+        Err(CubeError::user(format!(
+            "Unsupported date format: {}",
+            date
+        )))
+    }
+}
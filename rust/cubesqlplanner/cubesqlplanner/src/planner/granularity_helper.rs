@@ -1,3 +1,4 @@
+use chrono::{Datelike, Duration, Months, NaiveDate, NaiveDateTime, Timelike};
 use cubenativeutils::CubeError;
 use itertools::Itertools;
 use lazy_static::lazy_static;
@@ -61,6 +62,88 @@ impl GranularityHelper {
         }
     }
 
+    pub fn is_predefined_granularity(granularity: &str) -> bool {
+        Self::standard_granularity_parents().contains_key(granularity)
+    }
+
+    /// Returns the biggest predefined granularity the date is aligned with
+    pub fn granularity_for_date(date: &NaiveDateTime) -> String {
+        let is_midnight = date.num_seconds_from_midnight() == 0 && date.nanosecond() == 0;
+        let res = if is_midnight && date.month() == 1 && date.day() == 1 {
+            "year"
+        } else if is_midnight && date.day() == 1 {
+            "month"
+        } else if is_midnight && date.weekday().number_from_monday() == 1 {
+            "week"
+        } else if is_midnight {
+            "day"
+        } else if date.minute() == 0 && date.second() == 0 && date.nanosecond() == 0 {
+            "hour"
+        } else if date.second() == 0 && date.nanosecond() == 0 {
+            "minute"
+        } else {
+            "second"
+        };
+        res.to_string()
+    }
+
+    /// Truncates the date to the start of the predefined granularity, weeks start on Monday
+    pub fn align_date_to_granularity(
+        date: &NaiveDateTime,
+        granularity: &str,
+    ) -> Result<NaiveDateTime, CubeError> {
+        let day_start = |d: NaiveDate| d.and_hms_opt(0, 0, 0).unwrap();
+        let res = match granularity {
+            "second" => date.with_nanosecond(0),
+            "minute" => date.with_nanosecond(0).and_then(|d| d.with_second(0)),
+            "hour" => date
+                .with_nanosecond(0)
+                .and_then(|d| d.with_second(0))
+                .and_then(|d| d.with_minute(0)),
+            "day" => Some(day_start(date.date())),
+            "week" => Some(day_start(
+                date.date() - Duration::days(date.weekday().num_days_from_monday() as i64),
+            )),
+            "month" => date.date().with_day(1).map(day_start),
+            "quarter" => {
+                NaiveDate::from_ymd_opt(date.year(), (date.month0() / 3) * 3 + 1, 1).map(day_start)
+            }
+            "year" => NaiveDate::from_ymd_opt(date.year(), 1, 1).map(day_start),
+            _ => {
+                return Err(CubeError::user(format!(
+                    "Unsupported time granularity: {}",
+                    granularity
+                )))
+            }
+        };
+        res.ok_or_else(|| {
+            CubeError::internal(format!("Can't align date {} to {}", date, granularity))
+        })
+    }
+
+    pub fn add_granularity(
+        date: &NaiveDateTime,
+        granularity: &str,
+    ) -> Result<NaiveDateTime, CubeError> {
+        let res = match granularity {
+            "second" => date.checked_add_signed(Duration::seconds(1)),
+            "minute" => date.checked_add_signed(Duration::minutes(1)),
+            "hour" => date.checked_add_signed(Duration::hours(1)),
+            "day" => date.checked_add_signed(Duration::days(1)),
+            "week" => date.checked_add_signed(Duration::weeks(1)),
+            "month" => date.checked_add_months(Months::new(1)),
+            "quarter" => date.checked_add_months(Months::new(3)),
+            "year" => date.checked_add_months(Months::new(12)),
+            _ => {
+                return Err(CubeError::user(format!(
+                    "Unsupported time granularity: {}",
+                    granularity
+                )))
+            }
+        };
+        res.ok_or_else(|| CubeError::internal(format!("Date {} is out of range", date)))
+    }
+
     pub fn granularity_parents(granularity: &str) -> Result<&Vec<String>, CubeError> {
         if let Some(parents) = Self::standard_granularity_parents().get(granularity) {
             Ok(parents)
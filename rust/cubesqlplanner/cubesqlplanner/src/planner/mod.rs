@@ -6,12 +6,15 @@ pub mod base_member;
 pub mod base_query;
 pub mod base_time_dimension;
 pub mod filter;
+pub mod granularity;
 pub mod granularity_helper;
 pub mod params_allocator;
+pub mod plan_description;
 pub mod planners;
 pub mod query_properties;
 pub mod query_tools;
 pub mod sql_evaluator;
+pub mod sql_interval;
 pub mod sql_templates;
 pub mod utils;
 pub mod visitor_context;
@@ -23,7 +26,9 @@ pub use base_measure::BaseMeasure;
 pub use base_member::{BaseMember, BaseMemberHelper};
 pub use base_query::BaseQuery;
 pub use base_time_dimension::BaseTimeDimension;
+pub use granularity::Granularity;
 pub use granularity_helper::GranularityHelper;
 pub use params_allocator::ParamsAllocator;
 pub use query_properties::{FullKeyAggregateMeasures, OrderByItem, QueryProperties};
+pub use sql_interval::SqlInterval;
 pub use visitor_context::{evaluate_sql_call_with_context, evaluate_with_context, VisitorContext};
@@ -0,0 +1,175 @@
+use super::planners::pre_aggregations::MatchedRollup;
+use crate::cube_bridge::join_definition::JoinDefinition;
+use crate::plan::FilterItem;
+use cubenativeutils::CubeError;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::rc::Rc;
+
+/// Structured description of the plan `BaseQuery` builds for a query. It's returned
+/// instead of SQL by `BaseQuery::build_plan_description`, so plans can be inspected
+/// and compared between schema versions. Maps are ordered to keep the output stable.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct QueryPlanDescription {
+    /// `rollup`, `simple` or `full_key_aggregate`
+    pub planner: String,
+    #[serde(rename = "preAggregation")]
+    pub pre_aggregation: Option<PreAggregationUsageDescription>,
+    /// Join of the simple query. Full key aggregate queries have a join per subquery
+    pub join: Option<JoinDescription>,
+    /// Subqueries of the full key aggregate query, `i`-th one is joined as `q_i`
+    pub subqueries: Vec<SubqueryDescription>,
+    /// All multi-stage CTEs in the order they are rendered
+    #[serde(rename = "multiStage")]
+    pub multi_stage: Vec<MultiStageCteDescription>,
+    pub filters: FiltersDescription,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PreAggregationUsageDescription {
+    #[serde(rename = "cubeName")]
+    pub cube_name: String,
+    pub name: String,
+    /// Rollup granularity for each time dimension used by the query
+    #[serde(rename = "timeDimensions")]
+    pub time_dimensions: BTreeMap<String, String>,
+}
+
+impl PreAggregationUsageDescription {
+    pub fn new(rollup: &MatchedRollup) -> Self {
+        Self {
+            cube_name: rollup.cube_name.clone(),
+            name: rollup.pre_aggregation.name.clone(),
+            time_dimensions: rollup.time_dimensions.clone().into_iter().collect(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct JoinDescription {
+    pub root: String,
+    pub joins: Vec<JoinItemDescription>,
+    #[serde(rename = "multiplicationFactor")]
+    pub multiplication_factor: BTreeMap<String, bool>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct JoinItemDescription {
+    pub from: String,
+    pub to: String,
+    pub relationship: String,
+}
+
+impl JoinDescription {
+    pub fn try_new(join: &Rc<dyn JoinDefinition>) -> Result<Self, CubeError> {
+        let joins = join
+            .joins()?
+            .items()
+            .iter()
+            .map(|item| -> Result<_, CubeError> {
+                let static_data = item.static_data();
+                Ok(JoinItemDescription {
+                    from: static_data.from.clone(),
+                    to: static_data.to.clone(),
+                    relationship: item.join()?.static_data().relationship.clone(),
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self {
+            root: join.static_data().root.clone(),
+            joins,
+            multiplication_factor: join
+                .static_data()
+                .multiplication_factor
+                .clone()
+                .into_iter()
+                .collect(),
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SubqueryDescription {
+    /// `regular` for measures which aren't multiplied by the join, `aggregate` for
+    /// multiplied measures of `key_cube` and `multi_stage` for a top level CTE
+    #[serde(rename = "type")]
+    pub subquery_type: String,
+    pub measures: Vec<String>,
+    #[serde(rename = "keyCube")]
+    pub key_cube: Option<String>,
+    pub join: Option<JoinDescription>,
+    pub cte: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct MultiStageCteDescription {
+    pub alias: String,
+    pub member: String,
+    /// `time_series` or `measure` for leaf CTEs, `rank`, `aggregate`, `calculate`,
+    /// `rolling_window` or `running_total` for the ones built over `input`
+    #[serde(rename = "type")]
+    pub cte_type: String,
+    /// Aliases of the CTEs this one is built from
+    pub input: Vec<String>,
+    pub dimensions: Vec<String>,
+    /// Time dimensions with the granularity applied, e.g. `orders.created_at.month`
+    #[serde(rename = "timeDimensions")]
+    pub time_dimensions: Vec<String>,
+    #[serde(rename = "timeShifts")]
+    pub time_shifts: BTreeMap<String, String>,
+    pub filters: FiltersDescription,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct FiltersDescription {
+    #[serde(rename = "timeDimensions")]
+    pub time_dimensions: Vec<FilterDescription>,
+    pub dimensions: Vec<FilterDescription>,
+    pub measures: Vec<FilterDescription>,
+}
+
+impl FiltersDescription {
+    pub fn new(
+        time_dimensions_filters: &Vec<FilterItem>,
+        dimensions_filters: &Vec<FilterItem>,
+        measures_filters: &Vec<FilterItem>,
+    ) -> Self {
+        Self {
+            time_dimensions: FilterDescription::from_items(time_dimensions_filters),
+            dimensions: FilterDescription::from_items(dimensions_filters),
+            measures: FilterDescription::from_items(measures_filters),
+        }
+    }
+}
+
+/// Either a single member filter or an `and`/`or` group of `items`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct FilterDescription {
+    pub member: Option<String>,
+    pub operator: String,
+    pub values: Vec<Option<String>>,
+    pub items: Vec<FilterDescription>,
+}
+
+impl FilterDescription {
+    pub fn new(item: &FilterItem) -> Self {
+        match item {
+            FilterItem::Group(group) => Self {
+                member: None,
+                operator: group.operator.to_string().to_lowercase(),
+                values: vec![],
+                items: Self::from_items(&group.items),
+            },
+            FilterItem::Item(filter) => Self {
+                member: Some(filter.member_name()),
+                operator: filter.filter_operator().to_string(),
+                values: filter.values().clone(),
+                items: vec![],
+            },
+        }
+    }
+
+    pub fn from_items(items: &Vec<FilterItem>) -> Vec<Self> {
+        items.iter().map(Self::new).collect()
+    }
+}
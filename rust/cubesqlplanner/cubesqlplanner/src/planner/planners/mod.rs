@@ -5,6 +5,7 @@ pub mod multi_stage;
 pub mod multi_stage_query_planner;
 pub mod multiplied_measures_query_planner;
 pub mod order_planner;
+pub mod pre_aggregations;
 pub mod simple_query_planer;
 
 pub use common_utils::CommonUtils;
@@ -13,4 +14,5 @@ pub use join_planner::JoinPlanner;
 pub use multi_stage_query_planner::MultiStageQueryPlanner;
 pub use multiplied_measures_query_planner::MultipliedMeasuresQueryPlanner;
 pub use order_planner::OrderPlanner;
+pub use pre_aggregations::{MatchedRollup, PreAggregationMatcher, RollupQueryPlanner};
 pub use simple_query_planer::SimpleQueryPlanner;
@@ -2,6 +2,7 @@ use crate::plan::{FilterGroup, FilterItem};
 use crate::planner::filter::FilterOperator;
 use crate::planner::planners::multi_stage::MultiStageTimeShift;
 use crate::planner::{BaseDimension, BaseTimeDimension};
+use cubenativeutils::CubeError;
 use itertools::Itertools;
 use std::cmp::PartialEq;
 use std::collections::HashMap;
@@ -92,14 +93,15 @@ impl MultiStageAppliedState {
         &mut self,
         dimension_name: &str,
         new_granularity: Option<String>,
-    ) {
+    ) -> Result<(), CubeError> {
         if let Some(time_dimension) = self
             .time_dimensions
             .iter_mut()
             .find(|dim| dim.member_evaluator().full_name() == dimension_name)
         {
-            *time_dimension = time_dimension.change_granularity(new_granularity);
+            *time_dimension = time_dimension.change_granularity(new_granularity)?;
         }
+        Ok(())
     }
 
     pub fn remove_filter_for_member(&mut self, member_name: &String) {
@@ -63,14 +63,25 @@ impl MultiStageMemberQueryPlanner {
         &self,
         time_dimension: Rc<BaseTimeDimension>,
     ) -> Result<Rc<Cte>, CubeError> {
-        let granularity = time_dimension.get_granularity().unwrap(); //FIXME remove this unwrap
-        let date_range = time_dimension.get_date_range().unwrap(); //FIXME remove this unwrap
+        let granularity = time_dimension
+            .get_granularity_obj()
+            .clone()
+            .ok_or_else(|| {
+                CubeError::user(format!(
+                    "Time series requires granularity for {}",
+                    time_dimension.full_name()
+                ))
+            })?;
+        let date_range = time_dimension.get_date_range().ok_or_else(|| {
+            CubeError::user(format!(
+                "Time series requires date range for {}",
+                time_dimension.full_name()
+            ))
+        })?;
+        let precision = self.query_tools.base_tools().timestamp_precision()?;
+        let seria = granularity.time_series(&date_range, precision)?;
         let from_date = date_range[0].clone();
         let to_date = date_range[1].clone();
-        let seria = self
-            .query_tools
-            .base_tools()
-            .generate_time_series(granularity, date_range.clone())?;
         let time_seira = TimeSeries::new(
             time_dimension.full_name(),
             Some(from_date),
@@ -5,12 +5,15 @@ use super::multi_stage::{
 };
 use crate::cube_bridge::measure_definition::RollingWindow;
 use crate::plan::{Cte, From, Schema, Select, SelectBuilder};
+use crate::planner::plan_description::{
+    FiltersDescription, MultiStageCteDescription, SubqueryDescription,
+};
 use crate::planner::query_tools::QueryTools;
 use crate::planner::sql_evaluator::collectors::has_multi_stage_members;
 use crate::planner::sql_evaluator::collectors::member_childs;
 use crate::planner::sql_evaluator::sql_nodes::SqlNodesFactory;
 use crate::planner::sql_evaluator::MemberSymbol;
-use crate::planner::{BaseDimension, BaseMeasure};
+use crate::planner::{BaseDimension, BaseMeasure, BaseMember};
 use crate::planner::{BaseTimeDimension, GranularityHelper, QueryProperties};
 use cubenativeutils::CubeError;
 use itertools::Itertools;
@@ -30,6 +33,67 @@ impl MultiStageQueryPlanner {
         }
     }
     pub fn plan_queries(&self) -> Result<(Vec<Rc<Cte>>, Vec<Rc<Select>>), CubeError> {
+        let (descriptions, top_level_ctes) = self.make_descriptions()?;
+        if descriptions.is_empty() {
+            return Ok((vec![], vec![]));
+        }
+
+        let mut cte_schemas = HashMap::new();
+        let all_queries = descriptions
+            .into_iter()
+            .map(|descr| -> Result<_, CubeError> {
+                let res = MultiStageMemberQueryPlanner::new(
+                    self.query_tools.clone(),
+                    self.query_properties.clone(),
+                    descr.clone(),
+                )
+                .plan_query(&cte_schemas)?;
+                cte_schemas.insert(descr.alias().clone(), res.query().schema());
+                Ok(res)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let cte_joins = top_level_ctes
+            .iter()
+            .map(|descr| self.cte_select(descr.alias(), &cte_schemas))
+            .collect_vec();
+
+        Ok((all_queries, cte_joins))
+    }
+
+    /// Describes CTEs and top level subqueries `plan_queries` builds, in the same order
+    pub fn describe_queries(
+        &self,
+    ) -> Result<(Vec<MultiStageCteDescription>, Vec<SubqueryDescription>), CubeError> {
+        let (descriptions, top_level_ctes) = self.make_descriptions()?;
+        let ctes = descriptions
+            .iter()
+            .map(|descr| self.describe_cte(descr))
+            .collect_vec();
+        let subqueries = top_level_ctes
+            .iter()
+            .map(|descr| SubqueryDescription {
+                subquery_type: "multi_stage".to_string(),
+                measures: vec![descr.member_name()],
+                key_cube: None,
+                join: None,
+                cte: Some(descr.alias().clone()),
+            })
+            .collect_vec();
+        Ok((ctes, subqueries))
+    }
+
+    /// Returns all multi-stage queries ordered by their dependencies and the ones
+    /// for the query members
+    fn make_descriptions(
+        &self,
+    ) -> Result<
+        (
+            Vec<Rc<MultiStageQueryDescription>>,
+            Vec<Rc<MultiStageQueryDescription>>,
+        ),
+        CubeError,
+    > {
         let multi_stage_members = self
             .query_properties
             .all_members(false)
@@ -56,39 +120,56 @@ impl MultiStageQueryPlanner {
 
         let top_level_ctes = multi_stage_members
             .into_iter()
-            .map(|memb| -> Result<_, CubeError> {
-                Ok(self
-                    .make_queries_descriptions(
-                        memb.member_evaluator().clone(),
-                        state.clone(),
-                        &mut descriptions,
-                    )?
-                    .alias()
-                    .clone())
-            })
-            .collect::<Result<Vec<_>, _>>()?;
-
-        let mut cte_schemas = HashMap::new();
-        let all_queries = descriptions
-            .into_iter()
-            .map(|descr| -> Result<_, CubeError> {
-                let res = MultiStageMemberQueryPlanner::new(
-                    self.query_tools.clone(),
-                    self.query_properties.clone(),
-                    descr.clone(),
+            .map(|memb| {
+                self.make_queries_descriptions(
+                    memb.member_evaluator().clone(),
+                    state.clone(),
+                    &mut descriptions,
                 )
-                .plan_query(&cte_schemas)?;
-                cte_schemas.insert(descr.alias().clone(), res.query().schema());
-                Ok(res)
             })
             .collect::<Result<Vec<_>, _>>()?;
 
-        let cte_joins = top_level_ctes
-            .iter()
-            .map(|alias| self.cte_select(alias, &cte_schemas))
-            .collect_vec();
+        Ok((descriptions, top_level_ctes))
+    }
 
-        Ok((all_queries, cte_joins))
+    fn describe_cte(&self, descr: &Rc<MultiStageQueryDescription>) -> MultiStageCteDescription {
+        let cte_type = match descr.member().member_type() {
+            MultiStageMemberType::Leaf(MultiStageLeafMemberType::Measure) => "measure",
+            MultiStageMemberType::Leaf(MultiStageLeafMemberType::TimeSeries(_)) => "time_series",
+            MultiStageMemberType::Inode(inode) => match inode.inode_type() {
+                MultiStageInodeMemberType::Rank => "rank",
+                MultiStageInodeMemberType::Aggregate => "aggregate",
+                MultiStageInodeMemberType::Calculate => "calculate",
+                MultiStageInodeMemberType::RollingWindow(_) => "rolling_window",
+                MultiStageInodeMemberType::RunningTotal(_) => "running_total",
+            },
+        };
+        let state = descr.state();
+        MultiStageCteDescription {
+            alias: descr.alias().clone(),
+            member: descr.member_name(),
+            cte_type: cte_type.to_string(),
+            input: descr.input().iter().map(|i| i.alias().clone()).collect(),
+            dimensions: state.dimensions().iter().map(|d| d.full_name()).collect(),
+            time_dimensions: state
+                .time_dimensions()
+                .iter()
+                .map(|d| {
+                    let name = d.member_evaluator().full_name();
+                    if let Some(granularity) = d.get_granularity() {
+                        format!("{}.{}", name, granularity)
+                    } else {
+                        name
+                    }
+                })
+                .collect(),
+            time_shifts: state.time_shifts().clone().into_iter().collect(),
+            filters: FiltersDescription::new(
+                state.time_dimensions_filters(),
+                state.dimensions_filters(),
+                state.measures_filters(),
+            ),
+        }
     }
 
     pub fn cte_select(
@@ -215,10 +296,10 @@ impl MultiStageQueryPlanner {
             GranularityHelper::min_granularity(&trailing_granularity, &leading_granularity)?;
         let result_granularity = GranularityHelper::min_granularity(
             &window_granularity,
-            &time_dimension.get_granularity(),
+            &time_dimension.min_granularity()?,
         )?;
 
-        new_state.change_time_dimension_granularity(&time_dimension_name, result_granularity);
+        new_state.change_time_dimension_granularity(&time_dimension_name, result_granularity)?;
 
         new_state.expand_date_range_filter(
             &time_dimension_name,
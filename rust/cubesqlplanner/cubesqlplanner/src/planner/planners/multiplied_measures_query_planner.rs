@@ -4,13 +4,16 @@ use crate::plan::{
     Expr, From, JoinBuilder, JoinCondition, MemberExpression, QualifiedColumnName, Select,
     SelectBuilder,
 };
+use crate::planner::plan_description::{JoinDescription, SubqueryDescription};
 use crate::planner::query_tools::QueryTools;
 use crate::planner::sql_evaluator::collectors::{
     collect_cube_names, collect_join_hints, collect_join_hints_for_measures,
 };
 use crate::planner::sql_evaluator::sql_nodes::SqlNodesFactory;
 use crate::planner::sql_evaluator::ReferencesBuilder;
-use crate::planner::{BaseMeasure, BaseMember, BaseMemberHelper, QueryProperties};
+use crate::planner::{
+    BaseMeasure, BaseMember, BaseMemberHelper, FullKeyAggregateMeasures, QueryProperties,
+};
 use cubenativeutils::CubeError;
 use itertools::Itertools;
 use std::collections::HashMap;
@@ -49,50 +52,97 @@ impl MultipliedMeasuresQueryPlanner {
         let measures = self.query_properties.full_key_aggregate_measures()?;
 
         let mut joins = Vec::new();
+        for (i, (join, measures)) in self.regular_measures_groups(&measures)?.iter().enumerate() {
+            let regular_subquery = self.regular_measures_subquery(
+                measures,
+                join.clone(),
+                if i == 0 {
+                    "main".to_string()
+                } else {
+                    format!("main_{}", i)
+                },
+            )?;
+            joins.push(regular_subquery);
+        }
 
-        if !measures.regular_measures.is_empty() {
-            let join_multi_fact_groups = self
-                .query_properties
-                .compute_join_multi_fact_groups_with_measures(&measures.regular_measures)?;
-            for (i, (join, measures)) in join_multi_fact_groups.iter().enumerate() {
-                let regular_subquery = self.regular_measures_subquery(
-                    measures,
-                    join.clone(),
-                    if i == 0 {
-                        "main".to_string()
-                    } else {
-                        format!("main_{}", i)
-                    },
-                )?;
-                joins.push(regular_subquery);
-            }
+        for (cube_name, join, measures) in self.aggregate_measures_groups(&measures)? {
+            let aggregate_subquery = self.aggregate_subquery(&cube_name, &measures, join)?;
+            joins.push(aggregate_subquery);
+        }
+        Ok(joins)
+    }
+
+    /// Describes subqueries `plan_queries` builds, in the same order
+    pub fn describe_queries(&self) -> Result<Vec<SubqueryDescription>, CubeError> {
+        if self.query_properties.is_simple_query()? {
+            return Err(CubeError::internal(format!(
+                "MultipliedMeasuresQueryPlanner should not be used for simple query"
+            )));
         }
 
-        for (cube_name, measures) in measures
+        let measures = self.query_properties.full_key_aggregate_measures()?;
+
+        let mut result = Vec::new();
+        for (join, measures) in self.regular_measures_groups(&measures)? {
+            result.push(SubqueryDescription {
+                subquery_type: "regular".to_string(),
+                measures: measures.iter().map(|m| m.full_name()).collect(),
+                key_cube: None,
+                join: Some(JoinDescription::try_new(&join)?),
+                cte: None,
+            });
+        }
+        for (cube_name, join, measures) in self.aggregate_measures_groups(&measures)? {
+            result.push(SubqueryDescription {
+                subquery_type: "aggregate".to_string(),
+                measures: measures.iter().map(|m| m.full_name()).collect(),
+                key_cube: Some(cube_name),
+                join: Some(JoinDescription::try_new(&join)?),
+                cte: None,
+            });
+        }
+        Ok(result)
+    }
+
+    fn regular_measures_groups(
+        &self,
+        measures: &FullKeyAggregateMeasures,
+    ) -> Result<Vec<(Rc<dyn JoinDefinition>, Vec<Rc<BaseMeasure>>)>, CubeError> {
+        if measures.regular_measures.is_empty() {
+            return Ok(vec![]);
+        }
+        self.query_properties
+            .compute_join_multi_fact_groups_with_measures(&measures.regular_measures)
+    }
+
+    /// Multiplied measures grouped by their cube, ordered by cube name
+    fn aggregate_measures_groups(
+        &self,
+        measures: &FullKeyAggregateMeasures,
+    ) -> Result<Vec<(String, Rc<dyn JoinDefinition>, Vec<Rc<BaseMeasure>>)>, CubeError> {
+        measures
             .multiplied_measures
             .clone()
             .into_iter()
             .into_group_map_by(|m| m.cube_name().clone())
-        {
-            let join_multi_fact_groups = self
-                .query_properties
-                .compute_join_multi_fact_groups_with_measures(&measures)?;
-            if join_multi_fact_groups.len() != 1 {
-                return Err(CubeError::internal(
-                    format!(
-                        "Expected just one multi-fact join group for aggregate measures but got multiple: {}",
-                        join_multi_fact_groups.into_iter().map(|(_, measures)| format!("({})", measures.iter().map(|m| m.full_name()).join(", "))).join(", ")
-                    )
-                ));
-            }
-            let aggregate_subquery = self.aggregate_subquery(
-                &cube_name,
-                &measures,
-                join_multi_fact_groups.into_iter().next().unwrap().0,
-            )?;
-            joins.push(aggregate_subquery);
-        }
-        Ok(joins)
+            .into_iter()
+            .sorted_by(|(a, _), (b, _)| a.cmp(b))
+            .map(|(cube_name, measures)| -> Result<_, CubeError> {
+                let join_multi_fact_groups = self
+                    .query_properties
+                    .compute_join_multi_fact_groups_with_measures(&measures)?;
+                if join_multi_fact_groups.len() != 1 {
+                    return Err(CubeError::internal(
+                        format!(
+                            "Expected just one multi-fact join group for aggregate measures but got multiple: {}",
+                            join_multi_fact_groups.into_iter().map(|(_, measures)| format!("({})", measures.iter().map(|m| m.full_name()).join(", "))).join(", ")
+                        )
+                    ));
+                }
+                let join = join_multi_fact_groups.into_iter().next().unwrap().0;
+                Ok((cube_name, join, measures))
+            })
+            .collect()
     }
 
     fn aggregate_subquery(
@@ -271,8 +321,8 @@ impl MultipliedMeasuresQueryPlanner {
         let mut select_builder = SelectBuilder::new(source.clone());
         let mut context_factory = self.context_factory.clone();
         for time_dim in self.query_properties.time_dimensions() {
-            if let Some(granularity) = time_dim.get_granularity() {
-                context_factory.add_leaf_time_dimension(&time_dim.full_name(), &granularity);
+            if let Some(granularity) = time_dim.get_granularity_obj() {
+                context_factory.add_leaf_time_dimension(&time_dim.full_name(), granularity);
             }
         }
 
@@ -305,8 +355,8 @@ impl MultipliedMeasuresQueryPlanner {
         let mut select_builder = SelectBuilder::new(source);
         let mut context_factory = self.context_factory.clone();
         for time_dim in self.query_properties.time_dimensions() {
-            if let Some(granularity) = time_dim.get_granularity() {
-                context_factory.add_leaf_time_dimension(&time_dim.full_name(), &granularity);
+            if let Some(granularity) = time_dim.get_granularity_obj() {
+                context_factory.add_leaf_time_dimension(&time_dim.full_name(), granularity);
             }
         }
         for member in dimensions.iter() {
@@ -0,0 +1,236 @@
+use super::RollupMeasure;
+use crate::cube_bridge::pre_aggregation_description::{
+    PreAggregationDescription, PreAggregationForQuery,
+};
+use crate::plan::FilterItem;
+use crate::planner::filter::FilterOperator;
+use crate::planner::query_tools::QueryTools;
+use crate::planner::{BaseMember, GranularityHelper, QueryProperties};
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime, NaiveTime, Timelike};
+use cubenativeutils::CubeError;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+pub struct MatchedRollup {
+    pub cube_name: String,
+    pub table_name: String,
+    pub pre_aggregation: PreAggregationDescription,
+    /// Rollup granularity for each time dimension used by the query.
+    pub time_dimensions: HashMap<String, String>,
+}
+
+/// Checks that the rollup selected on the JS side can answer the query without
+/// touching the original cube SQL and resolves its time dimension granularities.
+///
+/// Selection itself (order, external and partitioned rollups, `disableExternalPreAggregations`)
+/// stays in `PreAggregations.findPreAggregationForQuery()`, so the planner reads from the same
+/// table the orchestrator builds. When the rollup can't be used by this planner, the query is
+/// planned against the cube SQL.
+///
+/// Only additive measures are matched: the rollup stores partial aggregates
+/// per group and they are combined again with `sum`, `min` or `max`.
+/// Segments of the rollup are grouping columns, so they never prevent a match.
+pub struct PreAggregationMatcher {
+    query_tools: Rc<QueryTools>,
+    query_properties: Rc<QueryProperties>,
+}
+
+impl PreAggregationMatcher {
+    pub fn new(query_tools: Rc<QueryTools>, query_properties: Rc<QueryProperties>) -> Self {
+        Self {
+            query_tools,
+            query_properties,
+        }
+    }
+
+    pub fn try_match(
+        &self,
+        selected: &PreAggregationForQuery,
+    ) -> Result<Option<MatchedRollup>, CubeError> {
+        if self.query_properties.ungrouped()
+            || self.query_properties.measures().is_empty()
+            || !self.query_properties.measures_filters().is_empty()
+            || self.query_properties.should_use_time_series()?
+            || self
+                .query_properties
+                .full_key_aggregate_measures()?
+                .has_multi_stage_measures()
+        {
+            return Ok(None);
+        }
+
+        let pre_aggregation = self
+            .query_tools
+            .cube_evaluator()
+            .pre_aggregations_for_cube_as_array(selected.cube_name.clone())?
+            .into_iter()
+            .find(|p| p.name == selected.name);
+        let pre_aggregation = match pre_aggregation {
+            Some(pre_aggregation) if pre_aggregation.is_rollup() => pre_aggregation,
+            _ => return Ok(None),
+        };
+
+        Ok(self
+            .match_rollup(&pre_aggregation)?
+            .map(|time_dimensions| MatchedRollup {
+                cube_name: selected.cube_name.clone(),
+                table_name: selected.table_name.clone(),
+                pre_aggregation,
+                time_dimensions,
+            }))
+    }
+
+    fn match_rollup(
+        &self,
+        pre_aggregation: &PreAggregationDescription,
+    ) -> Result<Option<HashMap<String, String>>, CubeError> {
+        for measure in self.query_properties.measures() {
+            if measure.is_calculated()
+                || RollupMeasure::rollup_aggregation(measure.measure_type()).is_none()
+                || !pre_aggregation.measures.contains(&measure.full_name())
+            {
+                return Ok(None);
+            }
+        }
+
+        for dimension in self.query_properties.dimensions() {
+            if !Self::has_dimension(pre_aggregation, &dimension.full_name()) {
+                return Ok(None);
+            }
+        }
+
+        let mut time_dimensions = HashMap::new();
+        for time_dimension in self.query_properties.time_dimensions() {
+            let name = time_dimension.full_name();
+            let granularity = time_dimension
+                .get_granularity()
+                .unwrap_or("day".to_string());
+            let parents = match GranularityHelper::granularity_parents(&granularity) {
+                Ok(parents) => parents,
+                Err(_) => return Ok(None),
+            };
+            let rollup_time_dimension = pre_aggregation
+                .time_dimensions
+                .iter()
+                .find(|td| td.dimension == name && parents.contains(&td.granularity));
+            if let Some(rollup_time_dimension) = rollup_time_dimension {
+                time_dimensions.insert(name, rollup_time_dimension.granularity.clone());
+            } else {
+                return Ok(None);
+            }
+        }
+
+        for item in self
+            .query_properties
+            .dimensions_filters()
+            .iter()
+            .chain(self.query_properties.time_dimensions_filters().iter())
+        {
+            if !self.match_filter_item(pre_aggregation, item, &mut time_dimensions) {
+                return Ok(None);
+            }
+        }
+
+        Ok(Some(time_dimensions))
+    }
+
+    fn match_filter_item(
+        &self,
+        pre_aggregation: &PreAggregationDescription,
+        item: &FilterItem,
+        time_dimensions: &mut HashMap<String, String>,
+    ) -> bool {
+        match item {
+            FilterItem::Group(group) => group
+                .items
+                .iter()
+                .all(|itm| self.match_filter_item(pre_aggregation, itm, time_dimensions)),
+            FilterItem::Item(filter) => {
+                let name = filter.member_name();
+                if Self::has_dimension(pre_aggregation, &name) {
+                    return true;
+                }
+                if filter.filter_operator() != &FilterOperator::InDateRange {
+                    return false;
+                }
+                let granularity = if let Some(granularity) = time_dimensions.get(&name) {
+                    granularity.clone()
+                } else if let Some(td) = pre_aggregation
+                    .time_dimensions
+                    .iter()
+                    .find(|td| td.dimension == name)
+                {
+                    td.granularity.clone()
+                } else {
+                    return false;
+                };
+                let is_aligned = pre_aggregation
+                    .allow_non_strict_date_range_match
+                    .unwrap_or(false)
+                    || Self::is_date_range_aligned(filter.values(), &granularity);
+                if is_aligned {
+                    time_dimensions.insert(name, granularity);
+                }
+                is_aligned
+            }
+        }
+    }
+
+    fn has_dimension(pre_aggregation: &PreAggregationDescription, name: &String) -> bool {
+        pre_aggregation.dimensions.contains(name) || pre_aggregation.segments.contains(name)
+    }
+
+    /// Rollup stores time truncated to its granularity, so a date range can be
+    /// served only if it starts and ends on the granularity boundaries.
+    fn is_date_range_aligned(values: &Vec<Option<String>>, granularity: &str) -> bool {
+        let (from, to) = match (values.get(0), values.get(1)) {
+            (Some(Some(from)), Some(Some(to))) => (from, to),
+            _ => return false,
+        };
+        let from = Self::parse_date_time(from);
+        let to_exclusive = Self::parse_date_time(to).and_then(|to_value| {
+            if to.len() == 10 {
+                Some(to_value + Duration::days(1))
+            } else if to_value.nanosecond() >= 999_000_000 {
+                Some(to_value.with_nanosecond(0)? + Duration::seconds(1))
+            } else {
+                None
+            }
+        });
+        match (from, to_exclusive) {
+            (Some(from), Some(to_exclusive)) => {
+                Self::truncate(&from, granularity) == Some(from)
+                    && Self::truncate(&to_exclusive, granularity) == Some(to_exclusive)
+            }
+            _ => false,
+        }
+    }
+
+    fn parse_date_time(value: &str) -> Option<NaiveDateTime> {
+        if value.len() == 10 {
+            NaiveDate::parse_from_str(value, "%Y-%m-%d")
+                .ok()
+                .map(|d| d.and_time(NaiveTime::MIN))
+        } else {
+            NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S%.f").ok()
+        }
+    }
+
+    fn truncate(value: &NaiveDateTime, granularity: &str) -> Option<NaiveDateTime> {
+        let date = value.date();
+        let result = match granularity {
+            "second" => value.with_nanosecond(0)?,
+            "minute" => date.and_hms_opt(value.hour(), value.minute(), 0)?,
+            "hour" => date.and_hms_opt(value.hour(), 0, 0)?,
+            "day" => date.and_time(NaiveTime::MIN),
+            "week" => (date - Duration::days(date.weekday().num_days_from_monday() as i64))
+                .and_time(NaiveTime::MIN),
+            "month" => date.with_day(1)?.and_time(NaiveTime::MIN),
+            "quarter" => NaiveDate::from_ymd_opt(date.year(), (date.month0() / 3) * 3 + 1, 1)?
+                .and_time(NaiveTime::MIN),
+            "year" => NaiveDate::from_ymd_opt(date.year(), 1, 1)?.and_time(NaiveTime::MIN),
+            _ => return None,
+        };
+        Some(result)
+    }
+}
@@ -0,0 +1,7 @@
+mod matcher;
+mod rollup_member;
+mod rollup_query_planner;
+
+pub use matcher::{MatchedRollup, PreAggregationMatcher};
+pub use rollup_member::{RollupMeasure, RollupTimeDimension};
+pub use rollup_query_planner::RollupQueryPlanner;
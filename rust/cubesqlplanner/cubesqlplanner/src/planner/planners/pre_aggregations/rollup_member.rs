@@ -0,0 +1,134 @@
+use crate::planner::query_tools::QueryTools;
+use crate::planner::sql_evaluator::MemberSymbol;
+use crate::planner::{BaseMeasure, BaseMember, BaseTimeDimension, VisitorContext};
+use cubenativeutils::CubeError;
+use std::rc::Rc;
+
+/// Measure re-aggregated from the column of a rollup table.
+pub struct RollupMeasure {
+    measure: Rc<BaseMeasure>,
+    query_tools: Rc<QueryTools>,
+    rollup_alias: String,
+}
+
+impl RollupMeasure {
+    pub fn new(
+        measure: Rc<BaseMeasure>,
+        query_tools: Rc<QueryTools>,
+        rollup_alias: String,
+    ) -> Rc<Self> {
+        Rc::new(Self {
+            measure,
+            query_tools,
+            rollup_alias,
+        })
+    }
+
+    /// Aggregation which combines partial values stored in the rollup,
+    /// `None` if measure can't be re-aggregated.
+    pub fn rollup_aggregation(measure_type: &str) -> Option<&'static str> {
+        match measure_type {
+            "count" | "sum" => Some("sum"),
+            "min" => Some("min"),
+            "max" => Some("max"),
+            _ => None,
+        }
+    }
+}
+
+impl BaseMember for RollupMeasure {
+    fn to_sql(&self, _context: Rc<VisitorContext>) -> Result<String, CubeError> {
+        let aggregation =
+            Self::rollup_aggregation(self.measure.measure_type()).ok_or_else(|| {
+                CubeError::internal(format!(
+                    "Measure {} of type {} can't be re-aggregated from rollup",
+                    self.measure.full_name(),
+                    self.measure.measure_type()
+                ))
+            })?;
+        Ok(format!(
+            "{}({}.{})",
+            aggregation,
+            self.query_tools.escape_column_name(&self.rollup_alias),
+            self.query_tools.escaped_alias_name(self.measure.measure())
+        ))
+    }
+
+    fn alias_name(&self) -> String {
+        self.measure.alias_name()
+    }
+
+    fn member_evaluator(&self) -> Rc<MemberSymbol> {
+        self.measure.member_evaluator().clone()
+    }
+
+    fn as_base_member(self: Rc<Self>) -> Rc<dyn BaseMember> {
+        self.clone()
+    }
+
+    fn cube_name(&self) -> &String {
+        self.measure.cube_name()
+    }
+
+    fn name(&self) -> &String {
+        self.measure.name()
+    }
+}
+
+/// Time dimension read from the rollup column and regrouped when the query
+/// granularity is coarser than the rollup one.
+pub struct RollupTimeDimension {
+    time_dimension: Rc<BaseTimeDimension>,
+    query_tools: Rc<QueryTools>,
+    rollup_granularity: String,
+}
+
+impl RollupTimeDimension {
+    pub fn new(
+        time_dimension: Rc<BaseTimeDimension>,
+        query_tools: Rc<QueryTools>,
+        rollup_granularity: String,
+    ) -> Rc<Self> {
+        Rc::new(Self {
+            time_dimension,
+            query_tools,
+            rollup_granularity,
+        })
+    }
+}
+
+impl BaseMember for RollupTimeDimension {
+    fn to_sql(&self, context: Rc<VisitorContext>) -> Result<String, CubeError> {
+        let column = self.time_dimension.to_sql(context)?;
+        match self.time_dimension.get_granularity_obj() {
+            Some(granularity) if granularity.granularity() != &self.rollup_granularity => {
+                granularity.apply_to_input_sql(&self.query_tools, column)
+            }
+            _ => Ok(column),
+        }
+    }
+
+    fn alias_name(&self) -> String {
+        self.time_dimension.alias_name()
+    }
+
+    fn member_evaluator(&self) -> Rc<MemberSymbol> {
+        self.time_dimension.member_evaluator()
+    }
+
+    fn as_base_member(self: Rc<Self>) -> Rc<dyn BaseMember> {
+        self.clone()
+    }
+
+    fn cube_name(&self) -> &String {
+        self.time_dimension.cube_name()
+    }
+
+    fn name(&self) -> &String {
+        self.time_dimension.name()
+    }
+
+    fn alias_suffix(&self) -> Option<String> {
+        self.time_dimension.alias_suffix()
+    }
+}
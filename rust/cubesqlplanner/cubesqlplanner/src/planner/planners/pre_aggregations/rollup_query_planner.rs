@@ -0,0 +1,124 @@
+use super::{MatchedRollup, RollupMeasure, RollupTimeDimension};
+use crate::plan::{
+    Expr, From, MemberExpression, QualifiedColumnName, Schema, Select, SelectBuilder,
+};
+use crate::planner::planners::OrderPlanner;
+use crate::planner::query_tools::QueryTools;
+use crate::planner::sql_evaluator::sql_nodes::SqlNodesFactory;
+use crate::planner::{BaseMember, BaseMemberHelper, QueryProperties};
+use cubenativeutils::CubeError;
+use std::rc::Rc;
+
+pub struct RollupQueryPlanner {
+    query_tools: Rc<QueryTools>,
+    query_properties: Rc<QueryProperties>,
+    context_factory: SqlNodesFactory,
+}
+
+impl RollupQueryPlanner {
+    pub fn new(
+        query_tools: Rc<QueryTools>,
+        query_properties: Rc<QueryProperties>,
+        context_factory: SqlNodesFactory,
+    ) -> Self {
+        Self {
+            query_tools,
+            query_properties,
+            context_factory,
+        }
+    }
+
+    pub fn plan(&self, rollup: &MatchedRollup) -> Result<Select, CubeError> {
+        let pre_aggregation = &rollup.pre_aggregation;
+        let table_name = rollup.table_name.clone();
+        let alias = self
+            .query_tools
+            .alias_name(&format!("{}.{}", rollup.cube_name, pre_aggregation.name));
+        let from = From::new_from_table_reference(
+            table_name,
+            Rc::new(Schema::empty()),
+            Some(alias.clone()),
+        );
+
+        let mut context_factory = self.context_factory.clone();
+        for dimension in pre_aggregation
+            .dimensions
+            .iter()
+            .chain(pre_aggregation.segments.iter())
+        {
+            context_factory.add_render_reference(
+                dimension.clone(),
+                QualifiedColumnName::new(
+                    Some(alias.clone()),
+                    self.query_tools.alias_name(dimension),
+                ),
+            );
+        }
+        for (dimension, granularity) in rollup.time_dimensions.iter() {
+            context_factory.add_render_reference(
+                dimension.clone(),
+                QualifiedColumnName::new(
+                    Some(alias.clone()),
+                    self.query_tools
+                        .alias_name(&format!("{}_{}", dimension, granularity)),
+                ),
+            );
+        }
+
+        let dimensions =
+            BaseMemberHelper::upcast_vec_to_base_member(self.query_properties.dimensions());
+        let mut time_dimensions: Vec<Rc<dyn BaseMember>> = Vec::new();
+        for time_dimension in self.query_properties.time_dimensions() {
+            let rollup_granularity = rollup
+                .time_dimensions
+                .get(&time_dimension.full_name())
+                .ok_or_else(|| {
+                    CubeError::internal(format!(
+                        "Time dimension {} is not matched to rollup {}",
+                        time_dimension.full_name(),
+                        pre_aggregation.name
+                    ))
+                })?;
+            time_dimensions.push(RollupTimeDimension::new(
+                time_dimension.clone(),
+                self.query_tools.clone(),
+                rollup_granularity.clone(),
+            ));
+        }
+        let measures = self
+            .query_properties
+            .measures()
+            .iter()
+            .map(|m| -> Rc<dyn BaseMember> {
+                RollupMeasure::new(m.clone(), self.query_tools.clone(), alias.clone())
+            })
+            .collect::<Vec<_>>();
+
+        let all_members = dimensions
+            .iter()
+            .chain(time_dimensions.iter())
+            .chain(measures.iter())
+            .cloned()
+            .collect::<Vec<_>>();
+
+        let mut select_builder = SelectBuilder::new(from);
+        for member in all_members.iter() {
+            select_builder.add_projection_member(member, None);
+        }
+        select_builder.set_filter(self.query_properties.all_filters());
+        select_builder.set_group_by(
+            dimensions
+                .iter()
+                .chain(time_dimensions.iter())
+                .map(|m| Expr::Member(MemberExpression::new(m.clone())))
+                .collect(),
+        );
+        select_builder.set_order_by(OrderPlanner::custom_order(
+            self.query_properties.order_by(),
+            &all_members,
+        ));
+        select_builder.set_limit(self.query_properties.row_limit());
+        select_builder.set_offset(self.query_properties.offset());
+        Ok(select_builder.build(context_factory))
+    }
+}
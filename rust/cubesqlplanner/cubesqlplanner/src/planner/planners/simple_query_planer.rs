@@ -41,8 +41,8 @@ impl SimpleQueryPlanner {
             .make_join_node_impl(&None, self.query_properties.simple_query_join()?)?;
         let mut select_builder = SelectBuilder::new(from.clone());
         for time_dim in self.query_properties.time_dimensions() {
-            if let Some(granularity) = time_dim.get_granularity() {
-                context_factory.add_leaf_time_dimension(&time_dim.full_name(), &granularity);
+            if let Some(granularity) = time_dim.get_granularity_obj() {
+                context_factory.add_leaf_time_dimension(&time_dim.full_name(), granularity);
             }
         }
         for member in self
@@ -6,6 +6,7 @@ use super::{
     UngroupedQueryFinalMeasureSqlNode,
 };
 use crate::plan::schema::QualifiedColumnName;
+use crate::planner::Granularity;
 use std::collections::HashMap;
 use std::rc::Rc;
 
@@ -16,7 +17,7 @@ pub struct SqlNodesFactory {
     ungrouped_measure: bool,
     render_references: HashMap<String, QualifiedColumnName>,
     ungrouped_measure_references: HashMap<String, QualifiedColumnName>,
-    leaf_time_dimensions: HashMap<String, String>,
+    leaf_time_dimensions: HashMap<String, Granularity>,
     cube_name_references: HashMap<String, String>,
     multi_stage_rank: Option<Vec<String>>,   //partition_by
     multi_stage_window: Option<Vec<String>>, //partition_by
@@ -67,7 +68,7 @@ impl SqlNodesFactory {
         self.multi_stage_rank = Some(partition_by);
     }
 
-    pub fn add_leaf_time_dimension(&mut self, dimension_name: &String, granularity: &String) {
+    pub fn add_leaf_time_dimension(&mut self, dimension_name: &String, granularity: &Granularity) {
         self.leaf_time_dimensions
             .insert(dimension_name.clone(), granularity.clone());
     }
@@ -2,6 +2,7 @@ use super::SqlNode;
 use crate::planner::query_tools::QueryTools;
 use crate::planner::sql_evaluator::MemberSymbol;
 use crate::planner::sql_evaluator::SqlEvaluatorVisitor;
+use crate::planner::Granularity;
 use cubenativeutils::CubeError;
 use std::any::Any;
 use std::collections::HashMap;
@@ -9,11 +10,14 @@ use std::rc::Rc;
 
 pub struct LeafTimeDimensionNode {
     input: Rc<dyn SqlNode>,
-    leaf_time_dimensions: HashMap<String, String>,
+    leaf_time_dimensions: HashMap<String, Granularity>,
 }
 
 impl LeafTimeDimensionNode {
-    pub fn new(input: Rc<dyn SqlNode>, leaf_time_dimensions: HashMap<String, String>) -> Rc<Self> {
+    pub fn new(
+        input: Rc<dyn SqlNode>,
+        leaf_time_dimensions: HashMap<String, Granularity>,
+    ) -> Rc<Self> {
         Rc::new(Self {
             input,
             leaf_time_dimensions,
@@ -36,9 +40,7 @@ impl SqlNode for LeafTimeDimensionNode {
 
         let res = if let Some(granularity) = self.leaf_time_dimensions.get(&full_name) {
             let converted_tz = query_tools.base_tools().convert_tz(input_sql)?;
-            query_tools
-                .base_tools()
-                .time_grouped_column(granularity.clone(), converted_tz)?
+            granularity.apply_to_input_sql(&query_tools, converted_tz)?
         } else {
             input_sql
         };
@@ -1,5 +1,5 @@
 use super::{MemberSymbol, SymbolFactory};
-use crate::cube_bridge::dimension_definition::DimensionDefinition;
+use crate::cube_bridge::dimension_definition::{DimensionDefinition, GranularityDefinition};
 use crate::cube_bridge::evaluator::CubeEvaluator;
 use crate::cube_bridge::memeber_sql::MemberSql;
 use crate::planner::query_tools::QueryTools;
@@ -51,6 +51,14 @@ impl DimensionSymbol {
     pub fn is_multi_stage(&self) -> bool {
         self.definition.static_data().multi_stage.unwrap_or(false)
     }
+
+    pub fn granularity_definition(&self, granularity: &str) -> Option<GranularityDefinition> {
+        self.definition
+            .static_data()
+            .granularities
+            .as_ref()
+            .and_then(|granularities| granularities.get(granularity).cloned())
+    }
     pub fn get_dependencies(&self) -> Vec<Rc<MemberSymbol>> {
         let mut deps = vec![];
         self.member_sql.extract_symbol_deps(&mut deps);
@@ -38,6 +38,7 @@ pub struct MeasureSymbol {
     name: String,
     definition: Rc<dyn MeasureDefinition>,
     measure_filters: Vec<Rc<SqlCall>>,
+    measure_drill_filters: Vec<Rc<SqlCall>>,
     measure_order_by: Vec<MeasureOrderBy>,
     member_sql: Rc<SqlCall>,
     is_splitted_source: bool,
@@ -50,6 +51,7 @@ impl MeasureSymbol {
         member_sql: Rc<SqlCall>,
         definition: Rc<dyn MeasureDefinition>,
         measure_filters: Vec<Rc<SqlCall>>,
+        measure_drill_filters: Vec<Rc<SqlCall>>,
         measure_order_by: Vec<MeasureOrderBy>,
     ) -> Self {
         Self {
@@ -58,6 +60,7 @@ impl MeasureSymbol {
             member_sql,
             definition,
             measure_filters,
+            measure_drill_filters,
             measure_order_by,
             is_splitted_source: false,
         }
@@ -80,6 +83,7 @@ impl MeasureSymbol {
             self.member_sql.clone(),
             self.definition().clone(),
             self.measure_filters.clone(),
+            self.measure_drill_filters.clone(),
             self.measure_order_by.clone(),
         );
         (measure_with_source, source)
@@ -157,6 +161,10 @@ impl MeasureSymbol {
         &self.measure_filters
     }
 
+    pub fn measure_drill_filters(&self) -> &Vec<Rc<SqlCall>> {
+        &self.measure_drill_filters
+    }
+
     pub fn measure_order_by(&self) -> &Vec<MeasureOrderBy> {
         &self.measure_order_by
     }
@@ -256,6 +264,14 @@ impl SymbolFactory for MeasureSymbolFactory {
             }
         }
 
+        let mut measure_drill_filters = vec![];
+        if let Some(filters) = definition.drill_filters()? {
+            for filter in filters.items().iter() {
+                let node = compiler.compile_sql_call(&cube_name, filter.sql()?)?;
+                measure_drill_filters.push(node);
+            }
+        }
+
         let mut measure_order_by = vec![];
         if let Some(group_by) = definition.order_by()? {
             for item in group_by.items().iter() {
@@ -271,6 +287,7 @@ impl SymbolFactory for MeasureSymbolFactory {
             sql,
             definition,
             measure_filters,
+            measure_drill_filters,
             measure_order_by,
         )))
     }
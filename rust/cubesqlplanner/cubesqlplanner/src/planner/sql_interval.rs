@@ -0,0 +1,145 @@
+use chrono::{Duration, Months, NaiveDateTime};
+use cubenativeutils::CubeError;
+use std::str::FromStr;
+
+/// PostgreSQL-like interval, e.g. `2 years 15 months 100 weeks 99 hours 15 seconds`.
+/// Negative units are also supported: `-2 months 5 days -10 hours`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SqlInterval {
+    pub year: i32,
+    pub quarter: i32,
+    pub month: i32,
+    pub week: i32,
+    pub day: i32,
+    pub hour: i32,
+    pub minute: i32,
+    pub second: i32,
+}
+
+impl SqlInterval {
+    /// Returns the smallest granularity for the interval. It is important to bubble up from
+    /// the smallest unit, as this is used e.g. for minimum rollup granularity.
+    pub fn min_granularity(&self) -> String {
+        let units = self.units();
+        if units.len() == 1 {
+            return units[0].0.to_string();
+        }
+
+        let res = if self.second != 0 {
+            "second"
+        } else if self.minute != 0 {
+            "minute"
+        } else if self.hour != 0 {
+            "hour"
+        } else if self.day != 0 || self.week != 0 {
+            "day"
+        } else if self.month != 0 || self.quarter != 0 {
+            "month"
+        } else {
+            "year"
+        };
+        res.to_string()
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.to_seconds() < 0
+    }
+
+    /// Approximate length of the interval, months are counted the same way as moment.js does
+    pub fn to_seconds(&self) -> i64 {
+        const DAY: i64 = 86400;
+        const MONTH: i64 = 2629746;
+
+        (self.year as i64) * 12 * MONTH
+            + (self.quarter as i64) * 3 * MONTH
+            + (self.month as i64) * MONTH
+            + (self.week as i64) * 7 * DAY
+            + (self.day as i64) * DAY
+            + (self.hour as i64) * 3600
+            + (self.minute as i64) * 60
+            + (self.second as i64)
+    }
+
+    pub fn add_to(&self, date: &NaiveDateTime) -> Result<NaiveDateTime, CubeError> {
+        self.apply(date, 1)
+    }
+
+    pub fn subtract_from(&self, date: &NaiveDateTime) -> Result<NaiveDateTime, CubeError> {
+        self.apply(date, -1)
+    }
+
+    fn apply(&self, date: &NaiveDateTime, sign: i32) -> Result<NaiveDateTime, CubeError> {
+        let months = sign * (self.year * 12 + self.quarter * 3 + self.month);
+        let date = if months >= 0 {
+            date.checked_add_months(Months::new(months as u32))
+        } else {
+            date.checked_sub_months(Months::new(months.unsigned_abs()))
+        };
+        let duration = Duration::weeks(self.week as i64)
+            + Duration::days(self.day as i64)
+            + Duration::hours(self.hour as i64)
+            + Duration::minutes(self.minute as i64)
+            + Duration::seconds(self.second as i64);
+        let date = date.and_then(|d| {
+            if sign > 0 {
+                d.checked_add_signed(duration)
+            } else {
+                d.checked_sub_signed(duration)
+            }
+        });
+        date.ok_or_else(|| CubeError::user(format!("Date is out of range for interval {:?}", self)))
+    }
+
+    fn units(&self) -> Vec<(&'static str, i32)> {
+        vec![
+            ("year", self.year),
+            ("quarter", self.quarter),
+            ("month", self.month),
+            ("week", self.week),
+            ("day", self.day),
+            ("hour", self.hour),
+            ("minute", self.minute),
+            ("second", self.second),
+        ]
+        .into_iter()
+        .filter(|(_, v)| *v != 0)
+        .collect()
+    }
+}
+
+impl FromStr for SqlInterval {
+    type Err = CubeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts = s.split_whitespace().collect::<Vec<_>>();
+        if parts.is_empty() || parts.len() % 2 != 0 {
+            return Err(CubeError::user(format!("Invalid interval: '{}'", s)));
+        }
+
+        let mut result = SqlInterval::default();
+        for part in parts.chunks(2) {
+            let value = part[0]
+                .parse::<i32>()
+                .map_err(|_| CubeError::user(format!("Invalid interval: '{}'", s)))?;
+            let unit = part[1].to_lowercase();
+            let unit = unit.strip_suffix('s').unwrap_or(&unit);
+            match unit {
+                "year" => result.year += value,
+                "quarter" => result.quarter += value,
+                "month" => result.month += value,
+                "week" => result.week += value,
+                "day" => result.day += value,
+                "hour" => result.hour += value,
+                "minute" => result.minute += value,
+                "second" => result.second += value,
+                _ => {
+                    return Err(CubeError::user(format!(
+                        "Invalid interval unit '{}' in '{}'",
+                        part[1], s
+                    )))
+                }
+            }
+        }
+        Ok(result)
+    }
+}
@@ -61,6 +61,22 @@ impl FilterTemplates {
         )
     }
 
+    pub fn time_not_in_range_filter(
+        &self,
+        column: String,
+        from_timestamp: String,
+        to_timestamp: String,
+    ) -> Result<String, CubeError> {
+        self.render.render_template(
+            &"filters/time_not_in_range_filter",
+            context! {
+                column => column,
+                from_timestamp => from_timestamp,
+                to_timestamp => to_timestamp,
+            },
+        )
+    }
+
     pub fn in_where(
         &self,
         column: String,
@@ -182,6 +198,11 @@ impl FilterTemplates {
         )
     }
 
+    pub fn always_true(&self) -> Result<String, CubeError> {
+        self.render
+            .render_template(&"filters/always_true", context! {})
+    }
+
     pub fn additional_null_check(&self, need: bool, column: &String) -> Result<String, CubeError> {
         if need {
             self.or_is_null_check(column.clone())
@@ -215,4 +236,20 @@ impl FilterTemplates {
             },
         )
     }
+
+    pub fn regex(&self, column: &str, value: &str, not: bool) -> Result<String, CubeError> {
+        if !self.render.contains_template("filters/regex") {
+            return Err(CubeError::user(
+                "regex filter is not supported by this dialect".to_string(),
+            ));
+        }
+        self.render.render_template(
+            &"filters/regex",
+            context! {
+                column => column,
+                value => value,
+                negated => not
+            },
+        )
+    }
 }
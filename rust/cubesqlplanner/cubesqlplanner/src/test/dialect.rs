@@ -0,0 +1,80 @@
+use cubenativeutils::CubeError;
+use minijinja::{context, Environment};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// Dialect specific part of `BaseQuery`: SQL templates and the few helpers
+/// the planner still calls through `BaseTools`.
+///
+/// The bundled dialects are synthetic: they are written by hand after the
+/// `sqlTemplates()` of the schema compiler adapters and are not kept in sync
+/// with them automatically.
+#[derive(Deserialize, Debug)]
+pub struct DialectYaml {
+    pub timestamp_precision: u32,
+    pub convert_tz: Option<String>,
+    pub time_grouped_column: Option<String>,
+    pub date_bin: Option<String>,
+    pub templates: HashMap<String, HashMap<String, String>>,
+}
+
+impl DialectYaml {
+    pub fn ansi() -> Self {
+        Self::from_yaml(include_str!("fixtures/dialects/ansi.yaml")).unwrap()
+    }
+
+    pub fn postgres() -> Self {
+        Self::from_yaml(include_str!("fixtures/dialects/postgres.yaml")).unwrap()
+    }
+
+    pub fn from_yaml(yaml: &str) -> Result<Self, CubeError> {
+        serde_yaml::from_str(yaml)
+            .map_err(|e| CubeError::internal(format!("Failed to parse dialect: {}", e)))
+    }
+
+    pub fn convert_tz(&self, field: &str, timezone: &str) -> Result<String, CubeError> {
+        Self::render(
+            "convert_tz",
+            &self.convert_tz,
+            context! { field => field, timezone => timezone },
+        )
+    }
+
+    pub fn time_grouped_column(
+        &self,
+        granularity: &str,
+        dimension: &str,
+    ) -> Result<String, CubeError> {
+        Self::render(
+            "time_grouped_column",
+            &self.time_grouped_column,
+            context! { granularity => granularity, dimension => dimension },
+        )
+    }
+
+    pub fn date_bin(
+        &self,
+        interval: &str,
+        source: &str,
+        origin: &str,
+    ) -> Result<String, CubeError> {
+        Self::render(
+            "date_bin",
+            &self.date_bin,
+            context! { interval => interval, source => source, origin => origin },
+        )
+    }
+
+    fn render(
+        name: &str,
+        template: &Option<String>,
+        ctx: minijinja::Value,
+    ) -> Result<String, CubeError> {
+        let template = template.as_ref().ok_or_else(|| {
+            CubeError::internal(format!("{} is not implemented by dialect", name))
+        })?;
+        Environment::new()
+            .render_str(template, ctx)
+            .map_err(|e| CubeError::internal(format!("Error rendering {}: {}", name, e)))
+    }
+}
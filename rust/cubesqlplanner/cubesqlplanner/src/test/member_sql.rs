@@ -0,0 +1,127 @@
+use cubenativeutils::wrappers::object::{NativeFunction, NativeString, NativeStruct, NativeType};
+use cubenativeutils::wrappers::rust::{RustFunction, RustInnerTypes, RustString};
+use cubenativeutils::wrappers::NativeObjectHandle;
+use cubenativeutils::CubeError;
+use itertools::Itertools;
+use lazy_static::lazy_static;
+use regex::{Captures, Regex};
+use serde::Serialize;
+use std::collections::HashMap;
+
+lazy_static! {
+    static ref REFERENCE_RE: Regex =
+        Regex::new(r"\{([A-Za-z_][A-Za-z0-9_]*(?:\.[A-Za-z_][A-Za-z0-9_]*)*)\}").unwrap();
+}
+
+#[derive(Serialize, Debug)]
+pub struct CallDep {
+    pub name: String,
+    pub parent: Option<usize>,
+}
+
+fn references(sql: &str) -> Vec<Vec<String>> {
+    REFERENCE_RE
+        .captures_iter(sql)
+        .map(|c| c[1].split('.').map(|s| s.to_string()).collect())
+        .collect()
+}
+
+/// Builds the function JS schema compiler would produce for member sql, e.g.
+/// `{CUBE}.amount` becomes `(CUBE) => `${CUBE}.amount``. The source sql is kept
+/// as function definition, so call dependencies can be resolved from it later.
+pub fn member_sql_function(sql: &str) -> RustFunction {
+    let args_names = references(sql)
+        .into_iter()
+        .map(|path| path[0].clone())
+        .unique()
+        .collect_vec();
+    let source = sql.to_string();
+    let names = args_names.clone();
+    RustFunction::new(args_names, sql, move |args| {
+        let mut error = None;
+        let result = REFERENCE_RE
+            .replace_all(&source, |caps: &Captures| {
+                match resolve_reference(&names, &args, &caps[1]) {
+                    Ok(res) => res,
+                    Err(e) => {
+                        error.get_or_insert(e);
+                        String::new()
+                    }
+                }
+            })
+            .to_string();
+        if let Some(error) = error {
+            return Err(error);
+        }
+        Ok(NativeObjectHandle::new(
+            RustString::new(result).into_object(),
+        ))
+    })
+}
+
+fn resolve_reference(
+    names: &Vec<String>,
+    args: &Vec<NativeObjectHandle<RustInnerTypes>>,
+    reference: &str,
+) -> Result<String, CubeError> {
+    let mut path = reference.split('.');
+    let arg_name = path.next().unwrap();
+    let mut value = names
+        .iter()
+        .position(|n| n == arg_name)
+        .and_then(|i| args.get(i).cloned())
+        .ok_or_else(|| CubeError::internal(format!("Argument {} is not passed", arg_name)))?;
+    for field in path {
+        value = value.to_struct()?.get_field(field)?;
+        if value.is_undefined() {
+            return Err(CubeError::internal(format!(
+                "Cannot resolve {} in {}",
+                field, reference
+            )));
+        }
+    }
+    if let Ok(s) = value.to_string() {
+        s.value()
+    } else {
+        value
+            .to_struct()?
+            .call_method("toString", vec![])?
+            .to_string()?
+            .value()
+    }
+}
+
+/// Emulates `resolveSymbolsCallDeps` of the JS evaluator: top level references
+/// come in arguments order, property accesses become children of their parent.
+/// Bare cube references get a `toString` child, as they are rendered as cube alias.
+pub fn resolve_call_deps(
+    member_sql: &RustFunction,
+    is_cube_reference: impl Fn(&str) -> bool,
+) -> Result<Vec<CallDep>, CubeError> {
+    let mut result: Vec<CallDep> = Vec::new();
+    let mut indexes: HashMap<Vec<String>, usize> = HashMap::new();
+    let mut add_dep = |path: &[String], result: &mut Vec<CallDep>| {
+        let mut parent = None;
+        for depth in 1..=path.len() {
+            let prefix = path[..depth].to_vec();
+            let index = if let Some(index) = indexes.get(&prefix) {
+                *index
+            } else {
+                result.push(CallDep {
+                    name: path[depth - 1].clone(),
+                    parent,
+                });
+                indexes.insert(prefix, result.len() - 1);
+                result.len() - 1
+            };
+            parent = Some(index);
+        }
+    };
+    for path in references(&member_sql.definition()?) {
+        add_dep(&path, &mut result);
+        if path.len() == 1 && is_cube_reference(&path[0]) {
+            add_dep(&[path[0].clone(), "toString".to_string()], &mut result);
+        }
+    }
+    Ok(result)
+}
@@ -0,0 +1,92 @@
+//! Pure Rust harness for the planner. Cube definitions are loaded from YAML
+//! fixtures and exposed through the same bridge traits Node.js implements,
+//! so `BaseQuery` can be run without Neon. Dialect fixtures are synthetic
+//! subsets of the adapters' SQL templates, see [`dialect::DialectYaml`].
+pub mod dialect;
+pub mod member_sql;
+pub mod native_objects;
+pub mod schema;
+mod test_base_query;
+mod test_filters;
+mod test_plan_description;
+mod test_pre_aggregations;
+mod test_time_series;
+
+use crate::cube_bridge::base_query_options::NativeBaseQueryOptions;
+use crate::planner::base_query::BaseQuery;
+use crate::planner::plan_description::QueryPlanDescription;
+use cubenativeutils::wrappers::object::NativeObject;
+use cubenativeutils::wrappers::rust::{RustContext, RustInnerTypes, RustObject};
+use cubenativeutils::wrappers::serializer::NativeDeserializer;
+use cubenativeutils::wrappers::NativeType;
+use cubenativeutils::CubeError;
+use dialect::DialectYaml;
+use schema::SchemaYaml;
+use std::rc::Rc;
+
+pub struct TestContext {
+    schema: Rc<SchemaYaml>,
+    dialect: Rc<DialectYaml>,
+}
+
+impl TestContext {
+    pub fn new(schema: SchemaYaml, dialect: DialectYaml) -> Self {
+        Self {
+            schema: Rc::new(schema),
+            dialect: Rc::new(dialect),
+        }
+    }
+
+    pub fn default_schema() -> SchemaYaml {
+        SchemaYaml::from_yaml(include_str!("fixtures/schema.yaml")).unwrap()
+    }
+
+    /// Builds SQL and params for a query given in the same shape as the
+    /// JS `BaseQuery` options, e.g. `{measures: [orders.count]}`.
+    pub fn build_sql(&self, query_yaml: &str) -> Result<(String, Vec<String>), CubeError> {
+        let query = self.base_query(query_yaml)?;
+        NativeDeserializer::deserialize::<RustInnerTypes, (String, Vec<String>)>(
+            query.build_sql_and_params()?,
+        )
+    }
+
+    pub fn build_plan_description(
+        &self,
+        query_yaml: &str,
+    ) -> Result<QueryPlanDescription, CubeError> {
+        let query = self.base_query(query_yaml)?;
+        NativeDeserializer::deserialize::<RustInnerTypes, QueryPlanDescription>(
+            query.build_plan_description()?,
+        )
+    }
+
+    fn base_query(&self, query_yaml: &str) -> Result<BaseQuery<RustInnerTypes>, CubeError> {
+        let mut query: serde_json::Value = serde_yaml::from_str(query_yaml)
+            .map_err(|e| CubeError::internal(format!("Failed to parse query: {}", e)))?;
+        let timezone = query
+            .get("timezone")
+            .and_then(|tz| tz.as_str())
+            .unwrap_or("UTC")
+            .to_string();
+        query["timezone"] = serde_json::Value::String(timezone.clone());
+
+        let options = RustObject::from_json(&query)
+            .into_struct()?
+            .with_field(
+                "cubeEvaluator",
+                native_objects::cube_evaluator(self.schema.clone()).into_object(),
+            )
+            .with_field(
+                "baseTools",
+                native_objects::base_tools(self.schema.clone(), self.dialect.clone(), timezone)
+                    .into_object(),
+            )
+            .with_field(
+                "joinGraph",
+                native_objects::join_graph(self.schema.clone()).into_object(),
+            );
+        let options =
+            NativeBaseQueryOptions::<RustInnerTypes>::try_new(options.into_object().into_handle())?;
+        BaseQuery::try_new(RustContext::new_holder(), Rc::new(options))
+    }
+}
@@ -0,0 +1,404 @@
+use super::dialect::DialectYaml;
+use super::member_sql::{member_sql_function, resolve_call_deps};
+use super::schema::{CubeYaml, SchemaYaml};
+use chrono::{NaiveDateTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use cubenativeutils::wrappers::object::{NativeArray, NativeObject};
+use cubenativeutils::wrappers::rust::{
+    RustArray, RustContext, RustFunction, RustInnerTypes, RustObject, RustStruct,
+};
+use cubenativeutils::wrappers::serializer::{NativeDeserializer, NativeSerialize};
+use cubenativeutils::wrappers::{NativeObjectHandle, NativeType};
+use cubenativeutils::CubeError;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::json;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::rc::Rc;
+
+type Handle = NativeObjectHandle<RustInnerTypes>;
+
+fn arg<T: DeserializeOwned>(args: &Vec<Handle>, index: usize) -> Result<T, CubeError> {
+    let arg = args
+        .get(index)
+        .cloned()
+        .ok_or_else(|| CubeError::internal(format!("Argument {} is missing", index)))?;
+    NativeDeserializer::deserialize::<RustInnerTypes, T>(arg)
+}
+
+fn to_native<T: Serialize>(value: &T) -> Result<Handle, CubeError> {
+    value.to_native(RustContext::new_holder())
+}
+
+fn with_method<F>(target: RustStruct, name: &str, method: F) -> RustStruct
+where
+    F: Fn(Vec<Handle>) -> Result<Handle, CubeError> + 'static,
+{
+    target.with_field(name, RustFunction::new(vec![], name, method).into_object())
+}
+
+fn split_path(path: &str) -> Result<(String, String), CubeError> {
+    path.split_once('.')
+        .map(|(cube, member)| (cube.to_string(), member.to_string()))
+        .ok_or_else(|| CubeError::user(format!("Invalid member path: {}", path)))
+}
+
+fn cube_definition(cube: &CubeYaml) -> RustStruct {
+    let mut result = RustObject::from_json(&json!({ "name": cube.name }))
+        .into_struct()
+        .unwrap();
+    if let Some(sql_table) = &cube.sql_table {
+        result = result.with_field("sqlTable", member_sql_function(sql_table).into_object());
+    }
+    if let Some(sql) = &cube.sql {
+        result = result.with_field("sql", member_sql_function(sql).into_object());
+    }
+    result
+}
+
+fn measure_filters(filters: &Vec<String>) -> RustObject {
+    let result = RustArray::new();
+    for (i, filter) in filters.iter().enumerate() {
+        let filter = RustStruct::new().with_field("sql", member_sql_function(filter).into_object());
+        result
+            .set(i as u32, NativeObjectHandle::new(filter.into_object()))
+            .unwrap();
+    }
+    result.into_object()
+}
+
+struct SchemaObjects {
+    cubes: HashMap<String, RustStruct>,
+    measures: HashMap<String, RustStruct>,
+    dimensions: HashMap<String, RustStruct>,
+}
+
+impl SchemaObjects {
+    fn new(schema: &SchemaYaml) -> Self {
+        let mut cubes = HashMap::new();
+        let mut measures = HashMap::new();
+        let mut dimensions = HashMap::new();
+        for cube in schema.cubes.iter() {
+            let cube_object = cube_definition(cube);
+            for dimension in cube.dimensions.iter() {
+                let granularities = dimension
+                    .granularities
+                    .iter()
+                    .map(|g| (g.name.clone(), g))
+                    .collect::<HashMap<_, _>>();
+                let object = RustObject::from_json(&json!({
+                    "type": dimension.dimension_type,
+                    "ownedByCube": true,
+                    "granularities": granularities,
+                }))
+                .into_struct()
+                .unwrap()
+                .with_field("sql", member_sql_function(&dimension.sql).into_object());
+                dimensions.insert(format!("{}.{}", cube.name, dimension.name), object);
+            }
+            for measure in cube.measures.iter() {
+                let mut object = RustObject::from_json(&json!({
+                    "type": measure.measure_type,
+                    "ownedByCube": true,
+                    "rollingWindow": measure.rolling_window,
+                }))
+                .into_struct()
+                .unwrap();
+                if let Some(sql) = &measure.sql {
+                    object = object.with_field("sql", member_sql_function(sql).into_object());
+                }
+                if !measure.filters.is_empty() {
+                    object = object.with_field("filters", measure_filters(&measure.filters));
+                }
+                if !measure.drill_filters.is_empty() {
+                    object =
+                        object.with_field("drillFilters", measure_filters(&measure.drill_filters));
+                }
+                let cube_object = cube_object.clone();
+                object = with_method(object, "cube", move |_| {
+                    Ok(NativeObjectHandle::new(cube_object.clone().into_object()))
+                });
+                measures.insert(format!("{}.{}", cube.name, measure.name), object);
+            }
+            cubes.insert(cube.name.clone(), cube_object);
+        }
+        Self {
+            cubes,
+            measures,
+            dimensions,
+        }
+    }
+}
+
+fn call_deps_method(schema: Rc<SchemaYaml>) -> impl Fn(Vec<Handle>) -> Result<Handle, CubeError> {
+    move |args| {
+        let member_sql = args
+            .get(1)
+            .ok_or_else(|| CubeError::internal("Member sql is missing".to_string()))?
+            .to_function()?;
+        let deps = resolve_call_deps(&member_sql, |name| {
+            name == "CUBE" || name == "TABLE" || schema.find_cube(name).is_ok()
+        })?;
+        to_native(&deps)
+    }
+}
+
+/// Counterpart of the JS `CubeEvaluator` built over the YAML schema.
+pub fn cube_evaluator(schema: Rc<SchemaYaml>) -> Handle {
+    let objects = Rc::new(SchemaObjects::new(&schema));
+    let primary_keys = schema
+        .cubes
+        .iter()
+        .map(|c| (c.name.clone(), c.primary_keys()))
+        .collect::<HashMap<_, _>>();
+    let result = RustObject::from_json(&json!({ "primaryKeys": primary_keys }))
+        .into_struct()
+        .unwrap();
+
+    let objs = objects.clone();
+    let result = with_method(result, "parsePath", move |args| {
+        let path_type = arg::<String>(&args, 0)?;
+        let path = arg::<String>(&args, 1)?;
+        let exists = match path_type.as_str() {
+            "measures" => objs.measures.contains_key(&path),
+            "dimensions" => objs.dimensions.contains_key(&path),
+            _ => false,
+        };
+        if !exists {
+            return Err(CubeError::user(format!(
+                "{} '{}' not found",
+                path_type, path
+            )));
+        }
+        let (cube, member) = split_path(&path)?;
+        to_native(&vec![cube, member])
+    });
+    let objs = objects.clone();
+    let result = with_method(result, "measureByPath", move |args| {
+        let path = arg::<String>(&args, 0)?;
+        objs.measures
+            .get(&path)
+            .map(|m| NativeObjectHandle::new(m.clone().into_object()))
+            .ok_or_else(|| CubeError::user(format!("Measure '{}' not found", path)))
+    });
+    let objs = objects.clone();
+    let result = with_method(result, "dimensionByPath", move |args| {
+        let path = arg::<String>(&args, 0)?;
+        objs.dimensions
+            .get(&path)
+            .map(|d| NativeObjectHandle::new(d.clone().into_object()))
+            .ok_or_else(|| CubeError::user(format!("Dimension '{}' not found", path)))
+    });
+    let objs = objects.clone();
+    let result = with_method(result, "cubeFromPath", move |args| {
+        let path = arg::<String>(&args, 0)?;
+        objs.cubes
+            .get(&path)
+            .map(|c| NativeObjectHandle::new(c.clone().into_object()))
+            .ok_or_else(|| CubeError::user(format!("Cube '{}' not found", path)))
+    });
+    let objs = objects.clone();
+    let result = with_method(result, "isMeasure", move |args| {
+        let path = arg::<Vec<String>>(&args, 0)?;
+        to_native(&objs.measures.contains_key(&path.join(".")))
+    });
+    let objs = objects.clone();
+    let result = with_method(result, "isDimension", move |args| {
+        let path = arg::<Vec<String>>(&args, 0)?;
+        to_native(&objs.dimensions.contains_key(&path.join(".")))
+    });
+    let objs = objects.clone();
+    let result = with_method(result, "cubeExists", move |args| {
+        let name = arg::<String>(&args, 0)?;
+        to_native(&objs.cubes.contains_key(&name))
+    });
+    let result = with_method(
+        result,
+        "resolveSymbolsCallDeps",
+        call_deps_method(schema.clone()),
+    );
+    let sch = schema.clone();
+    let result = with_method(result, "preAggregationsForCubeAsArray", move |args| {
+        let cube_name = arg::<String>(&args, 0)?;
+        let cube = sch.find_cube(&cube_name)?;
+        Ok(NativeObjectHandle::new(RustObject::from_json(&json!(
+            cube.pre_aggregations
+        ))))
+    });
+
+    NativeObjectHandle::new(result.into_object())
+}
+
+fn in_db_time_zone(date: &str, timezone: &str) -> Result<String, CubeError> {
+    let tz = timezone
+        .parse::<Tz>()
+        .map_err(|_| CubeError::user(format!("Incorrect timezone {}", timezone)))?;
+    let local = NaiveDateTime::parse_from_str(date, "%Y-%m-%dT%H:%M:%S%.f")
+        .map_err(|e| CubeError::user(format!("Can't parse date {}: {}", date, e)))?;
+    let utc = tz
+        .from_local_datetime(&local)
+        .single()
+        .ok_or_else(|| CubeError::user(format!("Ambiguous local time {}", date)))?
+        .with_timezone(&Utc);
+    Ok(utc.format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string())
+}
+
+/// Counterpart of the JS `BaseQuery` methods exposed to the planner as `BaseTools`.
+pub fn base_tools(schema: Rc<SchemaYaml>, dialect: Rc<DialectYaml>, timezone: String) -> Handle {
+    let result = RustStruct::new();
+
+    let (dial, tz) = (dialect.clone(), timezone.clone());
+    let result = with_method(result, "convertTz", move |args| {
+        to_native(&dial.convert_tz(&arg::<String>(&args, 0)?, &tz)?)
+    });
+    let dial = dialect.clone();
+    let result = with_method(result, "timeGroupedColumn", move |args| {
+        to_native(&dial.time_grouped_column(&arg::<String>(&args, 0)?, &arg::<String>(&args, 1)?)?)
+    });
+    let dial = dialect.clone();
+    let result = with_method(result, "dateBin", move |args| {
+        to_native(&dial.date_bin(
+            &arg::<String>(&args, 0)?,
+            &arg::<String>(&args, 1)?,
+            &arg::<String>(&args, 2)?,
+        )?)
+    });
+    let dial = dialect.clone();
+    let result = with_method(result, "sqlTemplates", move |_| to_native(&dial.templates));
+    let result = with_method(
+        result,
+        "resolveSymbolsCallDeps",
+        call_deps_method(schema.clone()),
+    );
+    let result = with_method(result, "securityContextForRust", |_| {
+        Ok(NativeObjectHandle::new(RustStruct::new().into_object()))
+    });
+    let result = with_method(result, "filtersProxy", |_| {
+        Ok(NativeObjectHandle::new(RustStruct::new().into_object()))
+    });
+    let result = with_method(result, "filterGroupFunction", |_| {
+        Ok(NativeObjectHandle::new(RustStruct::new().into_object()))
+    });
+    let precision = dialect.timestamp_precision;
+    let result = with_method(result, "timestampPrecision", move |_| to_native(&precision));
+    let tz = timezone.clone();
+    let result = with_method(result, "inDbTimeZone", move |args| {
+        to_native(&in_db_time_zone(&arg::<String>(&args, 0)?, &tz)?)
+    });
+    let result = with_method(result, "getAllocatedParams", |_| {
+        to_native(&Vec::<String>::new())
+    });
+    let sch = schema.clone();
+    let result = with_method(result, "allCubeMembers", move |args| {
+        let cube = sch.find_cube(&arg::<String>(&args, 0)?)?;
+        let members = cube
+            .measures
+            .iter()
+            .map(|m| m.name.clone())
+            .chain(cube.dimensions.iter().map(|d| d.name.clone()))
+            .collect::<Vec<_>>();
+        to_native(&members)
+    });
+
+    NativeObjectHandle::new(result.into_object())
+}
+
+fn find_join_path(schema: &SchemaYaml, root: &str, target: &str) -> Option<Vec<(String, String)>> {
+    let mut parents: HashMap<String, String> = HashMap::new();
+    let mut visited = HashSet::from([root.to_string()]);
+    let mut queue = VecDeque::from([root.to_string()]);
+    while let Some(cube_name) = queue.pop_front() {
+        if cube_name == target {
+            let mut path = Vec::new();
+            let mut current = cube_name;
+            while let Some(parent) = parents.get(&current) {
+                path.push((parent.clone(), current.clone()));
+                current = parent.clone();
+            }
+            path.reverse();
+            return Some(path);
+        }
+        let cube = schema.find_cube(&cube_name).ok()?;
+        for join in cube.joins.iter() {
+            if visited.insert(join.name.clone()) {
+                parents.insert(join.name.clone(), cube_name.clone());
+                queue.push_back(join.name.clone());
+            }
+        }
+    }
+    None
+}
+
+fn build_join(schema: &SchemaYaml, cubes_to_join: Vec<String>) -> Result<Handle, CubeError> {
+    let mut cubes = Vec::new();
+    for cube in cubes_to_join {
+        if !cubes.contains(&cube) {
+            cubes.push(cube);
+        }
+    }
+    // As JS join graph does, try every cube as a root until all others are reachable
+    let (root, edges) = cubes
+        .iter()
+        .find_map(|root| {
+            let mut edges: Vec<(String, String)> = Vec::new();
+            for target in cubes.iter() {
+                for edge in find_join_path(schema, root, target)? {
+                    if !edges.contains(&edge) {
+                        edges.push(edge);
+                    }
+                }
+            }
+            Some((root.clone(), edges))
+        })
+        .ok_or_else(|| {
+            CubeError::user(format!("Can't find join path to join {}", cubes.join(", ")))
+        })?;
+
+    let mut multiplication_factor = HashMap::from([(root.clone(), false)]);
+    let joins = RustArray::new();
+    for (i, (from, to)) in edges.iter().enumerate() {
+        let join = schema
+            .find_cube(from)?
+            .joins
+            .iter()
+            .find(|j| &j.name == to)
+            .unwrap();
+        let relationship = join.normalized_relationship()?;
+        if relationship == "hasMany" {
+            multiplication_factor.insert(from.clone(), true);
+        }
+        let to_multiplied = relationship == "belongsTo";
+        let entry = multiplication_factor.entry(to.clone()).or_insert(false);
+        *entry = *entry || to_multiplied;
+
+        let definition = RustObject::from_json(&json!({ "relationship": relationship }))
+            .into_struct()?
+            .with_field("sql", member_sql_function(&join.sql).into_object());
+        let item = RustObject::from_json(&json!({
+            "from": from,
+            "to": to,
+            "originalFrom": from,
+            "originalTo": to,
+        }))
+        .into_struct()?
+        .with_field("join", definition.into_object());
+        joins.set(i as u32, NativeObjectHandle::new(item.into_object()))?;
+    }
+
+    let result = RustObject::from_json(&json!({
+        "root": root,
+        "multiplicationFactor": multiplication_factor,
+    }))
+    .into_struct()?
+    .with_field("joins", joins.into_object());
+    Ok(NativeObjectHandle::new(result.into_object()))
+}
+
+/// Counterpart of the JS `JoinGraph`. Joins are directed, from the cube
+/// which declares the join to the joined one.
+pub fn join_graph(schema: Rc<SchemaYaml>) -> Handle {
+    let result = with_method(RustStruct::new(), "buildJoin", move |args| {
+        build_join(&schema, arg::<Vec<String>>(&args, 0)?)
+    });
+    NativeObjectHandle::new(result.into_object())
+}
@@ -0,0 +1,114 @@
+use crate::cube_bridge::measure_definition::RollingWindow;
+use cubenativeutils::CubeError;
+use serde::{Deserialize, Serialize};
+
+/// Data model used by the test harness. Member `sql` uses the same reference
+/// syntax as the JS schema: `{CUBE}`, `{CUBE.member}`, `{member}` and `{cube.member}`.
+#[derive(Deserialize, Debug)]
+pub struct SchemaYaml {
+    pub cubes: Vec<CubeYaml>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct CubeYaml {
+    pub name: String,
+    pub sql_table: Option<String>,
+    pub sql: Option<String>,
+    #[serde(default)]
+    pub joins: Vec<JoinYaml>,
+    #[serde(default)]
+    pub dimensions: Vec<DimensionYaml>,
+    #[serde(default)]
+    pub measures: Vec<MeasureYaml>,
+    /// Pre-aggregations are passed to the planner as is, so they use the
+    /// camelCase keys of the compiled JS schema (`timeDimensions`, `sqlAlias`, ...)
+    #[serde(default)]
+    pub pre_aggregations: Vec<serde_json::Value>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct JoinYaml {
+    pub name: String,
+    pub relationship: String,
+    pub sql: String,
+}
+
+impl JoinYaml {
+    pub fn normalized_relationship(&self) -> Result<&'static str, CubeError> {
+        match self.relationship.as_str() {
+            "belongsTo" | "belongs_to" | "many_to_one" => Ok("belongsTo"),
+            "hasMany" | "has_many" | "one_to_many" => Ok("hasMany"),
+            "hasOne" | "has_one" | "one_to_one" => Ok("hasOne"),
+            other => Err(CubeError::user(format!(
+                "Unknown join relationship: {}",
+                other
+            ))),
+        }
+    }
+}
+
+#[derive(Deserialize, Debug)]
+pub struct DimensionYaml {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub dimension_type: String,
+    pub sql: String,
+    #[serde(default)]
+    pub primary_key: bool,
+    #[serde(default)]
+    pub granularities: Vec<GranularityYaml>,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct GranularityYaml {
+    #[serde(skip_serializing)]
+    pub name: String,
+    pub interval: String,
+    pub origin: Option<String>,
+    pub offset: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct MeasureYaml {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub measure_type: String,
+    pub sql: Option<String>,
+    #[serde(default)]
+    pub filters: Vec<String>,
+    #[serde(default)]
+    pub drill_filters: Vec<String>,
+    pub rolling_window: Option<RollingWindow>,
+}
+
+impl SchemaYaml {
+    pub fn from_yaml(yaml: &str) -> Result<Self, CubeError> {
+        serde_yaml::from_str(yaml)
+            .map_err(|e| CubeError::internal(format!("Failed to parse schema: {}", e)))
+    }
+
+    pub fn find_cube(&self, name: &str) -> Result<&CubeYaml, CubeError> {
+        self.cubes
+            .iter()
+            .find(|c| c.name == name)
+            .ok_or_else(|| CubeError::user(format!("Cube '{}' not found", name)))
+    }
+}
+
+impl CubeYaml {
+    pub fn find_measure(&self, name: &str) -> Option<&MeasureYaml> {
+        self.measures.iter().find(|m| m.name == name)
+    }
+
+    pub fn find_dimension(&self, name: &str) -> Option<&DimensionYaml> {
+        self.dimensions.iter().find(|d| d.name == name)
+    }
+
+    pub fn primary_keys(&self) -> Vec<String> {
+        self.dimensions
+            .iter()
+            .filter(|d| d.primary_key)
+            .map(|d| d.name.clone())
+            .collect()
+    }
+}
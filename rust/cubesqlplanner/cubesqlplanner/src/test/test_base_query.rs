@@ -0,0 +1,89 @@
+use super::dialect::DialectYaml;
+use super::TestContext;
+
+fn postgres_context() -> TestContext {
+    TestContext::new(TestContext::default_schema(), DialectYaml::postgres())
+}
+
+#[test]
+fn test_simple_measure_and_dimension() {
+    let (sql, params) = postgres_context()
+        .build_sql(
+            r#"
+measures: [orders.count]
+dimensions: [orders.status]
+"#,
+        )
+        .unwrap();
+
+    insta::assert_snapshot!("simple_measure_and_dimension", sql);
+    assert!(params.is_empty());
+}
+
+#[test]
+fn test_dimension_filter_postgres() {
+    let (sql, params) = postgres_context()
+        .build_sql(
+            r#"
+measures: [orders.count]
+dimensions: [orders.status]
+filters:
+  - member: orders.status
+    operator: equals
+    values: [completed]
+"#,
+        )
+        .unwrap();
+
+    insta::assert_snapshot!("dimension_filter_postgres", sql);
+    assert_eq!(params, vec!["completed".to_string()]);
+}
+
+#[test]
+fn test_dimension_filter_ansi() {
+    let (sql, params) = TestContext::new(TestContext::default_schema(), DialectYaml::ansi())
+        .build_sql(
+            r#"
+measures: [orders.count]
+dimensions: [orders.status]
+filters:
+  - member: orders.status
+    operator: equals
+    values: [completed]
+"#,
+        )
+        .unwrap();
+
+    insta::assert_snapshot!("dimension_filter_ansi", sql);
+    assert_eq!(params, vec!["completed".to_string()]);
+}
+
+#[test]
+fn test_time_dimension_with_date_range() {
+    let (sql, params) = postgres_context()
+        .build_sql(
+            r#"
+measures: [orders.count]
+timeDimensions:
+  - dimension: orders.created_at
+    granularity: month
+    dateRange: ["2024-01-01", "2024-03-31"]
+"#,
+        )
+        .unwrap();
+
+    insta::assert_snapshot!("time_dimension_with_date_range", sql);
+    assert_eq!(
+        params,
+        vec![
+            "2024-01-01T00:00:00.000Z".to_string(),
+            "2024-03-31T23:59:59.999Z".to_string()
+        ]
+    );
+}
+
+#[test]
+fn test_unknown_member() {
+    let result = postgres_context().build_sql("measures: [orders.unknown]");
+    assert!(result.is_err());
+}
@@ -0,0 +1,126 @@
+use super::dialect::DialectYaml;
+use super::TestContext;
+
+fn build(
+    dialect: DialectYaml,
+    query: &str,
+) -> Result<(String, Vec<String>), cubenativeutils::CubeError> {
+    TestContext::new(TestContext::default_schema(), dialect).build_sql(query)
+}
+
+#[test]
+fn test_before_and_after_date() {
+    let (sql, params) = build(
+        DialectYaml::postgres(),
+        r#"
+measures: [orders.count]
+filters:
+  - member: orders.created_at
+    operator: beforeDate
+    values: ["2024-01-01"]
+  - member: orders.created_at
+    operator: afterOrOnDate
+    values: ["2023-01-01"]
+"#,
+    )
+    .unwrap();
+
+    assert!(sql.contains("< $1::timestamptz"), "{}", sql);
+    assert!(sql.contains(">= $2::timestamptz"), "{}", sql);
+    assert_eq!(
+        params,
+        vec![
+            "2024-01-01T00:00:00.000Z".to_string(),
+            "2023-01-01T00:00:00.000Z".to_string()
+        ]
+    );
+}
+
+#[test]
+fn test_not_in_date_range() {
+    let (sql, params) = build(
+        DialectYaml::postgres(),
+        r#"
+measures: [orders.count]
+filters:
+  - member: orders.created_at
+    operator: notInDateRange
+    values: ["2024-01-01", "2024-01-31"]
+"#,
+    )
+    .unwrap();
+
+    assert!(sql.contains("< $1::timestamptz OR"), "{}", sql);
+    assert!(sql.contains("> $2::timestamptz"), "{}", sql);
+    assert_eq!(
+        params,
+        vec![
+            "2024-01-01T00:00:00.000Z".to_string(),
+            "2024-01-31T23:59:59.999Z".to_string()
+        ]
+    );
+}
+
+#[test]
+fn test_regex() {
+    let query = r#"
+measures: [orders.count]
+filters:
+  - member: orders.status
+    operator: notRegex
+    values: ["^compl", "ed$"]
+"#;
+    let (sql, params) = build(DialectYaml::postgres(), query).unwrap();
+
+    assert!(
+        sql.contains(r#"("orders".status !~ $1 AND "orders".status !~ $2)"#),
+        "{}",
+        sql
+    );
+    assert_eq!(params, vec!["^compl".to_string(), "ed$".to_string()]);
+
+    // ANSI dialect has no regex template
+    let err = build(DialectYaml::ansi(), query).unwrap_err();
+    assert_eq!(err.message, "regex filter is not supported by this dialect");
+}
+
+#[test]
+fn test_measure_filter() {
+    let (sql, _) = build(
+        DialectYaml::postgres(),
+        r#"
+measures: [orders.count]
+filters:
+  - member: orders.completed_count
+    operator: measureFilter
+"#,
+    )
+    .unwrap();
+
+    assert!(
+        sql.contains(r#"WHERE (("orders".status = 'completed'))"#),
+        "{}",
+        sql
+    );
+    assert!(!sql.contains("HAVING"), "{}", sql);
+}
+
+#[test]
+fn test_measure_filter_with_drill_filters() {
+    let (sql, _) = build(
+        DialectYaml::postgres(),
+        r#"
+measures: [orders.count]
+filters:
+  - member: orders.large_completed_count
+    operator: measureFilter
+"#,
+    )
+    .unwrap();
+
+    assert!(
+        sql.contains(r#"WHERE (("orders".status = 'completed') AND ("orders".amount > 100))"#),
+        "{}",
+        sql
+    );
+}
@@ -0,0 +1,136 @@
+use super::dialect::DialectYaml;
+use super::TestContext;
+use crate::planner::plan_description::{FilterDescription, JoinItemDescription};
+use std::collections::BTreeMap;
+
+fn postgres_context() -> TestContext {
+    TestContext::new(TestContext::default_schema(), DialectYaml::postgres())
+}
+
+#[test]
+fn test_simple_query_plan() {
+    let plan = postgres_context()
+        .build_plan_description(
+            r#"
+measures: [orders.count]
+dimensions: [orders.status]
+filters:
+  - member: orders.status
+    operator: equals
+    values: [completed]
+"#,
+        )
+        .unwrap();
+
+    assert_eq!(plan.planner, "simple");
+    assert!(plan.pre_aggregation.is_none());
+    assert!(plan.subqueries.is_empty());
+    assert!(plan.multi_stage.is_empty());
+
+    let join = plan.join.unwrap();
+    assert_eq!(join.root, "orders");
+    assert!(join.joins.is_empty());
+
+    assert_eq!(
+        plan.filters.dimensions,
+        vec![FilterDescription {
+            member: Some("orders.status".to_string()),
+            operator: "equals".to_string(),
+            values: vec![Some("completed".to_string())],
+            items: vec![],
+        }]
+    );
+    assert!(plan.filters.measures.is_empty());
+}
+
+#[test]
+fn test_multiplied_measures_plan() {
+    let plan = postgres_context()
+        .build_plan_description(
+            r#"
+measures: [users.count]
+dimensions: [orders.status]
+"#,
+        )
+        .unwrap();
+
+    assert_eq!(plan.planner, "full_key_aggregate");
+    assert!(plan.join.is_none());
+    assert!(plan.multi_stage.is_empty());
+    assert_eq!(plan.subqueries.len(), 1);
+
+    let subquery = &plan.subqueries[0];
+    assert_eq!(subquery.subquery_type, "aggregate");
+    assert_eq!(subquery.measures, vec!["users.count".to_string()]);
+    assert_eq!(subquery.key_cube, Some("users".to_string()));
+
+    let join = subquery.join.as_ref().unwrap();
+    assert_eq!(join.root, "orders");
+    assert_eq!(
+        join.joins,
+        vec![JoinItemDescription {
+            from: "orders".to_string(),
+            to: "users".to_string(),
+            relationship: "belongsTo".to_string(),
+        }]
+    );
+    assert_eq!(
+        join.multiplication_factor,
+        BTreeMap::from([("orders".to_string(), false), ("users".to_string(), true)])
+    );
+}
+
+#[test]
+fn test_rolling_window_plan() {
+    let plan = postgres_context()
+        .build_plan_description(
+            r#"
+measures: [orders.rolling_count]
+timeDimensions:
+  - dimension: orders.created_at
+    granularity: month
+    dateRange: ["2024-01-01", "2024-03-31"]
+"#,
+        )
+        .unwrap();
+
+    assert_eq!(plan.planner, "full_key_aggregate");
+
+    let ctes = plan
+        .multi_stage
+        .iter()
+        .map(|cte| {
+            (
+                cte.alias.as_str(),
+                cte.member.as_str(),
+                cte.cte_type.as_str(),
+                cte.input.clone(),
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        ctes,
+        vec![
+            ("time_series", "orders.created_at", "time_series", vec![]),
+            ("cte_1", "orders.rolling_count", "measure", vec![]),
+            (
+                "cte_2",
+                "orders.rolling_count",
+                "rolling_window",
+                vec!["time_series".to_string(), "cte_1".to_string()]
+            ),
+        ]
+    );
+    assert_eq!(
+        plan.multi_stage[1].time_dimensions,
+        vec!["orders.created_at.month".to_string()]
+    );
+
+    assert_eq!(plan.subqueries.len(), 1);
+    assert_eq!(plan.subqueries[0].subquery_type, "multi_stage");
+    assert_eq!(plan.subqueries[0].cte, Some("cte_2".to_string()));
+    assert_eq!(
+        plan.subqueries[0].measures,
+        vec!["orders.rolling_count".to_string()]
+    );
+}
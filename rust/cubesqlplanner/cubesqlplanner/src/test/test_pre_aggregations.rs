@@ -0,0 +1,197 @@
+use super::dialect::DialectYaml;
+use super::TestContext;
+use std::collections::BTreeMap;
+
+fn postgres_context() -> TestContext {
+    TestContext::new(TestContext::default_schema(), DialectYaml::postgres())
+}
+
+/// Pre-aggregation is selected by `findPreAggregationForQuery()` on the JS side
+fn with_pre_aggregation(query: &str, name: &str) -> String {
+    format!(
+        r#"{}
+preAggregationForQuery:
+  cubeName: orders
+  name: {}
+  tableName: prod_pre_aggregations.orders_{}
+"#,
+        query, name, name
+    )
+}
+
+#[test]
+fn test_rollup_match() {
+    let query = with_pre_aggregation(
+        r#"
+measures: [orders.count, orders.total_amount]
+dimensions: [orders.status]
+timeDimensions:
+  - dimension: orders.created_at
+    granularity: day
+"#,
+        "daily_by_status",
+    );
+    let context = postgres_context();
+
+    let plan = context.build_plan_description(&query).unwrap();
+    assert_eq!(plan.planner, "rollup");
+    let pre_aggregation = plan.pre_aggregation.unwrap();
+    assert_eq!(pre_aggregation.cube_name, "orders");
+    assert_eq!(pre_aggregation.name, "daily_by_status");
+    assert_eq!(
+        pre_aggregation.time_dimensions,
+        BTreeMap::from([("orders.created_at".to_string(), "day".to_string())])
+    );
+
+    let (sql, _) = context.build_sql(&query).unwrap();
+    assert!(sql.contains("prod_pre_aggregations.orders_daily_by_status"));
+    assert!(!sql.contains("public.orders"));
+}
+
+#[test]
+fn test_rollup_no_match() {
+    let context = postgres_context();
+
+    // Dimension isn't stored in the rollup, so the query is planned against the cube SQL
+    let query = with_pre_aggregation(
+        r#"
+measures: [orders.count]
+dimensions: [orders.id]
+"#,
+        "daily_by_status",
+    );
+    let plan = context.build_plan_description(&query).unwrap();
+    assert_eq!(plan.planner, "simple");
+    assert!(plan.pre_aggregation.is_none());
+    let (sql, _) = context.build_sql(&query).unwrap();
+    assert!(sql.contains("public.orders"));
+    assert!(!sql.contains("prod_pre_aggregations"));
+
+    // Filter on a member which isn't stored in the rollup
+    let query = with_pre_aggregation(
+        r#"
+measures: [orders.count]
+dimensions: [orders.status]
+filters:
+  - member: orders.id
+    operator: equals
+    values: ["1"]
+"#,
+        "daily_by_status",
+    );
+    let plan = context.build_plan_description(&query).unwrap();
+    assert!(plan.pre_aggregation.is_none());
+
+    // Rollup covers the query, but it isn't selected on the JS side
+    let plan = context
+        .build_plan_description(
+            r#"
+measures: [orders.count]
+dimensions: [orders.status]
+"#,
+        )
+        .unwrap();
+    assert!(plan.pre_aggregation.is_none());
+
+    // Query which builds the pre-aggregation never reads from it
+    let query = with_pre_aggregation(
+        r#"
+measures: [orders.count]
+dimensions: [orders.status]
+preAggregationQuery: true
+"#,
+        "daily_by_status",
+    );
+    let plan = context.build_plan_description(&query).unwrap();
+    assert!(plan.pre_aggregation.is_none());
+
+    // Only rollups are read by the planner
+    let query = with_pre_aggregation(
+        r#"
+measures: [orders.count]
+"#,
+        "source",
+    );
+    let plan = context.build_plan_description(&query).unwrap();
+    assert!(plan.pre_aggregation.is_none());
+}
+
+#[test]
+fn test_rollup_granularity() {
+    let context = postgres_context();
+
+    // Daily rollup is rolled up to months
+    let query = with_pre_aggregation(
+        r#"
+measures: [orders.count]
+timeDimensions:
+  - dimension: orders.created_at
+    granularity: month
+    dateRange: ["2024-01-01", "2024-03-31"]
+"#,
+        "daily_by_status",
+    );
+    let plan = context.build_plan_description(&query).unwrap();
+    assert_eq!(plan.planner, "rollup");
+    assert_eq!(
+        plan.pre_aggregation.unwrap().time_dimensions,
+        BTreeMap::from([("orders.created_at".to_string(), "day".to_string())])
+    );
+    let (sql, params) = context.build_sql(&query).unwrap();
+    assert!(sql.contains("prod_pre_aggregations.orders_daily_by_status"));
+    assert_eq!(params.len(), 2);
+
+    // Monthly rollup can't serve days
+    let query = with_pre_aggregation(
+        r#"
+measures: [orders.count]
+timeDimensions:
+  - dimension: orders.created_at
+    granularity: day
+"#,
+        "monthly",
+    );
+    let plan = context.build_plan_description(&query).unwrap();
+    assert!(plan.pre_aggregation.is_none());
+
+    // Date range which isn't aligned to months can't be served by the monthly rollup
+    let query = with_pre_aggregation(
+        r#"
+measures: [orders.count]
+timeDimensions:
+  - dimension: orders.created_at
+    granularity: year
+    dateRange: ["2024-01-15", "2024-12-31"]
+"#,
+        "monthly",
+    );
+    let plan = context.build_plan_description(&query).unwrap();
+    assert!(plan.pre_aggregation.is_none());
+}
+
+#[test]
+fn test_external_rollup() {
+    let context = postgres_context();
+
+    // External rollups are routed to the external query on the JS side,
+    // the planner reads from the table it's given
+    let query = with_pre_aggregation(
+        r#"
+measures: [orders.count]
+timeDimensions:
+  - dimension: orders.created_at
+    granularity: year
+"#,
+        "monthly",
+    );
+    let plan = context.build_plan_description(&query).unwrap();
+    assert_eq!(plan.planner, "rollup");
+    assert_eq!(
+        plan.pre_aggregation.unwrap().time_dimensions,
+        BTreeMap::from([("orders.created_at".to_string(), "month".to_string())])
+    );
+
+    let (sql, _) = context.build_sql(&query).unwrap();
+    assert!(sql.contains("prod_pre_aggregations.orders_monthly"));
+    assert!(!sql.contains("public.orders"));
+}
@@ -0,0 +1,268 @@
+use super::dialect::DialectYaml;
+use super::TestContext;
+use crate::planner::{Granularity, SqlInterval};
+use chrono::NaiveDateTime;
+use chrono_tz::Tz;
+use std::str::FromStr;
+
+fn postgres_context() -> TestContext {
+    TestContext::new(TestContext::default_schema(), DialectYaml::postgres())
+}
+
+fn range(from: &str, to: &str) -> Vec<String> {
+    vec![from.to_string(), to.to_string()]
+}
+
+fn series(items: &[(&str, &str)]) -> Vec<Vec<String>> {
+    items
+        .iter()
+        .map(|(from, to)| vec![from.to_string(), to.to_string()])
+        .collect()
+}
+
+fn half_year() -> Granularity {
+    Granularity::try_new_custom(
+        Tz::UTC,
+        "half_year".to_string(),
+        Some("2024-01-01T00:00:00".to_string()),
+        "6 months".to_string(),
+        None,
+    )
+    .unwrap()
+}
+
+#[test]
+fn test_sql_interval() {
+    let interval = SqlInterval::from_str("2 years 15 months -10 hours 1 second").unwrap();
+    assert_eq!(
+        interval,
+        SqlInterval {
+            year: 2,
+            month: 15,
+            hour: -10,
+            second: 1,
+            ..Default::default()
+        }
+    );
+    assert_eq!(interval.min_granularity(), "second");
+    assert_eq!(
+        SqlInterval::from_str("2 weeks 1 month")
+            .unwrap()
+            .min_granularity(),
+        "day"
+    );
+    assert!(SqlInterval::from_str("1 fortnight").is_err());
+
+    let date = |s: &str| NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S").unwrap();
+    let interval = SqlInterval::from_str("1 month 1 day").unwrap();
+    assert_eq!(
+        interval.add_to(&date("2024-01-31T00:00:00")).unwrap(),
+        date("2024-03-01T00:00:00")
+    );
+    assert_eq!(
+        interval
+            .subtract_from(&date("2024-03-31T00:00:00"))
+            .unwrap(),
+        date("2024-02-28T00:00:00")
+    );
+}
+
+#[test]
+fn test_predefined_time_series() {
+    let month = Granularity::try_new_predefined(Tz::UTC, "month".to_string()).unwrap();
+    assert_eq!(
+        month
+            .time_series(&range("2024-01-15", "2024-03-10"), 3)
+            .unwrap(),
+        series(&[
+            ("2024-01-01T00:00:00.000", "2024-01-31T23:59:59.999"),
+            ("2024-02-01T00:00:00.000", "2024-02-29T23:59:59.999"),
+            ("2024-03-01T00:00:00.000", "2024-03-31T23:59:59.999"),
+        ])
+    );
+
+    let week = Granularity::try_new_predefined(Tz::UTC, "week".to_string()).unwrap();
+    assert_eq!(
+        week.time_series(&range("2024-01-03", "2024-01-10"), 6)
+            .unwrap(),
+        series(&[
+            ("2024-01-01T00:00:00.000000", "2024-01-07T23:59:59.999999"),
+            ("2024-01-08T00:00:00.000000", "2024-01-14T23:59:59.999999"),
+        ])
+    );
+
+    let second = Granularity::try_new_predefined(Tz::UTC, "second".to_string()).unwrap();
+    assert!(second
+        .time_series(&range("2022-01-01", "2024-01-01"), 3)
+        .is_err());
+}
+
+#[test]
+fn test_custom_time_series() {
+    assert_eq!(
+        half_year()
+            .time_series(&range("2024-03-01", "2024-12-31"), 3)
+            .unwrap(),
+        series(&[
+            ("2024-01-01T00:00:00.000", "2024-06-30T23:59:59.999"),
+            ("2024-07-01T00:00:00.000", "2024-12-31T23:59:59.999"),
+        ])
+    );
+    // Date range before the origin
+    assert_eq!(
+        half_year()
+            .time_series(&range("2023-05-01", "2023-08-01"), 3)
+            .unwrap(),
+        series(&[
+            ("2023-01-01T00:00:00.000", "2023-06-30T23:59:59.999"),
+            ("2023-07-01T00:00:00.000", "2023-12-31T23:59:59.999"),
+        ])
+    );
+}
+
+#[test]
+fn test_custom_granularity_properties() {
+    let half_year = half_year();
+    assert!(!half_year.is_natural_aligned());
+    assert_eq!(half_year.resolved_granularity().unwrap(), "month");
+    assert_eq!(half_year.min_granularity().unwrap(), "month");
+    assert_eq!(
+        half_year.origin_local_formatted(),
+        "2024-01-01T00:00:00.000"
+    );
+
+    let fiscal_year = Granularity::try_new_custom(
+        Tz::UTC,
+        "fiscal_year".to_string(),
+        None,
+        "1 year".to_string(),
+        Some("3 months".to_string()),
+    )
+    .unwrap();
+    assert!(fiscal_year.is_natural_aligned());
+    assert_eq!(fiscal_year.resolved_granularity().unwrap(), "year");
+    assert_eq!(fiscal_year.min_granularity().unwrap(), "month");
+
+    assert!(Granularity::try_new_custom(
+        Tz::UTC,
+        "backwards".to_string(),
+        None,
+        "-1 month".to_string(),
+        None,
+    )
+    .is_err());
+}
+
+#[test]
+fn test_custom_granularity_with_offset() {
+    let (sql, _) = postgres_context()
+        .build_sql(
+            r#"
+measures: [orders.count]
+timeDimensions:
+  - dimension: orders.created_at
+    granularity: fiscal_year
+    dateRange: ["2024-01-01", "2024-12-31"]
+"#,
+        )
+        .unwrap();
+
+    assert!(
+        sql.contains("(date_trunc('year', (\"orders\".created_at::timestamptz AT TIME ZONE 'UTC') - interval '3 months') + interval '3 months') \"orders__created_at_fiscal_year\""),
+        "{}",
+        sql
+    );
+}
+
+#[test]
+fn test_custom_granularity_with_origin() {
+    let (sql, _) = postgres_context()
+        .build_sql(
+            r#"
+measures: [orders.count]
+timeDimensions:
+  - dimension: orders.created_at
+    granularity: half_year
+"#,
+        )
+        .unwrap();
+
+    assert!(
+        sql.contains("('2024-01-01T00:00:00.000'::timestamp + INTERVAL '6 months' * FLOOR("),
+        "{}",
+        sql
+    );
+    assert!(sql.contains("\"orders__created_at_half_year\""), "{}", sql);
+}
+
+#[test]
+fn test_custom_granularity_min_granularity() {
+    let half_year_from_15th = Granularity::try_new_custom(
+        Tz::UTC,
+        "half_year_from_15th".to_string(),
+        Some("2024-01-15T00:00:00".to_string()),
+        "6 months".to_string(),
+        None,
+    )
+    .unwrap();
+    assert_eq!(half_year_from_15th.resolved_granularity().unwrap(), "month");
+    // Periods start in the middle of the month, they can be calculated only from days
+    assert_eq!(half_year_from_15th.min_granularity().unwrap(), "day");
+    assert_eq!(
+        half_year_from_15th
+            .time_series(&range("2024-02-01", "2024-12-31"), 3)
+            .unwrap(),
+        series(&[
+            ("2024-01-15T00:00:00.000", "2024-07-14T23:59:59.999"),
+            ("2024-07-15T00:00:00.000", "2025-01-14T23:59:59.999"),
+        ])
+    );
+}
+
+#[test]
+fn test_rolling_window_with_custom_granularity() {
+    let (sql, _) = postgres_context()
+        .build_sql(
+            r#"
+measures: [orders.rolling_count]
+timeDimensions:
+  - dimension: orders.created_at
+    granularity: half_year_from_15th
+    dateRange: ["2024-02-01", "2024-12-31"]
+"#,
+        )
+        .unwrap();
+
+    // Gaps are filled by the time series CTE of the custom granularity
+    assert!(
+        sql.contains("VALUES ('2024-01-15T00:00:00.000', '2024-07-14T23:59:59.999'), ('2024-07-15T00:00:00.000', '2025-01-14T23:59:59.999')) AS dates (date_from, date_to)"),
+        "{}",
+        sql
+    );
+    // Rolling window is calculated over days, months would be cut at the origin
+    assert!(
+        sql.contains("date_trunc('day', (\"orders\".created_at::timestamptz AT TIME ZONE 'UTC'))"),
+        "{}",
+        sql
+    );
+    assert!(
+        !sql.contains(
+            "date_trunc('month', (\"orders\".created_at::timestamptz AT TIME ZONE 'UTC'))"
+        ),
+        "{}",
+        sql
+    );
+}
+
+#[test]
+fn test_unknown_granularity() {
+    let result = postgres_context().build_sql(
+        r#"
+measures: [orders.count]
+timeDimensions:
+  - dimension: orders.created_at
+    granularity: fortnight
+"#,
+    );
+    assert!(result.is_err());
+}
@@ -243,6 +243,7 @@ pub fn sql_tests() -> Vec<(&'static str, TestFn)> {
             aggregate_index_with_hll_bytes,
         ),
         t("aggregate_index_errors", aggregate_index_errors),
+        t("aggregate_index_rollup", aggregate_index_rollup),
         t("inline_tables", inline_tables),
         t("inline_tables_2x", inline_tables_2x),
         t("build_range_end", build_range_end),
@@ -252,6 +253,7 @@ pub fn sql_tests() -> Vec<(&'static str, TestFn)> {
         t("cache_compaction", cache_compaction),
         t("cache_set_nx", cache_set_nx),
         t("cache_prefix_keys", cache_prefix_keys),
+        t("cache_cas_mset_mget_lock", cache_cas_mset_mget_lock),
         t("queue_list_v1", queue_list_v1),
         t("queue_full_workflow_v1", queue_full_workflow_v1),
         t("queue_full_workflow_v2", queue_full_workflow_v2),
@@ -269,6 +271,7 @@ pub fn sql_tests() -> Vec<(&'static str, TestFn)> {
             queue_multiple_result_blocking,
         ),
         t("queue_custom_orphaned", queue_custom_orphaned),
+        t("queue_delay_and_retries", queue_delay_and_retries),
         t("limit_pushdown_group", limit_pushdown_group),
         t("limit_pushdown_group_order", limit_pushdown_group_order),
         t(
@@ -6509,6 +6512,105 @@ async fn aggregate_index(service: Box<dyn SqlClient>) {
     );
 }
 
+async fn aggregate_index_rollup(service: Box<dyn SqlClient>) {
+    service.exec_query("CREATE SCHEMA s").await.unwrap();
+    service
+        .exec_query(
+            "CREATE TABLE s.Orders(t timestamp, a int, b int, a_sum int, a_max int)
+                     AGGREGATIONS(sum(a_sum), max(a_max))
+                     AGGREGATE INDEX aggr_index (a, b, t)
+                     ",
+        )
+        .await
+        .unwrap();
+    service
+        .exec_query(
+            "INSERT INTO s.Orders (t, a, b, a_sum, a_max) VALUES \
+             ('2020-01-01T00:00:00.000Z', 1, 10, 10, 10), \
+             ('2020-01-01T10:00:00.000Z', 1, 20, 20, 20), \
+             ('2020-01-02T00:00:00.000Z', 1, 10, 30, 30), \
+             ('2020-01-02T10:00:00.000Z', 2, 10, 40, 40)",
+        )
+        .await
+        .unwrap();
+
+    let mut show_match = PPOptions::default();
+    show_match.show_aggregate_index_match = true;
+
+    let query =
+        "SELECT date_trunc('day', t) d, sum(a_sum), max(a_max) FROM s.Orders GROUP BY 1 ORDER BY 1";
+    let p = service.plan_query(query).await.unwrap();
+    let worker_plan = pp_phys_plan_ext(p.worker.as_ref(), &show_match);
+    assert!(
+        worker_plan
+            .contains("rollup[group_by: date_trunc(day, t); measures: SUM(a_sum), MAX(a_max)]"),
+        "{}",
+        worker_plan
+    );
+    let res = service.exec_query(query).await.unwrap();
+    assert_eq!(
+        to_rows(&res),
+        vec![
+            vec![
+                TableValue::Timestamp(timestamp_from_string("2020-01-01T00:00:00.000Z").unwrap()),
+                TableValue::Int(30),
+                TableValue::Int(20),
+            ],
+            vec![
+                TableValue::Timestamp(timestamp_from_string("2020-01-02T00:00:00.000Z").unwrap()),
+                TableValue::Int(70),
+                TableValue::Int(40),
+            ],
+        ]
+    );
+
+    // Coarser group by with a filter on another dimension.
+    let query = "SELECT a, sum(a_sum) FROM s.Orders WHERE b = 10 GROUP BY 1 ORDER BY 1";
+    let p = service.plan_query(query).await.unwrap();
+    let worker_plan = pp_phys_plan_ext(p.worker.as_ref(), &show_match);
+    assert!(
+        worker_plan.contains("rollup[group_by: a; measures: SUM(a_sum)]"),
+        "{}",
+        worker_plan
+    );
+    let res = service.exec_query(query).await.unwrap();
+    assert_eq!(to_rows(&res), rows(&[(1, 40), (2, 40)]));
+
+    // Pre-aggregated columns can't be grouped or filtered by, and non-additive aggregates can't be
+    // computed from the aggregate index.
+    for query in [
+        "SELECT a, a_sum FROM s.Orders GROUP BY 1, 2",
+        "SELECT a, sum(a_sum) FROM s.Orders WHERE a_max > 10 GROUP BY 1",
+        "SELECT date_trunc('day', t), avg(a_sum) FROM s.Orders GROUP BY 1",
+        "SELECT date_trunc('day', t), count(*) FROM s.Orders GROUP BY 1",
+        "SELECT a, b, t, a_sum FROM s.Orders",
+    ] {
+        let p = service.plan_query(query).await.unwrap();
+        let worker_plan = pp_phys_plan(p.worker.as_ref());
+        assert!(
+            !worker_plan.contains("aggr_index"),
+            "{}: {}",
+            query,
+            worker_plan
+        );
+    }
+
+    let res = service
+        .exec_query(
+            "EXPLAIN SELECT a, date_trunc('month', t), sum(a_sum) FROM s.Orders GROUP BY 1, 2",
+        )
+        .await
+        .unwrap();
+    match &res.get_rows()[0].values()[0] {
+        TableValue::String(pp_plan) => assert!(
+            pp_plan.contains("rollup[group_by: a, date_trunc(month, t); measures: SUM(a_sum)]"),
+            "{}",
+            pp_plan
+        ),
+        v => panic!("unexpected explain output: {:?}", v),
+    }
+}
+
 async fn aggregate_index_with_hll_bytes(service: Box<dyn SqlClient>) {
     service.exec_query("CREATE SCHEMA s").await.unwrap();
     service
@@ -7236,6 +7338,137 @@ async fn cache_prefix_keys(service: Box<dyn SqlClient>) {
     );
 }
 
+async fn cache_cas_mset_mget_lock(service: Box<dyn SqlClient>) {
+    service
+        .exec_query("CACHE MSET 'batch:key1' '1' 'batch:key2' '2';")
+        .await
+        .unwrap();
+
+    let result = service
+        .exec_query("CACHE MGET 'batch:key1' 'batch:key2' 'batch:key3';")
+        .await
+        .unwrap();
+    assert_eq!(
+        result.get_columns(),
+        &vec![
+            Column::new("key".to_string(), ColumnType::String, 0),
+            Column::new("value".to_string(), ColumnType::String, 1),
+        ]
+    );
+    assert_eq!(
+        result.get_rows(),
+        &vec![
+            Row::new(vec![
+                TableValue::String("batch:key1".to_string()),
+                TableValue::String("1".to_string())
+            ]),
+            Row::new(vec![
+                TableValue::String("batch:key2".to_string()),
+                TableValue::String("2".to_string())
+            ]),
+            Row::new(vec![
+                TableValue::String("batch:key3".to_string()),
+                TableValue::Null
+            ]),
+        ]
+    );
+
+    let result = service
+        .exec_query("CACHE CAS 'batch:key1' '2' '3';")
+        .await
+        .unwrap();
+    assert_eq!(
+        result.get_rows(),
+        &vec![Row::new(vec![TableValue::Boolean(false)])]
+    );
+
+    let result = service
+        .exec_query("CACHE CAS 'batch:key1' '1' '3' TTL 60;")
+        .await
+        .unwrap();
+    assert_eq!(
+        result.get_rows(),
+        &vec![Row::new(vec![TableValue::Boolean(true)])]
+    );
+
+    let result = service.exec_query("CACHE GET 'batch:key1';").await.unwrap();
+    assert_eq!(
+        result.get_rows(),
+        &vec![Row::new(vec![TableValue::String("3".to_string())])]
+    );
+
+    let result = service
+        .exec_query("CACHE LOCK 'locks:refresh' TTL 2;")
+        .await
+        .unwrap();
+    assert_eq!(
+        result.get_columns(),
+        &vec![Column::new("token".to_string(), ColumnType::String, 0)]
+    );
+    let first_token = match &result.get_rows()[0].values()[0] {
+        TableValue::String(token) => token.parse::<u64>().unwrap(),
+        other => panic!("token expected, actual: {:?}", other),
+    };
+
+    // lock is held
+    let result = service
+        .exec_query("CACHE LOCK 'locks:refresh' 2;")
+        .await
+        .unwrap();
+    assert_eq!(result.get_rows(), &vec![Row::new(vec![TableValue::Null])]);
+
+    tokio::time::sleep(Duration::new(3, 0)).await;
+
+    // lock was expired, fencing token must grow
+    let result = service
+        .exec_query("CACHE LOCK 'locks:refresh' 2;")
+        .await
+        .unwrap();
+    let second_token = match &result.get_rows()[0].values()[0] {
+        TableValue::String(token) => token.parse::<u64>().unwrap(),
+        other => panic!("token expected, actual: {:?}", other),
+    };
+    assert!(second_token > first_token);
+
+    // the expired token can't release the lock of the new holder
+    let result = service
+        .exec_query(&format!("CACHE UNLOCK 'locks:refresh' '{}';", first_token))
+        .await
+        .unwrap();
+    assert_eq!(
+        result.get_rows(),
+        &vec![Row::new(vec![TableValue::Boolean(false)])]
+    );
+
+    let result = service
+        .exec_query(&format!("CACHE UNLOCK 'locks:refresh' '{}';", second_token))
+        .await
+        .unwrap();
+    assert_eq!(
+        result.get_rows(),
+        &vec![Row::new(vec![TableValue::Boolean(true)])]
+    );
+
+    // CAS with NULL creates a missing key
+    let result = service
+        .exec_query("CACHE CAS 'batch:key4' NULL '1';")
+        .await
+        .unwrap();
+    assert_eq!(
+        result.get_rows(),
+        &vec![Row::new(vec![TableValue::Boolean(true)])]
+    );
+
+    let result = service
+        .exec_query("CACHE CAS 'batch:key4' NULL '2';")
+        .await
+        .unwrap();
+    assert_eq!(
+        result.get_rows(),
+        &vec![Row::new(vec![TableValue::Boolean(false)])]
+    );
+}
+
 async fn limit_pushdown_group(service: Box<dyn SqlClient>) {
     service.exec_query("CREATE SCHEMA foo").await.unwrap();
     service
@@ -9973,6 +10206,134 @@ async fn queue_custom_orphaned(service: Box<dyn SqlClient>) {
     );
 }
 
+async fn queue_delay_and_retries(service: Box<dyn SqlClient>) {
+    service
+        .exec_query(r#"QUEUE ADD PRIORITY 1 DELAY 60 "STANDALONE#queue:delayed" "payload1";"#)
+        .await
+        .unwrap();
+
+    service
+        .exec_query(r#"QUEUE ADD PRIORITY 1 MAX_ATTEMPTS 2 "STANDALONE#queue:retried" "payload2";"#)
+        .await
+        .unwrap();
+
+    {
+        // delayed item cannot be retrieved before its time
+        let retrieve_response = service
+            .exec_query(r#"QUEUE RETRIEVE CONCURRENCY 2 "STANDALONE#queue:delayed""#)
+            .await
+            .unwrap();
+        assert_queue_retrieve_columns(&retrieve_response);
+        assert_eq!(
+            retrieve_response.get_rows()[0].values()[0],
+            TableValue::Null
+        );
+
+        let pending_response = service
+            .exec_query(r#"QUEUE PENDING "STANDALONE#queue""#)
+            .await
+            .unwrap();
+        assert_eq!(pending_response.len(), 1);
+    }
+
+    {
+        let retrieve_response = service
+            .exec_query(r#"QUEUE RETRIEVE CONCURRENCY 2 "STANDALONE#queue:retried""#)
+            .await
+            .unwrap();
+        assert_eq!(
+            retrieve_response.get_rows()[0].values()[0],
+            TableValue::String("payload2".to_string())
+        );
+
+        let nack_response = service
+            .exec_query(r#"QUEUE NACK "STANDALONE#queue:retried""#)
+            .await
+            .unwrap();
+        assert_eq!(
+            nack_response.get_rows(),
+            &vec![Row::new(vec![TableValue::Boolean(true)])]
+        );
+
+        // item is re-queued with a backoff
+        let retrieve_response = service
+            .exec_query(r#"QUEUE RETRIEVE CONCURRENCY 2 "STANDALONE#queue:retried""#)
+            .await
+            .unwrap();
+        assert_eq!(
+            retrieve_response.get_rows()[0].values()[0],
+            TableValue::Null
+        );
+    }
+
+    tokio::time::sleep(Duration::new(2, 0)).await;
+
+    {
+        let retrieve_response = service
+            .exec_query(r#"QUEUE RETRIEVE CONCURRENCY 2 "STANDALONE#queue:retried""#)
+            .await
+            .unwrap();
+        assert_eq!(
+            retrieve_response.get_rows()[0].values()[0],
+            TableValue::String("payload2".to_string())
+        );
+
+        // the last attempt, item goes to the dead-letter prefix
+        let nack_response = service
+            .exec_query(r#"QUEUE NACK "STANDALONE#queue:retried""#)
+            .await
+            .unwrap();
+        assert_eq!(
+            nack_response.get_rows(),
+            &vec![Row::new(vec![TableValue::Boolean(true)])]
+        );
+
+        let nack_response = service
+            .exec_query(r#"QUEUE NACK "STANDALONE#queue:retried""#)
+            .await
+            .unwrap();
+        assert_eq!(
+            nack_response.get_rows(),
+            &vec![Row::new(vec![TableValue::Boolean(false)])]
+        );
+    }
+
+    let failed_response = service
+        .exec_query(r#"QUEUE FAILED "STANDALONE#queue""#)
+        .await
+        .unwrap();
+    assert_eq!(failed_response.len(), 1);
+    assert_eq!(
+        failed_response.get_rows()[0].values()[0],
+        TableValue::String("retried".to_string())
+    );
+    assert_eq!(
+        failed_response.get_rows()[0].values()[2],
+        TableValue::String("failed".to_string())
+    );
+
+    let res = service
+        .exec_query(
+            r#"SELECT attempts, max_attempts FROM system.queue WHERE prefix = 'dead_letter:STANDALONE#queue'"#,
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        res.get_rows(),
+        &vec![Row::new(vec![TableValue::Int(2), TableValue::Int(2)])]
+    );
+
+    // key is free again after moving to the dead-letter prefix
+    let add_response = service
+        .exec_query(r#"QUEUE ADD "STANDALONE#queue:retried" "payload3";"#)
+        .await
+        .unwrap();
+    assert_eq!(
+        add_response.get_rows()[0].values()[1],
+        TableValue::Boolean(true)
+    );
+}
+
 async fn sys_cachestore_info(service: Box<dyn SqlClient>) {
     service.exec_query("SYS CACHESTORE INFO").await.unwrap();
 }
@@ -1,7 +1,7 @@
 use crate::cachestore::cache_item::{
     CacheItemRocksIndex, CacheItemRocksTable, CACHE_ITEM_SIZE_WITHOUT_VALUE,
 };
-use crate::cachestore::CacheItem;
+use crate::cachestore::{CacheItem, CacheKeyEventPublisher, CacheKeyEventType};
 use crate::config::ConfigObj;
 use crate::metastore::{
     BaseRocksSecondaryIndex, IdRow, PackedDateTime, RocksSecondaryIndexValueTTLExtended,
@@ -152,6 +152,7 @@ pub struct CacheEvictionManager {
     // this help to delete upcoming keys for deleting
     eviction_min_ttl_threshold: u32,
     compaction_trigger_size: u64,
+    key_events: CacheKeyEventPublisher,
     // background listener to track events
     _ttl_tl_loop_join_handle: Arc<AbortingJoinHandle<()>>,
 }
@@ -213,7 +214,7 @@ impl CacheEvictionManager {
         self.stats_total_raw_size.load(Ordering::SeqCst)
     }
 
-    pub fn new(config: &Arc<dyn ConfigObj>) -> Self {
+    pub fn new(config: &Arc<dyn ConfigObj>, key_events: CacheKeyEventPublisher) -> Self {
         let ttl_buffer: HashMap<u64, CachePolicyData> = HashMap::new();
         let ttl_buffer = Arc::new(tokio::sync::RwLock::new(ttl_buffer));
         let (ttl_event_tx, mut ttl_event_rx) =
@@ -310,6 +311,7 @@ impl CacheEvictionManager {
             eviction_below_threshold: config.cachestore_cache_eviction_below_threshold(),
             eviction_min_ttl_threshold: config.cachestore_cache_eviction_min_ttl_threshold(),
             compaction_trigger_size: config.cachestore_cache_compaction_trigger_size(),
+            key_events,
             //
             _ttl_tl_loop_join_handle: Arc::new(AbortingJoinHandle::new(join_handle)),
         }
@@ -343,7 +345,9 @@ impl CacheEvictionManager {
                     let current_batch =
                         std::mem::replace(&mut batch, Vec::with_capacity(self.eviction_batch_size));
 
-                    let batch_result = self.delete_batch(current_batch, &store).await?;
+                    let batch_result = self
+                        .delete_batch(current_batch, &store, keys_are_expired)
+                        .await?;
 
                     total_size_removed += batch_result.deleted_size;
                     total_keys_removed += batch_result.deleted_count;
@@ -357,7 +361,9 @@ impl CacheEvictionManager {
         };
 
         if last_batch.len() > 0 {
-            let batch_result = self.delete_batch(last_batch, &store).await?;
+            let batch_result = self
+                .delete_batch(last_batch, &store, keys_are_expired)
+                .await?;
 
             total_size_removed += batch_result.deleted_size;
             total_keys_removed += batch_result.deleted_count;
@@ -385,8 +391,16 @@ impl CacheEvictionManager {
         &self,
         batch: Vec<(u64, u32)>,
         store: &Arc<RocksStore>,
+        keys_are_expired: bool,
     ) -> Result<DeleteBatchResult, CubeError> {
-        let (deleted_count, deleted_size, skipped) = store
+        let event_type = if keys_are_expired {
+            CacheKeyEventType::Expire
+        } else {
+            CacheKeyEventType::Evict
+        };
+
+        let mut key_events = self.key_events.batch();
+        let (deleted_count, deleted_size, skipped, key_events) = store
             .write_operation(move |db_ref, pipe| {
                 let cache_schema = CacheItemRocksTable::new(db_ref.clone());
 
@@ -395,7 +409,9 @@ impl CacheEvictionManager {
                 let mut skipped: u32 = 0;
 
                 for (id, raw_size) in batch {
-                    if let Some(_) = cache_schema.try_delete(id, pipe)? {
+                    if let Some(row) = cache_schema.try_delete(id, pipe)? {
+                        key_events.add(row.get_row().get_path(), event_type.clone());
+
                         deleted_count += 1;
                         deleted_size += raw_size as u64;
                     } else {
@@ -403,10 +419,12 @@ impl CacheEvictionManager {
                     };
                 }
 
-                Ok((deleted_count, deleted_size, skipped))
+                Ok((deleted_count, deleted_size, skipped, key_events))
             })
             .await?;
 
+        self.key_events.publish(key_events);
+
         self.stats_total_keys
             .fetch_sub(deleted_count, Ordering::Relaxed);
         self.stats_total_raw_size
@@ -83,6 +83,42 @@ impl CacheItem {
     }
 }
 
+#[derive(Clone, Serialize, Deserialize, Debug, Eq, PartialEq, Hash)]
+pub enum CacheKeyEventType {
+    Set,
+    Delete,
+    Expire,
+    Evict,
+    /// All keys were removed, it's sent with `*` as a path
+    Truncate,
+    QueueResult,
+}
+
+impl ToString for CacheKeyEventType {
+    fn to_string(&self) -> String {
+        match self {
+            CacheKeyEventType::Set => "set".to_string(),
+            CacheKeyEventType::Delete => "delete".to_string(),
+            CacheKeyEventType::Expire => "expire".to_string(),
+            CacheKeyEventType::Evict => "evict".to_string(),
+            CacheKeyEventType::Truncate => "truncate".to_string(),
+            CacheKeyEventType::QueueResult => "queue_result".to_string(),
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, Eq, PartialEq, Hash)]
+pub struct CacheKeyEvent {
+    pub path: String,
+    pub event_type: CacheKeyEventType,
+}
+
+impl CacheKeyEvent {
+    pub fn new(path: String, event_type: CacheKeyEventType) -> Self {
+        Self { path, event_type }
+    }
+}
+
 #[derive(Clone, Copy, Debug)]
 pub(crate) enum CacheItemRocksIndex {
     ByPath = 1,
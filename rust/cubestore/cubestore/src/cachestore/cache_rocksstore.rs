@@ -1,13 +1,13 @@
 use crate::cachestore::cache_item::{
-    CacheItem, CacheItemIndexKey, CacheItemRocksIndex, CacheItemRocksTable,
-    CACHE_ITEM_SIZE_WITHOUT_VALUE,
+    CacheItem, CacheItemIndexKey, CacheItemRocksIndex, CacheItemRocksTable, CacheKeyEvent,
+    CacheKeyEventType, CACHE_ITEM_SIZE_WITHOUT_VALUE,
 };
 use crate::cachestore::queue_item::{
     QueueItem, QueueItemIndexKey, QueueItemRocksIndex, QueueItemRocksTable, QueueItemStatus,
     QueueResultAckEvent, QueueResultAckEventResult, QueueRetrieveResponse,
 };
 use crate::cachestore::queue_result::{QueueResultRocksIndex, QueueResultRocksTable};
-use crate::cachestore::{compaction, QueueItemPayload, QueueResult};
+use crate::cachestore::{compaction, CacheKeyEventPublisher, QueueItemPayload, QueueResult};
 use crate::config::injection::DIService;
 use crate::config::{Config, ConfigObj};
 use std::collections::HashMap;
@@ -182,6 +182,7 @@ impl RocksStoreDetails for RocksCacheStoreDetails {
 pub struct RocksCacheStore {
     store: Arc<RocksStore>,
     cache_eviction_manager: CacheEvictionManager,
+    key_events: CacheKeyEventPublisher,
     upload_loop: Arc<WorkerLoop>,
     metrics_loop: Arc<WorkerLoop>,
 }
@@ -215,11 +216,13 @@ impl RocksCacheStore {
     }
 
     fn new_from_store(store: Arc<RocksStore>) -> Result<Arc<Self>, CubeError> {
-        let cache_eviction_manager = CacheEvictionManager::new(&store.config);
+        let key_events = CacheKeyEventPublisher::new();
+        let cache_eviction_manager = CacheEvictionManager::new(&store.config, key_events.clone());
 
         Ok(Arc::new(Self {
             store,
             cache_eviction_manager,
+            key_events,
             upload_loop: Arc::new(WorkerLoop::new("Cachestore upload")),
             metrics_loop: Arc::new(WorkerLoop::new("Cachestore metrics")),
         }))
@@ -389,6 +392,10 @@ impl RocksCacheStore {
         self.store.add_listener(listener).await;
     }
 
+    pub fn set_key_event_sender(&self, sender: Sender<CacheKeyEvent>) {
+        self.key_events.set_sender(sender);
+    }
+
     pub fn prepare_test_cachestore(
         test_name: &str,
         config: Config,
@@ -484,6 +491,19 @@ impl RocksCacheStore {
 }
 
 impl RocksCacheStore {
+    fn check_cache_item_size(&self, item: &CacheItem) -> Result<(), CubeError> {
+        if item.get_value().len() >= self.store.config.cachestore_cache_max_entry_size() {
+            return Err(CubeError::user(format!(
+                "Unable to SET cache with '{}' key, exceeds maximum allowed size for payload: {}, max allowed: {}",
+                item.key,
+                humansize::format_size(item.get_value().len(), humansize::DECIMAL),
+                humansize::format_size(self.store.config.cachestore_cache_max_entry_size(), humansize::DECIMAL),
+            )));
+        }
+
+        Ok(())
+    }
+
     async fn queue_result_delete_by_id(&self, id: u64) -> Result<(), CubeError> {
         self.store
             .write_operation(move |db_ref, batch_pipe| {
@@ -567,6 +587,44 @@ impl RocksCacheStore {
             .await
     }
 
+    fn queue_retry_or_fail_impl(
+        queue_schema: &QueueItemRocksTable,
+        queue_payload_schema: &QueueItemPayloadRocksTable,
+        batch_pipe: &mut BatchPipe,
+        item_row: IdRow<QueueItem>,
+        now: DateTime<Utc>,
+        backoff_base: u64,
+    ) -> Result<IdRow<QueueItem>, CubeError> {
+        let new = item_row.get_row().retry_or_fail(now, backoff_base);
+        if new.get_status() == &QueueItemStatus::Failed {
+            // Only the latest failure is kept in the dead-letter prefix
+            let index_key = QueueItemIndexKey::ByPath(new.get_path());
+            if let Some(prev) = queue_schema
+                .get_single_opt_row_by_index(&index_key, &QueueItemRocksIndex::ByPath)?
+            {
+                let prev_id = prev.get_id();
+                queue_schema.delete_row(prev, batch_pipe)?;
+                queue_payload_schema.try_delete(prev_id, batch_pipe)?;
+            }
+        }
+
+        if let Some(payload_row) = queue_payload_schema.get_row(item_row.get_id())? {
+            let payload = payload_row.get_row();
+            queue_payload_schema.update(
+                payload_row.get_id(),
+                QueueItemPayload::new(
+                    payload.get_value().clone(),
+                    payload.get_created().clone(),
+                    new.get_expire().clone(),
+                ),
+                payload,
+                batch_pipe,
+            )?;
+        }
+
+        queue_schema.update(item_row.get_id(), new, item_row.get_row(), batch_pipe)
+    }
+
     fn filter_to_cancel(
         now: DateTime<Utc>,
         items: Vec<IdRow<QueueItem>>,
@@ -582,7 +640,11 @@ impl RocksCacheStore {
                             return if orphaned < &now { true } else { false };
                         }
 
-                        let elapsed = now - item.get_row().get_created().clone();
+                        let scheduled = item
+                            .get_row()
+                            .get_not_before()
+                            .unwrap_or(item.get_row().get_created().clone());
+                        let elapsed = now - scheduled;
                         if elapsed.num_milliseconds() > orphaned_timeout as i64 {
                             true
                         } else {
@@ -645,6 +707,8 @@ pub struct QueueAddPayload {
     pub value: String,
     pub priority: i64,
     pub orphaned: Option<u32>,
+    pub delay: Option<u32>,
+    pub max_attempts: Option<u32>,
 }
 
 #[derive(Clone, Serialize, Deserialize, Debug, Eq, PartialEq)]
@@ -761,9 +825,24 @@ pub trait CacheStore: DIService + Send + Sync {
     ) -> Result<bool, CubeError>;
     async fn cache_truncate(&self) -> Result<(), CubeError>;
     async fn cache_delete(&self, key: String) -> Result<(), CubeError>;
+    /// Deletes all existing keys in one write, returns how many of them were deleted
+    async fn cache_mdelete(&self, keys: Vec<String>) -> Result<u64, CubeError>;
+    /// Sets a new TTL for an existing key, returns false when the key doesn't exist
+    async fn cache_expire(&self, key: String, ttl: u32) -> Result<bool, CubeError>;
     async fn cache_get(&self, key: String) -> Result<Option<IdRow<CacheItem>>, CubeError>;
     async fn cache_keys(&self, prefix: String) -> Result<Vec<IdRow<CacheItem>>, CubeError>;
     async fn cache_incr(&self, key: String) -> Result<IdRow<CacheItem>, CubeError>;
+    /// Replaces the value only if it's equal to `expected`, `None` expects the key to not exist
+    async fn cache_cas(&self, item: CacheItem, expected: Option<String>)
+        -> Result<bool, CubeError>;
+    async fn cache_mset(&self, items: Vec<CacheItem>) -> Result<(), CubeError>;
+    async fn cache_mget(
+        &self,
+        keys: Vec<String>,
+    ) -> Result<Vec<Option<IdRow<CacheItem>>>, CubeError>;
+    async fn cache_lock(&self, key: String, ttl: u32) -> Result<Option<u64>, CubeError>;
+    /// Releases the lock only if it's still held with this fencing token
+    async fn cache_unlock(&self, key: String, token: u64) -> Result<bool, CubeError>;
 
     // queue
     async fn queue_all(&self, limit: Option<usize>) -> Result<Vec<QueueAllItem>, CubeError>;
@@ -797,6 +876,7 @@ pub trait CacheStore: DIService + Send + Sync {
         allow_concurrency: u32,
     ) -> Result<QueueRetrieveResponse, CubeError>;
     async fn queue_ack(&self, key: QueueKey, result: Option<String>) -> Result<bool, CubeError>;
+    async fn queue_nack(&self, key: QueueKey) -> Result<bool, CubeError>;
     async fn queue_result_by_path(
         &self,
         path: String,
@@ -835,43 +915,44 @@ impl CacheStore for RocksCacheStore {
         item: CacheItem,
         update_if_not_exists: bool,
     ) -> Result<bool, CubeError> {
-        if item.get_value().len() >= self.store.config.cachestore_cache_max_entry_size() {
-            return Err(CubeError::user(format!(
-                "Unable to SET cache with '{}' key, exceeds maximum allowed size for payload: {}, max allowed: {}",
-                item.key,
-                humansize::format_size(item.get_value().len(), humansize::DECIMAL),
-                humansize::format_size(self.store.config.cachestore_cache_max_entry_size(), humansize::DECIMAL),
-            )));
-        }
+        self.check_cache_item_size(&item)?;
 
         self.cache_eviction_manager
             .before_insert(item.get_value().len() as u64)
             .await?;
 
-        let (result, inserted) = self
+        let mut key_events = self.key_events.batch();
+        let (result, inserted, key_events) = self
             .store
             .write_operation(move |db_ref, batch_pipe| {
                 let cache_schema = CacheItemRocksTable::new(db_ref.clone());
-                let index_key = CacheItemIndexKey::ByPath(item.get_path());
+                let path = item.get_path();
+                let index_key = CacheItemIndexKey::ByPath(path.clone());
                 let id_row_opt = cache_schema
                     .get_single_opt_row_by_index(&index_key, &CacheItemRocksIndex::ByPath)?;
 
-                if let Some(id_row) = id_row_opt {
+                let (result, inserted) = if let Some(id_row) = id_row_opt {
                     if update_if_not_exists {
-                        return Ok((false, None));
+                        return Ok((false, None, key_events));
                     };
 
                     cache_schema.update(id_row.id, item, &id_row.row, batch_pipe)?;
-                    Ok((true, None))
+                    (true, None)
                 } else {
                     let raw_size = item.get_value().len();
 
                     cache_schema.insert(item, batch_pipe)?;
-                    Ok((true, Some(raw_size)))
-                }
+                    (true, Some(raw_size))
+                };
+
+                key_events.add(path, CacheKeyEventType::Set);
+
+                Ok((result, inserted, key_events))
             })
             .await?;
 
+        self.key_events.publish(key_events);
+
         if let Some(raw_size) = inserted {
             self.cache_eviction_manager.notify_insert(raw_size as u64)?;
         }
@@ -882,24 +963,30 @@ impl CacheStore for RocksCacheStore {
     async fn cache_truncate(&self) -> Result<(), CubeError> {
         let block = self.cache_eviction_manager.truncation_block().await;
 
+        let mut key_events = self.key_events.batch();
         let result = self
             .store
             .write_operation(move |db_ref, batch_pipe| {
                 let cache_schema = CacheItemRocksTable::new(db_ref);
                 cache_schema.truncate(batch_pipe)?;
 
-                Ok(())
+                key_events.add("*".to_string(), CacheKeyEventType::Truncate);
+
+                Ok(key_events)
             })
             .await;
 
         self.cache_eviction_manager.notify_truncate_end().await?;
         drop(block);
 
-        result
+        self.key_events.publish(result?);
+
+        Ok(())
     }
 
     async fn cache_delete(&self, key: String) -> Result<(), CubeError> {
-        let result = self
+        let mut key_events = self.key_events.batch();
+        let (result, key_events) = self
             .store
             .write_operation(move |db_ref, batch_pipe| {
                 let cache_schema = CacheItemRocksTable::new(db_ref.clone());
@@ -911,15 +998,18 @@ impl CacheStore for RocksCacheStore {
                     let row_id = row.id;
                     let raw_size = row.get_row().get_value().len();
 
+                    key_events.add(row.get_row().get_path(), CacheKeyEventType::Delete);
                     cache_schema.delete_row(row, batch_pipe)?;
 
-                    Ok(Some((row_id, raw_size)))
+                    Ok((Some((row_id, raw_size)), key_events))
                 } else {
-                    Ok(None)
+                    Ok((None, key_events))
                 }
             })
             .await?;
 
+        self.key_events.publish(key_events);
+
         if let Some((row_id, raw_size)) = result {
             self.cache_eviction_manager
                 .notify_delete(row_id, raw_size as u64)?;
@@ -928,6 +1018,68 @@ impl CacheStore for RocksCacheStore {
         Ok(())
     }
 
+    async fn cache_mdelete(&self, keys: Vec<String>) -> Result<u64, CubeError> {
+        let mut key_events = self.key_events.batch();
+        let (deleted, key_events) = self
+            .store
+            .write_operation(move |db_ref, batch_pipe| {
+                let cache_schema = CacheItemRocksTable::new(db_ref.clone());
+                let mut deleted = Vec::new();
+
+                for key in keys.into_iter().unique() {
+                    let index_key = CacheItemIndexKey::ByPath(key);
+                    let row_opt = cache_schema
+                        .get_single_opt_row_by_index(&index_key, &CacheItemRocksIndex::ByPath)?;
+
+                    if let Some(row) = row_opt {
+                        deleted.push((row.id, row.get_row().get_value().len()));
+
+                        key_events.add(row.get_row().get_path(), CacheKeyEventType::Delete);
+                        cache_schema.delete_row(row, batch_pipe)?;
+                    }
+                }
+
+                Ok((deleted, key_events))
+            })
+            .await?;
+
+        self.key_events.publish(key_events);
+
+        for (row_id, raw_size) in deleted.iter() {
+            self.cache_eviction_manager
+                .notify_delete(*row_id, *raw_size as u64)?;
+        }
+
+        Ok(deleted.len() as u64)
+    }
+
+    async fn cache_expire(&self, key: String, ttl: u32) -> Result<bool, CubeError> {
+        let mut key_events = self.key_events.batch();
+        let (result, key_events) = self
+            .store
+            .write_operation(move |db_ref, batch_pipe| {
+                let cache_schema = CacheItemRocksTable::new(db_ref.clone());
+                let index_key = CacheItemIndexKey::ByPath(key.clone());
+                let id_row_opt = cache_schema
+                    .get_single_opt_row_by_index(&index_key, &CacheItemRocksIndex::ByPath)?;
+
+                if let Some(id_row) = id_row_opt {
+                    let new = CacheItem::new(key.clone(), Some(ttl), id_row.row.value.clone());
+                    cache_schema.update(id_row.id, new, &id_row.row, batch_pipe)?;
+                    key_events.add(key, CacheKeyEventType::Set);
+
+                    Ok((true, key_events))
+                } else {
+                    Ok((false, key_events))
+                }
+            })
+            .await?;
+
+        self.key_events.publish(key_events);
+
+        Ok(result)
+    }
+
     async fn cache_get(&self, key: String) -> Result<Option<IdRow<CacheItem>>, CubeError> {
         let res = self
             .store
@@ -963,7 +1115,8 @@ impl CacheStore for RocksCacheStore {
     }
 
     async fn cache_incr(&self, path: String) -> Result<IdRow<CacheItem>, CubeError> {
-        let item = self
+        let mut key_events = self.key_events.batch();
+        let (item, key_events) = self
             .store
             .write_operation(move |db_ref, batch_pipe| {
                 let cache_schema = CacheItemRocksTable::new(db_ref.clone());
@@ -971,26 +1124,244 @@ impl CacheStore for RocksCacheStore {
                 let id_row_opt = cache_schema
                     .get_single_opt_row_by_index(&index_key, &CacheItemRocksIndex::ByPath)?;
 
+                key_events.add(path.clone(), CacheKeyEventType::Set);
+
                 // TODO: Merge operator?
-                if let Some(id_row) = id_row_opt {
+                let item = if let Some(id_row) = id_row_opt {
                     let mut new = id_row.row.clone();
 
                     let last_val = id_row.row.value.parse::<i64>()?;
                     new.value = (last_val + 1).to_string();
 
-                    cache_schema.update(id_row.id, new, &id_row.row, batch_pipe)
+                    cache_schema.update(id_row.id, new, &id_row.row, batch_pipe)?
                 } else {
                     let item = CacheItem::new(path, None, "1".to_string());
-                    cache_schema.insert(item, batch_pipe)
-                }
+                    cache_schema.insert(item, batch_pipe)?
+                };
+
+                Ok((item, key_events))
             })
             .await?;
 
+        self.key_events.publish(key_events);
+
         self.cache_eviction_manager.notify_lookup(&item)?;
 
         Ok(item)
     }
 
+    async fn cache_cas(
+        &self,
+        item: CacheItem,
+        expected: Option<String>,
+    ) -> Result<bool, CubeError> {
+        self.check_cache_item_size(&item)?;
+
+        if expected.is_none() {
+            self.cache_eviction_manager
+                .before_insert(item.get_value().len() as u64)
+                .await?;
+        }
+
+        let mut key_events = self.key_events.batch();
+        let (result, inserted, key_events) = self
+            .store
+            .write_operation(move |db_ref, batch_pipe| {
+                let cache_schema = CacheItemRocksTable::new(db_ref.clone());
+                let path = item.get_path();
+                let index_key = CacheItemIndexKey::ByPath(path.clone());
+                let id_row_opt = cache_schema
+                    .get_single_opt_row_by_index(&index_key, &CacheItemRocksIndex::ByPath)?;
+
+                match (id_row_opt, expected) {
+                    (Some(id_row), Some(expected)) if id_row.get_row().get_value() == &expected => {
+                        cache_schema.update(id_row.id, item, &id_row.row, batch_pipe)?;
+                        key_events.add(path, CacheKeyEventType::Set);
+
+                        Ok((true, None, key_events))
+                    }
+                    (None, None) => {
+                        let raw_size = item.get_value().len();
+
+                        cache_schema.insert(item, batch_pipe)?;
+                        key_events.add(path, CacheKeyEventType::Set);
+
+                        Ok((true, Some(raw_size), key_events))
+                    }
+                    _ => Ok((false, None, key_events)),
+                }
+            })
+            .await?;
+
+        self.key_events.publish(key_events);
+
+        if let Some(raw_size) = inserted {
+            self.cache_eviction_manager.notify_insert(raw_size as u64)?;
+        }
+
+        Ok(result)
+    }
+
+    async fn cache_mset(&self, items: Vec<CacheItem>) -> Result<(), CubeError> {
+        for item in items.iter() {
+            self.check_cache_item_size(item)?;
+        }
+
+        self.cache_eviction_manager
+            .before_insert(items.iter().map(|i| i.get_value().len() as u64).sum())
+            .await?;
+
+        let mut key_events = self.key_events.batch();
+        let (inserted, key_events) = self
+            .store
+            .write_operation(move |db_ref, batch_pipe| {
+                let cache_schema = CacheItemRocksTable::new(db_ref.clone());
+                let mut inserted = Vec::new();
+
+                // Lookups don't see the batch, the last value for the same key wins
+                let items = items
+                    .into_iter()
+                    .rev()
+                    .unique_by(|item| item.get_path())
+                    .collect::<Vec<_>>();
+                for item in items.into_iter().rev() {
+                    let path = item.get_path();
+                    let index_key = CacheItemIndexKey::ByPath(path.clone());
+                    let id_row_opt = cache_schema
+                        .get_single_opt_row_by_index(&index_key, &CacheItemRocksIndex::ByPath)?;
+
+                    if let Some(id_row) = id_row_opt {
+                        cache_schema.update(id_row.id, item, &id_row.row, batch_pipe)?;
+                    } else {
+                        inserted.push(item.get_value().len());
+                        cache_schema.insert(item, batch_pipe)?;
+                    }
+
+                    key_events.add(path, CacheKeyEventType::Set);
+                }
+
+                Ok((inserted, key_events))
+            })
+            .await?;
+
+        self.key_events.publish(key_events);
+
+        for raw_size in inserted {
+            self.cache_eviction_manager.notify_insert(raw_size as u64)?;
+        }
+
+        Ok(())
+    }
+
+    async fn cache_mget(
+        &self,
+        keys: Vec<String>,
+    ) -> Result<Vec<Option<IdRow<CacheItem>>>, CubeError> {
+        let res =
+            self.store
+                .read_operation(move |db_ref| {
+                    let cache_schema = CacheItemRocksTable::new(db_ref.clone());
+                    let mut res = Vec::with_capacity(keys.len());
+
+                    for key in keys {
+                        let index_key = CacheItemIndexKey::ByPath(key);
+                        res.push(cache_schema.get_single_opt_row_by_index(
+                            &index_key,
+                            &CacheItemRocksIndex::ByPath,
+                        )?);
+                    }
+
+                    Ok(res)
+                })
+                .await?;
+
+        for item in res.iter().flatten() {
+            self.cache_eviction_manager.notify_lookup(item)?;
+        }
+
+        Ok(res)
+    }
+
+    async fn cache_lock(&self, key: String, ttl: u32) -> Result<Option<u64>, CubeError> {
+        // Token is not known before the write, reserve space for the widest one
+        self.cache_eviction_manager
+            .before_insert(u64::MAX.to_string().len() as u64)
+            .await?;
+
+        let mut key_events = self.key_events.batch();
+        let (acquired, key_events) = self
+            .store
+            .write_operation(move |db_ref, batch_pipe| {
+                let cache_schema = CacheItemRocksTable::new(db_ref.clone());
+                let index_key = CacheItemIndexKey::ByPath(key.clone());
+                let id_row_opt = cache_schema
+                    .get_single_opt_row_by_index(&index_key, &CacheItemRocksIndex::ByPath)?;
+
+                if id_row_opt.is_some() {
+                    return Ok((None, key_events));
+                }
+
+                // Table sequence is persisted and never goes back, which makes it usable as a
+                // fencing token. Expired lock rows are not visible for lookups.
+                let token = cache_schema.next_table_seq()?;
+                let item = CacheItem::new(key.clone(), Some(ttl), token.to_string());
+                let raw_size = item.get_value().len();
+
+                cache_schema.insert_with_pk(token, item, batch_pipe)?;
+                key_events.add(key, CacheKeyEventType::Set);
+
+                Ok((Some((token, raw_size)), key_events))
+            })
+            .await?;
+
+        self.key_events.publish(key_events);
+
+        if let Some((token, raw_size)) = acquired {
+            self.cache_eviction_manager.notify_insert(raw_size as u64)?;
+
+            Ok(Some(token))
+        } else {
+            Ok(None)
+        }
+    }
+
+    async fn cache_unlock(&self, key: String, token: u64) -> Result<bool, CubeError> {
+        let mut key_events = self.key_events.batch();
+        let (deleted, key_events) = self
+            .store
+            .write_operation(move |db_ref, batch_pipe| {
+                let cache_schema = CacheItemRocksTable::new(db_ref.clone());
+                let index_key = CacheItemIndexKey::ByPath(key.clone());
+                let row_opt = cache_schema
+                    .get_single_opt_row_by_index(&index_key, &CacheItemRocksIndex::ByPath)?;
+
+                match row_opt {
+                    // Lock was expired and acquired by somebody else, it's not ours to release
+                    Some(row) if row.get_row().get_value() == &token.to_string() => {
+                        let deleted = (row.id, row.get_row().get_value().len());
+
+                        key_events.add(key, CacheKeyEventType::Delete);
+                        cache_schema.delete_row(row, batch_pipe)?;
+
+                        Ok((Some(deleted), key_events))
+                    }
+                    _ => Ok((None, key_events)),
+                }
+            })
+            .await?;
+
+        self.key_events.publish(key_events);
+
+        if let Some((row_id, raw_size)) = deleted {
+            self.cache_eviction_manager
+                .notify_delete(row_id, raw_size as u64)?;
+
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+
     async fn queue_all(&self, limit: Option<usize>) -> Result<Vec<QueueAllItem>, CubeError> {
         self.store
             .read_operation(move |db_ref| {
@@ -1068,6 +1439,8 @@ impl CacheStore for RocksCacheStore {
                             QueueItem::status_default(),
                             payload.priority,
                             payload.orphaned.clone(),
+                            payload.delay.clone(),
+                            payload.max_attempts.clone(),
                         ),
                         batch_pipe,
                     )?;
@@ -1120,21 +1493,67 @@ impl CacheStore for RocksCacheStore {
         orphaned_timeout: Option<u32>,
         heartbeat_timeout: Option<u32>,
     ) -> Result<Vec<IdRow<QueueItem>>, CubeError> {
-        self.store
+        let (to_cancel, to_retry) = self
+            .store
             .read_operation(move |db_ref| {
                 let queue_schema = QueueItemRocksTable::new(db_ref.clone());
                 let index_key = QueueItemIndexKey::ByPrefix(prefix);
                 let items =
                     queue_schema.get_rows_by_index(&index_key, &QueueItemRocksIndex::ByPrefix)?;
 
+                // Stalled items with retries are re-queued instead of being canceled
                 Ok(Self::filter_to_cancel(
                     db_ref.start_time.clone(),
                     items,
                     orphaned_timeout,
                     heartbeat_timeout,
-                ))
+                )
+                .into_iter()
+                .partition::<Vec<_>, _>(|item| {
+                    item.get_row().get_status() != &QueueItemStatus::Active
+                        || item.get_row().get_max_attempts().is_none()
+                }))
             })
-            .await
+            .await?;
+
+        // Polling stays read-only until there is something to retry
+        if !to_retry.is_empty() {
+            let backoff_base = self.store.config.cachestore_queue_retry_backoff_base();
+
+            self.store
+                .write_operation(move |db_ref, batch_pipe| {
+                    let queue_schema = QueueItemRocksTable::new(db_ref.clone());
+                    let queue_payload_schema = QueueItemPayloadRocksTable::new(db_ref.clone());
+                    let now = db_ref.start_time.clone();
+
+                    for item in to_retry {
+                        // The item could be acked or heartbeated after the read
+                        let item_row = match queue_schema.get_row(item.get_id())? {
+                            Some(item_row) => item_row,
+                            None => continue,
+                        };
+                        if item_row.get_row().get_status() != &QueueItemStatus::Active
+                            || item_row.get_row().get_heartbeat() != item.get_row().get_heartbeat()
+                        {
+                            continue;
+                        }
+
+                        Self::queue_retry_or_fail_impl(
+                            &queue_schema,
+                            &queue_payload_schema,
+                            batch_pipe,
+                            item_row,
+                            now,
+                            backoff_base,
+                        )?;
+                    }
+
+                    Ok(())
+                })
+                .await?;
+        }
+
+        Ok(to_cancel)
     }
 
     async fn queue_list(
@@ -1148,7 +1567,7 @@ impl CacheStore for RocksCacheStore {
             .read_operation(move |db_ref| {
                 let queue_schema = QueueItemRocksTable::new(db_ref.clone());
 
-                let items = if let Some(status_filter) = status_filter {
+                let items = if let Some(status_filter) = status_filter.clone() {
                     let index_key = QueueItemIndexKey::ByPrefixAndStatus(prefix, status_filter);
                     queue_schema
                         .get_rows_by_index(&index_key, &QueueItemRocksIndex::ByPrefixAndStatus)?
@@ -1157,6 +1576,16 @@ impl CacheStore for RocksCacheStore {
                     queue_schema.get_rows_by_index(&index_key, &QueueItemRocksIndex::ByPrefix)?
                 };
 
+                // Delayed items cannot be retrieved yet, there is no reason to show them as pending
+                let items = if status_filter == Some(QueueItemStatus::Pending) {
+                    items
+                        .into_iter()
+                        .filter(|item| item.get_row().is_ready(&db_ref.start_time))
+                        .collect()
+                } else {
+                    items
+                };
+
                 let items = if priority_sort {
                     items
                         .into_iter()
@@ -1310,11 +1739,12 @@ impl CacheStore for RocksCacheStore {
                 };
 
                 if id_row.get_row().get_status() == &QueueItemStatus::Pending {
+                    if !id_row.get_row().is_ready(&db_ref.start_time) {
+                        return Ok(QueueRetrieveResponse::NotFound { pending, active });
+                    }
+
                     let mut new = id_row.get_row().clone();
-                    new.status = QueueItemStatus::Active;
-                    // It's an important to insert heartbeat, because
-                    // without that created datetime will be used for orphaned filtering
-                    new.update_heartbeat();
+                    new.start_attempt();
 
                     let queue_payload_schema = QueueItemPayloadRocksTable::new(db_ref.clone());
 
@@ -1351,7 +1781,9 @@ impl CacheStore for RocksCacheStore {
     }
 
     async fn queue_ack(&self, key: QueueKey, result: Option<String>) -> Result<bool, CubeError> {
-        self.store
+        let mut key_events = self.key_events.batch();
+        let (acked, key_events) = self
+            .store
             .write_operation(move |db_ref, batch_pipe| {
                 let queue_item_tbl = QueueItemRocksTable::new(db_ref.clone());
                 let queue_item_payload_tbl = QueueItemPayloadRocksTable::new(db_ref.clone());
@@ -1364,6 +1796,8 @@ impl CacheStore for RocksCacheStore {
                     queue_item_tbl.delete_row(item_row, batch_pipe)?;
                     queue_item_payload_tbl.try_delete(id, batch_pipe)?;
 
+                    key_events.add(path.clone(), CacheKeyEventType::QueueResult);
+
                     if let Some(result) = result {
                         let queue_result = QueueResult::new(path.clone(), result);
                         let result_schema = QueueResultRocksTable::new(db_ref.clone());
@@ -1386,10 +1820,49 @@ impl CacheStore for RocksCacheStore {
                         }));
                     }
 
-                    Ok(true)
+                    Ok((true, key_events))
                 } else {
                     warn!("Unable to ack queue, unknown key: {:?}", key);
 
+                    Ok((false, key_events))
+                }
+            })
+            .await?;
+
+        self.key_events.publish(key_events);
+
+        Ok(acked)
+    }
+
+    async fn queue_nack(&self, key: QueueKey) -> Result<bool, CubeError> {
+        let backoff_base = self.store.config.cachestore_queue_retry_backoff_base();
+
+        self.store
+            .write_operation(move |db_ref, batch_pipe| {
+                let queue_schema = QueueItemRocksTable::new(db_ref.clone());
+                let queue_payload_schema = QueueItemPayloadRocksTable::new(db_ref.clone());
+
+                let item_row = queue_schema.get_row_by_key(key.clone())?;
+                if let Some(item_row) = item_row {
+                    if item_row.get_row().get_status() != &QueueItemStatus::Active {
+                        warn!("Unable to nack queue item which is not active: {:?}", key);
+
+                        return Ok(false);
+                    }
+
+                    Self::queue_retry_or_fail_impl(
+                        &queue_schema,
+                        &queue_payload_schema,
+                        batch_pipe,
+                        item_row,
+                        db_ref.start_time.clone(),
+                        backoff_base,
+                    )?;
+
+                    Ok(true)
+                } else {
+                    warn!("Unable to nack queue, unknown key: {:?}", key);
+
                     Ok(false)
                 }
             })
@@ -1561,6 +2034,14 @@ impl CacheStore for ClusterCacheStoreClient {
         panic!("CacheStore cannot be used on the worker node! cache_delete was used.")
     }
 
+    async fn cache_mdelete(&self, _keys: Vec<String>) -> Result<u64, CubeError> {
+        panic!("CacheStore cannot be used on the worker node! cache_mdelete was used.")
+    }
+
+    async fn cache_expire(&self, _key: String, _ttl: u32) -> Result<bool, CubeError> {
+        panic!("CacheStore cannot be used on the worker node! cache_expire was used.")
+    }
+
     async fn cache_get(&self, _key: String) -> Result<Option<IdRow<CacheItem>>, CubeError> {
         panic!("CacheStore cannot be used on the worker node! cache_get was used.")
     }
@@ -1573,6 +2054,33 @@ impl CacheStore for ClusterCacheStoreClient {
         panic!("CacheStore cannot be used on the worker node! cache_incr was used.")
     }
 
+    async fn cache_cas(
+        &self,
+        _item: CacheItem,
+        _expected: Option<String>,
+    ) -> Result<bool, CubeError> {
+        panic!("CacheStore cannot be used on the worker node! cache_cas was used.")
+    }
+
+    async fn cache_mset(&self, _items: Vec<CacheItem>) -> Result<(), CubeError> {
+        panic!("CacheStore cannot be used on the worker node! cache_mset was used.")
+    }
+
+    async fn cache_mget(
+        &self,
+        _keys: Vec<String>,
+    ) -> Result<Vec<Option<IdRow<CacheItem>>>, CubeError> {
+        panic!("CacheStore cannot be used on the worker node! cache_mget was used.")
+    }
+
+    async fn cache_lock(&self, _key: String, _ttl: u32) -> Result<Option<u64>, CubeError> {
+        panic!("CacheStore cannot be used on the worker node! cache_lock was used.")
+    }
+
+    async fn cache_unlock(&self, _key: String, _token: u64) -> Result<bool, CubeError> {
+        panic!("CacheStore cannot be used on the worker node! cache_unlock was used.")
+    }
+
     async fn queue_all(&self, _limit: Option<usize>) -> Result<Vec<QueueAllItem>, CubeError> {
         panic!("CacheStore cannot be used on the worker node! queue_all was used.")
     }
@@ -1639,6 +2147,10 @@ impl CacheStore for ClusterCacheStoreClient {
         panic!("CacheStore cannot be used on the worker node! queue_ack was used.")
     }
 
+    async fn queue_nack(&self, _key: QueueKey) -> Result<bool, CubeError> {
+        panic!("CacheStore cannot be used on the worker node! queue_nack was used.")
+    }
+
     async fn queue_result_by_path(
         &self,
         _path: String,
@@ -1771,6 +2283,193 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_cache_key_events() -> Result<(), CubeError> {
+        init_test_logger().await;
+
+        let (_, cachestore) = RocksCacheStore::prepare_test_cachestore(
+            "cache_key_events",
+            Config::test("cachestore_key_events"),
+        );
+
+        let (key_event_sender, _) = tokio::sync::broadcast::channel(16);
+        cachestore.set_key_event_sender(key_event_sender.clone());
+
+        // Without subscribers nothing is collected for key changes
+        cachestore
+            .cache_set(
+                CacheItem::new("prefix:a".to_string(), None, "1".to_string()),
+                false,
+            )
+            .await?;
+
+        let mut key_events = key_event_sender.subscribe();
+        cachestore
+            .cache_set(
+                CacheItem::new("prefix:b".to_string(), None, "1".to_string()),
+                false,
+            )
+            .await?;
+        cachestore.cache_delete("prefix:a".to_string()).await?;
+        cachestore.cache_truncate().await?;
+
+        assert_eq!(
+            key_events.recv().await?,
+            CacheKeyEvent::new("prefix:b".to_string(), CacheKeyEventType::Set)
+        );
+        assert_eq!(
+            key_events.recv().await?,
+            CacheKeyEvent::new("prefix:a".to_string(), CacheKeyEventType::Delete)
+        );
+        assert_eq!(
+            key_events.recv().await?,
+            CacheKeyEvent::new("*".to_string(), CacheKeyEventType::Truncate)
+        );
+
+        RocksCacheStore::cleanup_test_cachestore("cache_key_events");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_cache_cas_mset_lock() -> Result<(), CubeError> {
+        init_test_logger().await;
+
+        let (_, cachestore) = RocksCacheStore::prepare_test_cachestore(
+            "cache_cas_mset_lock",
+            Config::test("cachestore_cas_mset_lock"),
+        );
+
+        cachestore
+            .cache_mset(vec![
+                CacheItem::new("prefix:a".to_string(), None, "1".to_string()),
+                CacheItem::new("prefix:b".to_string(), None, "1".to_string()),
+                CacheItem::new("prefix:b".to_string(), None, "2".to_string()),
+            ])
+            .await?;
+
+        let values = cachestore
+            .cache_mget(vec![
+                "prefix:a".to_string(),
+                "prefix:b".to_string(),
+                "prefix:c".to_string(),
+            ])
+            .await?
+            .into_iter()
+            .map(|row| row.map(|r| r.into_row().value))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            values,
+            vec![Some("1".to_string()), Some("2".to_string()), None]
+        );
+
+        assert_eq!(
+            cachestore
+                .cache_cas(
+                    CacheItem::new("prefix:a".to_string(), None, "3".to_string()),
+                    Some("2".to_string())
+                )
+                .await?,
+            false
+        );
+        assert_eq!(
+            cachestore
+                .cache_cas(
+                    CacheItem::new("prefix:a".to_string(), None, "3".to_string()),
+                    Some("1".to_string())
+                )
+                .await?,
+            true
+        );
+        assert_eq!(
+            cachestore
+                .cache_cas(
+                    CacheItem::new("prefix:c".to_string(), None, "1".to_string()),
+                    Some("1".to_string())
+                )
+                .await?,
+            false
+        );
+        // Missing key is created only when it's expected to not exist
+        assert_eq!(
+            cachestore
+                .cache_cas(
+                    CacheItem::new("prefix:c".to_string(), None, "1".to_string()),
+                    None
+                )
+                .await?,
+            true
+        );
+        assert_eq!(
+            cachestore
+                .cache_cas(
+                    CacheItem::new("prefix:c".to_string(), None, "2".to_string()),
+                    None
+                )
+                .await?,
+            false
+        );
+        assert_eq!(
+            cachestore
+                .cache_get("prefix:c".to_string())
+                .await?
+                .unwrap()
+                .into_row()
+                .value,
+            "1"
+        );
+
+        let first_token = cachestore
+            .cache_lock("lock:refresh".to_string(), 60)
+            .await?
+            .expect("lock must be acquired");
+        assert_eq!(
+            cachestore
+                .cache_lock("lock:refresh".to_string(), 60)
+                .await?,
+            None
+        );
+        assert_eq!(
+            cachestore
+                .cache_get("lock:refresh".to_string())
+                .await?
+                .unwrap()
+                .into_row()
+                .value,
+            first_token.to_string()
+        );
+
+        // Unlock with a stale token must not release the lock
+        assert_eq!(
+            cachestore
+                .cache_unlock("lock:refresh".to_string(), first_token + 1)
+                .await?,
+            false
+        );
+        assert_eq!(
+            cachestore
+                .cache_unlock("lock:refresh".to_string(), first_token)
+                .await?,
+            true
+        );
+        assert_eq!(
+            cachestore
+                .cache_unlock("lock:refresh".to_string(), first_token)
+                .await?,
+            false
+        );
+
+        let second_token = cachestore
+            .cache_lock("lock:refresh".to_string(), 60)
+            .await?
+            .expect("lock must be acquired");
+        assert!(second_token > first_token);
+
+        RocksCacheStore::cleanup_test_cachestore("cache_cas_mset_lock");
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_cache_set_max_entry_size() -> Result<(), CubeError> {
         init_test_logger().await;
@@ -2053,19 +2752,47 @@ mod tests {
         let now = Utc::now();
         let item_pending_custom_orphaned = IdRow::new(
             1,
-            QueueItem::new("1".to_string(), QueueItemStatus::Pending, 1, Some(10)),
+            QueueItem::new(
+                "1".to_string(),
+                QueueItemStatus::Pending,
+                1,
+                Some(10),
+                None,
+                None,
+            ),
         );
         let item_pending_custom_orphaned_expired = IdRow::new(
             2,
-            QueueItem::new("2".to_string(), QueueItemStatus::Pending, 1, Some(1)),
+            QueueItem::new(
+                "2".to_string(),
+                QueueItemStatus::Pending,
+                1,
+                Some(1),
+                None,
+                None,
+            ),
         );
         let item_active_custom_orphaned = IdRow::new(
             3,
-            QueueItem::new("3".to_string(), QueueItemStatus::Active, 1, Some(10)),
+            QueueItem::new(
+                "3".to_string(),
+                QueueItemStatus::Active,
+                1,
+                Some(10),
+                None,
+                None,
+            ),
         );
         let mut item_active_custom_orphaned_expired = IdRow::new(
             4,
-            QueueItem::new("4".to_string(), QueueItemStatus::Active, 1, Some(1)),
+            QueueItem::new(
+                "4".to_string(),
+                QueueItemStatus::Active,
+                1,
+                Some(1),
+                None,
+                None,
+            ),
         );
 
         assert_eq!(
@@ -5,8 +5,8 @@ use crate::cachestore::cache_rocksstore::{
 };
 use crate::cachestore::queue_item::QueueRetrieveResponse;
 use crate::cachestore::{
-    CacheItem, CacheStore, QueueCancelResponse, QueueItem, QueueItemStatus, QueueKey, QueueResult,
-    QueueResultResponse, RocksCacheStore,
+    CacheItem, CacheKeyEvent, CacheStore, QueueCancelResponse, QueueItem, QueueItemStatus,
+    QueueKey, QueueResult, QueueResultResponse, RocksCacheStore,
 };
 use crate::config::ConfigObj;
 use crate::metastore::{IdRow, MetaStoreEvent, MetaStoreFs, RocksPropertyRow};
@@ -24,6 +24,7 @@ pub enum LazyRocksCacheStoreState {
         metastore_fs: Arc<dyn MetaStoreFs>,
         config: Arc<dyn ConfigObj>,
         listeners: Vec<tokio::sync::broadcast::Sender<MetaStoreEvent>>,
+        key_event_sender: tokio::sync::broadcast::Sender<CacheKeyEvent>,
         init_flag: Sender<bool>,
     },
     Closed {},
@@ -44,12 +45,14 @@ impl LazyRocksCacheStore {
         metastore_fs: Arc<dyn MetaStoreFs>,
         config: Arc<dyn ConfigObj>,
         listeners: Vec<tokio::sync::broadcast::Sender<MetaStoreEvent>>,
+        key_event_sender: tokio::sync::broadcast::Sender<CacheKeyEvent>,
     ) -> Result<Arc<Self>, CubeError> {
         let store = RocksCacheStore::load_from_dump(path, dump_path, metastore_fs, config).await?;
 
         for listener in listeners {
             store.add_listener(listener).await;
         }
+        store.set_key_event_sender(key_event_sender);
 
         Ok(Arc::new(Self {
             init_signal: None,
@@ -62,6 +65,7 @@ impl LazyRocksCacheStore {
         metastore_fs: Arc<dyn MetaStoreFs>,
         config: Arc<dyn ConfigObj>,
         listeners: Vec<tokio::sync::broadcast::Sender<MetaStoreEvent>>,
+        key_event_sender: tokio::sync::broadcast::Sender<CacheKeyEvent>,
     ) -> Result<Arc<Self>, CubeError> {
         let (init_flag, init_signal) = tokio::sync::watch::channel::<bool>(false);
 
@@ -72,6 +76,7 @@ impl LazyRocksCacheStore {
                 metastore_fs,
                 config,
                 listeners,
+                key_event_sender,
                 init_flag,
             }),
         }))
@@ -100,6 +105,7 @@ impl LazyRocksCacheStore {
                 metastore_fs,
                 config,
                 listeners,
+                key_event_sender,
                 // receiver will be closed on drop
                 init_flag: _,
             } => {
@@ -110,6 +116,7 @@ impl LazyRocksCacheStore {
                 for listener in listeners {
                     store.add_listener(listener.clone()).await;
                 }
+                store.set_key_event_sender(key_event_sender.clone());
 
                 *guard = LazyRocksCacheStoreState::Initialized {
                     store: store.clone(),
@@ -195,6 +202,14 @@ impl CacheStore for LazyRocksCacheStore {
         self.init().await?.cache_delete(key).await
     }
 
+    async fn cache_mdelete(&self, keys: Vec<String>) -> Result<u64, CubeError> {
+        self.init().await?.cache_mdelete(keys).await
+    }
+
+    async fn cache_expire(&self, key: String, ttl: u32) -> Result<bool, CubeError> {
+        self.init().await?.cache_expire(key, ttl).await
+    }
+
     async fn cache_get(&self, key: String) -> Result<Option<IdRow<CacheItem>>, CubeError> {
         self.init().await?.cache_get(key).await
     }
@@ -207,6 +222,33 @@ impl CacheStore for LazyRocksCacheStore {
         self.init().await?.cache_incr(path).await
     }
 
+    async fn cache_cas(
+        &self,
+        item: CacheItem,
+        expected: Option<String>,
+    ) -> Result<bool, CubeError> {
+        self.init().await?.cache_cas(item, expected).await
+    }
+
+    async fn cache_mset(&self, items: Vec<CacheItem>) -> Result<(), CubeError> {
+        self.init().await?.cache_mset(items).await
+    }
+
+    async fn cache_mget(
+        &self,
+        keys: Vec<String>,
+    ) -> Result<Vec<Option<IdRow<CacheItem>>>, CubeError> {
+        self.init().await?.cache_mget(keys).await
+    }
+
+    async fn cache_lock(&self, key: String, ttl: u32) -> Result<Option<u64>, CubeError> {
+        self.init().await?.cache_lock(key, ttl).await
+    }
+
+    async fn cache_unlock(&self, key: String, token: u64) -> Result<bool, CubeError> {
+        self.init().await?.cache_unlock(key, token).await
+    }
+
     async fn queue_all(&self, limit: Option<usize>) -> Result<Vec<QueueAllItem>, CubeError> {
         self.init().await?.queue_all(limit).await
     }
@@ -282,6 +324,10 @@ impl CacheStore for LazyRocksCacheStore {
         self.init().await?.queue_ack(key, result).await
     }
 
+    async fn queue_nack(&self, key: QueueKey) -> Result<bool, CubeError> {
+        self.init().await?.queue_nack(key).await
+    }
+
     async fn queue_result_by_path(
         &self,
         path: String,
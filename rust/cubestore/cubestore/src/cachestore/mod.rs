@@ -8,11 +8,12 @@ mod queue_item;
 mod queue_item_payload;
 mod queue_result;
 mod scheduler;
+mod subscription;
 
 pub use cache_eviction_manager::{
     CacheEvictionManager, CacheEvictionPolicy, EvictionFinishedResult, EvictionResult,
 };
-pub use cache_item::CacheItem;
+pub use cache_item::{CacheItem, CacheKeyEvent, CacheKeyEventType};
 pub use cache_rocksstore::{
     CacheStore, CacheStoreRpcClient, CachestoreInfo, ClusterCacheStoreClient, QueueAddPayload,
     QueueAddResponse, QueueAllItem, QueueCancelResponse, QueueGetResponse, QueueKey, QueueListItem,
@@ -23,3 +24,7 @@ pub use queue_item::{QueueItem, QueueItemStatus, QueueResultAckEvent, QueueRetri
 pub use queue_item_payload::QueueItemPayload;
 pub use queue_result::QueueResult;
 pub use scheduler::CacheStoreSchedulerImpl;
+pub use subscription::{
+    CacheKeyEventBatch, CacheKeyEventPublisher, CacheKeyNotification, CacheKeySubscriber,
+    CacheKeySubscriptions,
+};
@@ -57,6 +57,7 @@ pub enum QueueItemStatus {
     Pending = 0,
     Active = 1,
     Finished = 2,
+    Failed = 3,
 }
 
 impl ToString for QueueItemStatus {
@@ -65,6 +66,7 @@ impl ToString for QueueItemStatus {
             QueueItemStatus::Pending => "pending".to_string(),
             QueueItemStatus::Active => "active".to_string(),
             QueueItemStatus::Finished => "finished".to_string(),
+            QueueItemStatus::Failed => "failed".to_string(),
         }
     }
 }
@@ -86,8 +88,24 @@ pub struct QueueItem {
     orphaned: Option<DateTime<Utc>>,
     #[serde(with = "ts_seconds")]
     expire: DateTime<Utc>,
+    // Item cannot be retrieved before this time, it's used for delayed items and retries
+    #[serde(with = "ts_seconds_option", default)]
+    not_before: Option<DateTime<Utc>>,
+    #[serde(default)]
+    attempts: u32,
+    #[serde(default)]
+    max_attempts: Option<u32>,
 }
 
+/// Items which exhausted all attempts are moved under this prefix with [QueueItemStatus::Failed].
+pub const QUEUE_DEAD_LETTER_PREFIX: &str = "dead_letter";
+
+// Upper bound for the exponential backoff between attempts, in seconds
+const QUEUE_RETRY_BACKOFF_MAX: u64 = 60 * 60;
+
+/// Attempts for items without explicit `max_attempts`, a failed item is retried before it's dead-lettered
+pub const QUEUE_DEFAULT_MAX_ATTEMPTS: u32 = 3;
+
 impl RocksEntity for QueueItem {
     fn version() -> u32 {
         4
@@ -134,9 +152,14 @@ impl QueueItem {
         status: QueueItemStatus,
         priority: i64,
         orphaned: Option<u32>,
+        delay: Option<u32>,
+        max_attempts: Option<u32>,
     ) -> Self {
         let (prefix, key) = QueueItem::parse_path(path);
         let created = Utc::now();
+        let not_before = delay.map(|delay| created + Duration::seconds(delay as i64));
+        // Timeouts are counted from the moment when the item becomes available
+        let scheduled = not_before.unwrap_or(created);
 
         QueueItem {
             prefix,
@@ -146,16 +169,19 @@ impl QueueItem {
             extra: None,
             heartbeat: None,
             orphaned: if let Some(orphaned) = orphaned {
-                Some(created + Duration::seconds(orphaned as i64))
+                Some(scheduled + Duration::seconds(orphaned as i64))
             } else {
                 None
             },
             expire: if let Some(orphaned) = orphaned {
-                created + Duration::seconds(orphaned as i64) + Duration::hours(2)
+                scheduled + Duration::seconds(orphaned as i64) + Duration::hours(2)
             } else {
-                created.clone() + Duration::hours(4)
+                scheduled + Duration::hours(4)
             },
             created,
+            not_before,
+            attempts: 0,
+            max_attempts,
         }
     }
 
@@ -213,6 +239,80 @@ impl QueueItem {
         &self.expire
     }
 
+    pub fn get_not_before(&self) -> &Option<DateTime<Utc>> {
+        &self.not_before
+    }
+
+    pub fn get_attempts(&self) -> u32 {
+        self.attempts
+    }
+
+    pub fn get_max_attempts(&self) -> &Option<u32> {
+        &self.max_attempts
+    }
+
+    /// Delayed items and items waiting for the next attempt cannot be retrieved yet.
+    pub fn is_ready(&self, now: &DateTime<Utc>) -> bool {
+        if let Some(not_before) = &self.not_before {
+            not_before <= now
+        } else {
+            true
+        }
+    }
+
+    pub fn dead_letter_prefix(prefix: &Option<String>) -> String {
+        if let Some(prefix) = prefix {
+            format!("{}:{}", QUEUE_DEAD_LETTER_PREFIX, prefix)
+        } else {
+            QUEUE_DEAD_LETTER_PREFIX.to_string()
+        }
+    }
+
+    pub fn retry_backoff(attempts: u32, backoff_base: u64) -> Duration {
+        let multiplier = 1_u64 << attempts.saturating_sub(1).min(20);
+        let backoff = backoff_base
+            .saturating_mul(multiplier)
+            .min(QUEUE_RETRY_BACKOFF_MAX);
+
+        Duration::seconds(backoff as i64)
+    }
+
+    pub fn start_attempt(&mut self) {
+        self.status = QueueItemStatus::Active;
+        self.attempts += 1;
+        // It's an important to insert heartbeat, because
+        // without that created datetime will be used for orphaned filtering
+        self.update_heartbeat();
+    }
+
+    /// Returns a failed item back to pending with an exponential backoff. When the item has no
+    /// attempts left, it's moved to the dead-letter prefix with the failed status.
+    pub fn retry_or_fail(&self, now: DateTime<Utc>, backoff_base: u64) -> Self {
+        let mut new = self.clone();
+        new.heartbeat = None;
+
+        if self.attempts < self.max_attempts.unwrap_or(QUEUE_DEFAULT_MAX_ATTEMPTS) {
+            let not_before = now + Self::retry_backoff(self.attempts, backoff_base);
+            let scheduled = self.not_before.unwrap_or(self.created);
+
+            new.status = QueueItemStatus::Pending;
+            new.not_before = Some(not_before);
+            // Keep the same timeouts relatively to the new schedule
+            new.orphaned = self
+                .orphaned
+                .map(|orphaned| not_before + (orphaned - scheduled));
+            new.expire = not_before + (self.expire - scheduled);
+        } else {
+            new.status = QueueItemStatus::Failed;
+            new.prefix = Some(Self::dead_letter_prefix(&self.prefix));
+            new.not_before = None;
+            new.orphaned = None;
+            new.expire = now + Duration::hours(24);
+        }
+
+        new
+    }
+
     pub fn status_default() -> QueueItemStatus {
         QueueItemStatus::Pending
     }
@@ -397,6 +497,7 @@ impl RocksSecondaryIndex<QueueItem, QueueItemIndexKey> for QueueItemRocksIndex {
                     QueueItemStatus::Pending => r.push(0_u8),
                     QueueItemStatus::Active => r.push(1_u8),
                     QueueItemStatus::Finished => r.push(2_u8),
+                    QueueItemStatus::Failed => r.push(3_u8),
                 }
 
                 r
@@ -441,12 +542,54 @@ mod tests {
 
     #[test]
     fn test_queue_item_sort() -> Result<(), CubeError> {
-        let priority0_1 = QueueItem::new("1".to_string(), QueueItemStatus::Active, 0, None);
-        let priority0_2 = QueueItem::new("2".to_string(), QueueItemStatus::Active, 0, None);
-        let priority0_3 = QueueItem::new("3".to_string(), QueueItemStatus::Active, 0, None);
-        let priority10_4 = QueueItem::new("4".to_string(), QueueItemStatus::Active, 10, None);
-        let priority0_5 = QueueItem::new("5".to_string(), QueueItemStatus::Active, 0, None);
-        let priority_n5_6 = QueueItem::new("6".to_string(), QueueItemStatus::Active, -5, None);
+        let priority0_1 = QueueItem::new(
+            "1".to_string(),
+            QueueItemStatus::Active,
+            0,
+            None,
+            None,
+            None,
+        );
+        let priority0_2 = QueueItem::new(
+            "2".to_string(),
+            QueueItemStatus::Active,
+            0,
+            None,
+            None,
+            None,
+        );
+        let priority0_3 = QueueItem::new(
+            "3".to_string(),
+            QueueItemStatus::Active,
+            0,
+            None,
+            None,
+            None,
+        );
+        let priority10_4 = QueueItem::new(
+            "4".to_string(),
+            QueueItemStatus::Active,
+            10,
+            None,
+            None,
+            None,
+        );
+        let priority0_5 = QueueItem::new(
+            "5".to_string(),
+            QueueItemStatus::Active,
+            0,
+            None,
+            None,
+            None,
+        );
+        let priority_n5_6 = QueueItem::new(
+            "6".to_string(),
+            QueueItemStatus::Active,
+            -5,
+            None,
+            None,
+            None,
+        );
 
         assert_eq!(
             vec![
@@ -496,4 +639,66 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_queue_item_retry_or_fail() -> Result<(), CubeError> {
+        assert_eq!(QueueItem::retry_backoff(1, 5), Duration::seconds(5));
+        assert_eq!(QueueItem::retry_backoff(3, 5), Duration::seconds(20));
+        assert_eq!(QueueItem::retry_backoff(30, 5), Duration::seconds(60 * 60));
+
+        let mut item = QueueItem::new(
+            "STANDALONE#queue:1".to_string(),
+            QueueItemStatus::Pending,
+            0,
+            Some(60),
+            Some(10),
+            Some(2),
+        );
+        let now = Utc::now();
+        assert!(!item.is_ready(&now));
+        assert!(item.is_ready(&(now + Duration::seconds(10))));
+
+        item.start_attempt();
+        let retried = item.retry_or_fail(now, 5);
+        assert_eq!(retried.get_status(), &QueueItemStatus::Pending);
+        assert_eq!(retried.get_attempts(), 1);
+        assert_eq!(retried.get_not_before(), &Some(now + Duration::seconds(5)));
+        assert_eq!(
+            retried.get_orphaned(),
+            &Some(now + Duration::seconds(5) + Duration::seconds(60))
+        );
+        assert_eq!(retried.get_path(), "STANDALONE#queue:1".to_string());
+
+        let mut item = retried;
+        item.start_attempt();
+        let failed = item.retry_or_fail(now, 5);
+        assert_eq!(failed.get_status(), &QueueItemStatus::Failed);
+        assert_eq!(failed.get_attempts(), 2);
+        assert_eq!(failed.get_not_before(), &None);
+        assert_eq!(
+            failed.get_path(),
+            "dead_letter:STANDALONE#queue:1".to_string()
+        );
+
+        // Items without max_attempts are retried by default
+        let mut item = QueueItem::new(
+            "STANDALONE#queue:2".to_string(),
+            QueueItemStatus::Pending,
+            0,
+            None,
+            None,
+            None,
+        );
+        for attempt in 1..QUEUE_DEFAULT_MAX_ATTEMPTS {
+            item.start_attempt();
+            item = item.retry_or_fail(now, 5);
+            assert_eq!(item.get_status(), &QueueItemStatus::Pending);
+            assert_eq!(item.get_attempts(), attempt);
+        }
+        item.start_attempt();
+        let failed = item.retry_or_fail(now, 5);
+        assert_eq!(failed.get_status(), &QueueItemStatus::Failed);
+
+        Ok(())
+    }
 }
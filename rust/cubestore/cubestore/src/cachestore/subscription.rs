@@ -0,0 +1,321 @@
+use crate::cachestore::{CacheItem, CacheKeyEvent, CacheKeyEventType};
+use crate::util::aborting_join_handle::AbortingJoinHandle;
+use datafusion::cube_ext;
+use log::trace;
+use std::collections::HashSet;
+use std::sync::{Arc, RwLock};
+use tokio::sync::{broadcast, mpsc};
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum CacheKeyNotification {
+    Changed {
+        prefix: String,
+        path: String,
+        event: String,
+    },
+    /// Subscriber was not able to keep up with changes, some notifications were dropped.
+    Lagged { prefix: String, skipped: u64 },
+}
+
+impl CacheKeyNotification {
+    pub fn prefix(&self) -> &String {
+        match self {
+            CacheKeyNotification::Changed { prefix, .. } => prefix,
+            CacheKeyNotification::Lagged { prefix, .. } => prefix,
+        }
+    }
+}
+
+/// Publishing side of key-change notifications. Key changes don't go to the cachestore event
+/// channel: queue acks are processed from it, and a busy cache would make those listeners lag.
+/// Changes are collected only while there is at least one subscriber.
+#[derive(Clone, Default)]
+pub struct CacheKeyEventPublisher {
+    sender: Arc<RwLock<Option<broadcast::Sender<CacheKeyEvent>>>>,
+}
+
+impl CacheKeyEventPublisher {
+    pub fn new() -> Self {
+        Self {
+            sender: Arc::new(RwLock::new(None)),
+        }
+    }
+
+    pub fn set_sender(&self, sender: broadcast::Sender<CacheKeyEvent>) {
+        *self.sender.write().unwrap() = Some(sender);
+    }
+
+    /// Starts a batch for one write operation, it ignores all events if nobody is subscribed.
+    pub fn batch(&self) -> CacheKeyEventBatch {
+        let enabled = self
+            .sender
+            .read()
+            .unwrap()
+            .as_ref()
+            .map(|sender| sender.receiver_count() > 0)
+            .unwrap_or(false);
+
+        CacheKeyEventBatch {
+            enabled,
+            events: Vec::new(),
+        }
+    }
+
+    /// Must be called after the write operation for this batch was committed.
+    pub fn publish(&self, batch: CacheKeyEventBatch) {
+        if batch.events.is_empty() {
+            return;
+        }
+
+        if let Some(sender) = &*self.sender.read().unwrap() {
+            for event in batch.events {
+                // The last subscriber can be gone since the batch was started
+                let _ = sender.send(event);
+            }
+        }
+    }
+}
+
+pub struct CacheKeyEventBatch {
+    enabled: bool,
+    events: Vec<CacheKeyEvent>,
+}
+
+impl CacheKeyEventBatch {
+    pub fn add(&mut self, path: String, event_type: CacheKeyEventType) {
+        if self.enabled {
+            self.events.push(CacheKeyEvent::new(path, event_type));
+        }
+    }
+}
+
+/// Entry point for key-change subscriptions, receives changes from `CacheKeyEventPublisher`.
+pub struct CacheKeySubscriptions {
+    event_sender: broadcast::Sender<CacheKeyEvent>,
+    buffer_size: usize,
+}
+
+crate::di_service!(CacheKeySubscriptions, []);
+
+impl CacheKeySubscriptions {
+    pub fn new(event_sender: broadcast::Sender<CacheKeyEvent>, buffer_size: usize) -> Arc<Self> {
+        Arc::new(Self {
+            event_sender,
+            buffer_size,
+        })
+    }
+
+    pub fn subscriber(&self) -> CacheKeySubscriber {
+        CacheKeySubscriber::new(self.event_sender.subscribe(), self.buffer_size)
+    }
+}
+
+/// Per connection subscriber. Notifications are buffered up to `buffer_size`, when the buffer is
+/// full, forwarding is paused and the subscriber receives `Lagged` for skipped events.
+pub struct CacheKeySubscriber {
+    prefixes: Arc<RwLock<HashSet<String>>>,
+    receiver: mpsc::Receiver<CacheKeyNotification>,
+    _forward_handle: AbortingJoinHandle<()>,
+}
+
+impl CacheKeySubscriber {
+    fn new(event_receiver: broadcast::Receiver<CacheKeyEvent>, buffer_size: usize) -> Self {
+        let prefixes = Arc::new(RwLock::new(HashSet::new()));
+        let (sender, receiver) = mpsc::channel(buffer_size.max(1));
+
+        let forward_handle =
+            cube_ext::spawn(Self::forward_loop(event_receiver, prefixes.clone(), sender));
+
+        Self {
+            prefixes,
+            receiver,
+            _forward_handle: AbortingJoinHandle::new(forward_handle),
+        }
+    }
+
+    /// Returns false if there is already a subscription for this prefix.
+    pub fn subscribe(&self, prefix: String) -> bool {
+        let prefix = CacheItem::parse_path_to_prefix(prefix);
+        self.prefixes.write().unwrap().insert(prefix)
+    }
+
+    /// Returns false if there was no subscription for this prefix.
+    pub fn unsubscribe(&self, prefix: String) -> bool {
+        let prefix = CacheItem::parse_path_to_prefix(prefix);
+        self.prefixes.write().unwrap().remove(&prefix)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.prefixes.read().unwrap().is_empty()
+    }
+
+    pub async fn recv(&mut self) -> Option<CacheKeyNotification> {
+        self.receiver.recv().await
+    }
+
+    fn matching_prefixes(prefixes: &RwLock<HashSet<String>>, event: &CacheKeyEvent) -> Vec<String> {
+        let path = &event.path;
+
+        prefixes
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|prefix| {
+                event.event_type == CacheKeyEventType::Truncate
+                    || (path.len() > prefix.len()
+                        && path.starts_with(prefix.as_str())
+                        && path.as_bytes()[prefix.len()] == b':')
+            })
+            .cloned()
+            .collect()
+    }
+
+    async fn forward_loop(
+        mut event_receiver: broadcast::Receiver<CacheKeyEvent>,
+        prefixes: Arc<RwLock<HashSet<String>>>,
+        sender: mpsc::Sender<CacheKeyNotification>,
+    ) {
+        loop {
+            let notifications = match event_receiver.recv().await {
+                Ok(event) => Self::matching_prefixes(&prefixes, &event)
+                    .into_iter()
+                    .map(|prefix| CacheKeyNotification::Changed {
+                        prefix,
+                        path: event.path.clone(),
+                        event: event.event_type.to_string(),
+                    })
+                    .collect(),
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    trace!("Cache key subscriber is lagging for {} events", skipped);
+
+                    prefixes
+                        .read()
+                        .unwrap()
+                        .iter()
+                        .map(|prefix| CacheKeyNotification::Lagged {
+                            prefix: prefix.clone(),
+                            skipped,
+                        })
+                        .collect::<Vec<_>>()
+                }
+                Err(broadcast::error::RecvError::Closed) => return,
+            };
+
+            for notification in notifications {
+                // Waiting on a full buffer is the backpressure, events which arrive in the
+                // meantime are reported as lagged by the broadcast channel.
+                if sender.send(notification).await.is_err() {
+                    return;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set_event(path: &str) -> CacheKeyEvent {
+        CacheKeyEvent::new(path.to_string(), CacheKeyEventType::Set)
+    }
+
+    #[tokio::test]
+    async fn test_subscriber_prefix_matching() {
+        let (sender, _) = broadcast::channel(16);
+        let subscriptions = CacheKeySubscriptions::new(sender.clone(), 16);
+        let mut subscriber = subscriptions.subscriber();
+
+        assert!(subscriber.subscribe("users:*".to_string()));
+        assert!(!subscriber.subscribe("users".to_string()));
+
+        sender.send(set_event("usersx:1")).unwrap();
+        sender.send(set_event("orders:1")).unwrap();
+        sender.send(set_event("users:1")).unwrap();
+        sender
+            .send(CacheKeyEvent::new(
+                "users:nested:2".to_string(),
+                CacheKeyEventType::Expire,
+            ))
+            .unwrap();
+        sender
+            .send(CacheKeyEvent::new(
+                "*".to_string(),
+                CacheKeyEventType::Truncate,
+            ))
+            .unwrap();
+
+        assert_eq!(
+            subscriber.recv().await,
+            Some(CacheKeyNotification::Changed {
+                prefix: "users".to_string(),
+                path: "users:1".to_string(),
+                event: "set".to_string(),
+            })
+        );
+        assert_eq!(
+            subscriber.recv().await,
+            Some(CacheKeyNotification::Changed {
+                prefix: "users".to_string(),
+                path: "users:nested:2".to_string(),
+                event: "expire".to_string(),
+            })
+        );
+        assert_eq!(
+            subscriber.recv().await,
+            Some(CacheKeyNotification::Changed {
+                prefix: "users".to_string(),
+                path: "*".to_string(),
+                event: "truncate".to_string(),
+            })
+        );
+
+        assert!(subscriber.unsubscribe("users:".to_string()));
+        assert!(subscriber.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_subscriber_lagged() {
+        let (sender, _) = broadcast::channel(2);
+        let subscriptions = CacheKeySubscriptions::new(sender.clone(), 1);
+        let mut subscriber = subscriptions.subscriber();
+        subscriber.subscribe("users".to_string());
+
+        for i in 0..8 {
+            sender.send(set_event(&format!("users:{}", i))).unwrap();
+        }
+
+        let mut lagged = false;
+        while let Ok(Some(notification)) =
+            tokio::time::timeout(std::time::Duration::from_millis(100), subscriber.recv()).await
+        {
+            if let CacheKeyNotification::Lagged { prefix, skipped } = notification {
+                assert_eq!(prefix, "users");
+                assert!(skipped > 0);
+
+                lagged = true;
+            }
+        }
+
+        assert!(lagged);
+    }
+
+    #[tokio::test]
+    async fn test_publisher_without_subscribers() {
+        let (sender, _) = broadcast::channel(16);
+        let publisher = CacheKeyEventPublisher::new();
+        publisher.set_sender(sender.clone());
+
+        // Nobody is subscribed, events are not collected and not sent
+        let mut batch = publisher.batch();
+        batch.add("users:1".to_string(), CacheKeyEventType::Set);
+        assert!(batch.events.is_empty());
+        publisher.publish(batch);
+
+        let mut receiver = sender.subscribe();
+        let mut batch = publisher.batch();
+        batch.add("users:1".to_string(), CacheKeyEventType::Set);
+        publisher.publish(batch);
+        assert_eq!(receiver.recv().await.unwrap(), set_event("users:1"));
+    }
+}
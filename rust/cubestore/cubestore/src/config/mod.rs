@@ -3,8 +3,8 @@ pub mod injection;
 pub mod processing_loop;
 
 use crate::cachestore::{
-    CacheEvictionPolicy, CacheStore, CacheStoreSchedulerImpl, ClusterCacheStoreClient,
-    LazyRocksCacheStore,
+    CacheEvictionPolicy, CacheKeySubscriptions, CacheStore, CacheStoreSchedulerImpl,
+    ClusterCacheStoreClient, LazyRocksCacheStore,
 };
 use crate::cluster::ingestion::job_processor::{JobProcessor, JobProcessorImpl};
 use crate::cluster::rate_limiter::{BasicProcessRateLimiter, ProcessRateLimiter};
@@ -29,6 +29,7 @@ use crate::remotefs::minio::MINIORemoteFs;
 use crate::remotefs::queue::QueueRemoteFs;
 use crate::remotefs::s3::S3RemoteFs;
 use crate::remotefs::{LocalDirRemoteFs, RemoteFs};
+use crate::resp::RespServer;
 use crate::scheduler::SchedulerImpl;
 use crate::sql::cache::SqlResultCache;
 use crate::sql::{SqlService, SqlServiceImpl};
@@ -171,6 +172,12 @@ impl CubeServices {
                     mysql_server.processing_loop().await
                 }));
             }
+            if self.injector.has_service_typed::<RespServer>().await {
+                let resp_server = self.injector.get_service_typed::<RespServer>().await;
+                futures.push(cube_ext::spawn(async move {
+                    resp_server.processing_loop().await
+                }));
+            }
             if self.injector.has_service_typed::<HttpServer>().await {
                 let http_server = self.injector.get_service_typed::<HttpServer>().await;
                 futures.push(cube_ext::spawn(
@@ -226,6 +233,14 @@ impl CubeServices {
                 .await?;
         }
 
+        if self.injector.has_service_typed::<RespServer>().await {
+            self.injector
+                .get_service_typed::<RespServer>()
+                .await
+                .stop_processing()
+                .await?;
+        }
+
         if self.injector.has_service_typed::<HttpServer>().await {
             self.injector
                 .get_service_typed::<HttpServer>()
@@ -406,6 +421,8 @@ pub trait ConfigObj: DIService {
 
     fn http_bind_address(&self) -> &Option<String>;
 
+    fn redis_bind_address(&self) -> &Option<String>;
+
     fn query_timeout(&self) -> u64;
 
     fn not_used_timeout(&self) -> u64;
@@ -472,6 +489,10 @@ pub trait ConfigObj: DIService {
 
     fn cachestore_queue_results_expire(&self) -> u64;
 
+    fn cachestore_queue_retry_backoff_base(&self) -> u64;
+
+    fn cachestore_subscription_buffer_size(&self) -> usize;
+
     fn cachestore_metrics_interval(&self) -> u64;
 
     fn download_concurrency(&self) -> u64;
@@ -575,6 +596,7 @@ pub struct ConfigObjImpl {
     pub bind_address: Option<String>,
     pub status_bind_address: Option<String>,
     pub http_bind_address: Option<String>,
+    pub redis_bind_address: Option<String>,
     pub query_timeout: u64,
     /// Must be set to 2*query_timeout in prod, only for overrides in tests.
     pub not_used_timeout: u64,
@@ -601,6 +623,8 @@ pub struct ConfigObjImpl {
     pub cachestore_cache_compaction_trigger_size: u64,
     pub cachestore_cache_threshold_to_force_eviction: u8,
     pub cachestore_queue_results_expire: u64,
+    pub cachestore_queue_retry_backoff_base: u64,
+    pub cachestore_subscription_buffer_size: usize,
     pub cachestore_metrics_interval: u64,
     pub cachestore_cache_max_keys: u32,
     pub cachestore_cache_policy: CacheEvictionPolicy,
@@ -740,6 +764,10 @@ impl ConfigObj for ConfigObjImpl {
         &self.http_bind_address
     }
 
+    fn redis_bind_address(&self) -> &Option<String> {
+        &self.redis_bind_address
+    }
+
     fn query_timeout(&self) -> u64 {
         self.query_timeout
     }
@@ -868,6 +896,14 @@ impl ConfigObj for ConfigObjImpl {
         self.cachestore_queue_results_expire
     }
 
+    fn cachestore_queue_retry_backoff_base(&self) -> u64 {
+        self.cachestore_queue_retry_backoff_base
+    }
+
+    fn cachestore_subscription_buffer_size(&self) -> usize {
+        self.cachestore_subscription_buffer_size
+    }
+
     fn cachestore_metrics_interval(&self) -> u64 {
         self.cachestore_metrics_interval
     }
@@ -1326,6 +1362,9 @@ impl Config {
                 http_bind_address: Some(env::var("CUBESTORE_HTTP_BIND_ADDR").ok().unwrap_or(
                     format!("0.0.0.0:{}", env_parse("CUBESTORE_HTTP_PORT", 3030)),
                 )),
+                redis_bind_address: env::var("CUBESTORE_REDIS_BIND_ADDR").ok().or_else(|| {
+                    env_optparse::<u16>("CUBESTORE_REDIS_PORT").map(|v| format!("0.0.0.0:{}", v))
+                }),
                 query_timeout,
                 not_used_timeout: 2 * query_timeout,
                 in_memory_not_used_timeout: 30,
@@ -1394,6 +1433,16 @@ impl Config {
                     Some(60 * 5),
                     Some(1),
                 ),
+                cachestore_queue_retry_backoff_base: env_parse_duration(
+                    "CUBESTORE_QUEUE_RETRY_BACKOFF_BASE",
+                    5,
+                    Some(60 * 60),
+                    Some(1),
+                ),
+                cachestore_subscription_buffer_size: env_parse(
+                    "CUBESTORE_CACHE_SUBSCRIPTION_BUFFER_SIZE",
+                    1024,
+                ),
                 cachestore_metrics_interval: env_parse_duration(
                     "CUBESTORE_CACHESTORE_METRICS_LOOP",
                     15,
@@ -1597,6 +1646,7 @@ impl Config {
                 bind_address: None,
                 status_bind_address: None,
                 http_bind_address: None,
+                redis_bind_address: None,
                 query_timeout,
                 not_used_timeout: 2 * query_timeout,
                 in_memory_not_used_timeout: 30,
@@ -1618,6 +1668,8 @@ impl Config {
                 cachestore_cache_compaction_trigger_size: 4096 * 2 << 20,
                 cachestore_cache_threshold_to_force_eviction: 25,
                 cachestore_queue_results_expire: 90,
+                cachestore_queue_retry_backoff_base: 1,
+                cachestore_subscription_buffer_size: 1024,
                 cachestore_metrics_interval: 15,
                 cachestore_cache_max_keys: 100_000,
                 cachestore_cache_policy: CacheEvictionPolicy::SampledLru,
@@ -1883,6 +1935,9 @@ impl Config {
     pub async fn configure_cache_store(&self) {
         let (cachestore_event_sender, _) = broadcast::channel(2048); // TODO config
         let cachestore_event_sender_to_move = cachestore_event_sender.clone();
+        // Key changes have their own channel, the one above is shared with queue listeners
+        let (cache_key_event_sender, _) = broadcast::channel(2048);
+        let cache_key_event_sender_to_move = cache_key_event_sender.clone();
 
         if uses_remote_metastore(&self.injector).await {
             self.injector
@@ -1916,6 +1971,7 @@ impl Config {
                                 cachestore_fs,
                                 config,
                                 vec![cachestore_event_sender],
+                                cache_key_event_sender_to_move,
                             )
                             .await
                             .unwrap()
@@ -1925,6 +1981,7 @@ impl Config {
                                 cachestore_fs,
                                 config,
                                 vec![cachestore_event_sender],
+                                cache_key_event_sender_to_move,
                             )
                             .await
                             .unwrap()
@@ -1944,6 +2001,16 @@ impl Config {
                 ))
             })
             .await;
+
+        self.injector
+            .register_typed::<CacheKeySubscriptions, _, _, _>(async move |i| {
+                let config = i.get_service_typed::<dyn ConfigObj>().await;
+                CacheKeySubscriptions::new(
+                    cache_key_event_sender,
+                    config.cachestore_subscription_buffer_size(),
+                )
+            })
+            .await;
     }
 
     pub async fn configure_meta_store(&self) {
@@ -2323,9 +2390,27 @@ impl Config {
                         Duration::from_secs(config.drop_ws_complete_messages_after_secs()),
                         config.transport_max_message_size(),
                         config.transport_max_frame_size(),
+                        i.get_service_typed().await,
                     )
                 })
                 .await;
+
+            if self.config_obj.redis_bind_address().is_some() {
+                self.injector
+                    .register_typed::<RespServer, _, _, _>(async move |i| {
+                        RespServer::new(
+                            i.get_service_typed::<dyn ConfigObj>()
+                                .await
+                                .redis_bind_address()
+                                .as_ref()
+                                .unwrap()
+                                .to_string(),
+                            i.get_service_typed().await,
+                            i.get_service_typed().await,
+                        )
+                    })
+                    .await;
+            }
         }
     }
 
@@ -4,6 +4,9 @@ use std::sync::Arc;
 
 use warp::{Filter, Rejection, Reply};
 
+use crate::cachestore::{
+    CacheItem, CacheKeyNotification, CacheKeySubscriber, CacheKeySubscriptions,
+};
 use crate::codegen::{
     root_as_http_message, HttpColumnValue, HttpColumnValueArgs, HttpError, HttpErrorArgs,
     HttpMessageArgs, HttpQuery, HttpQueryArgs, HttpResultSet, HttpResultSetArgs, HttpRow,
@@ -11,6 +14,7 @@ use crate::codegen::{
 };
 use crate::metastore::{Column, ColumnType, ImportFormat};
 use crate::mysql::SqlAuthService;
+use crate::sql::parser::{CacheCommand, CubeStoreParser, Statement as CubeStoreStatement};
 use crate::sql::{InlineTable, InlineTables, SqlQueryContext, SqlService};
 use crate::store::DataFrame;
 use crate::table::{Row, TableValue};
@@ -52,6 +56,7 @@ pub struct HttpServer {
     cancel_token: CancellationToken,
     max_message_size: usize,
     max_frame_size: usize,
+    cache_subscriptions: Arc<CacheKeySubscriptions>,
 }
 
 crate::di_service!(HttpServer, []);
@@ -85,6 +90,7 @@ impl HttpServer {
         drop_complete_messages_after: Duration,
         max_message_size: usize,
         max_frame_size: usize,
+        cache_subscriptions: Arc<CacheKeySubscriptions>,
     ) -> Arc<Self> {
         Arc::new(Self {
             bind_address,
@@ -95,6 +101,7 @@ impl HttpServer {
             drop_complete_messages_after,
             max_message_size,
             max_frame_size,
+            cache_subscriptions,
             worker_loop: WorkerLoop::new("HttpServer message processing"),
             drop_orphaned_messages_loop: WorkerLoop::new("HttpServer drop orphaned messages"),
             cancel_token: CancellationToken::new(),
@@ -129,15 +136,21 @@ impl HttpServer {
         let context_filter_to_move = context_filter.clone();
         let max_frame_size = self.max_frame_size.clone();
         let max_message_size = self.max_message_size.clone();
+        let cache_subscriptions = self.cache_subscriptions.clone();
+        let cache_subscriptions_filter = warp::any().map(move || cache_subscriptions.clone());
 
         let query_route = warp::path!("ws")
             .and(context_filter_to_move)
+            .and(cache_subscriptions_filter)
             .and(warp::ws::ws())
-            .and_then(move |tx: mpsc::Sender<(mpsc::Sender<Arc<HttpMessage>>, SqlQueryContext, HttpMessage)>, sql_query_context: SqlQueryContext, ws: Ws| async move {
+            .and_then(move |tx: mpsc::Sender<(mpsc::Sender<Arc<HttpMessage>>, SqlQueryContext, HttpMessage)>, sql_query_context: SqlQueryContext, cache_subscriptions: Arc<CacheKeySubscriptions>, ws: Ws| async move {
                 let tx_to_move = tx.clone();
                 let sql_query_context = sql_query_context.clone();
                 Result::<_, Rejection>::Ok(ws.max_frame_size(max_frame_size).max_message_size(max_message_size).on_upgrade(async move |mut web_socket| {
                     let (response_tx, mut response_rx) = mpsc::channel::<Arc<HttpMessage>>(10000);
+                    // Subscriber is created on the first CACHE SUBSCRIBE and lives with the connection
+                    let mut cache_subscriber: Option<CacheKeySubscriber> = None;
+                    let mut cache_subscription_ids = HashMap::<String, (u32, Option<String>)>::new();
                     loop {
                         tokio::select! {
                             Some(res) = response_rx.recv() => {
@@ -151,6 +164,15 @@ impl HttpServer {
                                    break;
                                 }
                             }
+                            Some(notification) = HttpServer::next_cache_notification(&mut cache_subscriber) => {
+                                if let Some((message_id, connection_id)) = cache_subscription_ids.get(notification.prefix()) {
+                                    let message = HttpServer::cache_notification_message(*message_id, connection_id.clone(), notification);
+                                    let send_res = web_socket.send(Message::binary(message.bytes())).await;
+                                    if let Err(e) = send_res {
+                                        error!("Websocket cache notification send error: {:?}", e)
+                                    }
+                                }
+                            }
                             Some(msg) = web_socket.next() => {
                                 match msg {
                                     Err(e) => {
@@ -165,6 +187,21 @@ impl HttpServer {
                                                     trace!("Received web socket message");
                                                     let message_id = msg.message_id;
                                                     let connection_id = msg.connection_id.clone();
+                                                    if let Some(command) = HttpServer::cache_subscription_command(&msg.command) {
+                                                        let response = HttpServer::process_cache_subscription(
+                                                            &cache_subscriptions,
+                                                            &mut cache_subscriber,
+                                                            &mut cache_subscription_ids,
+                                                            message_id,
+                                                            connection_id,
+                                                            command,
+                                                        );
+                                                        let send_res = web_socket.send(Message::binary(response.bytes())).await;
+                                                        if let Err(e) = send_res {
+                                                            error!("Websocket message send error: {:?}", e)
+                                                        }
+                                                        continue;
+                                                    }
                                                     // TODO use timeout instead of try send for burst control however try_send is safer for now
                                                     if let Err(e) = tx_to_move.try_send((response_tx.clone(), sql_query_context.clone(), msg)) {
                                                         error!("Websocket channel error: {:?}", e);
@@ -541,6 +578,128 @@ impl HttpServer {
         }
     }
 
+    /// CACHE SUBSCRIBE/UNSUBSCRIBE are bound to the web socket connection, so they are handled
+    /// in place instead of going through the processing loop.
+    fn cache_subscription_command(command: &HttpCommand) -> Option<CacheCommand> {
+        let query = if let HttpCommand::Query { query, .. } = command {
+            query.trim_start()
+        } else {
+            return None;
+        };
+
+        // Fast path to skip parsing for regular queries
+        if !query
+            .get(0..5)
+            .map(|s| s.eq_ignore_ascii_case("cache"))
+            .unwrap_or(false)
+        {
+            return None;
+        }
+
+        match CubeStoreParser::new(query).and_then(|mut parser| parser.parse_statement()) {
+            Ok(CubeStoreStatement::Cache(
+                command @ (CacheCommand::Subscribe { .. } | CacheCommand::Unsubscribe { .. }),
+            )) => Some(command),
+            _ => None,
+        }
+    }
+
+    async fn next_cache_notification(
+        subscriber: &mut Option<CacheKeySubscriber>,
+    ) -> Option<CacheKeyNotification> {
+        if let Some(subscriber) = subscriber {
+            subscriber.recv().await
+        } else {
+            futures::future::pending().await
+        }
+    }
+
+    /// Notifications are sent with the message_id of the CACHE SUBSCRIBE message.
+    fn process_cache_subscription(
+        cache_subscriptions: &CacheKeySubscriptions,
+        subscriber: &mut Option<CacheKeySubscriber>,
+        subscription_ids: &mut HashMap<String, (u32, Option<String>)>,
+        message_id: u32,
+        connection_id: Option<String>,
+        command: CacheCommand,
+    ) -> HttpMessage {
+        let (event, prefix) = match command {
+            CacheCommand::Subscribe { prefix } => {
+                let prefix = CacheItem::parse_path_to_prefix(prefix.value);
+
+                subscriber
+                    .get_or_insert_with(|| cache_subscriptions.subscriber())
+                    .subscribe(prefix.clone());
+                subscription_ids.insert(prefix.clone(), (message_id, connection_id.clone()));
+
+                ("subscribed", prefix)
+            }
+            CacheCommand::Unsubscribe { prefix } => {
+                let prefix = CacheItem::parse_path_to_prefix(prefix.value);
+
+                if let Some(current) = subscriber {
+                    current.unsubscribe(prefix.clone());
+
+                    if current.is_empty() {
+                        *subscriber = None;
+                    }
+                }
+                subscription_ids.remove(&prefix);
+
+                ("unsubscribed", prefix)
+            }
+            x => {
+                return HttpMessage {
+                    message_id,
+                    connection_id,
+                    command: HttpCommand::Error {
+                        error: format!("Unexpected cache subscription command: {:?}", x),
+                    },
+                }
+            }
+        };
+
+        HttpMessage {
+            message_id,
+            connection_id,
+            command: HttpCommand::ResultSet {
+                data_frame: Self::cache_notification_data_frame(
+                    event.to_string(),
+                    TableValue::String(prefix),
+                ),
+            },
+        }
+    }
+
+    fn cache_notification_message(
+        message_id: u32,
+        connection_id: Option<String>,
+        notification: CacheKeyNotification,
+    ) -> HttpMessage {
+        let (event, key) = match notification {
+            CacheKeyNotification::Changed { path, event, .. } => (event, TableValue::String(path)),
+            CacheKeyNotification::Lagged { .. } => ("lagged".to_string(), TableValue::Null),
+        };
+
+        HttpMessage {
+            message_id,
+            connection_id,
+            command: HttpCommand::ResultSet {
+                data_frame: Self::cache_notification_data_frame(event, key),
+            },
+        }
+    }
+
+    fn cache_notification_data_frame(event: String, key: TableValue) -> Arc<DataFrame> {
+        Arc::new(DataFrame::new(
+            vec![
+                Column::new("event".to_string(), ColumnType::String, 0),
+                Column::new("key".to_string(), ColumnType::String, 1),
+            ],
+            vec![Row::new(vec![TableValue::String(event), key])],
+        ))
+    }
+
     pub async fn authorize(
         auth: Arc<dyn SqlAuthService>,
         auth_header: Option<String>,
@@ -857,6 +1016,7 @@ impl HttpMessage {
 
 #[cfg(test)]
 mod tests {
+    use crate::cachestore::{CacheKeyEvent, CacheKeyEventType, CacheKeySubscriptions};
     use crate::codegen::{HttpMessageArgs, HttpQuery, HttpQueryArgs, HttpTable, HttpTableArgs};
     use crate::config::{init_test_logger, Config};
     use crate::http::{HttpCommand, HttpMessage, HttpServer};
@@ -877,6 +1037,7 @@ mod tests {
     use std::sync::Arc;
     use std::time::Duration;
     use tokio::net::TcpStream;
+    use tokio::sync::broadcast;
     use tokio_tungstenite::tungstenite::Message;
     use tokio_tungstenite::{connect_async, MaybeTlsStream, WebSocketStream};
     use url::Url;
@@ -1077,6 +1238,7 @@ mod tests {
             Duration::from_millis(1000),
             config.transport_max_message_size(),
             config.transport_max_frame_size(),
+            CacheKeySubscriptions::new(broadcast::channel(16).0, 16),
         ));
         {
             let http_server = http_server.clone();
@@ -1234,4 +1396,134 @@ mod tests {
 
         http_server.stop_processing().await;
     }
+
+    #[tokio::test]
+    async fn ws_cache_subscribe_test() {
+        init_test_logger().await;
+
+        let mut auth = MockSqlAuthService::new();
+        auth.expect_authenticate().return_const(Ok(None));
+
+        let config = Config::test("ws_cache_subscribe_test").config_obj();
+        let (event_sender, _) = broadcast::channel(16);
+
+        let http_server = Arc::new(HttpServer::new(
+            "127.0.0.1:53032".to_string(),
+            Arc::new(auth),
+            Arc::new(SqlServiceMock {
+                message_counter: AtomicU64::new(0),
+            }),
+            Duration::from_millis(100),
+            Duration::from_millis(10000),
+            Duration::from_millis(1000),
+            config.transport_max_message_size(),
+            config.transport_max_frame_size(),
+            CacheKeySubscriptions::new(event_sender.clone(), 16),
+        ));
+        {
+            let http_server = http_server.clone();
+            cube_ext::spawn(async move { http_server.run_server().await });
+        }
+
+        tokio::time::sleep(Duration::from_secs(1)).await;
+
+        let (mut socket, _) = connect_async(Url::parse("ws://127.0.0.1:53032/ws").unwrap())
+            .await
+            .unwrap();
+
+        async fn send_query(
+            socket: &mut WebSocketStream<MaybeTlsStream<TcpStream>>,
+            message_id: u32,
+            query: &str,
+        ) {
+            socket
+                .send(Message::binary(
+                    HttpMessage {
+                        message_id,
+                        command: HttpCommand::Query {
+                            query: query.to_string(),
+                            inline_tables: vec![],
+                            trace_obj: None,
+                        },
+                        connection_id: Some("sub".to_string()),
+                    }
+                    .bytes(),
+                ))
+                .await
+                .unwrap();
+        }
+
+        async fn assert_notification(
+            socket: &mut WebSocketStream<MaybeTlsStream<TcpStream>>,
+            message_id: u32,
+            expected: Vec<TableValue>,
+        ) {
+            let msg = socket.next().await.unwrap().unwrap();
+            let message = HttpMessage::read(msg.into_data()).await.unwrap();
+            assert_eq!(message.message_id, message_id);
+
+            if let HttpCommand::ResultSet { data_frame } = message.command {
+                assert_eq!(data_frame.get_rows()[0].values(), &expected);
+            } else {
+                panic!("Result set expected, actual: {:?}", message.command);
+            }
+        }
+
+        send_query(&mut socket, 1, "CACHE SUBSCRIBE 'users:*'").await;
+        assert_notification(
+            &mut socket,
+            1,
+            vec![
+                TableValue::String("subscribed".to_string()),
+                TableValue::String("users".to_string()),
+            ],
+        )
+        .await;
+
+        for (path, event_type) in vec![
+            ("orders:1", CacheKeyEventType::Set),
+            ("users:1", CacheKeyEventType::Set),
+            ("users:2", CacheKeyEventType::Expire),
+        ] {
+            event_sender
+                .send(CacheKeyEvent::new(path.to_string(), event_type))
+                .unwrap();
+        }
+
+        assert_notification(
+            &mut socket,
+            1,
+            vec![
+                TableValue::String("set".to_string()),
+                TableValue::String("users:1".to_string()),
+            ],
+        )
+        .await;
+        assert_notification(
+            &mut socket,
+            1,
+            vec![
+                TableValue::String("expire".to_string()),
+                TableValue::String("users:2".to_string()),
+            ],
+        )
+        .await;
+
+        send_query(&mut socket, 2, "CACHE UNSUBSCRIBE 'users'").await;
+        assert_notification(
+            &mut socket,
+            2,
+            vec![
+                TableValue::String("unsubscribed".to_string()),
+                TableValue::String("users".to_string()),
+            ],
+        )
+        .await;
+
+        // Regular queries are still processed by SqlService
+        send_query(&mut socket, 3, "foo").await;
+        assert_notification(&mut socket, 3, vec![TableValue::String("0".to_string())]).await;
+
+        http_server.stop_processing().await;
+    }
 }
@@ -46,6 +46,7 @@ pub mod metastore;
 pub mod mysql;
 pub mod queryplanner;
 pub mod remotefs;
+pub mod resp;
 pub mod scheduler;
 pub mod shared;
 pub mod sql;
@@ -43,6 +43,13 @@ impl InfoSchemaTableDef for SystemQueueTableDef {
             ),
             Field::new("value", DataType::Utf8, false),
             Field::new("extra", DataType::Utf8, true),
+            Field::new(
+                "not_before",
+                DataType::Timestamp(TimeUnit::Nanosecond, None),
+                true,
+            ),
+            Field::new("attempts", DataType::Int64, false),
+            Field::new("max_attempts", DataType::Int64, true),
         ]
     }
 
@@ -124,6 +131,36 @@ impl InfoSchemaTableDef for SystemQueueTableDef {
                         .map(|row| row.item.get_row().get_extra().clone()),
                 ))
             }),
+            Box::new(|items| {
+                Arc::new(TimestampNanosecondArray::from(
+                    items
+                        .iter()
+                        .map(|row| {
+                            row.item
+                                .get_row()
+                                .get_not_before()
+                                .as_ref()
+                                .map(|v| v.timestamp_nanos())
+                        })
+                        .collect::<Vec<_>>(),
+                ))
+            }),
+            Box::new(|items| {
+                Arc::new(Int64Array::from(
+                    items
+                        .iter()
+                        .map(|row| row.item.get_row().get_attempts() as i64)
+                        .collect::<Vec<_>>(),
+                ))
+            }),
+            Box::new(|items| {
+                Arc::new(Int64Array::from(
+                    items
+                        .iter()
+                        .map(|row| row.item.get_row().get_max_attempts().map(|v| v as i64))
+                        .collect::<Vec<_>>(),
+                ))
+            }),
         ]
     }
 }
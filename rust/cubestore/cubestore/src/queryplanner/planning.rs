@@ -27,6 +27,7 @@ use datafusion::execution::context::ExecutionContextState;
 use datafusion::logical_plan::{DFSchemaRef, Expr, LogicalPlan, Operator, UserDefinedLogicalNode};
 use datafusion::physical_plan::aggregates::AggregateFunction as FusionAggregateFunction;
 use datafusion::physical_plan::empty::EmptyExec;
+use datafusion::physical_plan::functions::BuiltinScalarFunction;
 use datafusion::physical_plan::planner::ExtensionPlanner;
 use datafusion::physical_plan::{
     ExecutionPlan, OptimizerHints, Partitioning, PhysicalPlanner, SendableRecordBatchStream,
@@ -37,9 +38,10 @@ use itertools::{EitherOrBoth, Itertools};
 
 use crate::cluster::Cluster;
 use crate::metastore::multi_index::MultiPartition;
-use crate::metastore::table::{Table, TablePath};
+use crate::metastore::table::{AggregateColumn, Table, TablePath};
 use crate::metastore::{
-    AggregateFunction, Chunk, Column, IdRow, Index, IndexType, MetaStore, Partition, Schema,
+    AggregateFunction, Chunk, Column, ColumnType, IdRow, Index, IndexType, MetaStore, Partition,
+    Schema,
 };
 use crate::queryplanner::optimizations::rewrite_plan::{rewrite_plan, PlanRewriter};
 use crate::queryplanner::panic::{plan_panic_worker, PanicWorkerNode};
@@ -47,7 +49,7 @@ use crate::queryplanner::partition_filter::PartitionFilter;
 use crate::queryplanner::providers::InfoSchemaQueryCacheTableProvider;
 use crate::queryplanner::query_executor::{ClusterSendExec, CubeTable, InlineTableProvider};
 use crate::queryplanner::serialized_plan::{
-    IndexSnapshot, InlineSnapshot, PartitionSnapshot, SerializedPlan,
+    AggregateIndexMatch, IndexSnapshot, InlineSnapshot, PartitionSnapshot, SerializedPlan,
 };
 use crate::queryplanner::topk::{materialize_topk, plan_topk, ClusterAggregateTopK};
 use crate::queryplanner::{CubeTableLogical, InfoSchemaTableProvider};
@@ -56,6 +58,7 @@ use crate::CubeError;
 use datafusion::logical_plan;
 use datafusion::optimizer::utils::expr_to_columns;
 use datafusion::physical_plan::parquet::NoopParquetMetadataCache;
+use datafusion::scalar::ScalarValue;
 use serde::{Deserialize as SerdeDeser, Deserializer, Serialize as SerdeSer, Serializer};
 use serde_derive::Deserialize;
 use serde_derive::Serialize;
@@ -398,6 +401,8 @@ struct IndexConstraints {
     projection: Option<Vec<usize>>,
     filters: Vec<Expr>,
     aggregates: Vec<Expr>,
+    /// Group by expressions of the aggregation above the scan, if any.
+    group_expr: Option<Vec<Expr>>,
 }
 
 #[derive(Default)]
@@ -409,6 +414,7 @@ struct CollectConstraints {
 struct ConstraintsContext {
     sort_on: Option<SortColumns>,
     aggregates: Vec<Expr>,
+    group_expr: Option<Vec<Expr>>,
     order_col_names: Option<Vec<String>>,
 }
 
@@ -417,6 +423,7 @@ impl ConstraintsContext {
         Self {
             sort_on,
             aggregates: self.aggregates.clone(),
+            group_expr: self.group_expr.clone(),
             order_col_names: self.order_col_names.clone(),
         }
     }
@@ -424,6 +431,7 @@ impl ConstraintsContext {
         Self {
             sort_on: self.sort_on.clone(),
             aggregates: self.aggregates.clone(),
+            group_expr: self.group_expr.clone(),
             order_col_names: Some(order_col_names),
         }
     }
@@ -482,6 +490,7 @@ impl PlanRewriter for CollectConstraints {
                         projection: projection.clone(),
                         filters: filters.clone(),
                         aggregates: c.aggregates.clone(),
+                        group_expr: c.group_expr.clone(),
                     })
                 };
             }
@@ -516,6 +525,7 @@ impl PlanRewriter for CollectConstraints {
                 Some(ConstraintsContext {
                     sort_on,
                     aggregates: aggr_expr.to_vec(),
+                    group_expr: Some(group_expr.to_vec()),
                     order_col_names: current_context.order_col_names.clone(),
                 })
             }
@@ -573,6 +583,7 @@ impl PlanRewriter for CollectConstraints {
                 required: true,
             }),
             aggregates: Vec::new(),
+            group_expr: None,
             order_col_names: None,
         })
     }
@@ -594,6 +605,7 @@ impl PlanRewriter for CollectConstraints {
                 required: true,
             }),
             aggregates: Vec::new(),
+            group_expr: None,
             order_col_names: None,
         })
     }
@@ -939,73 +951,120 @@ struct IndexCandidate {
     pub partitioned_index: Option<IndexSnapshot>,
 }
 
-fn check_aggregates_expr(table: &IdRow<Table>, aggregates: &Vec<Expr>) -> bool {
-    let table_aggregates = table.get_row().aggregate_columns();
-
-    for aggr in aggregates.iter() {
-        match aggr {
-            Expr::AggregateFunction { fun, args, .. } => {
-                if args.len() != 1 {
-                    return false;
-                }
-
-                let aggr_fun = match fun {
-                    FusionAggregateFunction::Sum => Some(AggregateFunction::SUM),
-                    FusionAggregateFunction::Max => Some(AggregateFunction::MAX),
-                    FusionAggregateFunction::Min => Some(AggregateFunction::MIN),
-                    _ => None,
-                };
+/// Checks whether the aggregate index can answer the aggregation above the scan by
+/// re-aggregating its rows. Group by expressions must be index dimensions or `date_trunc` of a
+/// timestamp dimension, filters can only reference dimensions and every aggregate has to be an
+/// additive function over the matching aggregate column of the table.
+fn match_aggregate_index(
+    index: &IdRow<Index>,
+    table: &IdRow<Table>,
+    c: &IndexConstraints,
+    projection_columns: &Vec<Column>,
+    filter_columns: &HashSet<logical_plan::Column>,
+) -> Option<AggregateIndexMatch> {
+    let group_expr = c.group_expr.as_ref()?;
+    let dimensions = &index.get_row().get_columns()[..index.get_row().sort_key_size() as usize];
+    let is_dimension = |name: &String| dimensions.iter().any(|d| d.get_name() == name);
 
-                if aggr_fun.is_none() {
-                    return false;
-                }
+    let group_by = group_expr
+        .iter()
+        .map(|e| aggregate_index_group_expr(e, dimensions))
+        .collect::<Option<Vec<_>>>()?;
 
-                let aggr_fun = aggr_fun.unwrap();
+    let table_aggregates = table.get_row().aggregate_columns();
+    let measures = c
+        .aggregates
+        .iter()
+        .map(|e| aggregate_index_measure(e, &table_aggregates))
+        .collect::<Option<Vec<_>>>()?;
+
+    // Aggregate columns hold pre-aggregated values and can only be read through the matching
+    // aggregate function.
+    let projection_check = projection_columns.iter().all(|p| {
+        is_dimension(p.get_name())
+            || measures
+                .iter()
+                .any(|m| m.column().get_name() == p.get_name())
+    });
+    let filter_check = filter_columns.iter().all(|f| is_dimension(&f.name));
+    if !projection_check || !filter_check {
+        return None;
+    }
 
-                let col_match = match &args[0] {
-                    Expr::Column(col) => table_aggregates.iter().any(|ta| {
-                        ta.function() == &aggr_fun && ta.column().get_name() == &col.name
-                    }),
-                    _ => false,
-                };
+    Some(AggregateIndexMatch {
+        group_by,
+        measures: measures
+            .iter()
+            .map(|m| format!("{}({})", m.function(), m.column().get_name()))
+            .collect(),
+    })
+}
 
-                if !col_match {
-                    return false;
+fn aggregate_index_group_expr(e: &Expr, dimensions: &[Column]) -> Option<String> {
+    match e {
+        Expr::Alias(e, _) => aggregate_index_group_expr(e, dimensions),
+        Expr::Column(col) => dimensions
+            .iter()
+            .find(|d| d.get_name() == &col.name)
+            .map(|d| d.get_name().clone()),
+        Expr::ScalarFunction {
+            fun: BuiltinScalarFunction::DateTrunc,
+            args,
+        } if args.len() == 2 => match (&args[0], &args[1]) {
+            (Expr::Literal(ScalarValue::Utf8(Some(granularity))), Expr::Column(col)) => {
+                let dimension = dimensions.iter().find(|d| d.get_name() == &col.name)?;
+                if dimension.get_column_type() != &ColumnType::Timestamp {
+                    return None;
                 }
+                Some(format!(
+                    "date_trunc({}, {})",
+                    granularity.to_lowercase(),
+                    dimension.get_name()
+                ))
             }
-            Expr::AggregateUDF { fun, args } => {
-                if args.len() != 1 {
-                    return false;
-                }
-
-                let aggr_fun = match fun.name.to_uppercase().as_str() {
-                    "MERGE" => Some(AggregateFunction::MERGE),
-                    _ => None,
-                };
-
-                if aggr_fun.is_none() {
-                    return false;
-                }
-
-                let aggr_fun = aggr_fun.unwrap();
-
-                let col_match = match &args[0] {
-                    Expr::Column(col) => table_aggregates.iter().any(|ta| {
-                        ta.function() == &aggr_fun && ta.column().get_name() == &col.name
-                    }),
-                    _ => false,
-                };
+            _ => None,
+        },
+        _ => None,
+    }
+}
 
-                if !col_match {
-                    return false;
-                }
-            }
-            _ => {
-                return false;
-            }
-        };
+fn aggregate_index_measure(
+    e: &Expr,
+    table_aggregates: &Vec<AggregateColumn>,
+) -> Option<AggregateColumn> {
+    let (aggr_fun, args) = match e {
+        Expr::AggregateFunction {
+            fun,
+            args,
+            distinct: false,
+        } => {
+            let aggr_fun = match fun {
+                FusionAggregateFunction::Sum => AggregateFunction::SUM,
+                FusionAggregateFunction::Max => AggregateFunction::MAX,
+                FusionAggregateFunction::Min => AggregateFunction::MIN,
+                _ => return None,
+            };
+            (aggr_fun, args)
+        }
+        Expr::AggregateUDF { fun, args } => {
+            let aggr_fun = match fun.name.to_uppercase().as_str() {
+                "MERGE" => AggregateFunction::MERGE,
+                _ => return None,
+            };
+            (aggr_fun, args)
+        }
+        _ => return None,
+    };
+    if args.len() != 1 {
+        return None;
+    }
+    match &args[0] {
+        Expr::Column(col) => table_aggregates
+            .iter()
+            .find(|ta| ta.function() == &aggr_fun && ta.column().get_name() == &col.name)
+            .cloned(),
+        _ => None,
     }
-    true
 }
 
 // Picks the index, but not partitions snapshots.
@@ -1017,8 +1076,7 @@ async fn pick_index(
 ) -> Result<IndexCandidate, DataFusionError> {
     let sort_on = c.sort_on.as_ref().map(|sc| (&sc.sort_on, sc.required));
 
-    let aggr_index_allowed = check_aggregates_expr(&table, &c.aggregates);
-
+    let mut aggregate_matches = HashMap::new();
     let default_index = indices.iter().next().expect("no default index");
     let (index, mut partitioned_index, sort_on) = if let Some(projection_column_indices) =
         &c.projection
@@ -1030,41 +1088,28 @@ async fn pick_index(
             expr_to_columns(f, &mut filter_columns)?;
         }
 
+        for i in indices.iter() {
+            if i.get_row().get_type() != IndexType::Aggregate {
+                continue;
+            }
+            if let Some(m) =
+                match_aggregate_index(i, &table, c, &projection_columns, &filter_columns)
+            {
+                aggregate_matches.insert(i.get_id(), m);
+            }
+        }
+        // Aggregate indexes are usable only when they can answer the aggregation.
+        let eligible_indices = indices.iter().skip(1).filter(|i| {
+            i.get_row().get_type() != IndexType::Aggregate
+                || aggregate_matches.contains_key(&i.get_id())
+        });
+
         // Skipping default index
-        let filtered_by_sort_on = indices.iter().skip(1).filter(|i| {
+        let filtered_by_sort_on = eligible_indices.clone().filter(|i| {
             if let Some((join_on_columns, required)) = sort_on.as_ref() {
                 if i.get_row().sort_key_size() < (join_on_columns.len() as u64) {
                     return false;
                 }
-                let all_columns_in_index = match i.get_row().get_type() {
-                    IndexType::Aggregate => {
-                        if aggr_index_allowed {
-                            let projection_check = projection_columns.iter().all(|c| {
-                                i.get_row()
-                                    .get_columns()
-                                    .iter()
-                                    .find(|ic| ic.get_name() == c.get_name())
-                                    .is_some()
-                            });
-                            let filter_check = filter_columns.iter().all(|c| {
-                                i.get_row()
-                                    .get_columns()
-                                    .iter()
-                                    .find(|ic| ic.get_name() == &c.name)
-                                    .is_some()
-                            });
-
-                            projection_check && filter_check
-                        } else {
-                            false
-                        }
-                    }
-                    _ => true,
-                };
-
-                if !all_columns_in_index {
-                    return false;
-                }
                 let join_columns_in_index = join_on_columns
                     .iter()
                     .map(|c| {
@@ -1132,7 +1177,7 @@ async fn pick_index(
             } else {
                 let optimal = optimal_index_by_score(
                     // Skipping default index
-                    indices.iter().skip(1),
+                    eligible_indices,
                     &projection_columns,
                     &filter_columns,
                 );
@@ -1184,6 +1229,7 @@ async fn pick_index(
                 schema: schema.clone(),
             },
             sort_on: index_sort_on,
+            aggregate_match: aggregate_matches.get(&index.get_id()).cloned(),
         }
     };
     Ok(IndexCandidate {
@@ -52,6 +52,7 @@ pub struct PPOptions {
     // Applies only to physical plan.
     pub show_output_hints: bool,
     pub show_check_memory_nodes: bool,
+    pub show_aggregate_index_match: bool,
 }
 
 pub fn pp_phys_plan(p: &dyn ExecutionPlan) -> String {
@@ -149,7 +150,7 @@ pub fn pp_plan_ext(p: &LogicalPlan, opts: &PPOptions) -> String {
                     self.output += &format!(
                         "Scan {}, source: {}",
                         table_name,
-                        pp_source(source.as_ref())
+                        pp_source(source.as_ref(), self.opts)
                     );
                     if projected_schema.fields().len() != source.schema().fields().len() {
                         self.output += &format!(
@@ -233,7 +234,7 @@ pub fn pp_plan_ext(p: &LogicalPlan, opts: &PPOptions) -> String {
     }
 }
 
-fn pp_index(index: &IndexSnapshot) -> String {
+fn pp_index(index: &IndexSnapshot, o: &PPOptions) -> String {
     let mut r = format!(
         "{}:{}:{:?}",
         index.index.get_row().get_name(),
@@ -247,14 +248,23 @@ fn pp_index(index: &IndexSnapshot) -> String {
     if let Some(so) = &index.sort_on {
         r += &format!(":sort_on[{}]", so.join(", "))
     }
+    if o.show_aggregate_index_match {
+        if let Some(m) = &index.aggregate_match {
+            r += &format!(
+                ":rollup[group_by: {}; measures: {}]",
+                m.group_by.join(", "),
+                m.measures.join(", ")
+            )
+        }
+    }
     r
 }
 
-fn pp_source(t: &dyn TableProvider) -> String {
+fn pp_source(t: &dyn TableProvider, o: &PPOptions) -> String {
     if t.as_any().is::<CubeTableLogical>() {
         "CubeTableLogical".to_string()
     } else if let Some(t) = t.as_any().downcast_ref::<CubeTable>() {
-        format!("CubeTable(index: {})", pp_index(t.index_snapshot()))
+        format!("CubeTable(index: {})", pp_index(t.index_snapshot(), o))
     } else if let Some(t) = t.as_any().downcast_ref::<InlineTableProvider>() {
         format!("InlineTableProvider(data: {} rows)", t.get_data().len())
     } else {
@@ -305,7 +315,7 @@ fn pp_phys_plan_indented(p: &dyn ExecutionPlan, indent: usize, o: &PPOptions, ou
 
         let a = p.as_any();
         if let Some(t) = a.downcast_ref::<CubeTableExec>() {
-            *out += &format!("Scan, index: {}", pp_index(&t.index_snapshot));
+            *out += &format!("Scan, index: {}", pp_index(&t.index_snapshot, o));
             if t.index_snapshot.index.get_row().columns().len() == t.schema().fields().len() {
                 *out += ", fields: *";
             } else {
@@ -8,7 +8,7 @@ use crate::queryplanner::filter_by_key_range::FilterByKeyRangeExec;
 use crate::queryplanner::optimizations::CubeQueryPlanner;
 use crate::queryplanner::physical_plan_flags::PhysicalPlanFlags;
 use crate::queryplanner::planning::{get_worker_plan, Snapshot, Snapshots};
-use crate::queryplanner::pretty_printers::{pp_phys_plan, pp_plan};
+use crate::queryplanner::pretty_printers::{pp_phys_plan, pp_phys_plan_ext, pp_plan, PPOptions};
 use crate::queryplanner::serialized_plan::{IndexSnapshot, RowFilter, RowRange, SerializedPlan};
 use crate::queryplanner::trace_data_loaded::DataLoadedSize;
 use crate::store::DataFrame;
@@ -308,7 +308,9 @@ impl QueryExecutor for QueryExecutorImpl {
             ));
         }
 
-        Ok(pp_phys_plan(worker_plan.as_ref()))
+        let mut opts = PPOptions::default();
+        opts.show_aggregate_index_match = true;
+        Ok(pp_phys_plan_ext(worker_plan.as_ref(), &opts))
     }
 }
 
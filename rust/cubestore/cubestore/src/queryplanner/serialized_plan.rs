@@ -90,6 +90,8 @@ pub struct IndexSnapshot {
     pub index: IdRow<Index>,
     pub partitions: Vec<PartitionSnapshot>,
     pub sort_on: Option<Vec<String>>,
+    /// Set when an aggregate index was picked to answer the query by re-aggregating its rows.
+    pub aggregate_match: Option<AggregateIndexMatch>,
 }
 
 impl IndexSnapshot {
@@ -112,6 +114,18 @@ impl IndexSnapshot {
     pub fn sort_on(&self) -> Option<&Vec<String>> {
         self.sort_on.as_ref()
     }
+
+    pub fn aggregate_match(&self) -> Option<&AggregateIndexMatch> {
+        self.aggregate_match.as_ref()
+    }
+}
+
+/// Describes how the query is answered from an aggregate index: group by expressions over the
+/// index dimensions and the measures re-aggregated from the index aggregate columns.
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub struct AggregateIndexMatch {
+    pub group_by: Vec<String>,
+    pub measures: Vec<String>,
 }
 
 #[derive(Clone, Serialize, Deserialize, Debug)]
@@ -775,6 +775,14 @@ impl CacheStore for CacheStoreMock {
         panic!("CacheStore mock!")
     }
 
+    async fn cache_mdelete(&self, _keys: Vec<String>) -> Result<u64, CubeError> {
+        panic!("CacheStore mock!")
+    }
+
+    async fn cache_expire(&self, _key: String, _ttl: u32) -> Result<bool, CubeError> {
+        panic!("CacheStore mock!")
+    }
+
     async fn cache_get(&self, _key: String) -> Result<Option<IdRow<CacheItem>>, CubeError> {
         panic!("CacheStore mock!")
     }
@@ -787,6 +795,33 @@ impl CacheStore for CacheStoreMock {
         panic!("CacheStore mock!")
     }
 
+    async fn cache_cas(
+        &self,
+        _item: CacheItem,
+        _expected: Option<String>,
+    ) -> Result<bool, CubeError> {
+        panic!("CacheStore mock!")
+    }
+
+    async fn cache_mset(&self, _items: Vec<CacheItem>) -> Result<(), CubeError> {
+        panic!("CacheStore mock!")
+    }
+
+    async fn cache_mget(
+        &self,
+        _keys: Vec<String>,
+    ) -> Result<Vec<Option<IdRow<CacheItem>>>, CubeError> {
+        panic!("CacheStore mock!")
+    }
+
+    async fn cache_lock(&self, _key: String, _ttl: u32) -> Result<Option<u64>, CubeError> {
+        panic!("CacheStore mock!")
+    }
+
+    async fn cache_unlock(&self, _key: String, _token: u64) -> Result<bool, CubeError> {
+        panic!("CacheStore mock!")
+    }
+
     async fn queue_all(&self, _limit: Option<usize>) -> Result<Vec<QueueAllItem>, CubeError> {
         panic!("CacheStore mock!")
     }
@@ -856,6 +891,10 @@ impl CacheStore for CacheStoreMock {
         panic!("CacheStore mock!")
     }
 
+    async fn queue_nack(&self, _key: QueueKey) -> Result<bool, CubeError> {
+        panic!("CacheStore mock!")
+    }
+
     async fn queue_result_by_path(
         &self,
         _path: String,
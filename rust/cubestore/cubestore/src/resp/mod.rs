@@ -0,0 +1,924 @@
+mod protocol;
+
+pub use protocol::{read_command, RespValue, RespVersion};
+
+use crate::cachestore::{CacheItem, CacheStore, QueueAddPayload, QueueKey};
+use crate::config::processing_loop::ProcessingLoop;
+use crate::mysql::SqlAuthService;
+use crate::table::{Row, TableValue};
+use crate::CubeError;
+use async_trait::async_trait;
+use chrono::Utc;
+use datafusion::cube_ext;
+use log::{error, info};
+use std::sync::Arc;
+use tokio::io::{AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::{watch, RwLock};
+
+/// Redis compatible (RESP2/RESP3) front-end for the cache and queue from CacheStore.
+/// Keys are mapped to CacheStore paths as is, `prefix:key`.
+pub struct RespServer {
+    address: String,
+    cachestore: Arc<dyn CacheStore>,
+    auth: Arc<dyn SqlAuthService>,
+    close_socket_rx: RwLock<watch::Receiver<bool>>,
+    close_socket_tx: watch::Sender<bool>,
+}
+
+crate::di_service!(RespServer, []);
+
+#[async_trait]
+impl ProcessingLoop for RespServer {
+    async fn processing_loop(&self) -> Result<(), CubeError> {
+        let listener = TcpListener::bind(self.address.clone()).await?;
+
+        info!("Redis port open on {}", self.address);
+
+        loop {
+            let mut stop_receiver = self.close_socket_rx.write().await;
+            let (socket, _) = tokio::select! {
+                res = stop_receiver.changed() => {
+                    if res.is_err() || *stop_receiver.borrow() {
+                        return Ok(());
+                    } else {
+                        continue;
+                    }
+                }
+                accept_res = listener.accept() => {
+                    match accept_res {
+                        Ok(res) => res,
+                        Err(err) => {
+                            error!("Network error: {}", err);
+                            continue;
+                        }
+                    }
+                }
+            };
+
+            let cachestore = self.cachestore.clone();
+            let auth = self.auth.clone();
+            cube_ext::spawn(async move {
+                if let Err(e) = RespConnection::run(cachestore, auth, socket).await {
+                    error!("Error during processing Redis connection: {}", e);
+                }
+            });
+        }
+    }
+
+    async fn stop_processing(&self) -> Result<(), CubeError> {
+        self.close_socket_tx.send(true)?;
+        Ok(())
+    }
+}
+
+impl RespServer {
+    pub fn new(
+        address: String,
+        cachestore: Arc<dyn CacheStore>,
+        auth: Arc<dyn SqlAuthService>,
+    ) -> Arc<Self> {
+        let (close_socket_tx, close_socket_rx) = watch::channel(false);
+        Arc::new(Self {
+            address,
+            cachestore,
+            auth,
+            close_socket_rx: RwLock::new(close_socket_rx),
+            close_socket_tx,
+        })
+    }
+}
+
+struct RespConnection {
+    cachestore: Arc<dyn CacheStore>,
+    auth: Arc<dyn SqlAuthService>,
+    version: RespVersion,
+    authenticated: bool,
+}
+
+struct RespCommand {
+    name: String,
+    args: Vec<Vec<u8>>,
+}
+
+impl RespCommand {
+    fn new(mut args: Vec<Vec<u8>>) -> Self {
+        let name = String::from_utf8_lossy(&args.remove(0)).to_lowercase();
+
+        Self { name, args }
+    }
+
+    fn expect_args(&self, min: usize, max: Option<usize>) -> Result<(), CubeError> {
+        if self.args.len() < min || max.map(|max| self.args.len() > max).unwrap_or(false) {
+            return Err(CubeError::user(format!(
+                "wrong number of arguments for '{}' command",
+                self.name
+            )));
+        }
+
+        Ok(())
+    }
+
+    fn string(&self, idx: usize) -> Result<String, CubeError> {
+        Ok(String::from_utf8(self.args[idx].clone())?)
+    }
+
+    fn integer(&self, idx: usize) -> Result<i64, CubeError> {
+        self.string(idx)?
+            .parse::<i64>()
+            .map_err(|_| CubeError::user("value is not an integer or out of range".to_string()))
+    }
+
+    fn unsigned(&self, idx: usize) -> Result<u32, CubeError> {
+        u32::try_from(self.integer(idx)?)
+            .map_err(|_| CubeError::user("value is out of range, must be positive".to_string()))
+    }
+
+    fn option(&self, idx: usize) -> Result<String, CubeError> {
+        Ok(self.string(idx)?.to_lowercase())
+    }
+}
+
+impl RespConnection {
+    async fn run(
+        cachestore: Arc<dyn CacheStore>,
+        auth: Arc<dyn SqlAuthService>,
+        socket: TcpStream,
+    ) -> Result<(), CubeError> {
+        let (read, mut write) = socket.into_split();
+        let mut reader = BufReader::new(read);
+
+        let authenticated = auth.authenticate(None).await?.is_none();
+        let mut connection = RespConnection {
+            cachestore,
+            auth,
+            version: RespVersion::Resp2,
+            authenticated,
+        };
+
+        loop {
+            let (reply, close) = match read_command(&mut reader).await {
+                Ok(Some(args)) if args.is_empty() => continue,
+                Ok(Some(args)) => connection.execute(RespCommand::new(args)).await,
+                Ok(None) => return Ok(()),
+                // Protocol errors are not recoverable, because stream position is unknown
+                Err(e) => (RespValue::error(format!("ERR {}", e.message)), true),
+            };
+
+            let mut buf = Vec::new();
+            reply.encode(connection.version, &mut buf);
+            write.write_all(&buf).await?;
+
+            if close {
+                return Ok(());
+            }
+        }
+    }
+
+    async fn execute(&mut self, command: RespCommand) -> (RespValue, bool) {
+        match command.name.as_str() {
+            "quit" => return (RespValue::ok(), true),
+            "auth" | "hello" => {}
+            _ if !self.authenticated => {
+                return (
+                    RespValue::error("NOAUTH Authentication required.".to_string()),
+                    false,
+                )
+            }
+            _ => {}
+        };
+
+        match self.execute_command(command).await {
+            Ok(reply) => (reply, false),
+            Err(e) => (RespValue::error(format!("ERR {}", e.message)), false),
+        }
+    }
+
+    async fn execute_command(&mut self, command: RespCommand) -> Result<RespValue, CubeError> {
+        match command.name.as_str() {
+            "ping" => {
+                command.expect_args(0, Some(1))?;
+                if command.args.is_empty() {
+                    Ok(RespValue::SimpleString("PONG".to_string()))
+                } else {
+                    Ok(RespValue::BulkString(command.args[0].clone()))
+                }
+            }
+            "echo" => {
+                command.expect_args(1, Some(1))?;
+                Ok(RespValue::BulkString(command.args[0].clone()))
+            }
+            "hello" => self.hello(command).await,
+            "auth" => {
+                command.expect_args(1, Some(2))?;
+                let (user, password) = if command.args.len() == 2 {
+                    (Some(command.string(0)?), command.string(1)?)
+                } else {
+                    (None, command.string(0)?)
+                };
+
+                self.authenticate(user, password).await
+            }
+            "select" => {
+                command.expect_args(1, Some(1))?;
+                if command.integer(0)? != 0 {
+                    return Err(CubeError::user("DB index is out of range".to_string()));
+                }
+
+                Ok(RespValue::ok())
+            }
+            // redis-cli and client libraries call them on connect, there is nothing to configure
+            "client" => Ok(RespValue::ok()),
+            "command" => Ok(RespValue::Array(vec![])),
+            "get" => {
+                command.expect_args(1, Some(1))?;
+                Ok(match self.cache_get(command.string(0)?).await? {
+                    Some(item) => RespValue::bulk(item.value),
+                    None => RespValue::Null,
+                })
+            }
+            "set" => self.set(command).await,
+            "del" => {
+                command.expect_args(1, None)?;
+                let keys = (0..command.args.len())
+                    .map(|idx| command.string(idx))
+                    .collect::<Result<Vec<_>, _>>()?;
+                let deleted = self.cachestore.cache_mdelete(keys).await?;
+
+                Ok(RespValue::Integer(deleted as i64))
+            }
+            "exists" => {
+                command.expect_args(1, None)?;
+                let mut exists = 0;
+                for idx in 0..command.args.len() {
+                    if self.cache_get(command.string(idx)?).await?.is_some() {
+                        exists += 1;
+                    }
+                }
+
+                Ok(RespValue::Integer(exists))
+            }
+            "incr" => {
+                command.expect_args(1, Some(1))?;
+                let row = self.cachestore.cache_incr(command.string(0)?).await?;
+                let value = row.get_row().get_value().parse::<i64>().map_err(|_| {
+                    CubeError::user("value is not an integer or out of range".to_string())
+                })?;
+
+                Ok(RespValue::Integer(value))
+            }
+            "expire" => {
+                command.expect_args(2, Some(2))?;
+                let key = command.string(0)?;
+                let ttl = command.integer(1)?;
+
+                // Both branches are a single write, the key can't be changed in between
+                let changed = if ttl <= 0 {
+                    self.cachestore.cache_mdelete(vec![key]).await? > 0
+                } else {
+                    let ttl = u32::try_from(ttl).map_err(|_| {
+                        CubeError::user("invalid expire time in 'expire' command".to_string())
+                    })?;
+                    self.cachestore.cache_expire(key, ttl).await?
+                };
+
+                Ok(RespValue::Integer(changed as i64))
+            }
+            "ttl" => {
+                command.expect_args(1, Some(1))?;
+                Ok(match self.cache_get(command.string(0)?).await? {
+                    Some(item) => match item.get_expire() {
+                        Some(expire) => {
+                            RespValue::Integer((*expire - Utc::now()).num_seconds().max(0))
+                        }
+                        None => RespValue::Integer(-1),
+                    },
+                    None => RespValue::Integer(-2),
+                })
+            }
+            "scan" => {
+                command.expect_args(1, None)?;
+                if command.string(0)? != "0" {
+                    return Err(CubeError::user("invalid cursor".to_string()));
+                }
+
+                let mut pattern = "*".to_string();
+                let mut idx = 1;
+                while idx < command.args.len() {
+                    match command.option(idx)?.as_str() {
+                        "match" if idx + 1 < command.args.len() => {
+                            pattern = command.string(idx + 1)?;
+                        }
+                        // All keys are returned in one batch
+                        "count" if idx + 1 < command.args.len() => {
+                            command.integer(idx + 1)?;
+                        }
+                        _ => return Err(CubeError::user("syntax error".to_string())),
+                    }
+                    idx += 2;
+                }
+
+                let keys = self.keys(pattern).await?;
+                Ok(RespValue::Array(vec![
+                    RespValue::bulk("0".to_string()),
+                    keys,
+                ]))
+            }
+            "keys" => {
+                command.expect_args(1, Some(1))?;
+                self.keys(command.string(0)?).await
+            }
+            "cache.lock" => {
+                command.expect_args(2, Some(2))?;
+                let ttl = command.unsigned(1)?;
+                if ttl == 0 {
+                    return Err(CubeError::user(
+                        "invalid expire time in 'cache.lock' command".to_string(),
+                    ));
+                }
+
+                Ok(
+                    match self.cachestore.cache_lock(command.string(0)?, ttl).await? {
+                        Some(token) => RespValue::bulk(token.to_string()),
+                        None => RespValue::Null,
+                    },
+                )
+            }
+            "cache.unlock" => {
+                command.expect_args(2, Some(2))?;
+                let token = command.string(1)?.parse::<u64>().map_err(|_| {
+                    CubeError::user("value is not an integer or out of range".to_string())
+                })?;
+                let success = self
+                    .cachestore
+                    .cache_unlock(command.string(0)?, token)
+                    .await?;
+
+                Ok(RespValue::Integer(success as i64))
+            }
+            "queue.add" => self.queue_add(command).await,
+            "queue.retrieve" => {
+                command.expect_args(1, Some(3))?;
+                let concurrency = if command.args.len() == 3 {
+                    if command.option(1)? != "concurrency" {
+                        return Err(CubeError::user("syntax error".to_string()));
+                    }
+
+                    command.unsigned(2)?
+                } else {
+                    1
+                };
+
+                let response = self
+                    .cachestore
+                    .queue_retrieve_by_path(command.string(0)?, concurrency)
+                    .await?;
+
+                Ok(Self::rows_to_resp(response.into_queue_retrieve_rows(true)))
+            }
+            "queue.ack" => {
+                command.expect_args(1, Some(2))?;
+                let result = if command.args.len() == 2 {
+                    Some(command.string(1)?)
+                } else {
+                    None
+                };
+                let success = self
+                    .cachestore
+                    .queue_ack(QueueKey::ByPath(command.string(0)?), result)
+                    .await?;
+
+                Ok(RespValue::Integer(success as i64))
+            }
+            "queue.nack" => {
+                command.expect_args(1, Some(1))?;
+                let success = self
+                    .cachestore
+                    .queue_nack(QueueKey::ByPath(command.string(0)?))
+                    .await?;
+
+                Ok(RespValue::Integer(success as i64))
+            }
+            "queue.heartbeat" => {
+                command.expect_args(1, Some(1))?;
+                self.cachestore
+                    .queue_heartbeat(QueueKey::ByPath(command.string(0)?))
+                    .await?;
+
+                Ok(RespValue::ok())
+            }
+            "queue.get" => {
+                command.expect_args(1, Some(1))?;
+                let response = self
+                    .cachestore
+                    .queue_get(QueueKey::ByPath(command.string(0)?))
+                    .await?;
+
+                Ok(Self::rows_to_resp(
+                    response
+                        .map(|r| r.into_queue_get_row())
+                        .into_iter()
+                        .collect(),
+                ))
+            }
+            "queue.cancel" => {
+                command.expect_args(1, Some(1))?;
+                let response = self
+                    .cachestore
+                    .queue_cancel(QueueKey::ByPath(command.string(0)?))
+                    .await?;
+
+                Ok(Self::rows_to_resp(
+                    response
+                        .map(|r| r.into_queue_cancel_row())
+                        .into_iter()
+                        .collect(),
+                ))
+            }
+            "queue.result" => {
+                command.expect_args(1, Some(1))?;
+                let response = self
+                    .cachestore
+                    .queue_result_by_path(command.string(0)?)
+                    .await?;
+
+                Ok(Self::rows_to_resp(
+                    response
+                        .map(|r| r.into_queue_result_row())
+                        .into_iter()
+                        .collect(),
+                ))
+            }
+            _ => Err(CubeError::user(format!(
+                "unknown command '{}'",
+                command.name
+            ))),
+        }
+    }
+
+    async fn hello(&mut self, command: RespCommand) -> Result<RespValue, CubeError> {
+        let mut idx = 0;
+        if command.args.len() > 0 {
+            self.version = match command.integer(0)? {
+                2 => RespVersion::Resp2,
+                3 => RespVersion::Resp3,
+                _ => {
+                    return Ok(RespValue::error(
+                        "NOPROTO unsupported protocol version".to_string(),
+                    ))
+                }
+            };
+            idx += 1;
+        }
+
+        while idx < command.args.len() {
+            match command.option(idx)?.as_str() {
+                "auth" if idx + 2 < command.args.len() => {
+                    let reply = self
+                        .authenticate(Some(command.string(idx + 1)?), command.string(idx + 2)?)
+                        .await?;
+                    if let RespValue::Error(_) = reply {
+                        return Ok(reply);
+                    }
+                    idx += 3;
+                }
+                "setname" if idx + 1 < command.args.len() => {
+                    idx += 2;
+                }
+                _ => return Err(CubeError::user("syntax error".to_string())),
+            }
+        }
+
+        if !self.authenticated {
+            return Ok(RespValue::error(
+                "NOAUTH HELLO must be called with the client already authenticated".to_string(),
+            ));
+        }
+
+        Ok(RespValue::Map(vec![
+            (
+                RespValue::bulk("server".to_string()),
+                RespValue::bulk("cubestore".to_string()),
+            ),
+            (
+                RespValue::bulk("version".to_string()),
+                RespValue::bulk(env!("CARGO_PKG_VERSION").to_string()),
+            ),
+            (
+                RespValue::bulk("proto".to_string()),
+                RespValue::Integer(match self.version {
+                    RespVersion::Resp2 => 2,
+                    RespVersion::Resp3 => 3,
+                }),
+            ),
+            (
+                RespValue::bulk("mode".to_string()),
+                RespValue::bulk("standalone".to_string()),
+            ),
+            (
+                RespValue::bulk("modules".to_string()),
+                RespValue::Array(vec![]),
+            ),
+        ]))
+    }
+
+    async fn authenticate(
+        &mut self,
+        user: Option<String>,
+        password: String,
+    ) -> Result<RespValue, CubeError> {
+        let user = user.filter(|u| u != "default");
+        match self.auth.authenticate(user).await? {
+            Some(expected) if expected != password => Ok(RespValue::error(
+                "WRONGPASS invalid username-password pair or user is disabled.".to_string(),
+            )),
+            _ => {
+                self.authenticated = true;
+                Ok(RespValue::ok())
+            }
+        }
+    }
+
+    async fn set(&self, command: RespCommand) -> Result<RespValue, CubeError> {
+        command.expect_args(2, None)?;
+
+        let mut ttl = None;
+        let mut nx = false;
+        let mut idx = 2;
+        while idx < command.args.len() {
+            match command.option(idx)?.as_str() {
+                "nx" => {
+                    nx = true;
+                    idx += 1;
+                }
+                "ex" if idx + 1 < command.args.len() => {
+                    ttl = Some(command.integer(idx + 1)?);
+                    idx += 2;
+                }
+                "px" if idx + 1 < command.args.len() => {
+                    // CacheStore works with seconds precision
+                    ttl = Some((command.integer(idx + 1)? + 999) / 1000);
+                    idx += 2;
+                }
+                _ => return Err(CubeError::user("syntax error".to_string())),
+            }
+        }
+
+        let ttl = match ttl {
+            Some(ttl) if ttl <= 0 => {
+                return Err(CubeError::user(
+                    "invalid expire time in 'set' command".to_string(),
+                ))
+            }
+            Some(ttl) => Some(u32::try_from(ttl).map_err(|_| {
+                CubeError::user("invalid expire time in 'set' command".to_string())
+            })?),
+            None => None,
+        };
+
+        let success = self
+            .cachestore
+            .cache_set(
+                CacheItem::new(command.string(0)?, ttl, command.string(1)?),
+                nx,
+            )
+            .await?;
+
+        Ok(if success {
+            RespValue::ok()
+        } else {
+            RespValue::Null
+        })
+    }
+
+    async fn queue_add(&self, command: RespCommand) -> Result<RespValue, CubeError> {
+        command.expect_args(2, None)?;
+
+        let mut payload = QueueAddPayload {
+            path: command.string(0)?,
+            value: command.string(1)?,
+            priority: 0,
+            orphaned: None,
+            delay: None,
+            max_attempts: None,
+        };
+
+        let mut idx = 2;
+        while idx + 1 < command.args.len() {
+            match command.option(idx)?.as_str() {
+                "priority" => payload.priority = command.integer(idx + 1)?,
+                "orphaned" => payload.orphaned = Some(command.unsigned(idx + 1)?),
+                "delay" => payload.delay = Some(command.unsigned(idx + 1)?),
+                "max_attempts" => payload.max_attempts = Some(command.unsigned(idx + 1)?),
+                _ => return Err(CubeError::user("syntax error".to_string())),
+            }
+            idx += 2;
+        }
+
+        if idx != command.args.len() {
+            return Err(CubeError::user("syntax error".to_string()));
+        }
+
+        let response = self.cachestore.queue_add(payload).await?;
+
+        Ok(RespValue::Array(vec![
+            RespValue::bulk(response.id.to_string()),
+            RespValue::Integer(response.added as i64),
+            RespValue::Integer(response.pending as i64),
+        ]))
+    }
+
+    async fn cache_get(&self, key: String) -> Result<Option<CacheItem>, CubeError> {
+        let item = self.cachestore.cache_get(key).await?;
+
+        // Expired items can live in RocksDB until the next compaction
+        Ok(item.map(|item| item.into_row()).filter(|item| {
+            item.get_expire()
+                .map(|expire| expire > Utc::now())
+                .unwrap_or(true)
+        }))
+    }
+
+    async fn keys(&self, pattern: String) -> Result<RespValue, CubeError> {
+        let rows = if pattern == "*" {
+            self.cachestore.cache_all(None).await?
+        } else {
+            let prefix = if pattern.ends_with(":*") {
+                CacheItem::parse_path_to_prefix(pattern)
+            } else {
+                return Err(CubeError::user(
+                    "only prefix patterns are supported, e.g. MATCH prefix:*".to_string(),
+                ));
+            };
+            if prefix.contains(['*', '?', '[']) {
+                return Err(CubeError::user(
+                    "only prefix patterns are supported, e.g. MATCH prefix:*".to_string(),
+                ));
+            }
+
+            self.cachestore.cache_keys(prefix).await?
+        };
+
+        let now = Utc::now();
+
+        Ok(RespValue::Array(
+            rows.into_iter()
+                .filter(|row| {
+                    row.get_row()
+                        .get_expire()
+                        .map(|expire| expire > now)
+                        .unwrap_or(true)
+                })
+                .map(|row| RespValue::bulk(row.get_row().get_path()))
+                .collect(),
+        ))
+    }
+
+    fn rows_to_resp(rows: Vec<Row>) -> RespValue {
+        match rows.into_iter().next() {
+            Some(row) => RespValue::Array(
+                row.values()
+                    .iter()
+                    .map(|value| match value {
+                        TableValue::Null => RespValue::Null,
+                        TableValue::String(v) => RespValue::bulk(v.clone()),
+                        TableValue::Int(v) => RespValue::Integer(*v),
+                        TableValue::Boolean(v) => RespValue::Integer(*v as i64),
+                        v => RespValue::bulk(format!("{:?}", v)),
+                    })
+                    .collect(),
+            ),
+            None => RespValue::Null,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cachestore::RocksCacheStore;
+    use crate::config::{init_test_logger, Config};
+    use crate::mysql::SqlAuthDefaultImpl;
+    use tokio::io::AsyncReadExt;
+
+    async fn send(stream: &mut TcpStream, command: &[&str]) -> Result<String, CubeError> {
+        let mut buf = format!("*{}\r\n", command.len());
+        for arg in command {
+            buf.push_str(&format!("${}\r\n{}\r\n", arg.len(), arg));
+        }
+        stream.write_all(buf.as_bytes()).await?;
+
+        // Replies in this test are small enough to be received by one read
+        let mut response = vec![0; 4096];
+        let n = stream.read(&mut response).await?;
+        response.truncate(n);
+
+        Ok(String::from_utf8(response)?)
+    }
+
+    #[tokio::test]
+    async fn test_resp_server() -> Result<(), CubeError> {
+        init_test_logger().await;
+
+        let (_, cachestore) =
+            RocksCacheStore::prepare_test_cachestore("resp_server", Config::test("resp_server"));
+        let server = RespServer::new(
+            "127.0.0.1:16379".to_string(),
+            cachestore.clone(),
+            Arc::new(SqlAuthDefaultImpl),
+        );
+        let server_to_move = server.clone();
+        let handle = cube_ext::spawn(async move { server_to_move.processing_loop().await });
+
+        let mut stream = loop {
+            match TcpStream::connect("127.0.0.1:16379").await {
+                Ok(stream) => break stream,
+                Err(_) => tokio::time::sleep(std::time::Duration::from_millis(10)).await,
+            }
+        };
+
+        assert_eq!(send(&mut stream, &["PING"]).await?, "+PONG\r\n");
+        assert_eq!(send(&mut stream, &["GET", "p:k1"]).await?, "$-1\r\n");
+        assert_eq!(send(&mut stream, &["SET", "p:k1", "v1"]).await?, "+OK\r\n");
+        assert_eq!(
+            send(&mut stream, &["SET", "p:k1", "v2", "NX"]).await?,
+            "$-1\r\n"
+        );
+        assert_eq!(send(&mut stream, &["GET", "p:k1"]).await?, "$2\r\nv1\r\n");
+        assert_eq!(send(&mut stream, &["TTL", "p:k1"]).await?, ":-1\r\n");
+        assert_eq!(
+            send(&mut stream, &["EXPIRE", "p:k1", "100"]).await?,
+            ":1\r\n"
+        );
+        assert!(send(&mut stream, &["TTL", "p:k1"]).await?.starts_with(":9"));
+        assert_eq!(
+            send(&mut stream, &["SET", "p:k2", "v2", "EX", "100"]).await?,
+            "+OK\r\n"
+        );
+        assert_eq!(send(&mut stream, &["INCR", "p:counter"]).await?, ":1\r\n");
+        assert_eq!(send(&mut stream, &["INCR", "p:counter"]).await?, ":2\r\n");
+
+        let scan = send(&mut stream, &["SCAN", "0", "MATCH", "p:*", "COUNT", "100"]).await?;
+        assert!(scan.starts_with("*2\r\n$1\r\n0\r\n*3\r\n"));
+        assert!(scan.contains("$4\r\np:k2\r\n"));
+        assert!(send(&mut stream, &["SCAN", "0", "MATCH", "p*"])
+            .await?
+            .starts_with("-ERR"));
+        // Without MATCH all keys are returned
+        assert!(send(&mut stream, &["SCAN", "0"])
+            .await?
+            .starts_with("*2\r\n$1\r\n0\r\n*3\r\n"));
+
+        assert_eq!(
+            send(&mut stream, &["DEL", "p:k1", "p:k2", "p:k1", "p:k3"]).await?,
+            ":2\r\n"
+        );
+        assert_eq!(send(&mut stream, &["TTL", "p:k1"]).await?, ":-2\r\n");
+        assert_eq!(
+            send(&mut stream, &["EXPIRE", "p:k1", "100"]).await?,
+            ":0\r\n"
+        );
+        assert_eq!(
+            send(&mut stream, &["EXPIRE", "p:counter", "0"]).await?,
+            ":1\r\n"
+        );
+        assert_eq!(send(&mut stream, &["GET", "p:counter"]).await?, "$-1\r\n");
+
+        for args in [
+            &["QUEUE.ADD", "q:2", "payload", "DELAY", "-1"][..],
+            &["QUEUE.ADD", "q:2", "payload", "MAX_ATTEMPTS", "-1"][..],
+            &["QUEUE.RETRIEVE", "q:2", "CONCURRENCY", "-1"][..],
+        ] {
+            assert!(send(&mut stream, args).await?.starts_with("-ERR"));
+        }
+
+        assert_eq!(
+            send(
+                &mut stream,
+                &["QUEUE.ADD", "q:1", "payload", "PRIORITY", "1"]
+            )
+            .await?,
+            "*3\r\n$1\r\n1\r\n:1\r\n:1\r\n"
+        );
+        assert!(
+            send(&mut stream, &["QUEUE.RETRIEVE", "q:1", "CONCURRENCY", "1"])
+                .await?
+                .starts_with("*5\r\n$7\r\npayload\r\n$-1\r\n")
+        );
+        assert_eq!(
+            send(&mut stream, &["QUEUE.ACK", "q:1", "result"]).await?,
+            ":1\r\n"
+        );
+        assert_eq!(
+            send(&mut stream, &["QUEUE.RESULT", "q:1"]).await?,
+            "*2\r\n$6\r\nresult\r\n$7\r\nsuccess\r\n"
+        );
+
+        let lock = send(&mut stream, &["CACHE.LOCK", "l:1", "60"]).await?;
+        let token = lock.split("\r\n").nth(1).unwrap().to_string();
+        assert_eq!(
+            send(&mut stream, &["CACHE.LOCK", "l:1", "60"]).await?,
+            "$-1\r\n"
+        );
+        assert_eq!(
+            send(&mut stream, &["CACHE.UNLOCK", "l:1", &u64::MAX.to_string()]).await?,
+            ":0\r\n"
+        );
+        assert_eq!(
+            send(&mut stream, &["CACHE.UNLOCK", "l:1", &token]).await?,
+            ":1\r\n"
+        );
+
+        assert_eq!(
+            send(&mut stream, &["HELLO", "3"]).await?.chars().next(),
+            Some('%')
+        );
+        assert_eq!(send(&mut stream, &["GET", "p:k1"]).await?, "_\r\n");
+        assert!(send(&mut stream, &["UNKNOWN"]).await?.starts_with("-ERR"));
+        assert_eq!(send(&mut stream, &["QUIT"]).await?, "+OK\r\n");
+
+        server.stop_processing().await?;
+        handle.await??;
+
+        RocksCacheStore::cleanup_test_cachestore("resp_server");
+
+        Ok(())
+    }
+
+    /// Runs commands through a stock `redis-cli`, skipped when it isn't installed
+    #[tokio::test]
+    async fn test_resp_server_redis_cli() -> Result<(), CubeError> {
+        init_test_logger().await;
+
+        let redis_cli = |args: &[&str]| -> Option<String> {
+            let output = std::process::Command::new("redis-cli")
+                .args(["-h", "127.0.0.1", "-p", "16380"])
+                .args(args)
+                .output()
+                .ok()?;
+
+            // Error replies are printed to stdout as well, e.g. `ERR syntax error`
+            Some(
+                String::from_utf8(output.stdout)
+                    .unwrap()
+                    .trim_end()
+                    .to_string(),
+            )
+        };
+
+        let (_, cachestore) = RocksCacheStore::prepare_test_cachestore(
+            "resp_server_redis_cli",
+            Config::test("resp_server_redis_cli"),
+        );
+        let server = RespServer::new(
+            "127.0.0.1:16380".to_string(),
+            cachestore.clone(),
+            Arc::new(SqlAuthDefaultImpl),
+        );
+        let server_to_move = server.clone();
+        let handle = cube_ext::spawn(async move { server_to_move.processing_loop().await });
+
+        loop {
+            match TcpStream::connect("127.0.0.1:16380").await {
+                Ok(_) => break,
+                Err(_) => tokio::time::sleep(std::time::Duration::from_millis(10)).await,
+            }
+        }
+
+        let result = tokio::task::spawn_blocking(move || {
+            if redis_cli(&["PING"]).is_none() {
+                println!("redis-cli is not installed, skipping");
+                return;
+            }
+
+            assert_eq!(redis_cli(&["PING"]).unwrap(), "PONG");
+            assert_eq!(
+                redis_cli(&["SET", "p:k1", "v1", "EX", "100"]).unwrap(),
+                "OK"
+            );
+            assert_eq!(redis_cli(&["GET", "p:k1"]).unwrap(), "v1");
+            assert_eq!(redis_cli(&["EXPIRE", "p:k1", "200"]).unwrap(), "1");
+            assert_eq!(redis_cli(&["--scan"]).unwrap(), "p:k1");
+            assert_eq!(redis_cli(&["--scan", "--pattern", "p:*"]).unwrap(), "p:k1");
+            assert_eq!(redis_cli(&["DEL", "p:k1", "p:k2"]).unwrap(), "1");
+            assert_eq!(redis_cli(&["GET", "p:k1"]).unwrap(), "");
+            assert_eq!(
+                redis_cli(&["QUEUE.ADD", "q:1", "payload"]).unwrap(),
+                "1\n1\n1"
+            );
+            assert!(redis_cli(&["QUEUE.RETRIEVE", "q:1", "CONCURRENCY", "-1"])
+                .unwrap()
+                .starts_with("ERR"));
+        })
+        .await;
+
+        server.stop_processing().await?;
+        handle.await??;
+
+        RocksCacheStore::cleanup_test_cachestore("resp_server_redis_cli");
+
+        result?;
+
+        Ok(())
+    }
+}
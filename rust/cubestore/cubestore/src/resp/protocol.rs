@@ -0,0 +1,240 @@
+use crate::CubeError;
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncReadExt};
+
+// Same limits as Redis uses by default
+const MAX_BULK_LENGTH: usize = 512 * 1024 * 1024;
+const MAX_ARRAY_LENGTH: usize = 1024 * 1024;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum RespVersion {
+    Resp2,
+    Resp3,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum RespValue {
+    SimpleString(String),
+    Error(String),
+    Integer(i64),
+    BulkString(Vec<u8>),
+    Null,
+    Array(Vec<RespValue>),
+    // RESP3 only, encoded as a flat array for RESP2
+    Map(Vec<(RespValue, RespValue)>),
+}
+
+impl RespValue {
+    pub fn ok() -> Self {
+        RespValue::SimpleString("OK".to_string())
+    }
+
+    pub fn bulk(value: String) -> Self {
+        RespValue::BulkString(value.into_bytes())
+    }
+
+    pub fn error(message: String) -> Self {
+        // Error messages cannot contain new lines
+        RespValue::Error(message.replace(['\r', '\n'], " "))
+    }
+
+    pub fn encode(&self, version: RespVersion, buf: &mut Vec<u8>) {
+        match self {
+            RespValue::SimpleString(s) => {
+                buf.push(b'+');
+                buf.extend_from_slice(s.as_bytes());
+                buf.extend_from_slice(b"\r\n");
+            }
+            RespValue::Error(s) => {
+                buf.push(b'-');
+                buf.extend_from_slice(s.as_bytes());
+                buf.extend_from_slice(b"\r\n");
+            }
+            RespValue::Integer(i) => {
+                buf.extend_from_slice(format!(":{}\r\n", i).as_bytes());
+            }
+            RespValue::BulkString(s) => {
+                buf.extend_from_slice(format!("${}\r\n", s.len()).as_bytes());
+                buf.extend_from_slice(s);
+                buf.extend_from_slice(b"\r\n");
+            }
+            RespValue::Null => match version {
+                RespVersion::Resp2 => buf.extend_from_slice(b"$-1\r\n"),
+                RespVersion::Resp3 => buf.extend_from_slice(b"_\r\n"),
+            },
+            RespValue::Array(items) => {
+                buf.extend_from_slice(format!("*{}\r\n", items.len()).as_bytes());
+                for item in items {
+                    item.encode(version, buf);
+                }
+            }
+            RespValue::Map(items) => {
+                match version {
+                    RespVersion::Resp2 => {
+                        buf.extend_from_slice(format!("*{}\r\n", items.len() * 2).as_bytes())
+                    }
+                    RespVersion::Resp3 => {
+                        buf.extend_from_slice(format!("%{}\r\n", items.len()).as_bytes())
+                    }
+                };
+                for (k, v) in items {
+                    k.encode(version, buf);
+                    v.encode(version, buf);
+                }
+            }
+        }
+    }
+}
+
+async fn read_line<R: AsyncBufRead + Unpin>(reader: &mut R) -> Result<Option<String>, CubeError> {
+    let mut line = String::new();
+    if reader.read_line(&mut line).await? == 0 {
+        return Ok(None);
+    }
+
+    if !line.ends_with("\r\n") {
+        return Err(CubeError::user(
+            "Protocol error: expected '\\r\\n' at the end of line".to_string(),
+        ));
+    }
+
+    line.truncate(line.len() - 2);
+    Ok(Some(line))
+}
+
+fn parse_length(line: &str, max: usize) -> Result<usize, CubeError> {
+    let len = line
+        .parse::<usize>()
+        .map_err(|_| CubeError::user(format!("Protocol error: invalid length '{}'", line)))?;
+    if len > max {
+        return Err(CubeError::user(format!(
+            "Protocol error: length {} exceeds maximum allowed {}",
+            len, max
+        )));
+    }
+
+    Ok(len)
+}
+
+/// Reads the next command sent by a client. Clients send commands as an array of bulk strings,
+/// but inline commands (plain space separated line) are supported too, as Redis does.
+/// Returns `None` when the connection was closed.
+pub async fn read_command<R: AsyncBufRead + Unpin>(
+    reader: &mut R,
+) -> Result<Option<Vec<Vec<u8>>>, CubeError> {
+    let line = match read_line(reader).await? {
+        Some(line) => line,
+        None => return Ok(None),
+    };
+
+    if let Some(len) = line.strip_prefix('*') {
+        let len = parse_length(len, MAX_ARRAY_LENGTH)?;
+        let mut args = Vec::with_capacity(len);
+
+        for _ in 0..len {
+            let header = read_line(reader).await?.ok_or_else(|| {
+                CubeError::user("Protocol error: unexpected end of stream".to_string())
+            })?;
+            let len = if let Some(len) = header.strip_prefix('$') {
+                parse_length(len, MAX_BULK_LENGTH)?
+            } else {
+                return Err(CubeError::user(format!(
+                    "Protocol error: expected '$', got '{}'",
+                    header
+                )));
+            };
+
+            // Value + \r\n
+            let mut value = vec![0; len + 2];
+            reader.read_exact(&mut value).await?;
+            if &value[len..] != b"\r\n" {
+                return Err(CubeError::user(
+                    "Protocol error: expected '\\r\\n' after bulk string".to_string(),
+                ));
+            }
+
+            value.truncate(len);
+            args.push(value);
+        }
+
+        Ok(Some(args))
+    } else {
+        Ok(Some(
+            line.split_whitespace()
+                .map(|arg| arg.as_bytes().to_vec())
+                .collect(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_read_command() -> Result<(), CubeError> {
+        let mut input: &[u8] = b"*3\r\n$3\r\nSET\r\n$5\r\nkey:1\r\n$7\r\nva\r\nlue\r\nPING\r\n";
+
+        assert_eq!(
+            read_command(&mut input).await?,
+            Some(vec![
+                b"SET".to_vec(),
+                b"key:1".to_vec(),
+                b"va\r\nlue".to_vec()
+            ])
+        );
+        assert_eq!(
+            read_command(&mut input).await?,
+            Some(vec![b"PING".to_vec()])
+        );
+        assert_eq!(read_command(&mut input).await?, None);
+
+        let mut input: &[u8] = b"*1\r\n+SET\r\n";
+        assert!(read_command(&mut input).await.is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_encode() {
+        let value = RespValue::Array(vec![
+            RespValue::ok(),
+            RespValue::Integer(-1),
+            RespValue::bulk("value".to_string()),
+            RespValue::Null,
+            RespValue::error("ERR multi\nline".to_string()),
+        ]);
+
+        let mut buf = Vec::new();
+        value.encode(RespVersion::Resp2, &mut buf);
+        assert_eq!(
+            String::from_utf8(buf).unwrap(),
+            "*5\r\n+OK\r\n:-1\r\n$5\r\nvalue\r\n$-1\r\n-ERR multi line\r\n"
+        );
+
+        let mut buf = Vec::new();
+        value.encode(RespVersion::Resp3, &mut buf);
+        assert_eq!(
+            String::from_utf8(buf).unwrap(),
+            "*5\r\n+OK\r\n:-1\r\n$5\r\nvalue\r\n_\r\n-ERR multi line\r\n"
+        );
+
+        let value = RespValue::Map(vec![(
+            RespValue::bulk("proto".to_string()),
+            RespValue::Integer(3),
+        )]);
+
+        let mut buf = Vec::new();
+        value.encode(RespVersion::Resp2, &mut buf);
+        assert_eq!(
+            String::from_utf8(buf).unwrap(),
+            "*2\r\n$5\r\nproto\r\n:3\r\n"
+        );
+
+        let mut buf = Vec::new();
+        value.encode(RespVersion::Resp3, &mut buf);
+        assert_eq!(
+            String::from_utf8(buf).unwrap(),
+            "%1\r\n$5\r\nproto\r\n:3\r\n"
+        );
+    }
+}
@@ -1,4 +1,6 @@
-use crate::cachestore::{CacheItem, CacheStore, EvictionResult, QueueAddPayload, QueueItem};
+use crate::cachestore::{
+    CacheItem, CacheStore, EvictionResult, QueueAddPayload, QueueItem, QueueItemStatus,
+};
 use crate::metastore::{Column, ColumnType};
 
 use crate::cluster::rate_limiter::{ProcessRateLimiter, TaskType, TraceIndex};
@@ -272,6 +274,110 @@ impl CacheStoreSqlService {
                     true,
                 )
             }
+            CacheCommand::Cas {
+                key,
+                expected,
+                value,
+                ttl,
+            } => {
+                let value_size = key.value.deep_size_of() + value.deep_size_of();
+                let success = self
+                    .cachestore
+                    .cache_cas(CacheItem::new(key.value, ttl, value), expected)
+                    .await?;
+
+                (
+                    Arc::new(DataFrame::new(
+                        vec![Column::new("success".to_string(), ColumnType::Boolean, 0)],
+                        vec![Row::new(vec![TableValue::Boolean(success)])],
+                    )),
+                    Some(value_size),
+                    true,
+                )
+            }
+            CacheCommand::MSet { items, ttl } => {
+                let value_size: usize = items
+                    .iter()
+                    .map(|(key, value)| key.value.deep_size_of() + value.deep_size_of())
+                    .sum();
+                self.cachestore
+                    .cache_mset(
+                        items
+                            .into_iter()
+                            .map(|(key, value)| CacheItem::new(key.value, ttl, value))
+                            .collect(),
+                    )
+                    .await?;
+
+                (
+                    Arc::new(DataFrame::new(vec![], vec![])),
+                    Some(value_size),
+                    true,
+                )
+            }
+            CacheCommand::MGet { keys } => {
+                let keys = keys.into_iter().map(|key| key.value).collect::<Vec<_>>();
+                let result = self.cachestore.cache_mget(keys.clone()).await?;
+
+                (
+                    Arc::new(DataFrame::new(
+                        vec![
+                            Column::new("key".to_string(), ColumnType::String, 0),
+                            Column::new("value".to_string(), ColumnType::String, 1),
+                        ],
+                        keys.into_iter()
+                            .zip(result.into_iter())
+                            .map(|(key, item)| {
+                                Row::new(vec![
+                                    TableValue::String(key),
+                                    if let Some(item) = item {
+                                        TableValue::String(item.into_row().value)
+                                    } else {
+                                        TableValue::Null
+                                    },
+                                ])
+                            })
+                            .collect(),
+                    )),
+                    None,
+                    true,
+                )
+            }
+            CacheCommand::Lock { key, ttl } => {
+                let token = self.cachestore.cache_lock(key.value, ttl).await?;
+
+                (
+                    Arc::new(DataFrame::new(
+                        vec![Column::new("token".to_string(), ColumnType::String, 0)],
+                        vec![Row::new(vec![if let Some(token) = token {
+                            TableValue::String(token.to_string())
+                        } else {
+                            TableValue::Null
+                        }])],
+                    )),
+                    None,
+                    true,
+                )
+            }
+            CacheCommand::Unlock { key, token } => {
+                let success = self.cachestore.cache_unlock(key.value, token).await?;
+
+                (
+                    Arc::new(DataFrame::new(
+                        vec![Column::new("success".to_string(), ColumnType::Boolean, 0)],
+                        vec![Row::new(vec![TableValue::Boolean(success)])],
+                    )),
+                    None,
+                    true,
+                )
+            }
+            CacheCommand::Subscribe { .. } | CacheCommand::Unsubscribe { .. } => {
+                // Subscriptions are bound to the connection, see HttpServer
+                return Err(CubeError::user(format!(
+                    "CACHE {} is supported only over WebSocket connection",
+                    command_tag.to_ascii_uppercase()
+                )));
+            }
         };
 
         let trace_index = TraceIndex {
@@ -325,6 +431,8 @@ impl CacheStoreSqlService {
                 key,
                 priority,
                 orphaned,
+                delay,
+                max_attempts,
                 value,
             } => {
                 let value_size = key.value.deep_size_of() + value.deep_size_of();
@@ -335,6 +443,8 @@ impl CacheStoreSqlService {
                         value,
                         priority,
                         orphaned,
+                        delay,
+                        max_attempts,
                     })
                     .await?;
 
@@ -403,6 +513,18 @@ impl CacheStoreSqlService {
                     true,
                 )
             }
+            QueueCommand::Nack { key } => {
+                let success = self.cachestore.queue_nack(key).await?;
+
+                (
+                    Arc::new(DataFrame::new(
+                        vec![Column::new("success".to_string(), ColumnType::Boolean, 0)],
+                        vec![Row::new(vec![TableValue::Boolean(success)])],
+                    )),
+                    None,
+                    true,
+                )
+            }
             QueueCommand::Get { key } => {
                 let result = self.cachestore.queue_get(key).await?;
                 let rows = if let Some(result) = result {
@@ -456,9 +578,14 @@ impl CacheStoreSqlService {
                 status_filter,
                 sort_by_priority,
             } => {
+                let prefix = if status_filter == Some(QueueItemStatus::Failed) {
+                    QueueItem::dead_letter_prefix(&Some(prefix.value))
+                } else {
+                    prefix.value
+                };
                 let rows = self
                     .cachestore
-                    .queue_list(prefix.value, status_filter, sort_by_priority, with_payload)
+                    .queue_list(prefix, status_filter, sort_by_priority, with_payload)
                     .await?;
 
                 let mut columns = vec![
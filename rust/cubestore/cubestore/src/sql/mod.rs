@@ -48,7 +48,7 @@ use crate::metastore::{
     MetaStoreTable, Schema,
 };
 use crate::queryplanner::panic::PanicWorkerNode;
-use crate::queryplanner::pretty_printers::{pp_phys_plan, pp_plan};
+use crate::queryplanner::pretty_printers::{pp_phys_plan, pp_plan_ext, PPOptions};
 use crate::queryplanner::query_executor::{batches_to_dataframe, ClusterSendExec, QueryExecutor};
 use crate::queryplanner::serialized_plan::{RowFilter, SerializedPlan};
 use crate::queryplanner::{PlanningMeta, QueryPlan, QueryPlanner};
@@ -407,6 +407,8 @@ impl SqlServiceImpl {
                         HashMap::new(),
                         NoopParquetMetadataCache::new(),
                     )?;
+                    let mut opts = PPOptions::default();
+                    opts.show_aggregate_index_match = true;
 
                     DataFrame::new(
                         vec![Column::new(
@@ -414,7 +416,10 @@ impl SqlServiceImpl {
                             ColumnType::String,
                             0,
                         )],
-                        vec![Row::new(vec![TableValue::String(pp_plan(&logical_plan))])],
+                        vec![Row::new(vec![TableValue::String(pp_plan_ext(
+                            &logical_plan,
+                            &opts,
+                        ))])],
                     )
                 } else {
                     let cluster = self.cluster.clone();
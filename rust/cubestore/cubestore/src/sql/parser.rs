@@ -89,6 +89,34 @@ pub enum CacheCommand {
     Incr {
         path: Ident,
     },
+    Cas {
+        key: Ident,
+        /// `NULL` expects the key to not exist
+        expected: Option<String>,
+        value: String,
+        ttl: Option<u32>,
+    },
+    MSet {
+        items: Vec<(Ident, String)>,
+        ttl: Option<u32>,
+    },
+    MGet {
+        keys: Vec<Ident>,
+    },
+    Lock {
+        key: Ident,
+        ttl: u32,
+    },
+    Unlock {
+        key: Ident,
+        token: u64,
+    },
+    Subscribe {
+        prefix: Ident,
+    },
+    Unsubscribe {
+        prefix: Ident,
+    },
 }
 
 impl CacheCommand {
@@ -100,6 +128,13 @@ impl CacheCommand {
             CacheCommand::Remove { .. } => "remove",
             CacheCommand::Truncate { .. } => "truncate",
             CacheCommand::Incr { .. } => "incr",
+            CacheCommand::Cas { .. } => "cas",
+            CacheCommand::MSet { .. } => "mset",
+            CacheCommand::MGet { .. } => "mget",
+            CacheCommand::Lock { .. } => "lock",
+            CacheCommand::Unlock { .. } => "unlock",
+            CacheCommand::Subscribe { .. } => "subscribe",
+            CacheCommand::Unsubscribe { .. } => "unsubscribe",
         }
     }
 }
@@ -109,6 +144,8 @@ pub enum QueueCommand {
     Add {
         priority: i64,
         orphaned: Option<u32>,
+        delay: Option<u32>,
+        max_attempts: Option<u32>,
         key: Ident,
         value: String,
     },
@@ -136,6 +173,9 @@ pub enum QueueCommand {
         key: QueueKey,
         result: Option<String>,
     },
+    Nack {
+        key: QueueKey,
+    },
     MergeExtra {
         key: QueueKey,
         payload: String,
@@ -164,11 +204,13 @@ impl QueueCommand {
             QueueCommand::List { status_filter, .. } => match status_filter {
                 Some(QueueItemStatus::Active) => "active",
                 Some(QueueItemStatus::Pending) => "pending",
+                Some(QueueItemStatus::Failed) => "failed",
                 _ => "list",
             },
             QueueCommand::Cancel { .. } => "cancel",
             QueueCommand::Heartbeat { .. } => "heartbeat",
             QueueCommand::Ack { .. } => "ack",
+            QueueCommand::Nack { .. } => "nack",
             QueueCommand::MergeExtra { .. } => "merge_extra",
             QueueCommand::Retrieve { .. } => "retrieve",
             QueueCommand::Result { .. } => "result",
@@ -349,9 +391,89 @@ impl<'a> CubeStoreParser<'a> {
                 key: self.parser.parse_identifier()?,
             },
             "truncate" => CacheCommand::Truncate {},
+            "cas" => {
+                let key = self.parser.parse_identifier()?;
+                let expected = if self.parser.parse_keyword(Keyword::NULL) {
+                    None
+                } else {
+                    Some(self.parser.parse_literal_string()?)
+                };
+                let value = self.parser.parse_literal_string()?;
+                let ttl = if self.parse_custom_token(&"ttl") {
+                    Some(self.parse_integer("ttl", false)?)
+                } else {
+                    None
+                };
+
+                CacheCommand::Cas {
+                    key,
+                    expected,
+                    value,
+                    ttl,
+                }
+            }
+            "mset" => {
+                let ttl = if self.parse_custom_token(&"ttl") {
+                    Some(self.parse_integer("ttl", false)?)
+                } else {
+                    None
+                };
+
+                let mut items = vec![(
+                    self.parser.parse_identifier()?,
+                    self.parser.parse_literal_string()?,
+                )];
+                while !matches!(self.parser.peek_token(), Token::EOF | Token::SemiColon) {
+                    items.push((
+                        self.parser.parse_identifier()?,
+                        self.parser.parse_literal_string()?,
+                    ));
+                }
+
+                CacheCommand::MSet { items, ttl }
+            }
+            "mget" => {
+                let mut keys = vec![self.parser.parse_identifier()?];
+                while !matches!(self.parser.peek_token(), Token::EOF | Token::SemiColon) {
+                    keys.push(self.parser.parse_identifier()?);
+                }
+
+                CacheCommand::MGet { keys }
+            }
+            "lock" => {
+                let key = self.parser.parse_identifier()?;
+                // TTL keyword is optional, LOCK key 30 and LOCK key TTL 30 are the same
+                self.parse_custom_token(&"ttl");
+
+                CacheCommand::Lock {
+                    key,
+                    ttl: self.parse_integer("ttl", false)?,
+                }
+            }
+            "unlock" => {
+                let key = self.parser.parse_identifier()?;
+                // Tokens are returned by LOCK as strings
+                let token = self.parser.parse_literal_string()?;
+
+                CacheCommand::Unlock {
+                    key,
+                    token: token.parse::<u64>().map_err(|_| {
+                        ParserError::ParserError(format!(
+                            "Invalid lock token: {}, it must be a positive integer",
+                            token
+                        ))
+                    })?,
+                }
+            }
+            "subscribe" => CacheCommand::Subscribe {
+                prefix: self.parser.parse_identifier()?,
+            },
+            "unsubscribe" => CacheCommand::Unsubscribe {
+                prefix: self.parser.parse_identifier()?,
+            },
             other => {
                 return Err(ParserError::ParserError(format!(
-                    "Unknown cache command: {}, available: SET|GET|KEYS|INC|REMOVE|TRUNCATE",
+                    "Unknown cache command: {}, available: SET|GET|KEYS|INC|REMOVE|TRUNCATE|CAS|MSET|MGET|LOCK|UNLOCK|SUBSCRIBE|UNSUBSCRIBE",
                     other
                 )))
             }
@@ -484,9 +606,23 @@ impl<'a> CubeStoreParser<'a> {
                     None
                 };
 
+                let delay = if self.parse_custom_token(&"delay") {
+                    Some(self.parse_integer("delay", false)?)
+                } else {
+                    None
+                };
+
+                let max_attempts = if self.parse_custom_token(&"max_attempts") {
+                    Some(self.parse_integer("max_attempts", false)?)
+                } else {
+                    None
+                };
+
                 QueueCommand::Add {
                     priority,
                     orphaned,
+                    delay,
+                    max_attempts,
                     key: self.parser.parse_identifier()?,
                     value: self.parser.parse_literal_string()?,
                 }
@@ -507,6 +643,9 @@ impl<'a> CubeStoreParser<'a> {
 
                 QueueCommand::Ack { key, result }
             }
+            "nack" => QueueCommand::Nack {
+                key: self.parse_queue_key()?,
+            },
             "merge_extra" => QueueCommand::MergeExtra {
                 key: self.parse_queue_key()?,
                 payload: self.parser.parse_literal_string()?,
@@ -562,6 +701,16 @@ impl<'a> CubeStoreParser<'a> {
                     sort_by_priority: false,
                 }
             }
+            "failed" => {
+                let with_payload = self.parse_custom_token(&"with_payload");
+
+                QueueCommand::List {
+                    prefix: self.parser.parse_identifier()?,
+                    with_payload,
+                    status_filter: Some(QueueItemStatus::Failed),
+                    sort_by_priority: false,
+                }
+            }
             "list" => {
                 let with_payload = self.parse_custom_token(&"with_payload");
 